@@ -0,0 +1,114 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use anyhow::Result;
+use clap::Parser;
+use dsi_bitstream::prelude::*;
+use dsi_progress_logger::prelude::*;
+use std::collections::VecDeque;
+use std::hint::black_box;
+use std::path::PathBuf;
+use sux::prelude::BitVec;
+use webgraph::algo::SeqBfs;
+use webgraph::prelude::*;
+
+#[derive(Parser, Debug)]
+#[command(about = "Benchmark many small breadth-first visits, enumerating balls around pseudorandom roots, allocating the visit state at each visit or reusing a SeqBfs.", long_about = None)]
+struct Args {
+    /// The basename of the graph.
+    basename: PathBuf,
+
+    /// The number of visits.
+    #[arg(short, long, default_value_t = 100_000)]
+    visits: usize,
+
+    /// The radius of the balls.
+    #[arg(long, default_value_t = 2)]
+    radius: usize,
+
+    /// The number of repeats.
+    #[arg(short, long, default_value_t = 3)]
+    repeats: usize,
+}
+
+/// Returns the size of the ball of given radius around `root`, allocating
+/// the visit state.
+fn ball_alloc(graph: &impl RandomAccessGraph, root: usize, radius: usize) -> usize {
+    let mut seen = BitVec::new(graph.num_nodes());
+    let mut queue = VecDeque::new();
+    seen.set(root, true);
+    queue.push_back((root, 0));
+    let mut size = 0;
+    while let Some((node, distance)) = queue.pop_front() {
+        size += 1;
+        if distance == radius {
+            continue;
+        }
+        for succ in graph.successors(node) {
+            if !seen[succ] {
+                seen.set(succ, true);
+                queue.push_back((succ, distance + 1));
+            }
+        }
+    }
+    size
+}
+
+fn bench_impl<E: Endianness + 'static>(args: Args) -> Result<()>
+where
+    for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+{
+    let graph = BvGraph::with_basename(&args.basename)
+        .endianness::<E>()
+        .load()?;
+    let num_nodes = graph.num_nodes();
+    let root = |i: usize| (i * 7919) % num_nodes;
+    let mut bfs = SeqBfs::new(&graph);
+
+    for _ in 0..args.repeats {
+        let mut pl = ProgressLogger::default();
+        pl.item_name("visit");
+
+        pl.start("Visits allocating the state...");
+        for i in 0..args.visits {
+            black_box(ball_alloc(&graph, root(i), args.radius));
+        }
+        pl.done_with_count(args.visits);
+
+        pl.start("Visits reusing a SeqBfs...");
+        for i in 0..args.visits {
+            let mut size = 0;
+            bfs.reset();
+            bfs.visit(root(i), args.radius, |_, _| size += 1);
+            black_box(size);
+        }
+        pl.done_with_count(args.visits);
+    }
+
+    Ok(())
+}
+
+pub fn main() -> Result<()> {
+    let args = Args::parse();
+
+    env_logger::builder()
+        .filter_level(log::LevelFilter::Info)
+        .try_init()?;
+
+    match get_endianness(&args.basename)?.as_str() {
+        #[cfg(any(
+            feature = "be_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        BE::NAME => bench_impl::<BE>(args),
+        #[cfg(any(
+            feature = "le_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        LE::NAME => bench_impl::<LE>(args),
+        e => panic!("Unknown endianness: {}", e),
+    }
+}
@@ -0,0 +1,106 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use anyhow::Result;
+use clap::Parser;
+use dsi_bitstream::prelude::*;
+use dsi_progress_logger::prelude::*;
+use std::path::PathBuf;
+use tempfile::Builder;
+use webgraph::algo::{minhash_order, par_bfs_perm, IntraLayerOrder};
+use webgraph::prelude::*;
+
+#[derive(Parser, Debug)]
+#[command(about = "Compare the compressed size of a graph in its natural order, in BFS order and in minhash order.", long_about = None)]
+struct Args {
+    /// The basename of the graph.
+    basename: PathBuf,
+
+    /// The number of hash functions of the minhash signatures.
+    #[arg(short = 'k', long, default_value_t = 4)]
+    num_hashes: usize,
+
+    /// The seed of the hash functions.
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+}
+
+fn bench_impl<E: Endianness + Send + Sync + 'static>(args: Args) -> Result<()>
+where
+    for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+{
+    let graph = BvGraph::with_basename(&args.basename)
+        .endianness::<E>()
+        .load()?;
+    let thread_pool = rayon::ThreadPoolBuilder::new().build()?;
+    let dir = Builder::new().prefix("bench_minhash_order").tempdir()?;
+    let basename = dir.path().join("permuted");
+
+    let identity = (0..graph.num_nodes()).collect::<Vec<_>>();
+    let bfs = par_bfs_perm(
+        &graph,
+        IntraLayerOrder::Parent,
+        &thread_pool,
+        &mut ProgressLogger::default(),
+    )
+    .into_vec();
+    let mut pl = ProgressLogger::default();
+    pl.start("Computing the minhash order...");
+    let minhash = minhash_order(
+        &graph,
+        args.num_hashes,
+        args.seed,
+        &thread_pool,
+        &mut ProgressLogger::default(),
+    )
+    .into_vec();
+    pl.done_with_count(graph.num_nodes());
+
+    for (name, perm) in [("natural", identity), ("BFS", bfs), ("minhash", minhash)] {
+        let csr = PermutedGraph {
+            graph: &graph,
+            perm: &perm,
+        }
+        .materialize()?;
+        let bits = compress::<BE, _>(
+            &csr,
+            &basename,
+            CompFlags::default(),
+            &thread_pool,
+            &mut ProgressLogger::default(),
+        )?;
+        println!(
+            "{}: {} bits, {:.4} bits/arc",
+            name,
+            bits,
+            bits as f64 / graph.num_arcs() as f64
+        );
+    }
+
+    Ok(())
+}
+
+pub fn main() -> Result<()> {
+    let args = Args::parse();
+
+    env_logger::builder()
+        .filter_level(log::LevelFilter::Info)
+        .try_init()?;
+
+    match get_endianness(&args.basename)?.as_str() {
+        #[cfg(any(
+            feature = "be_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        BE::NAME => bench_impl::<BE>(args),
+        #[cfg(any(
+            feature = "le_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        LE::NAME => bench_impl::<LE>(args),
+        e => panic!("Unknown endianness: {}", e),
+    }
+}
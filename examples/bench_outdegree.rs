@@ -0,0 +1,100 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use anyhow::Result;
+use clap::Parser;
+use dsi_bitstream::prelude::*;
+use dsi_progress_logger::prelude::*;
+use std::collections::HashMap;
+use std::hint::black_box;
+use std::path::PathBuf;
+use webgraph::prelude::*;
+
+#[derive(Parser, Debug)]
+#[command(about = "Benchmark the inner loop of layered label propagation on the symmetric view of a graph, skipping nodes with no successors using the outdegree or the outdegree hint.", long_about = None)]
+struct Args {
+    /// The basename of the graph.
+    basename: PathBuf,
+
+    /// The basename of the transpose of the graph.
+    transpose: PathBuf,
+
+    /// The number of repeats.
+    #[arg(short, long, default_value_t = 3)]
+    repeats: usize,
+}
+
+/// Computes the frequency of the successors of each node modulo 1024,
+/// similarly to the inner loop of layered label propagation, and returns
+/// the sum of the number of distinct values.
+fn inner_loop(graph: &impl RandomAccessGraph, hint: bool) -> usize {
+    let mut total = 0;
+    for node in 0..graph.num_nodes() {
+        let successors = graph.successors(node);
+        let outdegree = if hint {
+            graph.outdegree_hint(node)
+        } else {
+            graph.outdegree(node)
+        };
+        if outdegree == 0 {
+            continue;
+        }
+        let mut map = HashMap::with_capacity(Ord::min(outdegree + 1, 1024));
+        for succ in successors {
+            *map.entry(succ % 1024).or_insert(0_usize) += 1;
+        }
+        total += map.len();
+    }
+    total
+}
+
+fn bench_impl<E: Endianness + 'static>(args: Args) -> Result<()>
+where
+    for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+{
+    let graph = BvGraph::with_basename(&args.basename)
+        .endianness::<E>()
+        .load()?;
+    let transpose = BvGraph::with_basename(&args.transpose)
+        .endianness::<E>()
+        .load()?;
+    let view = SymmetricView::new(&graph, &transpose);
+
+    for _ in 0..args.repeats {
+        let mut pl = ProgressLogger::default();
+        pl.start("Inner loop using the outdegree...");
+        black_box(inner_loop(&view, false));
+        pl.done_with_count(graph.num_nodes());
+
+        pl.start("Inner loop using the outdegree hint...");
+        black_box(inner_loop(&view, true));
+        pl.done_with_count(graph.num_nodes());
+    }
+
+    Ok(())
+}
+
+pub fn main() -> Result<()> {
+    let args = Args::parse();
+
+    env_logger::builder()
+        .filter_level(log::LevelFilter::Info)
+        .try_init()?;
+
+    match get_endianness(&args.basename)?.as_str() {
+        #[cfg(any(
+            feature = "be_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        BE::NAME => bench_impl::<BE>(args),
+        #[cfg(any(
+            feature = "le_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        LE::NAME => bench_impl::<LE>(args),
+        e => panic!("Unknown endianness: {}", e),
+    }
+}
@@ -0,0 +1,97 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use anyhow::Result;
+use clap::Parser;
+use dsi_bitstream::prelude::*;
+use dsi_progress_logger::prelude::*;
+use rand::{rngs::SmallRng, seq::SliceRandom, SeedableRng};
+use std::path::PathBuf;
+use tempfile::Builder;
+use webgraph::prelude::*;
+
+#[derive(Parser, Debug)]
+#[command(about = "Benchmark compression of a randomly permuted graph using external sorting and in-memory materialization.", long_about = None)]
+struct Args {
+    /// The basename of the graph.
+    basename: PathBuf,
+
+    /// The batch size for external sorting.
+    #[arg(short, long, default_value_t = 10_000_000)]
+    batch_size: usize,
+
+    /// The number of repeats.
+    #[arg(short, long, default_value_t = 3)]
+    repeats: usize,
+}
+
+fn bench_impl<E: Endianness + 'static>(args: Args) -> Result<()>
+where
+    for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+{
+    let graph = BvGraphSeq::with_basename(&args.basename)
+        .endianness::<E>()
+        .load()?;
+    let mut perm = (0..graph.num_nodes()).collect::<Vec<_>>();
+    perm.shuffle(&mut SmallRng::seed_from_u64(0));
+
+    let thread_pool = rayon::ThreadPoolBuilder::new().build()?;
+    let dir = Builder::new().prefix("bench_permute").tempdir()?;
+    let basename = dir.path().join("permuted");
+
+    for _ in 0..args.repeats {
+        let mut pl = ProgressLogger::default();
+        pl.start("Permuting and compressing with external sorting...");
+        let sorted = webgraph::transform::permute(&graph, &perm, args.batch_size)?;
+        compress::<BE, _>(
+            &sorted,
+            &basename,
+            CompFlags::default(),
+            &thread_pool,
+            &mut ProgressLogger::default(),
+        )?;
+        pl.done_with_count(graph.num_nodes());
+
+        pl.start("Permuting and compressing with in-memory materialization...");
+        let csr = PermutedGraph {
+            graph: &graph,
+            perm: &perm,
+        }
+        .materialize()?;
+        compress::<BE, _>(
+            &csr,
+            &basename,
+            CompFlags::default(),
+            &thread_pool,
+            &mut ProgressLogger::default(),
+        )?;
+        pl.done_with_count(graph.num_nodes());
+    }
+
+    Ok(())
+}
+
+pub fn main() -> Result<()> {
+    let args = Args::parse();
+
+    env_logger::builder()
+        .filter_level(log::LevelFilter::Info)
+        .try_init()?;
+
+    match get_endianness(&args.basename)?.as_str() {
+        #[cfg(any(
+            feature = "be_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        BE::NAME => bench_impl::<BE>(args),
+        #[cfg(any(
+            feature = "le_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        LE::NAME => bench_impl::<LE>(args),
+        e => panic!("Unknown endianness: {}", e),
+    }
+}
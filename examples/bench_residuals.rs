@@ -0,0 +1,99 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use anyhow::{ensure, Result};
+use clap::Parser;
+use dsi_bitstream::prelude::*;
+use dsi_progress_logger::prelude::*;
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+use std::hint::black_box;
+use webgraph::prelude::*;
+
+#[derive(Parser, Debug)]
+#[command(about = "Benchmark the decoding of residuals with a dynamic-dispatch decoder, one at a time and in batches.", long_about = None)]
+struct Args {
+    /// The number of residuals.
+    #[arg(short, long, default_value_t = 100_000_000)]
+    num_residuals: usize,
+
+    /// The largest residual.
+    #[arg(short, long, default_value_t = 1000)]
+    max: u64,
+
+    /// The batch size.
+    #[arg(short, long, default_value_t = 64)]
+    batch_size: usize,
+
+    /// The number of repeats.
+    #[arg(short, long, default_value_t = 3)]
+    repeats: usize,
+}
+
+pub fn main() -> Result<()> {
+    let args = Args::parse();
+    ensure!(args.batch_size > 0, "The batch size must be positive");
+
+    env_logger::builder()
+        .filter_level(log::LevelFilter::Info)
+        .try_init()?;
+
+    // Residuals are written with the default code (ζ₃)
+    let cf = CompFlags::default();
+    let mut words: Vec<u64> = vec![];
+    {
+        let mut encoder = <DynCodesEncoder<BE, _>>::new(
+            <BufBitWriter<BE, _>>::new(MemWordWriterVec::new(&mut words)),
+            &cf,
+        );
+        let mut rng = SmallRng::seed_from_u64(0);
+        for _ in 0..args.num_residuals {
+            encoder.write_residual(rng.gen_range(0..=args.max))?;
+        }
+        encoder.flush()?;
+    }
+    words.extend([0; 4]);
+    let words_32: &[u32] = unsafe { words.align_to().1 };
+
+    let mut buf = vec![0; args.batch_size];
+    let mut pl = ProgressLogger::default();
+    pl.item_name("residual");
+
+    for _ in 0..args.repeats {
+        let mut decoder = DynCodesDecoder::new(
+            <BufBitReader<BE, _>>::new(MemWordReader::new(words_32)),
+            &cf,
+        )?;
+        pl.start("Decoding residuals one at a time...");
+        let mut sum = 0;
+        for _ in 0..args.num_residuals {
+            sum += decoder.read_residual();
+        }
+        black_box(sum);
+        pl.done_with_count(args.num_residuals);
+
+        let mut decoder = DynCodesDecoder::new(
+            <BufBitReader<BE, _>>::new(MemWordReader::new(words_32)),
+            &cf,
+        )?;
+        pl.start(format!(
+            "Decoding residuals in batches of {}...",
+            args.batch_size
+        ));
+        let mut sum = 0;
+        let mut to_go = args.num_residuals;
+        while to_go != 0 {
+            let len = to_go.min(args.batch_size);
+            let read = decoder.read_residuals_into(&mut buf[..len]);
+            sum += buf[..read].iter().sum::<u64>();
+            to_go -= read;
+        }
+        black_box(sum);
+        pl.done_with_count(args.num_residuals);
+    }
+
+    Ok(())
+}
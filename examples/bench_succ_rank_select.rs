@@ -0,0 +1,106 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use anyhow::Result;
+use clap::Parser;
+use dsi_bitstream::prelude::*;
+use dsi_progress_logger::prelude::*;
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+use std::hint::black_box;
+use std::path::PathBuf;
+use webgraph::prelude::*;
+
+#[derive(Parser, Debug)]
+#[command(about = "Benchmarks successor rank and select against the materialization of successor lists.", long_about = None)]
+struct Args {
+    /// The basename of the graph.
+    basename: PathBuf,
+    /// The number of random queries.
+    #[arg(short, long, default_value_t = 1_000_000)]
+    n: usize,
+}
+
+fn bench_impl<E: Endianness + 'static>(args: Args) -> Result<()>
+where
+    for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+{
+    let graph = BvGraph::with_basename(&args.basename)
+        .endianness::<E>()
+        .load()?;
+    let num_nodes = graph.num_nodes();
+
+    // Generate the queries: a node with nonempty successor list, a rank
+    // within its list and a random target
+    let mut rng = SmallRng::seed_from_u64(0);
+    let mut queries = Vec::with_capacity(args.n);
+    while queries.len() < args.n {
+        let node = rng.gen_range(0..num_nodes);
+        let outdegree = graph.outdegree(node);
+        if outdegree != 0 {
+            queries.push((
+                node,
+                rng.gen_range(0..outdegree),
+                rng.gen_range(0..num_nodes),
+            ));
+        }
+    }
+    let mut buf = vec![];
+
+    for _ in 0..5 {
+        let mut pl = ProgressLogger::default();
+
+        pl.start("Select by materialization...");
+        for &(node, k, _) in &queries {
+            graph.decode_successors_into(node, &mut buf);
+            black_box(buf[k]);
+        }
+        pl.done_with_count(args.n);
+
+        pl.start("Select...");
+        for &(node, k, _) in &queries {
+            black_box(graph.succ_select(node, k));
+        }
+        pl.done_with_count(args.n);
+
+        pl.start("Rank by materialization...");
+        for &(node, _, v) in &queries {
+            graph.decode_successors_into(node, &mut buf);
+            black_box(buf.partition_point(|&x| x < v));
+        }
+        pl.done_with_count(args.n);
+
+        pl.start("Rank...");
+        for &(node, _, v) in &queries {
+            black_box(graph.succ_rank(node, v));
+        }
+        pl.done_with_count(args.n);
+    }
+
+    Ok(())
+}
+
+pub fn main() -> Result<()> {
+    let args = Args::parse();
+
+    env_logger::builder()
+        .filter_level(log::LevelFilter::Info)
+        .try_init()?;
+
+    match get_endianness(&args.basename)?.as_str() {
+        #[cfg(any(
+            feature = "be_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        BE::NAME => bench_impl::<BE>(args),
+        #[cfg(any(
+            feature = "le_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        LE::NAME => bench_impl::<LE>(args),
+        e => panic!("Unknown endianness: {}", e),
+    }
+}
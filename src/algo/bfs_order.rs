@@ -4,9 +4,17 @@
  * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
  */
 
+use super::distances::ResettableVisited;
 use crate::traits::RandomAccessGraph;
+use anyhow::{ensure, Context, Result};
 use dsi_progress_logger::prelude::*;
+use rayon::prelude::*;
+use rayon::ThreadPool;
+use std::cmp::Reverse;
 use std::collections::VecDeque;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use sux::prelude::BitVec;
 
 /// Iterator on all nodes of the graph in a BFS order
@@ -48,12 +56,12 @@ impl<G: RandomAccessGraph> Iterator for BfsOrder<'_, G> {
         self.pl.light_update();
         let current_node = match self.queue.pop_front() {
             None => {
-                while self.seen[self.start] {
+                while self.start < self.graph.num_nodes() && self.seen[self.start] {
                     self.start += 1;
-                    if self.start >= self.graph.num_nodes() {
-                        self.pl.done();
-                        return None;
-                    }
+                }
+                if self.start >= self.graph.num_nodes() {
+                    self.pl.done();
+                    return None;
                 }
                 self.seen.set(self.start, true);
                 self.start
@@ -77,3 +85,400 @@ impl<G: RandomAccessGraph> ExactSizeIterator for BfsOrder<'_, G> {
         self.graph.num_nodes()
     }
 }
+
+/// A reusable sequential breadth-first visit from given roots.
+///
+/// Unlike [`BfsOrder`], which visits all nodes of a graph once, this
+/// structure is meant to perform many small visits (e.g., enumerating the
+/// balls of radius two around many nodes): it is constructed once, and each
+/// visit allocates no memory after the buffers have grown to the size of the
+/// largest layer. The set of visited nodes is cleared sparsely, so
+/// [`reset`](SeqBfs::reset) takes time proportional to the previous visit,
+/// and a visit costs time proportional to the part of the graph it explores.
+///
+/// Memory usage is at most two bits per node, plus the buffers of the layers.
+pub struct SeqBfs<'a, G: RandomAccessGraph> {
+    graph: &'a G,
+    visited: ResettableVisited,
+    curr: Vec<usize>,
+    next: Vec<usize>,
+}
+
+impl<'a, G: RandomAccessGraph> SeqBfs<'a, G> {
+    /// Creates a new visit of `graph`.
+    pub fn new(graph: &'a G) -> Self {
+        Self {
+            graph,
+            visited: ResettableVisited::new(graph.num_nodes()),
+            curr: Vec::new(),
+            next: Vec::new(),
+        }
+    }
+
+    /// Marks all nodes as not visited.
+    pub fn reset(&mut self) {
+        self.visited.reset();
+    }
+
+    /// Returns whether `node` has been visited since the last
+    /// [`reset`](SeqBfs::reset).
+    pub fn is_visited(&self, node: usize) -> bool {
+        self.visited.is_visited(node)
+    }
+
+    /// Visits the nodes at distance at most `max_distance` from `root` that
+    /// have not been visited since the last [`reset`](SeqBfs::reset),
+    /// passing each node and its distance from `root` to `on_node`.
+    ///
+    /// If `root` has already been visited, nothing happens. Otherwise, the
+    /// visit does not cross nodes visited before, so distances are correct
+    /// only if the visit is performed after a [`reset`](SeqBfs::reset); in
+    /// this way, however, visits from multiple roots can be combined. Use
+    /// `usize::MAX` as `max_distance` for an unbounded visit.
+    pub fn visit(
+        &mut self,
+        root: usize,
+        max_distance: usize,
+        mut on_node: impl FnMut(usize, usize),
+    ) {
+        if !self.visited.visit(root) {
+            return;
+        }
+        on_node(root, 0);
+        self.curr.clear();
+        self.curr.push(root);
+        let mut distance = 0;
+        while !self.curr.is_empty() && distance < max_distance {
+            distance += 1;
+            self.next.clear();
+            for &node in &self.curr {
+                for succ in self.graph.successors(node) {
+                    if self.visited.visit(succ) {
+                        on_node(succ, distance);
+                        self.next.push(succ);
+                    }
+                }
+            }
+            std::mem::swap(&mut self.curr, &mut self.next);
+        }
+    }
+
+    /// Performs unbounded [visits](SeqBfs::visit) from the given roots,
+    /// skipping those already visited, passing each node and its distance
+    /// from the root of its visit to `on_node` and, at the end of each
+    /// visit, the root and the number of nodes it discovered to
+    /// `on_component`.
+    ///
+    /// `on_component` is called once per discovered region, in the order of
+    /// the roots. If the graph is symmetric and the visit starts after a
+    /// [`reset`](SeqBfs::reset) with all nodes as roots (e.g., `0..n`), the
+    /// regions are exactly the connected components of the graph; on a
+    /// directed graph, each region contains the nodes reachable from its
+    /// root that were not discovered by previous visits.
+    pub fn visit_components(
+        &mut self,
+        roots: impl IntoIterator<Item = usize>,
+        mut on_node: impl FnMut(usize, usize),
+        mut on_component: impl FnMut(usize, usize),
+    ) {
+        for root in roots {
+            if self.is_visited(root) {
+                continue;
+            }
+            let mut size = 0;
+            self.visit(root, usize::MAX, |node, distance| {
+                size += 1;
+                on_node(node, distance);
+            });
+            on_component(root, size);
+        }
+    }
+}
+
+/// The order of the nodes within a layer of a [parallel breadth-first
+/// visit](par_bfs_layers).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IntraLayerOrder {
+    /// By node.
+    #[default]
+    Original,
+    /// By decreasing outdegree, and then by node.
+    DegreeDesc,
+    /// By position of the first parent in the previous layer, and then by
+    /// node. On graphs with sorted successors, this is the order of a
+    /// sequential visit such as [`BfsOrder`].
+    Parent,
+}
+
+/// A resumable parallel breadth-first visit, layer by layer.
+///
+/// As in [`BfsOrder`], the visit starts from node zero, and when no more
+/// nodes are reachable it restarts from the smallest unvisited node, which
+/// forms a new layer at distance zero. Each call to
+/// [`next_layer`](ParBfs::next_layer) passes the current layer, sorted by the
+/// [intra-layer order](IntraLayerOrder), to a callback together with its
+/// distance from the root of the visit, and computes the next layer.
+///
+/// Each layer is computed in parallel from the previous one, and then sorted
+/// in parallel; since the sort keys are total, the layers do not depend on
+/// the number of threads.
+///
+/// # Resuming a visit
+///
+/// Between two calls to [`next_layer`](ParBfs::next_layer), the state of the
+/// visit is given by the set of visited nodes, the current layer, its
+/// distance from the root, and the next candidate root. The state can be
+/// stored with [`save_state`](ParBfs::save_state) and restored with
+/// [`load_state`](ParBfs::load_state), so that a visit interrupted, e.g., by
+/// a crash can be resumed from the last saved layer.
+///
+/// The state must be loaded into a visit of the same graph: only the number
+/// of nodes is checked, and resuming on a different graph with the same
+/// number of nodes yields meaningless results. Since layers do not depend on
+/// the number of threads, the visit can be resumed with a different thread
+/// pool; it can also be resumed with a different intra-layer order, but then
+/// the remaining layers will be sorted differently.
+///
+/// The state is stored as a sequence of little-endian 64-bit words
+/// containing the number of nodes, the next candidate root, the distance of
+/// the current layer, the length of the current layer, the nodes of the
+/// current layer, and the visited nodes as a bit vector (one bit per node,
+/// starting from the lowest bit of the first word).
+pub struct ParBfs<'a, G: RandomAccessGraph> {
+    graph: &'a G,
+    order: IntraLayerOrder,
+    seen: Vec<AtomicBool>,
+    /// The smallest position in the previous layer of a parent of a node;
+    /// it is `usize::MAX` for all nodes between layers.
+    parent: Vec<AtomicUsize>,
+    /// The current layer; its nodes are already marked as seen.
+    layer: Vec<usize>,
+    distance: usize,
+    /// The next candidate root when the current layer is empty.
+    root: usize,
+}
+
+impl<'a, G: RandomAccessGraph + Sync> ParBfs<'a, G> {
+    /// Creates a new visit of `graph` sorting layers by `order`.
+    pub fn new(graph: &'a G, order: IntraLayerOrder) -> Self {
+        let num_nodes = graph.num_nodes();
+        Self {
+            graph,
+            order,
+            seen: (0..num_nodes).map(|_| AtomicBool::new(false)).collect(),
+            parent: (0..num_nodes)
+                .map(|_| AtomicUsize::new(usize::MAX))
+                .collect(),
+            layer: vec![],
+            distance: 0,
+            root: 0,
+        }
+    }
+
+    /// Resets the visit, so that it restarts from node zero.
+    pub fn reset(&mut self) {
+        self.seen
+            .iter()
+            .for_each(|seen| seen.store(false, Ordering::Relaxed));
+        self.layer.clear();
+        self.distance = 0;
+        self.root = 0;
+    }
+
+    /// Returns the number of nodes visited so far, including the nodes of
+    /// the current layer.
+    pub fn num_visited(&self) -> usize {
+        self.seen
+            .iter()
+            .filter(|seen| seen.load(Ordering::Relaxed))
+            .count()
+    }
+
+    /// Passes the current layer and its distance from the root to
+    /// `on_layer`, and computes the next layer using `thread_pool`.
+    ///
+    /// Returns false, without calling `on_layer`, if the visit is complete.
+    pub fn next_layer(
+        &mut self,
+        thread_pool: &ThreadPool,
+        on_layer: impl FnOnce(usize, &[usize]),
+    ) -> bool {
+        let (graph, seen, parent) = (self.graph, &self.seen, &self.parent);
+        if self.layer.is_empty() {
+            while self.root < seen.len() && seen[self.root].load(Ordering::Relaxed) {
+                self.root += 1;
+            }
+            if self.root == seen.len() {
+                return false;
+            }
+            seen[self.root].store(true, Ordering::Relaxed);
+            self.layer.push(self.root);
+            self.distance = 0;
+        }
+
+        on_layer(self.distance, &self.layer);
+
+        let layer = &self.layer;
+        let order = self.order;
+        self.layer = thread_pool.install(|| {
+            let mut next = layer
+                .par_iter()
+                .enumerate()
+                .flat_map_iter(|(pos, &node)| {
+                    graph.successors(node).into_iter().filter(move |&succ| {
+                        !seen[succ].load(Ordering::Relaxed)
+                            && parent[succ].fetch_min(pos, Ordering::Relaxed) == usize::MAX
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            match order {
+                IntraLayerOrder::Original => next.par_sort_unstable(),
+                IntraLayerOrder::DegreeDesc => {
+                    next.par_sort_unstable_by_key(|&node| (Reverse(graph.outdegree(node)), node))
+                }
+                IntraLayerOrder::Parent => next
+                    .par_sort_unstable_by_key(|&node| (parent[node].load(Ordering::Relaxed), node)),
+            }
+
+            next.par_iter().for_each(|&node| {
+                seen[node].store(true, Ordering::Relaxed);
+                parent[node].store(usize::MAX, Ordering::Relaxed);
+            });
+            next
+        });
+        self.distance += 1;
+        true
+    }
+
+    /// Writes atomically the state of the visit to `path`.
+    ///
+    /// See the [type documentation](ParBfs#resuming-a-visit) for the format.
+    pub fn save_state(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let tmp_path = path.with_extension("tmp");
+        let mut file = BufWriter::new(
+            std::fs::File::create(&tmp_path)
+                .with_context(|| format!("Could not create {}", tmp_path.display()))?,
+        );
+        let header = [self.seen.len(), self.root, self.distance, self.layer.len()];
+        let seen = self.seen.chunks(64).map(|chunk| {
+            chunk.iter().enumerate().fold(0, |word, (i, seen)| {
+                word | (seen.load(Ordering::Relaxed) as usize) << i
+            })
+        });
+        for word in header
+            .into_iter()
+            .chain(self.layer.iter().copied())
+            .chain(seen)
+        {
+            file.write_all(&(word as u64).to_le_bytes())
+                .with_context(|| format!("Could not write {}", tmp_path.display()))?;
+        }
+        file.into_inner()
+            .context("Could not flush visit state")?
+            .sync_all()
+            .context("Could not sync visit state")?;
+        std::fs::rename(&tmp_path, path)
+            .with_context(|| format!("Could not rename visit state to {}", path.display()))?;
+        Ok(())
+    }
+
+    /// Restores the state of the visit from `path`, which must have been
+    /// written by [`save_state`](ParBfs::save_state) for a visit of the same
+    /// graph.
+    pub fn load_state(&mut self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let mut file = BufReader::new(
+            std::fs::File::open(path)
+                .with_context(|| format!("Could not open {}", path.display()))?,
+        );
+        let mut read_word = || -> Result<usize> {
+            let mut buf = [0; 8];
+            file.read_exact(&mut buf)
+                .with_context(|| format!("Could not read {}", path.display()))?;
+            Ok(u64::from_le_bytes(buf) as usize)
+        };
+        let num_nodes = self.seen.len();
+        let state_nodes = read_word()?;
+        ensure!(
+            state_nodes == num_nodes,
+            "The visit state {} has {} nodes, but the graph has {} nodes",
+            path.display(),
+            state_nodes,
+            num_nodes
+        );
+        let root = read_word()?;
+        let distance = read_word()?;
+        let layer_len = read_word()?;
+        ensure!(
+            root <= num_nodes && layer_len <= num_nodes,
+            "Invalid visit state {}",
+            path.display()
+        );
+        let layer = (0..layer_len)
+            .map(|_| read_word())
+            .collect::<Result<Vec<_>>>()?;
+        ensure!(
+            layer.iter().all(|&node| node < num_nodes),
+            "Invalid node in the layer of visit state {}",
+            path.display()
+        );
+        for chunk in self.seen.chunks(64) {
+            let word = read_word()?;
+            for (i, seen) in chunk.iter().enumerate() {
+                seen.store(word & (1 << i) != 0, Ordering::Relaxed);
+            }
+        }
+        self.root = root;
+        self.distance = distance;
+        self.layer = layer;
+        Ok(())
+    }
+}
+
+/// Visits in parallel a graph breadth-first, layer by layer, passing each
+/// layer, sorted by `order`, to `on_layer` together with its distance from
+/// the root of the visit.
+///
+/// This function performs a complete [`ParBfs`] visit; use the latter
+/// directly to save and resume the state of the visit.
+pub fn par_bfs_layers<G: RandomAccessGraph + Sync>(
+    graph: &G,
+    order: IntraLayerOrder,
+    thread_pool: &ThreadPool,
+    pl: &mut impl ProgressLog,
+    mut on_layer: impl FnMut(usize, &[usize]),
+) {
+    pl.item_name("node")
+        .expected_updates(Some(graph.num_nodes()));
+    pl.start("Visiting graph in parallel BFS order...");
+
+    let mut visit = ParBfs::new(graph, order);
+    while visit.next_layer(thread_pool, |distance, layer| {
+        on_layer(distance, layer);
+        pl.update_with_count(layer.len());
+    }) {}
+
+    pl.done();
+}
+
+/// Returns the permutation induced by a [parallel breadth-first
+/// visit](par_bfs_layers), that is, the position of each node in the
+/// concatenation of the layers.
+pub fn par_bfs_perm<G: RandomAccessGraph + Sync>(
+    graph: &G,
+    order: IntraLayerOrder,
+    thread_pool: &ThreadPool,
+    pl: &mut impl ProgressLog,
+) -> Box<[usize]> {
+    let mut perm = vec![0; graph.num_nodes()].into_boxed_slice();
+    let mut next_id = 0;
+    par_bfs_layers(graph, order, thread_pool, pl, |_, layer| {
+        for &node in layer {
+            perm[node] = next_id;
+            next_id += 1;
+        }
+    });
+    perm
+}
@@ -0,0 +1,302 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::traits::{RandomAccessGraph, RandomAccessLabeling};
+use dsi_progress_logger::prelude::*;
+use sux::bits::BitVec;
+
+/// The biconnected components of an undirected graph, as returned by
+/// [`biconnected_components`].
+///
+/// Biconnected (i.e., 2-vertex-connected) components partition the edges of
+/// the graph; a node belongs to all the components of its incident edges,
+/// and nodes belonging to more than one component are exactly the
+/// articulation points.
+#[derive(Debug, Clone)]
+pub struct BiconnectedComponents {
+    num_components: usize,
+    articulation_points: BitVec,
+    /// The edges `(x, y)`, with `x` < `y`, in lexicographical order, and their
+    /// component.
+    edges: Box<[((usize, usize), usize)]>,
+    /// The number of nodes of each component.
+    sizes: Box<[usize]>,
+}
+
+impl BiconnectedComponents {
+    /// Returns the number of components.
+    pub fn num_components(&self) -> usize {
+        self.num_components
+    }
+
+    /// Returns the articulation points, that is, the nodes whose removal
+    /// increases the number of connected components of the graph, as a bit
+    /// vector indexed by nodes.
+    pub fn articulation_points(&self) -> &BitVec {
+        &self.articulation_points
+    }
+
+    /// Returns the edges `(x, y)`, with `x` < `y`, in lexicographical order,
+    /// together with their component.
+    pub fn edges(&self) -> &[((usize, usize), usize)] {
+        &self.edges
+    }
+
+    /// Returns the component of the edge between `x` and `y`, or `None` if
+    /// there is no such edge (or if `x` = `y`).
+    pub fn component(&self, x: usize, y: usize) -> Option<usize> {
+        let edge = (x.min(y), x.max(y));
+        self.edges
+            .binary_search_by_key(&edge, |&(e, _)| e)
+            .ok()
+            .map(|i| self.edges[i].1)
+    }
+
+    /// Returns the number of nodes of each component.
+    pub fn sizes(&self) -> &[usize] {
+        &self.sizes
+    }
+
+    /// Returns the index and the number of nodes of the largest component
+    /// (the first one in case of ties), or `None` if the graph has no edges.
+    pub fn largest(&self) -> Option<(usize, usize)> {
+        self.sizes
+            .iter()
+            .enumerate()
+            .max_by(|(a, x), (b, y)| x.cmp(y).then(b.cmp(a)))
+            .map(|(c, &size)| (c, size))
+    }
+}
+
+/// A frame of the stack of the depth-first visit.
+struct Frame<I> {
+    node: usize,
+    /// The parent of `node` in the visit tree, or `usize::MAX` for roots.
+    parent: usize,
+    /// Whether we have already skipped the arc back to the parent.
+    parent_skipped: bool,
+    /// The number of children of `node` in the visit tree.
+    children: usize,
+    succ: I,
+}
+
+/// Computes the biconnected components and the articulation points of an
+/// undirected graph.
+///
+/// The graph must be symmetric (i.e., undirected). Loops are ignored, and
+/// multiple copies of an edge belong to the same component, so they do not
+/// contribute to the biconnectivity of the graph. Isolated nodes belong to
+/// no component. Components are numbered in the order in which the visit
+/// completes them, and the size of the largest one is logged.
+///
+/// The computation uses the Hopcroft–Tarjan linear-time algorithm based on
+/// low links, with an explicit stack, so it does not overflow the call stack
+/// on deep graphs. Besides the result, it requires two `usize` per node and a
+/// stack of edges.
+pub fn biconnected_components<G: RandomAccessGraph>(
+    graph: &G,
+    pl: &mut impl ProgressLog,
+) -> BiconnectedComponents {
+    let num_nodes = graph.num_nodes();
+    // Discovery times (usize::MAX for unvisited nodes) and low links
+    let mut disc = vec![usize::MAX; num_nodes];
+    let mut low = vec![0; num_nodes];
+    let mut time = 0;
+    let mut articulation_points = BitVec::new(num_nodes);
+    let mut edge_stack = Vec::new();
+    let mut edges = Vec::new();
+    let mut sizes = Vec::new();
+    // The last component containing each node
+    let mut last_component = vec![usize::MAX; num_nodes];
+    let mut stack: Vec<Frame<<<G as RandomAccessLabeling>::Labels<'_> as IntoIterator>::IntoIter>> =
+        Vec::new();
+
+    pl.item_name("node").expected_updates(Some(num_nodes));
+    pl.start("Computing biconnected components...");
+
+    for root in 0..num_nodes {
+        if disc[root] != usize::MAX {
+            continue;
+        }
+        disc[root] = time;
+        low[root] = time;
+        time += 1;
+        stack.push(Frame {
+            node: root,
+            parent: usize::MAX,
+            parent_skipped: false,
+            children: 0,
+            succ: graph.successors(root).into_iter(),
+        });
+        pl.light_update();
+
+        while let Some(frame) = stack.last_mut() {
+            let node = frame.node;
+            match frame.succ.next() {
+                Some(succ) => {
+                    if succ == node {
+                        // loops are ignored
+                    } else if succ == frame.parent && !frame.parent_skipped {
+                        // A single copy of the tree arc back to the parent is
+                        // not a back arc; other copies are parallel arcs
+                        frame.parent_skipped = true;
+                    } else if disc[succ] == usize::MAX {
+                        frame.children += 1;
+                        edge_stack.push((node, succ));
+                        disc[succ] = time;
+                        low[succ] = time;
+                        time += 1;
+                        stack.push(Frame {
+                            node: succ,
+                            parent: node,
+                            parent_skipped: false,
+                            children: 0,
+                            succ: graph.successors(succ).into_iter(),
+                        });
+                        pl.light_update();
+                    } else if disc[succ] < disc[node] {
+                        // A back arc to an ancestor (arcs to descendants
+                        // have already been pushed from the other side)
+                        edge_stack.push((node, succ));
+                        low[node] = low[node].min(disc[succ]);
+                    }
+                }
+                None => {
+                    let Frame {
+                        parent, children, ..
+                    } = stack.pop().unwrap();
+                    if parent == usize::MAX {
+                        if children > 1 {
+                            articulation_points.set(node, true);
+                        }
+                        continue;
+                    }
+                    low[parent] = low[parent].min(low[node]);
+                    if low[node] >= disc[parent] {
+                        // parent separates the subtree of node: unless it is
+                        // a root, it is an articulation point
+                        if stack.last().unwrap().parent != usize::MAX {
+                            articulation_points.set(parent, true);
+                        }
+                        let component = sizes.len();
+                        let mut size = 0;
+                        loop {
+                            let (x, y) = edge_stack.pop().unwrap();
+                            for z in [x, y] {
+                                if last_component[z] != component {
+                                    last_component[z] = component;
+                                    size += 1;
+                                }
+                            }
+                            edges.push(((x.min(y), x.max(y)), component));
+                            if (x, y) == (parent, node) {
+                                break;
+                            }
+                        }
+                        sizes.push(size);
+                    }
+                }
+            }
+        }
+    }
+
+    pl.done();
+
+    // Parallel edges are in the same component
+    edges.sort_unstable();
+    edges.dedup();
+    let result = BiconnectedComponents {
+        num_components: sizes.len(),
+        articulation_points,
+        edges: edges.into_boxed_slice(),
+        sizes: sizes.into_boxed_slice(),
+    };
+    if let Some((_, size)) = result.largest() {
+        log::info!(
+            "{} biconnected components; the largest one has {} nodes",
+            result.num_components(),
+            size
+        );
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphs::vec_graph::VecGraph;
+    use crate::labels::Left;
+
+    fn undirected(arcs: &[(usize, usize)]) -> Left<VecGraph> {
+        Left(VecGraph::from_arc_list(
+            arcs.iter().flat_map(|&(x, y)| [(x, y), (y, x)]),
+        ))
+    }
+
+    fn articulation_points(b: &BiconnectedComponents) -> Vec<usize> {
+        let points = b.articulation_points();
+        (0..points.len()).filter(|&x| points.get(x)).collect()
+    }
+
+    #[test]
+    fn test_biconnected_components() {
+        // Two triangles sharing node 2, a square attached to node 4 by
+        // the edge 4-5, a pendant edge 8-9, and a loop on node 0
+        let mut g = undirected(&[
+            (0, 1),
+            (1, 2),
+            (2, 0),
+            (2, 3),
+            (3, 4),
+            (4, 2),
+            (4, 5),
+            (5, 6),
+            (6, 7),
+            (7, 8),
+            (8, 5),
+            (8, 9),
+        ]);
+        g.0.add_arc(0, 0);
+        g.0.add_node(10);
+        let b = biconnected_components(&g, &mut ProgressLogger::default());
+        assert_eq!(b.num_components(), 5);
+        assert_eq!(articulation_points(&b), vec![2, 4, 5, 8]);
+        assert_eq!(b.component(0, 1), b.component(2, 1));
+        assert_eq!(b.component(0, 1), b.component(0, 2));
+        assert_ne!(b.component(0, 1), b.component(2, 3));
+        assert_eq!(b.component(2, 3), b.component(4, 2));
+        assert_eq!(b.component(5, 6), b.component(8, 5));
+        assert_ne!(b.component(4, 5), b.component(5, 6));
+        assert_ne!(b.component(8, 9), b.component(7, 8));
+        assert_eq!(b.component(0, 0), None);
+        assert_eq!(b.component(0, 3), None);
+        assert_eq!(b.edges().len(), 12);
+        assert_eq!(b.largest().map(|(_, size)| size), Some(4));
+        let mut sizes = b.sizes().to_vec();
+        sizes.sort_unstable();
+        assert_eq!(sizes, vec![2, 2, 3, 3, 4]);
+    }
+
+    #[test]
+    fn test_biconnected_cycle_and_path() {
+        let cycle = undirected(&[(0, 1), (1, 2), (2, 3), (3, 0)]);
+        let b = biconnected_components(&cycle, &mut ProgressLogger::default());
+        assert_eq!(b.num_components(), 1);
+        assert!(articulation_points(&b).is_empty());
+        assert_eq!(b.largest(), Some((0, 4)));
+
+        let path = undirected(&[(0, 1), (1, 2), (2, 3)]);
+        let b = biconnected_components(&path, &mut ProgressLogger::default());
+        assert_eq!(b.num_components(), 3);
+        assert_eq!(articulation_points(&b), vec![1, 2]);
+        assert_eq!(b.edges().len(), 3);
+
+        let empty = Left(VecGraph::<()>::empty(3));
+        let b = biconnected_components(&empty, &mut ProgressLogger::default());
+        assert_eq!(b.num_components(), 0);
+        assert_eq!(b.largest(), None);
+    }
+}
@@ -0,0 +1,248 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use super::strongly_connected_components;
+use crate::traits::RandomAccessGraph;
+use dsi_progress_logger::prelude::*;
+use sux::bits::BitVec;
+
+/// The regions of the [bow-tie decomposition](bow_tie) of a graph.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum BowTieRegion {
+    /// The largest strongly connected component.
+    Core,
+    /// Nodes not in the core that can reach the core.
+    In,
+    /// Nodes not in the core that are reachable from the core.
+    Out,
+    /// Nodes reachable from `In` that cannot reach `Out`, or that can reach
+    /// `Out` but are not reachable from `In` (and are not in other regions).
+    Tendrils,
+    /// Nodes reachable from `In` that can reach `Out` (and are not in other
+    /// regions).
+    Tubes,
+    /// All other nodes.
+    Disconnected,
+}
+
+impl BowTieRegion {
+    /// All regions, in the order of their numerical values.
+    pub const ALL: [BowTieRegion; 6] = [
+        BowTieRegion::Core,
+        BowTieRegion::In,
+        BowTieRegion::Out,
+        BowTieRegion::Tendrils,
+        BowTieRegion::Tubes,
+        BowTieRegion::Disconnected,
+    ];
+
+    /// Returns the name of the region in lowercase.
+    pub fn name(&self) -> &'static str {
+        match self {
+            BowTieRegion::Core => "core",
+            BowTieRegion::In => "in",
+            BowTieRegion::Out => "out",
+            BowTieRegion::Tendrils => "tendrils",
+            BowTieRegion::Tubes => "tubes",
+            BowTieRegion::Disconnected => "disconnected",
+        }
+    }
+}
+
+/// The bow-tie decomposition of a graph, as returned by [`bow_tie`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BowTie {
+    regions: Box<[BowTieRegion]>,
+    sizes: [usize; 6],
+}
+
+impl BowTie {
+    /// Returns the region of each node.
+    pub fn regions(&self) -> &[BowTieRegion] {
+        &self.regions
+    }
+
+    /// Returns the size of a region.
+    pub fn size(&self, region: BowTieRegion) -> usize {
+        self.sizes[region as usize]
+    }
+
+    /// Returns the sizes of the regions, indexed by their numerical values
+    /// (i.e., in the order of [`BowTieRegion::ALL`]).
+    pub fn sizes(&self) -> &[usize; 6] {
+        &self.sizes
+    }
+}
+
+/// Computes the bow-tie decomposition of a graph, given the graph and its
+/// transpose.
+///
+/// The core of the bow tie is the largest strongly connected component (the
+/// one completed first by [`strongly_connected_components`], in case of
+/// ties). A forward visit from the core finds the `Out` region, and a
+/// backward visit (i.e., a visit of the transpose) finds the `In` region.
+/// Then, a forward visit from `In` and a backward visit from `Out`, both
+/// restricted to the remaining nodes, find tubes and tendrils; all other nodes
+/// are disconnected. Tendrils thus include both nodes hanging off `In` and
+/// nodes leading into `Out`.
+///
+/// Besides the [`Components`](super::Components) used to find the core, the
+/// algorithm uses a byte and a bit per node, plus the lists of nodes in the
+/// core, `In` and `Out`. An empty graph has an empty decomposition.
+pub fn bow_tie(
+    graph: &impl RandomAccessGraph,
+    transpose: &impl RandomAccessGraph,
+    pl: &mut impl ProgressLog,
+) -> BowTie {
+    let num_nodes = graph.num_nodes();
+    assert_eq!(
+        num_nodes,
+        transpose.num_nodes(),
+        "The graph and its transpose have a different number of nodes"
+    );
+    // Nodes not yet assigned to a region are temporarily disconnected
+    let mut regions = vec![BowTieRegion::Disconnected; num_nodes].into_boxed_slice();
+    let mut sizes = [0; 6];
+    if num_nodes == 0 {
+        return BowTie { regions, sizes };
+    }
+
+    let mut components = strongly_connected_components(graph, pl);
+    components.sort_by_size();
+    let core = components
+        .components()
+        .iter()
+        .enumerate()
+        .filter(|&(_, &c)| c == 0)
+        .map(|(node, _)| node)
+        .collect::<Vec<_>>();
+    for &node in &core {
+        regions[node] = BowTieRegion::Core;
+    }
+    drop(components);
+
+    pl.item_name("node");
+    pl.expected_updates(Some(num_nodes));
+    pl.start("Computing the bow-tie decomposition...");
+    pl.update_with_count(core.len());
+
+    // Out and In: forward and backward visits from the core
+    let mut out_nodes = Vec::new();
+    visit(graph, core.clone(), |node| {
+        if regions[node] != BowTieRegion::Disconnected {
+            return false;
+        }
+        regions[node] = BowTieRegion::Out;
+        out_nodes.push(node);
+        pl.light_update();
+        true
+    });
+    let mut in_nodes = Vec::new();
+    visit(transpose, core, |node| {
+        if regions[node] != BowTieRegion::Disconnected {
+            return false;
+        }
+        regions[node] = BowTieRegion::In;
+        in_nodes.push(node);
+        pl.light_update();
+        true
+    });
+
+    // Remaining nodes reachable from In are temporarily marked as tendrils
+    visit(graph, in_nodes, |node| {
+        if regions[node] != BowTieRegion::Disconnected {
+            return false;
+        }
+        regions[node] = BowTieRegion::Tendrils;
+        pl.light_update();
+        true
+    });
+
+    // Remaining nodes that can reach Out are tubes, if they are reachable
+    // from In, or tendrils otherwise
+    let mut seen = BitVec::new(num_nodes);
+    visit(transpose, out_nodes, |node| {
+        if seen.get(node) {
+            return false;
+        }
+        match regions[node] {
+            BowTieRegion::Tendrils => regions[node] = BowTieRegion::Tubes,
+            BowTieRegion::Disconnected => {
+                regions[node] = BowTieRegion::Tendrils;
+                pl.light_update();
+            }
+            _ => return false,
+        }
+        seen.set(node, true);
+        true
+    });
+
+    pl.done();
+
+    for &region in regions.iter() {
+        sizes[region as usize] += 1;
+    }
+    BowTie { regions, sizes }
+}
+
+/// Visits the graph from the given sources, enqueueing the successors for
+/// which `reach` returns true.
+fn visit(
+    graph: &impl RandomAccessGraph,
+    sources: Vec<usize>,
+    mut reach: impl FnMut(usize) -> bool,
+) {
+    let mut queue = sources;
+    while let Some(node) = queue.pop() {
+        for succ in graph.successors(node) {
+            if reach(succ) {
+                queue.push(succ);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_bow_tie() {
+    use crate::graphs::vec_graph::VecGraph;
+    use crate::prelude::*;
+    let arcs = [
+        (0, 1),
+        (1, 2),
+        (2, 0),
+        (3, 0),
+        (1, 4),
+        (3, 5),
+        (6, 4),
+        (3, 7),
+        (7, 4),
+        (8, 9),
+    ];
+    let graph = Left(VecGraph::from_arc_list(arcs));
+    let transpose = Left(VecGraph::from_arc_list(arcs.map(|(x, y)| (y, x))));
+    let bow_tie = bow_tie(&graph, &transpose, &mut ProgressLogger::default());
+
+    use BowTieRegion::*;
+    assert_eq!(
+        bow_tie.regions(),
+        &[
+            Core,
+            Core,
+            Core,
+            In,
+            Out,
+            Tendrils,
+            Tendrils,
+            Tubes,
+            Disconnected,
+            Disconnected
+        ]
+    );
+    assert_eq!(bow_tie.sizes(), &[3, 1, 1, 2, 1, 2]);
+    assert_eq!(bow_tie.size(Tendrils), 2);
+}
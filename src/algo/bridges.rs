@@ -0,0 +1,195 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use super::Components;
+use crate::traits::{RandomAccessGraph, RandomAccessLabeling};
+use dsi_progress_logger::prelude::*;
+
+/// A frame of the stack of the depth-first visit.
+struct Frame<I> {
+    node: usize,
+    /// The parent of `node` in the visit tree, or `usize::MAX` for roots.
+    parent: usize,
+    /// Whether we have already skipped the arc back to the parent.
+    parent_skipped: bool,
+    /// The position of `node` in the stack of visited nodes.
+    pos: usize,
+    succ: I,
+}
+
+/// Runs Tarjan's bridge-finding algorithm, calling `on_bridge` on each bridge
+/// and `on_component` on the nodes of each 2-edge-connected component.
+fn visit<G: RandomAccessGraph>(
+    graph: &G,
+    mut on_bridge: impl FnMut(usize, usize),
+    mut on_component: impl FnMut(&[usize]),
+) {
+    let num_nodes = graph.num_nodes();
+    // Discovery times (usize::MAX for unvisited nodes) and low links
+    let mut disc = vec![usize::MAX; num_nodes];
+    let mut low = vec![0; num_nodes];
+    let mut time = 0;
+    let mut nodes = Vec::new();
+    let mut stack: Vec<Frame<<<G as RandomAccessLabeling>::Labels<'_> as IntoIterator>::IntoIter>> =
+        Vec::new();
+
+    let mut pl = ProgressLogger::default();
+    pl.display_memory(true)
+        .item_name("node")
+        .expected_updates(Some(num_nodes));
+    pl.start("Looking for bridges...");
+
+    for root in 0..num_nodes {
+        if disc[root] != usize::MAX {
+            continue;
+        }
+        disc[root] = time;
+        low[root] = time;
+        time += 1;
+        stack.push(Frame {
+            node: root,
+            parent: usize::MAX,
+            parent_skipped: false,
+            pos: nodes.len(),
+            succ: graph.successors(root).into_iter(),
+        });
+        nodes.push(root);
+        pl.light_update();
+
+        while let Some(frame) = stack.last_mut() {
+            let node = frame.node;
+            match frame.succ.next() {
+                Some(succ) => {
+                    if succ == frame.parent && !frame.parent_skipped {
+                        // A single copy of the tree arc back to the parent is
+                        // not a back arc; other copies are parallel arcs
+                        frame.parent_skipped = true;
+                    } else if disc[succ] == usize::MAX {
+                        disc[succ] = time;
+                        low[succ] = time;
+                        time += 1;
+                        stack.push(Frame {
+                            node: succ,
+                            parent: node,
+                            parent_skipped: false,
+                            pos: nodes.len(),
+                            succ: graph.successors(succ).into_iter(),
+                        });
+                        nodes.push(succ);
+                        pl.light_update();
+                    } else {
+                        low[node] = low[node].min(disc[succ]);
+                    }
+                }
+                None => {
+                    let Frame { parent, pos, .. } = stack.pop().unwrap();
+                    if parent != usize::MAX {
+                        low[parent] = low[parent].min(low[node]);
+                    }
+                    if parent == usize::MAX || low[node] > disc[parent] {
+                        if parent != usize::MAX {
+                            on_bridge(parent, node);
+                        }
+                        on_component(&nodes[pos..]);
+                        nodes.truncate(pos);
+                    }
+                }
+            }
+        }
+    }
+
+    pl.done();
+}
+
+/// Returns the bridges of an undirected graph.
+///
+/// A bridge is an arc whose removal increases the number of connected
+/// components of the graph. The graph must be symmetric (i.e., undirected), and
+/// each bridge is returned once as a pair `(x, y)` with `x` < `y`; pairs are
+/// sorted lexicographically. Loops are never bridges, and neither are
+/// arcs with multiple copies.
+///
+/// The computation uses Tarjan's linear-time algorithm with an explicit stack,
+/// so it does not overflow the call stack on deep graphs.
+pub fn bridges(graph: &impl RandomAccessGraph) -> Box<[(usize, usize)]> {
+    let mut bridges = Vec::new();
+    visit(graph, |x, y| bridges.push((x.min(y), x.max(y))), |_| {});
+    bridges.sort_unstable();
+    bridges.into_boxed_slice()
+}
+
+/// Returns the 2-edge-connected components of an undirected graph.
+///
+/// The 2-edge-connected components are the connected components of the graph
+/// obtained by removing all [bridges](bridges). The graph must be symmetric
+/// (i.e., undirected). Components are numbered in the order in which the
+/// visit completes them.
+pub fn bridge_components(graph: &impl RandomAccessGraph) -> Components {
+    let mut components = vec![0; graph.num_nodes()].into_boxed_slice();
+    let mut num_components = 0;
+    visit(
+        graph,
+        |_, _| {},
+        |nodes| {
+            for &node in nodes {
+                components[node] = num_components;
+            }
+            num_components += 1;
+        },
+    );
+    Components::new(num_components, components)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphs::vec_graph::VecGraph;
+    use crate::labels::Left;
+
+    fn undirected(arcs: &[(usize, usize)]) -> Left<VecGraph> {
+        Left(VecGraph::from_arc_list(
+            arcs.iter().flat_map(|&(x, y)| [(x, y), (y, x)]),
+        ))
+    }
+
+    #[test]
+    fn test_bridges() {
+        // Two triangles joined by the arc 2-3, plus the pendant arc 5-6
+        let g = undirected(&[
+            (0, 1),
+            (1, 2),
+            (2, 0),
+            (2, 3),
+            (3, 4),
+            (4, 5),
+            (5, 3),
+            (5, 6),
+        ]);
+        assert_eq!(bridges(&g).as_ref(), &[(2, 3), (5, 6)]);
+
+        let mut components = bridge_components(&g);
+        assert_eq!(components.num_components(), 3);
+        assert_eq!(components.sort_by_size().as_ref(), &[3, 3, 1]);
+        let c = components.components();
+        assert_eq!(c[0], c[1]);
+        assert_eq!(c[1], c[2]);
+        assert_eq!(c[3], c[4]);
+        assert_eq!(c[4], c[5]);
+        assert_ne!(c[0], c[3]);
+        assert_eq!(c[6], 2);
+    }
+
+    #[test]
+    fn test_bridges_path_and_cycle() {
+        let path = undirected(&[(0, 1), (1, 2), (2, 3)]);
+        assert_eq!(bridges(&path).as_ref(), &[(0, 1), (1, 2), (2, 3)]);
+        assert_eq!(bridge_components(&path).num_components(), 4);
+
+        let cycle = undirected(&[(0, 1), (1, 2), (2, 3), (3, 0)]);
+        assert!(bridges(&cycle).is_empty());
+        assert_eq!(bridge_components(&cycle).num_components(), 1);
+    }
+}
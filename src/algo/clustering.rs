@@ -0,0 +1,104 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::traits::RandomAccessGraph;
+use dsi_progress_logger::prelude::*;
+use rayon::prelude::*;
+use rayon::ThreadPool;
+use std::sync::Mutex;
+
+/// The number of nodes processed by a parallel task.
+const CHUNK_SIZE: usize = 1024;
+
+/// Computes in parallel the local clustering coefficient of every node of a
+/// symmetric graph.
+///
+/// The local clustering coefficient of a node with _d_ ≥ 2 neighbors is the
+/// number of edges between its neighbors divided by _d_(_d_ − 1) / 2; nodes
+/// with less than two neighbors have coefficient zero. Loops are ignored.
+///
+/// The graph must be symmetric and without duplicate arcs; successor lists
+/// need not be sorted.
+pub fn local_clustering_coefficients(
+    graph: &(impl RandomAccessGraph + Sync),
+    thread_pool: &ThreadPool,
+    pl: &mut (impl ProgressLog + Send),
+) -> Vec<f64> {
+    let num_nodes = graph.num_nodes();
+    let mut coefficients = vec![0.0; num_nodes];
+
+    pl.item_name("node").expected_updates(Some(num_nodes));
+    pl.start("Computing local clustering coefficients...");
+    let pl = Mutex::new(pl);
+
+    thread_pool.install(|| {
+        coefficients
+            .par_chunks_mut(CHUNK_SIZE)
+            .enumerate()
+            .for_each_init(Vec::new, |neighbors, (chunk_index, chunk)| {
+                let first_node = chunk_index * CHUNK_SIZE;
+                for (i, coefficient) in chunk.iter_mut().enumerate() {
+                    *coefficient = local_clustering(graph, first_node + i, neighbors);
+                }
+                pl.lock().unwrap().update_with_count(chunk.len());
+            })
+    });
+
+    pl.into_inner().unwrap().done();
+    coefficients
+}
+
+/// Computes the local clustering coefficient of `node` using `neighbors` as a
+/// buffer.
+fn local_clustering(
+    graph: &impl RandomAccessGraph,
+    node: usize,
+    neighbors: &mut Vec<usize>,
+) -> f64 {
+    neighbors.clear();
+    neighbors.extend(graph.successors(node).into_iter().filter(|&x| x != node));
+    neighbors.sort_unstable();
+    neighbors.dedup();
+
+    let d = neighbors.len();
+    if d < 2 {
+        return 0.0;
+    }
+
+    // Each edge between neighbors is counted twice
+    let mut links = 0_u64;
+    for &u in neighbors.iter() {
+        for v in graph.successors(u) {
+            if v != u && neighbors.binary_search(&v).is_ok() {
+                links += 1;
+            }
+        }
+    }
+
+    links as f64 / (d * (d - 1)) as f64
+}
+
+#[cfg(test)]
+#[test]
+fn test_local_clustering_coefficients() {
+    use crate::graphs::vec_graph::VecGraph;
+    use crate::labels::Left;
+    // A triangle 0-1-2 with a pendant path 2-3-4, plus an isolated node 5
+    let mut arcs = vec![];
+    for (x, y) in [(0, 1), (1, 2), (2, 0), (2, 3), (3, 4)] {
+        arcs.push((x, y));
+        arcs.push((y, x));
+    }
+    let mut g = VecGraph::from_arc_list(arcs);
+    g.add_node(5);
+    let g = Left(g);
+    let thread_pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(2)
+        .build()
+        .unwrap();
+    let c = local_clustering_coefficients(&g, &thread_pool, &mut ProgressLogger::default());
+    assert_eq!(c, vec![1.0, 1.0, 1.0 / 3.0, 0.0, 0.0, 0.0]);
+}
@@ -0,0 +1,126 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::traits::SequentialGraph;
+use dsi_progress_logger::prelude::*;
+use lender::*;
+
+/// The sizes of the connected components of a graph.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComponentSizes {
+    /// The sizes of the components, in non-increasing order.
+    pub sizes: Vec<usize>,
+    /// A node of the largest component (or `None` if the graph is empty).
+    pub largest_representative: Option<usize>,
+}
+
+/// A union-find structure with union by size and path halving.
+///
+/// Each node uses a single `usize`: non-root nodes store the index of their
+/// parent, whereas roots store the bitwise negation of the size of their set,
+/// which is distinguishable from a node index as it has the most significant
+/// bit set.
+struct UnionFind(Vec<usize>);
+
+impl UnionFind {
+    fn new(n: usize) -> Self {
+        Self(vec![!1; n])
+    }
+
+    #[inline(always)]
+    fn is_root(&self, x: usize) -> bool {
+        self.0[x] > isize::MAX as usize
+    }
+
+    fn find(&mut self, mut x: usize) -> usize {
+        while !self.is_root(x) {
+            let parent = self.0[x];
+            if !self.is_root(parent) {
+                self.0[x] = self.0[parent];
+            }
+            x = parent;
+        }
+        x
+    }
+
+    fn union(&mut self, x: usize, y: usize) {
+        let (x, y) = (self.find(x), self.find(y));
+        if x == y {
+            return;
+        }
+        let (size_x, size_y) = (!self.0[x], !self.0[y]);
+        let (small, large) = if size_x < size_y { (x, y) } else { (y, x) };
+        self.0[large] = !(size_x + size_y);
+        self.0[small] = large;
+    }
+}
+
+/// Computes the sizes of the connected components of a graph without storing
+/// the component of each node.
+///
+/// The computation uses a union-find structure with one `usize` per node,
+/// which is freed before returning, and a single sequential scan of the
+/// graph. Arcs are considered undirected, so on a directed graph the result
+/// are the sizes of the weakly connected components.
+///
+/// The returned [`ComponentSizes`] contain the multiset of sizes, sorted in
+/// non-increasing order, and a representative node of the largest component,
+/// which can be used to extract it later, e.g., by a visit.
+pub fn component_sizes(graph: &impl SequentialGraph) -> ComponentSizes {
+    let num_nodes = graph.num_nodes();
+    let mut uf = UnionFind::new(num_nodes);
+
+    let mut pl = ProgressLogger::default();
+    pl.display_memory(true)
+        .item_name("node")
+        .expected_updates(Some(num_nodes));
+    pl.start("Computing connected components...");
+
+    for_!( (src, succ) in graph.iter() {
+        for dst in succ {
+            uf.union(src, dst);
+        }
+        pl.light_update();
+    });
+    pl.done();
+
+    let mut sizes = Vec::new();
+    let mut largest_representative = None;
+    let mut largest_size = 0;
+    for node in 0..num_nodes {
+        if uf.is_root(node) {
+            let size = !uf.0[node];
+            if size > largest_size {
+                largest_size = size;
+                largest_representative = Some(node);
+            }
+            sizes.push(size);
+        }
+    }
+    drop(uf);
+
+    sizes.sort_unstable_by(|a, b| b.cmp(a));
+    ComponentSizes {
+        sizes,
+        largest_representative,
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_component_sizes() {
+    use crate::graphs::vec_graph::VecGraph;
+    use crate::labels::Left;
+    let mut g = VecGraph::from_arc_list([(0, 1), (1, 2), (3, 4), (5, 3), (6, 6)]);
+    g.add_node(7);
+    let cs = component_sizes(&Left(g));
+    assert_eq!(cs.sizes, vec![3, 3, 1, 1]);
+    assert!(matches!(cs.largest_representative, Some(0..=2)));
+
+    let empty = component_sizes(&Left(VecGraph::<()>::new()));
+    assert!(empty.sizes.is_empty());
+    assert_eq!(empty.largest_representative, None);
+}
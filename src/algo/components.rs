@@ -0,0 +1,85 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+/// A partition of the nodes of a graph into components.
+///
+/// Components are numbered from zero, and the component of each node is
+/// stored in a slice indexed by nodes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Components {
+    num_components: usize,
+    components: Box<[usize]>,
+}
+
+impl Components {
+    /// Creates a new partition given the number of components and the
+    /// component of each node.
+    ///
+    /// # Panics
+    ///
+    /// If some component index is not smaller than `num_components`.
+    pub fn new(num_components: usize, components: Box<[usize]>) -> Self {
+        assert!(
+            components.iter().all(|&c| c < num_components),
+            "Component indices must be smaller than the number of components"
+        );
+        Self {
+            num_components,
+            components,
+        }
+    }
+
+    /// Returns the number of components.
+    pub fn num_components(&self) -> usize {
+        self.num_components
+    }
+
+    /// Returns the component of each node.
+    pub fn components(&self) -> &[usize] {
+        &self.components
+    }
+
+    /// Returns the component of each node, consuming this structure.
+    pub fn into_components(self) -> Box<[usize]> {
+        self.components
+    }
+
+    /// Returns the size of each component.
+    pub fn compute_sizes(&self) -> Box<[usize]> {
+        let mut sizes = vec![0; self.num_components].into_boxed_slice();
+        for &c in self.components.iter() {
+            sizes[c] += 1;
+        }
+        sizes
+    }
+
+    /// Renumbers the components in non-increasing order of size, and returns
+    /// their sizes.
+    ///
+    /// Components of the same size keep their relative order.
+    pub fn sort_by_size(&mut self) -> Box<[usize]> {
+        let sizes = self.compute_sizes();
+        let mut order = (0..self.num_components).collect::<Vec<_>>();
+        order.sort_by(|&a, &b| sizes[b].cmp(&sizes[a]));
+        let mut new_index = vec![0; self.num_components];
+        for (i, &c) in order.iter().enumerate() {
+            new_index[c] = i;
+        }
+        for c in self.components.iter_mut() {
+            *c = new_index[*c];
+        }
+        order.iter().map(|&c| sizes[c]).collect()
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_sort_by_size() {
+    let mut components = Components::new(3, vec![0, 1, 1, 2, 1, 2].into_boxed_slice());
+    assert_eq!(components.compute_sizes().as_ref(), &[1, 3, 2]);
+    assert_eq!(components.sort_by_size().as_ref(), &[3, 2, 1]);
+    assert_eq!(components.components(), &[2, 0, 0, 1, 0, 1]);
+}
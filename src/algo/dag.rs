@@ -0,0 +1,170 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::graphs::arc_list_graph::ArcListGraph;
+use crate::labels::Left;
+use crate::traits::RandomAccessGraph;
+use crate::utils::sort_pairs::{BatchIterator, KMergeIters, SortPairs};
+use anyhow::{ensure, Result};
+use dsi_progress_logger::prelude::*;
+use lender::*;
+use tempfile::Builder;
+
+/// Returns a topological order of the nodes of a graph, that is, a sequence
+/// of all nodes in which the source of every arc precedes its target, or
+/// `None` if the graph contains a cycle (including self-loops).
+///
+/// The order is computed by Kahn's algorithm, and it is the smallest in
+/// the sense that at each step the smallest node with no remaining
+/// predecessors is chosen among those made available by the previous steps
+/// (in first-in, first-out order).
+pub fn topological_sort(graph: &impl RandomAccessGraph) -> Option<Box<[usize]>> {
+    let num_nodes = graph.num_nodes();
+    let mut indegrees = vec![0_usize; num_nodes];
+    for_!((_node, succ) in graph.iter() {
+        for s in succ {
+            indegrees[s] += 1;
+        }
+    });
+    let mut order = Vec::with_capacity(num_nodes);
+    order.extend((0..num_nodes).filter(|&node| indegrees[node] == 0));
+    let mut next = 0;
+    while next < order.len() {
+        let node = order[next];
+        next += 1;
+        for succ in graph.successors(node) {
+            indegrees[succ] -= 1;
+            if indegrees[succ] == 0 {
+                order.push(succ);
+            }
+        }
+    }
+    (order.len() == num_nodes).then(|| order.into_boxed_slice())
+}
+
+/// Returns whether a graph is acyclic.
+///
+/// Self-loops are cycles, so a graph with a self-loop is not acyclic.
+pub fn is_acyclic(graph: &impl RandomAccessGraph) -> bool {
+    topological_sort(graph).is_some()
+}
+
+/// Returns the transitive reduction of a directed acyclic graph as a
+/// [sequential graph](crate::traits::SequentialGraph).
+///
+/// The transitive reduction of a DAG is the unique graph with the fewest arcs
+/// having the same reachability relation; it is obtained by removing every
+/// arc *x* → *y* such that *y* is reachable from *x* by a path of length at
+/// least two. The transitive reduction is defined uniquely only for DAGs,
+/// so an error is returned if the graph [is not acyclic](is_acyclic).
+/// Duplicate arcs are reduced to a single arc.
+///
+/// The successors of each node *x* are examined in [topological
+/// order](topological_sort): a successor already reachable from a previous
+/// successor is redundant; otherwise, the arc is kept and all nodes reachable
+/// from the successor are marked. Since every node reachable from *x* is
+/// marked at most once, the algorithm requires time *O*(*n* + *m*) per node,
+/// that is, *O*(*nm*) in the worst case (but usually much less, as
+/// visits stop at marked nodes), and two `usize` per node plus a visit stack.
+///
+/// The arcs of the result are sorted using [`SortPairs`]; for the meaning of
+/// `batch_size`, see [`SortPairs::new`].
+#[allow(clippy::type_complexity)]
+pub fn transitive_reduction(
+    dag: &impl RandomAccessGraph,
+    batch_size: usize,
+    pl: &mut impl ProgressLog,
+) -> Result<Left<ArcListGraph<KMergeIters<BatchIterator<()>, ()>>>> {
+    let num_nodes = dag.num_nodes();
+    let order = topological_sort(dag);
+    ensure!(
+        order.is_some(),
+        "The graph contains a cycle, so its transitive reduction is not defined"
+    );
+    let order = order.unwrap();
+    let mut position = vec![0; num_nodes];
+    for (pos, &node) in order.iter().enumerate() {
+        position[node] = pos;
+    }
+
+    let dir = Builder::new().prefix("transitive_reduction_").tempdir()?;
+    let mut sorted = SortPairs::new(batch_size, dir.path())?;
+
+    // The last node whose visit marked each node; usize::MAX if never marked
+    let mut marked_by = vec![usize::MAX; num_nodes];
+    let mut successors = Vec::new();
+    let mut stack = Vec::new();
+
+    pl.item_name("node").expected_updates(Some(num_nodes));
+    pl.start("Computing transitive reduction...");
+    for node in 0..num_nodes {
+        successors.clear();
+        successors.extend(dag.successors(node));
+        successors.sort_unstable_by_key(|&succ| position[succ]);
+        for &succ in &successors {
+            if marked_by[succ] == node {
+                // reachable from a previous successor, or a duplicate
+                continue;
+            }
+            sorted.push(node, succ)?;
+            marked_by[succ] = node;
+            stack.push(succ);
+            while let Some(x) = stack.pop() {
+                for y in dag.successors(x) {
+                    if marked_by[y] != node {
+                        marked_by[y] = node;
+                        stack.push(y);
+                    }
+                }
+            }
+        }
+        pl.light_update();
+    }
+    pl.done();
+
+    Ok(Left(ArcListGraph::new_labeled(num_nodes, sorted.iter()?)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphs::vec_graph::VecGraph;
+    use crate::prelude::*;
+
+    #[test]
+    fn test_transitive_reduction() -> Result<()> {
+        // 0 -> 1 -> 2 -> 3, plus the shortcuts 0 -> 2, 0 -> 3, 1 -> 3,
+        // and 4 -> 3, 4 -> 0, 4 -> 2 (implied by 4 -> 0)
+        let dag = Left(VecGraph::from_arc_list([
+            (0, 1),
+            (0, 2),
+            (0, 3),
+            (1, 2),
+            (1, 3),
+            (2, 3),
+            (4, 0),
+            (4, 2),
+            (4, 3),
+        ]));
+        assert!(is_acyclic(&dag));
+        let order = topological_sort(&dag).unwrap();
+        assert_eq!(&*order, &[4, 0, 1, 2, 3]);
+
+        let reduced = transitive_reduction(&dag, 10, &mut ProgressLogger::default())?;
+        let mut arcs = vec![];
+        for_!((src, succ) in reduced.iter() {
+            arcs.extend(succ.into_iter().map(|dst| (src, dst)));
+        });
+        assert_eq!(arcs, vec![(0, 1), (1, 2), (2, 3), (4, 0)]);
+
+        let cyclic = Left(VecGraph::from_arc_list([(0, 1), (1, 2), (2, 0)]));
+        assert!(!is_acyclic(&cyclic));
+        assert!(transitive_reduction(&cyclic, 10, &mut ProgressLogger::default()).is_err());
+        let self_loop = Left(VecGraph::from_arc_list([(0, 1), (1, 1)]));
+        assert!(!is_acyclic(&self_loop));
+        Ok(())
+    }
+}
@@ -0,0 +1,115 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::traits::SequentialGraph;
+use dsi_progress_logger::prelude::*;
+use lender::prelude::*;
+use rayon::prelude::*;
+use rayon::ThreadPool;
+
+/// The degree used by [`degree_centrality`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DegreeMode {
+    /// The indegree divided by the number of nodes minus one.
+    In,
+    /// The outdegree divided by the number of nodes minus one.
+    Out,
+    /// The ratio between the indegree plus one and the outdegree plus one.
+    Ratio,
+}
+
+/// Computes in parallel a degree centrality of every node given functions
+/// returning the outdegree and the indegree of a node.
+///
+/// In-degree and out-degree centralities are normalized by the maximum
+/// possible degree, that is, the number of nodes minus one, so they are in
+/// [0 . . 1] for graphs without loops. The ratio centrality is
+/// (_d_<sup>−</sup> + 1) / (_d_<sup>+</sup> + 1), where _d_<sup>−</sup> and
+/// _d_<sup>+</sup> are the indegree and the outdegree: the additive
+/// smoothing makes it finite for nodes without successors, and equal to one
+/// for isolated nodes.
+///
+/// These centralities require a single pass and are thus a cheap baseline
+/// for iterative centralities such as PageRank or those computed by
+/// HyperBall. Only the degree function required by `mode` is called.
+///
+/// Indegrees can be obtained from the outdegrees of the transpose or
+/// computed by [`indegrees`].
+pub fn degree_centrality(
+    num_nodes: usize,
+    outdegree: impl Fn(usize) -> usize + Sync,
+    indegree: impl Fn(usize) -> usize + Sync,
+    mode: DegreeMode,
+    thread_pool: &ThreadPool,
+) -> Box<[f64]> {
+    let norm = num_nodes.saturating_sub(1).max(1) as f64;
+    thread_pool.install(|| {
+        (0..num_nodes)
+            .into_par_iter()
+            .map(|node| match mode {
+                DegreeMode::In => indegree(node) as f64 / norm,
+                DegreeMode::Out => outdegree(node) as f64 / norm,
+                DegreeMode::Ratio => (indegree(node) + 1) as f64 / (outdegree(node) + 1) as f64,
+            })
+            .collect()
+    })
+}
+
+/// Returns the indegrees of the nodes of a graph, computed by a sequential
+/// pass over its arcs.
+pub fn indegrees(graph: &impl SequentialGraph, pl: &mut impl ProgressLog) -> Box<[usize]> {
+    let mut indegrees = vec![0; graph.num_nodes()];
+    pl.item_name("node")
+        .expected_updates(Some(graph.num_nodes()));
+    pl.start("Computing indegrees...");
+    for_!((_node, succ) in graph.iter() {
+        for s in succ {
+            indegrees[s] += 1;
+        }
+        pl.light_update();
+    });
+    pl.done();
+    indegrees.into_boxed_slice()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphs::vec_graph::VecGraph;
+    use crate::labels::Left;
+    use crate::traits::{RandomAccessLabeling, SequentialLabeling};
+
+    #[test]
+    fn test_degree_centrality() {
+        // A star with center 0 plus the arc 1 -> 2, and an isolated node
+        let mut graph = VecGraph::from_arc_list([(1, 0), (2, 0), (3, 0), (1, 2)]);
+        graph.add_node(4);
+        let graph = Left(graph);
+        assert_eq!(graph.num_nodes(), 5);
+
+        let indegrees = indegrees(&graph, &mut ProgressLogger::default());
+        assert_eq!(indegrees.as_ref(), &[3, 0, 1, 0, 0]);
+
+        let thread_pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(2)
+            .build()
+            .unwrap();
+        let outdegree = |x| graph.outdegree(x);
+        let indegree = |x| indegrees[x];
+        assert_eq!(
+            degree_centrality(5, outdegree, indegree, DegreeMode::In, &thread_pool).as_ref(),
+            &[0.75, 0.0, 0.25, 0.0, 0.0]
+        );
+        assert_eq!(
+            degree_centrality(5, outdegree, indegree, DegreeMode::Out, &thread_pool).as_ref(),
+            &[0.0, 0.5, 0.25, 0.25, 0.0]
+        );
+        assert_eq!(
+            degree_centrality(5, outdegree, indegree, DegreeMode::Ratio, &thread_pool).as_ref(),
+            &[4.0, 1.0 / 3.0, 1.0, 0.5, 1.0]
+        );
+    }
+}
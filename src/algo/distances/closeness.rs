@@ -0,0 +1,402 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::traits::RandomAccessGraph;
+use dsi_progress_logger::prelude::*;
+use rayon::prelude::*;
+use rayon::ThreadPool;
+use std::sync::Mutex;
+use sux::bits::BitVec;
+
+/// The closeness centralities of a node.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Closeness {
+    /// The reciprocal of the sum of the distances to the reachable nodes (zero
+    /// if no other node is reachable).
+    pub classic: f64,
+    /// The sum of the reciprocals of the distances to the reachable nodes.
+    pub harmonic: f64,
+}
+
+/// A set of visited nodes that can be reset in time proportional to the
+/// number of visited nodes.
+///
+/// The set is a bit vector. Visited nodes are also recorded in a list, so
+/// that [`reset`](ResettableVisited::reset) clears just their bits; when the
+/// list reaches one entry every [`usize::BITS`] nodes (i.e., as many entries
+/// as the words of the bit vector), recording stops, and the next reset
+/// clears the whole bit vector, which then costs about the same. Thus, a
+/// reset costs time proportional to the previous visit, and memory usage is
+/// at most two bits per node.
+#[derive(Debug, Clone)]
+pub(crate) struct ResettableVisited {
+    bits: BitVec,
+    recorded: Vec<usize>,
+    max_recorded: usize,
+    overflow: bool,
+}
+
+impl ResettableVisited {
+    /// Creates a new structure for `num_nodes` nodes, none of which is
+    /// visited.
+    pub(crate) fn new(num_nodes: usize) -> Self {
+        Self {
+            bits: BitVec::new(num_nodes),
+            recorded: vec![],
+            max_recorded: num_nodes.div_ceil(usize::BITS as usize),
+            overflow: false,
+        }
+    }
+
+    /// Marks all nodes as not visited.
+    pub(crate) fn reset(&mut self) {
+        if self.overflow {
+            self.bits.fill(false);
+            self.overflow = false;
+        } else {
+            for &node in &self.recorded {
+                self.bits.set(node, false);
+            }
+        }
+        self.recorded.clear();
+    }
+
+    /// Returns whether `node` has been visited.
+    #[inline(always)]
+    pub(crate) fn is_visited(&self, node: usize) -> bool {
+        self.bits.get(node)
+    }
+
+    /// Marks `node` as visited and returns whether it was not visited before.
+    #[inline(always)]
+    pub(crate) fn visit(&mut self, node: usize) -> bool {
+        if self.bits.get(node) {
+            return false;
+        }
+        self.bits.set(node, true);
+        if !self.overflow {
+            if self.recorded.len() < self.max_recorded {
+                self.recorded.push(node);
+            } else {
+                self.overflow = true;
+            }
+        }
+        true
+    }
+}
+
+/// Per-thread state of [`closeness_subset`] and
+/// [`closeness_centrality_exact`].
+struct VisitState {
+    visited: ResettableVisited,
+    curr: Vec<usize>,
+    next: Vec<usize>,
+}
+
+/// The quantities gathered by a breadth-first visit from a node.
+struct VisitSums {
+    /// The number of reachable nodes, including the starting node.
+    reachable: usize,
+    /// The sum of the distances to the reachable nodes.
+    sum_of_distances: u64,
+    /// The sum of the reciprocals of the distances to the reachable nodes.
+    harmonic: f64,
+}
+
+/// Visits the graph breadth-first from `node`, gathering distance sums.
+fn visit_sums(graph: &impl RandomAccessGraph, node: usize, state: &mut VisitState) -> VisitSums {
+    let VisitState {
+        visited,
+        curr,
+        next,
+    } = state;
+    visited.reset();
+    curr.clear();
+    visited.visit(node);
+    curr.push(node);
+
+    let mut reachable = 1;
+    let mut sum_of_distances = 0_u64;
+    let mut harmonic = 0.0;
+    let mut distance = 0_u64;
+    while !curr.is_empty() {
+        distance += 1;
+        next.clear();
+        for &x in curr.iter() {
+            for succ in graph.successors(x) {
+                if visited.visit(succ) {
+                    next.push(succ);
+                }
+            }
+        }
+        reachable += next.len();
+        sum_of_distances += distance * next.len() as u64;
+        harmonic += next.len() as f64 / distance as f64;
+        std::mem::swap(curr, next);
+    }
+
+    VisitSums {
+        reachable,
+        sum_of_distances,
+        harmonic,
+    }
+}
+
+/// Computes the closeness of `node` with a breadth-first visit.
+fn closeness(graph: &impl RandomAccessGraph, node: usize, state: &mut VisitState) -> Closeness {
+    let sums = visit_sums(graph, node, state);
+    Closeness {
+        classic: if sums.sum_of_distances == 0 {
+            0.0
+        } else {
+            1.0 / sums.sum_of_distances as f64
+        },
+        harmonic: sums.harmonic,
+    }
+}
+
+/// Computes exactly the classic and harmonic closeness of a subset of nodes.
+///
+/// This function performs a breadth-first visit from each node in `nodes`,
+/// in parallel using the given thread pool. Each thread reuses the same
+/// visit state, whose set of visited nodes is cleared sparsely, rather than
+/// completely, at each visit. Memory usage is thus at most two bits per node
+/// per thread, plus the visit queues.
+///
+/// The result contains the closeness of `nodes[i]` in position `i`.
+pub fn closeness_subset(
+    graph: &(impl RandomAccessGraph + Sync),
+    nodes: &[usize],
+    thread_pool: &ThreadPool,
+    pl: &mut (impl ProgressLog + Send),
+) -> Vec<Closeness> {
+    let num_nodes = graph.num_nodes();
+    pl.item_name("visit").expected_updates(Some(nodes.len()));
+    pl.start("Computing closeness...");
+    let pl = Mutex::new(pl);
+
+    let result = thread_pool.install(|| {
+        nodes
+            .par_iter()
+            .map_init(
+                || VisitState {
+                    visited: ResettableVisited::new(num_nodes),
+                    curr: Vec::new(),
+                    next: Vec::new(),
+                },
+                |state, &node| {
+                    let result = closeness(graph, node, state);
+                    pl.lock().unwrap().light_update();
+                    result
+                },
+            )
+            .collect()
+    });
+
+    pl.into_inner().unwrap().done();
+    result
+}
+
+/// The normalizations of the closeness computed by
+/// [`closeness_centrality_exact`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClosenessKind {
+    /// The reciprocal of the sum of the distances to the reachable nodes.
+    Classic,
+    /// The classic closeness multiplied by (_r_ − 1)² / (_n_ − 1), where _r_
+    /// is the number of reachable nodes (including the node itself) and _n_
+    /// the number of nodes, so that nodes reaching few other nodes are not
+    /// overrated.
+    Lin,
+}
+
+/// Computes exactly the closeness of all nodes of a graph.
+///
+/// This function performs a breadth-first visit from every node, in parallel
+/// using the given thread pool, as [`closeness_subset`] does. The cost is thus
+/// quadratic—_O_(_nm_) time for a graph with _n_ nodes and _m_ arcs—and this
+/// function is meant for small graphs, or to validate the approximations
+/// computed by [`hyperball`](super::hyperball).
+///
+/// Only reachable nodes contribute to the sums of distances, so this function
+/// is meaningful also on graphs that are not strongly connected. The
+/// closeness of a node that reaches no other node (e.g., an isolated node) is
+/// zero for both kinds.
+pub fn closeness_centrality_exact(
+    graph: &(impl RandomAccessGraph + Sync),
+    kind: ClosenessKind,
+    thread_pool: &ThreadPool,
+    pl: &mut (impl ProgressLog + Send),
+) -> Box<[f64]> {
+    let num_nodes = graph.num_nodes();
+    pl.item_name("visit").expected_updates(Some(num_nodes));
+    pl.start("Computing exact closeness...");
+    let pl = Mutex::new(pl);
+
+    let result = thread_pool.install(|| {
+        (0..num_nodes)
+            .into_par_iter()
+            .map_init(
+                || VisitState {
+                    visited: ResettableVisited::new(num_nodes),
+                    curr: Vec::new(),
+                    next: Vec::new(),
+                },
+                |state, node| {
+                    let sums = visit_sums(graph, node, state);
+                    pl.lock().unwrap().light_update();
+                    if sums.sum_of_distances == 0 {
+                        return 0.0;
+                    }
+                    let classic = 1.0 / sums.sum_of_distances as f64;
+                    match kind {
+                        ClosenessKind::Classic => classic,
+                        ClosenessKind::Lin => {
+                            let r = (sums.reachable - 1) as f64;
+                            // sum_of_distances > 0 implies num_nodes > 1
+                            classic * r * r / (num_nodes - 1) as f64
+                        }
+                    }
+                },
+            )
+            .collect::<Vec<_>>()
+            .into_boxed_slice()
+    });
+
+    pl.into_inner().unwrap().done();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphs::vec_graph::VecGraph;
+    use crate::labels::Left;
+    use crate::traits::SequentialLabeling;
+    use std::collections::VecDeque;
+
+    fn naive_closeness(graph: &impl RandomAccessGraph, node: usize) -> Closeness {
+        let mut dist = vec![None; graph.num_nodes()];
+        let mut queue = VecDeque::new();
+        dist[node] = Some(0_usize);
+        queue.push_back(node);
+        while let Some(x) = queue.pop_front() {
+            for succ in graph.successors(x) {
+                if dist[succ].is_none() {
+                    dist[succ] = Some(dist[x].unwrap() + 1);
+                    queue.push_back(succ);
+                }
+            }
+        }
+        let sum: usize = dist.iter().flatten().sum();
+        let harmonic = dist
+            .iter()
+            .flatten()
+            .filter(|&&d| d > 0)
+            .map(|&d| 1.0 / d as f64)
+            .sum();
+        Closeness {
+            classic: if sum == 0 { 0.0 } else { 1.0 / sum as f64 },
+            harmonic,
+        }
+    }
+
+    #[test]
+    fn test_closeness_subset() {
+        let graph = Left(VecGraph::from_arc_list([
+            (0, 1),
+            (1, 2),
+            (2, 0),
+            (2, 3),
+            (3, 4),
+            (4, 5),
+            (5, 3),
+            (6, 0),
+        ]));
+        let nodes = [6, 0, 3, 5, 2];
+        let thread_pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(2)
+            .build()
+            .unwrap();
+        let result = closeness_subset(&graph, &nodes, &thread_pool, &mut ProgressLogger::default());
+        for (i, &node) in nodes.iter().enumerate() {
+            let expected = naive_closeness(&graph, node);
+            assert!((result[i].classic - expected.classic).abs() < 1E-12);
+            assert!((result[i].harmonic - expected.harmonic).abs() < 1E-12);
+        }
+    }
+
+    #[test]
+    fn test_closeness_centrality_exact() {
+        let graph = Left(VecGraph::from_arc_list([
+            (0, 1),
+            (1, 2),
+            (2, 0),
+            (2, 3),
+            (3, 4),
+            (4, 5),
+            (5, 3),
+            (6, 0),
+            (8, 7),
+        ]));
+        let n = graph.num_nodes();
+        let thread_pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(2)
+            .build()
+            .unwrap();
+        let classic = closeness_centrality_exact(
+            &graph,
+            ClosenessKind::Classic,
+            &thread_pool,
+            &mut ProgressLogger::default(),
+        );
+        let lin = closeness_centrality_exact(
+            &graph,
+            ClosenessKind::Lin,
+            &thread_pool,
+            &mut ProgressLogger::default(),
+        );
+        for node in 0..n {
+            let expected = naive_closeness(&graph, node);
+            assert!((classic[node] - expected.classic).abs() < 1E-12);
+        }
+        // 7 reaches no other node
+        assert_eq!(classic[7], 0.0);
+        assert_eq!(lin[7], 0.0);
+        // 8 reaches only 7, at distance 1
+        assert!((lin[8] - 1.0 / (n - 1) as f64).abs() < 1E-12);
+        // 0 reaches 1, 2, 3, 4, 5 at distances 1, 2, 3, 4, 5 (sum 15)
+        assert!((lin[0] - 25.0 / 15.0 / (n - 1) as f64).abs() < 1E-12);
+    }
+
+    #[test]
+    fn test_resettable_visited() {
+        let mut visited = ResettableVisited::new(130);
+        for node in 0..130 {
+            assert!(!visited.is_visited(node));
+        }
+        // Three words: up to three nodes are cleared sparsely
+        assert!(visited.visit(0));
+        assert!(!visited.visit(0));
+        assert!(visited.visit(129));
+        assert!(visited.is_visited(129));
+        visited.reset();
+        assert!(!visited.overflow);
+        assert!(!visited.is_visited(0));
+        assert!(!visited.is_visited(129));
+        // More nodes than words: the bit vector is cleared completely
+        for node in (0..130).step_by(2) {
+            assert!(visited.visit(node));
+        }
+        assert!(visited.overflow);
+        visited.reset();
+        for node in 0..130 {
+            assert!(!visited.is_visited(node));
+        }
+        assert!(visited.visit(64));
+        assert!(!visited.visit(64));
+    }
+}
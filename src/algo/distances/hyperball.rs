@@ -0,0 +1,297 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::traits::RandomAccessGraph;
+use anyhow::{ensure, Result};
+use dsi_progress_logger::prelude::*;
+use rayon::prelude::*;
+use rayon::ThreadPool;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Computes an approximation of the neighborhood function of a graph using
+/// the HyperBall algorithm.
+///
+/// The neighborhood function _N_(_t_) is the number of pairs of nodes
+/// (_x_, _y_) such that _y_ is reachable from _x_ in at most _t_ steps. HyperBall
+/// associates with each node a [HyperLogLog
+/// counter](https://doi.org/10.46298/dmtcs.3545) with 2<sup>`log2m`</sup>
+/// registers of one byte, and at each iteration _t_ sets the counter of each
+/// node to the union of its counter and of the counters of its successors at
+/// iteration _t_ − 1. The counter of _x_ at iteration _t_ thus approximates
+/// the size of the ball of radius _t_ around _x_, and the sum of all counters
+/// approximates _N_(_t_). The relative standard deviation of each counter is
+/// about 1.04 / √2<sup>`log2m`</sup>.
+///
+/// Iterations stop when no counter changes, and the returned vector contains
+/// the approximate value of _N_(_t_) in position _t_. Its last value is thus
+/// an approximation of the number of pairs of nodes connected by a path.
+///
+/// Each iteration is executed in parallel using the given thread pool.
+/// Memory usage is 2<sup>`log2m` + 1</sup> bytes per node.
+///
+/// See the paper “[In-Core Computation of Geometric Centralities with
+/// HyperBall: A Hundred Billion Nodes and
+/// Beyond](https://doi.org/10.1109/ICDMW.2013.10)”, by Paolo Boldi and
+/// Sebastiano Vigna, for more details.
+pub fn hyperball(
+    graph: &(impl RandomAccessGraph + Sync),
+    log2m: usize,
+    seed: u64,
+    thread_pool: &ThreadPool,
+    pl: &mut impl ProgressLog,
+) -> Result<Vec<f64>> {
+    Ok(run(graph, log2m, seed, thread_pool, pl)?.0)
+}
+
+/// Returns, for each node, an approximation of the number of nodes reachable
+/// from it (including the node itself), computed by [`hyperball`].
+///
+/// The estimate for a node is the value of its HyperLogLog counter when
+/// HyperBall stops, with the same relative standard deviation. Parameters
+/// and resource usage are the same of [`hyperball`].
+pub fn reachable_set_sizes(
+    graph: &(impl RandomAccessGraph + Sync),
+    log2m: usize,
+    seed: u64,
+    thread_pool: &ThreadPool,
+    pl: &mut impl ProgressLog,
+) -> Result<Box<[f64]>> {
+    hyperball_and_reachable_set_sizes(graph, log2m, seed, thread_pool, pl).map(|(_, sizes)| sizes)
+}
+
+/// Returns both the approximate neighborhood function computed by
+/// [`hyperball`] and the approximate reachable-set sizes computed by
+/// [`reachable_set_sizes`], running HyperBall once.
+pub fn hyperball_and_reachable_set_sizes(
+    graph: &(impl RandomAccessGraph + Sync),
+    log2m: usize,
+    seed: u64,
+    thread_pool: &ThreadPool,
+    pl: &mut impl ProgressLog,
+) -> Result<(Vec<f64>, Box<[f64]>)> {
+    let (nf, registers) = run(graph, log2m, seed, thread_pool, pl)?;
+    let sizes = thread_pool.install(|| {
+        registers
+            .par_chunks(1 << log2m)
+            .map(estimate)
+            .collect::<Vec<_>>()
+            .into_boxed_slice()
+    });
+    Ok((nf, sizes))
+}
+
+/// Runs HyperBall, returning the neighborhood function and the final
+/// counters.
+fn run(
+    graph: &(impl RandomAccessGraph + Sync),
+    log2m: usize,
+    seed: u64,
+    thread_pool: &ThreadPool,
+    pl: &mut impl ProgressLog,
+) -> Result<(Vec<f64>, Vec<u8>)> {
+    ensure!(
+        (4..=16).contains(&log2m),
+        "The base-2 logarithm of the number of registers must be between 4 and 16, found {}",
+        log2m
+    );
+    let num_nodes = graph.num_nodes();
+    let m = 1 << log2m;
+
+    let mut curr = vec![0_u8; num_nodes * m];
+    for (node, registers) in curr.chunks_mut(m).enumerate() {
+        add(registers, log2m, node, seed);
+    }
+    let mut next = curr.clone();
+    let mut nf = vec![thread_pool.install(|| estimate_all(&curr, log2m))];
+
+    pl.item_name("iteration");
+    pl.start("Running HyperBall...");
+    loop {
+        let modified = AtomicBool::new(false);
+        thread_pool.install(|| {
+            next.par_chunks_mut(m)
+                .enumerate()
+                .for_each(|(node, registers)| {
+                    let old = &curr[node * m..(node + 1) * m];
+                    registers.copy_from_slice(old);
+                    for succ in graph.successors(node) {
+                        let other = &curr[succ * m..(succ + 1) * m];
+                        for (r, &o) in registers.iter_mut().zip(other) {
+                            *r = (*r).max(o);
+                        }
+                    }
+                    if registers != old {
+                        modified.store(true, Ordering::Relaxed);
+                    }
+                })
+        });
+        std::mem::swap(&mut curr, &mut next);
+        pl.update();
+
+        if !modified.load(Ordering::Relaxed) {
+            break;
+        }
+        let value = thread_pool.install(|| estimate_all(&curr, log2m));
+        log::info!("N({}) ≈ {}", nf.len(), value);
+        nf.push(value);
+    }
+    pl.done();
+
+    Ok((nf, curr))
+}
+
+/// Returns the (interpolated) effective diameter at the given percentile of
+/// a neighborhood function.
+///
+/// The effective diameter at percentile `p` is the smallest distance _t_ at
+/// which the neighborhood function `nf` reaches `p` times its final value.
+/// Since `nf` is defined only on integer distances, the result is linearly
+/// interpolated between the two consecutive distances around the threshold.
+///
+/// # Panics
+///
+/// If `nf` is empty or `p` is not in (0..1].
+pub fn effective_diameter(nf: &[f64], p: f64) -> f64 {
+    assert!(!nf.is_empty(), "The neighborhood function is empty");
+    assert!(
+        p > 0.0 && p <= 1.0,
+        "The percentile must be in (0..1], found {}",
+        p
+    );
+    let target = p * nf[nf.len() - 1];
+    // The last value is always above the target, except for rounding errors
+    let t = nf
+        .iter()
+        .position(|&value| value >= target)
+        .unwrap_or(nf.len() - 1);
+    if t == 0 {
+        return 0.0;
+    }
+    (t - 1) as f64 + (target - nf[t - 1]) / (nf[t] - nf[t - 1])
+}
+
+/// A finalization step of MurmurHash3, used to hash nodes.
+#[inline(always)]
+fn mix64(mut x: u64) -> u64 {
+    x ^= x >> 33;
+    x = x.wrapping_mul(0xff51afd7ed558ccd);
+    x ^= x >> 33;
+    x = x.wrapping_mul(0xc4ceb9fe1a85ec53);
+    x ^ (x >> 33)
+}
+
+/// Adds a node to a HyperLogLog counter.
+#[inline(always)]
+fn add(registers: &mut [u8], log2m: usize, node: usize, seed: u64) {
+    let hash = mix64((node as u64 ^ seed).wrapping_mul(0x9e3779b97f4a7c15));
+    let index = (hash & ((1 << log2m) - 1)) as usize;
+    let rest = hash >> log2m;
+    let rho = if rest == 0 {
+        64 - log2m as u32 + 1
+    } else {
+        rest.trailing_zeros() + 1
+    };
+    registers[index] = registers[index].max(rho as u8);
+}
+
+/// Returns the HyperLogLog estimate of the cardinality of a counter.
+fn estimate(registers: &[u8]) -> f64 {
+    let m = registers.len() as f64;
+    let alpha = match registers.len() {
+        16 => 0.673,
+        32 => 0.697,
+        64 => 0.709,
+        _ => 0.7213 / (1.0 + 1.079 / m),
+    };
+    let mut sum = 0.0;
+    let mut zeroes = 0;
+    for &r in registers {
+        sum += 1.0 / (1_u64 << r) as f64;
+        zeroes += (r == 0) as usize;
+    }
+    let e = alpha * m * m / sum;
+    if e <= 2.5 * m && zeroes != 0 {
+        // Small-range correction (linear counting)
+        m * (m / zeroes as f64).ln()
+    } else {
+        e
+    }
+}
+
+/// Returns the sum of the estimates of all counters.
+fn estimate_all(registers: &[u8], log2m: usize) -> f64 {
+    registers.par_chunks(1 << log2m).map(estimate).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphs::vec_graph::VecGraph;
+    use crate::labels::Left;
+
+    #[test]
+    fn test_hyperball_path() -> Result<()> {
+        // A directed path with 10 nodes
+        let g = Left(VecGraph::from_arc_list((0..9).map(|x| (x, x + 1))));
+        let thread_pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(2)
+            .build()
+            .unwrap();
+        let nf = hyperball(&g, 10, 0, &thread_pool, &mut ProgressLogger::default())?;
+        assert_eq!(nf.len(), 10);
+        for (t, &value) in nf.iter().enumerate() {
+            let exact = (0..10_usize).map(|x| t.min(9 - x) + 1).sum::<usize>() as f64;
+            assert!(
+                (value - exact).abs() / exact < 0.05,
+                "{} != {}",
+                value,
+                exact
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_reachable_set_sizes() -> Result<()> {
+        // A directed path with 100 nodes and an isolated node
+        let mut g = VecGraph::from_arc_list((0..99).map(|x| (x, x + 1)));
+        g.add_node(100);
+        let g = Left(g);
+        let thread_pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(2)
+            .build()
+            .unwrap();
+        let sizes = reachable_set_sizes(&g, 10, 0, &thread_pool, &mut ProgressLogger::default())?;
+        assert_eq!(sizes.len(), 101);
+        for (node, &size) in sizes.iter().enumerate() {
+            let exact = if node == 100 {
+                1.0
+            } else {
+                (100 - node) as f64
+            };
+            assert!((size - exact).abs() / exact < 0.05, "{} != {}", size, exact);
+        }
+        let (nf, both) = hyperball_and_reachable_set_sizes(
+            &g,
+            10,
+            0,
+            &thread_pool,
+            &mut ProgressLogger::default(),
+        )?;
+        assert_eq!(both, sizes);
+        assert!((nf[nf.len() - 1] - sizes.iter().sum::<f64>()).abs() < 1E-6);
+        Ok(())
+    }
+
+    #[test]
+    fn test_effective_diameter() {
+        let nf = [10.0, 20.0, 30.0, 40.0];
+        assert_eq!(effective_diameter(&nf, 1.0), 3.0);
+        assert_eq!(effective_diameter(&nf, 0.25), 0.0);
+        assert!((effective_diameter(&nf, 0.9) - 2.6).abs() < 1E-12);
+        assert_eq!(effective_diameter(&[5.0], 0.9), 0.0);
+    }
+}
@@ -0,0 +1,266 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+//! Landmark-based approximate distance oracles.
+//!
+//! An [`Oracle`] stores, for each node, its distances to and from a small set
+//! of _landmarks_; the distance from _u_ to _v_ is then bounded from above by
+//! the minimum over all landmarks _ℓ_ of _d_(_u_, _ℓ_) + _d_(_ℓ_, _v_). The
+//! bound is exact whenever some landmark lies on a shortest path from _u_ to
+//! _v_ (in particular, when _u_ or _v_ is a landmark).
+//!
+//! Oracles are built by [`build`], and they can be serialized with ε-serde
+//! to a file with extension [`LANDMARKS_EXTENSION`].
+
+use crate::traits::RandomAccessGraph;
+use dsi_progress_logger::prelude::*;
+use epserde::prelude::*;
+use std::collections::VecDeque;
+use sux::prelude::*;
+
+/// The extension of the file containing a serialized [`Oracle`].
+pub const LANDMARKS_EXTENSION: &str = "lmk";
+
+/// The strategy used to choose landmarks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LandmarkSelection {
+    /// The nodes of largest degree (outdegree plus indegree for directed
+    /// graphs), ties broken by node index.
+    #[default]
+    Degree,
+    /// The node of largest degree, and then repeatedly the node farthest
+    /// from all landmarks chosen so far (nodes not connected to any landmark
+    /// being the farthest), ties broken by degree and then by node index.
+    Coverage,
+}
+
+/// A landmark-based approximate distance oracle.
+///
+/// Each node stores _k_ distances to the landmarks and, for directed graphs,
+/// _k_ distances from the landmarks, in [`BitFieldVec`]s whose bit width is
+/// ⌈log₂(_e_ + 2)⌉, where _e_ is the maximum eccentricity of a landmark (at
+/// most the diameter of the graph); the largest representable value is used
+/// to represent unreachability.
+#[derive(Epserde, Debug, Clone)]
+pub struct Oracle {
+    num_nodes: usize,
+    landmarks: Vec<usize>,
+    symmetric: bool,
+    /// The distance from each landmark to each node, at index
+    /// `node * k + landmark`.
+    from: BitFieldVec,
+    /// The distance from each node to each landmark, with the same layout;
+    /// empty if the graph is symmetric.
+    to: BitFieldVec,
+}
+
+impl Oracle {
+    /// Returns the number of nodes of the graph.
+    pub fn num_nodes(&self) -> usize {
+        self.num_nodes
+    }
+
+    /// Returns the landmarks.
+    pub fn landmarks(&self) -> &[usize] {
+        &self.landmarks
+    }
+
+    /// Returns whether the oracle was built for a symmetric graph.
+    pub fn symmetric(&self) -> bool {
+        self.symmetric
+    }
+
+    /// Returns the bit width of the stored distances.
+    pub fn bit_width(&self) -> usize {
+        self.from.bit_width()
+    }
+
+    /// Returns the distance from `landmark` (an index in
+    /// [`landmarks`](Oracle::landmarks)) to `node`, or `None` if `node` is not
+    /// reachable from the landmark.
+    pub fn from_landmark(&self, landmark: usize, node: usize) -> Option<usize> {
+        self.decode(self.from.get(node * self.landmarks.len() + landmark))
+    }
+
+    /// Returns the distance from `node` to `landmark` (an index in
+    /// [`landmarks`](Oracle::landmarks)), or `None` if the landmark is not
+    /// reachable from `node`.
+    pub fn to_landmark(&self, node: usize, landmark: usize) -> Option<usize> {
+        let to = if self.symmetric { &self.from } else { &self.to };
+        self.decode(to.get(node * self.landmarks.len() + landmark))
+    }
+
+    /// Returns an upper bound on the distance from `src` to `dst`, or `None`
+    /// if no landmark is reachable from `src` and reaches `dst`.
+    ///
+    /// The bound is exact if some landmark lies on a shortest path from `src`
+    /// to `dst`. Answering a query requires time _O_(_k_).
+    pub fn upper_bound(&self, src: usize, dst: usize) -> Option<usize> {
+        if src == dst {
+            return Some(0);
+        }
+        (0..self.landmarks.len())
+            .filter_map(|l| Some(self.to_landmark(src, l)? + self.from_landmark(l, dst)?))
+            .min()
+    }
+
+    #[inline(always)]
+    fn decode(&self, value: usize) -> Option<usize> {
+        if value == usize::MAX >> (usize::BITS as usize - self.from.bit_width()) {
+            None
+        } else {
+            Some(value)
+        }
+    }
+}
+
+/// Computes by a breadth-first visit the distances from `src`, storing them
+/// in `dist` (unreachable nodes are set to `usize::MAX`), and returns the
+/// eccentricity of `src`.
+fn bfs(
+    graph: &impl RandomAccessGraph,
+    src: usize,
+    dist: &mut [usize],
+    queue: &mut VecDeque<usize>,
+) -> usize {
+    dist.fill(usize::MAX);
+    dist[src] = 0;
+    queue.clear();
+    queue.push_back(src);
+    let mut ecc = 0;
+    while let Some(node) = queue.pop_front() {
+        let d = dist[node] + 1;
+        for succ in graph.successors(node) {
+            if dist[succ] == usize::MAX {
+                dist[succ] = d;
+                ecc = d;
+                queue.push_back(succ);
+            }
+        }
+    }
+    ecc
+}
+
+/// Builds a landmark-based distance oracle with `k` landmarks.
+///
+/// If `transpose` is `None` the graph is assumed to be symmetric, and just
+/// one distance vector is stored. Otherwise, `transpose` must be the transpose
+/// of `graph`, and it is used for the backward visits from the landmarks.
+///
+/// Since the bit width of the distances depends on the eccentricities of the
+/// landmarks, the visits are performed twice: first to choose the landmarks
+/// and compute their eccentricities, then to fill the distance vectors.
+/// Thus, the construction requires 2_k_ (or 4_k_ for directed graphs)
+/// breadth-first visits, and temporary memory for two `usize` per node.
+///
+/// If `k` is larger than the number of nodes, all nodes are landmarks.
+pub fn build<G: RandomAccessGraph>(
+    graph: &G,
+    transpose: Option<&G>,
+    k: usize,
+    selection: LandmarkSelection,
+    pl: &mut impl ProgressLog,
+) -> Oracle {
+    let num_nodes = graph.num_nodes();
+    let k = k.min(num_nodes);
+    let degree = |node: usize| graph.outdegree(node) + transpose.map_or(0, |t| t.outdegree(node));
+    // Largest degree first, ties broken by node index
+    let by_degree = |&a: &usize, &b: &usize| degree(b).cmp(&degree(a)).then(a.cmp(&b));
+
+    let mut dist = vec![0; num_nodes];
+    let mut queue = VecDeque::new();
+    let mut landmarks = Vec::with_capacity(k);
+    let mut max_ecc = 0;
+
+    pl.item_name("landmark").expected_updates(Some(k));
+    pl.start("Choosing landmarks...");
+    match selection {
+        LandmarkSelection::Degree => {
+            let mut nodes = (0..num_nodes).collect::<Vec<_>>();
+            if k < num_nodes {
+                nodes.select_nth_unstable_by(k, by_degree);
+                nodes.truncate(k);
+            }
+            nodes.sort_unstable_by(by_degree);
+            for landmark in nodes {
+                max_ecc = max_ecc.max(bfs(graph, landmark, &mut dist, &mut queue));
+                if let Some(transpose) = transpose {
+                    max_ecc = max_ecc.max(bfs(transpose, landmark, &mut dist, &mut queue));
+                }
+                landmarks.push(landmark);
+                pl.update();
+            }
+        }
+        LandmarkSelection::Coverage => {
+            // The minimum distance between each node and the landmarks
+            let mut covered = vec![usize::MAX; num_nodes];
+            let mut next = (0..num_nodes).min_by(by_degree);
+            while let Some(landmark) = next.filter(|_| landmarks.len() < k) {
+                landmarks.push(landmark);
+                max_ecc = max_ecc.max(bfs(graph, landmark, &mut dist, &mut queue));
+                covered
+                    .iter_mut()
+                    .zip(&dist)
+                    .for_each(|(c, &d)| *c = (*c).min(d));
+                if let Some(transpose) = transpose {
+                    max_ecc = max_ecc.max(bfs(transpose, landmark, &mut dist, &mut queue));
+                    covered
+                        .iter_mut()
+                        .zip(&dist)
+                        .for_each(|(c, &d)| *c = (*c).min(d));
+                }
+                next = (0..num_nodes)
+                    .filter(|&node| covered[node] != 0)
+                    .min_by(|a, b| covered[*b].cmp(&covered[*a]).then(by_degree(a, b)));
+                pl.update();
+            }
+        }
+    }
+    pl.done();
+
+    // The largest value representable is the unreachability marker, so it
+    // must be larger than max_ecc
+    let bit_width = (usize::BITS - (max_ecc + 1).leading_zeros()) as usize;
+    let mut from = BitFieldVec::new(bit_width, num_nodes * k);
+    let mut to = BitFieldVec::new(
+        bit_width,
+        if transpose.is_some() {
+            num_nodes * k
+        } else {
+            0
+        },
+    );
+    let unreachable = usize::MAX >> (usize::BITS as usize - bit_width);
+    let store = |vec: &mut BitFieldVec, l: usize, dist: &[usize]| {
+        for (node, &d) in dist.iter().enumerate() {
+            vec.set(node * k + l, if d == usize::MAX { unreachable } else { d });
+        }
+    };
+
+    pl.item_name("landmark").expected_updates(Some(k));
+    pl.start(format!(
+        "Computing distances with bit width {}...",
+        bit_width
+    ));
+    for (l, &landmark) in landmarks.iter().enumerate() {
+        bfs(graph, landmark, &mut dist, &mut queue);
+        store(&mut from, l, &dist);
+        if let Some(transpose) = transpose {
+            bfs(transpose, landmark, &mut dist, &mut queue);
+            store(&mut to, l, &dist);
+        }
+        pl.update();
+    }
+    pl.done();
+
+    Oracle {
+        num_nodes,
+        landmarks,
+        symmetric: transpose.is_none(),
+        from,
+        to,
+    }
+}
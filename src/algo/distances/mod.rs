@@ -0,0 +1,18 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+//! Distance-based algorithms.
+
+mod closeness;
+pub use closeness::*;
+
+mod hyperball;
+pub use hyperball::*;
+
+mod pairs;
+pub use pairs::*;
+
+pub mod landmarks;
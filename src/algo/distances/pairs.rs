@@ -0,0 +1,443 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use super::ResettableVisited;
+use crate::traits::RandomAccessGraph;
+use dsi_progress_logger::prelude::*;
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+use rayon::prelude::*;
+use rayon::ThreadPool;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+/// Reusable state for computing distances between pairs of nodes.
+///
+/// The sets of visited nodes are cleared sparsely, so after the initial
+/// allocation each visit costs time proportional to the part of the graph it
+/// explores. Memory usage is at most four bits per node, plus the visit
+/// queues.
+#[derive(Debug, Clone)]
+pub struct PairDistances {
+    forward: ResettableVisited,
+    backward: ResettableVisited,
+    curr: Vec<usize>,
+    next: Vec<usize>,
+    back_curr: Vec<usize>,
+    back_next: Vec<usize>,
+}
+
+/// Expands `curr` by one level of a breadth-first visit, storing the new
+/// frontier in `next`. Returns true as soon as a node visited by `other` is
+/// found.
+fn expand(
+    graph: &impl RandomAccessGraph,
+    visited: &mut ResettableVisited,
+    other: &ResettableVisited,
+    curr: &[usize],
+    next: &mut Vec<usize>,
+) -> bool {
+    next.clear();
+    for &x in curr {
+        for succ in graph.successors(x) {
+            if visited.visit(succ) {
+                if other.is_visited(succ) {
+                    return true;
+                }
+                next.push(succ);
+            }
+        }
+    }
+    false
+}
+
+impl PairDistances {
+    /// Creates a new state for graphs with `num_nodes` nodes.
+    pub fn new(num_nodes: usize) -> Self {
+        Self {
+            forward: ResettableVisited::new(num_nodes),
+            backward: ResettableVisited::new(num_nodes),
+            curr: Vec::new(),
+            next: Vec::new(),
+            back_curr: Vec::new(),
+            back_next: Vec::new(),
+        }
+    }
+
+    /// Returns the distance from `src` to `dst` computed by a breadth-first
+    /// visit stopping as soon as `dst` is found, or `None` if `dst` is not
+    /// reachable from `src`.
+    pub fn bfs(&mut self, graph: &impl RandomAccessGraph, src: usize, dst: usize) -> Option<usize> {
+        // The backward set contains just dst, so the visit stops as soon as
+        // dst is found
+        self.backward.reset();
+        self.backward.visit(dst);
+        self.forward.reset();
+        if src == dst {
+            return Some(0);
+        }
+        self.forward.visit(src);
+        self.curr.clear();
+        self.curr.push(src);
+
+        let mut distance = 0;
+        while !self.curr.is_empty() {
+            distance += 1;
+            if expand(
+                graph,
+                &mut self.forward,
+                &self.backward,
+                &self.curr,
+                &mut self.next,
+            ) {
+                return Some(distance);
+            }
+            std::mem::swap(&mut self.curr, &mut self.next);
+        }
+        None
+    }
+
+    /// Returns the distance from `src` to `dst` computed by a bidirectional
+    /// breadth-first visit, or `None` if `dst` is not reachable from `src`.
+    ///
+    /// The visit advances alternately a forward frontier from `src` on
+    /// `graph` and a backward frontier from `dst` on `transpose`, expanding at
+    /// each step the smaller one by a whole level, and stops as soon as the
+    /// two sets of visited nodes intersect. Since at that point the two
+    /// balls were disjoint before the last expansion, the distance is exactly
+    /// the sum of their radii. On graphs with a small diameter this explores
+    /// a tiny fraction of the nodes explored by [`bfs`](PairDistances::bfs).
+    ///
+    /// `transpose` must be the transpose of `graph`; if `graph` is symmetric,
+    /// it can be `graph` itself.
+    pub fn bidirectional(
+        &mut self,
+        graph: &impl RandomAccessGraph,
+        transpose: &impl RandomAccessGraph,
+        src: usize,
+        dst: usize,
+    ) -> Option<usize> {
+        if src == dst {
+            return Some(0);
+        }
+        self.forward.reset();
+        self.backward.reset();
+        self.forward.visit(src);
+        self.backward.visit(dst);
+        self.curr.clear();
+        self.curr.push(src);
+        self.back_curr.clear();
+        self.back_curr.push(dst);
+
+        let mut distance = 0;
+        while !self.curr.is_empty() && !self.back_curr.is_empty() {
+            distance += 1;
+            let found = if self.curr.len() <= self.back_curr.len() {
+                let found = expand(
+                    graph,
+                    &mut self.forward,
+                    &self.backward,
+                    &self.curr,
+                    &mut self.next,
+                );
+                std::mem::swap(&mut self.curr, &mut self.next);
+                found
+            } else {
+                let found = expand(
+                    transpose,
+                    &mut self.backward,
+                    &self.forward,
+                    &self.back_curr,
+                    &mut self.back_next,
+                );
+                std::mem::swap(&mut self.back_curr, &mut self.back_next);
+                found
+            };
+            if found {
+                return Some(distance);
+            }
+        }
+        None
+    }
+}
+
+/// Returns the distance from `src` to `dst`, or `None` if `dst` is not
+/// reachable from `src`, using a breadth-first visit.
+///
+/// This function allocates a new [`PairDistances`]: to compute many
+/// distances, use [`PairDistances::bfs`] instead.
+pub fn bfs_distance(graph: &impl RandomAccessGraph, src: usize, dst: usize) -> Option<usize> {
+    PairDistances::new(graph.num_nodes()).bfs(graph, src, dst)
+}
+
+/// Returns the distance from `src` to `dst`, or `None` if `dst` is not
+/// reachable from `src`, using a bidirectional breadth-first visit.
+///
+/// `transpose` must be the transpose of `graph`. This function allocates a
+/// new [`PairDistances`]: to compute many distances, use
+/// [`PairDistances::bidirectional`] instead.
+pub fn bidirectional_bfs(
+    graph: &impl RandomAccessGraph,
+    transpose: &impl RandomAccessGraph,
+    src: usize,
+    dst: usize,
+) -> Option<usize> {
+    PairDistances::new(graph.num_nodes()).bidirectional(graph, transpose, src, dst)
+}
+
+/// Returns a shortest path from `src` to `dst`, including both endpoints, or
+/// `None` if `dst` is not reachable from `src`.
+///
+/// The path is reconstructed from the predecessors recorded by a
+/// breadth-first visit that stops as soon as `dst` is found, so its length
+/// minus one is the distance from `src` to `dst`. Memory usage is eight bytes
+/// per node, plus the visit queue.
+pub fn bfs_shortest_path(
+    graph: &impl RandomAccessGraph,
+    src: usize,
+    dst: usize,
+) -> Option<Vec<usize>> {
+    if src == dst {
+        return Some(vec![src]);
+    }
+    let mut pred = vec![usize::MAX; graph.num_nodes()];
+    pred[src] = src;
+    let mut queue = VecDeque::new();
+    queue.push_back(src);
+
+    'visit: while let Some(x) = queue.pop_front() {
+        for succ in graph.successors(x) {
+            if pred[succ] == usize::MAX {
+                pred[succ] = x;
+                if succ == dst {
+                    break 'visit;
+                }
+                queue.push_back(succ);
+            }
+        }
+    }
+
+    if pred[dst] == usize::MAX {
+        return None;
+    }
+    let mut path = vec![dst];
+    let mut node = dst;
+    while node != src {
+        node = pred[node];
+        path.push(node);
+    }
+    path.reverse();
+    Some(path)
+}
+
+/// The distances between sampled pairs of nodes computed by
+/// [`sample_pair_distances`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PairDistanceSample {
+    /// The number of sampled pairs at each distance.
+    pub distribution: Vec<usize>,
+    /// The number of sampled pairs whose target is not reachable from the
+    /// source.
+    pub unreachable: usize,
+}
+
+impl PairDistanceSample {
+    /// Returns the number of sampled pairs.
+    pub fn samples(&self) -> usize {
+        self.reachable() + self.unreachable
+    }
+
+    /// Returns the number of sampled pairs whose target is reachable from the
+    /// source.
+    pub fn reachable(&self) -> usize {
+        self.distribution.iter().sum()
+    }
+
+    /// Returns the average distance between reachable sampled pairs, or NaN if
+    /// no sampled pair is reachable.
+    pub fn average_distance(&self) -> f64 {
+        self.distribution
+            .iter()
+            .enumerate()
+            .map(|(d, &count)| (d * count) as f64)
+            .sum::<f64>()
+            / self.reachable() as f64
+    }
+
+    /// Returns the sample standard deviation of the distance between
+    /// reachable sampled pairs, or NaN if less than two sampled pairs are
+    /// reachable.
+    pub fn std_dev_distance(&self) -> f64 {
+        let avg = self.average_distance();
+        (self
+            .distribution
+            .iter()
+            .enumerate()
+            .map(|(d, &count)| count as f64 * (d as f64 - avg).powi(2))
+            .sum::<f64>()
+            / (self.reachable() as f64 - 1.0))
+            .sqrt()
+    }
+}
+
+/// Samples the distances between pairs of distinct nodes chosen uniformly at
+/// random.
+///
+/// Pairs are generated using the given seed, and their distances are computed
+/// in parallel using the given thread pool. If `transpose` is provided, it
+/// must be the transpose of `graph` (or `graph` itself, if `graph` is
+/// symmetric), and distances are computed by a [bidirectional
+/// visit](PairDistances::bidirectional); otherwise, by a [breadth-first
+/// visit](PairDistances::bfs) stopping at the target.
+pub fn sample_pair_distances<G: RandomAccessGraph + Sync, T: RandomAccessGraph + Sync>(
+    graph: &G,
+    transpose: Option<&T>,
+    samples: usize,
+    seed: u64,
+    thread_pool: &ThreadPool,
+    pl: &mut (impl ProgressLog + Send),
+) -> PairDistanceSample {
+    let num_nodes = graph.num_nodes();
+    let mut rng = SmallRng::seed_from_u64(seed);
+    let pairs = if num_nodes < 2 {
+        vec![]
+    } else {
+        (0..samples)
+            .map(|_| {
+                let src = rng.gen_range(0..num_nodes);
+                // Choose uniformly a node different from src
+                let dst = (src + rng.gen_range(1..num_nodes)) % num_nodes;
+                (src, dst)
+            })
+            .collect::<Vec<_>>()
+    };
+
+    pl.item_name("pair").expected_updates(Some(pairs.len()));
+    pl.start("Sampling distances...");
+    let locked_pl = Mutex::new(&mut *pl);
+    let distances = thread_pool.install(|| {
+        pairs
+            .par_iter()
+            .map_init(
+                || PairDistances::new(num_nodes),
+                |state, &(src, dst)| {
+                    let distance = match transpose {
+                        Some(transpose) => state.bidirectional(graph, transpose, src, dst),
+                        None => state.bfs(graph, src, dst),
+                    };
+                    locked_pl.lock().unwrap().light_update();
+                    distance
+                },
+            )
+            .collect::<Vec<_>>()
+    });
+    pl.done();
+
+    let mut sample = PairDistanceSample {
+        distribution: vec![],
+        unreachable: 0,
+    };
+    for distance in distances {
+        match distance {
+            Some(d) => {
+                if d >= sample.distribution.len() {
+                    sample.distribution.resize(d + 1, 0);
+                }
+                sample.distribution[d] += 1;
+            }
+            None => sample.unreachable += 1,
+        }
+    }
+    sample
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphs::random::ErdosRenyi;
+    use crate::graphs::vec_graph::VecGraph;
+    use crate::labels::Left;
+    use crate::traits::SequentialLabeling;
+
+    #[test]
+    fn test_bidirectional_bfs() {
+        for (n, p, seed) in [(50, 0.02, 0), (100, 0.03, 1), (100, 0.1, 2)] {
+            let graph = Left(VecGraph::from_lender(ErdosRenyi::new(n, p, seed).iter()));
+            let mut transpose = VecGraph::empty(n);
+            for x in 0..n {
+                for y in graph.successors(x) {
+                    transpose.add_arc(y, x);
+                }
+            }
+            let transpose = Left(transpose);
+            assert_eq!(transpose.num_nodes(), graph.num_nodes());
+
+            let mut state = PairDistances::new(n);
+            for src in 0..n {
+                for dst in 0..n {
+                    let d = state.bfs(&graph, src, dst);
+                    assert_eq!(state.bidirectional(&graph, &transpose, src, dst), d);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_pair_distances_path() {
+        // A directed path 0 -> 1 -> 2 -> 3
+        let graph = Left(VecGraph::from_arc_list([(0, 1), (1, 2), (2, 3)]));
+        let transpose = Left(VecGraph::from_arc_list([(1, 0), (2, 1), (3, 2)]));
+        assert_eq!(bfs_distance(&graph, 0, 3), Some(3));
+        assert_eq!(bfs_distance(&graph, 3, 0), None);
+        assert_eq!(bidirectional_bfs(&graph, &transpose, 0, 3), Some(3));
+        assert_eq!(bidirectional_bfs(&graph, &transpose, 3, 0), None);
+        assert_eq!(bidirectional_bfs(&graph, &transpose, 2, 2), Some(0));
+
+        let thread_pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(2)
+            .build()
+            .unwrap();
+        let sample = sample_pair_distances(
+            &graph,
+            None::<&Left<VecGraph>>,
+            100,
+            0,
+            &thread_pool,
+            &mut ProgressLogger::default(),
+        );
+        assert_eq!(sample.samples(), 100);
+        assert_eq!(
+            sample_pair_distances(
+                &graph,
+                Some(&transpose),
+                100,
+                0,
+                &thread_pool,
+                &mut ProgressLogger::default(),
+            ),
+            sample
+        );
+    }
+
+    #[test]
+    fn test_bfs_shortest_path() {
+        let graph = Left(VecGraph::from_lender(ErdosRenyi::new(100, 0.03, 0).iter()));
+        for src in 0..100 {
+            for dst in 0..100 {
+                let distance = bfs_distance(&graph, src, dst);
+                let path = bfs_shortest_path(&graph, src, dst);
+                assert_eq!(path.as_ref().map(|path| path.len() - 1), distance);
+                if let Some(path) = path {
+                    assert_eq!(path[0], src);
+                    assert_eq!(*path.last().unwrap(), dst);
+                    for w in path.windows(2) {
+                        assert!(graph.successors(w[0]).into_iter().any(|s| s == w[1]));
+                    }
+                }
+            }
+        }
+    }
+}
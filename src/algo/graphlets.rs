@@ -0,0 +1,161 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+//! Sampling-based estimation of graphlet frequencies.
+//!
+//! Graphlets are small connected induced subgraphs. We follow the numbering
+//! of orbits (i.e., of the automorphism classes of nodes of a graphlet) of
+//! [ORCA](https://doi.org/10.1093/bioinformatics/btt717) for graphlets with
+//! up to four nodes:
+//!
+//! | Graphlet             | Orbits                                            |
+//! |----------------------|---------------------------------------------------|
+//! | edge                 | 0                                                 |
+//! | path on 3 nodes      | 1 (end), 2 (middle)                               |
+//! | triangle             | 3                                                 |
+//! | path on 4 nodes      | 4 (end), 5 (middle)                               |
+//! | star on 4 nodes      | 6 (leaf), 7 (center)                              |
+//! | cycle on 4 nodes     | 8                                                 |
+//! | tailed triangle      | 9 (tail), 10 (degree-two node), 11 (degree three) |
+//! | diamond              | 12 (degree two), 13 (degree three)                |
+//! | clique on 4 nodes    | 14                                                |
+
+use crate::traits::RandomAccessGraph;
+use dsi_progress_logger::prelude::*;
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+
+/// The number of orbits of graphlets with up to four nodes.
+pub const NUM_ORBITS: usize = 15;
+
+/// Estimates the frequencies of the orbits of graphlets with up to four nodes
+/// by sampling.
+///
+/// Each of the `num_samples` samples starts from a uniformly random node and
+/// grows a connected set of up to four nodes by adding, at each step, a
+/// uniformly random node adjacent to the current set. Each prefix of the
+/// growth sequence with two, three, and four nodes is classified, and every
+/// node of the induced graphlet increments the count of its orbit (see the
+/// [module documentation](self) for the numbering). Samples that cannot be
+/// extended (e.g., in components with less than four nodes) contribute only
+/// their smaller prefixes.
+///
+/// The graph should be symmetric and loopless: adjacency between nodes of a
+/// sample is tested in both directions, but the growth step uses successors
+/// only.
+pub fn sample_4node_graphlets(
+    graph: &impl RandomAccessGraph,
+    num_samples: usize,
+    seed: u64,
+) -> [u64; NUM_ORBITS] {
+    let mut counts = [0; NUM_ORBITS];
+    let num_nodes = graph.num_nodes();
+    if num_nodes == 0 {
+        return counts;
+    }
+
+    let mut rng = SmallRng::seed_from_u64(seed);
+    let mut sample = Vec::with_capacity(4);
+    let mut frontier = Vec::new();
+
+    let mut pl = ProgressLogger::default();
+    pl.display_memory(true)
+        .item_name("sample")
+        .expected_updates(Some(num_samples));
+    pl.start("Sampling graphlets...");
+
+    for _ in 0..num_samples {
+        sample.clear();
+        sample.push(rng.gen_range(0..num_nodes));
+
+        while sample.len() < 4 {
+            frontier.clear();
+            for &node in &sample {
+                frontier.extend(graph.successors(node));
+            }
+            frontier.sort_unstable();
+            frontier.dedup();
+            frontier.retain(|node| !sample.contains(node));
+            if frontier.is_empty() {
+                break;
+            }
+            sample.push(frontier[rng.gen_range(0..frontier.len())]);
+            classify(graph, &sample, &mut counts);
+        }
+        pl.light_update();
+    }
+    pl.done();
+
+    counts
+}
+
+/// Classifies the graphlet induced by `nodes`, which must be connected and
+/// contain between two and four nodes, and updates the orbit counts.
+fn classify(graph: &impl RandomAccessGraph, nodes: &[usize], counts: &mut [u64; NUM_ORBITS]) {
+    let n = nodes.len();
+    let mut deg = [0_usize; 4];
+    let mut num_edges = 0;
+    for i in 0..n {
+        for j in i + 1..n {
+            if graph.has_arc(nodes[i], nodes[j]) || graph.has_arc(nodes[j], nodes[i]) {
+                deg[i] += 1;
+                deg[j] += 1;
+                num_edges += 1;
+            }
+        }
+    }
+    let max_deg = *deg[..n].iter().max().unwrap();
+
+    for &d in &deg[..n] {
+        let orbit = match (n, num_edges) {
+            (2, _) => 0,
+            (3, 2) => d,
+            (3, _) => 3,
+            (4, 3) if max_deg == 3 => 5 + d.min(2),
+            (4, 3) => 3 + d,
+            (4, 4) if max_deg == 2 => 8,
+            (4, 4) => 8 + d,
+            (4, 5) => 10 + d,
+            _ => 14,
+        };
+        counts[orbit] += 1;
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_sample_4node_graphlets() {
+    use crate::graphs::vec_graph::VecGraph;
+    use crate::labels::Left;
+    // A symmetric 4-clique
+    let mut arcs = vec![];
+    for x in 0..4 {
+        for y in 0..4 {
+            if x != y {
+                arcs.push((x, y));
+            }
+        }
+    }
+    let g = Left(VecGraph::from_arc_list(arcs));
+    let counts = sample_4node_graphlets(&g, 100, 0);
+    assert_eq!(counts[0], 200);
+    assert_eq!(counts[3], 300);
+    assert_eq!(counts[14], 400);
+    assert_eq!(counts.iter().sum::<u64>(), 900);
+
+    // A symmetric star with center 0
+    let g = Left(VecGraph::from_arc_list([
+        (0, 1),
+        (0, 2),
+        (0, 3),
+        (1, 0),
+        (2, 0),
+        (3, 0),
+    ]));
+    let counts = sample_4node_graphlets(&g, 100, 0);
+    assert_eq!(counts[6], 300);
+    assert_eq!(counts[7], 100);
+}
@@ -0,0 +1,146 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::traits::*;
+use lender::*;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Enumerates the paths _u_ → _v_ → _w_ of length two of a labeled graph,
+/// calling `callback` with _u_, _v_, _w_, the label of _u_ → _v_ and the
+/// label of _v_ → _w_.
+///
+/// The graph is scanned sequentially, and the successors of each middle node
+/// _v_ are retrieved by random access. If `max_middle_outdegree` is not
+/// `None`, arcs _u_ → _v_ such that the outdegree of _v_ exceeds the bound
+/// are skipped, as their paths would dominate the enumeration (the number of
+/// paths through _v_ is the product of its indegree and its outdegree).
+///
+/// Returns the number of skipped arcs.
+pub fn for_each_two_hop_path<L, G: LabeledRandomAccessGraph<L>>(
+    graph: &G,
+    max_middle_outdegree: Option<usize>,
+    mut callback: impl FnMut(usize, usize, usize, &L, &L),
+) -> u64 {
+    let max_middle_outdegree = max_middle_outdegree.unwrap_or(usize::MAX);
+    let mut skipped = 0;
+    for_!((src, succ) in graph.iter() {
+        for (mid, first) in succ {
+            if graph.outdegree(mid) > max_middle_outdegree {
+                skipped += 1;
+                continue;
+            }
+            for (dst, second) in graph.successors(mid) {
+                callback(src, mid, dst, &first, &second);
+            }
+        }
+    });
+    skipped
+}
+
+/// The frequencies of the pairs of labels along the paths of length two of a
+/// labeled graph, as computed by [`label_pair_counts`].
+#[derive(Debug, Clone)]
+pub struct LabelPairCounts<L> {
+    /// The number of paths _u_ → _v_ → _w_ with the given pair of labels.
+    pub counts: HashMap<(L, L), u64>,
+    /// The number of enumerated paths.
+    pub num_paths: u64,
+    /// The number of arcs skipped because of the bound on the outdegree of
+    /// middle nodes.
+    pub skipped: u64,
+}
+
+impl<L: Ord> LabelPairCounts<L> {
+    /// Returns the `n` most frequent pairs of labels with their counts, in
+    /// decreasing order of count; ties are broken by increasing pair.
+    pub fn top(&self, n: usize) -> Vec<(&(L, L), u64)> {
+        let mut pairs = self
+            .counts
+            .iter()
+            .map(|(pair, &count)| (pair, count))
+            .collect::<Vec<_>>();
+        pairs.sort_unstable_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        pairs.truncate(n);
+        pairs
+    }
+}
+
+/// Counts the pairs (label of _u_ → _v_, label of _v_ → _w_) along the paths
+/// _u_ → _v_ → _w_ of length two of a labeled graph.
+///
+/// Paths are enumerated by [`for_each_two_hop_path`], to which
+/// `max_middle_outdegree` is passed. The counts are accumulated in a hash map,
+/// so memory usage is linear in the number of distinct pairs.
+pub fn label_pair_counts<L: Clone + Eq + Hash, G: LabeledRandomAccessGraph<L>>(
+    graph: &G,
+    max_middle_outdegree: Option<usize>,
+) -> LabelPairCounts<L> {
+    let mut counts = HashMap::new();
+    let mut num_paths = 0;
+    let skipped = for_each_two_hop_path(graph, max_middle_outdegree, |_, _, _, first, second| {
+        *counts.entry((first.clone(), second.clone())).or_insert(0) += 1;
+        num_paths += 1;
+    });
+    LabelPairCounts {
+        counts,
+        num_paths,
+        skipped,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphs::vec_graph::VecGraph;
+
+    #[test]
+    fn test_label_pair_counts() {
+        let arcs = [
+            (0, 1, 'a'),
+            (0, 2, 'b'),
+            (1, 2, 'a'),
+            (1, 3, 'c'),
+            (2, 0, 'a'),
+            (2, 3, 'b'),
+            (2, 4, 'c'),
+            (3, 3, 'a'),
+            (4, 0, 'b'),
+        ];
+        let mut graph = VecGraph::empty(5);
+        for (src, dst, label) in arcs {
+            graph.add_labeled_arc(src, dst, label);
+        }
+
+        // Brute force over all pairs of arcs
+        let mut expected = HashMap::new();
+        for &(_, v, first) in &arcs {
+            for &(x, _, second) in &arcs {
+                if x == v {
+                    *expected.entry((first, second)).or_insert(0) += 1;
+                }
+            }
+        }
+        let counts = label_pair_counts(&graph, None);
+        assert_eq!(counts.counts, expected);
+        assert_eq!(counts.num_paths, expected.values().sum::<u64>());
+        assert_eq!(counts.skipped, 0);
+        assert_eq!(counts.top(1), vec![(&('a', 'a'), 4)]);
+
+        // Node 2 has outdegree 3, and it is the target of two arcs
+        let counts = label_pair_counts(&graph, Some(2));
+        assert_eq!(counts.skipped, 2);
+        let mut expected = HashMap::new();
+        for &(_, v, first) in &arcs {
+            for &(x, _, second) in &arcs {
+                if x == v && v != 2 {
+                    *expected.entry((first, second)).or_insert(0) += 1;
+                }
+            }
+        }
+        assert_eq!(counts.counts, expected);
+    }
+}
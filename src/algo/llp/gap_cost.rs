@@ -16,7 +16,14 @@ use sux::prelude::*;
 ///
 /// Note that this implementation uses the _base_ of the base-2 logarithm
 /// as a measure of cost, where as the Java implementation uses the _ceiling_.
-pub(crate) fn compute_log_gap_cost<G: SequentialGraph + Sync>(
+///
+/// This is the cost minimized by [Layered Label
+/// Propagation](super::layered_label_propagation); applied to a
+/// [`PermutedGraph`](crate::graphs::permuted_graph::PermutedGraph), it can be
+/// used to compare different orderings of the same graph without
+/// recompressing it. `deg_cumul` is the degree cumulative function of
+/// `graph`, which is used to balance the parallel computation.
+pub fn compute_log_gap_cost<G: SequentialGraph + Sync>(
     graph: &G,
     arc_granularity: usize,
     deg_cumul: &(impl Succ<Input = usize, Output = usize> + Send + Sync),
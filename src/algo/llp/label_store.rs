@@ -29,10 +29,20 @@ impl LabelStore {
         }
     }
 
-    pub(crate) fn init(&mut self) {
-        self.volumes
-            .par_iter()
-            .for_each(|v| v.store(1, Ordering::Relaxed));
+    /// Assigns each node its own label, and sets the volume of each label to
+    /// the weight of the corresponding node (one if `weights` is `None`).
+    pub(crate) fn init(&mut self, weights: Option<&[usize]>) {
+        match weights {
+            Some(weights) => self
+                .volumes
+                .par_iter()
+                .zip(weights)
+                .for_each(|(v, &w)| v.store(w, Ordering::Relaxed)),
+            None => self
+                .volumes
+                .par_iter()
+                .for_each(|v| v.store(1, Ordering::Relaxed)),
+        }
         self.labels
             .par_iter_mut()
             .enumerate()
@@ -49,12 +59,13 @@ impl LabelStore {
         self.volumes[node].load(Ordering::Relaxed)
     }
 
-    /// Updates the label of a node.
+    /// Updates the label of a node, moving its weight from the volume of the
+    /// old label to the volume of the new one.
     #[inline(always)]
-    pub(crate) fn update(&self, node: usize, new_label: usize) {
+    pub(crate) fn update(&self, node: usize, new_label: usize, weight: usize) {
         let old_label = unsafe { core::mem::replace(&mut *self.labels[node].get(), new_label) };
-        self.volumes[old_label].fetch_sub(1, Ordering::Relaxed);
-        self.volumes[new_label].fetch_add(1, Ordering::Relaxed);
+        self.volumes[old_label].fetch_sub(weight, Ordering::Relaxed);
+        self.volumes[new_label].fetch_add(weight, Ordering::Relaxed);
     }
 
     pub(crate) fn labels(&mut self) -> &mut [usize] {
@@ -25,11 +25,49 @@
 //! # Memory requirements
 //!
 //! LLP requires three `usize` and a boolean per node, plus the memory that is
-//! necessary to load the graph.
+//! necessary to load the graph and the node weights, if any.
+//!
+//! # Node weights
+//!
+//! Optionally, a weight *w*(*x*) can be associated with each node *x*: for
+//! example, if the graph has been obtained by [contracting](crate::transform::contract)
+//! a larger graph, the weight of a node might be the number of nodes it
+//! represents. The volume *v*<sub>λ</sub> of a label λ is then the sum of the
+//! weights of the nodes with label λ, rather than their number.
+//!
+//! When updating node *x*, LLP chooses the label λ maximizing
+//!
+//! > (1 + ɣ) *k*<sub>λ</sub> − ɣ (*v*<sub>λ</sub> + *w*(*x*)),
+//!
+//! where *k*<sub>λ</sub> is the number of neighbors of *x* with label λ, and
+//! the variation of the objective function is the difference between the
+//! value of the chosen label and that of the current label, multiplied by
+//! *w*(*x*). Thus, the weight of a node scales both its contribution to the
+//! objective function and its contribution to the volume of its label.
+//!
+//! When all weights are one (or no weights are provided) the volume of a label
+//! is the number of nodes with that label, the expression above becomes
+//! (1 + ɣ) *k*<sub>λ</sub> − ɣ (*v*<sub>λ</sub> + 1), and the algorithm
+//! behaves exactly as the unweighted one.
+//!
+//! # Checkpointing
+//!
+//! The final phase, in which the labelings obtained for the different ɣ's are
+//! combined, might take a long time on large graphs. If a checkpoint directory
+//! is provided, the labelings are stored in the directory, rather than in a
+//! temporary directory, and after each combination step the current combined
+//! labels are written to a checkpoint file named [`COMBINE_CHECKPOINT`] in the
+//! same directory. If the computation is resumed, the ɣ's are not recomputed and
+//! the combination restarts from the step following the one in the checkpoint.
+//!
+//! The checkpoint file is a sequence of little-endian 64-bit words containing
+//! the number of nodes, the number of ɣ's, the indices of the ɣ's in the order
+//! in which they are combined, the index of the last completed step, and the
+//! combined labels.
 //!
 use crate::prelude::*;
 use crate::traits::*;
-use anyhow::{Context, Result};
+use anyhow::{ensure, Context, Result};
 use dsi_progress_logger::prelude::*;
 use epserde::prelude::*;
 use llp::preds::PredParams;
@@ -43,13 +81,15 @@ use rand::SeedableRng;
 use rayon::prelude::*;
 use std::collections::HashMap;
 use std::collections::VecDeque;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
 use std::sync::atomic::Ordering;
 use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize};
 use sux::traits::Succ;
 use sync_cell_slice::SyncSlice;
 use tempfile::tempdir;
 
-pub(crate) mod gap_cost;
+pub mod gap_cost;
 pub(crate) mod label_store;
 mod mix64;
 pub mod preds;
@@ -76,6 +116,12 @@ pub mod preds;
 ///   computed adaptively. This is an advanced option: see
 ///   [par_apply](crate::traits::SequentialLabeling::par_apply).
 /// * `seed` - The seed to use for pseudorandom number generation.
+/// * `node_weights` - If not `None`, the [weights](self#node-weights) of the
+///   nodes; it must have length equal to the number of nodes.
+/// * `checkpoint_dir` - If not `None`, a directory in which the labelings are
+///   stored and the combination phase is [checkpointed](self#checkpointing).
+/// * `resume` - Whether to resume the computation from the checkpoint in
+///   `checkpoint_dir`, if any.
 #[allow(clippy::type_complexity)]
 #[allow(clippy::too_many_arguments)]
 pub fn layered_label_propagation<R: RandomAccessGraph + Sync>(
@@ -87,11 +133,68 @@ pub fn layered_label_propagation<R: RandomAccessGraph + Sync>(
     granularity: Option<usize>,
     seed: u64,
     predicate: impl Predicate<preds::PredParams>,
+    node_weights: Option<&[usize]>,
+    checkpoint_dir: Option<&Path>,
+    resume: bool,
 ) -> Result<Box<[usize]>> {
-    let work_dir = tempdir().context("Could not create temporary directory")?;
-    let labels_path = |gamma_index| work_dir.path().join(format!("labels_{gamma_index}.bin"));
+    let temp_dir = tempdir().context("Could not create temporary directory")?;
+    let work_dir = checkpoint_dir.unwrap_or(temp_dir.path());
+    let labels_path = |gamma_index| work_dir.join(format!("labels_{gamma_index}.bin"));
     const IMPROV_WINDOW: usize = 10;
     let num_nodes = sym_graph.num_nodes();
+    if let Some(node_weights) = node_weights {
+        ensure!(
+            node_weights.len() == num_nodes,
+            "The number of node weights ({}) is different from the number of nodes ({})",
+            node_weights.len(),
+            num_nodes
+        );
+    }
+    let weight = |node: usize| node_weights.map_or(1, |w| w[node]);
+
+    // There is nothing to propagate, and several computations below (e.g.,
+    // the average degree) are not defined
+    if num_nodes <= 1 {
+        info!("The graph has {} nodes: returning the identity", num_nodes);
+        return Ok((0..num_nodes).collect());
+    }
+
+    let checkpoint = match checkpoint_dir {
+        Some(dir) if resume && dir.join(COMBINE_CHECKPOINT).exists() => {
+            let checkpoint =
+                load_combine_checkpoint(dir.join(COMBINE_CHECKPOINT), num_nodes, gammas.len())?;
+            // The labels will be used to rank the nodes, so we check their
+            // length before starting to combine them
+            for gamma_index in 0..gammas.len() {
+                let path = labels_path(gamma_index);
+                ensure!(
+                    path.exists(),
+                    "Missing labels {} for checkpoint",
+                    path.display()
+                );
+                let labels = <Vec<usize>>::load_mmap(&path, Flags::empty())
+                    .with_context(|| format!("Could not load labels {}", path.display()))?;
+                validate_perm(labels.len(), num_nodes, PermMode::Strict)
+                    .with_context(|| format!("Invalid labels {}", path.display()))?;
+            }
+            info!(
+                "Resuming combination from checkpoint after step {}",
+                checkpoint.1
+            );
+            Some(checkpoint)
+        }
+        Some(_) if resume => {
+            info!("No combination checkpoint found: starting from scratch");
+            None
+        }
+        _ => None,
+    };
+    // If we are resuming, we skip all ɣ's
+    let num_gammas_to_run = if checkpoint.is_some() {
+        0
+    } else {
+        gammas.len()
+    };
     let chunk_size = chunk_size.unwrap_or(1_000_000);
     let granularity = granularity.unwrap_or(Ord::max((sym_graph.num_arcs() >> 9) as usize, 1024));
 
@@ -103,7 +206,9 @@ pub fn layered_label_propagation<R: RandomAccessGraph + Sync>(
     let mut label_store = label_store::LabelStore::new(num_nodes as _);
     let stack_size = std::env::var("RUST_MIN_STACK")
         .map(|value| value.parse().unwrap())
-        .unwrap_or(1024 * num_nodes.ilog2_ceil() as usize);
+        // on very small graphs the default would be smaller than the stack
+        // needed to run and tear down a worker thread
+        .unwrap_or((1024 * num_nodes.ilog2_ceil() as usize).max(32 * 1024));
     // build a thread_pool so we avoid having to re-create the threads
     let num_threads = num_threads.unwrap_or_else(num_cpus::get);
     let thread_pool = rayon::ThreadPoolBuilder::new()
@@ -113,19 +218,21 @@ pub fn layered_label_propagation<R: RandomAccessGraph + Sync>(
         .context("Could not create thread pool")?;
 
     // init the gamma progress logger
-    let mut gamma_pl = progress_logger!(
-        display_memory = true,
-        item_name = "gamma",
-        expected_updates = Some(gammas.len()),
-    );
+    let mut gamma_pl = ProgressLogger::default();
+    gamma_pl
+        .display_memory(true)
+        .item_name("gamma")
+        .expected_updates(Some(gammas.len()));
 
     // init the iteration progress logger
-    let mut iter_pl = progress_logger!(item_name = "update");
+    let mut iter_pl = ProgressLogger::default();
+    iter_pl.item_name("update");
 
     let hash_map_init = Ord::max(sym_graph.num_arcs() / sym_graph.num_nodes() as u64, 16) as usize;
 
     // init the update progress logger
-    let mut update_pl = progress_logger!(item_name = "node", local_speed = true);
+    let mut update_pl = ProgressLogger::default();
+    update_pl.item_name("node").local_speed(true);
 
     let seed = AtomicU64::new(seed);
     let mut costs = Vec::with_capacity(gammas.len());
@@ -133,7 +240,7 @@ pub fn layered_label_propagation<R: RandomAccessGraph + Sync>(
     gamma_pl.start(format!("Running {} threads", num_threads));
     info!("Stopping criterion: {predicate}");
 
-    for (gamma_index, gamma) in gammas.iter().enumerate() {
+    for (gamma_index, gamma) in gammas.iter().enumerate().take(num_gammas_to_run) {
         // Reset mutable state for the next gamma
         iter_pl.start(format!(
             "Starting iterations with gamma={} ({}/{})...",
@@ -141,7 +248,7 @@ pub fn layered_label_propagation<R: RandomAccessGraph + Sync>(
             gamma_index + 1,
             gammas.len(),
         ));
-        label_store.init();
+        label_store.init(node_weights);
         can_change
             .par_iter()
             .for_each(|c| c.store(true, Ordering::Relaxed));
@@ -190,16 +297,22 @@ pub fn layered_label_propagation<R: RandomAccessGraph + Sync>(
                         can_change[node].store(false, Ordering::Relaxed);
 
                         let successors = sym_graph.successors(node);
-                        if sym_graph.outdegree(node) == 0 {
+                        // the hint is an upper bound, so a zero hint
+                        // implies no successors
+                        let outdegree_hint = sym_graph.outdegree_hint(node);
+                        if outdegree_hint == 0 {
                             continue;
                         }
 
-                        // get the label of this node
+                        // get the label and the weight of this node
                         let curr_label = label_store.label(node);
+                        let node_weight = weight(node);
 
                         // compute the frequency of successor labels
-                        let mut map =
-                            HashMap::with_capacity_and_hasher(hash_map_init, mix64::Mix64Builder);
+                        let mut map = HashMap::with_capacity_and_hasher(
+                            Ord::min(outdegree_hint + 1, hash_map_init),
+                            mix64::Mix64Builder,
+                        );
                         for succ in successors {
                             map.entry(label_store.label(succ))
                                 .and_modify(|counter| *counter += 1)
@@ -224,7 +337,8 @@ pub fn layered_label_propagation<R: RandomAccessGraph + Sync>(
                             // the volume of the current label it will get a
                             // value larger by one WRT the Java version.
                             let volume = label_store.volume(label); // - (label == curr_label) as usize;
-                            let val = (1.0 + gamma) * count as f64 - gamma * (volume + 1) as f64;
+                            let val = (1.0 + gamma) * count as f64
+                                - gamma * (volume + node_weight) as f64;
 
                             if max == val {
                                 majorities.push(label);
@@ -249,9 +363,9 @@ pub fn layered_label_propagation<R: RandomAccessGraph + Sync>(
                             for succ in sym_graph.successors(node) {
                                 can_change[succ].store(true, Ordering::Relaxed);
                             }
-                            label_store.update(node, next_label);
+                            label_store.update(node, next_label, node_weight);
                         }
-                        local_obj_func += max - old;
+                        local_obj_func += (max - old) * node_weight as f64;
                     }
                     local_obj_func
                 },
@@ -338,53 +452,231 @@ pub fn layered_label_propagation<R: RandomAccessGraph + Sync>(
 
     gamma_pl.done();
 
-    // compute the indices that sorts the gammas by cost
-    let mut gamma_indices = (0..costs.len()).collect::<Vec<_>>();
-    // sort in descending order
-    gamma_indices.sort_by(|a, b| costs[*b].total_cmp(&costs[*a]));
+    let (gamma_indices, first_step, mut result_labels) = match checkpoint {
+        Some((gamma_indices, step, result_labels)) => (gamma_indices, step + 1, result_labels),
+        None => {
+            // compute the indices that sorts the gammas by cost
+            let mut gamma_indices = (0..costs.len()).collect::<Vec<_>>();
+            // sort in descending order
+            gamma_indices.sort_by(|a, b| costs[*b].total_cmp(&costs[*a]));
+
+            let best_gamma_index = *gamma_indices.last().unwrap();
+            let worst_gamma_index = gamma_indices[0];
+            let best_gamma = gammas[best_gamma_index];
+            let worst_gamma = gammas[worst_gamma_index];
+            info!(
+                "Best gamma: {}\twith log-gap cost {}",
+                best_gamma, costs[best_gamma_index]
+            );
+            info!(
+                "Worst gamma: {}\twith log-gap cost {}",
+                worst_gamma, costs[worst_gamma_index]
+            );
+            let result_labels = <Vec<usize>>::load_mem(labels_path(best_gamma_index))
+                .context("Could not load labels from best gammar")?
+                .to_vec();
+            (gamma_indices, 0, result_labels)
+        }
+    };
 
     // the best gamma is the last because it has the min cost
     let best_gamma_index = *gamma_indices.last().unwrap();
-    let worst_gamma_index = gamma_indices[0];
-    let best_gamma = gammas[best_gamma_index];
-    let worst_gamma = gammas[worst_gamma_index];
-    info!(
-        "Best gamma: {}\twith log-gap cost {}",
-        best_gamma, costs[best_gamma_index]
-    );
-    info!(
-        "Worst gamma: {}\twith log-gap cost {}",
-        worst_gamma, costs[worst_gamma_index]
-    );
     // reuse the update_perm to store the final permutation
     let mut temp_perm = update_perm;
 
-    let mut result_labels = <Vec<usize>>::load_mem(labels_path(best_gamma_index))
-        .context("Could not load labels from best gammar")?
-        .to_vec();
-
     let mmap_flags = Flags::TRANSPARENT_HUGE_PAGES | Flags::RANDOM_ACCESS;
-    for (i, gamma_index) in gamma_indices.iter().enumerate() {
+    for (i, gamma_index) in gamma_indices.iter().enumerate().skip(first_step) {
         info!("Starting step {}...", i);
         let labels = <Vec<usize>>::load_mmap(labels_path(*gamma_index), mmap_flags)
             .context("Could not load labels")?;
-        combine(&mut result_labels, *labels, &mut temp_perm).context("Could not combine labels")?;
+        combine(&mut result_labels, *labels, &mut temp_perm, None)
+            .context("Could not combine labels")?;
         // This recombination with the best labels does not appear in the paper, but
         // it is not harmful and fixes a few corner cases in which experimentally
         // LLP does not perform well. It was introduced by Marco Rosa in the Java
         // LAW code.
         let best_labels = <Vec<usize>>::load_mmap(labels_path(best_gamma_index), mmap_flags)
             .context("Could not load labels from best gamma")?;
-        let number_of_labels = combine(&mut result_labels, *best_labels, &mut temp_perm)?;
+        let number_of_labels = combine(&mut result_labels, *best_labels, &mut temp_perm, None)?;
         info!("Number of labels: {}", number_of_labels);
+        if let Some(dir) = checkpoint_dir {
+            store_combine_checkpoint(
+                dir.join(COMBINE_CHECKPOINT),
+                &gamma_indices,
+                i,
+                &result_labels,
+            )?;
+        }
         info!("Finished step {}.", i);
     }
 
     Ok(result_labels.into_boxed_slice())
 }
 
+/// The name of the checkpoint file of the combination phase.
+pub const COMBINE_CHECKPOINT: &str = "combine.ckpt";
+
+/// Writes atomically a checkpoint of the combination phase.
+fn store_combine_checkpoint(
+    path: impl AsRef<Path>,
+    gamma_indices: &[usize],
+    step: usize,
+    labels: &[usize],
+) -> Result<()> {
+    let path = path.as_ref();
+    let tmp_path = path.with_extension("tmp");
+    let mut file = BufWriter::new(
+        std::fs::File::create(&tmp_path)
+            .with_context(|| format!("Could not create {}", tmp_path.display()))?,
+    );
+    let header = [labels.len(), gamma_indices.len()]
+        .into_iter()
+        .chain(gamma_indices.iter().copied())
+        .chain([step]);
+    for word in header.chain(labels.iter().copied()) {
+        file.write_all(&(word as u64).to_le_bytes())
+            .with_context(|| format!("Could not write {}", tmp_path.display()))?;
+    }
+    file.into_inner()
+        .context("Could not flush checkpoint")?
+        .sync_all()
+        .context("Could not sync checkpoint")?;
+    std::fs::rename(&tmp_path, path)
+        .with_context(|| format!("Could not rename checkpoint to {}", path.display()))?;
+    Ok(())
+}
+
+/// Loads a checkpoint of the combination phase, returning the indices of the
+/// ɣ's in combination order, the last completed step, and the combined labels.
+fn load_combine_checkpoint(
+    path: impl AsRef<Path>,
+    num_nodes: usize,
+    num_gammas: usize,
+) -> Result<(Vec<usize>, usize, Vec<usize>)> {
+    let path = path.as_ref();
+    let mut file = BufReader::new(
+        std::fs::File::open(path).with_context(|| format!("Could not open {}", path.display()))?,
+    );
+    let mut read_word = || -> Result<usize> {
+        let mut buf = [0; 8];
+        file.read_exact(&mut buf)
+            .with_context(|| format!("Could not read {}", path.display()))?;
+        Ok(u64::from_le_bytes(buf) as usize)
+    };
+    let checkpoint_nodes = read_word()?;
+    ensure!(
+        checkpoint_nodes == num_nodes,
+        "The checkpoint {} has {} nodes, but the graph has {} nodes",
+        path.display(),
+        checkpoint_nodes,
+        num_nodes
+    );
+    let checkpoint_gammas = read_word()?;
+    ensure!(
+        checkpoint_gammas == num_gammas,
+        "The checkpoint {} has {} gammas, but {} gammas were specified",
+        path.display(),
+        checkpoint_gammas,
+        num_gammas
+    );
+    let gamma_indices = (0..num_gammas)
+        .map(|_| read_word())
+        .collect::<Result<Vec<_>>>()?;
+    ensure!(
+        gamma_indices.iter().all(|&i| i < num_gammas),
+        "Invalid gamma index in checkpoint {}",
+        path.display()
+    );
+    let step = read_word()?;
+    let labels = (0..num_nodes)
+        .map(|_| read_word())
+        .collect::<Result<Vec<_>>>()?;
+    Ok((gamma_indices, step, labels))
+}
+
+/// The error returned by [`combine_labels_with`] when the combination is
+/// cancelled.
+///
+/// Since the combination is performed on an internal copy of the labels,
+/// the partial work is simply discarded. Use
+/// [`anyhow::Error::downcast_ref`] to detect this error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl std::fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "The combination of labels was cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+/// Combines labelings into a single one, as in the final phase of
+/// [`layered_label_propagation`], and returns the combined labels.
+///
+/// The labelings must be sorted by decreasing cost, so the last one is the
+/// best: starting from the best labeling, each labeling is combined with the
+/// current labels, and the result is combined again with the best labeling.
+///
+/// This is equivalent to [`combine_labels_with`] with no logging and no
+/// cancellation.
+pub fn combine_labels(labelings: &[impl AsRef<[usize]>]) -> Result<Box<[usize]>> {
+    combine_labels_with(
+        labelings,
+        &mut Option::<ProgressLogger>::None,
+        &AtomicBool::new(false),
+    )
+}
+
+/// Combines labelings into a single one like [`combine_labels`], logging
+/// progress to `pl` and stopping with a [`Cancelled`] error when `cancel`
+/// becomes true.
+///
+/// The cancellation flag is checked between steps, and within each step
+/// between sorting and relabeling; the parallel sort, however, cannot be
+/// interrupted.
+pub fn combine_labels_with(
+    labelings: &[impl AsRef<[usize]>],
+    pl: &mut impl ProgressLog,
+    cancel: &AtomicBool,
+) -> Result<Box<[usize]>> {
+    let best = labelings
+        .last()
+        .context("There are no labelings to combine")?
+        .as_ref();
+    let mut result = best.to_vec();
+    let mut temp_perm = vec![0; best.len()];
+
+    pl.item_name("step").expected_updates(Some(labelings.len()));
+    pl.start("Combining labels...");
+    for labels in labelings {
+        if cancel.load(Ordering::Relaxed) {
+            return Err(Cancelled.into());
+        }
+        let labels = labels.as_ref();
+        ensure!(
+            labels.len() == best.len(),
+            "The labelings have different lengths"
+        );
+        combine(&mut result, labels, &mut temp_perm, Some(cancel))?;
+        combine(&mut result, best, &mut temp_perm, Some(cancel))?;
+        pl.update();
+    }
+    pl.done();
+
+    Ok(result.into_boxed_slice())
+}
+
 /// combine the labels from two permutations into a single one
-fn combine(result: &mut [usize], labels: &[usize], temp_perm: &mut [usize]) -> Result<usize> {
+///
+/// If `cancel` becomes true after sorting, [`Cancelled`] is returned and
+/// `result` is left in an unspecified state.
+fn combine(
+    result: &mut [usize],
+    labels: &[usize],
+    temp_perm: &mut [usize],
+    cancel: Option<&AtomicBool>,
+) -> Result<usize> {
     // re-init the permutation
     temp_perm.iter_mut().enumerate().for_each(|(i, x)| *x = i);
     // permute by the devilish function
@@ -393,6 +685,12 @@ fn combine(result: &mut [usize], labels: &[usize], temp_perm: &mut [usize]) -> R
             .then_with(|| labels[a].cmp(&labels[b]))
             .then_with(|| result[a].cmp(&result[b]))
     });
+    if cancel.is_some_and(|cancel| cancel.load(Ordering::Relaxed)) {
+        return Err(Cancelled.into());
+    }
+    if temp_perm.is_empty() {
+        return Ok(0);
+    }
     let mut prev_labels = (result[temp_perm[0]], labels[temp_perm[0]]);
     let mut curr_label = 0;
     result[temp_perm[0]] = curr_label;
@@ -415,3 +713,171 @@ pub fn invert_permutation(perm: &[usize], inv_perm: &mut [usize]) {
         unsafe { sync_slice[x].set(i) };
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_combine_checkpoint() -> Result<()> {
+        let dir = tempdir()?;
+        let path = dir.path().join(COMBINE_CHECKPOINT);
+        let labels = vec![3, 1, 4, 1, 5, 9, 2];
+        store_combine_checkpoint(&path, &[2, 0, 1], 1, &labels)?;
+        assert_eq!(
+            load_combine_checkpoint(&path, labels.len(), 3)?,
+            (vec![2, 0, 1], 1, labels.clone())
+        );
+        // Wrong number of nodes or of gammas
+        assert!(load_combine_checkpoint(&path, labels.len() + 1, 3).is_err());
+        assert!(load_combine_checkpoint(&path, labels.len(), 2).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_combine_labels_cancel() -> Result<()> {
+        let labelings = [
+            vec![0, 0, 1, 1, 2, 2],
+            vec![0, 1, 1, 2, 2, 0],
+            vec![0, 0, 0, 1, 1, 1],
+        ];
+        let combined = combine_labels(&labelings)?;
+        // Two nodes have the same label iff they have the same label in all
+        // labelings
+        for x in 0..6 {
+            for y in 0..6 {
+                assert_eq!(
+                    combined[x] == combined[y],
+                    labelings.iter().all(|labels| labels[x] == labels[y])
+                );
+            }
+        }
+
+        // Cancel when the second step starts
+        struct Watched<'a>(&'a [usize], Option<&'a AtomicBool>);
+        impl AsRef<[usize]> for Watched<'_> {
+            fn as_ref(&self) -> &[usize] {
+                if let Some(cancel) = self.1 {
+                    cancel.store(true, Ordering::Relaxed);
+                }
+                self.0
+            }
+        }
+        let cancel = AtomicBool::new(false);
+        let watched = [
+            Watched(&labelings[0], None),
+            Watched(&labelings[1], Some(&cancel)),
+            Watched(&labelings[2], None),
+        ];
+        let err = combine_labels_with(&watched, &mut Option::<ProgressLogger>::None, &cancel)
+            .unwrap_err();
+        assert_eq!(err.downcast_ref::<Cancelled>(), Some(&Cancelled));
+
+        // Cancelled before starting
+        let err = combine_labels_with(
+            &labelings,
+            &mut Option::<ProgressLogger>::None,
+            &AtomicBool::new(true),
+        )
+        .unwrap_err();
+        assert!(err.is::<Cancelled>());
+        Ok(())
+    }
+
+    #[test]
+    fn test_unit_node_weights() -> Result<()> {
+        use crate::graphs::vec_graph::VecGraph;
+        use sux::dict::EliasFanoBuilder;
+        use sux::rank_sel::{SelectAdaptConst, SelectZeroAdaptConst};
+
+        // Two triangles connected by an edge, symmetrized
+        let arcs = [(0, 1), (1, 2), (2, 0), (3, 4), (4, 5), (5, 3), (2, 3)];
+        let graph = Left(VecGraph::from_arc_list(
+            arcs.iter().flat_map(|&(x, y)| [(x, y), (y, x)]),
+        ));
+        let num_nodes = graph.num_nodes();
+        let mut efb = EliasFanoBuilder::new(num_nodes + 1, graph.num_arcs() as usize);
+        let mut cumul = 0;
+        efb.push(0);
+        for node in 0..num_nodes {
+            cumul += graph.outdegree(node);
+            efb.push(cumul);
+        }
+        let deg_cumul: DCF = unsafe {
+            efb.build().map_high_bits(|bits| {
+                SelectZeroAdaptConst::<_, _, 12, 4>::new(SelectAdaptConst::<_, _, 12, 4>::new(bits))
+            })
+        };
+
+        let run = |node_weights: Option<&[usize]>| {
+            layered_label_propagation(
+                &graph,
+                &deg_cumul,
+                vec![0.0, 0.5, 1.0],
+                Some(1),
+                None,
+                None,
+                0,
+                preds::MaxUpdates::from(10),
+                node_weights,
+                None,
+                false,
+            )
+        };
+        // Unit weights must give exactly the unweighted labels
+        let ones = vec![1; num_nodes];
+        assert_eq!(run(None)?, run(Some(&ones))?);
+        // The number of weights must match the number of nodes
+        assert!(run(Some(&ones[1..])).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_combine_labels_tiny() -> Result<()> {
+        assert!(combine_labels(&[Vec::<usize>::new(), vec![]])?.is_empty());
+        assert_eq!(&*combine_labels(&[vec![3], vec![7]])?, &[0]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_tiny_graphs() -> Result<()> {
+        use crate::graphs::vec_graph::VecGraph;
+        use sux::dict::EliasFanoBuilder;
+        use sux::rank_sel::{SelectAdaptConst, SelectZeroAdaptConst};
+
+        // The empty graph and a single node with a loop
+        for graph in [
+            Left(VecGraph::empty(0)),
+            Left(VecGraph::from_arc_list([(0, 0)])),
+        ] {
+            let num_nodes = graph.num_nodes();
+            let mut efb = EliasFanoBuilder::new(num_nodes + 1, graph.num_arcs() as usize);
+            efb.push(0);
+            if num_nodes == 1 {
+                efb.push(1);
+            }
+            let deg_cumul: DCF = unsafe {
+                efb.build().map_high_bits(|bits| {
+                    SelectZeroAdaptConst::<_, _, 12, 4>::new(SelectAdaptConst::<_, _, 12, 4>::new(
+                        bits,
+                    ))
+                })
+            };
+            let labels = layered_label_propagation(
+                &graph,
+                &deg_cumul,
+                vec![0.0, 1.0],
+                Some(1),
+                None,
+                None,
+                0,
+                preds::MaxUpdates::from(10),
+                None,
+                None,
+                false,
+            )?;
+            assert_eq!(&*labels, (0..num_nodes).collect::<Vec<_>>().as_slice());
+        }
+        Ok(())
+    }
+}
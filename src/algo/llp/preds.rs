@@ -18,10 +18,12 @@
 //! ```
 //! # fn main() -> Result<(), Box<dyn std::error::Error>> {
 //! use predicates::prelude::*;
-//! use webgraph::algo::llp::preds::{MinGain, MaxUpdates};
+//! use webgraph::algo::llp::preds::{MinGain, MaxUpdates, UpdateCap};
 //!
 //! let mut predicate = MinGain::try_from(0.001)?.boxed();
 //! predicate = predicate.or(MaxUpdates::from(100)).boxed();
+//! // A safety cap must be combined last
+//! predicate = predicate.or(UpdateCap::from(1000)).boxed();
 //! #     Ok(())
 //! # }
 //! ```
@@ -86,6 +88,66 @@ impl Predicate<PredParams> for MaxUpdates {
     }
 }
 
+/// Stop after at most the provided number of updates for a given ɣ, logging
+/// a warning.
+///
+/// This predicate is not meant as a stopping criterion, like [`MaxUpdates`],
+/// but as a safety cap for unattended runs, so that a pathological ɣ cannot
+/// run unboundedly. It should be combined last using `or`: since
+/// combinations are evaluated lazily, in this way it is evaluated (and the
+/// warning is logged) only when no other predicate stops the updates.
+///
+/// The default cap is unlimited.
+#[derive(Debug, Clone)]
+pub struct UpdateCap {
+    max_updates: usize,
+}
+
+impl UpdateCap {
+    pub const DEFAULT_MAX_UPDATES: usize = usize::MAX;
+}
+
+impl From<Option<usize>> for UpdateCap {
+    fn from(max_updates: Option<usize>) -> Self {
+        match max_updates {
+            Some(max_updates) => UpdateCap { max_updates },
+            None => Self::default(),
+        }
+    }
+}
+
+impl From<usize> for UpdateCap {
+    fn from(max_updates: usize) -> Self {
+        Some(max_updates).into()
+    }
+}
+
+impl Default for UpdateCap {
+    fn default() -> Self {
+        Self::from(Self::DEFAULT_MAX_UPDATES)
+    }
+}
+
+impl Display for UpdateCap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("(update cap: {})", self.max_updates))
+    }
+}
+
+impl PredicateReflection for UpdateCap {}
+impl Predicate<PredParams> for UpdateCap {
+    fn eval(&self, pred_params: &PredParams) -> bool {
+        let capped = pred_params.update + 1 >= self.max_updates;
+        if capped {
+            log::warn!(
+                "Stopping after {} updates because the cap on the number of updates per ɣ was reached",
+                pred_params.update + 1
+            );
+        }
+        capped
+    }
+}
+
 #[derive(Debug, Clone)]
 /// Stop if the gain of the objective function is below the given threshold.
 ///
@@ -250,3 +312,37 @@ impl Predicate<PredParams> for PercModified {
         (pred_params.modified as f64) <= (pred_params.num_nodes as f64) * self.threshold
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use predicates::prelude::*;
+
+    fn params(update: usize, gain: f64) -> PredParams {
+        PredParams {
+            num_nodes: 100,
+            num_arcs: 1000,
+            gain,
+            avg_gain_impr: 1.0,
+            modified: 50,
+            update,
+        }
+    }
+
+    #[test]
+    fn test_update_cap() -> anyhow::Result<()> {
+        assert!(!UpdateCap::default().eval(&params(1_000_000, 1.0)));
+        let cap = UpdateCap::from(3);
+        assert!(!cap.eval(&params(0, 1.0)));
+        assert!(!cap.eval(&params(1, 1.0)));
+        assert!(cap.eval(&params(2, 1.0)));
+
+        let predicate = MinGain::try_from(0.1)?.boxed().or(cap).boxed();
+        assert!(!predicate.eval(&params(0, 1.0)));
+        // The user's criterion fires before the cap
+        assert!(predicate.eval(&params(0, 0.01)));
+        // The cap fires even if the user's criterion does not
+        assert!(predicate.eval(&params(2, 1.0)));
+        Ok(())
+    }
+}
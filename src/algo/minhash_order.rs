@@ -0,0 +1,127 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::traits::RandomAccessGraph;
+use crate::utils::random_key::{mix64, GOLDEN_GAMMA};
+use dsi_progress_logger::prelude::*;
+use rayon::prelude::*;
+use rayon::ThreadPool;
+
+/// Returns the permutation sorting the nodes of a graph lexicographically by
+/// the minhash signatures of their successor sets.
+///
+/// The signature of a node is given by `num_hashes` values, the _i_-th being
+/// the minimum over the successors of the node of a hash function depending
+/// on `seed` and _i_. Nodes with similar successor sets have, with high
+/// probability, signatures with a long common prefix, so they are placed
+/// close to each other; nodes without successors are placed at the end. Ties
+/// are broken by node index, so the result depends only on the graph,
+/// `num_hashes` and `seed`, and not on the number of threads.
+///
+/// This ordering is much cheaper than [LLP](crate::algo::llp) and often
+/// recovers a good part of its compression gains. Signatures are computed in
+/// a single parallel pass over the successor lists, and they are then sorted
+/// in parallel, so the computation requires 8`num_hashes` + 8 bytes per node.
+///
+/// As in [`par_bfs_perm`](super::par_bfs_perm), the result maps each node to
+/// its position in the new order.
+pub fn minhash_order<G: RandomAccessGraph + Sync>(
+    graph: &G,
+    num_hashes: usize,
+    seed: u64,
+    thread_pool: &ThreadPool,
+    pl: &mut impl ProgressLog,
+) -> Box<[usize]> {
+    let num_nodes = graph.num_nodes();
+    let hash_seeds = (0..num_hashes as u64)
+        .map(|i| mix64(seed.wrapping_add((i + 1).wrapping_mul(GOLDEN_GAMMA))))
+        .collect::<Vec<_>>();
+
+    pl.item_name("node").expected_updates(Some(num_nodes));
+    pl.start(format!("Computing {} minhashes per node...", num_hashes));
+    let mut signatures = vec![u64::MAX; num_nodes * num_hashes];
+    thread_pool.install(|| {
+        signatures
+            .par_chunks_mut(num_hashes.max(1))
+            .enumerate()
+            .for_each(|(node, signature)| {
+                for succ in graph.successors(node) {
+                    for (min, &hash_seed) in signature.iter_mut().zip(&hash_seeds) {
+                        *min = (*min).min(mix64(hash_seed ^ succ as u64));
+                    }
+                }
+            })
+    });
+    pl.done_with_count(num_nodes);
+
+    pl.start("Sorting nodes by signature...");
+    let signature = |node: usize| &signatures[node * num_hashes..(node + 1) * num_hashes];
+    let mut nodes = (0..num_nodes).collect::<Vec<_>>();
+    thread_pool.install(|| {
+        nodes.par_sort_unstable_by(|&a, &b| signature(a).cmp(signature(b)).then(a.cmp(&b)))
+    });
+    pl.done_with_count(num_nodes);
+
+    let mut perm = vec![0; num_nodes].into_boxed_slice();
+    for (pos, node) in nodes.into_iter().enumerate() {
+        perm[node] = pos;
+    }
+    perm
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphs::random::ErdosRenyi;
+    use crate::graphs::vec_graph::VecGraph;
+    use crate::prelude::*;
+
+    #[test]
+    fn test_minhash_order() {
+        let graph = Left(VecGraph::from_lender(ErdosRenyi::new(1000, 0.01, 0).iter()));
+        let mut perms = vec![];
+        for num_threads in [1, 4] {
+            let thread_pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(num_threads)
+                .build()
+                .unwrap();
+            for _ in 0..2 {
+                perms.push(minhash_order(
+                    &graph,
+                    4,
+                    42,
+                    &thread_pool,
+                    &mut ProgressLogger::default(),
+                ));
+            }
+        }
+        // Deterministic, also with a different number of threads
+        assert!(perms.iter().all(|perm| perm == &perms[0]));
+        // A valid permutation
+        let mut sorted = perms[0].to_vec();
+        sorted.sort_unstable();
+        assert_eq!(sorted, (0..graph.num_nodes()).collect::<Vec<_>>());
+
+        // Nodes with the same successors are adjacent, nodes without
+        // successors are at the end
+        let graph = Left(VecGraph::from_arc_list([
+            (0, 5),
+            (0, 6),
+            (1, 7),
+            (2, 5),
+            (2, 6),
+            (3, 7),
+        ]));
+        let thread_pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(2)
+            .build()
+            .unwrap();
+        let perm = minhash_order(&graph, 2, 0, &thread_pool, &mut ProgressLogger::default());
+        assert_eq!(perm[0].abs_diff(perm[2]), 1);
+        assert_eq!(perm[1].abs_diff(perm[3]), 1);
+        assert!((4..8).all(|node| perm[node] >= 4));
+    }
+}
@@ -8,7 +8,50 @@
 //! Algorithmic utilities.
 
 mod bfs_order;
-pub use bfs_order::BfsOrder;
+pub use bfs_order::{par_bfs_layers, par_bfs_perm, BfsOrder, IntraLayerOrder, ParBfs, SeqBfs};
+
+mod biconnected;
+pub use biconnected::{biconnected_components, BiconnectedComponents};
+
+mod bow_tie;
+pub use bow_tie::{bow_tie, BowTie, BowTieRegion};
+
+mod bridges;
+pub use bridges::{bridge_components, bridges};
+
+mod clustering;
+pub use clustering::local_clustering_coefficients;
+
+mod component_sizes;
+pub use component_sizes::{component_sizes, ComponentSizes};
+
+mod components;
+pub use components::Components;
+
+mod dag;
+pub use dag::{is_acyclic, topological_sort, transitive_reduction};
+
+mod degree_centrality;
+pub use degree_centrality::{degree_centrality, indegrees, DegreeMode};
+
+pub mod distances;
+
+pub mod graphlets;
+
+mod label_pairs;
+pub use label_pairs::{for_each_two_hop_path, label_pair_counts, LabelPairCounts};
 
 pub mod llp;
 pub use llp::*;
+
+mod minhash_order;
+pub use minhash_order::minhash_order;
+
+mod scc;
+pub use scc::strongly_connected_components;
+
+mod small_world;
+pub use small_world::{small_world_coefficient, SmallWorld};
+
+mod triad_census;
+pub use triad_census::{triad_census, TRIAD_NAMES};
@@ -0,0 +1,120 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use super::Components;
+use crate::traits::RandomAccessGraph;
+use dsi_progress_logger::prelude::*;
+
+/// Computes the strongly connected components of a graph using an iterative
+/// version of Tarjan's algorithm.
+///
+/// Components are numbered in the order in which they are completed, which
+/// is a reverse topological order of the condensation of the graph: if there
+/// is an arc from a node of component *a* to a node of component *b* ≠ *a*,
+/// then *b* < *a*.
+///
+/// Besides the returned [`Components`], the algorithm uses three `usize` per
+/// node, and a stack of successor iterators whose depth is at most the
+/// length of the longest simple path visited.
+pub fn strongly_connected_components(
+    graph: &impl RandomAccessGraph,
+    pl: &mut impl ProgressLog,
+) -> Components {
+    let num_nodes = graph.num_nodes();
+    // Discovery index of each node; usize::MAX if not yet visited
+    let mut index = vec![usize::MAX; num_nodes];
+    let mut low_link = vec![0; num_nodes];
+    // The component of each node; usize::MAX if still on the Tarjan stack
+    let mut components = vec![usize::MAX; num_nodes];
+    let mut stack = Vec::new();
+    let mut call_stack = Vec::new();
+    let mut next_index = 0;
+    let mut num_components = 0;
+
+    pl.item_name("node");
+    pl.expected_updates(Some(num_nodes));
+    pl.start("Computing strongly connected components...");
+
+    for root in 0..num_nodes {
+        if index[root] != usize::MAX {
+            continue;
+        }
+        index[root] = next_index;
+        low_link[root] = next_index;
+        next_index += 1;
+        stack.push(root);
+        call_stack.push((root, graph.successors(root).into_iter()));
+
+        while let Some((node, successors)) = call_stack.last_mut() {
+            let node = *node;
+            match successors.next() {
+                Some(succ) if index[succ] == usize::MAX => {
+                    index[succ] = next_index;
+                    low_link[succ] = next_index;
+                    next_index += 1;
+                    stack.push(succ);
+                    call_stack.push((succ, graph.successors(succ).into_iter()));
+                }
+                Some(succ) => {
+                    // Nodes with an assigned component are not on the stack
+                    if components[succ] == usize::MAX {
+                        low_link[node] = low_link[node].min(index[succ]);
+                    }
+                }
+                None => {
+                    call_stack.pop();
+                    if let Some(&(parent, _)) = call_stack.last() {
+                        low_link[parent] = low_link[parent].min(low_link[node]);
+                    }
+                    if low_link[node] == index[node] {
+                        loop {
+                            let x = stack.pop().unwrap();
+                            components[x] = num_components;
+                            pl.light_update();
+                            if x == node {
+                                break;
+                            }
+                        }
+                        num_components += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    pl.done();
+    Components::new(num_components, components.into_boxed_slice())
+}
+
+#[cfg(test)]
+#[test]
+fn test_scc() {
+    use crate::graphs::vec_graph::VecGraph;
+    use crate::prelude::*;
+    let arcs = [
+        (0, 1),
+        (1, 2),
+        (2, 0),
+        (2, 3),
+        (3, 4),
+        (4, 3),
+        (5, 4),
+        (6, 6),
+    ];
+    let graph = Left(VecGraph::from_arc_list(arcs));
+    let components = strongly_connected_components(&graph, &mut ProgressLogger::default());
+    assert_eq!(components.num_components(), 4);
+    let c = components.components();
+    assert!(c[0] == c[1] && c[1] == c[2]);
+    assert_eq!(c[3], c[4]);
+    assert_ne!(c[0], c[3]);
+    assert_ne!(c[5], c[3]);
+    assert_ne!(c[6], c[0]);
+    // Reverse topological order
+    for (x, y) in arcs {
+        assert!(c[y] <= c[x]);
+    }
+}
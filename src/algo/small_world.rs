@@ -0,0 +1,146 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use super::distances::sample_pair_distances;
+use super::local_clustering_coefficients;
+use crate::traits::RandomAccessGraph;
+use dsi_progress_logger::prelude::*;
+use rayon::ThreadPool;
+
+/// The result of [`small_world_coefficient`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SmallWorld {
+    /// The average local clustering coefficient _C_ of the graph.
+    pub clustering: f64,
+    /// The estimated average distance _L_ between reachable pairs of distinct
+    /// nodes.
+    pub path_length: f64,
+    /// The clustering coefficient _k_ / _n_ of an Erdős–Rényi graph with the
+    /// same number of nodes _n_ and average degree _k_.
+    pub random_clustering: f64,
+    /// The average distance ln _n_ / ln _k_ of an Erdős–Rényi graph with the
+    /// same number of nodes _n_ and average degree _k_.
+    pub random_path_length: f64,
+    /// The number of sampled pairs.
+    pub samples: usize,
+    /// The number of sampled pairs whose target is reachable from the source.
+    pub reachable_samples: usize,
+}
+
+impl SmallWorld {
+    /// Returns the Watts–Strogatz coefficient (_C_ / _C_<sub>rand</sub>) / (_L_
+    /// / _L_<sub>rand</sub>).
+    ///
+    /// The result is NaN if any of the quantities involved is undefined,
+    /// for example, because no sampled pair was reachable.
+    pub fn coefficient(&self) -> f64 {
+        (self.clustering / self.random_clustering) / (self.path_length / self.random_path_length)
+    }
+
+    /// Returns whether the graph qualifies as small-world, that is, whether
+    /// the [coefficient](SmallWorld::coefficient) is greater than one.
+    pub fn is_small_world(&self) -> bool {
+        self.coefficient() > 1.0
+    }
+}
+
+/// Estimates the Watts–Strogatz small-world coefficient of a symmetric graph.
+///
+/// The coefficient is (_C_ / _C_<sub>rand</sub>) / (_L_ /
+/// _L_<sub>rand</sub>), where _C_ is the average [local clustering
+/// coefficient](local_clustering_coefficients), computed exactly by counting
+/// triangles, _L_ is the average distance between pairs of distinct nodes,
+/// and _C_<sub>rand</sub> and _L_<sub>rand</sub> are the same quantities
+/// for an Erdős–Rényi graph with the same number of nodes and arcs.
+///
+/// _L_ is estimated by [sampling](sample_pair_distances) the distance
+/// between `path_length_samples` pairs of distinct nodes chosen uniformly at
+/// random using the given seed; since the graph is symmetric, distances are
+/// computed by bidirectional visits. Unreachable pairs are ignored.
+///
+/// The graph must be symmetric and without duplicate arcs.
+pub fn small_world_coefficient(
+    graph: &(impl RandomAccessGraph + Sync),
+    path_length_samples: usize,
+    seed: u64,
+    thread_pool: &ThreadPool,
+    pl: &mut (impl ProgressLog + Send),
+) -> SmallWorld {
+    let num_nodes = graph.num_nodes();
+    let coefficients = local_clustering_coefficients(graph, thread_pool, pl);
+    let clustering = coefficients.iter().sum::<f64>() / num_nodes as f64;
+
+    let sample = sample_pair_distances(
+        graph,
+        Some(graph),
+        path_length_samples,
+        seed,
+        thread_pool,
+        pl,
+    );
+
+    let avg_degree = graph.num_arcs() as f64 / num_nodes as f64;
+    SmallWorld {
+        clustering,
+        path_length: sample.average_distance(),
+        random_clustering: avg_degree / num_nodes as f64,
+        random_path_length: (num_nodes as f64).ln() / avg_degree.ln(),
+        samples: sample.samples(),
+        reachable_samples: sample.reachable(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphs::vec_graph::VecGraph;
+    use crate::labels::Left;
+
+    #[test]
+    fn test_small_world_coefficient() {
+        let thread_pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(2)
+            .build()
+            .unwrap();
+
+        // A ring lattice in which each node is connected to its four nearest
+        // neighbors, plus some shortcuts
+        let n = 200;
+        let mut arcs = vec![];
+        for x in 0..n {
+            for d in 1..=2 {
+                arcs.push((x, (x + d) % n));
+                arcs.push(((x + d) % n, x));
+            }
+        }
+        for (x, y) in [(0, 100), (50, 150), (25, 125), (75, 175)] {
+            arcs.push((x, y));
+            arcs.push((y, x));
+        }
+        let g = Left(VecGraph::from_arc_list(arcs));
+        let sw = small_world_coefficient(&g, 1000, 0, &thread_pool, &mut ProgressLogger::default());
+        assert_eq!(sw.samples, 1000);
+        assert_eq!(sw.reachable_samples, 1000);
+        assert!(sw.clustering > 0.4);
+        assert!(sw.path_length > 1.0);
+        assert!(sw.is_small_world());
+
+        // A complete graph has C = L = 1
+        let mut arcs = vec![];
+        for x in 0..10 {
+            for y in 0..10 {
+                if x != y {
+                    arcs.push((x, y));
+                }
+            }
+        }
+        let g = Left(VecGraph::from_arc_list(arcs));
+        let sw = small_world_coefficient(&g, 100, 0, &thread_pool, &mut ProgressLogger::default());
+        assert_eq!(sw.clustering, 1.0);
+        assert_eq!(sw.path_length, 1.0);
+        assert_eq!(sw.random_clustering, 0.9);
+    }
+}
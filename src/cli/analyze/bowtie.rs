@@ -0,0 +1,106 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::algo::{bow_tie, BowTieRegion};
+use crate::cli::*;
+use crate::prelude::*;
+use anyhow::Result;
+use clap::{ArgMatches, Args, Command, FromArgMatches};
+use dsi_bitstream::prelude::*;
+use dsi_progress_logger::prelude::*;
+use std::path::PathBuf;
+
+pub const COMMAND_NAME: &str = "bowtie";
+
+#[derive(Args, Debug)]
+#[command(about = "Computes the bow-tie decomposition of a graph (core, in, out, tendrils, tubes, and disconnected nodes) around its largest strongly connected component, printing the size of each region.", long_about = None)]
+pub struct CliArgs {
+    /// The basename of the graph.
+    pub src: PathBuf,
+
+    #[arg(short, long)]
+    /// The basename of the transpose of the graph.
+    pub transposed: PathBuf,
+
+    #[arg(short, long)]
+    /// Where to write the region of each node, numbered as in the output
+    /// (core is 0, disconnected is 5).
+    pub regions: Option<PathBuf>,
+
+    #[arg(long, value_enum, default_value_t = IntVectorFormat::Ascii, requires = "regions")]
+    /// The format of the regions.
+    pub fmt: IntVectorFormat,
+}
+
+pub fn cli(command: Command) -> Command {
+    command.subcommand(
+        CliArgs::augment_args(Command::new(COMMAND_NAME))
+            .alias("bow-tie")
+            .display_order(0),
+    )
+}
+
+pub fn main(submatches: &ArgMatches) -> Result<()> {
+    let args = CliArgs::from_arg_matches(submatches)?;
+
+    match get_endianness(&args.src)?.as_str() {
+        #[cfg(any(
+            feature = "be_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        BE::NAME => bowtie::<BE>(args),
+        #[cfg(any(
+            feature = "le_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        LE::NAME => bowtie::<LE>(args),
+        e => panic!("Unknown endianness: {}", e),
+    }
+}
+
+pub fn bowtie<E: Endianness + 'static>(args: CliArgs) -> Result<()>
+where
+    for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+{
+    check_transposed(&args.src, &args.transposed)?;
+    let graph = BvGraph::with_basename(&args.src)
+        .endianness::<E>()
+        .mode::<Mmap>()
+        .flags(MemoryFlags::RANDOM_ACCESS)
+        .load()?;
+    let transpose = BvGraph::with_basename(&args.transposed)
+        .endianness::<E>()
+        .mode::<Mmap>()
+        .flags(MemoryFlags::RANDOM_ACCESS)
+        .load()?;
+
+    let mut pl = ProgressLogger::default();
+    pl.display_memory(true);
+    let bow_tie = bow_tie(&graph, &transpose, &mut pl);
+
+    let num_nodes = graph.num_nodes().max(1) as f64;
+    for (i, region) in BowTieRegion::ALL.iter().enumerate() {
+        let size = bow_tie.size(*region);
+        println!(
+            "{}\t{}\t{}\t{:.3}%",
+            i,
+            region.name(),
+            size,
+            100.0 * size as f64 / num_nodes
+        );
+    }
+
+    if let Some(path) = &args.regions {
+        let regions = bow_tie
+            .regions()
+            .iter()
+            .map(|&region| region as usize)
+            .collect::<Vec<_>>();
+        args.fmt.store(path, &regions)?;
+    }
+
+    Ok(())
+}
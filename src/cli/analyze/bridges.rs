@@ -0,0 +1,77 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::algo::bridges;
+use crate::cli::FileOrStdout;
+use crate::prelude::*;
+use anyhow::Result;
+use clap::{ArgMatches, Args, Command, FromArgMatches};
+use dsi_bitstream::prelude::*;
+use std::io::Write;
+use std::path::PathBuf;
+
+pub const COMMAND_NAME: &str = "bridges";
+
+#[derive(Args, Debug)]
+#[command(about = "Finds the bridges (cut arcs) of an undirected graph, that is, the arcs whose removal disconnects the graph.", long_about = None)]
+pub struct CliArgs {
+    /// The basename of the graph, which must be symmetric.
+    pub src: PathBuf,
+
+    #[arg(short, long)]
+    /// Where to write the bridges, one per line as a pair of nodes (the
+    /// smaller first) separated by a tab; if missing, the bridges are written
+    /// to stdout.
+    pub output: Option<PathBuf>,
+}
+
+pub fn cli(command: Command) -> Command {
+    command.subcommand(
+        CliArgs::augment_args(Command::new(COMMAND_NAME))
+            .alias("bridge-detection")
+            .display_order(0),
+    )
+}
+
+pub fn main(submatches: &ArgMatches) -> Result<()> {
+    let args = CliArgs::from_arg_matches(submatches)?;
+
+    match get_endianness(&args.src)?.as_str() {
+        #[cfg(any(
+            feature = "be_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        BE::NAME => find_bridges::<BE>(args),
+        #[cfg(any(
+            feature = "le_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        LE::NAME => find_bridges::<LE>(args),
+        e => panic!("Unknown endianness: {}", e),
+    }
+}
+
+pub fn find_bridges<E: Endianness + 'static>(args: CliArgs) -> Result<()>
+where
+    for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+{
+    let graph = BvGraph::with_basename(&args.src)
+        .endianness::<E>()
+        .mode::<Mmap>()
+        .flags(MemoryFlags::RANDOM_ACCESS)
+        .load()?;
+
+    let bridges = bridges(&graph);
+    log::info!("Found {} bridges", bridges.len());
+
+    let mut writer = FileOrStdout::create(args.output.as_ref())?;
+    for (x, y) in bridges.iter() {
+        writeln!(writer, "{}\t{}", x, y)?;
+    }
+    writer.finish()?;
+
+    Ok(())
+}
@@ -0,0 +1,77 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::prelude::*;
+use anyhow::Result;
+use clap::{ArgMatches, Args, Command, FromArgMatches};
+use dsi_bitstream::prelude::*;
+use dsi_progress_logger::prelude::*;
+use std::io::Write;
+use std::path::PathBuf;
+
+pub const COMMAND_NAME: &str = "degree-dist";
+
+#[derive(Args, Debug)]
+#[command(about = "Prints the outdegree distribution of a BvGraph as tab-separated pairs of outdegree and number of nodes, in increasing outdegree order, omitting outdegrees with no nodes. Only outdegrees are decoded, so this is much faster than a full scan and does not need offsets.", long_about = None)]
+pub struct CliArgs {
+    /// The basename of the graph.
+    pub src: PathBuf,
+}
+
+pub fn cli(command: Command) -> Command {
+    command.subcommand(CliArgs::augment_args(Command::new(COMMAND_NAME)).display_order(0))
+}
+
+pub fn main(submatches: &ArgMatches) -> Result<()> {
+    let args = CliArgs::from_arg_matches(submatches)?;
+
+    match get_endianness(&args.src)?.as_str() {
+        #[cfg(any(
+            feature = "be_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        BE::NAME => degree_dist::<BE>(args),
+        #[cfg(any(
+            feature = "le_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        LE::NAME => degree_dist::<LE>(args),
+        e => panic!("Unknown endianness: {}", e),
+    }
+}
+
+pub fn degree_dist<E: Endianness + 'static>(args: CliArgs) -> Result<()>
+where
+    for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+{
+    let graph = BvGraphSeq::with_basename(&args.src)
+        .endianness::<E>()
+        .load()?;
+
+    let mut pl = ProgressLogger::default();
+    pl.display_memory(true)
+        .item_name("node")
+        .expected_updates(Some(graph.num_nodes()));
+    pl.start("Scanning outdegrees...");
+    let mut dist = Vec::<u64>::new();
+    for degree in graph.degrees_iter() {
+        if degree >= dist.len() {
+            dist.resize(degree + 1, 0);
+        }
+        dist[degree] += 1;
+        pl.light_update();
+    }
+    pl.done();
+
+    let mut stdout = std::io::BufWriter::new(std::io::stdout().lock());
+    for (degree, &count) in dist.iter().enumerate() {
+        if count != 0 {
+            writeln!(stdout, "{}\t{}", degree, count)?;
+        }
+    }
+    stdout.flush()?;
+    Ok(())
+}
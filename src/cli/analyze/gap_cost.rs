@@ -0,0 +1,162 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::algo::llp::gap_cost::compute_log_gap_cost;
+use crate::cli::*;
+use crate::graphs::bvgraph::match_select_params;
+use crate::prelude::*;
+use anyhow::{Context, Result};
+use clap::{ArgMatches, Args, Command, FromArgMatches};
+use dsi_bitstream::prelude::*;
+use dsi_progress_logger::prelude::*;
+use epserde::prelude::*;
+use mmap_rs::MmapFlags;
+use std::path::PathBuf;
+
+pub const COMMAND_NAME: &str = "gap-cost";
+
+#[derive(Args, Debug)]
+#[command(about = "Computes the log-gap cost (the cost minimized by LLP) of a graph under a given permutation, so that different orderings can be compared without recompressing the graph. Without a permutation, the cost of the identity ordering is computed. Requires the .ef and .dcf files of the graph.", long_about = None)]
+pub struct CliArgs {
+    /// The basename of the graph.
+    pub src: PathBuf,
+
+    #[arg(short, long)]
+    /// The permutation to evaluate, mapping each node to its new index, in
+    /// binary big-endian format (or ε-serde format if --epserde is given); if
+    /// missing, the identity permutation is used.
+    pub perm: Option<PathBuf>,
+
+    #[arg(short, long)]
+    /// Load the permutation in ε-serde format.
+    pub epserde: bool,
+
+    #[clap(flatten)]
+    pub perm_mode: PermModeArg,
+
+    #[clap(flatten)]
+    pub num_threads: NumThreadsArg,
+
+    #[arg(long)]
+    /// The number of arcs in each parallel job (default: the number of arcs
+    /// divided by 512, but at least 1024, as in LLP).
+    pub granularity: Option<usize>,
+}
+
+pub fn cli(command: Command) -> Command {
+    command.subcommand(CliArgs::augment_args(Command::new(COMMAND_NAME)).display_order(0))
+}
+
+pub fn main(submatches: &ArgMatches) -> Result<()> {
+    let args = CliArgs::from_arg_matches(submatches)?;
+
+    match get_endianness(&args.src)?.as_str() {
+        #[cfg(any(
+            feature = "be_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        BE::NAME => gap_cost::<BE>(args),
+        #[cfg(any(
+            feature = "le_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        LE::NAME => gap_cost::<LE>(args),
+        e => panic!("Unknown endianness: {}", e),
+    }
+}
+
+pub fn gap_cost<E: Endianness + 'static + Send + Sync>(args: CliArgs) -> Result<()>
+where
+    for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+{
+    let graph = BvGraph::with_basename(&args.src)
+        .endianness::<E>()
+        .mode::<Mmap>()
+        .flags(MemoryFlags::RANDOM_ACCESS)
+        .load()?;
+    let num_nodes = graph.num_nodes();
+    let num_arcs = graph.num_arcs();
+
+    let granularity = args
+        .granularity
+        .unwrap_or_else(|| Ord::max((num_arcs >> 9) as usize, 1024));
+    let thread_pool = get_thread_pool(args.num_threads.num_threads);
+
+    let mut pl = ProgressLogger::default();
+    pl.display_memory(true)
+        .item_name("node")
+        .expected_updates(Some(num_nodes));
+
+    let params = SelectParams::load(&args.src, DCF_SELECT_PROPERTY)?;
+    let cost = match_select_params!(params, L, S => {
+        let deg_cumul = DCFSelect::<L, S>::mmap(
+            Basename::new(&args.src).with_ext(DEG_CUMUL_EXTENSION),
+            Flags::RANDOM_ACCESS,
+        )
+        .with_context(|| {
+            format!(
+                "Could not load degree cumulative function for basename {}",
+                args.src.display()
+            )
+        })?;
+
+        match &args.perm {
+            None => {
+                pl.start("Computing log-gap cost of the identity permutation...");
+                compute_log_gap_cost(
+                    &graph,
+                    granularity,
+                    &*deg_cumul,
+                    &thread_pool,
+                    Some(&mut pl),
+                )
+            }
+            Some(perm_path) if args.epserde => {
+                let perm = <Vec<usize>>::mmap(perm_path, Flags::RANDOM_ACCESS)?;
+                let perm = fit_perm(&*perm, num_nodes, args.perm_mode.into())?;
+                pl.start("Computing log-gap cost of the permuted graph...");
+                compute_log_gap_cost(
+                    &PermutedGraph {
+                        graph: &graph,
+                        perm: &perm,
+                    },
+                    granularity,
+                    &*deg_cumul,
+                    &thread_pool,
+                    Some(&mut pl),
+                )
+            }
+            Some(perm_path) => {
+                let perm = JavaPermutation::mmap(perm_path, MmapFlags::RANDOM_ACCESS)?;
+                let perm = fit_perm(perm, num_nodes, args.perm_mode.into())?;
+                pl.start("Computing log-gap cost of the permuted graph...");
+                compute_log_gap_cost(
+                    &PermutedGraph {
+                        graph: &graph,
+                        perm: &perm,
+                    },
+                    granularity,
+                    &*deg_cumul,
+                    &thread_pool,
+                    Some(&mut pl),
+                )
+            }
+        }
+    });
+    pl.done();
+
+    println!("Log-gap cost: {}", cost);
+    println!(
+        "Log-gap cost per arc: {}",
+        if num_arcs == 0 {
+            0.0
+        } else {
+            cost / num_arcs as f64
+        }
+    );
+
+    Ok(())
+}
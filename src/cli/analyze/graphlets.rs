@@ -0,0 +1,88 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::algo::graphlets::sample_4node_graphlets;
+use crate::cli::FileOrStdout;
+use crate::prelude::*;
+use anyhow::{ensure, Result};
+use clap::{ArgMatches, Args, Command, FromArgMatches};
+use dsi_bitstream::prelude::*;
+use std::io::Write;
+use std::path::PathBuf;
+
+pub const COMMAND_NAME: &str = "graphlets";
+
+#[derive(Args, Debug)]
+#[command(about = "Estimates by sampling the frequencies of the orbits of graphlets with up to four nodes of a symmetric graph.", long_about = None)]
+pub struct CliArgs {
+    /// The basename of the graph.
+    pub src: PathBuf,
+
+    #[arg(long = "4-node")]
+    /// Sample graphlets with up to four nodes (currently the only supported size).
+    pub four_node: bool,
+
+    #[arg(short, long, default_value_t = 100_000)]
+    /// The number of samples.
+    pub samples: usize,
+
+    #[arg(long, default_value_t = 0)]
+    /// The seed of the pseudorandom number generator.
+    pub seed: u64,
+
+    #[arg(short, long)]
+    /// Where to write the orbit counts, one per line as a tab-separated
+    /// orbit/count pair; if missing, counts are written to stdout.
+    pub output: Option<PathBuf>,
+}
+
+pub fn cli(command: Command) -> Command {
+    command.subcommand(CliArgs::augment_args(Command::new(COMMAND_NAME)).display_order(0))
+}
+
+pub fn main(submatches: &ArgMatches) -> Result<()> {
+    let args = CliArgs::from_arg_matches(submatches)?;
+
+    ensure!(
+        args.four_node,
+        "Only graphlets with up to four nodes are supported: please specify --4-node"
+    );
+
+    match get_endianness(&args.src)?.as_str() {
+        #[cfg(any(
+            feature = "be_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        BE::NAME => graphlets::<BE>(args),
+        #[cfg(any(
+            feature = "le_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        LE::NAME => graphlets::<LE>(args),
+        e => panic!("Unknown endianness: {}", e),
+    }
+}
+
+pub fn graphlets<E: Endianness + 'static>(args: CliArgs) -> Result<()>
+where
+    for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+{
+    let graph = BvGraph::with_basename(&args.src)
+        .endianness::<E>()
+        .mode::<Mmap>()
+        .flags(MemoryFlags::RANDOM_ACCESS)
+        .load()?;
+
+    let counts = sample_4node_graphlets(&graph, args.samples, args.seed);
+
+    let mut writer = FileOrStdout::create(args.output.as_ref())?;
+    for (orbit, count) in counts.iter().enumerate() {
+        writeln!(writer, "{}\t{}", orbit, count)?;
+    }
+    writer.finish()?;
+
+    Ok(())
+}
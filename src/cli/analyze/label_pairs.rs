@@ -0,0 +1,108 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::algo::label_pair_counts;
+use crate::cli::*;
+use crate::labels::bitstream::MmapBitStreamLabeling;
+use crate::prelude::*;
+use anyhow::{ensure, Result};
+use clap::{ArgMatches, Args, Command, FromArgMatches};
+use dsi_bitstream::prelude::*;
+use std::io::Write;
+use std::path::PathBuf;
+
+pub const COMMAND_NAME: &str = "label-pairs";
+
+#[derive(Args, Debug)]
+#[command(about = "Counts the pairs (label of u → v, label of v → w) along the paths u → v → w of length two of a graph with γ-coded arc labels (e.g., the multiplicities written by from arcs --multigraph), and prints the most frequent pairs.", long_about = None)]
+pub struct CliArgs {
+    /// The basename of the graph.
+    pub src: PathBuf,
+
+    #[arg(short, long)]
+    /// The basename of the labels (files .labels and .labelef, the latter
+    /// built by build label-ef); if missing, the basename of the graph.
+    pub labels: Option<PathBuf>,
+
+    #[arg(short = 'n', long, default_value_t = 10)]
+    /// The number of pairs to print, in decreasing order of frequency.
+    pub max_pairs: usize,
+
+    #[arg(long)]
+    /// Skip middle nodes whose outdegree is larger than this bound.
+    pub max_middle_outdegree: Option<usize>,
+
+    #[arg(short, long)]
+    /// Where to write the pairs, one per line containing the two labels and
+    /// the number of paths separated by tabs; if missing, the pairs are
+    /// written to stdout.
+    pub output: Option<PathBuf>,
+}
+
+pub fn cli(command: Command) -> Command {
+    command.subcommand(CliArgs::augment_args(Command::new(COMMAND_NAME)).display_order(0))
+}
+
+pub fn main(submatches: &ArgMatches) -> Result<()> {
+    let args = CliArgs::from_arg_matches(submatches)?;
+
+    match get_endianness(&args.src)?.as_str() {
+        #[cfg(any(
+            feature = "be_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        BE::NAME => label_pairs::<BE>(args),
+        #[cfg(any(
+            feature = "le_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        LE::NAME => label_pairs::<LE>(args),
+        e => panic!("Unknown endianness: {}", e),
+    }
+}
+
+pub fn label_pairs<E: Endianness + 'static>(args: CliArgs) -> Result<()>
+where
+    for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+{
+    let graph = BvGraph::with_basename(&args.src)
+        .endianness::<E>()
+        .mode::<Mmap>()
+        .flags(MemoryFlags::RANDOM_ACCESS)
+        .load()?;
+    let labels_basename = args.labels.as_ref().unwrap_or(&args.src);
+    let labels = MmapBitStreamLabeling::<BE, _>::mmap(labels_basename, GammaLabel)?;
+    ensure!(
+        labels.num_nodes() == graph.num_nodes(),
+        "The graph has {} nodes, but the labels are for {} nodes",
+        graph.num_nodes(),
+        labels.num_nodes()
+    );
+    let graph = Zip(graph, labels);
+
+    let start = std::time::Instant::now();
+    let counts = label_pair_counts(&graph, args.max_middle_outdegree);
+    log::info!(
+        "Enumerated {} paths with {} distinct pairs of labels in {:.3} seconds",
+        counts.num_paths,
+        counts.counts.len(),
+        start.elapsed().as_secs_f64()
+    );
+    if args.max_middle_outdegree.is_some() {
+        log::info!(
+            "Skipped {} arcs towards nodes with large outdegree",
+            counts.skipped
+        );
+    }
+
+    let mut writer = FileOrStdout::create(args.output.as_ref())?;
+    for ((first, second), count) in counts.top(args.max_pairs) {
+        writeln!(writer, "{}\t{}\t{}", first, second, count)?;
+    }
+    writer.finish()?;
+
+    Ok(())
+}
@@ -0,0 +1,97 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::algo::local_clustering_coefficients;
+use crate::cli::*;
+use crate::prelude::*;
+use anyhow::{Context, Result};
+use clap::{ArgMatches, Args, Command, FromArgMatches};
+use dsi_bitstream::prelude::*;
+use dsi_progress_logger::prelude::*;
+use std::io::Write;
+use std::path::PathBuf;
+
+pub const COMMAND_NAME: &str = "local-clustering-distribution";
+
+#[derive(Args, Debug)]
+#[command(about = "Computes the local clustering coefficient of every node of a symmetric graph and prints summary statistics.", long_about = None)]
+pub struct CliArgs {
+    /// The basename of the graph.
+    pub src: PathBuf,
+
+    #[arg(short, long)]
+    /// Where to write the coefficients, one per line in node order.
+    pub output: PathBuf,
+
+    #[clap(flatten)]
+    pub num_threads: NumThreadsArg,
+}
+
+pub fn cli(command: Command) -> Command {
+    command.subcommand(CliArgs::augment_args(Command::new(COMMAND_NAME)).display_order(0))
+}
+
+pub fn main(submatches: &ArgMatches) -> Result<()> {
+    let args = CliArgs::from_arg_matches(submatches)?;
+
+    match get_endianness(&args.src)?.as_str() {
+        #[cfg(any(
+            feature = "be_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        BE::NAME => local_clustering_distribution::<BE>(args),
+        #[cfg(any(
+            feature = "le_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        LE::NAME => local_clustering_distribution::<LE>(args),
+        e => panic!("Unknown endianness: {}", e),
+    }
+}
+
+pub fn local_clustering_distribution<E: Endianness + Send + Sync + 'static>(
+    args: CliArgs,
+) -> Result<()>
+where
+    for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+{
+    let graph = BvGraph::with_basename(&args.src)
+        .endianness::<E>()
+        .mode::<Mmap>()
+        .flags(MemoryFlags::RANDOM_ACCESS)
+        .load()?;
+
+    let thread_pool = get_thread_pool(args.num_threads.num_threads);
+    let mut pl = ProgressLogger::default();
+    pl.display_memory(true);
+    let coefficients = local_clustering_coefficients(&graph, &thread_pool, &mut pl);
+
+    let mut writer = OutputFile::create(&args.output)?;
+    for coefficient in &coefficients {
+        writeln!(writer, "{}", coefficient)
+            .with_context(|| format!("Could not write to {}", args.output.display()))?;
+    }
+    writer.finish()?;
+
+    if coefficients.is_empty() {
+        println!("The graph has no nodes");
+        return Ok(());
+    }
+
+    let mean = coefficients.iter().sum::<f64>() / coefficients.len() as f64;
+    let mut sorted = coefficients;
+    sorted.sort_unstable_by(f64::total_cmp);
+    let percentile = |p: usize| sorted[((sorted.len() - 1) * p).div_ceil(100)];
+
+    println!("mean\t{}", mean);
+    println!("min\t{}", sorted[0]);
+    for p in [1, 5, 25, 50, 75, 95, 99] {
+        println!("p{}\t{}", p, percentile(p));
+    }
+    println!("max\t{}", sorted[sorted.len() - 1]);
+
+    Ok(())
+}
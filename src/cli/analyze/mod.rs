@@ -7,7 +7,17 @@
 use anyhow::Result;
 use clap::{ArgMatches, Command};
 
+pub mod bowtie;
+pub mod bridges;
 pub mod codes;
+pub mod degree_dist;
+pub mod gap_cost;
+pub mod graphlets;
+pub mod label_pairs;
+pub mod local_clustering_distribution;
+pub mod node_bits;
+pub mod triad_census;
+pub mod watts_strogatz_coefficient;
 
 pub const COMMAND_NAME: &str = "analyze";
 
@@ -17,13 +27,37 @@ pub fn cli(command: Command) -> Command {
         .subcommand_required(true)
         .arg_required_else_help(true)
         .allow_external_subcommands(true);
+    let sub_command = bowtie::cli(sub_command);
+    let sub_command = bridges::cli(sub_command);
     let sub_command = codes::cli(sub_command);
+    let sub_command = degree_dist::cli(sub_command);
+    let sub_command = gap_cost::cli(sub_command);
+    let sub_command = graphlets::cli(sub_command);
+    let sub_command = label_pairs::cli(sub_command);
+    let sub_command = local_clustering_distribution::cli(sub_command);
+    let sub_command = node_bits::cli(sub_command);
+    let sub_command = triad_census::cli(sub_command);
+    let sub_command = watts_strogatz_coefficient::cli(sub_command);
     command.subcommand(sub_command.display_order(0))
 }
 
 pub fn main(submatches: &ArgMatches) -> Result<()> {
     match submatches.subcommand() {
+        Some((bowtie::COMMAND_NAME, sub_m)) => bowtie::main(sub_m),
+        Some((bridges::COMMAND_NAME, sub_m)) => bridges::main(sub_m),
         Some((codes::COMMAND_NAME, sub_m)) => codes::main(sub_m),
+        Some((degree_dist::COMMAND_NAME, sub_m)) => degree_dist::main(sub_m),
+        Some((gap_cost::COMMAND_NAME, sub_m)) => gap_cost::main(sub_m),
+        Some((graphlets::COMMAND_NAME, sub_m)) => graphlets::main(sub_m),
+        Some((label_pairs::COMMAND_NAME, sub_m)) => label_pairs::main(sub_m),
+        Some((local_clustering_distribution::COMMAND_NAME, sub_m)) => {
+            local_clustering_distribution::main(sub_m)
+        }
+        Some((node_bits::COMMAND_NAME, sub_m)) => node_bits::main(sub_m),
+        Some((triad_census::COMMAND_NAME, sub_m)) => triad_census::main(sub_m),
+        Some((watts_strogatz_coefficient::COMMAND_NAME, sub_m)) => {
+            watts_strogatz_coefficient::main(sub_m)
+        }
         Some((command_name, _)) => {
             eprintln!("Unknown command: {:?}", command_name);
             std::process::exit(1);
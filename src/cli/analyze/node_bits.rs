@@ -0,0 +1,129 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::cli::*;
+use crate::prelude::*;
+use anyhow::{Context, Result};
+use clap::{ArgMatches, Args, Command, FromArgMatches};
+use dsi_bitstream::prelude::*;
+use dsi_progress_logger::prelude::*;
+use log::info;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::PathBuf;
+
+pub const COMMAND_NAME: &str = "node-bits";
+
+#[derive(Args, Debug)]
+#[command(about = "Writes, for each node of a graph, the number of bits occupied by its encoding (i.e., the differences between consecutive offsets), and prints summary statistics. The .offsets file is used if present; otherwise, the graph is decoded.", long_about = None)]
+pub struct CliArgs {
+    /// The basename of the graph.
+    pub src: PathBuf,
+
+    #[arg(short, long)]
+    /// The file where the number of bits of each node will be stored.
+    pub output: PathBuf,
+
+    #[arg(long, value_enum, default_value_t = IntVectorFormat::Ascii)]
+    /// The format of the output file.
+    pub fmt: IntVectorFormat,
+}
+
+pub fn cli(command: Command) -> Command {
+    command.subcommand(CliArgs::augment_args(Command::new(COMMAND_NAME)).display_order(0))
+}
+
+pub fn main(submatches: &ArgMatches) -> Result<()> {
+    let args = CliArgs::from_arg_matches(submatches)?;
+
+    create_parent_dir(&args.output)?;
+
+    match get_endianness(&args.src)?.as_str() {
+        #[cfg(any(
+            feature = "be_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        BE::NAME => node_bits::<BE>(args),
+        #[cfg(any(
+            feature = "le_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        LE::NAME => node_bits::<LE>(args),
+        e => panic!("Unknown endianness: {}", e),
+    }
+}
+
+pub fn node_bits<E: Endianness + 'static>(args: CliArgs) -> Result<()>
+where
+    for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+{
+    let seq_graph = BvGraphSeq::with_basename(&args.src)
+        .endianness::<E>()
+        .load()?;
+    let num_nodes = seq_graph.num_nodes();
+    let offsets_path = Basename::new(&args.src).with_ext(OFFSETS_EXTENSION);
+
+    let mut bits = Vec::with_capacity(num_nodes);
+    let mut num_arcs = 0_u64;
+    let mut pl = ProgressLogger::default();
+    pl.display_memory(true)
+        .item_name("node")
+        .expected_updates(Some(num_nodes));
+
+    // The degrees are needed in both cases, so the graph is always scanned
+    let mut iter = seq_graph.offset_deg_iter();
+    if offsets_path.exists() {
+        info!("The offsets file exists, reading the number of bits from it");
+        let file = BufReader::with_capacity(
+            1 << 20,
+            File::open(&offsets_path)
+                .with_context(|| format!("Could not open {}", offsets_path.display()))?,
+        );
+        let mut reader = BufBitReader::<BE, _>::new(<WordAdapter<u32, _>>::new(file));
+        // The first gap is the offset of the first node
+        reader.read_gamma().context("Could not read gamma")?;
+        let gaps = (0..num_nodes).map(|_| reader.read_gamma());
+        pl.start("Reading offsets...");
+        for ((_offset, degree), gap) in iter.zip(gaps) {
+            bits.push(gap.context("Could not read gamma")? as usize);
+            num_arcs += degree as u64;
+            pl.light_update();
+        }
+    } else {
+        info!("The offsets file does not exist, decoding the graph");
+        pl.start("Decoding offsets...");
+        while let Some((offset, degree)) = iter.next() {
+            // After decoding the degree the position is the next offset
+            bits.push((iter.get_pos() - offset) as usize);
+            num_arcs += degree as u64;
+            pl.light_update();
+        }
+    }
+    pl.done();
+
+    args.fmt.store(&args.output, &bits)?;
+
+    let total = bits.iter().map(|&b| b as u64).sum::<u64>();
+    println!("Total bits: {}", total);
+    println!(
+        "Mean bits per node: {}",
+        if num_nodes == 0 {
+            0.0
+        } else {
+            total as f64 / num_nodes as f64
+        }
+    );
+    println!(
+        "Bits per arc: {}",
+        if num_arcs == 0 {
+            0.0
+        } else {
+            total as f64 / num_arcs as f64
+        }
+    );
+
+    Ok(())
+}
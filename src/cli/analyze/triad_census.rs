@@ -0,0 +1,87 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::algo::{triad_census, TRIAD_NAMES};
+use crate::cli::*;
+use crate::prelude::*;
+use anyhow::{ensure, Result};
+use clap::{ArgMatches, Args, Command, FromArgMatches};
+use dsi_bitstream::prelude::*;
+use std::io::Write;
+use std::path::PathBuf;
+
+pub const COMMAND_NAME: &str = "triad-census";
+
+#[derive(Args, Debug)]
+#[command(about = "Counts the triads of each of the 16 Holland–Leinhardt types in a directed graph.", long_about = None)]
+pub struct CliArgs {
+    /// The basename of the graph.
+    pub src: PathBuf,
+
+    /// The basename of the transpose of the graph.
+    pub transposed: PathBuf,
+
+    #[arg(short, long)]
+    /// Where to write the census, one line per triad type containing its
+    /// Holland–Leinhardt code and its count separated by a tab; if missing,
+    /// the census is written to stdout.
+    pub output: Option<PathBuf>,
+}
+
+pub fn cli(command: Command) -> Command {
+    command.subcommand(CliArgs::augment_args(Command::new(COMMAND_NAME)).display_order(0))
+}
+
+pub fn main(submatches: &ArgMatches) -> Result<()> {
+    let args = CliArgs::from_arg_matches(submatches)?;
+
+    match get_endianness(&args.src)?.as_str() {
+        #[cfg(any(
+            feature = "be_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        BE::NAME => census::<BE>(args),
+        #[cfg(any(
+            feature = "le_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        LE::NAME => census::<LE>(args),
+        e => panic!("Unknown endianness: {}", e),
+    }
+}
+
+pub fn census<E: Endianness + 'static>(args: CliArgs) -> Result<()>
+where
+    for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+{
+    check_transposed(&args.src, &args.transposed)?;
+    let graph = BvGraph::with_basename(&args.src)
+        .endianness::<E>()
+        .mode::<Mmap>()
+        .flags(MemoryFlags::RANDOM_ACCESS)
+        .load()?;
+    let transposed = BvGraph::with_basename(&args.transposed)
+        .endianness::<E>()
+        .mode::<Mmap>()
+        .flags(MemoryFlags::RANDOM_ACCESS)
+        .load()?;
+    ensure!(
+        graph.num_nodes() == transposed.num_nodes(),
+        "The graph has {} nodes, but the transposed graph has {} nodes",
+        graph.num_nodes(),
+        transposed.num_nodes()
+    );
+
+    let census = triad_census(&graph, &transposed);
+
+    let mut writer = FileOrStdout::create(args.output.as_ref())?;
+    for (name, count) in TRIAD_NAMES.iter().zip(census) {
+        writeln!(writer, "{}\t{}", name, count)?;
+    }
+    writer.finish()?;
+
+    Ok(())
+}
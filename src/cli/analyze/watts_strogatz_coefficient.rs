@@ -0,0 +1,94 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::algo::small_world_coefficient;
+use crate::cli::*;
+use crate::prelude::*;
+use anyhow::Result;
+use clap::{ArgMatches, Args, Command, FromArgMatches};
+use dsi_bitstream::prelude::*;
+use dsi_progress_logger::prelude::*;
+use std::path::PathBuf;
+
+pub const COMMAND_NAME: &str = "watts-strogatz-coefficient";
+
+#[derive(Args, Debug)]
+#[command(about = "Estimates the Watts–Strogatz small-world coefficient (C / C_rand) / (L / L_rand) of a symmetric graph, where C is the average local clustering coefficient, L is the average distance (estimated by sampling pairs of nodes), and C_rand and L_rand are the same quantities for an equivalent Erdős–Rényi graph.", long_about = None)]
+pub struct CliArgs {
+    /// The basename of the graph, which must be symmetric.
+    pub src: PathBuf,
+
+    #[arg(short = 'k', long, default_value_t = 1000)]
+    /// The number of random pairs of nodes used to estimate the average distance.
+    pub path_length_samples: usize,
+
+    #[arg(long, default_value_t = 0)]
+    /// The seed of the pseudorandom number generator.
+    pub seed: u64,
+
+    #[clap(flatten)]
+    pub num_threads: NumThreadsArg,
+}
+
+pub fn cli(command: Command) -> Command {
+    command.subcommand(
+        CliArgs::augment_args(Command::new(COMMAND_NAME))
+            .alias("small-world-coefficient")
+            .display_order(0),
+    )
+}
+
+pub fn main(submatches: &ArgMatches) -> Result<()> {
+    let args = CliArgs::from_arg_matches(submatches)?;
+
+    match get_endianness(&args.src)?.as_str() {
+        #[cfg(any(
+            feature = "be_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        BE::NAME => watts_strogatz_coefficient::<BE>(args),
+        #[cfg(any(
+            feature = "le_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        LE::NAME => watts_strogatz_coefficient::<LE>(args),
+        e => panic!("Unknown endianness: {}", e),
+    }
+}
+
+pub fn watts_strogatz_coefficient<E: Endianness + Send + Sync + 'static>(
+    args: CliArgs,
+) -> Result<()>
+where
+    for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+{
+    let graph = BvGraph::with_basename(&args.src)
+        .endianness::<E>()
+        .mode::<Mmap>()
+        .flags(MemoryFlags::RANDOM_ACCESS)
+        .load()?;
+
+    let thread_pool = get_thread_pool(args.num_threads.num_threads);
+    let mut pl = ProgressLogger::default();
+    pl.display_memory(true);
+    let sw = small_world_coefficient(
+        &graph,
+        args.path_length_samples,
+        args.seed,
+        &thread_pool,
+        &mut pl,
+    );
+
+    println!("C\t{}", sw.clustering);
+    println!("C_rand\t{}", sw.random_clustering);
+    println!("L\t{}", sw.path_length);
+    println!("L_rand\t{}", sw.random_path_length);
+    println!("reachable_pairs\t{}/{}", sw.reachable_samples, sw.samples);
+    println!("coefficient\t{}", sw.coefficient());
+    println!("small_world\t{}", sw.is_small_world());
+
+    Ok(())
+}
@@ -5,6 +5,7 @@
  * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
  */
 
+use crate::cli::PrefetchArg;
 use crate::prelude::*;
 use anyhow::Result;
 use clap::{ArgMatches, Args, Command, FromArgMatches};
@@ -53,6 +54,9 @@ pub struct CliArgs {
     /// successor lists.
     #[arg(long)]
     pub slice: bool,
+
+    #[clap(flatten)]
+    pub prefetch: PrefetchArg,
 }
 
 pub fn cli(command: Command) -> Command {
@@ -141,6 +145,7 @@ fn bench_seq(graph: impl SequentialGraph, repeats: usize) {
 fn bench_webgraph<E: Endianness, D: Dispatch>(args: CliArgs) -> Result<()>
 where
     for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+    for<'a> BufBitReader<E, PrefetchWordReader<'a>>: CodeRead<E>,
 {
     if args.check {
         let graph = BvGraph::with_basename(&args.src).endianness::<E>().load()?;
@@ -240,26 +245,26 @@ where
                 }
             }
             (None, true) => {
-                bench_seq(
-                    BvGraphSeq::with_basename(&args.src)
-                        .endianness::<E>()
-                        .dispatch::<Dynamic>()
-                        .mode::<Mmap>()
-                        .flags(MemoryFlags::TRANSPARENT_HUGE_PAGES | MemoryFlags::SEQUENTIAL)
-                        .load()?,
-                    args.repeats,
-                );
+                let config = BvGraphSeq::with_basename(&args.src)
+                    .endianness::<E>()
+                    .dispatch::<Dynamic>()
+                    .mode::<Mmap>()
+                    .flags(MemoryFlags::TRANSPARENT_HUGE_PAGES | MemoryFlags::SEQUENTIAL);
+                match args.prefetch.prefetch {
+                    Some(bytes) => bench_seq(config.prefetch(bytes).load()?, args.repeats),
+                    None => bench_seq(config.load()?, args.repeats),
+                }
             }
             (None, false) => {
-                bench_seq(
-                    BvGraphSeq::with_basename(&args.src)
-                        .endianness::<E>()
-                        .dispatch::<Static>()
-                        .mode::<Mmap>()
-                        .flags(MemoryFlags::TRANSPARENT_HUGE_PAGES | MemoryFlags::SEQUENTIAL)
-                        .load()?,
-                    args.repeats,
-                );
+                let config = BvGraphSeq::with_basename(&args.src)
+                    .endianness::<E>()
+                    .dispatch::<Static>()
+                    .mode::<Mmap>()
+                    .flags(MemoryFlags::TRANSPARENT_HUGE_PAGES | MemoryFlags::SEQUENTIAL);
+                match args.prefetch.prefetch {
+                    Some(bytes) => bench_seq(config.prefetch(bytes).load()?, args.repeats),
+                    None => bench_seq(config.load()?, args.repeats),
+                }
             }
         }
     }
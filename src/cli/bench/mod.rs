@@ -9,6 +9,8 @@ use clap::{ArgMatches, Command};
 
 pub mod bf_visit;
 pub mod bvgraph;
+pub mod offsets;
+pub mod sweep;
 
 pub const COMMAND_NAME: &str = "bench";
 
@@ -20,6 +22,8 @@ pub fn cli(command: Command) -> Command {
         .allow_external_subcommands(true);
     let sub_command = bvgraph::cli(sub_command);
     let sub_command = bf_visit::cli(sub_command);
+    let sub_command = offsets::cli(sub_command);
+    let sub_command = sweep::cli(sub_command);
     command.subcommand(sub_command.display_order(0))
 }
 
@@ -27,6 +31,8 @@ pub fn main(submatches: &ArgMatches) -> Result<()> {
     match submatches.subcommand() {
         Some((bf_visit::COMMAND_NAME, sub_m)) => bf_visit::main(sub_m),
         Some((bvgraph::COMMAND_NAME, sub_m)) => bvgraph::main(sub_m),
+        Some((offsets::COMMAND_NAME, sub_m)) => offsets::main(sub_m),
+        Some((sweep::COMMAND_NAME, sub_m)) => sweep::main(sub_m),
         Some((command_name, _)) => {
             eprintln!("Unknown command: {:?}", command_name);
             std::process::exit(1);
@@ -0,0 +1,111 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::graphs::bvgraph::match_select_params;
+use crate::prelude::*;
+use anyhow::{ensure, Result};
+use clap::{ArgMatches, Args, Command, FromArgMatches};
+use epserde::prelude::*;
+use rand::rngs::SmallRng;
+use rand::Rng;
+use rand::SeedableRng;
+use std::hint::black_box;
+use std::path::PathBuf;
+use sux::prelude::*;
+
+pub const COMMAND_NAME: &str = "offsets";
+
+#[derive(Args, Debug)]
+#[command(about = "Benchmarks random access to the Elias–Fano representation of the offsets of a graph (the .ef file) for each supported parameterization of its selection structure.", long_about = None)]
+pub struct CliArgs {
+    /// The basename of the graph.
+    pub src: PathBuf,
+
+    /// The number of random accesses per repeat.
+    #[arg(short, long, default_value_t = 10_000_000)]
+    pub samples: usize,
+
+    /// The number of repeats.
+    #[arg(short = 'R', long, default_value_t = 5)]
+    pub repeats: usize,
+
+    /// The parameters L,S to test (default: all supported parameters).
+    #[arg(short, long)]
+    pub params: Vec<SelectParams>,
+}
+
+pub fn cli(command: Command) -> Command {
+    command.subcommand(CliArgs::augment_args(Command::new(COMMAND_NAME)).display_order(0))
+}
+
+pub fn main(submatches: &ArgMatches) -> Result<()> {
+    bench_offsets(CliArgs::from_arg_matches(submatches)?)
+}
+
+fn bench_random(
+    params: SelectParams,
+    ef: &impl IndexedSeq<Input = usize, Output = usize>,
+    samples: usize,
+    repeats: usize,
+) {
+    let len = ef.len();
+    for _ in 0..repeats {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let start = std::time::Instant::now();
+        for _ in 0..samples {
+            black_box(ef.get(rng.gen_range(0..len)));
+        }
+        println!(
+            "{}:{:>20.3} ns/access",
+            params,
+            (start.elapsed().as_secs_f64() / samples as f64) * 1e9
+        );
+    }
+}
+
+pub fn bench_offsets(args: CliArgs) -> Result<()> {
+    let params = SelectParams::load(&args.src, EF_SELECT_PROPERTY)?;
+    let ef = File::load_offsets(
+        Basename::new(&args.src).with_ext(EF_EXTENSION),
+        params,
+        MemoryFlags::empty(),
+    )?;
+    let offsets = (&*ef).into_iter().collect::<Vec<_>>();
+    drop(ef);
+    ensure!(!offsets.is_empty(), "The .ef file is empty");
+    log::info!(
+        "Loaded {} offsets (select parameters {})",
+        offsets.len(),
+        params
+    );
+
+    let to_test = if args.params.is_empty() {
+        SelectParams::ALL.to_vec()
+    } else {
+        args.params
+    };
+
+    for params in to_test {
+        let mut efb = EliasFanoBuilder::new(offsets.len(), *offsets.last().unwrap());
+        for &offset in &offsets {
+            efb.push(offset);
+        }
+        let ef = efb.build();
+        match_select_params!(params, L, S => {
+            let ef: EFSelect<L, S> =
+                unsafe { ef.map_high_bits(SelectAdaptConst::<_, _, L, S>::new) };
+            let bytes = ef.serialize(&mut std::io::sink())?;
+            println!(
+                "{}: {:.3} bits/element",
+                params,
+                (bytes * 8) as f64 / offsets.len() as f64
+            );
+            bench_random(params, &ef, args.samples, args.repeats);
+        });
+    }
+
+    Ok(())
+}
@@ -0,0 +1,296 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::cli::PrivCode;
+use crate::prelude::*;
+use anyhow::{ensure, Result};
+use clap::{ArgMatches, Args, Command, FromArgMatches, ValueEnum};
+use dsi_bitstream::prelude::*;
+use dsi_progress_logger::prelude::*;
+use itertools::iproduct;
+use lender::*;
+use std::path::PathBuf;
+
+pub const COMMAND_NAME: &str = "sweep";
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+/// The format of the table of results.
+pub enum Format {
+    Csv,
+    Json,
+}
+
+#[derive(Args, Debug)]
+#[command(about = "Estimates the size of a BvGraph for each combination of a grid of compression parameters, printing on standard output a table of the resulting bits per arc and estimation times sorted by bits per arc.", long_about = None)]
+pub struct CliArgs {
+    /// The basename of the graph.
+    pub src: PathBuf,
+
+    /// The compression windows.
+    #[arg(short = 'w', long, value_delimiter = ',', default_value = "0,3,7,15")]
+    pub compression_windows: Vec<usize>,
+
+    /// The minimum interval lengths (0 for no intervals).
+    #[arg(short = 'i', long, value_delimiter = ',', default_value = "0,2,4")]
+    pub min_interval_lengths: Vec<usize>,
+
+    /// The maximum recursion depths for references (-1 for infinite
+    /// recursion depth).
+    #[arg(
+        short = 'r',
+        long,
+        value_delimiter = ',',
+        default_value = "3",
+        allow_negative_numbers = true
+    )]
+    pub max_ref_counts: Vec<isize>,
+
+    #[arg(value_enum)]
+    #[clap(long, value_delimiter = ',', default_value = "gamma")]
+    /// The codes to use for the outdegrees.
+    pub outdegrees: Vec<PrivCode>,
+
+    #[arg(value_enum)]
+    #[clap(long, value_delimiter = ',', default_value = "unary")]
+    /// The codes to use for the reference offsets.
+    pub references: Vec<PrivCode>,
+
+    #[arg(value_enum)]
+    #[clap(long, value_delimiter = ',', default_value = "gamma")]
+    /// The codes to use for the blocks.
+    pub blocks: Vec<PrivCode>,
+
+    #[arg(value_enum)]
+    #[clap(
+        long,
+        value_delimiter = ',',
+        default_value = "gamma,delta,zeta2,zeta3,zeta4,zeta5"
+    )]
+    /// The codes to use for the residuals.
+    pub residuals: Vec<PrivCode>,
+
+    /// Estimate on the first nodes of the graph only, which is much faster
+    /// on large graphs but less precise.
+    #[arg(short = 'n', long)]
+    pub max_nodes: Option<usize>,
+
+    /// The format of the table of results.
+    #[arg(short = 'f', long, value_enum, default_value_t = Format::Csv)]
+    pub format: Format,
+}
+
+pub fn cli(command: Command) -> Command {
+    command.subcommand(CliArgs::augment_args(Command::new(COMMAND_NAME)).display_order(0))
+}
+
+pub fn main(submatches: &ArgMatches) -> Result<()> {
+    let args = CliArgs::from_arg_matches(submatches)?;
+
+    match get_endianness(&args.src)?.as_str() {
+        #[cfg(any(
+            feature = "be_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        BE::NAME => sweep::<BE>(args),
+        #[cfg(any(
+            feature = "le_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        LE::NAME => sweep::<LE>(args),
+        e => panic!("Unknown endianness: {}", e),
+    }
+}
+
+/// A combination of compression parameters with its results.
+#[derive(Debug, Clone)]
+pub struct SweepResult {
+    pub compression_window: usize,
+    pub min_interval_length: usize,
+    pub max_ref_count: isize,
+    pub outdegrees: PrivCode,
+    pub references: PrivCode,
+    pub blocks: PrivCode,
+    pub residuals: PrivCode,
+    /// The estimated size of the graph bitstream in bits.
+    pub bits: u64,
+    /// The estimated number of bits per arc.
+    pub bits_per_arc: f64,
+    /// The estimation time in seconds.
+    pub seconds: f64,
+    /// Whether no other combination is at least as good in both bits per
+    /// arc and time, and strictly better in one of them.
+    pub pareto: bool,
+}
+
+impl SweepResult {
+    fn comp_flags(&self) -> CompFlags {
+        CompFlags {
+            outdegrees: self.outdegrees.into(),
+            references: self.references.into(),
+            blocks: self.blocks.into(),
+            intervals: PrivCode::Gamma.into(),
+            residuals: self.residuals.into(),
+            min_interval_length: self.min_interval_length,
+            compression_window: self.compression_window,
+            max_ref_count: match self.max_ref_count {
+                -1 => usize::MAX,
+                _ => self.max_ref_count as usize,
+            },
+            ..Default::default()
+        }
+    }
+}
+
+/// Returns the name of a code as accepted on the command line.
+fn code_name(code: PrivCode) -> String {
+    code.to_possible_value().unwrap().get_name().to_owned()
+}
+
+pub fn sweep<E: Endianness + 'static>(args: CliArgs) -> Result<()>
+where
+    for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+{
+    ensure!(
+        args.max_ref_counts.iter().all(|&r| r >= -1),
+        "Maximum recursion depths must be nonnegative or -1"
+    );
+    let graph = BvGraphSeq::with_basename(&args.src)
+        .endianness::<E>()
+        .load()?;
+    let num_nodes = args
+        .max_nodes
+        .map_or(graph.num_nodes(), |n| n.min(graph.num_nodes()));
+
+    let mut results = iproduct!(
+        args.compression_windows.iter().copied(),
+        args.min_interval_lengths.iter().copied(),
+        args.max_ref_counts.iter().copied(),
+        args.outdegrees.iter().copied(),
+        args.references.iter().copied(),
+        args.blocks.iter().copied(),
+        args.residuals.iter().copied()
+    )
+    .map(
+        |(
+            compression_window,
+            min_interval_length,
+            max_ref_count,
+            outdegrees,
+            references,
+            blocks,
+            residuals,
+        )| SweepResult {
+            compression_window,
+            min_interval_length,
+            max_ref_count,
+            outdegrees,
+            references,
+            blocks,
+            residuals,
+            bits: 0,
+            bits_per_arc: 0.0,
+            seconds: 0.0,
+            pareto: false,
+        },
+    )
+    .collect::<Vec<_>>();
+
+    let mut pl = ProgressLogger::default();
+    pl.display_memory(true)
+        .item_name("configuration")
+        .expected_updates(Some(results.len()));
+    pl.start(format!(
+        "Estimating {} configurations on {} nodes...",
+        results.len(),
+        num_nodes
+    ));
+
+    for result in results.iter_mut() {
+        let comp_flags = result.comp_flags();
+        let start = std::time::Instant::now();
+        let mut bvcomp = BvComp::new(
+            DynCodesEstimator::new(&comp_flags),
+            comp_flags.compression_window,
+            comp_flags.max_ref_count,
+            comp_flags.min_interval_length,
+            0,
+        )
+        .with_reference_selection(comp_flags.reference_selection);
+        result.bits = bvcomp.extend(graph.iter().take(num_nodes))?;
+        result.seconds = start.elapsed().as_secs_f64();
+        result.bits_per_arc = result.bits as f64 / bvcomp.arcs.max(1) as f64;
+        log::info!(
+            "{:?}: {:.3} bits/arc, {:.3} seconds",
+            comp_flags,
+            result.bits_per_arc,
+            result.seconds
+        );
+        pl.update();
+    }
+    pl.done();
+
+    mark_pareto(&mut results);
+    results.sort_by(|a, b| a.bits_per_arc.total_cmp(&b.bits_per_arc));
+
+    match args.format {
+        Format::Csv => {
+            println!("compression_window,min_interval_length,max_ref_count,outdegrees,references,blocks,residuals,bits,bits_per_arc,seconds,pareto");
+            for r in &results {
+                println!(
+                    "{},{},{},{},{},{},{},{},{:.6},{:.6},{}",
+                    r.compression_window,
+                    r.min_interval_length,
+                    r.max_ref_count,
+                    code_name(r.outdegrees),
+                    code_name(r.references),
+                    code_name(r.blocks),
+                    code_name(r.residuals),
+                    r.bits,
+                    r.bits_per_arc,
+                    r.seconds,
+                    r.pareto
+                );
+            }
+        }
+        Format::Json => {
+            println!("[");
+            for (i, r) in results.iter().enumerate() {
+                println!(
+                    "  {{\"compression_window\": {}, \"min_interval_length\": {}, \"max_ref_count\": {}, \"outdegrees\": \"{}\", \"references\": \"{}\", \"blocks\": \"{}\", \"residuals\": \"{}\", \"bits\": {}, \"bits_per_arc\": {:.6}, \"seconds\": {:.6}, \"pareto\": {}}}{}",
+                    r.compression_window,
+                    r.min_interval_length,
+                    r.max_ref_count,
+                    code_name(r.outdegrees),
+                    code_name(r.references),
+                    code_name(r.blocks),
+                    code_name(r.residuals),
+                    r.bits,
+                    r.bits_per_arc,
+                    r.seconds,
+                    r.pareto,
+                    if i + 1 < results.len() { "," } else { "" }
+                );
+            }
+            println!("]");
+        }
+    }
+
+    Ok(())
+}
+
+/// Marks the results that are Pareto optimal with respect to bits per arc
+/// and time.
+fn mark_pareto(results: &mut [SweepResult]) {
+    let points = results
+        .iter()
+        .map(|r| (r.bits_per_arc, r.seconds))
+        .collect::<Vec<_>>();
+    for (result, &(bits, secs)) in results.iter_mut().zip(&points) {
+        result.pareto = !points
+            .iter()
+            .any(|&(b, s)| b <= bits && s <= secs && (b < bits || s < secs));
+    }
+}
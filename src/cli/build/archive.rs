@@ -0,0 +1,38 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::cli::create_parent_dir;
+use crate::graphs::bvgraph::{write_archive, ARCHIVE_EXTENSION};
+use crate::utils::Basename;
+use anyhow::Result;
+use clap::{ArgMatches, Args, Command, FromArgMatches};
+use log::info;
+use std::path::PathBuf;
+
+pub const COMMAND_NAME: &str = "archive";
+
+#[derive(Args, Debug)]
+#[command(about = "Packs a graph and its .properties, .offsets and .ef files in a single archive file, which can be loaded with BvGraph::with_archive.", long_about = None)]
+pub struct CliArgs {
+    /// The basename of the graph.
+    pub src: PathBuf,
+    /// The archive file (default: the basename with extension .bvar).
+    pub dst: Option<PathBuf>,
+}
+
+pub fn cli(command: Command) -> Command {
+    command.subcommand(CliArgs::augment_args(Command::new(COMMAND_NAME)).display_order(0))
+}
+
+pub fn main(submatches: &ArgMatches) -> Result<()> {
+    let args = CliArgs::from_arg_matches(submatches)?;
+    let dst = args
+        .dst
+        .unwrap_or_else(|| Basename::new(&args.src).with_ext(ARCHIVE_EXTENSION));
+    create_parent_dir(&dst)?;
+    info!("Archiving {} in {}", args.src.display(), dst.display());
+    write_archive(&args.src, &dst)
+}
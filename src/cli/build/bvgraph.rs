@@ -0,0 +1,44 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::cli::from::arcs;
+use anyhow::{Context, Result};
+use clap::{ArgMatches, Args, Command, FromArgMatches};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::PathBuf;
+
+pub const COMMAND_NAME: &str = "bvgraph";
+
+#[derive(Args, Debug)]
+// The flattened arguments of from arcs already use the default group name
+#[group(skip)]
+pub struct CliArgs {
+    /// The file containing the list of arcs (e.g., a CSV file or an edge
+    /// list).
+    pub src: PathBuf,
+
+    #[clap(flatten)]
+    pub arcs: arcs::CliArgs,
+}
+
+pub fn cli(command: Command) -> Command {
+    // The description is set after the arguments, as the flattened arguments
+    // of from arcs carry their own
+    command.subcommand(
+        CliArgs::augment_args(Command::new(COMMAND_NAME))
+            .about("Compresses a list of arcs read from a file into a BvGraph in a single pass: arcs are sorted in batches on disk and compressed in parallel directly from the sorted batches, without building an intermediate graph. The options are the same of from arcs, which reads the arcs from standard input.")
+            .long_about(None)
+            .display_order(0),
+    )
+}
+
+pub fn main(submatches: &ArgMatches) -> Result<()> {
+    let args = CliArgs::from_arg_matches(submatches)?;
+    let file =
+        File::open(&args.src).with_context(|| format!("Could not open {}", args.src.display()))?;
+    arcs::from_reader(args.arcs, BufReader::new(file))
+}
@@ -5,25 +5,30 @@
  * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
  */
 
+use crate::cli::OutputFile;
 use crate::prelude::*;
-use anyhow::{Context, Result};
+use anyhow::{ensure, Context, Result};
 use clap::{ArgMatches, Args, Command, FromArgMatches};
 use dsi_bitstream::prelude::*;
 use dsi_progress_logger::prelude::*;
-use epserde::prelude::*;
 use log::info;
 use std::fs::File;
-use std::io::{BufReader, BufWriter};
-use std::path::PathBuf;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
 use sux::prelude::*;
 
 pub const COMMAND_NAME: &str = "dcf";
 
 #[derive(Args, Debug)]
-#[command(about = "Builds the Elias–Fano representation of the degree cumulative function of a graph.", long_about = None)]
+#[command(about = "Builds the Elias–Fano representation of the degree cumulative function of a graph, using the outdegrees in BASENAME.degrees, if present.", long_about = None)]
 pub struct CliArgs {
     /// The basename of the graph.
     pub src: PathBuf,
+
+    #[arg(long, default_value_t = SelectParams::default())]
+    /// The parameters L,S of the selection structures (see the documentation
+    /// of SelectParams for the supported values).
+    pub select_params: SelectParams,
 }
 
 pub fn cli(command: Command) -> Command {
@@ -52,8 +57,8 @@ pub fn build_dcf<E: Endianness + 'static>(args: CliArgs) -> Result<()>
 where
     for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
 {
-    let basename = args.src;
-    let properties_path = basename.with_extension(PROPERTIES_EXTENSION);
+    let basename = Basename::new(args.src);
+    let properties_path = basename.with_ext(PROPERTIES_EXTENSION);
     let f = File::open(&properties_path).with_context(|| {
         format!(
             "Could not open properties file: {}",
@@ -64,57 +69,99 @@ where
     let num_nodes = map.get("nodes").unwrap().parse::<usize>()?;
     let num_arcs = map.get("arcs").unwrap().parse::<usize>()?;
 
-    // TODO : not +1
-    let mut efb = EliasFanoBuilder::new(num_nodes + 1, num_arcs + 1);
-
-    let ef_path = basename.with_extension(DEG_CUMUL_EXTENSION);
-    let mut ef_file = BufWriter::new(
-        File::create(&ef_path)
-            .with_context(|| format!("Could not create {}", ef_path.display()))?,
-    );
-
     let mut pl = ProgressLogger::default();
     pl.display_memory(true)
         .item_name("offset")
         .expected_updates(Some(num_nodes));
-    let seq_graph = crate::graphs::bvgraph::sequential::BvGraphSeq::with_basename(&basename)
-        .endianness::<E>()
-        .load()
-        .with_context(|| format!("Could not load graph at {}", basename.display()))?;
-    // otherwise directly read the graph
-    // progress bar
-    pl.start("Building the degree cumulative function...");
-    // read the graph a write the offsets
-    let mut iter = seq_graph.offset_deg_iter();
-    let mut cumul_deg = 0;
 
+    let degrees_path = basename.with_ext(DEGREES_EXTENSION);
+    if degrees_path.exists() {
+        // use the precomputed outdegrees to avoid scanning the graph
+        let degrees = load_degrees(&basename)?;
+        ensure!(
+            degrees.len() == num_nodes,
+            "The outdegrees in {} are {}, but the graph has {} nodes",
+            degrees_path.display(),
+            degrees.len(),
+            num_nodes
+        );
+        pl.start(format!(
+            "Building the degree cumulative function from {}...",
+            degrees_path.display()
+        ));
+        store_dcf(
+            &basename,
+            num_nodes,
+            num_arcs,
+            (0..num_nodes).map(|node| degrees.get(node)),
+            args.select_params,
+            &mut pl,
+        )?;
+    } else {
+        let seq_graph = crate::graphs::bvgraph::sequential::BvGraphSeq::with_basename(&basename)
+            .endianness::<E>()
+            .load()
+            .with_context(|| format!("Could not load graph at {}", basename.display()))?;
+        // otherwise directly read the graph
+        pl.start("Building the degree cumulative function...");
+        store_dcf(
+            &basename,
+            num_nodes,
+            num_arcs,
+            seq_graph.offset_deg_iter().map(|(_offset, degree)| degree),
+            args.select_params,
+            &mut pl,
+        )?;
+    }
+
+    info!("Completed.");
+
+    Ok(())
+}
+
+/// Builds the Elias–Fano representation of the degree cumulative function of
+/// the graph with given basename from its outdegrees, and stores it, together
+/// with its selection parameters.
+///
+/// This function is used by `to bvgraph --build-dcf` to build the degree
+/// cumulative function from the outdegrees recorded during compression; the
+/// result is identical to that of `build dcf`.
+pub fn store_dcf(
+    basename: impl AsRef<Path>,
+    num_nodes: usize,
+    num_arcs: usize,
+    degrees: impl IntoIterator<Item = usize>,
+    select_params: SelectParams,
+    pl: &mut impl ProgressLog,
+) -> Result<()> {
+    let basename = Basename::new(basename);
+    // TODO : not +1
+    let mut efb = EliasFanoBuilder::new(num_nodes + 1, num_arcs + 1);
+
+    let ef_path = basename.with_ext(DEG_CUMUL_EXTENSION);
+    let mut ef_file = OutputFile::create(&ef_path)?;
+
+    let mut cumul_deg = 0;
     efb.push(0);
-    for (_new_offset, degree) in iter.by_ref() {
+    for degree in degrees {
         cumul_deg += degree;
-        // write where
         efb.push(cumul_deg as _);
-        // decode the next nodes so we know where the next node_id starts
         pl.light_update();
     }
     pl.done();
 
     let ef = efb.build();
-    let ef: DCF = unsafe {
-        ef.map_high_bits(|bits| {
-            SelectZeroAdaptConst::<_, _, 12, 4>::new(SelectAdaptConst::<_, _, 12, 4>::new(bits))
-        })
-    };
-
-    info!("Writing to disk...");
 
-    ef.serialize(&mut ef_file).with_context(|| {
-        format!(
-            "Could not serialize degree cumulative list to {}",
-            ef_path.display()
-        )
-    })?;
-
-    info!("Completed.");
-
-    Ok(())
+    info!("Building the selection structures and writing to disk...");
+
+    select_params
+        .serialize_dcf(ef, &mut ef_file)
+        .with_context(|| {
+            format!(
+                "Could not serialize degree cumulative list to {}",
+                ef_path.display()
+            )
+        })?;
+    ef_file.finish()?;
+    select_params.store(&basename, DCF_SELECT_PROPERTY)
 }
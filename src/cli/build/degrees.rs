@@ -0,0 +1,72 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::cli::*;
+use crate::prelude::*;
+use anyhow::Result;
+use clap::{ArgMatches, Args, Command, FromArgMatches};
+use dsi_bitstream::prelude::*;
+use std::path::PathBuf;
+
+pub const COMMAND_NAME: &str = "degrees";
+
+#[derive(Args, Debug)]
+#[command(about = "Builds the outdegrees of a graph in a bit-field vector of minimal bit width and stores it with ε-serde in BASENAME.degrees; the file is used by build dcf, if present, to avoid scanning the graph.", long_about = None)]
+pub struct CliArgs {
+    /// The basename of the graph.
+    pub src: PathBuf,
+
+    #[arg(long)]
+    /// An upper bound on the maximum outdegree; if missing, the maximum
+    /// outdegree is computed by a preliminary pass.
+    pub max_degree: Option<usize>,
+
+    #[arg(long, default_value_t = 1 << 16)]
+    /// The number of nodes scanned by each parallel task.
+    pub granularity: usize,
+
+    #[clap(flatten)]
+    pub num_threads: NumThreadsArg,
+}
+
+pub fn cli(command: Command) -> Command {
+    command.subcommand(CliArgs::augment_args(Command::new(COMMAND_NAME)).display_order(0))
+}
+
+pub fn main(submatches: &ArgMatches) -> Result<()> {
+    let args = CliArgs::from_arg_matches(submatches)?;
+
+    match get_endianness(&args.src)?.as_str() {
+        #[cfg(any(
+            feature = "be_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        BE::NAME => build_degrees::<BE>(args),
+        #[cfg(any(
+            feature = "le_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        LE::NAME => build_degrees::<LE>(args),
+        e => panic!("Unknown endianness: {}", e),
+    }
+}
+
+pub fn build_degrees<E: Endianness + Send + Sync + 'static>(args: CliArgs) -> Result<()>
+where
+    for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+{
+    let graph = BvGraph::with_basename(&args.src)
+        .endianness::<E>()
+        .mode::<Mmap>()
+        .flags(MemoryFlags::RANDOM_ACCESS)
+        .load()?;
+    let thread_pool = get_thread_pool(args.num_threads.num_threads);
+
+    log::info!("Computing outdegrees...");
+    let degrees = outdegrees_bitfield(&graph, args.max_degree, args.granularity, &thread_pool);
+    log::info!("Outdegrees have bit width {}", degrees.bit_width());
+    store_degrees(&args.src, &degrees)
+}
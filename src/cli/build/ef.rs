@@ -5,16 +5,16 @@
  * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
  */
 
+use crate::cli::OutputFile;
 use crate::prelude::*;
-use anyhow::{Context, Result};
+use anyhow::{ensure, Context, Result};
 use clap::{ArgMatches, Args, Command, FromArgMatches};
 use dsi_bitstream::prelude::*;
 use dsi_progress_logger::prelude::*;
-use epserde::prelude::*;
 use log::info;
 use std::fs::File;
-use std::io::{BufReader, BufWriter, Seek};
-use std::path::PathBuf;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
 use sux::prelude::*;
 
 pub const COMMAND_NAME: &str = "ef";
@@ -28,6 +28,11 @@ pub struct CliArgs {
     /// starting from a label offset file. It is usually one more than
     /// the number of nodes in the graph.
     pub n: Option<usize>,
+
+    #[arg(long, default_value_t = SelectParams::default())]
+    /// The parameters L,S of the selection structure (see the documentation of
+    /// SelectParams for the supported values).
+    pub select_params: SelectParams,
 }
 
 pub fn cli(command: Command) -> Command {
@@ -56,68 +61,21 @@ pub fn build_eliasfano<E: Endianness + 'static>(args: CliArgs) -> Result<()>
 where
     for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
 {
-    let basename = args.src;
+    let basename = Basename::new(args.src);
     if let Some(num_nodes) = args.n {
-        // Horribly temporary duplicated code for the case of label offsets.
-        let of_file_path = basename.with_extension(LABELOFFSETS_EXTENSION);
-        if of_file_path.exists() {
-            let labels_path = basename.with_extension(LABELS_EXTENSION);
-            let mut file = File::open(&labels_path)
-                .with_context(|| format!("Could not open {}", labels_path.display()))?;
-            let file_len = 8 * file
-                .seek(std::io::SeekFrom::End(0))
-                .with_context(|| format!("Could not seek to end of {}", labels_path.display()))?;
-
-            let mut efb = EliasFanoBuilder::new(num_nodes, file_len as usize);
-
+        if basename.with_ext(LABELOFFSETS_EXTENSION).exists() {
             info!("The offsets file exists, reading it to build Elias-Fano");
-            let of_file = BufReader::with_capacity(
-                1 << 20,
-                File::open(&of_file_path)
-                    .with_context(|| format!("Could not open {}", of_file_path.display()))?,
-            );
-            // create a bit reader on the file
-            let mut reader = BufBitReader::<BE, _>::new(<WordAdapter<u32, _>>::new(of_file));
-            // progress bar
-            let mut pl = ProgressLogger::default();
-            pl.display_memory(true)
-                .item_name("offset")
-                .expected_updates(Some(num_nodes));
-            pl.start("Translating offsets to EliasFano...");
-            // read the graph a write the offsets
-            let mut offset = 0;
-            for _node_id in 0..num_nodes {
-                // write where
-                offset += reader.read_gamma().context("Could not read gamma")?;
-                efb.push(offset as _);
-                // decode the next nodes so we know where the next node_id starts
-                pl.light_update();
-            }
-            let ef = efb.build();
-
-            let mut pl = ProgressLogger::default();
-            pl.display_memory(true);
-            pl.start("Building the Index over the ones in the high-bits...");
-            let ef: EF = unsafe { ef.map_high_bits(SelectAdaptConst::<_, _, 12, 4>::new) };
-            pl.done();
-
-            let mut pl = ProgressLogger::default();
-            pl.display_memory(true);
-            pl.start("Writing to disk...");
-            // serialize and dump the schema to disk
-            let ef_path = basename.with_extension(EF_EXTENSION);
-            let mut ef_file = BufWriter::new(
-                File::create(&ef_path)
-                    .with_context(|| format!("Could not create {}", ef_path.display()))?,
+            return ef_from_offsets_file(
+                &basename,
+                LABELOFFSETS_EXTENSION,
+                LABELS_EXTENSION,
+                num_nodes,
+                args.select_params,
             );
-            ef.serialize(&mut ef_file)
-                .with_context(|| format!("Could not serialize EF to {}", ef_path.display()))?;
-            pl.done();
-            return Ok(());
         }
     }
 
-    let properties_path = basename.with_extension(PROPERTIES_EXTENSION);
+    let properties_path = basename.with_ext(PROPERTIES_EXTENSION);
     let f = File::open(&properties_path).with_context(|| {
         format!(
             "Could not open properties file: {}",
@@ -127,86 +85,155 @@ where
     let map = java_properties::read(BufReader::new(f))?;
     let num_nodes = map.get("nodes").unwrap().parse::<usize>()?;
 
-    let graph_path = basename.with_extension(GRAPH_EXTENSION);
-    let mut file = File::open(&graph_path)
-        .with_context(|| format!("Could not open {}", graph_path.display()))?;
-    let file_len = 8 * file
-        .seek(std::io::SeekFrom::End(0))
-        .with_context(|| format!("Could not seek in {}", graph_path.display()))?;
+    // if the offset files exists, read it to build elias-fano
+    if basename.with_ext(OFFSETS_EXTENSION).exists() {
+        info!("The offsets file exists, reading it to build Elias-Fano");
+        return ef_from_offsets_file(
+            &basename,
+            OFFSETS_EXTENSION,
+            GRAPH_EXTENSION,
+            num_nodes + 1,
+            args.select_params,
+        );
+    }
+
+    let graph_path = basename.with_ext(GRAPH_EXTENSION);
+    let file_len = 8 * std::fs::metadata(&graph_path)
+        .with_context(|| format!("Could not read metadata of {}", graph_path.display()))?
+        .len();
 
     let mut efb = EliasFanoBuilder::new(num_nodes + 1, file_len as usize);
 
-    let ef_path = basename.with_extension(EF_EXTENSION);
-    let mut ef_file = BufWriter::new(
-        File::create(&ef_path)
-            .with_context(|| format!("Could not create {}", ef_path.display()))?,
+    let mut pl = ProgressLogger::default();
+    pl.display_memory(true)
+        .item_name("offset")
+        .expected_updates(Some(num_nodes));
+
+    info!("The offsets file does not exists, reading the graph to build Elias-Fano");
+    let seq_graph = crate::graphs::bvgraph::sequential::BvGraphSeq::with_basename(&basename)
+        .endianness::<E>()
+        .load()
+        .with_context(|| format!("Could not load graph at {}", basename.display()))?;
+    // otherwise directly read the graph
+    // progress bar
+    pl.start("Building EliasFano...");
+    // read the graph a write the offsets
+    let mut iter = seq_graph.offset_deg_iter();
+    for (new_offset, _degree) in iter.by_ref() {
+        // write where
+        efb.push(new_offset as _);
+        // decode the next nodes so we know where the next node_id starts
+        pl.light_update();
+    }
+    efb.push(iter.get_pos() as _);
+    pl.done();
+
+    store_ef(efb.build(), &basename, args.select_params)
+}
+
+/// Builds the `.ef` file of a graph from a file of offsets.
+///
+/// The file with extension `offsets_ext` must contain `n` γ-coded gaps,
+/// which are translated into an Elias-Fano representation whose upper
+/// bound is the length in bits of the file with extension `data_ext`; an
+/// error is returned if an offset exceeds the upper bound. The parameters
+/// of the selection structure are recorded in the `.properties` file.
+pub fn ef_from_offsets_file(
+    basename: &Basename,
+    offsets_ext: &str,
+    data_ext: &str,
+    n: usize,
+    params: SelectParams,
+) -> Result<()> {
+    let data_path = basename.with_ext(data_ext);
+    let file_len = 8 * std::fs::metadata(&data_path)
+        .with_context(|| format!("Could not read metadata of {}", data_path.display()))?
+        .len();
+
+    let of_file_path = basename.with_ext(offsets_ext);
+    let of_file = BufReader::with_capacity(
+        1 << 20,
+        File::open(&of_file_path)
+            .with_context(|| format!("Could not open {}", of_file_path.display()))?,
     );
+    // create a bit reader on the file
+    let mut reader = BufBitReader::<BE, _>::new(<WordAdapter<u32, _>>::new(of_file));
 
-    // Create the offsets file
-    let of_file_path = basename.with_extension(OFFSETS_EXTENSION);
+    let mut efb = EliasFanoBuilder::new(n, file_len as usize);
 
     let mut pl = ProgressLogger::default();
     pl.display_memory(true)
         .item_name("offset")
-        .expected_updates(Some(num_nodes));
-
-    // if the offset files exists, read it to build elias-fano
-    if of_file_path.exists() {
-        info!("The offsets file exists, reading it to build Elias-Fano");
-        let of_file = BufReader::with_capacity(
-            1 << 20,
-            File::open(&of_file_path)
-                .with_context(|| format!("Could not open {}", of_file_path.display()))?,
+        .expected_updates(Some(n));
+    pl.start("Translating offsets to EliasFano...");
+    let mut offset = 0;
+    for i in 0..n {
+        offset += reader
+            .read_gamma()
+            .with_context(|| format!("Could not read offset {}", i))?;
+        ensure!(
+            offset <= file_len,
+            "Offset {} at index {} is beyond the end of {} ({} bits)",
+            offset,
+            i,
+            data_path.display(),
+            file_len
         );
-        // create a bit reader on the file
-        let mut reader = BufBitReader::<BE, _>::new(<WordAdapter<u32, _>>::new(of_file));
-        // progress bar
-        pl.start("Translating offsets to EliasFano...");
-        // read the graph a write the offsets
-        let mut offset = 0;
-        for _node_id in 0..num_nodes + 1 {
-            // write where
-            offset += reader.read_gamma().context("Could not read gamma")?;
-            efb.push(offset as _);
-            // decode the next nodes so we know where the next node_id starts
-            pl.light_update();
-        }
-    } else {
-        info!("The offsets file does not exists, reading the graph to build Elias-Fano");
-        let seq_graph = crate::graphs::bvgraph::sequential::BvGraphSeq::with_basename(&basename)
-            .endianness::<E>()
-            .load()
-            .with_context(|| format!("Could not load graph at {}", basename.display()))?;
-        // otherwise directly read the graph
-        // progress bar
-        pl.start("Building EliasFano...");
-        // read the graph a write the offsets
-        let mut iter = seq_graph.offset_deg_iter();
-        for (new_offset, _degree) in iter.by_ref() {
-            // write where
-            efb.push(new_offset as _);
-            // decode the next nodes so we know where the next node_id starts
-            pl.light_update();
-        }
-        efb.push(iter.get_pos() as _);
+        efb.push(offset as _);
+        pl.light_update();
     }
     pl.done();
 
-    let ef = efb.build();
+    store_ef(efb.build(), basename, params)
+}
+
+/// Builds the selection structure of an Elias-Fano representation of the
+/// offsets, serializes it to the `.ef` file, and records its parameters in
+/// the `.properties` file.
+/// Builds the Elias–Fano representation of the offsets of the graph with
+/// given basename from the lengths in bits of its successor lists, and stores
+/// it, together with its selection parameters.
+///
+/// This function is used by `to bvgraph --build-ef` to build the offsets from
+/// the lengths recorded during compression; the result is identical to that of
+/// `build ef`.
+pub fn store_offsets_ef(
+    basename: impl AsRef<Path>,
+    num_nodes: usize,
+    node_bits: impl IntoIterator<Item = u64>,
+    select_params: SelectParams,
+    pl: &mut impl ProgressLog,
+) -> Result<()> {
+    let basename = Basename::new(basename);
+    let graph_path = basename.with_ext(GRAPH_EXTENSION);
+    let file_len = 8 * std::fs::metadata(&graph_path)
+        .with_context(|| format!("Could not read metadata of {}", graph_path.display()))?
+        .len();
 
-    let mut pl = ProgressLogger::default();
-    pl.display_memory(true);
-    pl.start("Building the Index over the ones in the high-bits...");
-    let ef: EF = unsafe { ef.map_high_bits(SelectAdaptConst::<_, _, 12, 4>::new) };
+    let mut efb = EliasFanoBuilder::new(num_nodes + 1, file_len as usize);
+    let mut offset = 0;
+    efb.push(0);
+    for bits in node_bits {
+        offset += bits;
+        efb.push(offset as _);
+        pl.light_update();
+    }
     pl.done();
 
+    store_ef(efb.build(), &basename, select_params)
+}
+
+fn store_ef(ef: EliasFano, basename: &Basename, params: SelectParams) -> Result<()> {
     let mut pl = ProgressLogger::default();
     pl.display_memory(true);
-    pl.start("Writing to disk...");
+    pl.start("Building the index over the ones in the high-bits and writing to disk...");
     // serialize and dump the schema to disk
-    ef.serialize(&mut ef_file)
+    let ef_path = basename.with_ext(EF_EXTENSION);
+    let mut ef_file = OutputFile::create(&ef_path)?;
+    params
+        .serialize_ef(ef, &mut ef_file)
         .with_context(|| format!("Could not serialize EliasFano to {}", ef_path.display()))?;
-
+    ef_file.finish()?;
     pl.done();
-    Ok(())
+    params.store(basename, EF_SELECT_PROPERTY)
 }
@@ -0,0 +1,76 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use super::ef::ef_from_offsets_file;
+use crate::prelude::*;
+use anyhow::{Context, Result};
+use clap::{ArgMatches, Args, Command, FromArgMatches};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+pub const COMMAND_NAME: &str = "ef-from-offsets";
+
+#[derive(Args, Debug)]
+#[command(about = "Builds the Elias-Fano representation of the offsets of a graph using only the .offsets file, without reading the graph.", long_about = None)]
+pub struct CliArgs {
+    /// The basename of the graph.
+    pub src: PathBuf,
+
+    #[arg(long, default_value_t = SelectParams::default())]
+    /// The parameters L,S of the selection structure (see the documentation of
+    /// SelectParams for the supported values).
+    pub select_params: SelectParams,
+}
+
+pub fn cli(command: Command) -> Command {
+    command.subcommand(CliArgs::augment_args(Command::new(COMMAND_NAME)).display_order(0))
+}
+
+pub fn main(submatches: &ArgMatches) -> Result<()> {
+    let args = CliArgs::from_arg_matches(submatches)?;
+    ef_from_offsets_with_params(args.src, args.select_params)
+}
+
+/// Builds the `.ef` file of a graph from its `.offsets` file.
+///
+/// The number of nodes is read from the `.properties` file, and the `.graph`
+/// file is never opened: its length, which is the upper bound of the
+/// Elias-Fano representation, is obtained from its metadata. Since offsets
+/// are always big endian, the endianness of the graph is irrelevant.
+///
+/// The selection structure uses the default [parameters](SelectParams).
+pub fn ef_from_offsets(basename: impl AsRef<Path>) -> Result<()> {
+    ef_from_offsets_with_params(basename, SelectParams::default())
+}
+
+/// Builds the `.ef` file of a graph from its `.offsets` file using the given
+/// [parameters](SelectParams) for the selection structure, which are
+/// recorded in the `.properties` file.
+pub fn ef_from_offsets_with_params(basename: impl AsRef<Path>, params: SelectParams) -> Result<()> {
+    let basename = Basename::new(basename);
+
+    let properties_path = basename.with_ext(PROPERTIES_EXTENSION);
+    let f = File::open(&properties_path).with_context(|| {
+        format!(
+            "Could not open properties file: {}",
+            properties_path.display()
+        )
+    })?;
+    let map = java_properties::read(BufReader::new(f))?;
+    let num_nodes = map
+        .get("nodes")
+        .with_context(|| format!("Missing 'nodes' property in {}", properties_path.display()))?
+        .parse::<usize>()?;
+
+    ef_from_offsets_file(
+        &basename,
+        OFFSETS_EXTENSION,
+        GRAPH_EXTENSION,
+        num_nodes + 1,
+        params,
+    )
+}
@@ -0,0 +1,108 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::cli::OutputFile;
+use crate::prelude::*;
+use anyhow::{anyhow, Context, Result};
+use clap::{ArgMatches, Args, Command, FromArgMatches};
+use dsi_bitstream::prelude::*;
+use dsi_progress_logger::prelude::*;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::PathBuf;
+use sux::prelude::*;
+
+pub const COMMAND_NAME: &str = "label-ef";
+
+#[derive(Args, Debug)]
+#[command(about = "Builds the Elias-Fano representation of the offsets of a labeling from its .labeloffsets file, saving it in a .labelef file that provides random access to labels.", long_about = None)]
+pub struct CliArgs {
+    /// The basename of the labeling.
+    pub src: PathBuf,
+
+    #[arg(short, long)]
+    /// The number of nodes; if missing, it is read from the properties file
+    /// with the same basename.
+    pub num_nodes: Option<usize>,
+}
+
+pub fn cli(command: Command) -> Command {
+    command.subcommand(CliArgs::augment_args(Command::new(COMMAND_NAME)).display_order(0))
+}
+
+pub fn main(submatches: &ArgMatches) -> Result<()> {
+    build_label_ef(CliArgs::from_arg_matches(submatches)?)
+}
+
+pub fn build_label_ef(args: CliArgs) -> Result<()> {
+    let basename = Basename::new(args.src);
+    let num_nodes = match args.num_nodes {
+        Some(num_nodes) => num_nodes,
+        None => {
+            let properties_path = basename.with_ext(PROPERTIES_EXTENSION);
+            let f = File::open(&properties_path).with_context(|| {
+                format!(
+                    "Could not open properties file {} (use --num-nodes to specify the number of nodes)",
+                    properties_path.display()
+                )
+            })?;
+            let map = java_properties::read(BufReader::new(f))?;
+            map.get("nodes")
+                .ok_or_else(|| {
+                    anyhow!("Missing 'nodes' property in {}", properties_path.display())
+                })?
+                .parse::<usize>()?
+        }
+    };
+
+    let labels_path = basename.with_ext(LABELS_EXTENSION);
+    let labels_len = 8 * labels_path
+        .metadata()
+        .with_context(|| format!("Could not stat {}", labels_path.display()))?
+        .len();
+
+    let of_file_path = basename.with_ext(LABELOFFSETS_EXTENSION);
+    let of_file = BufReader::with_capacity(
+        1 << 20,
+        File::open(&of_file_path)
+            .with_context(|| format!("Could not open {}", of_file_path.display()))?,
+    );
+    let mut reader = BufBitReader::<BE, _>::new(<WordAdapter<u32, _>>::new(of_file));
+
+    let mut efb = EliasFanoBuilder::new(num_nodes + 1, labels_len as usize);
+    let mut pl = ProgressLogger::default();
+    pl.display_memory(true)
+        .item_name("offset")
+        .expected_updates(Some(num_nodes + 1));
+    pl.start("Translating label offsets to Elias-Fano...");
+    let mut offset = 0;
+    for _ in 0..num_nodes + 1 {
+        offset += reader.read_gamma().with_context(|| {
+            format!(
+                "Could not read label offsets from {}",
+                of_file_path.display()
+            )
+        })?;
+        efb.push(offset as _);
+        pl.light_update();
+    }
+    pl.done();
+    let ef = efb.build();
+
+    let mut pl = ProgressLogger::default();
+    pl.display_memory(true);
+    pl.start("Building the index over the ones in the high-bits and writing to disk...");
+    let ef_path = basename.with_ext(LABELEF_EXTENSION);
+    let mut ef_file = OutputFile::create(&ef_path)?;
+    // The offsets are loaded as an EF, so we use the default parameters
+    SelectParams::default()
+        .serialize_ef(ef, &mut ef_file)
+        .with_context(|| format!("Could not serialize Elias-Fano to {}", ef_path.display()))?;
+    ef_file.finish()?;
+    pl.done();
+
+    Ok(())
+}
@@ -8,8 +8,14 @@ use anyhow::Result;
 use clap::{value_parser, ArgMatches, Command};
 use clap_complete::shells::Shell;
 
+pub mod archive;
+pub mod bvgraph;
 pub mod dcf;
+pub mod degrees;
 pub mod ef;
+pub mod ef_from_offsets;
+pub mod label_ef;
+pub mod node_mask;
 pub mod offsets;
 
 pub const COMMAND_NAME: &str = "build";
@@ -30,8 +36,14 @@ pub fn cli(command: Command) -> Command {
                         .value_parser(value_parser!(Shell)),
                 ),
         );
+    let sub_command = archive::cli(sub_command);
+    let sub_command = bvgraph::cli(sub_command);
     let sub_command = dcf::cli(sub_command);
+    let sub_command = degrees::cli(sub_command);
     let sub_command = ef::cli(sub_command);
+    let sub_command = ef_from_offsets::cli(sub_command);
+    let sub_command = label_ef::cli(sub_command);
+    let sub_command = node_mask::cli(sub_command);
     let sub_command = offsets::cli(sub_command);
     command.subcommand(sub_command.display_order(0))
 }
@@ -43,8 +55,14 @@ pub fn main(submatches: &ArgMatches, top_command: &mut Command) -> Result<()> {
             clap_complete::generate(*shell, top_command, "webgraph", &mut std::io::stdout());
             Ok(())
         }
+        Some((archive::COMMAND_NAME, sub_m)) => archive::main(sub_m),
+        Some((bvgraph::COMMAND_NAME, sub_m)) => bvgraph::main(sub_m),
         Some((dcf::COMMAND_NAME, sub_m)) => dcf::main(sub_m),
+        Some((degrees::COMMAND_NAME, sub_m)) => degrees::main(sub_m),
         Some((ef::COMMAND_NAME, sub_m)) => ef::main(sub_m),
+        Some((ef_from_offsets::COMMAND_NAME, sub_m)) => ef_from_offsets::main(sub_m),
+        Some((label_ef::COMMAND_NAME, sub_m)) => label_ef::main(sub_m),
+        Some((node_mask::COMMAND_NAME, sub_m)) => node_mask::main(sub_m),
         Some((offsets::COMMAND_NAME, sub_m)) => offsets::main(sub_m),
         Some((command_name, _)) => {
             eprintln!("Unknown command: {:?}", command_name);
@@ -0,0 +1,73 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::prelude::*;
+use anyhow::Result;
+use clap::{ArgMatches, Args, Command, FromArgMatches};
+use dsi_bitstream::prelude::*;
+use dsi_progress_logger::prelude::*;
+use lender::*;
+use std::path::PathBuf;
+use sux::prelude::*;
+
+pub const COMMAND_NAME: &str = "node-mask";
+
+#[derive(Args, Debug)]
+#[command(about = "Builds the mask of present nodes of a graph, that is, of nodes that are the source or the target of some arc, and stores it as an ε-serde bit vector in BASENAME.nodemask.", long_about = None)]
+pub struct CliArgs {
+    /// The basename of the graph.
+    pub src: PathBuf,
+}
+
+pub fn cli(command: Command) -> Command {
+    command.subcommand(CliArgs::augment_args(Command::new(COMMAND_NAME)).display_order(0))
+}
+
+pub fn main(submatches: &ArgMatches) -> Result<()> {
+    let args = CliArgs::from_arg_matches(submatches)?;
+
+    match get_endianness(&args.src)?.as_str() {
+        #[cfg(any(
+            feature = "be_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        BE::NAME => build_node_mask::<BE>(args),
+        #[cfg(any(
+            feature = "le_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        LE::NAME => build_node_mask::<LE>(args),
+        e => panic!("Unknown endianness: {}", e),
+    }
+}
+
+pub fn build_node_mask<E: Endianness + 'static>(args: CliArgs) -> Result<()>
+where
+    for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+{
+    let graph = BvGraphSeq::with_basename(&args.src)
+        .endianness::<E>()
+        .load()?;
+    let mut mask = BitVec::new(graph.num_nodes());
+
+    let mut pl = ProgressLogger::default();
+    pl.display_memory(true)
+        .item_name("node")
+        .expected_updates(Some(graph.num_nodes()));
+    pl.start("Computing present nodes...");
+    for_! ( (node, succ) in graph.iter() {
+        for succ in succ {
+            mask.set(node, true);
+            mask.set(succ, true);
+        }
+        pl.light_update();
+    });
+    pl.done();
+
+    let num_present = (0..mask.len()).filter(|&node| mask.get(node)).count();
+    log::info!("{} present nodes out of {}", num_present, graph.num_nodes());
+    store_node_mask(&args.src, &mask)
+}
@@ -5,16 +5,13 @@
  * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
  */
 
+use crate::cli::OutputFile;
 use crate::prelude::*;
 use anyhow::{Context, Result};
 use clap::{ArgMatches, Args, Command, FromArgMatches};
 use dsi_bitstream::prelude::*;
 use dsi_progress_logger::prelude::*;
-use std::{
-    fs::File,
-    io::{BufReader, BufWriter},
-    path::PathBuf,
-};
+use std::{fs::File, io::BufReader, path::PathBuf};
 
 pub const COMMAND_NAME: &str = "offsets";
 
@@ -56,13 +53,10 @@ where
     let seq_graph = BvGraphSeq::with_basename(&args.src)
         .endianness::<E>()
         .load()?;
-    let offsets = args.src.with_extension(OFFSETS_EXTENSION);
-    let file = std::fs::File::create(&offsets)
-        .with_context(|| format!("Could not create {}", offsets.display()))?;
+    let offsets = Basename::new(&args.src).with_ext(OFFSETS_EXTENSION);
+    let file = OutputFile::create(&offsets)?;
     // create a bit writer on the file
-    let mut writer = <BufBitWriter<BE, _>>::new(<WordAdapter<u64, _>>::new(
-        BufWriter::with_capacity(1 << 20, file),
-    ));
+    let mut writer = <BufBitWriter<BE, _>>::new(<WordAdapter<u64, _>>::new(file));
     // progress bar
     let mut pl = ProgressLogger::default();
     pl.display_memory(true)
@@ -86,6 +80,11 @@ where
         .write_gamma((degs_iter.get_pos() - offset) as _)
         .context("Could not write final gamma")?;
     pl.light_update();
+    writer
+        .into_inner()
+        .with_context(|| format!("Could not flush {}", offsets.display()))?
+        .into_inner()
+        .finish()?;
     pl.done();
     Ok(())
 }
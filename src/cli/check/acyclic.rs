@@ -0,0 +1,58 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use super::log_phase_summary;
+use crate::algo::is_acyclic;
+use crate::cli::ensure_check;
+use crate::prelude::*;
+use anyhow::Result;
+use clap::{ArgMatches, Args, Command, FromArgMatches};
+use dsi_bitstream::prelude::*;
+use std::path::PathBuf;
+use std::time::Instant;
+
+pub const COMMAND_NAME: &str = "acyclic";
+
+#[derive(Args, Debug)]
+#[command(about = "Checks that a graph is acyclic (self-loops are cycles). The graph must have an '.ef' file.", long_about = None)]
+pub struct CliArgs {
+    /// The basename of the graph.
+    pub src: PathBuf,
+}
+
+pub fn cli(command: Command) -> Command {
+    command.subcommand(CliArgs::augment_args(Command::new(COMMAND_NAME)).display_order(0))
+}
+
+pub fn main(submatches: &ArgMatches) -> Result<()> {
+    let args = CliArgs::from_arg_matches(submatches)?;
+
+    match get_endianness(&args.src)?.as_str() {
+        #[cfg(any(
+            feature = "be_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        BE::NAME => check_acyclic::<BE>(args),
+        #[cfg(any(
+            feature = "le_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        LE::NAME => check_acyclic::<LE>(args),
+        e => panic!("Unknown endianness: {}", e),
+    }
+}
+
+pub fn check_acyclic<E: Endianness + 'static>(args: CliArgs) -> Result<()>
+where
+    for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+{
+    let start = Instant::now();
+    let graph = BvGraph::with_basename(&args.src).endianness::<E>().load()?;
+    let acyclic = is_acyclic(&graph);
+    log_phase_summary("Acyclicity", graph.num_nodes(), "node", start);
+    ensure_check!(acyclic, "The graph contains a cycle");
+    Ok(())
+}
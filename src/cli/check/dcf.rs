@@ -0,0 +1,108 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use super::log_phase_summary;
+use crate::cli::{ensure_check, CheckFailed};
+use crate::graphs::bvgraph::match_select_params;
+use crate::prelude::*;
+use anyhow::{Context, Result};
+use clap::{ArgMatches, Args, Command, FromArgMatches};
+use dsi_bitstream::prelude::*;
+use dsi_progress_logger::prelude::*;
+use epserde::prelude::*;
+use log::info;
+use std::path::PathBuf;
+use std::time::Instant;
+use sux::traits::IndexedSeq;
+
+pub const COMMAND_NAME: &str = "dcf";
+
+#[derive(Args, Debug)]
+#[command(about = "Checks that the '.dcf' file is consistent with the graph. The structural invariants of the '.dcf' file (length, monotonicity, and last value equal to the number of arcs) are checked first.", long_about = None)]
+pub struct CliArgs {
+    /// The basename of the graph.
+    pub src: PathBuf,
+}
+
+pub fn cli(command: Command) -> Command {
+    command.subcommand(CliArgs::augment_args(Command::new(COMMAND_NAME)).display_order(0))
+}
+
+pub fn main(submatches: &ArgMatches) -> Result<()> {
+    let args = CliArgs::from_arg_matches(submatches)?;
+
+    match get_endianness(&args.src)?.as_str() {
+        #[cfg(any(
+            feature = "be_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        BE::NAME => check_dcf::<BE>(args),
+        #[cfg(any(
+            feature = "le_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        LE::NAME => check_dcf::<LE>(args),
+        e => panic!("Unknown endianness: {}", e),
+    }
+}
+
+pub fn check_dcf<E: Endianness + 'static>(args: CliArgs) -> Result<()>
+where
+    for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+{
+    let start = Instant::now();
+    let basename = Basename::new(&args.src);
+    let (num_nodes, num_arcs, _) = parse_properties::<E>(basename.with_ext(PROPERTIES_EXTENSION))?;
+
+    let params = SelectParams::load(&basename, DCF_SELECT_PROPERTY)?;
+    info!("Select parameters: {}", params);
+    let dcf_path = basename.with_ext(DEG_CUMUL_EXTENSION);
+
+    let seq_graph = BvGraphSeq::with_basename(&basename)
+        .endianness::<E>()
+        .load()
+        .with_context(|| format!("Could not load graph at {}", basename.display()))?;
+
+    let mut pl = ProgressLogger::default();
+    pl.display_memory(true)
+        .item_name("node")
+        .expected_updates(Some(num_nodes));
+
+    match_select_params!(params, L, S => {
+        let dcf = DCFSelect::<L, S>::mmap(&dcf_path, Flags::empty())
+            .with_context(|| format!("Could not map {}", dcf_path.display()))?;
+
+        validate_dcf(&*dcf, num_nodes, num_arcs, usize::MAX)
+            .map_err(|err| CheckFailed(format!("{:#}", err)))?;
+        info!("The degree cumulative function satisfies all structural invariants");
+
+        pl.start("Checking graph against the degree cumulative function...");
+        let phase_start = Instant::now();
+        let mut cumul_deg = 0;
+        for (node, (_offset, degree)) in seq_graph.offset_deg_iter().enumerate() {
+            cumul_deg += degree;
+            let value = dcf.get(node + 1);
+            ensure_check!(
+                value == cumul_deg,
+                "The graph and the degree cumulative function differ at index {}: {} != {}",
+                node + 1,
+                cumul_deg,
+                value
+            );
+            pl.light_update();
+        }
+        pl.done();
+        log_phase_summary(
+            "Graph vs degree cumulative function",
+            num_nodes,
+            "node",
+            phase_start,
+        );
+    });
+
+    log_phase_summary("Total", num_nodes, "node", start);
+    Ok(())
+}
@@ -5,22 +5,28 @@
  * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
  */
 
-use crate::graphs::bvgraph::{EF, EF_EXTENSION, OFFSETS_EXTENSION, PROPERTIES_EXTENSION};
+use super::log_phase_summary;
+use crate::cli::{ensure_check, CheckFailed};
+use crate::graphs::bvgraph::{
+    validate_ef, LoadMode, MemoryFlags, Mmap, SelectParams, EF_EXTENSION, EF_SELECT_PROPERTY,
+    GRAPH_EXTENSION, OFFSETS_EXTENSION, PROPERTIES_EXTENSION,
+};
+use crate::utils::Basename;
 use anyhow::{Context, Result};
 use clap::{ArgMatches, Args, Command, FromArgMatches};
 use dsi_bitstream::prelude::*;
 use dsi_progress_logger::prelude::*;
-use epserde::prelude::*;
 use log::info;
 use std::fs::File;
 use std::io::BufReader;
 use std::path::PathBuf;
+use std::time::Instant;
 use sux::prelude::*;
 
 pub const COMMAND_NAME: &str = "ef";
 
 #[derive(Args, Debug)]
-#[command(about = "Checks that the '.ef' file (and `.offsets` if present) is consistent with the graph.", long_about = None)]
+#[command(about = "Checks that the '.ef' file (and `.offsets` if present) is consistent with the graph. The structural invariants of the '.ef' file (length, monotonicity, and last offset equal to the length of the graph file) are checked first.", long_about = None)]
 pub struct CliArgs {
     /// The basename of the graph.
     pub src: PathBuf,
@@ -35,7 +41,8 @@ pub fn main(submatches: &ArgMatches) -> Result<()> {
 }
 
 pub fn check_ef(args: CliArgs) -> Result<()> {
-    let properties_path = args.src.with_extension(PROPERTIES_EXTENSION);
+    let start = Instant::now();
+    let properties_path = Basename::new(&args.src).with_ext(PROPERTIES_EXTENSION);
     let f = File::open(&properties_path).with_context(|| {
         format!(
             "Could not load properties file: {}",
@@ -43,12 +50,31 @@ pub fn check_ef(args: CliArgs) -> Result<()> {
         )
     })?;
     let map = java_properties::read(BufReader::new(f))?;
-    let num_nodes = map.get("nodes").unwrap().parse::<usize>()?;
+    let num_nodes = map
+        .get("nodes")
+        .with_context(|| format!("Missing 'nodes' property in {}", properties_path.display()))?
+        .parse::<usize>()?;
 
     // Create the offsets file
-    let of_file_path = args.src.with_extension(OFFSETS_EXTENSION);
+    let of_file_path = Basename::new(&args.src).with_ext(OFFSETS_EXTENSION);
 
-    let ef = EF::mmap(args.src.with_extension(EF_EXTENSION), Flags::default())?;
+    let params = SelectParams::load(&args.src, EF_SELECT_PROPERTY)?;
+    info!("Select parameters: {}", params);
+    let ef = Mmap::load_offsets(
+        Basename::new(&args.src).with_ext(EF_EXTENSION),
+        params,
+        MemoryFlags::default(),
+    )?;
+
+    // Check the structural invariants first, so that files for the wrong
+    // graph or truncated graph files are reported without decoding the graph
+    let graph_path = Basename::new(&args.src).with_ext(GRAPH_EXTENSION);
+    let graph_bits = 8 * std::fs::metadata(&graph_path)
+        .with_context(|| format!("Could not read metadata of {}", graph_path.display()))?
+        .len();
+    validate_ef(&*ef, num_nodes, graph_bits, usize::MAX)
+        .map_err(|err| CheckFailed(format!("{:#}", err)))?;
+    info!("The Elias-Fano offsets satisfy all structural invariants");
 
     let mut pl = ProgressLogger::default();
     pl.display_memory(true)
@@ -62,6 +88,7 @@ pub fn check_ef(args: CliArgs) -> Result<()> {
         let mut reader = BufBitReader::<BE, _>::new(<WordAdapter<u32, _>>::new(of_file));
         // progress bar
         pl.start("Checking offsets file against Elias-Fano...");
+        let phase_start = Instant::now();
         // read the graph a write the offsets
         let mut offset = 0;
         for node_id in 0..num_nodes + 1 {
@@ -69,10 +96,23 @@ pub fn check_ef(args: CliArgs) -> Result<()> {
             offset += reader.read_gamma()?;
             // read ef
             let ef_res = ef.get(node_id as _);
-            assert_eq!(offset, ef_res as _, "node_id: {}", node_id);
+            ensure_check!(
+                offset == ef_res as u64,
+                "The offsets file and the Elias-Fano offsets differ at index {}: {} != {}",
+                node_id,
+                offset,
+                ef_res
+            );
             // decode the next nodes so we know where the next node_id starts
             pl.light_update();
         }
+        pl.done();
+        log_phase_summary(
+            "Offsets vs Elias-Fano",
+            num_nodes + 1,
+            "offset",
+            phase_start,
+        );
     } else {
         info!("No offsets file, checking against graph file only");
     }
@@ -88,14 +128,23 @@ pub fn check_ef(args: CliArgs) -> Result<()> {
     // otherwise directly read the graph
     // progress bar
     pl.start("Checking graph against Elias-Fano...");
+    let phase_start = Instant::now();
     // read the graph a write the offsets
     for (node, (new_offset, _degree)) in seq_graph.offset_deg_iter().enumerate() {
         // decode the next nodes so we know where the next node_id starts
         // read ef
         let ef_res = ef.get(node as _);
-        assert_eq!(new_offset, ef_res as _, "node_id: {}", node);
+        ensure_check!(
+            new_offset == ef_res as u64,
+            "The graph and the Elias-Fano offsets differ at index {}: {} != {}",
+            node,
+            new_offset,
+            ef_res
+        );
         pl.light_update();
     }
     pl.done();
+    log_phase_summary("Graph vs Elias-Fano", num_nodes, "node", phase_start);
+    log_phase_summary("Total", num_nodes, "node", start);
     Ok(())
 }
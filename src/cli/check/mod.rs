@@ -4,10 +4,16 @@
  * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
  */
 
+use crate::cli::{pretty_print_elapsed, Exit, ExitCode};
 use anyhow::Result;
 use clap::{ArgMatches, Command};
+use std::time::Instant;
 
+pub mod acyclic;
+pub mod dcf;
 pub mod ef;
+pub mod roundtrip;
+pub mod targets;
 
 pub const COMMAND_NAME: &str = "check";
 
@@ -17,20 +23,63 @@ pub fn cli(command: Command) -> Command {
         .subcommand_required(true)
         .arg_required_else_help(true)
         .allow_external_subcommands(true);
+    let sub_command = acyclic::cli(sub_command);
+    let sub_command = dcf::cli(sub_command);
     let sub_command = ef::cli(sub_command);
+    let sub_command = roundtrip::cli(sub_command);
+    let sub_command = targets::cli(sub_command);
     command.subcommand(sub_command.display_order(0))
 }
 
+/// Logs a summary line for a check phase, reporting the total time
+/// and the throughput in items per second.
+pub(crate) fn log_phase_summary(phase: &str, items: usize, item_name: &str, start: Instant) {
+    let elapsed = start.elapsed().as_secs_f64();
+    log::info!(
+        "{}: checked {} {}s in {} ({:.2} {}s/s)",
+        phase,
+        items,
+        item_name,
+        pretty_print_elapsed(elapsed),
+        items as f64 / elapsed,
+        item_name,
+    );
+}
+
+/// Runs a command of the group.
+///
+/// The commands of the group follow a simplified exit-code contract, so
+/// that they can be used as guards in scripts: 0 if the check succeeds, 1
+/// if an inconsistency is found, and 2 on I/O or usage errors. Moreover,
+/// the global `--quiet` flag suppresses all output except for errors.
 pub fn main(submatches: &ArgMatches) -> Result<()> {
+    if let Ok(Some(true)) = submatches.try_get_one::<bool>("quiet") {
+        log::set_max_level(log::LevelFilter::Error);
+    }
+    run(submatches).map_err(|err| {
+        let code = if ExitCode::of(&err) == ExitCode::CheckFailed {
+            ExitCode::Failure
+        } else {
+            ExitCode::Usage
+        };
+        Exit(code, err).into()
+    })
+}
+
+fn run(submatches: &ArgMatches) -> Result<()> {
     match submatches.subcommand() {
+        Some((acyclic::COMMAND_NAME, sub_m)) => acyclic::main(sub_m),
+        Some((dcf::COMMAND_NAME, sub_m)) => dcf::main(sub_m),
         Some((ef::COMMAND_NAME, sub_m)) => ef::main(sub_m),
+        Some((roundtrip::COMMAND_NAME, sub_m)) => roundtrip::main(sub_m),
+        Some((targets::COMMAND_NAME, sub_m)) => targets::main(sub_m),
         Some((command_name, _)) => {
             eprintln!("Unknown command: {:?}", command_name);
-            std::process::exit(1);
+            std::process::exit(ExitCode::Usage as i32);
         }
         None => {
             eprintln!("No command given for check");
-            std::process::exit(1);
+            std::process::exit(ExitCode::Usage as i32);
         }
     }
 }
@@ -0,0 +1,127 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use super::log_phase_summary;
+use crate::cli::build::ef::{build_eliasfano, CliArgs as BuildEfArgs};
+use crate::cli::*;
+use crate::prelude::*;
+use anyhow::{Context, Result};
+use clap::{ArgMatches, Args, Command, FromArgMatches};
+use dsi_bitstream::prelude::*;
+use dsi_progress_logger::prelude::*;
+use std::path::PathBuf;
+use std::time::Instant;
+use tempfile::Builder;
+
+pub const COMMAND_NAME: &str = "roundtrip";
+
+#[derive(Args, Debug)]
+#[command(about = "Recompresses a graph in a temporary directory with the given compression parameters and checks that the result has the same successor lists. On a mismatch, the first differing node is decoded in both graphs, and the components of its record are printed side by side. The graph must have an '.ef' file.", long_about = None)]
+pub struct CliArgs {
+    /// The basename of the graph.
+    pub src: PathBuf,
+
+    #[clap(flatten)]
+    pub num_threads: NumThreadsArg,
+
+    /// The compression parameters of the recompressed graph (its endianness
+    /// is always that of the source graph).
+    #[clap(flatten)]
+    pub ca: CompressArgs,
+}
+
+pub fn cli(command: Command) -> Command {
+    command.subcommand(CliArgs::augment_args(Command::new(COMMAND_NAME)).display_order(0))
+}
+
+pub fn main(submatches: &ArgMatches) -> Result<()> {
+    let args = CliArgs::from_arg_matches(submatches)?;
+
+    match get_endianness(&args.src)?.as_str() {
+        #[cfg(any(
+            feature = "be_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        BE::NAME => check_roundtrip::<BE>(args),
+        #[cfg(any(
+            feature = "le_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        LE::NAME => check_roundtrip::<LE>(args),
+        e => panic!("Unknown endianness: {}", e),
+    }
+}
+
+pub fn check_roundtrip<E: Endianness + Send + Sync + 'static>(args: CliArgs) -> Result<()>
+where
+    for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+{
+    let start = Instant::now();
+    let thread_pool = get_thread_pool(args.num_threads.num_threads);
+    if let Some(endianness) = &args.ca.endianness {
+        if endianness != E::NAME {
+            log::warn!(
+                "Ignoring endianness {}: the graph will be recompressed in {} endianness",
+                endianness,
+                E::NAME
+            );
+        }
+    }
+
+    let seq_graph = BvGraphSeq::with_basename(&args.src)
+        .endianness::<E>()
+        .load()?;
+    let num_nodes = seq_graph.num_nodes();
+
+    let dir = Builder::new().prefix("check_roundtrip_").tempdir()?;
+    let recompressed = dir.path().join("recompressed");
+    let tmp_dir = Builder::new().prefix("check_roundtrip_comp_").tempdir()?;
+    BvComp::parallel_endianness(
+        &recompressed,
+        &seq_graph,
+        num_nodes,
+        args.ca.into(),
+        &thread_pool,
+        tmp_dir,
+        E::NAME,
+    )?;
+
+    let recompressed_seq = BvGraphSeq::with_basename(&recompressed)
+        .endianness::<E>()
+        .load()?;
+    let node = first_differing_node(
+        &seq_graph,
+        &recompressed_seq,
+        &mut ProgressLogger::default(),
+    );
+    log_phase_summary("Round trip", num_nodes, "node", start);
+
+    let Some(node) = node else {
+        return Ok(());
+    };
+
+    // Localize the mismatch
+    build_eliasfano::<E>(BuildEfArgs {
+        src: recompressed.clone(),
+        n: None,
+        select_params: SelectParams::default(),
+    })?;
+    let graph = BvGraph::with_basename(&args.src)
+        .endianness::<E>()
+        .load()
+        .with_context(|| format!("Could not load graph at {}", args.src.display()))?;
+    let recompressed_graph = BvGraph::with_basename(&recompressed)
+        .endianness::<E>()
+        .load()?;
+    if node < num_nodes && node < recompressed_graph.num_nodes() {
+        print!("{}", diff_node_trace(&graph, &recompressed_graph, node));
+    }
+    Err(CheckFailed(format!(
+        "The recompressed graph differs from the original one at node {}",
+        node
+    ))
+    .into())
+}
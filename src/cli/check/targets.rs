@@ -0,0 +1,123 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use super::log_phase_summary;
+use crate::cli::{get_thread_pool, CheckFailed, NumThreadsArg};
+use crate::prelude::*;
+use anyhow::Result;
+use clap::{ArgMatches, Args, Command, FromArgMatches};
+use dsi_bitstream::prelude::*;
+use dsi_progress_logger::prelude::*;
+use lender::*;
+use rayon::prelude::*;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Instant;
+
+pub const COMMAND_NAME: &str = "targets";
+
+#[derive(Args, Debug)]
+#[command(about = "Checks that all successors of a graph are smaller than its number of nodes. The graph must have an '.ef' file.", long_about = None)]
+pub struct CliArgs {
+    /// The basename of the graph.
+    pub src: PathBuf,
+
+    #[clap(flatten)]
+    pub num_threads: NumThreadsArg,
+}
+
+pub fn cli(command: Command) -> Command {
+    command.subcommand(CliArgs::augment_args(Command::new(COMMAND_NAME)).display_order(0))
+}
+
+pub fn main(submatches: &ArgMatches) -> Result<()> {
+    let args = CliArgs::from_arg_matches(submatches)?;
+
+    match get_endianness(&args.src)?.as_str() {
+        #[cfg(any(
+            feature = "be_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        BE::NAME => check_targets::<BE>(args),
+        #[cfg(any(
+            feature = "le_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        LE::NAME => check_targets::<LE>(args),
+        e => panic!("Unknown endianness: {}", e),
+    }
+}
+
+/// The first arc of a chunk whose target is out of range, and the number of
+/// such arcs in the chunk.
+#[derive(Debug, Default)]
+struct Violations {
+    first: Option<(usize, usize)>,
+    count: u64,
+}
+
+pub fn check_targets<E: Endianness + Send + Sync + 'static>(args: CliArgs) -> Result<()>
+where
+    for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+{
+    let start = Instant::now();
+    let thread_pool = get_thread_pool(args.num_threads.num_threads);
+    let graph = BvGraph::with_basename(&args.src).endianness::<E>().load()?;
+    let num_nodes = graph.num_nodes();
+
+    let mut pl = ProgressLogger::default();
+    pl.display_memory(true)
+        .item_name("node")
+        .expected_updates(Some(num_nodes));
+    pl.start("Checking targets...");
+    let pl = Mutex::new(pl);
+
+    // More chunks than threads to balance the load
+    let lenders = graph
+        .split_iter(16 * thread_pool.current_num_threads())
+        .collect::<Vec<_>>();
+    let violations = thread_pool.install(|| {
+        lenders
+            .into_par_iter()
+            .map(|mut lender| {
+                let mut violations = Violations::default();
+                let mut nodes = 0;
+                while let Some((node, succ)) = lender.next() {
+                    for s in succ {
+                        if s >= num_nodes {
+                            violations.first.get_or_insert((node, s));
+                            violations.count += 1;
+                        }
+                    }
+                    nodes += 1;
+                }
+                pl.lock().unwrap().update_with_count(nodes);
+                violations
+            })
+            .reduce(Violations::default, |a, b| Violations {
+                // Chunks are disjoint, so the smaller source comes first
+                first: match (a.first, b.first) {
+                    (Some(x), Some(y)) => Some(x.min(y)),
+                    (x, y) => x.or(y),
+                },
+                count: a.count + b.count,
+            })
+    });
+    pl.into_inner().unwrap().done();
+    log_phase_summary("Targets", num_nodes, "node", start);
+
+    if let Some((node, target)) = violations.first {
+        return Err(CheckFailed(format!(
+            "Found {} arcs with target out of range; the first one is ({}, {}), but the graph has {} nodes",
+            violations.count,
+            node,
+            target,
+            num_nodes
+        ))
+        .into());
+    }
+    Ok(())
+}
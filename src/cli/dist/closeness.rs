@@ -0,0 +1,109 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::algo::distances::closeness_subset;
+use crate::cli::*;
+use crate::prelude::*;
+use anyhow::{ensure, Context, Result};
+use clap::{ArgMatches, Args, Command, FromArgMatches};
+use dsi_bitstream::prelude::*;
+use dsi_progress_logger::prelude::*;
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+
+pub const COMMAND_NAME: &str = "closeness";
+
+#[derive(Args, Debug)]
+#[command(about = "Computes exactly the classic and harmonic closeness of a subset of nodes.", long_about = None)]
+pub struct CliArgs {
+    /// The basename of the graph.
+    pub src: PathBuf,
+
+    #[arg(long)]
+    /// A file containing the nodes whose closeness must be computed, one per line.
+    pub nodes: PathBuf,
+
+    #[arg(short, long)]
+    /// Where to write the results, one line per node containing the node, its
+    /// classic closeness and its harmonic closeness separated by tabs; if
+    /// missing, results are written to stdout.
+    pub output: Option<PathBuf>,
+
+    #[clap(flatten)]
+    pub num_threads: NumThreadsArg,
+}
+
+pub fn cli(command: Command) -> Command {
+    command.subcommand(CliArgs::augment_args(Command::new(COMMAND_NAME)).display_order(0))
+}
+
+pub fn main(submatches: &ArgMatches) -> Result<()> {
+    let args = CliArgs::from_arg_matches(submatches)?;
+
+    match get_endianness(&args.src)?.as_str() {
+        #[cfg(any(
+            feature = "be_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        BE::NAME => closeness::<BE>(args),
+        #[cfg(any(
+            feature = "le_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        LE::NAME => closeness::<LE>(args),
+        e => panic!("Unknown endianness: {}", e),
+    }
+}
+
+pub fn closeness<E: Endianness + Send + Sync + 'static>(args: CliArgs) -> Result<()>
+where
+    for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+{
+    let graph = BvGraph::with_basename(&args.src)
+        .endianness::<E>()
+        .mode::<Mmap>()
+        .flags(MemoryFlags::RANDOM_ACCESS)
+        .load()?;
+    let num_nodes = graph.num_nodes();
+
+    let file = std::fs::File::open(&args.nodes)
+        .with_context(|| format!("Could not open {}", args.nodes.display()))?;
+    let mut nodes = vec![];
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let node = line
+            .parse::<usize>()
+            .with_context(|| format!("Could not parse node {:?}", line))?;
+        ensure!(
+            node < num_nodes,
+            "Node {} is out of range (the graph has {} nodes)",
+            node,
+            num_nodes
+        );
+        nodes.push(node);
+    }
+
+    let thread_pool = get_thread_pool(args.num_threads.num_threads);
+    let mut pl = ProgressLogger::default();
+    pl.display_memory(true);
+    let result = closeness_subset(&graph, &nodes, &thread_pool, &mut pl);
+
+    let mut writer = FileOrStdout::create(args.output.as_ref())?;
+    for (node, closeness) in nodes.iter().zip(result) {
+        writeln!(
+            writer,
+            "{}\t{}\t{}",
+            node, closeness.classic, closeness.harmonic
+        )?;
+    }
+    writer.finish()?;
+
+    Ok(())
+}
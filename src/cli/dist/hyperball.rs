@@ -0,0 +1,131 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::algo::distances::{effective_diameter, hyperball, hyperball_and_reachable_set_sizes};
+use crate::cli::*;
+use crate::prelude::*;
+use anyhow::{ensure, Result};
+use clap::{ArgMatches, Args, Command, FromArgMatches};
+use dsi_bitstream::prelude::*;
+use dsi_progress_logger::prelude::*;
+use std::io::Write;
+use std::path::PathBuf;
+
+pub const COMMAND_NAME: &str = "hyperball";
+
+#[derive(Args, Debug)]
+#[command(about = "Approximates the neighborhood function of a graph using HyperBall.", long_about = None)]
+pub struct CliArgs {
+    /// The basename of the graph.
+    pub src: PathBuf,
+
+    #[arg(long, default_value_t = 10)]
+    /// The base-2 logarithm of the number of registers of each HyperLogLog
+    /// counter (between 4 and 16).
+    pub log2m: usize,
+
+    #[arg(long, default_value_t = 0)]
+    /// The seed of the hash function.
+    pub seed: u64,
+
+    #[arg(short, long)]
+    /// Where to write the neighborhood function, one line per distance
+    /// containing the distance and the approximate number of pairs within
+    /// that distance separated by a tab; if missing, it is written to stdout.
+    pub output: Option<PathBuf>,
+
+    #[arg(long, value_name = "P")]
+    /// Also print the (interpolated) effective diameter at the P-th
+    /// percentile, e.g., 90 for the 90% effective diameter.
+    pub effective_diameter: Option<f64>,
+
+    #[arg(long, value_name = "FILE")]
+    /// Also write to FILE, one per line, the approximate number of nodes
+    /// reachable from each node (including the node itself).
+    pub reachable_nodes: Option<PathBuf>,
+
+    #[clap(flatten)]
+    pub num_threads: NumThreadsArg,
+}
+
+pub fn cli(command: Command) -> Command {
+    command.subcommand(CliArgs::augment_args(Command::new(COMMAND_NAME)).display_order(0))
+}
+
+pub fn main(submatches: &ArgMatches) -> Result<()> {
+    let args = CliArgs::from_arg_matches(submatches)?;
+
+    match get_endianness(&args.src)?.as_str() {
+        #[cfg(any(
+            feature = "be_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        BE::NAME => run::<BE>(args),
+        #[cfg(any(
+            feature = "le_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        LE::NAME => run::<LE>(args),
+        e => panic!("Unknown endianness: {}", e),
+    }
+}
+
+pub fn run<E: Endianness + Send + Sync + 'static>(args: CliArgs) -> Result<()>
+where
+    for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+{
+    if let Some(p) = args.effective_diameter {
+        ensure!(
+            p > 0.0 && p <= 100.0,
+            "The effective-diameter percentile must be in (0..100], found {}",
+            p
+        );
+    }
+
+    let graph = BvGraph::with_basename(&args.src)
+        .endianness::<E>()
+        .mode::<Mmap>()
+        .flags(MemoryFlags::RANDOM_ACCESS)
+        .load()?;
+
+    let thread_pool = get_thread_pool(args.num_threads.num_threads);
+    let mut pl = ProgressLogger::default();
+    pl.display_memory(true);
+    let nf = match &args.reachable_nodes {
+        Some(path) => {
+            let (nf, sizes) = hyperball_and_reachable_set_sizes(
+                &graph,
+                args.log2m,
+                args.seed,
+                &thread_pool,
+                &mut pl,
+            )?;
+            let mut writer = OutputFile::create(path)?;
+            for size in sizes.iter() {
+                writeln!(writer, "{}", size)?;
+            }
+            writer.finish()?;
+            nf
+        }
+        None => hyperball(&graph, args.log2m, args.seed, &thread_pool, &mut pl)?,
+    };
+
+    let mut writer = FileOrStdout::create(args.output.as_ref())?;
+    for (t, value) in nf.iter().enumerate() {
+        writeln!(writer, "{}\t{}", t, value)?;
+    }
+    writer.finish()?;
+
+    if let Some(p) = args.effective_diameter {
+        println!(
+            "Effective diameter ({}%): {}",
+            p,
+            effective_diameter(&nf, p / 100.0)
+        );
+    }
+
+    Ok(())
+}
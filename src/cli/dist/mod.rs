@@ -0,0 +1,48 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use anyhow::Result;
+use clap::{ArgMatches, Command};
+
+pub mod closeness;
+pub mod hyperball;
+pub mod oracle;
+pub mod pairs;
+pub mod path;
+
+pub const COMMAND_NAME: &str = "dist";
+
+pub fn cli(command: Command) -> Command {
+    let sub_command = Command::new(COMMAND_NAME)
+        .about("Compute distance-based statistics on a BvGraph.")
+        .subcommand_required(true)
+        .arg_required_else_help(true)
+        .allow_external_subcommands(true);
+    let sub_command = closeness::cli(sub_command);
+    let sub_command = hyperball::cli(sub_command);
+    let sub_command = oracle::cli(sub_command);
+    let sub_command = pairs::cli(sub_command);
+    let sub_command = path::cli(sub_command);
+    command.subcommand(sub_command.display_order(0))
+}
+
+pub fn main(submatches: &ArgMatches) -> Result<()> {
+    match submatches.subcommand() {
+        Some((closeness::COMMAND_NAME, sub_m)) => closeness::main(sub_m),
+        Some((hyperball::COMMAND_NAME, sub_m)) => hyperball::main(sub_m),
+        Some((oracle::COMMAND_NAME, sub_m)) => oracle::main(sub_m),
+        Some((pairs::COMMAND_NAME, sub_m)) => pairs::main(sub_m),
+        Some((path::COMMAND_NAME, sub_m)) => path::main(sub_m),
+        Some((command_name, _)) => {
+            eprintln!("Unknown command: {:?}", command_name);
+            std::process::exit(1);
+        }
+        None => {
+            eprintln!("No command given for dist");
+            std::process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,133 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::algo::distances::landmarks::{self, LandmarkSelection, LANDMARKS_EXTENSION};
+use crate::cli::*;
+use crate::prelude::*;
+use anyhow::{ensure, Context, Result};
+use clap::{ArgMatches, Args, Command, FromArgMatches, ValueEnum};
+use dsi_bitstream::prelude::*;
+use dsi_progress_logger::prelude::*;
+use std::path::PathBuf;
+
+pub const COMMAND_NAME: &str = "build";
+
+/// The [`LandmarkSelection`] strategy.
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum Selection {
+    /// The nodes of largest degree.
+    Degree,
+    /// The node of largest degree, and then repeatedly the node farthest from
+    /// the landmarks chosen so far.
+    Coverage,
+}
+
+impl From<Selection> for LandmarkSelection {
+    fn from(selection: Selection) -> Self {
+        match selection {
+            Selection::Degree => LandmarkSelection::Degree,
+            Selection::Coverage => LandmarkSelection::Coverage,
+        }
+    }
+}
+
+#[derive(Args, Debug)]
+#[command(about = "Builds a landmark-based approximate distance oracle, storing it in BASENAME.lmk.", long_about = None)]
+pub struct CliArgs {
+    /// The basename of the graph.
+    pub src: PathBuf,
+
+    #[arg(short = 'k', long, default_value_t = 16)]
+    /// The number of landmarks.
+    pub landmarks: usize,
+
+    #[arg(short, long, value_enum, default_value_t = Selection::Degree)]
+    /// How to choose the landmarks.
+    pub selection: Selection,
+
+    #[arg(long, conflicts_with = "transposed")]
+    /// The graph is symmetric, so distances from and to landmarks coincide.
+    pub symm: bool,
+
+    #[arg(long, required_unless_present = "symm")]
+    /// The basename of the transpose of the graph, which will be used to
+    /// compute distances to the landmarks.
+    pub transposed: Option<PathBuf>,
+}
+
+pub fn cli(command: Command) -> Command {
+    command.subcommand(CliArgs::augment_args(Command::new(COMMAND_NAME)).display_order(0))
+}
+
+pub fn main(submatches: &ArgMatches) -> Result<()> {
+    let args = CliArgs::from_arg_matches(submatches)?;
+
+    match get_endianness(&args.src)?.as_str() {
+        #[cfg(any(
+            feature = "be_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        BE::NAME => build::<BE>(args),
+        #[cfg(any(
+            feature = "le_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        LE::NAME => build::<LE>(args),
+        e => panic!("Unknown endianness: {}", e),
+    }
+}
+
+pub fn build<E: Endianness + 'static>(args: CliArgs) -> Result<()>
+where
+    for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+{
+    let graph = BvGraph::with_basename(&args.src)
+        .endianness::<E>()
+        .mode::<Mmap>()
+        .flags(MemoryFlags::RANDOM_ACCESS)
+        .load()?;
+    let transposed = match &args.transposed {
+        Some(path) => {
+            check_transposed(&args.src, path)?;
+            let transposed = BvGraph::with_basename(path)
+                .endianness::<E>()
+                .mode::<Mmap>()
+                .flags(MemoryFlags::RANDOM_ACCESS)
+                .load()?;
+            ensure!(
+                graph.num_nodes() == transposed.num_nodes(),
+                "The graph has {} nodes, but the transposed graph has {} nodes",
+                graph.num_nodes(),
+                transposed.num_nodes()
+            );
+            Some(transposed)
+        }
+        None => None,
+    };
+
+    let mut pl = ProgressLogger::default();
+    pl.display_memory(true);
+    let oracle = landmarks::build(
+        &graph,
+        transposed.as_ref(),
+        args.landmarks,
+        args.selection.into(),
+        &mut pl,
+    );
+    log::info!(
+        "Built an oracle with {} landmarks and bit width {}",
+        oracle.landmarks().len(),
+        oracle.bit_width()
+    );
+
+    let path = Basename::new(&args.src).with_ext(LANDMARKS_EXTENSION);
+    let mut file = OutputFile::create(&path)?;
+    oracle
+        .serialize(&mut file)
+        .with_context(|| format!("Could not store oracle to {}", path.display()))?;
+    file.finish()?;
+    Ok(())
+}
@@ -0,0 +1,39 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use anyhow::Result;
+use clap::{ArgMatches, Command};
+
+pub mod build;
+pub mod query;
+
+pub const COMMAND_NAME: &str = "oracle";
+
+pub fn cli(command: Command) -> Command {
+    let sub_command = Command::new(COMMAND_NAME)
+        .about("Build and query landmark-based approximate distance oracles.")
+        .subcommand_required(true)
+        .arg_required_else_help(true)
+        .allow_external_subcommands(true);
+    let sub_command = build::cli(sub_command);
+    let sub_command = query::cli(sub_command);
+    command.subcommand(sub_command.display_order(0))
+}
+
+pub fn main(submatches: &ArgMatches) -> Result<()> {
+    match submatches.subcommand() {
+        Some((build::COMMAND_NAME, sub_m)) => build::main(sub_m),
+        Some((query::COMMAND_NAME, sub_m)) => query::main(sub_m),
+        Some((command_name, _)) => {
+            eprintln!("Unknown command: {:?}", command_name);
+            std::process::exit(1);
+        }
+        None => {
+            eprintln!("No command given for oracle");
+            std::process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,50 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::algo::distances::landmarks::{Oracle, LANDMARKS_EXTENSION};
+use crate::prelude::*;
+use anyhow::{ensure, Context, Result};
+use clap::{ArgMatches, Args, Command, FromArgMatches};
+use epserde::prelude::*;
+use std::path::PathBuf;
+
+pub const COMMAND_NAME: &str = "query";
+
+#[derive(Args, Debug)]
+#[command(about = "Prints an upper bound on the distance between two nodes using the oracle in BASENAME.lmk, or \"inf\" if no landmark connects them.", long_about = None)]
+pub struct CliArgs {
+    /// The basename of the graph.
+    pub src: PathBuf,
+    /// The source node.
+    pub u: usize,
+    /// The target node.
+    pub v: usize,
+}
+
+pub fn cli(command: Command) -> Command {
+    command.subcommand(CliArgs::augment_args(Command::new(COMMAND_NAME)).display_order(0))
+}
+
+pub fn main(submatches: &ArgMatches) -> Result<()> {
+    let args = CliArgs::from_arg_matches(submatches)?;
+
+    let path = Basename::new(&args.src).with_ext(LANDMARKS_EXTENSION);
+    let oracle = <Oracle>::load_full(&path)
+        .with_context(|| format!("Could not load oracle from {}", path.display()))?;
+    for node in [args.u, args.v] {
+        ensure!(
+            node < oracle.num_nodes(),
+            "Node {} does not exist (the graph has {} nodes)",
+            node,
+            oracle.num_nodes()
+        );
+    }
+    match oracle.upper_bound(args.u, args.v) {
+        Some(distance) => println!("{}", distance),
+        None => println!("inf"),
+    }
+    Ok(())
+}
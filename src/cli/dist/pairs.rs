@@ -0,0 +1,180 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::algo::distances::sample_pair_distances;
+use crate::cli::*;
+use crate::prelude::*;
+use anyhow::{ensure, Result};
+use clap::{ArgMatches, Args, Command, FromArgMatches};
+use dsi_bitstream::prelude::*;
+use dsi_progress_logger::prelude::*;
+use std::io::Write;
+use std::path::PathBuf;
+
+pub const COMMAND_NAME: &str = "pairs";
+
+/// The quantile of the standard normal distribution used for 95% confidence
+/// intervals.
+const Z_95: f64 = 1.959963984540054;
+
+#[derive(Args, Debug)]
+#[command(about = "Samples uniformly pairs of distinct nodes and computes their distances, reporting the empirical distance distribution of reachable pairs and the fraction of unreachable pairs, with 95% confidence intervals.", long_about = None)]
+pub struct CliArgs {
+    /// The basename of the graph.
+    pub src: PathBuf,
+
+    #[arg(short = 'k', long, default_value_t = 1000)]
+    /// The number of pairs to sample.
+    pub samples: usize,
+
+    #[arg(long, default_value_t = 0)]
+    /// The seed of the pseudorandom number generator.
+    pub seed: u64,
+
+    #[arg(long, conflicts_with = "transposed")]
+    /// The graph is symmetric, so distances can be computed by bidirectional
+    /// visits on the graph itself.
+    pub symm: bool,
+
+    #[arg(long)]
+    /// The basename of the transpose of the graph, which will be used to
+    /// compute distances by bidirectional visits.
+    pub transposed: Option<PathBuf>,
+
+    #[arg(short, long)]
+    /// Where to write the distance distribution, one line per distance
+    /// containing the distance, the number of sampled pairs at that distance,
+    /// their fraction among reachable pairs and the bounds of its confidence
+    /// interval, separated by tabs; if missing, it is written to stdout.
+    pub output: Option<PathBuf>,
+
+    #[clap(flatten)]
+    pub num_threads: NumThreadsArg,
+}
+
+pub fn cli(command: Command) -> Command {
+    command.subcommand(CliArgs::augment_args(Command::new(COMMAND_NAME)).display_order(0))
+}
+
+pub fn main(submatches: &ArgMatches) -> Result<()> {
+    let args = CliArgs::from_arg_matches(submatches)?;
+
+    match get_endianness(&args.src)?.as_str() {
+        #[cfg(any(
+            feature = "be_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        BE::NAME => pairs::<BE>(args),
+        #[cfg(any(
+            feature = "le_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        LE::NAME => pairs::<LE>(args),
+        e => panic!("Unknown endianness: {}", e),
+    }
+}
+
+/// Returns the Wilson score interval for a proportion given the number of
+/// successes and of trials.
+fn wilson_interval(successes: usize, trials: usize) -> (f64, f64) {
+    if trials == 0 {
+        return (0.0, 1.0);
+    }
+    let n = trials as f64;
+    let p = successes as f64 / n;
+    let z2 = Z_95 * Z_95;
+    let center = (p + z2 / (2.0 * n)) / (1.0 + z2 / n);
+    let half_width = Z_95 * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt() / (1.0 + z2 / n);
+    (
+        (center - half_width).max(0.0),
+        (center + half_width).min(1.0),
+    )
+}
+
+pub fn pairs<E: Endianness + Send + Sync + 'static>(args: CliArgs) -> Result<()>
+where
+    for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+{
+    let graph = BvGraph::with_basename(&args.src)
+        .endianness::<E>()
+        .mode::<Mmap>()
+        .flags(MemoryFlags::RANDOM_ACCESS)
+        .load()?;
+    let transposed = match &args.transposed {
+        Some(path) => {
+            check_transposed(&args.src, path)?;
+            let transposed = BvGraph::with_basename(path)
+                .endianness::<E>()
+                .mode::<Mmap>()
+                .flags(MemoryFlags::RANDOM_ACCESS)
+                .load()?;
+            ensure!(
+                graph.num_nodes() == transposed.num_nodes(),
+                "The graph has {} nodes, but the transposed graph has {} nodes",
+                graph.num_nodes(),
+                transposed.num_nodes()
+            );
+            Some(transposed)
+        }
+        None => None,
+    };
+    let transpose = if args.symm {
+        Some(&graph)
+    } else {
+        transposed.as_ref()
+    };
+
+    let thread_pool = get_thread_pool(args.num_threads.num_threads);
+    let mut pl = ProgressLogger::default();
+    pl.display_memory(true);
+    let sample = sample_pair_distances(
+        &graph,
+        transpose,
+        args.samples,
+        args.seed,
+        &thread_pool,
+        &mut pl,
+    );
+
+    let reachable = sample.reachable();
+    let mut writer = FileOrStdout::create(args.output.as_ref())?;
+    for (d, &count) in sample.distribution.iter().enumerate() {
+        if count == 0 {
+            continue;
+        }
+        let (low, high) = wilson_interval(count, reachable);
+        writeln!(
+            writer,
+            "{}\t{}\t{}\t{}\t{}",
+            d,
+            count,
+            count as f64 / reachable as f64,
+            low,
+            high
+        )?;
+    }
+    writer.finish()?;
+
+    let avg = sample.average_distance();
+    let half_width = Z_95 * sample.std_dev_distance() / (reachable as f64).sqrt();
+    println!(
+        "Average distance: {} (95% CI: [{}, {}])",
+        avg,
+        avg - half_width,
+        avg + half_width
+    );
+    let (low, high) = wilson_interval(sample.unreachable, sample.samples());
+    println!(
+        "Unreachable pairs: {}/{} ({}, 95% CI: [{}, {}])",
+        sample.unreachable,
+        sample.samples(),
+        sample.unreachable as f64 / sample.samples() as f64,
+        low,
+        high
+    );
+
+    Ok(())
+}
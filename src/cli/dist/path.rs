@@ -0,0 +1,87 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::algo::distances::bfs_shortest_path;
+use crate::prelude::*;
+use anyhow::{ensure, Result};
+use clap::{ArgMatches, Args, Command, FromArgMatches};
+use dsi_bitstream::prelude::*;
+use itertools::Itertools;
+use std::path::PathBuf;
+
+pub const COMMAND_NAME: &str = "path";
+
+#[derive(Args, Debug)]
+#[command(about = "Computes the distance between two nodes and a shortest path between them using a breadth-first visit that stops as soon as the target is found.", long_about = None)]
+pub struct CliArgs {
+    /// The basename of the graph.
+    pub src: PathBuf,
+
+    /// The source node.
+    pub source: usize,
+
+    /// The target node.
+    pub target: usize,
+}
+
+pub fn cli(command: Command) -> Command {
+    command.subcommand(CliArgs::augment_args(Command::new(COMMAND_NAME)).display_order(0))
+}
+
+pub fn main(submatches: &ArgMatches) -> Result<()> {
+    let args = CliArgs::from_arg_matches(submatches)?;
+
+    match get_endianness(&args.src)?.as_str() {
+        #[cfg(any(
+            feature = "be_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        BE::NAME => path::<BE>(args),
+        #[cfg(any(
+            feature = "le_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        LE::NAME => path::<LE>(args),
+        e => panic!("Unknown endianness: {}", e),
+    }
+}
+
+pub fn path<E: Endianness + 'static>(args: CliArgs) -> Result<()>
+where
+    for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+{
+    let graph = BvGraph::with_basename(&args.src)
+        .endianness::<E>()
+        .mode::<Mmap>()
+        .flags(MemoryFlags::RANDOM_ACCESS)
+        .load()?;
+    let num_nodes = graph.num_nodes();
+    ensure!(
+        args.source < num_nodes,
+        "The source node {} is out of bounds (the graph has {} nodes)",
+        args.source,
+        num_nodes
+    );
+    ensure!(
+        args.target < num_nodes,
+        "The target node {} is out of bounds (the graph has {} nodes)",
+        args.target,
+        num_nodes
+    );
+
+    match bfs_shortest_path(&graph, args.source, args.target) {
+        Some(path) => {
+            println!("Distance: {}", path.len() - 1);
+            println!("Path: {}", path.iter().join(" -> "));
+        }
+        None => println!(
+            "Node {} is not reachable from node {}",
+            args.target, args.source
+        ),
+    }
+
+    Ok(())
+}
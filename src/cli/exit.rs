@@ -0,0 +1,162 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+//! Exit codes for the command-line interface.
+//!
+//! Errors returned by [`main`](super::main) are mapped to an [`ExitCode`] by
+//! [`ExitCode::of`], so that scripts can tell apart usage errors, missing or
+//! corrupt inputs, and negative results of checks.
+
+use crate::utils::interrupt::Interrupted;
+use std::fmt::{self, Display, Formatter};
+
+/// The exit codes of the command-line interface.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u8)]
+pub enum ExitCode {
+    /// The command completed successfully.
+    Success = 0,
+    /// The command failed for a reason not covered by the other codes.
+    Failure = 1,
+    /// The command line is invalid.
+    Usage = 2,
+    /// An input file is missing, unreadable, or corrupt.
+    Input = 3,
+    /// A check completed, but its result is negative (e.g., an
+    /// inconsistency was found); see [`CheckFailed`].
+    CheckFailed = 4,
+    /// The command was interrupted.
+    Interrupted = 5,
+}
+
+impl ExitCode {
+    /// Returns the exit code corresponding to an error.
+    ///
+    /// The chain of causes is examined from the outermost error, and the
+    /// first cause with a specific meaning determines the exit code:
+    /// - [`Exit`] errors map to their exit code;
+    /// - [`CheckFailed`] errors map to [`ExitCode::CheckFailed`];
+    /// - [`clap::Error`]s map to [`ExitCode::Usage`];
+    /// - [`Interrupted`] errors, and I/O errors of kind
+    ///   [`Interrupted`](std::io::ErrorKind::Interrupted), map to
+    ///   [`ExitCode::Interrupted`];
+    /// - I/O errors signaling missing, unreadable or corrupt files, and
+    ///   errors parsing property files, map to [`ExitCode::Input`].
+    ///
+    /// All other errors map to [`ExitCode::Failure`].
+    pub fn of(err: &anyhow::Error) -> Self {
+        use std::io::ErrorKind;
+        for cause in err.chain() {
+            if let Some(Exit(code, _)) = cause.downcast_ref::<Exit>() {
+                return *code;
+            }
+            if cause.is::<CheckFailed>() {
+                return Self::CheckFailed;
+            }
+            if cause.is::<clap::Error>() {
+                return Self::Usage;
+            }
+            if cause.is::<java_properties::PropertiesError>() {
+                return Self::Input;
+            }
+            if cause.is::<Interrupted>() {
+                return Self::Interrupted;
+            }
+            if let Some(err) = cause.downcast_ref::<std::io::Error>() {
+                if err.get_ref().is_some_and(|err| err.is::<Interrupted>()) {
+                    return Self::Interrupted;
+                }
+                match err.kind() {
+                    ErrorKind::Interrupted => return Self::Interrupted,
+                    ErrorKind::NotFound
+                    | ErrorKind::PermissionDenied
+                    | ErrorKind::InvalidData
+                    | ErrorKind::UnexpectedEof => return Self::Input,
+                    _ => {}
+                }
+            }
+        }
+        Self::Failure
+    }
+}
+
+impl From<ExitCode> for std::process::ExitCode {
+    fn from(code: ExitCode) -> Self {
+        std::process::ExitCode::from(code as u8)
+    }
+}
+
+/// The error returned by commands whose purpose is a check when the check
+/// completes with a negative result.
+///
+/// Failing to perform the check (e.g., because a file is missing) is
+/// reported by other errors, so that the two situations are mapped to
+/// different [exit codes](ExitCode). Note that the commands of the `check`
+/// group use [their own exit codes](crate::cli::check::main).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckFailed(pub String);
+
+impl Display for CheckFailed {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for CheckFailed {}
+
+/// An error exiting with a given code, regardless of its causes.
+///
+/// The error displays as the wrapped error, and has the same causes.
+#[derive(Debug)]
+pub struct Exit(pub ExitCode, pub anyhow::Error);
+
+impl Display for Exit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.1, f)
+    }
+}
+
+impl std::error::Error for Exit {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.1.source()
+    }
+}
+
+/// Like [`anyhow::ensure!`], but returns a [`CheckFailed`] error.
+macro_rules! ensure_check {
+    ($cond:expr, $($arg:tt)+) => {
+        if !$cond {
+            return Err($crate::cli::CheckFailed(format!($($arg)+)).into());
+        }
+    };
+}
+
+pub(crate) use ensure_check;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::{anyhow, Context};
+
+    #[test]
+    fn test_exit_code() {
+        assert_eq!(ExitCode::of(&anyhow!("generic")), ExitCode::Failure);
+        let not_found = std::fs::File::open("/nonexistent/file")
+            .context("Could not open file")
+            .unwrap_err();
+        assert_eq!(ExitCode::of(&not_found), ExitCode::Input);
+        let check: anyhow::Error = CheckFailed("negative".into()).into();
+        assert_eq!(ExitCode::of(&check), ExitCode::CheckFailed);
+        let check = check.context("While checking");
+        assert_eq!(ExitCode::of(&check), ExitCode::CheckFailed);
+        let interrupted: anyhow::Error =
+            std::io::Error::from(std::io::ErrorKind::Interrupted).into();
+        assert_eq!(ExitCode::of(&interrupted), ExitCode::Interrupted);
+        let exit: anyhow::Error = Exit(ExitCode::Failure, check).into();
+        assert_eq!(ExitCode::of(&exit), ExitCode::Failure);
+        assert_eq!(exit.to_string(), "While checking");
+    }
+}
@@ -9,15 +9,16 @@ use crate::cli::create_parent_dir;
 use crate::cli::*;
 use crate::graphs::arc_list_graph::ArcListGraph;
 use crate::prelude::*;
-use anyhow::Result;
+use crate::utils::sort_pairs::{CountDuplicates, DuplicateArcs};
+use anyhow::{bail, ensure, Context, Result};
 use clap::{ArgMatches, Args, Command, FromArgMatches};
-use dsi_bitstream::prelude::{Endianness, BE};
+use dsi_bitstream::prelude::*;
 use dsi_progress_logger::prelude::*;
-use itertools::Itertools;
 use rayon::prelude::ParallelSliceMut;
 use std::collections::HashMap;
+use std::fs::File;
 use std::io::{BufRead, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tempfile::Builder;
 pub const COMMAND_NAME: &str = "arcs";
 
@@ -30,7 +31,8 @@ pub struct CliArgs {
     pub dst: PathBuf,
 
     #[arg(long)]
-    /// The number of nodes in the graph.
+    /// The number of nodes in the graph (with --one-based, the largest node
+    /// identifier).
     pub num_nodes: usize,
 
     #[arg(long)]
@@ -40,12 +42,41 @@ pub struct CliArgs {
     #[clap(flatten)]
     pub arcs_args: ArcsArgs,
 
+    #[arg(long)]
+    /// Preserve duplicate arcs: the graph will contain each arc once, and the
+    /// multiplicity of each arc will be stored, in graph order, as a γ-coded
+    /// label (minus one) in a big-endian file with extension .labels; the
+    /// γ-coded differences between the bit offsets of the labels of
+    /// consecutive nodes (starting with a zero) will be stored in a
+    /// big-endian file with extension .labeloffsets. Without this option,
+    /// duplicate arcs are removed.
+    pub multigraph: bool,
+
+    #[arg(long)]
+    /// Builds also the transpose of the graph with the given basename,
+    /// sorting the reversed arcs during the same ingestion pass. The
+    /// properties of the two graphs will record each other as counterpart;
+    /// with --multigraph, multiplicities are stored for the transpose, too.
+    pub also_transpose: Option<PathBuf>,
+
+    #[arg(long)]
+    /// Import only the subgraph induced by the nodes listed, one per line, in
+    /// the given file: arcs with an endpoint not in the list are dropped.
+    /// With --exact, the listed identifiers are compacted preserving their
+    /// order, and the original identifier of each node is saved in the
+    /// .nodes file; otherwise, the listed labels are numbered in the order
+    /// of the file.
+    pub node_filter: Option<PathBuf>,
+
     #[clap(flatten)]
     pub num_threads: NumThreadsArg,
 
     #[clap(flatten)]
     pub batch_size: BatchSizeArg,
 
+    #[clap(flatten)]
+    pub compress_tmp: CompressTmpArg,
+
     #[clap(flatten)]
     pub ca: CompressArgs,
 }
@@ -58,24 +89,86 @@ pub fn main(submatches: &ArgMatches) -> Result<()> {
     from_csv(CliArgs::from_arg_matches(submatches)?)
 }
 
+/// Reads the arcs from standard input and compresses the resulting graph.
 pub fn from_csv(args: CliArgs) -> Result<()> {
+    from_reader(args, std::io::stdin().lock())
+}
+
+/// Reads the arcs from the given reader and compresses the resulting graph.
+pub fn from_reader(args: CliArgs, reader: impl BufRead) -> Result<()> {
+    let start = std::time::Instant::now();
     let dir = Builder::new().prefix("from_arcs_sort_").tempdir()?;
 
-    let mut group_by = SortPairs::new(args.batch_size.batch_size, &dir)?;
+    // with --multigraph, duplicates are counted when building the graph
+    let new_sort_pairs = |dir: &Path| -> Result<SortPairs> {
+        Ok(SortPairs::new(args.batch_size.batch_size, dir)?
+            .duplicates(if args.multigraph {
+                DuplicateArcs::Keep
+            } else {
+                DuplicateArcs::Remove
+            })
+            .compression(args.compress_tmp.compress_tmp))
+    };
+    let mut group_by = new_sort_pairs(dir.path())?;
+    // the transpose shares the parsed arc stream, but needs its own batches
+    let transpose_dir = Builder::new()
+        .prefix("from_arcs_sort_transpose_")
+        .tempdir()?;
+    let mut transpose_group_by = args
+        .also_transpose
+        .as_ref()
+        .map(|_| new_sort_pairs(transpose_dir.path()))
+        .transpose()?;
     let mut nodes = HashMap::new();
+    // with --node-filter, only listed nodes are known in advance
+    let mut compaction = None;
+    if let Some(path) = &args.node_filter {
+        let listed = read_node_filter(path)?;
+        if args.arcs_args.exact {
+            let ids = listed
+                .iter()
+                .enumerate()
+                .map(|(i, id)| parse_id(id, args.arcs_args.one_based, i))
+                .collect::<Result<Vec<_>>>()
+                .with_context(|| format!("Could not parse node filter {}", path.display()))?;
+            if let Some(&id) = ids.iter().find(|&&id| id >= args.num_nodes) {
+                bail!(
+                    "Node {} in node filter {} is out of bounds (the graph has {} nodes)",
+                    id,
+                    path.display(),
+                    args.num_nodes
+                );
+            }
+            compaction = Some(NodeCompaction::new(args.num_nodes, ids));
+        } else {
+            for label in listed {
+                let node_id = nodes.len();
+                nodes.entry(label).or_insert(node_id);
+            }
+        }
+    }
+    let filter_labels = args.node_filter.is_some() && !args.arcs_args.exact;
+    let mut num_kept_arcs = 0;
 
     // read the csv and put it inside the sort pairs
-    let stdin = std::io::stdin();
     let mut pl = ProgressLogger::default();
     pl.display_memory(true)
         .item_name("lines")
         .expected_updates(args.arcs_args.max_lines.or(args.num_arcs));
     pl.start("Reading arcs CSV");
 
-    let mut iter = stdin.lock().lines();
+    let mut iter = reader.lines();
     // skip the first few lines
     for _ in 0..args.arcs_args.lines_to_skip {
-        iter.next().unwrap().unwrap();
+        // the input might be shorter than the lines to skip (e.g., empty)
+        if iter
+            .next()
+            .transpose()
+            .context("Could not read line")?
+            .is_none()
+        {
+            break;
+        }
     }
     let mut line_id = 0;
     for line in iter {
@@ -86,8 +179,8 @@ pub fn from_csv(args: CliArgs) -> Result<()> {
             }
         }
         let line = line.unwrap();
-        // skip comment
-        if line.trim().starts_with(args.arcs_args.line_comment_simbol) {
+        // skip comments and empty lines
+        if line.trim().is_empty() || line.trim().starts_with(args.arcs_args.line_comment_simbol) {
             continue;
         }
 
@@ -96,65 +189,252 @@ pub fn from_csv(args: CliArgs) -> Result<()> {
         let src = vals[0];
         let dst = vals[1];
 
-        // parse if exact, or build a node list
-        let src_id = if args.arcs_args.exact {
-            src.parse::<usize>().unwrap()
+        // parse if exact, or build a node list, dropping filtered arcs
+        let arc = if args.arcs_args.exact {
+            let src_id = parse_id(src, args.arcs_args.one_based, line_id)?;
+            let dst_id = parse_id(dst, args.arcs_args.one_based, line_id)?;
+            match &compaction {
+                Some(compaction) => compaction.arc(src_id, dst_id),
+                None => Some((src_id, dst_id)),
+            }
+        } else if filter_labels {
+            nodes.get(src).copied().zip(nodes.get(dst).copied())
         } else {
             let node_id = nodes.len();
-            *nodes.entry(src.to_string()).or_insert(node_id)
-        };
-        let dst_id = if args.arcs_args.exact {
-            dst.parse::<usize>().unwrap()
-        } else {
+            let src_id = *nodes.entry(src.to_string()).or_insert(node_id);
             let node_id = nodes.len();
-            *nodes.entry(dst.to_string()).or_insert(node_id)
+            let dst_id = *nodes.entry(dst.to_string()).or_insert(node_id);
+            Some((src_id, dst_id))
         };
 
-        group_by.push(src_id, dst_id).unwrap();
+        if let Some((src_id, dst_id)) = arc {
+            group_by.push(src_id, dst_id)?;
+            if let Some(transpose_group_by) = &mut transpose_group_by {
+                transpose_group_by.push(dst_id, src_id)?;
+            }
+            num_kept_arcs += 1;
+        }
         pl.light_update();
         line_id += 1;
     }
     pl.done();
     log::info!("Arcs read: {}", line_id);
 
-    // convert the iter to a graph
-    let g = Left(ArcListGraph::new(
-        args.num_nodes,
-        group_by
-            .iter()
-            .unwrap()
-            .map(|(src, dst, _)| (src, dst))
-            .dedup(),
-    ));
+    let num_nodes = match &compaction {
+        Some(compaction) => compaction.num_kept(),
+        None if filter_labels => nodes.len(),
+        None => args.num_nodes,
+    };
+    if args.node_filter.is_some() {
+        log::info!(
+            "Arcs surviving the node filter: {} out of {}",
+            num_kept_arcs,
+            line_id
+        );
+        log::info!("Nodes surviving the node filter: {}", num_nodes);
+    }
 
     create_parent_dir(&args.dst)?;
+    let output = GraphOutput::new(&args.dst);
 
     // compress it
-    let target_endianness = args.ca.endianness.clone();
-    let dir = Builder::new().prefix("from_arcs_compress_").tempdir()?;
+    let target_endianness = args
+        .ca
+        .endianness
+        .clone()
+        .unwrap_or_else(|| BE::NAME.into());
+    let comp_flags: CompFlags = args.ca.into();
     let thread_pool = crate::cli::get_thread_pool(args.num_threads.num_threads);
-    BvComp::parallel_endianness(
+    let num_arcs = compress_sorted(
         &args.dst,
-        &g,
-        args.num_nodes,
-        args.ca.into(),
+        &mut group_by,
+        num_nodes,
+        args.multigraph,
+        comp_flags,
         &thread_pool,
-        dir,
-        &target_endianness.unwrap_or_else(|| BE::NAME.into()),
-    )
-    .unwrap();
+        &target_endianness,
+    )?;
+    log::info!("Arcs in the graph: {}", num_arcs);
+
+    if let (Some(transpose_dst), Some(mut transpose_group_by)) =
+        (&args.also_transpose, transpose_group_by)
+    {
+        create_parent_dir(transpose_dst)?;
+        let transpose_output = GraphOutput::new(transpose_dst);
+        // both graphs have num_nodes nodes, so they are consistent
+        let transpose_num_arcs = compress_sorted(
+            transpose_dst,
+            &mut transpose_group_by,
+            num_nodes,
+            args.multigraph,
+            comp_flags,
+            &thread_pool,
+            &target_endianness,
+        )?;
+        log::info!("Arcs in the transpose: {}", transpose_num_arcs);
+        set_direction(&args.dst, ArcDirection::Forward, transpose_dst)?;
+        set_direction(transpose_dst, ArcDirection::Backward, &args.dst)?;
+        transpose_output.finish()?;
+    }
+    output.finish()?;
+
+    // save the original identifiers of the surviving nodes
+    if let Some(compaction) = &compaction {
+        let nodes_path = Basename::new(&args.dst).with_ext("nodes");
+        let mut buf = OutputFile::create(&nodes_path)?;
+        let offset = args.arcs_args.one_based as usize;
+        for &node in compaction.kept() {
+            writeln!(buf, "{}", node + offset)
+                .with_context(|| format!("Could not write to {}", nodes_path.display()))?;
+        }
+        buf.finish()?;
+    }
 
     // save the nodes
     if !args.arcs_args.exact {
-        let mut file = std::fs::File::create(args.dst.with_extension("nodes")).unwrap();
-        let mut buf = std::io::BufWriter::new(&mut file);
+        let nodes_path = Basename::new(&args.dst).with_ext("nodes");
+        let mut buf = OutputFile::create(&nodes_path)?;
         let mut nodes = nodes.into_iter().collect::<Vec<_>>();
         // sort based on the idx
         nodes.par_sort_by(|(_, a), (_, b)| a.cmp(b));
         for (node, _) in nodes {
-            buf.write_all(node.as_bytes()).unwrap();
-            buf.write_all(b"\n").unwrap();
+            buf.write_all(node.as_bytes())
+                .and_then(|_| buf.write_all(b"\n"))
+                .with_context(|| format!("Could not write to {}", nodes_path.display()))?;
         }
+        buf.finish()?;
+    }
+    log::info!("Completed in {} seconds", start.elapsed().as_secs_f64());
+    Ok(())
+}
+
+/// Compresses the graph described by the arcs sorted by `sort_pairs`,
+/// storing their multiplicities if `multigraph` is true, and returns the
+/// number of arcs of the compressed graph.
+fn compress_sorted(
+    basename: &Path,
+    sort_pairs: &mut SortPairs,
+    num_nodes: usize,
+    multigraph: bool,
+    comp_flags: CompFlags,
+    thread_pool: &rayon::ThreadPool,
+    endianness: &str,
+) -> Result<u64> {
+    let dir = Builder::new().prefix("from_arcs_compress_").tempdir()?;
+    if multigraph {
+        let g = Left(ArcListGraph::new_labeled(
+            num_nodes,
+            CountDuplicates::new(sort_pairs.iter()?),
+        ));
+        BvComp::parallel_endianness(
+            basename,
+            &g,
+            num_nodes,
+            comp_flags,
+            thread_pool,
+            dir,
+            endianness,
+        )?;
+        write_multiplicities(
+            basename,
+            num_nodes,
+            CountDuplicates::new(sort_pairs.iter()?),
+        )?;
+    } else {
+        let g = Left(ArcListGraph::new(
+            num_nodes,
+            sort_pairs.iter()?.map(|(src, dst, _)| (src, dst)),
+        ));
+        BvComp::parallel_endianness(
+            basename,
+            &g,
+            num_nodes,
+            comp_flags,
+            thread_pool,
+            dir,
+            endianness,
+        )?;
+    }
+
+    let path = Basename::new(basename).with_ext(PROPERTIES_EXTENSION);
+    let f = File::open(&path).with_context(|| format!("Cannot open {}", path.display()))?;
+    let map = java_properties::read(std::io::BufReader::new(f))
+        .with_context(|| format!("Cannot parse {}", path.display()))?;
+    map.get("arcs")
+        .with_context(|| format!("Missing 'arcs' property in {}", path.display()))?
+        .parse::<u64>()
+        .with_context(|| format!("Cannot parse 'arcs' property in {}", path.display()))
+}
+
+/// Reads the nodes listed in a node filter, one per line, skipping empty
+/// lines.
+fn read_node_filter(path: &Path) -> Result<Vec<String>> {
+    let file = File::open(path)
+        .with_context(|| format!("Could not open node filter {}", path.display()))?;
+    let mut listed = vec![];
+    for line in std::io::BufReader::new(file).lines() {
+        let line =
+            line.with_context(|| format!("Could not read node filter {}", path.display()))?;
+        let line = line.trim();
+        if !line.is_empty() {
+            listed.push(line.to_string());
+        }
+    }
+    Ok(listed)
+}
+
+/// Parses a node identifier, subtracting one if identifiers are one-based.
+fn parse_id(id: &str, one_based: bool, line_id: usize) -> Result<usize> {
+    let id = id.trim().parse::<usize>().with_context(|| {
+        format!(
+            "Could not parse node identifier {:?} in arc {}",
+            id, line_id
+        )
+    })?;
+    if one_based {
+        ensure!(
+            id != 0,
+            "Node identifier 0 in arc {} is not valid with --one-based",
+            line_id
+        );
+        Ok(id - 1)
+    } else {
+        Ok(id)
+    }
+}
+
+/// Writes the multiplicities of a sorted list of arcs as a bitstream of
+/// γ-coded labels (minus one) with γ-coded offsets.
+fn write_multiplicities(
+    basename: impl AsRef<Path>,
+    num_nodes: usize,
+    arcs: impl Iterator<Item = (usize, usize, usize)>,
+) -> Result<()> {
+    let basename = Basename::new(basename);
+    let labels_path = basename.with_ext("labels");
+    let mut labels = <BufBitWriter<BE, _>>::new(<WordAdapter<usize, _>>::new(OutputFile::create(
+        &labels_path,
+    )?));
+    let offsets_path = basename.with_ext("labeloffsets");
+    let mut offsets = <BufBitWriter<BE, _>>::new(<WordAdapter<usize, _>>::new(OutputFile::create(
+        &offsets_path,
+    )?));
+
+    offsets.write_gamma(0)?;
+    let mut arcs = arcs.peekable();
+    for node in 0..num_nodes {
+        let mut bits = 0;
+        while let Some((_, _, count)) = arcs.next_if(|&(src, _, _)| src == node) {
+            bits += labels.write_gamma(count as u64 - 1)?;
+        }
+        offsets.write_gamma(bits as u64)?;
+    }
+    for (stream, path) in [(labels, labels_path), (offsets, offsets_path)] {
+        stream
+            .into_inner()
+            .with_context(|| format!("Could not flush {}", path.display()))?
+            .into_inner()
+            .finish()?;
     }
     Ok(())
 }
@@ -8,6 +8,7 @@ use anyhow::Result;
 use clap::{ArgMatches, Command};
 
 pub mod arcs;
+pub mod twitter_edge_list;
 
 pub const COMMAND_NAME: &str = "from";
 
@@ -18,12 +19,14 @@ pub fn cli(command: Command) -> Command {
         .arg_required_else_help(true)
         .allow_external_subcommands(true);
     let sub_command = arcs::cli(sub_command);
+    let sub_command = twitter_edge_list::cli(sub_command);
     command.subcommand(sub_command.display_order(0))
 }
 
 pub fn main(submatches: &ArgMatches) -> Result<()> {
     match submatches.subcommand() {
         Some((arcs::COMMAND_NAME, sub_m)) => arcs::main(sub_m),
+        Some((twitter_edge_list::COMMAND_NAME, sub_m)) => twitter_edge_list::main(sub_m),
         Some((command_name, _)) => {
             eprintln!("Unknown command: {:?}", command_name);
             std::process::exit(1);
@@ -0,0 +1,186 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::cli::create_parent_dir;
+use crate::cli::*;
+use crate::graphs::arc_list_graph::ArcListGraph;
+use crate::prelude::*;
+use crate::utils::sort_pairs::DuplicateArcs;
+use anyhow::{anyhow, Context, Result};
+use clap::{ArgMatches, Args, Command, FromArgMatches};
+use dsi_bitstream::prelude::{Endianness, BE};
+use dsi_progress_logger::prelude::*;
+use flate2::read::MultiGzDecoder;
+use std::cmp::Ordering;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use tempfile::Builder;
+
+pub const COMMAND_NAME: &str = "twitter-edge-list";
+
+#[derive(Args, Debug)]
+#[command(
+    about = "Creates a new BvGraph from a Twitter-style edge list, that is, a (possibly gzip-compressed) file containing one arc per line specified by two string user identifiers. Lines starting with % or # are ignored. Identifiers are numbered in increasing order (numerically, if they are decimal numbers without leading zeros, irrespective of their size), and the identifier of each node will be saved, one per line, in a file with the same basename of the graph and extension .nodes."
+)]
+pub struct CliArgs {
+    /// The edge-list file (gzip compression is detected automatically).
+    pub src: PathBuf,
+
+    /// The basename of the graph.
+    pub dst: PathBuf,
+
+    #[arg(long)]
+    /// The column separator (default: any whitespace).
+    pub separator: Option<char>,
+
+    #[clap(flatten)]
+    pub num_threads: NumThreadsArg,
+
+    #[clap(flatten)]
+    pub batch_size: BatchSizeArg,
+
+    #[clap(flatten)]
+    pub compress_tmp: CompressTmpArg,
+
+    #[clap(flatten)]
+    pub ca: CompressArgs,
+}
+
+pub fn cli(command: Command) -> Command {
+    command.subcommand(CliArgs::augment_args(Command::new(COMMAND_NAME)).display_order(0))
+}
+
+pub fn main(submatches: &ArgMatches) -> Result<()> {
+    from_twitter_edge_list(CliArgs::from_arg_matches(submatches)?)
+}
+
+/// Opens a file, decompressing it on the fly if it starts with the gzip magic
+/// number.
+fn open(path: &Path) -> Result<Box<dyn BufRead>> {
+    let mut reader = BufReader::new(
+        File::open(path).with_context(|| format!("Could not open {}", path.display()))?,
+    );
+    let gzipped = reader.fill_buf()?.starts_with(&[0x1f, 0x8b]);
+    Ok(if gzipped {
+        Box::new(BufReader::new(MultiGzDecoder::new(reader)))
+    } else {
+        Box::new(reader)
+    })
+}
+
+/// Orders identifiers by length and then lexicographically, which is the
+/// numerical order for decimal numbers without leading zeros.
+fn cmp_ids(a: &str, b: &str) -> Ordering {
+    a.len().cmp(&b.len()).then_with(|| a.cmp(b))
+}
+
+/// Calls `f` on the source and target identifier of each arc of the edge list.
+fn for_each_arc(
+    path: &Path,
+    separator: Option<char>,
+    pl: &mut impl ProgressLog,
+    mut f: impl FnMut(&str, &str) -> Result<()>,
+) -> Result<()> {
+    for (line_num, line) in open(path)?.lines().enumerate() {
+        let line = line.with_context(|| format!("Could not read {}", path.display()))?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('%') || line.starts_with('#') {
+            continue;
+        }
+        let mut fields: Box<dyn Iterator<Item = &str>> = match separator {
+            Some(separator) => Box::new(line.split(separator).map(str::trim)),
+            None => Box::new(line.split_whitespace()),
+        };
+        let (src, dst) = fields.next().zip(fields.next()).ok_or_else(|| {
+            anyhow!(
+                "Line {} of {} does not contain two identifiers: {:?}",
+                line_num + 1,
+                path.display(),
+                line
+            )
+        })?;
+        f(src, dst)?;
+        pl.light_update();
+    }
+    Ok(())
+}
+
+pub fn from_twitter_edge_list(args: CliArgs) -> Result<()> {
+    let mut pl = ProgressLogger::default();
+    pl.display_memory(true).item_name("arc");
+
+    // First pass: build the sorted dictionary of identifiers
+    pl.start("Collecting identifiers...");
+    let mut ids = std::collections::HashSet::<Box<str>>::new();
+    for_each_arc(&args.src, args.separator, &mut pl, |src, dst| {
+        for id in [src, dst] {
+            if !ids.contains(id) {
+                ids.insert(id.into());
+            }
+        }
+        Ok(())
+    })?;
+    pl.done();
+    let mut ids = ids.into_iter().collect::<Vec<_>>();
+    ids.sort_unstable_by(|a, b| cmp_ids(a, b));
+    let num_nodes = ids.len();
+    log::info!("Found {} distinct identifiers", num_nodes);
+
+    // Second pass: map identifiers to nodes and sort the arcs
+    let dir = Builder::new().prefix("from_twitter_sort_").tempdir()?;
+    let mut sort_pairs = SortPairs::new(args.batch_size.batch_size, &dir)?
+        .duplicates(DuplicateArcs::Remove)
+        .compression(args.compress_tmp.compress_tmp);
+    // The input might have changed between the two passes
+    let node = |id: &str| {
+        ids.binary_search_by(|x| cmp_ids(x, id)).map_err(|_| {
+            anyhow!(
+                "Identifier {} of {} was not found in the first pass: the input changed between passes",
+                id,
+                args.src.display()
+            )
+        })
+    };
+    pl.start("Reading arcs...");
+    for_each_arc(&args.src, args.separator, &mut pl, |src, dst| {
+        sort_pairs.push(node(src)?, node(dst)?)
+    })?;
+    pl.done();
+
+    let g = Left(ArcListGraph::new(
+        num_nodes,
+        sort_pairs.iter()?.map(|(src, dst, _)| (src, dst)),
+    ));
+
+    create_parent_dir(&args.dst)?;
+    let output = GraphOutput::new(&args.dst);
+
+    let target_endianness = args.ca.endianness.clone();
+    let dir = Builder::new().prefix("from_twitter_compress_").tempdir()?;
+    let thread_pool = crate::cli::get_thread_pool(args.num_threads.num_threads);
+    BvComp::parallel_endianness(
+        &args.dst,
+        &g,
+        num_nodes,
+        args.ca.into(),
+        &thread_pool,
+        dir,
+        &target_endianness.unwrap_or_else(|| BE::NAME.into()),
+    )?;
+    output.finish()?;
+
+    // save the identifiers
+    let nodes_path = Basename::new(&args.dst).with_ext("nodes");
+    let mut nodes = OutputFile::create(&nodes_path)?;
+    for id in &ids {
+        writeln!(nodes, "{}", id)
+            .with_context(|| format!("Could not write to {}", nodes_path.display()))?;
+    }
+    nodes.finish()?;
+
+    Ok(())
+}
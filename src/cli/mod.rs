@@ -11,11 +11,18 @@
 //! implemented as a submodule.
 
 use crate::build_info;
-use crate::graphs::bvgraph::Code;
-use crate::prelude::CompFlags;
+use crate::graphs::bvgraph::{
+    canonicalize_basename, get_direction, is_symmetric, ArcDirection, Code,
+};
+use crate::prelude::{BitDeserializer, BitSerializer, CompFlags, ReferenceSelection};
+use crate::utils::PermMode;
 use anyhow::{anyhow, ensure, Context, Result};
-use clap::{Args, Command, ValueEnum};
+use clap::{ArgMatches, Args, Command, FromArgMatches, ValueEnum};
 use common_traits::UnsignedInt;
+use dsi_bitstream::prelude::{Endianness, GammaRead, GammaWrite};
+use epserde::deser::Deserialize;
+use epserde::ser::Serialize;
+use std::io::Write;
 use std::path::{Path, PathBuf};
 use sysinfo::System;
 
@@ -23,12 +30,20 @@ pub mod analyze;
 pub mod bench;
 pub mod build;
 pub mod check;
+pub mod dist;
 pub mod from;
 pub mod perm;
+pub mod pipeline;
+pub mod rank;
 pub mod run;
 pub mod to;
 pub mod transform;
 
+mod exit;
+pub use exit::*;
+mod output;
+pub use output::*;
+
 pub const DEFAULT_STACK_SIZE: usize = 64 * 1024 * 1024;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
@@ -65,6 +80,154 @@ impl From<PrivCode> for Code {
     }
 }
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+/// Strategies to choose references.
+///
+/// It is used to implement [`ValueEnum`] here instead of in the library.
+pub enum RefStrategy {
+    /// Stop at the first candidate that does not improve the estimated cost.
+    Greedy,
+    /// Choose the candidate with the smallest estimated cost in the window.
+    BestOfWindow,
+    /// Do not use references (the compression window is set to zero).
+    None,
+}
+
+impl From<RefStrategy> for ReferenceSelection {
+    fn from(value: RefStrategy) -> Self {
+        match value {
+            RefStrategy::Greedy => ReferenceSelection::Greedy,
+            RefStrategy::BestOfWindow => ReferenceSelection::BestOfWindow,
+            RefStrategy::None => ReferenceSelection::None,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+/// Formats for vectors of integers indexed by nodes.
+pub enum IntVectorFormat {
+    /// One value per line, in ASCII.
+    Ascii,
+    /// Big-endian 64-bit values, as in the permutations of the Java version.
+    Java,
+    /// A `Vec<usize>` serialized with ε-serde.
+    Epserde,
+    /// A [`BitFieldVec`](sux::bits::BitFieldVec) of minimal bit width
+    /// serialized with ε-serde.
+    Bitfieldvec,
+}
+
+impl IntVectorFormat {
+    /// Loads a vector of integers in this format.
+    pub fn load(&self, path: impl AsRef<Path>) -> Result<Vec<usize>> {
+        let path = path.as_ref();
+        match self {
+            IntVectorFormat::Ascii => {
+                let file = std::fs::File::open(path)
+                    .with_context(|| format!("Could not open {}", path.display()))?;
+                let mut data = vec![];
+                for (i, line) in std::io::BufRead::lines(std::io::BufReader::new(file)).enumerate()
+                {
+                    let line =
+                        line.with_context(|| format!("Could not read {}", path.display()))?;
+                    data.push(line.trim().parse().with_context(|| {
+                        format!("Could not parse line {} of {}", i + 1, path.display())
+                    })?);
+                }
+                Ok(data)
+            }
+            IntVectorFormat::Java => {
+                let bytes = std::fs::read(path)
+                    .with_context(|| format!("Could not read {}", path.display()))?;
+                ensure!(
+                    bytes.len() % 8 == 0,
+                    "The length of {} is not a multiple of 8",
+                    path.display()
+                );
+                Ok(bytes
+                    .chunks_exact(8)
+                    .map(|chunk| u64::from_be_bytes(chunk.try_into().unwrap()) as usize)
+                    .collect())
+            }
+            IntVectorFormat::Epserde => <Vec<usize>>::load_full(path)
+                .with_context(|| format!("Could not load {}", path.display())),
+            IntVectorFormat::Bitfieldvec => {
+                use sux::prelude::*;
+                let data = <BitFieldVec>::load_full(path)
+                    .with_context(|| format!("Could not load {}", path.display()))?;
+                Ok((0..data.len()).map(|i| data.get(i)).collect())
+            }
+        }
+    }
+
+    /// Stores a vector of integers in this format.
+    pub fn store(&self, path: impl AsRef<Path>, data: &[usize]) -> Result<()> {
+        let path = path.as_ref();
+        match self {
+            IntVectorFormat::Ascii => {
+                let mut writer = OutputFile::create(path)?;
+                for value in data {
+                    writeln!(writer, "{}", value)
+                        .with_context(|| format!("Could not write to {}", path.display()))?;
+                }
+                writer.finish()?;
+            }
+            IntVectorFormat::Java => {
+                let mut writer = OutputFile::create(path)?;
+                for &value in data {
+                    writer
+                        .write_all(&(value as u64).to_be_bytes())
+                        .with_context(|| format!("Could not write to {}", path.display()))?;
+                }
+                writer.finish()?;
+            }
+            IntVectorFormat::Epserde => {
+                let mut writer = OutputFile::create(path)?;
+                data.to_vec()
+                    .serialize(&mut writer)
+                    .with_context(|| format!("Could not store {}", path.display()))?;
+                writer.finish()?;
+            }
+            IntVectorFormat::Bitfieldvec => {
+                use sux::prelude::*;
+                let max = data.iter().copied().max().unwrap_or(0);
+                let bit_width = (usize::BITS - max.leading_zeros()) as usize;
+                let mut values = BitFieldVec::new(bit_width, data.len());
+                for (i, &value) in data.iter().enumerate() {
+                    values.set(i, value);
+                }
+                let mut writer = OutputFile::create(path)?;
+                values
+                    .serialize(&mut writer)
+                    .with_context(|| format!("Could not store {}", path.display()))?;
+                writer.finish()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+/// The (de)serializer of the γ-coded `u64` labels stored in `.labels` files,
+/// such as the multiplicities (minus one) written by `from arcs --multigraph`.
+pub struct GammaLabel;
+
+impl<E: Endianness, BW: GammaWrite<E>> BitSerializer<E, BW> for GammaLabel {
+    type SerType = u64;
+
+    fn serialize(&self, value: &u64, bitstream: &mut BW) -> Result<usize, BW::Error> {
+        bitstream.write_gamma(*value)
+    }
+}
+
+impl<E: Endianness, BR: GammaRead<E>> BitDeserializer<E, BR> for GammaLabel {
+    type DeserType = u64;
+
+    fn deserialize(&self, bitstream: &mut BR) -> Result<u64, BR::Error> {
+        bitstream.read_gamma()
+    }
+}
+
 #[derive(Args, Debug)]
 /// Shared CLI arguments for reading files containing arcs.
 pub struct ArcsArgs {
@@ -96,6 +259,12 @@ pub struct ArcsArgs {
     #[arg(long, default_value_t = false)]
     /// Source and destinations are node identifiers.
     pub exact: bool,
+
+    #[arg(long, default_value_t = false, requires = "exact")]
+    /// Node identifiers start from one: one is subtracted from each
+    /// identifier, and zero is rejected. The number of nodes of the resulting
+    /// graph is thus the largest identifier.
+    pub one_based: bool,
 }
 
 /// Shared CLI arguments for commands that specify a number of threads.
@@ -106,6 +275,35 @@ pub struct NumThreadsArg {
     pub num_threads: usize,
 }
 
+/// Shared CLI arguments for commands that check the number of decoded arcs.
+#[derive(Args, Debug)]
+pub struct ArcCountArg {
+    #[arg(long)]
+    /// Do not check that the number of decoded arcs is equal to the number of
+    /// arcs in the properties file.
+    pub no_arc_count_check: bool,
+}
+
+/// Shared CLI arguments for commands that apply a permutation to a graph.
+#[derive(Args, Debug, Clone, Copy)]
+pub struct PermModeArg {
+    #[arg(long)]
+    /// If the length of the permutation is different from the number of
+    /// nodes of the graph, pad missing entries with the identity and ignore
+    /// extra entries, rather than failing.
+    pub lenient: bool,
+}
+
+impl From<PermModeArg> for PermMode {
+    fn from(value: PermModeArg) -> Self {
+        if value.lenient {
+            PermMode::Lenient
+        } else {
+            PermMode::Strict
+        }
+    }
+}
+
 /// Shared CLI arguments for commands that specify a batch size.
 #[derive(Args, Debug)]
 pub struct BatchSizeArg {
@@ -117,6 +315,35 @@ pub struct BatchSizeArg {
     pub batch_size: usize,
 }
 
+/// Shared CLI arguments for commands that sort pairs using temporary files.
+#[derive(Args, Debug)]
+pub struct CompressTmpArg {
+    #[arg(long, value_name = "LEVEL", num_args = 0..=1, default_missing_value = "3")]
+    /// Compress the temporary batches of sorted pairs with Zstandard at the
+    /// given level (3 if the level is omitted). This option reduces the
+    /// temporary disk space and I/O at the cost of CPU time.
+    pub compress_tmp: Option<i32>,
+}
+
+/// Shared CLI arguments for commands that scan a graph sequentially.
+#[derive(Args, Debug)]
+pub struct PrefetchArg {
+    #[arg(long, value_name = "BYTES", value_parser = byte_size, num_args = 0..=1, default_missing_value = "8Mi")]
+    /// Prefetch chunks of the given size (8Mi if the size is omitted) of
+    /// the graph bitstream ahead of the decoder, overlapping I/O and
+    /// decoding. This option can substantially improve throughput on
+    /// spinning disks and network filesystems. You can use the SI and NIST
+    /// multipliers k, M, G, T, P, ki, Mi, Gi, Ti, and Pi.
+    pub prefetch: Option<usize>,
+}
+
+/// Parses a size in bytes, possibly followed by a SI or NIST multiplier
+/// (see [`batch_size`]).
+pub fn byte_size(arg: &str) -> anyhow::Result<usize> {
+    ensure!(!arg.trim().ends_with('%'), "percentages are not allowed");
+    batch_size(arg)
+}
+
 /// Parses a batch size.
 ///
 /// This function accepts either a number (possibly followed by a
@@ -183,6 +410,10 @@ pub struct CompressArgs {
     /// The maximum recursion depth for references (-1 for infinite recursion depth)
     #[clap(short = 'r', long, default_value_t = 3)]
     pub max_ref_count: isize,
+    /// The strategy used to choose references
+    #[arg(value_enum)]
+    #[clap(long, default_value = "best-of-window")]
+    pub ref_strategy: RefStrategy,
 
     #[arg(value_enum)]
     #[clap(long, default_value = "gamma")]
@@ -214,11 +445,16 @@ impl From<CompressArgs> for CompFlags {
             intervals: PrivCode::Gamma.into(),
             residuals: value.residuals.into(),
             min_interval_length: value.min_interval_length,
-            compression_window: value.compression_window,
+            // without references, a nonzero window would just waste a bit per node
+            compression_window: match value.ref_strategy {
+                RefStrategy::None => 0,
+                _ => value.compression_window,
+            },
             max_ref_count: match value.max_ref_count {
                 -1 => usize::MAX,
                 _ => value.max_ref_count as usize,
             },
+            reference_selection: value.ref_strategy.into(),
         }
     }
 }
@@ -239,15 +475,16 @@ pub fn get_thread_pool(num_threads: usize) -> rayon::ThreadPool {
 
 /// Appends a string to the filename of a path.
 ///
+/// The string is appended verbatim: dots in the filename are not interpreted
+/// as the start of an extension, and trailing separators are ignored.
+///
 /// # Panics
 /// * Will panic if there is no filename.
-/// * Will panic in test mode if the path has an extension.
 pub fn append(path: impl AsRef<Path>, s: impl AsRef<str>) -> PathBuf {
-    debug_assert!(path.as_ref().extension().is_none());
-    let mut path_buf = path.as_ref().to_owned();
+    let mut path_buf = path.as_ref().components().collect::<PathBuf>();
     let mut filename = path_buf.file_name().unwrap().to_owned();
     filename.push(s.as_ref());
-    path_buf.push(filename);
+    path_buf.set_file_name(filename);
     path_buf
 }
 
@@ -265,17 +502,195 @@ pub fn create_parent_dir(file_path: impl AsRef<Path>) -> Result<()> {
     Ok(())
 }
 
+/// Checks, using the direction information in the `.properties` files, that
+/// `transposed` is the transpose of the graph with basename `basename`.
+///
+/// If `transposed` does not contain direction information (e.g., it is a
+/// legacy graph) a warning is logged. If it is marked as a forward graph, or
+/// as the transpose of a different graph, an error is returned.
+pub fn check_transposed(basename: impl AsRef<Path>, transposed: impl AsRef<Path>) -> Result<()> {
+    let basename = basename.as_ref();
+    let transposed = transposed.as_ref();
+    match get_direction(transposed)? {
+        None => {
+            log::warn!(
+                "{} carries no direction information: cannot check that it is the transpose of {}",
+                transposed.display(),
+                basename.display()
+            );
+        }
+        Some((ArcDirection::Forward, _)) => {
+            anyhow::bail!(
+                "{} is marked as a forward graph, but it was passed as the transpose of {}",
+                transposed.display(),
+                basename.display()
+            );
+        }
+        Some((ArcDirection::Backward, counterpart)) => {
+            ensure!(
+                canonicalize_basename(&counterpart) == canonicalize_basename(basename),
+                "{} is marked as the transpose of {}, not of {}",
+                transposed.display(),
+                counterpart.display(),
+                basename.display()
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Checks, using the `.properties` file, that the graph with basename
+/// `basename` is symmetric, as required by `algorithm`.
+///
+/// If the graph carries no symmetry information (e.g., it is a legacy graph)
+/// a warning is logged. If it is marked as not symmetric, an error is
+/// returned.
+pub fn check_symmetric(basename: impl AsRef<Path>, algorithm: &str) -> Result<()> {
+    let basename = basename.as_ref();
+    match is_symmetric(basename)? {
+        None => {
+            log::warn!(
+                "{} carries no symmetry information: {} requires a symmetric graph (use \"webgraph transform simplify\" to symmetrize it)",
+                basename.display(),
+                algorithm
+            );
+        }
+        Some(false) => {
+            anyhow::bail!(
+                "{} is marked as not symmetric, but {} requires a symmetric graph (use \"webgraph transform simplify\" to symmetrize it)",
+                basename.display(),
+                algorithm
+            );
+        }
+        Some(true) => {}
+    }
+    Ok(())
+}
+
+macro_rules! impl_commands {
+    ($($module:ident),*) => {
+        /// Adds all commands to the given top-level command.
+        pub(crate) fn add_commands(command: Command) -> Command {
+            let command = build::cli(command);
+            $(
+                let command = $module::cli(command);
+            )*
+            command
+        }
+
+        /// Runs the command selected by the given subcommand matches of the
+        /// top-level command.
+        pub(crate) fn dispatch(
+            subcommand: (&str, &ArgMatches),
+            top_command: &mut Command,
+        ) -> Result<()> {
+            match subcommand {
+                (build::COMMAND_NAME, sub_m) => build::main(sub_m, top_command),
+                $(
+                    ($module::COMMAND_NAME, sub_m) => $module::main(sub_m),
+                )*
+                (command_name, _) => {
+                    // this shouldn't happen as clap should catch this
+                    eprintln!("Unknown command: {:?}", command_name);
+                    top_command.print_help().unwrap();
+                    std::process::exit(1);
+                }
+            }
+        }
+    };
+}
+
+impl_commands!(analyze, bench, check, dist, from, perm, pipeline, rank, run, to, transform);
+
+/// Flags available for all commands.
+#[derive(Args, Debug)]
+pub struct GlobalArgs {
+    #[arg(long, global = true)]
+    /// Sync output files to disk before reporting success.
+    pub fsync: bool,
+
+    #[arg(long, global = true)]
+    /// Keep partial output files after an error.
+    pub keep_partial: bool,
+
+    #[arg(long, global = true)]
+    /// Log only warnings and errors.
+    pub quiet: bool,
+
+    #[arg(long, global = true)]
+    /// Do not log progress, but keep all other messages.
+    pub no_progress: bool,
+}
+
+/// A logger filtering out progress messages.
+///
+/// Progress messages are recognized by their target, which is the
+/// [target](progress_log_target) of [`ProgressLogger::default`]; all progress
+/// loggers of the crate use it.
+struct NoProgressLogger {
+    logger: env_logger::Logger,
+    progress_target: String,
+}
+
+/// Returns the log target of [`ProgressLogger::default`], that is, the file
+/// name of the executable, or `main` if it is not available.
+fn progress_log_target() -> String {
+    std::env::current_exe()
+        .ok()
+        .and_then(|path| path.file_name()?.to_owned().into_string().ok())
+        .unwrap_or_else(|| "main".to_string())
+}
+
+impl log::Log for NoProgressLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        self.logger.enabled(metadata) && metadata.target() != self.progress_target
+    }
+
+    fn log(&self, record: &log::Record) {
+        if self.enabled(record.metadata()) {
+            self.logger.log(record);
+        }
+    }
+
+    fn flush(&self) {
+        self.logger.flush();
+    }
+}
+
+/// Initializes the logger, honoring the `--quiet` and `--no-progress`
+/// [global flags](GlobalArgs).
+fn init_logger(quiet: bool, no_progress: bool) {
+    let logger = env_logger::builder()
+        .filter_level(if quiet {
+            log::LevelFilter::Warn
+        } else {
+            log::LevelFilter::Debug
+        })
+        .build();
+    let max_level = logger.filter();
+    let result = if no_progress {
+        log::set_boxed_logger(Box::new(NoProgressLogger {
+            logger,
+            progress_target: progress_log_target(),
+        }))
+    } else {
+        log::set_boxed_logger(Box::new(logger))
+    };
+    // it's ok to fail since this might be called multiple times in tests
+    if result.is_ok() {
+        log::set_max_level(max_level);
+    }
+}
+
 /// The entry point of the command-line interface.
+///
+/// Errors can be mapped to exit codes using [`ExitCode::of`].
 pub fn main<I, T>(args: I) -> Result<()>
 where
     I: IntoIterator<Item = T>,
     T: Into<std::ffi::OsString> + Clone,
 {
     let start = std::time::Instant::now();
-    // it's ok to fail since this might be called multiple times in tests
-    let _ = env_logger::builder()
-        .filter_level(log::LevelFilter::Debug)
-        .try_init();
 
     let command = Command::new("webgraph")
         .about("Webgraph tools to build, convert, modify, and analyze webgraph files.")
@@ -286,40 +701,38 @@ where
             "Environment (noteworthy environment variables used):
 RUST_MIN_STACK: minimum thread stack size (in bytes)
 TMPDIR: where to store temporary files (potentially very large ones)
+
+Exit codes:
+0: success
+1: generic failure
+2: invalid command line
+3: missing, unreadable, or corrupt input
+4: negative result of a check
+5: interrupted
+Commands of the check group exit with 1 if an inconsistency is found, and
+with 2 on any other error.
 ",
         );
-
-    macro_rules! impl_dispatch {
-        ($command:expr, $($module:ident),*) => {{
-            let command = build::cli($command);
-            $(
-                let command = $module::cli(command);
-            )*
-            let command = command.display_order(0); // sort args alphabetically
-            let mut completion_command = command.clone();
-            let matches = command.get_matches_from(args);
-            let subcommand = matches.subcommand();
-            // if no command is specified, print the help message
-            if subcommand.is_none() {
-                completion_command.print_help().unwrap();
-                return Ok(());
-            }
-            match subcommand.unwrap() {
-                (build::COMMAND_NAME, sub_m) => build::main(sub_m, &mut completion_command),
-                $(
-                    ($module::COMMAND_NAME, sub_m) => $module::main(sub_m),
-                )*
-                (command_name, _) => {
-                    // this shouldn't happen as clap should catch this
-                    eprintln!("Unknown command: {:?}", command_name);
-                    completion_command.print_help().unwrap();
-                    std::process::exit(1);
-                }
-            }
-        }};
+    let command = GlobalArgs::augment_args(command);
+
+    let command = add_commands(command).display_order(0); // sort args alphabetically
+    let mut completion_command = command.clone();
+    let matches = command.get_matches_from(args);
+    let global_args = GlobalArgs::from_arg_matches(&matches)?;
+    init_logger(global_args.quiet, global_args.no_progress);
+    OutputOptions {
+        fsync: global_args.fsync,
+        keep_partial: global_args.keep_partial,
+    }
+    .set_global();
+    // if no command is specified, print the help message
+    match matches.subcommand() {
+        None => {
+            completion_command.print_help().unwrap();
+            return Ok(());
+        }
+        Some(subcommand) => dispatch(subcommand, &mut completion_command)?,
     }
-
-    impl_dispatch!(command, analyze, bench, check, from, perm, run, to, transform)?;
 
     log::info!(
         "The command took {}",
@@ -330,7 +743,7 @@ TMPDIR: where to store temporary files (potentially very large ones)
 }
 
 /// Pretty prints seconds in a humanly readable format.
-fn pretty_print_elapsed(elapsed: f64) -> String {
+pub(crate) fn pretty_print_elapsed(elapsed: f64) -> String {
     let mut result = String::new();
     let mut elapsed_seconds = elapsed as u64;
     let weeks = elapsed_seconds / (60 * 60 * 24 * 7);
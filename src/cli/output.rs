@@ -0,0 +1,493 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+//! Output files for the command-line interface.
+//!
+//! All commands writing files (graphs, offsets, vectors, permutations) should
+//! write them through an [`OutputFile`], which guarantees that a file is
+//! flushed (and, optionally, synced to disk) before success is reported, and
+//! that partial files do not survive errors or panics. Commands with an
+//! optional output file write through a [`FileOrStdout`], and graphs written
+//! by the library are protected by a [`GraphOutput`].
+
+use crate::prelude::*;
+use crate::utils::interrupt::check_interrupted;
+use anyhow::{Context, Result};
+use std::fs::File;
+use std::io::{BufWriter, Seek, SeekFrom, StdoutLock, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Instant, SystemTime};
+
+/// The size of the buffer of an [`OutputFile`].
+const BUFFER_SIZE: usize = 1 << 20;
+
+static FSYNC: AtomicBool = AtomicBool::new(false);
+static KEEP_PARTIAL: AtomicBool = AtomicBool::new(false);
+
+/// Options controlling the behavior of [`OutputFile`]s.
+///
+/// The CLI sets the global options from the top-level `--fsync` and
+/// `--keep-partial` flags, and [`OutputFile::create`] uses them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct OutputOptions {
+    /// Whether to sync the file to disk before reporting success.
+    pub fsync: bool,
+    /// Whether to keep partial files after an error or a panic.
+    pub keep_partial: bool,
+}
+
+impl OutputOptions {
+    /// Returns the global options.
+    pub fn global() -> Self {
+        Self {
+            fsync: FSYNC.load(Ordering::Relaxed),
+            keep_partial: KEEP_PARTIAL.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Sets the global options.
+    pub fn set_global(self) {
+        FSYNC.store(self.fsync, Ordering::Relaxed);
+        KEEP_PARTIAL.store(self.keep_partial, Ordering::Relaxed);
+    }
+
+    /// Syncs to disk a file written by other means (e.g., by the library),
+    /// if the `fsync` option is set.
+    pub fn sync_path(&self, path: impl AsRef<Path>) -> Result<()> {
+        if self.fsync {
+            let path = path.as_ref();
+            File::open(path)
+                .and_then(|file| file.sync_all())
+                .with_context(|| format!("Could not sync {}", path.display()))?;
+        }
+        Ok(())
+    }
+}
+
+/// The length and the modification time of a file, if it exists.
+fn file_state(path: &Path) -> Option<(u64, SystemTime)> {
+    let metadata = std::fs::metadata(path).ok()?;
+    Some((metadata.len(), metadata.modified().ok()?))
+}
+
+/// The graph, offsets and properties files of a graph written by the library
+/// (e.g., by [`BvComp`]).
+///
+/// A graph output must be created before the library starts writing the
+/// graph, and committed by calling [`finish`](GraphOutput::finish), which
+/// syncs the files to disk if the `fsync` [option](OutputOptions) is set. If
+/// the graph output is dropped without being finished (e.g., because the
+/// compression failed or has been interrupted), the files that have been
+/// created or modified after its creation are removed, unless the
+/// `keep_partial` option is set. Files that have not been touched (e.g., those
+/// of a previous version of the graph, if the source cannot be loaded) are
+/// left alone.
+#[derive(Debug)]
+pub struct GraphOutput {
+    /// The paths of the files, and their state when the output was created.
+    files: Vec<(PathBuf, Option<(u64, SystemTime)>)>,
+    options: OutputOptions,
+    finished: bool,
+}
+
+impl GraphOutput {
+    /// Creates a new graph output with the given basename using the
+    /// [global options](OutputOptions::global).
+    pub fn new(basename: impl AsRef<Path>) -> Self {
+        Self::with_options(basename, OutputOptions::global())
+    }
+
+    /// Creates a new graph output with the given basename using the given
+    /// options.
+    pub fn with_options(basename: impl AsRef<Path>, options: OutputOptions) -> Self {
+        let basename = Basename::new(basename);
+        let files = [GRAPH_EXTENSION, OFFSETS_EXTENSION, PROPERTIES_EXTENSION]
+            .into_iter()
+            .map(|ext| {
+                let path = basename.with_ext(ext);
+                let state = file_state(&path);
+                (path, state)
+            })
+            .collect();
+        Self {
+            files,
+            options,
+            finished: false,
+        }
+    }
+
+    /// Syncs the files to disk if required.
+    pub fn finish(mut self) -> Result<()> {
+        for (path, _) in &self.files {
+            if path.exists() {
+                self.options.sync_path(path)?;
+            }
+        }
+        self.finished = true;
+        Ok(())
+    }
+}
+
+impl Drop for GraphOutput {
+    fn drop(&mut self) {
+        if self.finished {
+            return;
+        }
+        for (path, state) in &self.files {
+            let current = file_state(path);
+            if current.is_none() || current == *state {
+                continue;
+            }
+            if self.options.keep_partial {
+                log::warn!("Keeping partial file {}", path.display());
+            } else {
+                log::warn!("Removing partial file {}", path.display());
+                if let Err(err) = std::fs::remove_file(path) {
+                    log::error!("Could not remove {}: {}", path.display(), err);
+                }
+            }
+        }
+    }
+}
+
+/// A destination that can be synced to disk.
+///
+/// This trait is implemented by [`File`]; other implementations are useful
+/// for testing.
+pub trait SyncAll: Write {
+    /// Syncs all data and metadata to disk.
+    fn sync_all(&mut self) -> std::io::Result<()>;
+}
+
+impl SyncAll for File {
+    fn sync_all(&mut self) -> std::io::Result<()> {
+        File::sync_all(self)
+    }
+}
+
+/// A buffered output file.
+///
+/// Data must be committed by calling [`finish`](OutputFile::finish), which
+/// flushes the buffer, syncs the file to disk if the `fsync`
+/// [option](OutputOptions) is set, and logs the number of bytes written and
+/// the write throughput. If the output file is dropped without being
+/// finished (e.g., because an error was propagated, or during a panic), or if
+/// finishing fails, the partial file is removed, unless the `keep_partial`
+/// option is set.
+#[derive(Debug)]
+pub struct OutputFile<W: SyncAll = File> {
+    path: PathBuf,
+    writer: Option<BufWriter<W>>,
+    options: OutputOptions,
+    bytes: u64,
+    start: Instant,
+}
+
+impl OutputFile {
+    /// Creates a new output file using the [global options](OutputOptions::global).
+    pub fn create(path: impl AsRef<Path>) -> Result<Self> {
+        Self::create_with_options(path, OutputOptions::global())
+    }
+
+    /// Creates a new output file using the given options.
+    pub fn create_with_options(path: impl AsRef<Path>, options: OutputOptions) -> Result<Self> {
+        let path = path.as_ref();
+        let file =
+            File::create(path).with_context(|| format!("Could not create {}", path.display()))?;
+        Ok(Self::new(path, file, options))
+    }
+}
+
+impl<W: SyncAll> OutputFile<W> {
+    /// Wraps a destination corresponding to the file at `path`.
+    ///
+    /// The path is used for logging and for removing the file if it is not
+    /// finished.
+    pub fn new(path: impl AsRef<Path>, writer: W, options: OutputOptions) -> Self {
+        Self {
+            path: path.as_ref().to_owned(),
+            writer: Some(BufWriter::with_capacity(BUFFER_SIZE, writer)),
+            options,
+            bytes: 0,
+            start: Instant::now(),
+        }
+    }
+
+    /// Returns the path of the file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the number of bytes written so far.
+    pub fn bytes_written(&self) -> u64 {
+        self.bytes
+    }
+
+    /// Flushes the file, syncs it to disk if required, and logs the write
+    /// throughput.
+    pub fn finish(mut self) -> Result<()> {
+        let writer = self.writer.as_mut().unwrap();
+        writer
+            .flush()
+            .with_context(|| format!("Could not flush {}", self.path.display()))?;
+        if self.options.fsync {
+            writer
+                .get_mut()
+                .sync_all()
+                .with_context(|| format!("Could not sync {}", self.path.display()))?;
+        }
+        // The buffer is empty, so dropping the writer does nothing
+        self.writer = None;
+        let elapsed = self.start.elapsed().as_secs_f64();
+        log::info!(
+            "Wrote {} bytes to {} in {:.3}s ({:.2} MB/s)",
+            self.bytes,
+            self.path.display(),
+            elapsed,
+            self.bytes as f64 / 1E6 / elapsed.max(f64::MIN_POSITIVE)
+        );
+        Ok(())
+    }
+}
+
+impl<W: SyncAll> Write for OutputFile<W> {
+    #[inline(always)]
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        check_interrupted()?;
+        let written = self.writer.as_mut().unwrap().write(buf)?;
+        self.bytes += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.as_mut().unwrap().flush()
+    }
+}
+
+impl<W: SyncAll + Seek> Seek for OutputFile<W> {
+    /// Seeks to the given position, flushing the buffer first.
+    ///
+    /// Bytes rewritten after seeking backwards are counted again in the
+    /// number of bytes written.
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        self.writer.as_mut().unwrap().seek(pos)
+    }
+}
+
+impl<W: SyncAll> Drop for OutputFile<W> {
+    fn drop(&mut self) {
+        let Some(writer) = self.writer.take() else {
+            return;
+        };
+        // Discard the buffer without writing it, and close the file
+        drop(writer.into_parts());
+        if self.options.keep_partial {
+            log::warn!("Keeping partial file {}", self.path.display());
+        } else {
+            log::warn!("Removing partial file {}", self.path.display());
+            if let Err(err) = std::fs::remove_file(&self.path) {
+                log::error!("Could not remove {}: {}", self.path.display(), err);
+            }
+        }
+    }
+}
+
+/// An [`OutputFile`], or the standard output.
+///
+/// This is the destination of commands whose output file is optional: the
+/// standard output is used if no path is given. Data must be committed by
+/// calling [`finish`](FileOrStdout::finish).
+#[derive(Debug)]
+pub enum FileOrStdout {
+    File(OutputFile),
+    Stdout(BufWriter<StdoutLock<'static>>),
+}
+
+impl FileOrStdout {
+    /// Creates an [`OutputFile`] using the [global
+    /// options](OutputOptions::global) if `path` is not `None`, and locks the
+    /// standard output otherwise.
+    pub fn create(path: Option<impl AsRef<Path>>) -> Result<Self> {
+        Ok(match path {
+            Some(path) => Self::File(OutputFile::create(path)?),
+            None => Self::Stdout(BufWriter::new(std::io::stdout().lock())),
+        })
+    }
+
+    /// Finishes the output file, or flushes the standard output.
+    pub fn finish(self) -> Result<()> {
+        match self {
+            Self::File(file) => file.finish(),
+            Self::Stdout(mut stdout) => stdout.flush().context("Could not flush stdout"),
+        }
+    }
+}
+
+impl Write for FileOrStdout {
+    #[inline(always)]
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            Self::File(file) => file.write(buf),
+            Self::Stdout(stdout) => stdout.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            Self::File(file) => file.flush(),
+            Self::Stdout(stdout) => stdout.flush(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+
+    /// A destination that fails after accepting a given number of bytes, and
+    /// records calls to [`SyncAll::sync_all`].
+    struct MockFile<'a> {
+        capacity: usize,
+        synced: &'a mut bool,
+    }
+
+    impl Write for MockFile<'_> {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            if buf.len() > self.capacity {
+                return Err(io::Error::other("disk full"));
+            }
+            self.capacity -= buf.len();
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl SyncAll for MockFile<'_> {
+        fn sync_all(&mut self) -> io::Result<()> {
+            *self.synced = true;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_fsync() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("out");
+        for fsync in [false, true] {
+            let mut synced = false;
+            std::fs::write(&path, "")?;
+            let mock = MockFile {
+                capacity: 100,
+                synced: &mut synced,
+            };
+            let mut out = OutputFile::new(
+                &path,
+                mock,
+                OutputOptions {
+                    fsync,
+                    keep_partial: false,
+                },
+            );
+            out.write_all(b"hello")?;
+            assert_eq!(out.bytes_written(), 5);
+            out.finish()?;
+            assert_eq!(synced, fsync);
+            assert!(path.exists());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_cleanup() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("out");
+        for keep_partial in [false, true] {
+            let options = OutputOptions {
+                fsync: true,
+                keep_partial,
+            };
+            // The error surfaces when the buffer is flushed
+            std::fs::write(&path, "")?;
+            let mut synced = false;
+            let mock = MockFile {
+                capacity: 3,
+                synced: &mut synced,
+            };
+            let mut out = OutputFile::new(&path, mock, options);
+            out.write_all(b"hello")?;
+            assert!(out.finish().is_err());
+            assert!(!synced);
+            assert_eq!(path.exists(), keep_partial);
+
+            // Dropping an unfinished file
+            let mut out = OutputFile::create_with_options(&path, options)?;
+            out.write_all(b"hello")?;
+            drop(out);
+            assert_eq!(path.exists(), keep_partial);
+            if keep_partial {
+                // The buffer has been discarded
+                assert_eq!(std::fs::read(&path)?.len(), 0);
+            }
+
+            // Panicking while writing
+            let result = std::panic::catch_unwind(|| {
+                let mut out = OutputFile::create_with_options(&path, options).unwrap();
+                out.write_all(b"hello").unwrap();
+                panic!("write failed");
+            });
+            assert!(result.is_err());
+            assert_eq!(path.exists(), keep_partial);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_graph_output() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let basename = Basename::new(dir.path().join("graph"));
+        let graph_path = basename.with_ext(GRAPH_EXTENSION);
+        let offsets_path = basename.with_ext(OFFSETS_EXTENSION);
+        let properties_path = basename.with_ext(PROPERTIES_EXTENSION);
+        for keep_partial in [false, true] {
+            let options = OutputOptions {
+                fsync: true,
+                keep_partial,
+            };
+            // The files of a previous version of the graph
+            std::fs::write(&graph_path, "old graph")?;
+            std::fs::write(&properties_path, "old properties")?;
+            let _ = std::fs::remove_file(&offsets_path);
+
+            // Nothing has been written
+            drop(GraphOutput::with_options(&basename, options));
+            assert!(graph_path.exists());
+            assert!(properties_path.exists());
+
+            // A failed compression overwrote the graph and created the offsets
+            let output = GraphOutput::with_options(&basename, options);
+            std::fs::write(&graph_path, "new")?;
+            std::fs::write(&offsets_path, "new")?;
+            drop(output);
+            assert_eq!(graph_path.exists(), keep_partial);
+            assert_eq!(offsets_path.exists(), keep_partial);
+            assert_eq!(std::fs::read_to_string(&properties_path)?, "old properties");
+
+            // A successful compression
+            let output = GraphOutput::with_options(&basename, options);
+            std::fs::write(&graph_path, "new graph")?;
+            std::fs::write(&offsets_path, "new offsets")?;
+            output.finish()?;
+            assert!(graph_path.exists());
+            assert!(offsets_path.exists());
+        }
+        Ok(())
+    }
+}
@@ -6,16 +6,39 @@
  */
 
 use crate::cli::create_parent_dir;
+use crate::cli::get_thread_pool;
+use crate::cli::IntVectorFormat;
+use crate::cli::NumThreadsArg;
 use crate::prelude::*;
 use anyhow::{Context, Result};
-use clap::{ArgMatches, Args, Command, FromArgMatches};
+use clap::{ArgMatches, Args, Command, FromArgMatches, ValueEnum};
 use dsi_bitstream::prelude::*;
-use epserde::prelude::Serialize;
-use std::io::{BufWriter, Write};
+use dsi_progress_logger::prelude::*;
 use std::path::PathBuf;
 
 pub const COMMAND_NAME: &str = "bfs";
 
+/// The [`IntraLayerOrder`] of the parallel visit.
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum IntraLayer {
+    /// By node.
+    Original,
+    /// By decreasing outdegree, and then by node.
+    DegreeDesc,
+    /// By position of the first parent in the previous layer, and then by node.
+    Parent,
+}
+
+impl From<IntraLayer> for IntraLayerOrder {
+    fn from(intra_layer: IntraLayer) -> Self {
+        match intra_layer {
+            IntraLayer::Original => IntraLayerOrder::Original,
+            IntraLayer::DegreeDesc => IntraLayerOrder::DegreeDesc,
+            IntraLayer::Parent => IntraLayerOrder::Parent,
+        }
+    }
+}
+
 #[derive(Args, Debug)]
 #[command(about = "Computes the permutation induced by a breadth-first visit.", long_about = None)]
 pub struct CliArgs {
@@ -28,6 +51,14 @@ pub struct CliArgs {
     #[arg(short, long)]
     /// Save the permutation in ε-serde format.
     pub epserde: bool,
+
+    #[arg(short, long, value_enum)]
+    /// Visit the graph in parallel layer by layer, sorting the nodes of
+    /// each layer in this order before numbering them.
+    pub intra_layer: Option<IntraLayer>,
+
+    #[clap(flatten)]
+    pub num_threads: NumThreadsArg,
 }
 
 pub fn cli(command: Command) -> Command {
@@ -66,24 +97,29 @@ where
         .load()?;
 
     // create the permutation
-    let mut perm = vec![0; graph.num_nodes()];
-    for (i, node_id) in crate::algo::BfsOrder::new(&graph).enumerate() {
-        perm[node_id] = i;
-    }
+    let perm = match args.intra_layer {
+        Some(intra_layer) => par_bfs_perm(
+            &graph,
+            intra_layer.into(),
+            &get_thread_pool(args.num_threads.num_threads),
+            &mut ProgressLogger::default(),
+        )
+        .into_vec(),
+        None => {
+            let mut perm = vec![0; graph.num_nodes()];
+            for (i, node_id) in crate::algo::BfsOrder::new(&graph).enumerate() {
+                perm[node_id] = i;
+            }
+            perm
+        }
+    };
 
     if args.epserde {
-        perm.store(&args.perm)
-            .with_context(|| format!("Could not write permutation to {}", args.perm.display()))?;
+        IntVectorFormat::Epserde.store(&args.perm, &perm)
     } else {
-        let mut file = std::fs::File::create(&args.perm)
-            .with_context(|| format!("Could not create permutation at {}", args.perm.display()))?;
-        let mut buf = BufWriter::new(&mut file);
-        for word in perm.iter() {
-            buf.write_all(&word.to_be_bytes()).with_context(|| {
-                format!("Could not write permutation to {}", args.perm.display())
-            })?;
-        }
+        IntVectorFormat::Java.store(&args.perm, &perm)
     }
+    .with_context(|| format!("Could not write permutation to {}", args.perm.display()))?;
     log::info!("Completed..");
     Ok(())
 }
@@ -5,13 +5,12 @@
  * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
  */
 
-use crate::cli::create_parent_dir;
+use crate::cli::{create_parent_dir, IntVectorFormat};
 use crate::prelude::*;
 use anyhow::{ensure, Result};
 use clap::{ArgMatches, Args, Command, FromArgMatches};
 use epserde::prelude::*;
 use mmap_rs::MmapFlags;
-use std::io::{BufWriter, Write};
 use std::path::PathBuf;
 use sux::traits::BitFieldSlice;
 
@@ -64,9 +63,8 @@ pub fn merge_perms(args: CliArgs) -> Result<()> {
             }
             merged.push(v);
         }
-        merged.store(&args.dst)?;
+        IntVectorFormat::Epserde.store(&args.dst, &merged)?;
     } else {
-        let mut writer = BufWriter::new(std::fs::File::create(&args.dst)?);
         let mut perm = Vec::new();
         for path in args.perms {
             let p = JavaPermutation::mmap(&path, MmapFlags::RANDOM_ACCESS)?;
@@ -87,9 +85,7 @@ pub fn merge_perms(args: CliArgs) -> Result<()> {
             }
             merged.push(v);
         }
-        for v in merged {
-            writer.write_all(&(v as u64).to_be_bytes())?;
-        }
+        IntVectorFormat::Java.store(&args.dst, &merged)?;
     }
     log::info!("Completed in {} seconds", start.elapsed().as_secs_f64());
     Ok(())
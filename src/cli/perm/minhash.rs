@@ -0,0 +1,94 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::algo::minhash_order;
+use crate::cli::*;
+use crate::prelude::*;
+use anyhow::Result;
+use clap::{ArgMatches, Args, Command, FromArgMatches};
+use dsi_bitstream::prelude::*;
+use dsi_progress_logger::prelude::*;
+use std::path::PathBuf;
+
+pub const COMMAND_NAME: &str = "minhash";
+
+#[derive(Args, Debug)]
+#[command(about = "Computes the permutation sorting nodes by the minhash signatures of their successor sets, a cheap alternative to LLP that clusters nodes with similar successors.", long_about = None)]
+pub struct CliArgs {
+    /// The basename of the graph.
+    pub src: PathBuf,
+
+    /// The filename of the permutation in binary big-endian format.
+    pub perm: PathBuf,
+
+    #[arg(short = 'k', long, default_value_t = 4)]
+    /// The number of hash functions of a signature.
+    pub num_hashes: usize,
+
+    #[arg(long, default_value_t = 0)]
+    /// The seed of the hash functions.
+    pub seed: u64,
+
+    #[arg(short, long)]
+    /// Save the permutation in ε-serde format.
+    pub epserde: bool,
+
+    #[clap(flatten)]
+    pub num_threads: NumThreadsArg,
+}
+
+pub fn cli(command: Command) -> Command {
+    command.subcommand(CliArgs::augment_args(Command::new(COMMAND_NAME)).display_order(0))
+}
+
+pub fn main(submatches: &ArgMatches) -> Result<()> {
+    let args = CliArgs::from_arg_matches(submatches)?;
+
+    create_parent_dir(&args.perm)?;
+
+    match get_endianness(&args.src)?.as_str() {
+        #[cfg(any(
+            feature = "be_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        BE::NAME => minhash::<BE>(args),
+        #[cfg(any(
+            feature = "le_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        LE::NAME => minhash::<LE>(args),
+        e => panic!("Unknown endianness: {}", e),
+    }
+}
+
+pub fn minhash<E: Endianness + 'static + Send + Sync>(args: CliArgs) -> Result<()>
+where
+    for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+{
+    let graph = BvGraph::with_basename(&args.src)
+        .mode::<LoadMmap>()
+        .flags(MemoryFlags::TRANSPARENT_HUGE_PAGES | MemoryFlags::RANDOM_ACCESS)
+        .endianness::<E>()
+        .load()?;
+
+    let mut pl = ProgressLogger::default();
+    pl.display_memory(true);
+    let perm = minhash_order(
+        &graph,
+        args.num_hashes,
+        args.seed,
+        &get_thread_pool(args.num_threads.num_threads),
+        &mut pl,
+    );
+
+    let format = if args.epserde {
+        IntVectorFormat::Epserde
+    } else {
+        IntVectorFormat::Java
+    };
+    format.store(&args.perm, &perm)?;
+    Ok(())
+}
@@ -9,7 +9,9 @@ use clap::{ArgMatches, Command};
 
 pub mod bfs;
 pub mod comp;
+pub mod minhash;
 pub mod rand;
+pub mod score;
 
 pub const COMMAND_NAME: &str = "perm";
 
@@ -21,7 +23,9 @@ pub fn cli(command: Command) -> Command {
         .allow_external_subcommands(true);
     let sub_command = bfs::cli(sub_command);
     let sub_command = comp::cli(sub_command);
+    let sub_command = minhash::cli(sub_command);
     let sub_command = rand::cli(sub_command);
+    let sub_command = score::cli(sub_command);
     command.subcommand(sub_command.display_order(0))
 }
 
@@ -29,7 +33,9 @@ pub fn main(submatches: &ArgMatches) -> Result<()> {
     match submatches.subcommand() {
         Some((bfs::COMMAND_NAME, sub_m)) => bfs::main(sub_m),
         Some((comp::COMMAND_NAME, sub_m)) => comp::main(sub_m),
+        Some((minhash::COMMAND_NAME, sub_m)) => minhash::main(sub_m),
         Some((rand::COMMAND_NAME, sub_m)) => rand::main(sub_m),
+        Some((score::COMMAND_NAME, sub_m)) => score::main(sub_m),
         Some((command_name, _)) => {
             eprintln!("Unknown command: {:?}", command_name);
             std::process::exit(1);
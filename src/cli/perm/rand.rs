@@ -5,18 +5,21 @@
  * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
  */
 
-use crate::cli::create_parent_dir;
-use anyhow::{Context, Result};
+use crate::cli::*;
+use crate::utils::random_key::*;
+use anyhow::{ensure, Context, Result};
 use clap::{ArgMatches, Args, Command, FromArgMatches};
-use epserde::ser::Serialize;
-use rand::prelude::SliceRandom;
-use std::io::prelude::*;
+use dsi_progress_logger::prelude::*;
 use std::path::PathBuf;
+use tempfile::Builder;
 
 pub const COMMAND_NAME: &str = "rand";
 
 #[derive(Args, Debug)]
-#[command(about = "Create a random permutation.", long_about = None)]
+#[command(
+    about = "Create a random permutation.",
+    long_about = "Create a uniformly random permutation by sorting indices by random keys in parallel and in external memory. The keys depend only on the seed and on the index, so the permutation depends only on the seed and on the number of elements, and not on the number of threads or on the batch size."
+)]
 pub struct CliArgs {
     /// The number of elements in the permutation.
     pub len: usize,
@@ -24,8 +27,26 @@ pub struct CliArgs {
     pub dst: PathBuf,
 
     #[arg(short = 'e', long)]
-    /// Store the permutation in ε-serde format.
+    /// Store the permutation in ε-serde format (the permutation will be
+    /// kept in memory).
     pub epserde: bool,
+
+    #[arg(long)]
+    /// The seed of the pseudorandom number generator; if missing, a random
+    /// seed will be used and logged.
+    pub seed: Option<u64>,
+
+    #[arg(long)]
+    /// Check fully that the output is a permutation using a bit vector with
+    /// one bit per element; by default, only a probabilistic check based on
+    /// a fingerprint of the output is performed.
+    pub full_check: bool,
+
+    #[clap(flatten)]
+    pub num_threads: NumThreadsArg,
+
+    #[clap(flatten)]
+    pub batch_size: BatchSizeArg,
 }
 
 pub fn cli(command: Command) -> Command {
@@ -33,28 +54,80 @@ pub fn cli(command: Command) -> Command {
 }
 
 pub fn main(submatches: &ArgMatches) -> Result<()> {
-    let args = CliArgs::from_arg_matches(submatches)?;
+    rand_perm(CliArgs::from_arg_matches(submatches)?)
+}
 
+pub fn rand_perm(args: CliArgs) -> Result<()> {
     create_parent_dir(&args.dst)?;
 
-    let mut rng = rand::thread_rng();
-    let mut perm = (0..args.len).collect::<Vec<_>>();
-    perm.shuffle(&mut rng);
+    let seed = args.seed.unwrap_or_else(rand::random);
+    log::info!("Seed: {}", seed);
 
+    let thread_pool = get_thread_pool(args.num_threads.num_threads);
+    let dir = Builder::new().prefix("perm_rand_").tempdir()?;
+    let mut pl = ProgressLogger::default();
+    pl.display_memory(true)
+        .item_name("index")
+        .expected_updates(Some(args.len));
+    pl.start("Sorting indices by random keys...");
+    let perm = sort_by_random_key(
+        args.len,
+        seed,
+        args.batch_size.batch_size,
+        dir.path(),
+        &thread_pool,
+    )?;
+    pl.done();
+
+    let mut fingerprint = 0_u64;
+    let mut count = 0;
+    let mut seen = args.full_check.then(|| sux::bits::BitVec::new(args.len));
+    let mut check = |x: usize| -> Result<()> {
+        fingerprint = perm_fingerprint([x]).wrapping_add(fingerprint);
+        count += 1;
+        if let Some(seen) = seen.as_mut() {
+            ensure!(
+                x < args.len && !seen.get(x),
+                "Index {} is repeated or out of range",
+                x
+            );
+            seen.set(x, true);
+        }
+        Ok(())
+    };
+
+    pl.start("Writing permutation...");
     if args.epserde {
-        perm.store(&args.dst)
+        let perm = perm.collect::<Vec<_>>();
+        for &x in &perm {
+            check(x)?;
+        }
+        IntVectorFormat::Epserde
+            .store(&args.dst, &perm)
             .with_context(|| format!("Could not store permutation to {}", args.dst.display()))?;
     } else {
-        let mut file =
-            std::io::BufWriter::new(std::fs::File::create(&args.dst).with_context(|| {
-                format!("Could not create permutation at {}", args.dst.display())
-            })?);
-        for perm in perm {
-            file.write_all(&perm.to_be_bytes()).with_context(|| {
+        let mut file = OutputFile::create(&args.dst)?;
+        for x in perm {
+            check(x)?;
+            file.write_all(&x.to_be_bytes()).with_context(|| {
                 format!("Could not write permutation to {}", args.dst.display())
             })?;
+            pl.light_update();
         }
+        file.finish()?;
     }
+    pl.done();
+
+    ensure!(
+        count == args.len,
+        "The output contains {} elements instead of {}",
+        count,
+        args.len
+    );
+    ensure!(
+        fingerprint == perm_fingerprint_range(args.len, &thread_pool),
+        "The output is not a permutation"
+    );
 
     Ok(())
 }
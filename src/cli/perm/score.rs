@@ -0,0 +1,205 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::algo::llp::gap_cost::compute_log_gap_cost;
+use crate::cli::*;
+use crate::graphs::bvgraph::match_select_params;
+use crate::prelude::*;
+use anyhow::{Context, Result};
+use clap::{ArgMatches, Args, Command, FromArgMatches};
+use dsi_bitstream::prelude::*;
+use dsi_progress_logger::prelude::*;
+use epserde::prelude::*;
+use lender::*;
+use mmap_rs::MmapFlags;
+use rayon::ThreadPool;
+use std::path::PathBuf;
+use sux::traits::{BitFieldSlice, Succ};
+
+pub const COMMAND_NAME: &str = "score";
+
+#[derive(Args, Debug)]
+#[command(about = "Reports the locality of a graph under a permutation: the average gap between consecutive successors, the log-gap cost (the cost minimized by LLP), and the size of the permuted graph estimated using the default codes, so that different orderings can be compared without recompressing the graph. Requires the .ef and .dcf files of the graph.", long_about = None)]
+pub struct CliArgs {
+    /// The basename of the graph.
+    pub src: PathBuf,
+
+    /// The permutation to evaluate, mapping each node to its new index, in
+    /// binary big-endian format (or ε-serde format if --epserde is given).
+    pub perm: PathBuf,
+
+    #[arg(short, long)]
+    /// Load the permutation in ε-serde format.
+    pub epserde: bool,
+
+    #[clap(flatten)]
+    pub perm_mode: PermModeArg,
+
+    #[clap(flatten)]
+    pub num_threads: NumThreadsArg,
+
+    #[clap(flatten)]
+    pub batch_size: BatchSizeArg,
+
+    #[arg(long)]
+    /// The number of arcs in each parallel job (default: the number of arcs
+    /// divided by 512, but at least 1024, as in LLP).
+    pub granularity: Option<usize>,
+}
+
+pub fn cli(command: Command) -> Command {
+    command.subcommand(CliArgs::augment_args(Command::new(COMMAND_NAME)).display_order(0))
+}
+
+pub fn main(submatches: &ArgMatches) -> Result<()> {
+    let args = CliArgs::from_arg_matches(submatches)?;
+
+    match get_endianness(&args.src)?.as_str() {
+        #[cfg(any(
+            feature = "be_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        BE::NAME => score::<BE>(args),
+        #[cfg(any(
+            feature = "le_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        LE::NAME => score::<LE>(args),
+        e => panic!("Unknown endianness: {}", e),
+    }
+}
+
+pub fn score<E: Endianness + 'static + Send + Sync>(args: CliArgs) -> Result<()>
+where
+    for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+{
+    let graph = BvGraph::with_basename(&args.src)
+        .endianness::<E>()
+        .mode::<Mmap>()
+        .flags(MemoryFlags::RANDOM_ACCESS)
+        .load()?;
+    let num_nodes = graph.num_nodes();
+
+    let granularity = args
+        .granularity
+        .unwrap_or_else(|| Ord::max((graph.num_arcs() >> 9) as usize, 1024));
+    let thread_pool = get_thread_pool(args.num_threads.num_threads);
+    let batch_size = args.batch_size.batch_size;
+
+    let params = SelectParams::load(&args.src, DCF_SELECT_PROPERTY)?;
+    match_select_params!(params, L, S => {
+        let deg_cumul = DCFSelect::<L, S>::mmap(
+            Basename::new(&args.src).with_ext(DEG_CUMUL_EXTENSION),
+            Flags::RANDOM_ACCESS,
+        )
+        .with_context(|| {
+            format!(
+                "Could not load degree cumulative function for basename {}",
+                args.src.display()
+            )
+        })?;
+
+        if args.epserde {
+            let perm = <Vec<usize>>::mmap(&args.perm, Flags::RANDOM_ACCESS)?;
+            let perm = fit_perm(&*perm, num_nodes, args.perm_mode.into())?;
+            report(
+                &graph,
+                &perm,
+                granularity,
+                &*deg_cumul,
+                &thread_pool,
+                batch_size,
+            )
+        } else {
+            let perm = JavaPermutation::mmap(&args.perm, MmapFlags::RANDOM_ACCESS)?;
+            let perm = fit_perm(perm, num_nodes, args.perm_mode.into())?;
+            report(
+                &graph,
+                &perm,
+                granularity,
+                &*deg_cumul,
+                &thread_pool,
+                batch_size,
+            )
+        }
+    })
+}
+
+/// Computes and prints the locality metrics of `graph` permuted by `perm`.
+fn report(
+    graph: &(impl SequentialGraph + Sync),
+    perm: &(impl BitFieldSlice<usize> + Sync),
+    granularity: usize,
+    deg_cumul: &(impl Succ<Input = usize, Output = usize> + Send + Sync),
+    thread_pool: &ThreadPool,
+    batch_size: usize,
+) -> Result<()> {
+    let num_nodes = graph.num_nodes();
+    let num_arcs = graph.num_arcs_hint().unwrap_or(0);
+
+    let mut pl = ProgressLogger::default();
+    pl.display_memory(true)
+        .item_name("node")
+        .expected_updates(Some(num_nodes));
+    pl.start("Computing log-gap cost of the permuted graph...");
+    let log_gap_cost = compute_log_gap_cost(
+        &PermutedGraph { graph, perm },
+        granularity,
+        deg_cumul,
+        thread_pool,
+        Some(&mut pl),
+    );
+    pl.done();
+
+    // The compressor needs the permuted graph in node order
+    let permuted = crate::transform::permute(graph, perm, batch_size)?;
+    let cf = CompFlags::default();
+    let mut bvcomp = BvComp::new(
+        DynCodesEstimator::new(&cf),
+        cf.compression_window,
+        cf.max_ref_count,
+        cf.min_interval_length,
+        0,
+    )
+    .with_reference_selection(cf.reference_selection);
+    let mut successors = vec![];
+    let (mut gaps, mut num_gaps, mut bits) = (0_u64, 0_u64, 0_u64);
+    pl.start("Estimating the size of the permuted graph...");
+    for_!((_, succ) in permuted.iter() {
+        successors.clear();
+        successors.extend(succ);
+        gaps += successors
+            .windows(2)
+            .map(|w| (w[1] - w[0]) as u64)
+            .sum::<u64>();
+        num_gaps += successors.len().saturating_sub(1) as u64;
+        bits += bvcomp.push(successors.iter().copied())?;
+        pl.light_update();
+    });
+    pl.done();
+
+    let per_arc = |value: f64| {
+        if num_arcs == 0 {
+            0.0
+        } else {
+            value / num_arcs as f64
+        }
+    };
+    println!(
+        "Average gap: {}",
+        if num_gaps == 0 {
+            0.0
+        } else {
+            gaps as f64 / num_gaps as f64
+        }
+    );
+    println!("Log-gap cost: {}", log_gap_cost);
+    println!("Log-gap cost per arc: {}", per_arc(log_gap_cost));
+    println!("Estimated size: {} bits", bits);
+    println!("Estimated bits per arc: {}", per_arc(bits as f64));
+
+    Ok(())
+}
@@ -0,0 +1,457 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+//! A command running a sequence of other commands described by a plan file.
+//!
+//! A plan is a TOML file containing a few global settings and an array of
+//! stages, each naming a command and its arguments as they would be passed on
+//! the command line:
+//!
+//! ```toml
+//! threads = 8          # passed as --num-threads to stages supporting it
+//! log_interval = 60    # seconds between pipeline progress logs
+//!
+//! [[stage]]
+//! name = "import"
+//! command = "from arcs"
+//! input = "arcs.tsv"   # read instead of standard input
+//! args = ["--num-nodes", "1000", "--exact", "--separator", "\t", "graph"]
+//!
+//! [[stage]]
+//! name = "transpose"
+//! command = "transform transpose"
+//! args = ["graph", "graph-t"]
+//! ```
+//!
+//! Stages are run in-process, in order. After a stage completes, a marker is
+//! written in the marker directory (by default, the plan file with extension
+//! `.stages`); when the plan is run again, stages with an up-to-date marker
+//! are skipped until the first stage that must be run, after which all
+//! stages are run.
+
+use crate::cli::*;
+use crate::graphs::bvgraph::{GRAPH_EXTENSION, PROPERTIES_EXTENSION};
+use crate::utils::Basename;
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use clap::{ArgMatches, Args, Command, FromArgMatches};
+use dsi_progress_logger::prelude::*;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use sysinfo::Disks;
+
+pub const COMMAND_NAME: &str = "pipeline";
+
+#[derive(Args, Debug)]
+#[command(about = "Runs a sequence of commands described by a TOML plan file, skipping stages completed by previous runs.", long_about = None)]
+pub struct CliArgs {
+    /// The plan file.
+    pub plan: PathBuf,
+
+    #[arg(long)]
+    /// Validate the plan and log the stages that would be run, without
+    /// running them.
+    pub dry_run: bool,
+
+    #[arg(long)]
+    /// Ignore completion markers and run all stages.
+    pub restart: bool,
+}
+
+pub fn cli(command: Command) -> Command {
+    command.subcommand(CliArgs::augment_args(Command::new(COMMAND_NAME)).display_order(0))
+}
+
+pub fn main(submatches: &ArgMatches) -> Result<()> {
+    let args = CliArgs::from_arg_matches(submatches)?;
+    Plan::load(&args.plan)?.run(args.dry_run, args.restart)
+}
+
+/// A stage of a [`Plan`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stage {
+    /// The name of the stage, used for its completion marker.
+    pub name: String,
+    /// The command group and command, e.g., `["transform", "transpose"]`.
+    pub command: Vec<String>,
+    /// The arguments of the command.
+    pub args: Vec<String>,
+    /// A file to read instead of standard input (only for `from arcs`).
+    pub input: Option<PathBuf>,
+}
+
+impl Stage {
+    /// Returns a description of the stage, stored in its completion marker.
+    fn description(&self) -> String {
+        let mut description = format!("{} {:?}", self.command.join(" "), self.args);
+        if let Some(input) = &self.input {
+            description.push_str(&format!(" < {}", input.display()));
+        }
+        description
+    }
+}
+
+/// A sequence of stages with global settings.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Plan {
+    /// The stages, in execution order.
+    pub stages: Vec<Stage>,
+    /// The number of threads, passed to all stages supporting `--num-threads`
+    /// that do not specify it.
+    pub threads: Option<usize>,
+    /// The log interval of the pipeline progress logger.
+    pub log_interval: Option<Duration>,
+    /// The directory containing the completion markers.
+    pub marker_dir: PathBuf,
+}
+
+const PLAN_KEYS: [&str; 4] = ["threads", "log_interval", "marker_dir", "stage"];
+const STAGE_KEYS: [&str; 4] = ["name", "command", "args", "input"];
+
+impl Plan {
+    /// Loads a plan from a file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Could not read plan {}", path.display()))?;
+        Self::parse(&text, path.with_extension("stages"))
+            .with_context(|| format!("Invalid plan {}", path.display()))
+    }
+
+    /// Parses a plan, using `default_marker_dir` as marker directory if the
+    /// plan does not specify one.
+    pub fn parse(text: &str, default_marker_dir: impl AsRef<Path>) -> Result<Self> {
+        let table: toml::Table = text.parse()?;
+        for key in table.keys() {
+            ensure!(PLAN_KEYS.contains(&key.as_str()), "Unknown key {:?}", key);
+        }
+
+        let threads = table
+            .get("threads")
+            .map(|value| {
+                value
+                    .as_integer()
+                    .filter(|&t| t > 0)
+                    .map(|t| t as usize)
+                    .ok_or_else(|| anyhow!("threads must be a positive integer"))
+            })
+            .transpose()?;
+        let log_interval = table
+            .get("log_interval")
+            .map(|value| {
+                value
+                    .as_float()
+                    .or_else(|| value.as_integer().map(|i| i as f64))
+                    .filter(|&s| s > 0.0)
+                    .map(Duration::from_secs_f64)
+                    .ok_or_else(|| anyhow!("log_interval must be a positive number of seconds"))
+            })
+            .transpose()?;
+        let marker_dir = match table.get("marker_dir") {
+            Some(value) => PathBuf::from(
+                value
+                    .as_str()
+                    .ok_or_else(|| anyhow!("marker_dir must be a string"))?,
+            ),
+            None => default_marker_dir.as_ref().to_owned(),
+        };
+
+        let mut stages = vec![];
+        let array = match table.get("stage") {
+            Some(value) => value
+                .as_array()
+                .ok_or_else(|| anyhow!("stage must be an array of tables"))?
+                .as_slice(),
+            None => &[],
+        };
+        for value in array {
+            let stage = value
+                .as_table()
+                .ok_or_else(|| anyhow!("stage must be an array of tables"))?;
+            let name = stage
+                .get("name")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow!("Stage {} has no name", stages.len()))?
+                .to_owned();
+            for key in stage.keys() {
+                ensure!(
+                    STAGE_KEYS.contains(&key.as_str()),
+                    "Unknown key {:?} in stage {}",
+                    key,
+                    name
+                );
+            }
+            ensure!(
+                !name.is_empty()
+                    && name
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'),
+                "Stage names must be nonempty and contain only alphanumeric characters, dashes and underscores, found {:?}",
+                name
+            );
+            let command = stage
+                .get("command")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow!("Stage {} has no command", name))?
+                .split_whitespace()
+                .map(str::to_owned)
+                .collect::<Vec<_>>();
+            let args = match stage.get("args") {
+                Some(value) => value
+                    .as_array()
+                    .ok_or_else(|| anyhow!("The arguments of stage {} must be an array", name))?
+                    .iter()
+                    .map(|arg| {
+                        arg.as_str().map(str::to_owned).ok_or_else(|| {
+                            anyhow!("The arguments of stage {} must be strings", name)
+                        })
+                    })
+                    .collect::<Result<Vec<_>>>()?,
+                None => vec![],
+            };
+            let input = match stage.get("input") {
+                Some(value) => {
+                    Some(PathBuf::from(value.as_str().ok_or_else(|| {
+                        anyhow!("The input of stage {} must be a string", name)
+                    })?))
+                }
+                None => None,
+            };
+            stages.push(Stage {
+                name,
+                command,
+                args,
+                input,
+            });
+        }
+
+        let mut names = stages.iter().map(|s| &s.name).collect::<Vec<_>>();
+        names.sort();
+        if let Some(w) = names.windows(2).find(|w| w[0] == w[1]) {
+            bail!("Duplicate stage name {:?}", w[0]);
+        }
+
+        Ok(Self {
+            stages,
+            threads,
+            log_interval,
+            marker_dir,
+        })
+    }
+
+    /// Validates the whole plan, returning the parsed command line of each
+    /// stage.
+    ///
+    /// Besides parsing the arguments of each stage, this method checks that
+    /// the graphs read by each stage exist or are written by a previous stage,
+    /// and that the destination file systems have enough space for a rough
+    /// estimate of the size of the graphs written.
+    pub fn validate(&self, top_command: &mut Command) -> Result<Vec<ArgMatches>> {
+        let mut result = vec![];
+        // Basename of each graph written by a stage -> estimated size
+        let mut written = HashMap::<PathBuf, u64>::new();
+        // Mount point -> estimated bytes written
+        let mut needed = HashMap::<PathBuf, u64>::new();
+        let disks = Disks::new_with_refreshed_list();
+
+        for stage in &self.stages {
+            ensure!(
+                stage.command.len() == 2,
+                "Stage {}: the command must be made of a group and a command, e.g., \"transform transpose\"",
+                stage.name
+            );
+            ensure!(
+                stage.command[0] != COMMAND_NAME,
+                "Stage {}: pipelines cannot be nested",
+                stage.name
+            );
+            let leaf = top_command
+                .find_subcommand(&stage.command[0])
+                .and_then(|group| group.find_subcommand(&stage.command[1]))
+                .ok_or_else(|| {
+                    anyhow!(
+                        "Stage {}: unknown command {:?}",
+                        stage.name,
+                        stage.command.join(" ")
+                    )
+                })?;
+
+            let mut argv = vec!["webgraph".to_owned()];
+            argv.extend(stage.command.iter().cloned());
+            argv.extend(stage.args.iter().cloned());
+            if let Some(threads) = self.threads {
+                let supported = leaf.get_arguments().any(|a| a.get_id() == "num_threads");
+                let given = stage
+                    .args
+                    .iter()
+                    .any(|a| a.starts_with("-j") || a.starts_with("--num-threads"));
+                if supported && !given {
+                    argv.push("--num-threads".to_owned());
+                    argv.push(threads.to_string());
+                }
+            }
+            let matches = top_command
+                .try_get_matches_from_mut(argv)
+                .with_context(|| format!("Stage {}: invalid arguments", stage.name))?;
+
+            let (_, group_matches) = matches.subcommand().unwrap();
+            let (_, leaf_matches) = group_matches.subcommand().unwrap();
+            let path_arg = |id: &str| {
+                leaf_matches
+                    .try_get_one::<PathBuf>(id)
+                    .ok()
+                    .flatten()
+                    .cloned()
+            };
+
+            let mut estimate = 0;
+            if let Some(input) = &stage.input {
+                ensure!(
+                    stage.command == ["from", "arcs"],
+                    "Stage {}: an input file can be specified only for \"from arcs\"",
+                    stage.name
+                );
+                estimate = std::fs::metadata(input)
+                    .with_context(|| {
+                        format!("Stage {}: cannot read {}", stage.name, input.display())
+                    })?
+                    .len();
+            }
+            for id in ["src", "transposed"] {
+                if let Some(basename) = path_arg(id) {
+                    let size = match written.get(&basename) {
+                        Some(&size) => size,
+                        None => {
+                            let properties =
+                                Basename::new(&basename).with_ext(PROPERTIES_EXTENSION);
+                            ensure!(
+                                properties.exists(),
+                                "Stage {}: graph {} does not exist and is not written by a previous stage",
+                                stage.name,
+                                basename.display()
+                            );
+                            std::fs::metadata(Basename::new(&basename).with_ext(GRAPH_EXTENSION))
+                                .map(|m| m.len())
+                                .unwrap_or(0)
+                        }
+                    };
+                    estimate = estimate.max(size);
+                }
+            }
+            if let Some(dst) = path_arg("dst") {
+                written.insert(dst.clone(), estimate);
+                if let Some(mount_point) = mount_point(&disks, &dst) {
+                    *needed.entry(mount_point).or_default() += estimate;
+                }
+            }
+
+            result.push(matches);
+        }
+
+        for (mount_point, bytes) in needed {
+            if let Some(disk) = disks.list().iter().find(|d| d.mount_point() == mount_point) {
+                ensure!(
+                    bytes <= disk.available_space(),
+                    "The plan needs about {} bytes on {}, but only {} are available",
+                    bytes,
+                    mount_point.display(),
+                    disk.available_space()
+                );
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Runs the plan.
+    ///
+    /// If `dry_run` is true, the plan is validated and the stages that would
+    /// be run are logged. If `restart` is true, completion markers are
+    /// ignored.
+    pub fn run(&self, dry_run: bool, restart: bool) -> Result<()> {
+        let mut top_command = add_commands(Command::new("webgraph").subcommand_required(true));
+        let matches = self.validate(&mut top_command)?;
+
+        if !dry_run {
+            std::fs::create_dir_all(&self.marker_dir).with_context(|| {
+                format!(
+                    "Could not create marker directory {}",
+                    self.marker_dir.display()
+                )
+            })?;
+        }
+
+        let mut pl = ProgressLogger::default();
+        pl.item_name("stage")
+            .expected_updates(Some(self.stages.len()));
+        if let Some(log_interval) = self.log_interval {
+            pl.log_interval(log_interval);
+        }
+        pl.start("Running pipeline...");
+
+        // Once a stage is run, the following ones must be run, too
+        let mut run_all = restart;
+        for (stage, matches) in self.stages.iter().zip(matches) {
+            let marker = self.marker_dir.join(format!("{}.done", stage.name));
+            let description = stage.description();
+            if !run_all && std::fs::read_to_string(&marker).ok().as_ref() == Some(&description) {
+                log::info!("Skipping completed stage {}", stage.name);
+                pl.update();
+                continue;
+            }
+            run_all = true;
+
+            if dry_run {
+                log::info!("Would run stage {}: {}", stage.name, description);
+                continue;
+            }
+            if marker.exists() {
+                std::fs::remove_file(&marker)
+                    .with_context(|| format!("Could not remove {}", marker.display()))?;
+            }
+
+            log::info!("Running stage {}: {}", stage.name, description);
+            let result = match &stage.input {
+                Some(input) => {
+                    let (_, group_matches) = matches.subcommand().unwrap();
+                    let (_, leaf_matches) = group_matches.subcommand().unwrap();
+                    let file = std::fs::File::open(input)
+                        .with_context(|| format!("Could not open {}", input.display()))?;
+                    from::arcs::from_reader(
+                        from::arcs::CliArgs::from_arg_matches(leaf_matches)?,
+                        std::io::BufReader::new(file),
+                    )
+                }
+                None => dispatch(matches.subcommand().unwrap(), &mut top_command),
+            };
+            result.with_context(|| format!("Stage {} failed", stage.name))?;
+
+            std::fs::write(&marker, description)
+                .with_context(|| format!("Could not write {}", marker.display()))?;
+            pl.update();
+        }
+        pl.done();
+
+        Ok(())
+    }
+}
+
+/// Returns the mount point of the file system that will contain `path`.
+fn mount_point(disks: &Disks, path: &Path) -> Option<PathBuf> {
+    let path = std::env::current_dir().ok()?.join(path);
+    // The nearest existing ancestor
+    let existing = path
+        .ancestors()
+        .find(|p| p.exists())
+        .and_then(|p| p.canonicalize().ok())?;
+    disks
+        .list()
+        .iter()
+        .map(|d| d.mount_point())
+        .filter(|m| existing.starts_with(m))
+        .max_by_key(|m| m.as_os_str().len())
+        .map(Path::to_path_buf)
+}
@@ -0,0 +1,146 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::cli::*;
+use crate::prelude::*;
+use anyhow::{ensure, Result};
+use clap::{ArgMatches, Args, Command, FromArgMatches, ValueEnum};
+use dsi_bitstream::prelude::*;
+use dsi_progress_logger::prelude::*;
+use std::io::Write;
+use std::path::PathBuf;
+
+pub const COMMAND_NAME: &str = "degree";
+
+/// The [`DegreeMode`] of the centrality.
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum Mode {
+    /// Indegree divided by the number of nodes minus one.
+    In,
+    /// Outdegree divided by the number of nodes minus one.
+    Out,
+    /// (indegree + 1) / (outdegree + 1).
+    Ratio,
+}
+
+impl From<Mode> for DegreeMode {
+    fn from(mode: Mode) -> Self {
+        match mode {
+            Mode::In => DegreeMode::In,
+            Mode::Out => DegreeMode::Out,
+            Mode::Ratio => DegreeMode::Ratio,
+        }
+    }
+}
+
+#[derive(Args, Debug)]
+#[command(about = "Computes a degree centrality (normalized indegree, normalized outdegree, or smoothed ratio between indegree and outdegree) of every node, a cheap baseline for iterative centralities.", long_about = None)]
+pub struct CliArgs {
+    /// The basename of the graph.
+    pub src: PathBuf,
+
+    #[arg(short, long, value_enum, default_value_t = Mode::In)]
+    /// The degree centrality to compute.
+    pub mode: Mode,
+
+    #[arg(long)]
+    /// The basename of the transpose of the graph, whose outdegrees will be
+    /// used as indegrees; if missing, indegrees are computed by a pass over
+    /// the graph, using eight bytes per node.
+    pub transposed: Option<PathBuf>,
+
+    #[arg(short, long)]
+    /// Where to write the centralities, one per line in node order; if
+    /// missing, they are written to stdout.
+    pub output: Option<PathBuf>,
+
+    #[clap(flatten)]
+    pub num_threads: NumThreadsArg,
+}
+
+pub fn cli(command: Command) -> Command {
+    command.subcommand(CliArgs::augment_args(Command::new(COMMAND_NAME)).display_order(0))
+}
+
+pub fn main(submatches: &ArgMatches) -> Result<()> {
+    let args = CliArgs::from_arg_matches(submatches)?;
+
+    match get_endianness(&args.src)?.as_str() {
+        #[cfg(any(
+            feature = "be_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        BE::NAME => degree::<BE>(args),
+        #[cfg(any(
+            feature = "le_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        LE::NAME => degree::<LE>(args),
+        e => panic!("Unknown endianness: {}", e),
+    }
+}
+
+pub fn degree<E: Endianness + Send + Sync + 'static>(args: CliArgs) -> Result<()>
+where
+    for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+{
+    let graph = BvGraph::with_basename(&args.src)
+        .endianness::<E>()
+        .mode::<Mmap>()
+        .flags(MemoryFlags::RANDOM_ACCESS)
+        .load()?;
+    let num_nodes = graph.num_nodes();
+    let mode = DegreeMode::from(args.mode);
+    let thread_pool = get_thread_pool(args.num_threads.num_threads);
+
+    let centralities = match &args.transposed {
+        Some(path) => {
+            check_transposed(&args.src, path)?;
+            let transposed = BvGraph::with_basename(path)
+                .endianness::<E>()
+                .mode::<Mmap>()
+                .flags(MemoryFlags::RANDOM_ACCESS)
+                .load()?;
+            ensure!(
+                num_nodes == transposed.num_nodes(),
+                "The graph has {} nodes, but the transposed graph has {} nodes",
+                num_nodes,
+                transposed.num_nodes()
+            );
+            degree_centrality(
+                num_nodes,
+                |x| graph.outdegree(x),
+                |x| transposed.outdegree(x),
+                mode,
+                &thread_pool,
+            )
+        }
+        None => {
+            let indegrees = if matches!(mode, DegreeMode::Out) {
+                Box::default()
+            } else {
+                let mut pl = ProgressLogger::default();
+                pl.display_memory(true);
+                indegrees(&graph, &mut pl)
+            };
+            degree_centrality(
+                num_nodes,
+                |x| graph.outdegree(x),
+                |x| indegrees[x],
+                mode,
+                &thread_pool,
+            )
+        }
+    };
+
+    let mut writer = FileOrStdout::create(args.output.as_ref())?;
+    for centrality in centralities.iter() {
+        writeln!(writer, "{}", centrality)?;
+    }
+    writer.finish()?;
+
+    Ok(())
+}
@@ -0,0 +1,36 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use anyhow::Result;
+use clap::{ArgMatches, Command};
+
+pub mod degree;
+
+pub const COMMAND_NAME: &str = "rank";
+
+pub fn cli(command: Command) -> Command {
+    let sub_command = Command::new(COMMAND_NAME)
+        .about("Compute centralities of the nodes of a BvGraph.")
+        .subcommand_required(true)
+        .arg_required_else_help(true)
+        .allow_external_subcommands(true);
+    let sub_command = degree::cli(sub_command);
+    command.subcommand(sub_command.display_order(0))
+}
+
+pub fn main(submatches: &ArgMatches) -> Result<()> {
+    match submatches.subcommand() {
+        Some((degree::COMMAND_NAME, sub_m)) => degree::main(sub_m),
+        Some((command_name, _)) => {
+            eprintln!("Unknown command: {:?}", command_name);
+            std::process::exit(1);
+        }
+        None => {
+            eprintln!("No command given for rank");
+            std::process::exit(1);
+        }
+    }
+}
@@ -8,19 +8,23 @@
 
 use self::llp::preds::MinAvgImprov;
 
+use crate::cli::check_symmetric;
 use crate::cli::create_parent_dir;
+use crate::cli::IntVectorFormat;
 use crate::cli::NumThreadsArg;
+use crate::cli::OutputFile;
+use crate::graphs::bvgraph::match_select_params;
 use crate::prelude::*;
 use anyhow::{bail, Context, Result};
 use clap::{ArgMatches, Args, Command, FromArgMatches};
 use dsi_bitstream::prelude::*;
 use epserde::prelude::*;
 use llp::invert_permutation;
-use llp::preds::{MaxUpdates, MinGain, MinModified, PercModified};
+use llp::preds::{MaxUpdates, MinGain, MinModified, PercModified, UpdateCap};
 
 use predicates::prelude::*;
 use rayon::prelude::*;
-use std::io::{BufWriter, Write};
+use std::io::Write;
 use std::path::PathBuf;
 
 pub const COMMAND_NAME: &str = "llp";
@@ -49,6 +53,12 @@ pub struct CliArgs {
     /// If specified, the maximum number of updates for a given ɣ.
     pub max_updates: usize,
 
+    #[arg(long)]
+    /// A hard cap on the number of updates for a given ɣ, checked after all
+    /// other stopping criteria; a warning is logged when the cap is hit.
+    /// Unlimited if not specified.
+    pub max_updates_per_gamma: Option<usize>,
+
     #[arg(short = 'M', long)]
     /// If true, updates will be stopped when the number of modified nodes is less
     /// than the square root of the number of nodes of the graph.
@@ -84,6 +94,26 @@ pub struct CliArgs {
     /// The chunk size used to localize the random permutation
     /// (advanced option).
     pub chunk_size: Option<usize>,
+
+    #[arg(long)]
+    /// The weights of the nodes, which scale the contribution of each node to
+    /// the objective function and to the volume of its label (e.g., the
+    /// sizes written by `transform contract --sizes`).
+    pub node_weights: Option<PathBuf>,
+
+    #[arg(long, value_enum, default_value_t = IntVectorFormat::Ascii)]
+    /// The format of the node weights.
+    pub node_weights_format: IntVectorFormat,
+
+    #[arg(long)]
+    /// A directory where the labels of each ɣ are stored, and where the
+    /// combined labels are checkpointed after each combination step.
+    pub checkpoint_combine: Option<PathBuf>,
+
+    #[arg(long, requires = "checkpoint_combine")]
+    /// Resume the combination phase from the checkpoint, if any, without
+    /// recomputing the labels of each ɣ.
+    pub resume: bool,
 }
 
 pub fn cli(command: Command) -> Command {
@@ -94,6 +124,10 @@ pub fn main(submatches: &ArgMatches) -> Result<()> {
     let args = CliArgs::from_arg_matches(submatches)?;
 
     create_parent_dir(&args.perm)?;
+    if let Some(dir) = &args.checkpoint_combine {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Could not create directory {}", dir.display()))?;
+    }
 
     match get_endianness(&args.src)?.as_str() {
         #[cfg(any(
@@ -116,6 +150,8 @@ where
 {
     let start = std::time::Instant::now();
 
+    check_symmetric(&args.src, "LLP")?;
+
     // Load the graph in THP memory
     log::info!(
         "Loading graph {} in THP memory...",
@@ -127,19 +163,6 @@ where
         .endianness::<E>()
         .load()?;
 
-    // Load degree cumulative function in THP memory
-    log::info!("Loading DCF in THP memory...");
-    let deg_cumul = DCF::load_mmap(
-        args.src.with_extension(DEG_CUMUL_EXTENSION),
-        Flags::TRANSPARENT_HUGE_PAGES | Flags::RANDOM_ACCESS,
-    )
-    .with_context(|| {
-        format!(
-            "Could not load degree cumulative function for basename {}",
-            args.src.display()
-        )
-    })?;
-
     // parse the gamma format
     let mut gammas = vec![];
     for gamma in args.gammas {
@@ -172,19 +195,53 @@ where
         predicate = predicate.or(PercModified::try_from(perc_modified)?).boxed();
     }
 
+    // The cap must be the last predicate, so that it is evaluated only if no
+    // other criterion fires
+    if let Some(max_updates) = args.max_updates_per_gamma {
+        predicate = predicate.or(UpdateCap::from(max_updates)).boxed();
+    }
+
     let num_nodes = graph.num_nodes();
 
-    // compute the LLP
-    let labels = llp::layered_label_propagation(
-        graph,
-        &*deg_cumul,
-        gammas,
-        Some(args.num_threads.num_threads),
-        args.chunk_size,
-        args.granularity,
-        args.seed,
-        predicate,
-    )
+    let node_weights = args
+        .node_weights
+        .as_ref()
+        .map(|path| {
+            log::info!("Loading node weights from {}...", path.display());
+            args.node_weights_format.load(path)
+        })
+        .transpose()?;
+
+    // Load degree cumulative function in THP memory, dispatching on the
+    // parameters of its selection structures, and compute the LLP
+    let params = SelectParams::load(&args.src, DCF_SELECT_PROPERTY)?;
+    log::info!("Loading DCF in THP memory...");
+    let labels = match_select_params!(params, L, S => {
+        let deg_cumul = DCFSelect::<L, S>::load_mmap(
+            Basename::new(&args.src).with_ext(DEG_CUMUL_EXTENSION),
+            Flags::TRANSPARENT_HUGE_PAGES | Flags::RANDOM_ACCESS,
+        )
+        .with_context(|| {
+            format!(
+                "Could not load degree cumulative function for basename {}",
+                args.src.display()
+            )
+        })?;
+
+        llp::layered_label_propagation(
+            graph,
+            &*deg_cumul,
+            gammas,
+            Some(args.num_threads.num_threads),
+            args.chunk_size,
+            args.granularity,
+            args.seed,
+            predicate,
+            node_weights.as_deref(),
+            args.checkpoint_combine.as_deref(),
+            args.resume,
+        )
+    })
     .context("Could not compute the LLP")?;
 
     let mut llp_perm = (0..num_nodes).collect::<Vec<_>>();
@@ -198,19 +255,18 @@ where
 
     let perm = args.perm;
 
+    let mut file = OutputFile::create(&perm)?;
     if args.epserde {
         llp_inv_perm
-            .store(&perm)
+            .serialize(&mut file)
             .with_context(|| format!("Could not write permutation to {}", perm.display()))?;
     } else {
-        let mut file = std::fs::File::create(&perm)
-            .with_context(|| format!("Could not create permutation at {}", perm.display()))?;
-        let mut buf = BufWriter::new(&mut file);
         for word in llp_inv_perm.into_iter() {
-            buf.write_all(&word.to_be_bytes())
+            file.write_all(&word.to_be_bytes())
                 .with_context(|| format!("Could not write permutation to {}", perm.display()))?;
         }
     }
+    file.finish()?;
     log::info!("Completed in {} seconds", start.elapsed().as_secs_f64());
     Ok(())
 }
@@ -16,6 +16,7 @@ use std::{
 };
 
 use crate::graphs::bvgraph::GRAPH_EXTENSION;
+use crate::utils::Basename;
 
 pub const COMMAND_NAME: &str = "pad";
 
@@ -56,7 +57,10 @@ pub fn main(submatches: &ArgMatches) -> Result<()> {
         WordSize::U128 => size_of::<u128>(),
     };
 
-    pad(args.basename.with_extension(GRAPH_EXTENSION), word_size)
+    pad(
+        Basename::new(&args.basename).with_ext(GRAPH_EXTENSION),
+        word_size,
+    )
 }
 
 pub fn pad(path: impl AsRef<Path>, block_size: usize) -> Result<()> {
@@ -5,31 +5,75 @@
  * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
  */
 
-use crate::graphs::bvgraph::{get_endianness, CodeRead};
-use crate::traits::SequentialLabeling;
+use crate::cli::{ArcCountArg, BatchSizeArg, CompressTmpArg, PrefetchArg};
+use crate::graphs::arc_count_check::ArcCountCheck;
+use crate::graphs::bvgraph::{get_endianness, BvGraphSeq, CodeRead, PrefetchWordReader};
+use crate::traits::{SequentialGraph, SequentialLabeling};
+use crate::utils::sort_pairs::SortPairs;
 use anyhow::Result;
-use clap::{ArgMatches, Args, Command, FromArgMatches};
+use clap::{ArgMatches, Args, Command, FromArgMatches, ValueEnum};
 use dsi_bitstream::prelude::*;
 use dsi_progress_logger::prelude::*;
 use lender::*;
 use std::io::Write;
 use std::path::PathBuf;
+use tempfile::Builder;
 
 pub const COMMAND_NAME: &str = "csv";
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+/// The order of the arcs in the output.
+pub enum SortBy {
+    /// Arcs are streamed in the order of the graph, that is, sorted by
+    /// source and then by target.
+    Source,
+    /// Arcs are sorted by target and then by source, as in the transpose
+    /// graph.
+    Target,
+}
+
 #[derive(Args, Debug)]
-#[command(about = "Dumps a graph as an ASCII list of arcs to stdout.", long_about = None)]
+#[command(about = "Dumps a graph as an ASCII list of arcs to stdout. The output is deterministic: by default arcs are sorted by source and then by target, but they can also be sorted by target and then by source, which is useful for transpose-friendly dumps and for comparing graphs.", long_about = None)]
 pub struct CliArgs {
     /// The basename of the graph.
     pub src: PathBuf,
 
+    #[clap(flatten)]
+    pub arc_count: ArcCountArg,
+
+    #[clap(flatten)]
+    pub prefetch: PrefetchArg,
+
     #[arg(long, default_value_t = ',')]
     /// The separator between source and target nodes.
     pub separator: char,
+
+    #[arg(long, default_value_t = false)]
+    /// Output one-based node identifiers, adding one to each node; this is
+    /// the inverse of the --one-based option of `from arcs`.
+    pub one_based: bool,
+
+    #[arg(long, value_enum, default_value_t = SortBy::Source)]
+    /// The order of the arcs. Sorting by target requires reading the whole
+    /// graph before writing the first arc: arcs are sorted in batches of the
+    /// given size (which are allocated in core memory) and then spilled to
+    /// temporary files, which need space proportional to the number of arcs
+    /// and are merged during output, so the dump takes time O(m log m).
+    pub sort_by: SortBy,
+
+    #[clap(flatten)]
+    pub batch_size: BatchSizeArg,
+
+    #[clap(flatten)]
+    pub compress_tmp: CompressTmpArg,
 }
 
 pub fn cli(command: Command) -> Command {
-    command.subcommand(CliArgs::augment_args(Command::new(COMMAND_NAME)).display_order(0))
+    command.subcommand(
+        CliArgs::augment_args(Command::new(COMMAND_NAME))
+            .alias("edgelist")
+            .display_order(0),
+    )
 }
 
 pub fn main(submatches: &ArgMatches) -> Result<()> {
@@ -53,10 +97,17 @@ pub fn main(submatches: &ArgMatches) -> Result<()> {
 pub fn to_csv<E: Endianness + 'static>(args: CliArgs) -> Result<()>
 where
     for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+    for<'a> BufBitReader<E, PrefetchWordReader<'a>>: CodeRead<E>,
 {
-    let graph = crate::graphs::bvgraph::sequential::BvGraphSeq::with_basename(args.src)
-        .endianness::<E>()
-        .load()?;
+    let config = BvGraphSeq::with_basename(&args.src).endianness::<E>();
+    match args.prefetch.prefetch {
+        Some(bytes) => write_csv(&args, config.prefetch(bytes).verify_arc_count().load()?),
+        None => write_csv(&args, config.verify_arc_count().load()?),
+    }
+}
+
+/// Writes the arcs of a graph to standard output.
+fn write_csv(args: &CliArgs, graph: ArcCountCheck<impl SequentialGraph>) -> Result<()> {
     let num_nodes = graph.num_nodes();
 
     // read the csv and put it inside the sort pairs
@@ -67,13 +118,42 @@ where
         .expected_updates(Some(num_nodes));
     pl.start("Reading BvGraph");
 
-    for_! ( (src, succ) in graph.iter() {
-        for dst in succ {
-            writeln!(stdout, "{}{}{}", src, args.separator, dst)?;
+    let offset = args.one_based as usize;
+    match args.sort_by {
+        SortBy::Source => {
+            for_! ( (src, succ) in graph.iter() {
+                for dst in succ {
+                    writeln!(stdout, "{}{}{}", src + offset, args.separator, dst + offset)?;
+                }
+                pl.light_update();
+            });
+            pl.done();
         }
-        pl.light_update();
-    });
+        SortBy::Target => {
+            let dir = Builder::new().prefix("to_csv_sort_").tempdir()?;
+            let mut sort_pairs = SortPairs::new(args.batch_size.batch_size, dir.path())?
+                .compression(args.compress_tmp.compress_tmp);
+            for_! ( (src, succ) in graph.iter() {
+                for dst in succ {
+                    sort_pairs.push(dst, src)?;
+                }
+                pl.light_update();
+            });
+            pl.done();
 
-    pl.done();
+            pl.item_name("arc")
+                .expected_updates(graph.num_arcs_hint().map(|n| n as usize));
+            pl.start("Writing arcs sorted by target");
+            for (dst, src, _) in sort_pairs.iter()? {
+                writeln!(stdout, "{}{}{}", src + offset, args.separator, dst + offset)?;
+                pl.light_update();
+            }
+            pl.done();
+        }
+    }
+
+    if !args.arc_count.no_arc_count_check {
+        graph.check()?;
+    }
     Ok(())
 }
@@ -5,6 +5,7 @@
  * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
  */
 
+use crate::cli::ArcCountArg;
 use crate::graphs::bvgraph::{get_endianness, CodeRead};
 use crate::traits::SequentialLabeling;
 use anyhow::Result;
@@ -21,6 +22,9 @@ pub const COMMAND_NAME: &str = "ascii";
 pub struct CliArgs {
     /// The basename of the graph.
     pub src: PathBuf,
+
+    #[clap(flatten)]
+    pub arc_count: ArcCountArg,
 }
 
 pub fn cli(command: Command) -> Command {
@@ -49,8 +53,9 @@ pub fn ascii_convert<E: Endianness + 'static>(args: CliArgs) -> Result<()>
 where
     for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
 {
-    let seq_graph = crate::graphs::bvgraph::sequential::BvGraphSeq::with_basename(args.src)
+    let seq_graph = crate::graphs::bvgraph::sequential::BvGraphSeq::with_basename(&args.src)
         .endianness::<E>()
+        .verify_arc_count()
         .load()?;
 
     let mut pl = ProgressLogger::default();
@@ -70,6 +75,10 @@ where
     }
 
     pl.done();
+    drop(iter);
 
+    if !args.arc_count.no_arc_count_check {
+        seq_graph.check()?;
+    }
     Ok(())
 }
@@ -5,16 +5,23 @@
  * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
  */
 
+use crate::cli::build::dcf::store_dcf;
+use crate::cli::build::ef::store_offsets_ef;
 use crate::cli::create_parent_dir;
 use crate::cli::*;
 use crate::prelude::*;
-use anyhow::Result;
+use anyhow::{ensure, Context, Result};
 use clap::{ArgMatches, Args, Command, FromArgMatches};
 use dsi_bitstream::prelude::*;
-use epserde::deser::DeserializeInner;
+use dsi_progress_logger::prelude::*;
+use epserde::deser::Flags;
+use epserde::prelude::Deserialize;
+use lender::*;
 use mmap_rs::MmapFlags;
-use std::path::PathBuf;
-use tempfile::Builder;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use sux::bits::BitVec;
 
 pub const COMMAND_NAME: &str = "bvgraph";
 
@@ -33,9 +40,84 @@ pub struct CliArgs {
     /// The path to an optional permutation in binary big-endian format to be applied to the graph.
     pub permutation: Option<PathBuf>,
 
+    #[clap(flatten)]
+    pub perm_mode: PermModeArg,
+
+    #[clap(long, requires = "permutation")]
+    /// Materialize the permuted graph in memory instead of sorting its arcs
+    /// externally.
+    pub in_memory: bool,
+
+    #[clap(long, requires = "permutation")]
+    /// Materialize the permuted graph in memory if its estimated size in
+    /// bytes is at most this value.
+    pub in_memory_budget: Option<usize>,
+
+    #[clap(long, requires = "permutation", conflicts_with_all = ["in_memory", "in_memory_budget"])]
+    /// Permute also the γ-coded labels of the graph (e.g., the multiplicities
+    /// written by "from arcs --multigraph"), reading them from the files with
+    /// extension .labels and .labelef, and writing them in big-endian format,
+    /// with their γ-coded offsets, to the files with extension .labels and
+    /// .labeloffsets of the destination.
+    pub labels: bool,
+
+    #[clap(long, conflicts_with = "permutation", requires = "changed")]
+    /// The basename of an old version of the graph, compressed with the .ef
+    /// file, whose bitstream will be reused for nodes that did not change;
+    /// the compression parameters, codes and endianness of the old graph will
+    /// be used.
+    pub reuse_old: Option<PathBuf>,
+
+    #[clap(long, requires = "reuse_old")]
+    /// A bit vector, serialized with ε-serde, whose set bits mark the nodes
+    /// whose successor lists are different in the old graph.
+    pub changed: Option<PathBuf>,
+
+    #[clap(long, conflicts_with_all = ["labels", "reuse_old"])]
+    /// Sort the successors of each node before compressing it, guaranteeing
+    /// a valid output even if the source returns unsorted successors (e.g.,
+    /// when it is an adapter graph). Sorting costs O(d log d) per node of
+    /// outdegree d, plus a copy of the successor list. Labeled successors
+    /// cannot be sorted, so this option is incompatible with --labels.
+    pub sort_successors: bool,
+
+    #[clap(long, requires = "sort_successors")]
+    /// Remove duplicate successors after sorting them. The arc-count check
+    /// is performed on the source arcs, before deduplication.
+    pub dedup: bool,
+
+    #[clap(long, conflicts_with = "reuse_old")]
+    /// Write the number of bits used by each component of the successor
+    /// lists, and the number of bits per node and per arc, in JSON format to
+    /// the file with extension .stats of the destination.
+    pub stats: bool,
+
+    #[clap(long, conflicts_with = "reuse_old")]
+    /// Record the length in bits of each successor list during compression
+    /// and build the Elias–Fano representation of the offsets (the .ef file);
+    /// the result is identical to that of "build ef", but neither the graph
+    /// nor the .offsets file are read again. Uses one word of memory per node.
+    pub build_ef: bool,
+
+    #[clap(long, conflicts_with = "reuse_old")]
+    /// Record the outdegrees during compression and build the Elias–Fano
+    /// representation of the degree cumulative function (the .dcf file); the
+    /// result is identical to that of "build dcf", but the graph is not read
+    /// again. Uses one word of memory per node.
+    pub build_dcf: bool,
+
+    #[arg(long, default_value_t = SelectParams::default())]
+    /// The parameters L,S of the selection structures built by --build-ef and
+    /// --build-dcf (see the documentation of SelectParams for the supported
+    /// values).
+    pub select_params: SelectParams,
+
     #[clap(flatten)]
     pub batch_size: BatchSizeArg,
 
+    #[clap(flatten)]
+    pub arc_count: ArcCountArg,
+
     #[clap(flatten)]
     pub ca: CompressArgs,
 }
@@ -49,6 +131,29 @@ pub fn main(submatches: &ArgMatches) -> Result<()> {
     let args = CliArgs::from_arg_matches(submatches)?;
 
     create_parent_dir(&args.dst)?;
+    let output = GraphOutput::new(&args.dst);
+
+    if args.reuse_old.is_some() {
+        match get_endianness(&args.src)?.as_str() {
+            #[cfg(any(
+                feature = "be_bins",
+                not(any(feature = "be_bins", feature = "le_bins"))
+            ))]
+            BE::NAME => reuse_old::<BE>(args)?,
+            #[cfg(any(
+                feature = "le_bins",
+                not(any(feature = "be_bins", feature = "le_bins"))
+            ))]
+            LE::NAME => reuse_old::<LE>(args)?,
+            e => panic!("Unknown endianness: {}", e),
+        };
+        output.finish()?;
+        log::info!(
+            "The re-compression took {:.3} seconds",
+            start.elapsed().as_secs_f64()
+        );
+        return Ok(());
+    }
 
     let permutation = if let Some(path) = args.permutation.as_ref() {
         Some(JavaPermutation::mmap(path, MmapFlags::RANDOM_ACCESS)?)
@@ -70,6 +175,7 @@ pub fn main(submatches: &ArgMatches) -> Result<()> {
         LE::NAME => compress::<LE>(args, target_endianness, permutation)?,
         e => panic!("Unknown endianness: {}", e),
     };
+    output.finish()?;
 
     log::info!(
         "The re-compression took {:.3} seconds",
@@ -86,90 +192,507 @@ pub fn compress<E: Endianness + Clone + Send + Sync>(
 where
     for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
 {
-    let dir = Builder::new().prefix("to_bvgraph_").tempdir()?;
-
     let thread_pool = crate::cli::get_thread_pool(args.num_threads.num_threads);
 
-    if args.src.with_extension(EF_EXTENSION).exists() {
+    // check the length of the permutation before starting
+    let permutation = match permutation {
+        Some(permutation) => {
+            let (num_nodes, _, _) =
+                parse_properties::<E>(Basename::new(&args.src).with_ext(PROPERTIES_EXTENSION))?;
+            Some(fit_perm(permutation, num_nodes, args.perm_mode.into())?)
+        }
+        None => None,
+    };
+
+    if args.labels {
+        let permutation = permutation.expect("--labels requires --permutation");
+        return compress_labeled::<E>(args, target_endianness, permutation, &thread_pool);
+    }
+
+    if Basename::new(&args.src).with_ext(EF_EXTENSION).exists() {
         let graph = BvGraph::with_basename(&args.src).endianness::<E>().load()?;
 
-        if let Some(permutation) = permutation {
+        let materialized = match &permutation {
+            Some(permutation) => materialize_if_fits(&args, &graph, permutation)?,
+            None => None,
+        };
+
+        if let Some(csr) = materialized {
+            compress_graph_sorted(
+                &csr,
+                args.sort_successors.then_some(args.dedup),
+                args.stats,
+                args.build_dcf.then_some(args.select_params),
+                args.build_ef.then_some(args.select_params),
+                args.dst,
+                args.ca.into(),
+                &thread_pool,
+                &target_endianness.unwrap_or_else(|| E::NAME.into()),
+            )?;
+        } else if let Some(permutation) = permutation {
             let batch_size = args.batch_size.batch_size;
 
             log::info!("Permuting graph with batch size {}", batch_size);
             let start = std::time::Instant::now();
             // TODO!: this type annotation is not needed in the nightly version
             let sorted = crate::transform::permute_split::<
-                BvGraph<
-                    DynCodesDecoderFactory<
-                        E,
-                        MmapHelper<u32>,
-                        <EF as DeserializeInner>::DeserType<'_>,
-                    >,
-                >,
-                JavaPermutation,
+                BvGraph<DynCodesDecoderFactory<E, MmapHelper<u32>, DispatchEFDeser>>,
+                FittedPerm<JavaPermutation>,
             >(&graph, &permutation, batch_size, &thread_pool)?;
             log::info!(
                 "Permuted the graph. It took {:.3} seconds",
                 start.elapsed().as_secs_f64()
             );
-            BvComp::parallel_endianness(
-                args.dst,
+            compress_graph_sorted(
                 &sorted,
-                sorted.num_nodes(),
+                args.sort_successors.then_some(args.dedup),
+                args.stats,
+                args.build_dcf.then_some(args.select_params),
+                args.build_ef.then_some(args.select_params),
+                args.dst,
                 args.ca.into(),
                 &thread_pool,
-                dir,
                 &target_endianness.unwrap_or_else(|| E::NAME.into()),
             )?;
         } else {
-            BvComp::parallel_endianness(
-                args.dst,
+            let num_arcs = graph.num_arcs();
+            let graph = ArcCountCheck::new(&graph, num_arcs);
+            compress_graph_sorted(
                 &graph,
-                graph.num_nodes(),
+                args.sort_successors.then_some(args.dedup),
+                args.stats,
+                args.build_dcf.then_some(args.select_params),
+                args.build_ef.then_some(args.select_params),
+                args.dst,
                 args.ca.into(),
                 &thread_pool,
-                dir,
                 &target_endianness.unwrap_or_else(|| E::NAME.into()),
             )?;
+            if !args.arc_count.no_arc_count_check {
+                graph.check()?;
+            }
         }
     } else {
         log::warn!("The .ef file does not exist. The graph will be sequentially which will result in slower compression. If you can, run `build_ef` before recompressing.");
         let seq_graph = BvGraphSeq::with_basename(&args.src)
             .endianness::<E>()
+            .verify_arc_count()
             .load()?;
 
-        if let Some(permutation) = permutation {
-            let batch_size = args.batch_size.batch_size;
+        let materialized = match &permutation {
+            Some(permutation) => materialize_if_fits(&args, &seq_graph, permutation)?,
+            None => None,
+        };
 
-            log::info!("Permuting graph with batch size {}", batch_size);
-            let start = std::time::Instant::now();
-            let permuted = crate::transform::permute(&seq_graph, &permutation, batch_size)?;
-            log::info!(
-                "Permuted the graph. It took {:.3} seconds",
-                start.elapsed().as_secs_f64()
-            );
-
-            BvComp::parallel_endianness(
+        if let Some(csr) = materialized {
+            // Materialization iterates twice on the graph, so we check the
+            // number of arcs of the result instead
+            if !args.arc_count.no_arc_count_check {
+                ensure!(
+                    csr.num_arcs() == seq_graph.expected(),
+                    "The number of decoded arcs ({}) differs from the number of arcs in the properties ({}): the graph might be corrupted",
+                    csr.num_arcs(),
+                    seq_graph.expected()
+                );
+            }
+            compress_graph_sorted(
+                &csr,
+                args.sort_successors.then_some(args.dedup),
+                args.stats,
+                args.build_dcf.then_some(args.select_params),
+                args.build_ef.then_some(args.select_params),
                 args.dst,
-                &permuted,
-                permuted.num_nodes(),
                 args.ca.into(),
                 &thread_pool,
-                dir,
                 &target_endianness.unwrap_or_else(|| E::NAME.into()),
             )?;
         } else {
-            BvComp::parallel_endianness(
-                args.dst,
-                &seq_graph,
-                seq_graph.num_nodes(),
-                args.ca.into(),
-                &thread_pool,
-                dir,
-                &target_endianness.unwrap_or_else(|| E::NAME.into()),
-            )?;
+            if let Some(permutation) = permutation {
+                let batch_size = args.batch_size.batch_size;
+
+                log::info!("Permuting graph with batch size {}", batch_size);
+                let start = std::time::Instant::now();
+                let permuted = crate::transform::permute(&seq_graph, &permutation, batch_size)?;
+                log::info!(
+                    "Permuted the graph. It took {:.3} seconds",
+                    start.elapsed().as_secs_f64()
+                );
+
+                compress_graph_sorted(
+                    &permuted,
+                    args.sort_successors.then_some(args.dedup),
+                    args.stats,
+                    args.build_dcf.then_some(args.select_params),
+                    args.build_ef.then_some(args.select_params),
+                    args.dst,
+                    args.ca.into(),
+                    &thread_pool,
+                    &target_endianness.unwrap_or_else(|| E::NAME.into()),
+                )?;
+            } else {
+                compress_graph_sorted(
+                    &seq_graph,
+                    args.sort_successors.then_some(args.dedup),
+                    args.stats,
+                    args.build_dcf.then_some(args.select_params),
+                    args.build_ef.then_some(args.select_params),
+                    args.dst,
+                    args.ca.into(),
+                    &thread_pool,
+                    &target_endianness.unwrap_or_else(|| E::NAME.into()),
+                )?;
+            }
+
+            if !args.arc_count.no_arc_count_check {
+                seq_graph.check()?;
+            }
         }
     }
     Ok(())
 }
+
+/// Recompresses a graph reusing the bitstream of an old version of the graph
+/// for the nodes that did not change.
+pub fn reuse_old<E: Endianness>(args: CliArgs) -> Result<()>
+where
+    BufBitWriter<E, WordAdapter<usize, BufWriter<File>>>: CodeWrite<E>,
+    for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+{
+    let old_basename = args
+        .reuse_old
+        .as_ref()
+        .expect("--changed requires --reuse-old");
+    let changed_path = args
+        .changed
+        .as_ref()
+        .expect("--reuse-old requires --changed");
+    let changed = <BitVec>::mmap(changed_path, Flags::empty())
+        .with_context(|| format!("Could not load {}", changed_path.display()))?;
+    let seq_graph = BvGraphSeq::with_basename(&args.src)
+        .endianness::<E>()
+        .load()?;
+    let stats = BvComp::reuse_old::<E, _, _>(old_basename, &args.dst, &seq_graph, &*changed)?;
+    if !args.arc_count.no_arc_count_check {
+        ensure!(
+            Some(stats.num_arcs) == seq_graph.num_arcs_hint(),
+            "The number of compressed arcs ({}) differs from the number of arcs in the properties ({:?}): the graph might be corrupted",
+            stats.num_arcs,
+            seq_graph.num_arcs_hint()
+        );
+    }
+    log::info!("Run \"webgraph build ef\" to build the Elias-Fano offsets of the new graph");
+    Ok(())
+}
+
+/// Permutes a graph together with its γ-coded labels, compressing the
+/// permuted graph and writing the permuted labels.
+fn compress_labeled<E: Endianness + Clone + Send + Sync>(
+    args: CliArgs,
+    target_endianness: Option<String>,
+    permutation: FittedPerm<JavaPermutation>,
+    thread_pool: &rayon::ThreadPool,
+) -> Result<()>
+where
+    for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+{
+    let seq_graph = BvGraphSeq::with_basename(&args.src)
+        .endianness::<E>()
+        .load()?;
+    let labels =
+        crate::labels::bitstream::MmapBitStreamLabeling::<BE, _>::mmap(&args.src, GammaLabel)?;
+    let graph = Zip(seq_graph, labels);
+
+    let batch_size = args.batch_size.batch_size;
+    log::info!("Permuting labeled graph with batch size {}", batch_size);
+    let start = std::time::Instant::now();
+    let permuted = crate::transform::permute_labeled(
+        &graph,
+        &permutation,
+        batch_size,
+        GammaLabel,
+        GammaLabel,
+    )?;
+    log::info!(
+        "Permuted the graph. It took {:.3} seconds",
+        start.elapsed().as_secs_f64()
+    );
+
+    let stats = compress_graph_recording(
+        &Left(permuted.clone()),
+        args.build_dcf.then_some(args.select_params),
+        args.build_ef.then_some(args.select_params),
+        &args.dst,
+        args.ca.into(),
+        thread_pool,
+        &target_endianness.unwrap_or_else(|| E::NAME.into()),
+    )?;
+    if args.stats {
+        stats.store(&args.dst)?;
+    }
+    write_labels(&args.dst, &permuted)
+}
+
+/// Writes the γ-coded labels of a labeled graph in a big-endian bitstream,
+/// together with the γ-coded differences between the bit offsets of the labels
+/// of consecutive nodes (starting with a zero).
+pub fn write_labels(
+    basename: impl AsRef<Path>,
+    graph: &impl LabeledSequentialGraph<u64>,
+) -> Result<()> {
+    let basename = Basename::new(basename);
+    let labels_path = basename.with_ext(LABELS_EXTENSION);
+    let mut labels = <BufBitWriter<BE, _>>::new(<WordAdapter<usize, _>>::new(OutputFile::create(
+        &labels_path,
+    )?));
+    let offsets_path = basename.with_ext(LABELOFFSETS_EXTENSION);
+    let mut offsets = <BufBitWriter<BE, _>>::new(<WordAdapter<usize, _>>::new(OutputFile::create(
+        &offsets_path,
+    )?));
+
+    let mut pl = ProgressLogger::default();
+    pl.display_memory(true)
+        .item_name("node")
+        .expected_updates(Some(graph.num_nodes()));
+    pl.start("Writing labels...");
+    offsets.write_gamma(0)?;
+    for_!( (_node, succ) in graph.iter() {
+        let mut bits = 0;
+        for (_, label) in succ {
+            bits += labels.write_gamma(label)?;
+        }
+        offsets.write_gamma(bits as u64)?;
+        pl.light_update();
+    });
+    pl.done();
+    for (stream, path) in [(labels, labels_path), (offsets, offsets_path)] {
+        stream
+            .into_inner()
+            .with_context(|| format!("Could not flush {}", path.display()))?
+            .into_inner()
+            .finish()?;
+    }
+    Ok(())
+}
+
+/// Materializes the permuted graph in memory if requested by `--in-memory`,
+/// or if its estimated size is within the budget given by
+/// `--in-memory-budget`.
+fn materialize_if_fits(
+    args: &CliArgs,
+    graph: &impl SequentialGraph,
+    permutation: &FittedPerm<JavaPermutation>,
+) -> Result<Option<CsrGraph>> {
+    let permuted = PermutedGraph {
+        graph,
+        perm: permutation,
+    };
+    let fits = args.in_memory_budget.is_some_and(|budget| {
+        permuted
+            .materialized_size()
+            .is_some_and(|size| size <= budget)
+    });
+    if !args.in_memory && !fits {
+        return Ok(None);
+    }
+
+    log::info!("Materializing the permuted graph in memory");
+    let start = std::time::Instant::now();
+    let csr = permuted.materialize()?;
+    log::info!(
+        "Materialized the graph. It took {:.3} seconds",
+        start.elapsed().as_secs_f64()
+    );
+    Ok(Some(csr))
+}
+
+/// Compresses a graph using [`compress_graph_recording`], first wrapping it in
+/// a [`SortedSuccessorsGraph`] if `sort` is not `None`; in that case, the
+/// value of `sort` says whether duplicate successors should be removed.
+/// If `store_stats` is true, the compression statistics are stored in the
+/// `.stats` file of the destination.
+#[allow(clippy::too_many_arguments)]
+fn compress_graph_sorted<G: SequentialGraph + SplitLabeling>(
+    graph: &G,
+    sort: Option<bool>,
+    store_stats: bool,
+    build_dcf: Option<SelectParams>,
+    build_ef: Option<SelectParams>,
+    dst: impl AsRef<Path> + Send + Sync,
+    compression_flags: CompFlags,
+    thread_pool: &rayon::ThreadPool,
+    endianness: &str,
+) -> Result<CompStats> {
+    let dst = dst.as_ref();
+    let stats = match sort {
+        Some(dedup) => {
+            log::info!(
+                "Sorting {}successors before compression",
+                if dedup { "and deduplicating " } else { "" }
+            );
+            compress_graph_recording(
+                &SortedSuccessorsGraph::new(graph, dedup),
+                build_dcf,
+                build_ef,
+                dst,
+                compression_flags,
+                thread_pool,
+                endianness,
+            )
+        }
+        None => compress_graph_recording(
+            graph,
+            build_dcf,
+            build_ef,
+            dst,
+            compression_flags,
+            thread_pool,
+            endianness,
+        ),
+    }?;
+    if store_stats {
+        stats.store(dst)?;
+    }
+    Ok(stats)
+}
+
+/// Compresses a graph using [`compress_graph_offsets`]; if `build_dcf` is not
+/// `None`, the outdegrees are recorded during compression using a
+/// [`DegreeRecorder`], and used to build the degree cumulative function of
+/// the destination with the given selection parameters.
+fn compress_graph_recording<G: SequentialGraph + SplitLabeling>(
+    graph: &G,
+    build_dcf: Option<SelectParams>,
+    build_ef: Option<SelectParams>,
+    dst: &Path,
+    compression_flags: CompFlags,
+    thread_pool: &rayon::ThreadPool,
+    endianness: &str,
+) -> Result<CompStats> {
+    let Some(select_params) = build_dcf else {
+        return compress_graph_offsets(
+            graph,
+            build_ef,
+            dst,
+            compression_flags,
+            thread_pool,
+            endianness,
+        );
+    };
+    let recorder = DegreeRecorder::new(graph);
+    let stats = compress_graph_offsets(
+        &recorder,
+        build_ef,
+        dst,
+        compression_flags,
+        thread_pool,
+        endianness,
+    )?;
+    let num_nodes = recorder.num_nodes();
+    let degrees = recorder.into_degrees();
+
+    let mut pl = ProgressLogger::default();
+    pl.display_memory(true)
+        .item_name("node")
+        .expected_updates(Some(num_nodes));
+    pl.start("Building the degree cumulative function from the recorded outdegrees...");
+    store_dcf(
+        dst,
+        num_nodes,
+        degrees.iter().sum(),
+        degrees,
+        select_params,
+        &mut pl,
+    )?;
+    Ok(stats)
+}
+
+/// Compresses a graph using [`compress_graph`]; if `build_ef` is not `None`,
+/// the length in bits of each successor list is recorded during compression
+/// using
+/// [`compress_with_node_bits`](crate::graphs::bvgraph::compress_with_node_bits),
+/// and used to build the Elias–Fano representation of the offsets of the
+/// destination with the given selection parameters.
+fn compress_graph_offsets<G: SequentialGraph + SplitLabeling>(
+    graph: &G,
+    build_ef: Option<SelectParams>,
+    dst: &Path,
+    compression_flags: CompFlags,
+    thread_pool: &rayon::ThreadPool,
+    endianness: &str,
+) -> Result<CompStats> {
+    let Some(select_params) = build_ef else {
+        return compress_graph(graph, dst, compression_flags, thread_pool, endianness);
+    };
+    let mut pl = ProgressLogger::default();
+    pl.display_memory(true);
+    let (stats, node_bits) = match endianness {
+        #[cfg(any(
+            feature = "be_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        BE::NAME => crate::graphs::bvgraph::compress_with_node_bits::<BE, _>(
+            graph,
+            dst,
+            compression_flags,
+            thread_pool,
+            &mut pl,
+        ),
+        #[cfg(any(
+            feature = "le_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        LE::NAME => crate::graphs::bvgraph::compress_with_node_bits::<LE, _>(
+            graph,
+            dst,
+            compression_flags,
+            thread_pool,
+            &mut pl,
+        ),
+        e => anyhow::bail!("Unknown endianness {}", e),
+    }?;
+
+    let num_nodes = node_bits.len();
+    pl.item_name("node").expected_updates(Some(num_nodes));
+    pl.start("Building the Elias–Fano offsets from the recorded lengths...");
+    store_offsets_ef(dst, num_nodes, node_bits, select_params, &mut pl)?;
+    Ok(stats)
+}
+
+/// Compresses a graph using
+/// [`compress_with_stats`](crate::graphs::bvgraph::compress_with_stats) with
+/// the endianness specified by a string.
+pub fn compress_graph<G: SequentialGraph + SplitLabeling>(
+    graph: &G,
+    dst: impl AsRef<Path> + Send + Sync,
+    compression_flags: CompFlags,
+    thread_pool: &rayon::ThreadPool,
+    endianness: &str,
+) -> Result<CompStats> {
+    let mut pl = ProgressLogger::default();
+    pl.display_memory(true);
+    match endianness {
+        #[cfg(any(
+            feature = "be_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        BE::NAME => crate::graphs::bvgraph::compress_with_stats::<BE, _>(
+            graph,
+            dst,
+            compression_flags,
+            thread_pool,
+            &mut pl,
+        ),
+        #[cfg(any(
+            feature = "le_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        LE::NAME => crate::graphs::bvgraph::compress_with_stats::<LE, _>(
+            graph,
+            dst,
+            compression_flags,
+            thread_pool,
+            &mut pl,
+        ),
+        e => anyhow::bail!("Unknown endianness {}", e),
+    }
+}
@@ -4,15 +4,13 @@
 * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
 */
 
-use crate::cli::create_parent_dir;
+use crate::cli::{create_parent_dir, GraphOutput, OutputFile};
 use crate::prelude::*;
 use anyhow::{Context, Result};
 use clap::{ArgMatches, Args, Command, FromArgMatches};
 use dsi_bitstream::prelude::*;
 use dsi_progress_logger::prelude::*;
 use log::info;
-use std::fs::File;
-use std::io::BufWriter;
 use std::path::PathBuf;
 
 pub const COMMAND_NAME: &str = "endianness";
@@ -38,7 +36,8 @@ macro_rules! impl_convert {
             <$dst>::NAME
         );
 
-        let properties_path = $args.src.with_extension(PROPERTIES_EXTENSION);
+        let output = GraphOutput::new(&$args.dst);
+        let properties_path = Basename::new(&$args.src).with_ext(PROPERTIES_EXTENSION);
         let (num_nodes, num_arcs, comp_flags) = parse_properties::<$src>(&properties_path)?;
         // also extract the bitstream length
         let f = std::fs::File::open(&properties_path)
@@ -70,35 +69,46 @@ macro_rules! impl_convert {
             .load()
             .with_context(|| format!("Could not load graph {}", $args.src.display()))?;
         // build the encoder with the opposite endianness
+        let target_properties_path = Basename::new(&$args.dst).with_ext(PROPERTIES_EXTENSION);
         std::fs::write(
-            &properties_path,
+            &target_properties_path,
             comp_flags.to_properties::<$dst>(num_nodes, num_arcs, bitstream_len)?,
         )
         .with_context(|| {
             format!(
                 "Could not write properties to {}",
-                properties_path.display()
+                target_properties_path.display()
             )
         })?;
-        let target_graph_path = $args.dst.with_extension(GRAPH_EXTENSION);
-        let writer = <BufBitWriter<$dst, _>>::new(<WordAdapter<usize, _>>::new(BufWriter::new(
-            File::create(&target_graph_path)
-                .with_context(|| format!("Could not create {}", target_graph_path.display()))?,
-        )));
-        let encoder = <DynCodesEncoder<$dst, _>>::new(writer, &comp_flags);
+        let target_graph_path = Basename::new(&$args.dst).with_ext(GRAPH_EXTENSION);
+        let writer = <BufBitWriter<$dst, _>>::new(<WordAdapter<usize, _>>::new(
+            OutputFile::create(&target_graph_path)?,
+        ));
+        let mut encoder = <DynCodesEncoder<$dst, _>>::new(writer, &comp_flags);
         // build the iterator that will read the graph and write it to the encoder
 
         pl.start("Inverting endianness...");
 
         let mut iter = seq_graph
             .offset_deg_iter()
-            .map_decoder(move |decoder| Converter { decoder, encoder });
+            .map_decoder(|decoder| Converter {
+                decoder,
+                encoder: &mut encoder,
+            });
         // consume the graph iterator reading all codes, but do nothing with them
         for _ in 0..num_nodes {
             iter.next_degree()?;
             pl.light_update();
         }
         pl.done();
+        drop(iter);
+        encoder
+            .into_inner()
+            .into_inner()
+            .with_context(|| format!("Could not flush {}", target_graph_path.display()))?
+            .into_inner()
+            .finish()?;
+        output.finish()?;
     };
 }
 
@@ -0,0 +1,107 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::cli::*;
+use crate::prelude::*;
+use anyhow::{ensure, Result};
+use clap::{ArgMatches, Args, Command, FromArgMatches};
+use dsi_bitstream::prelude::*;
+use std::path::PathBuf;
+use tempfile::Builder;
+
+pub const COMMAND_NAME: &str = "complement";
+
+#[derive(Args, Debug)]
+#[command(about = "Computes the complement of a BvGraph (all arcs not in the graph, excluding self-loops). Since the complement of a sparse graph is dense, this is meant for small graphs.", long_about = None)]
+pub struct CliArgs {
+    /// The basename of the graph.
+    pub src: PathBuf,
+    /// The basename of the complement.
+    pub dst: PathBuf,
+
+    #[arg(long, default_value_t = DEFAULT_COMPLEMENT_MAX_SIZE)]
+    /// The maximum square of the number of nodes of the graph.
+    pub max_size: u64,
+
+    #[arg(long)]
+    /// Compute the complement even if the square of the number of nodes
+    /// exceeds the maximum size.
+    pub force: bool,
+
+    #[clap(flatten)]
+    pub num_threads: NumThreadsArg,
+
+    #[clap(flatten)]
+    pub ca: CompressArgs,
+}
+
+pub fn cli(command: Command) -> Command {
+    command.subcommand(CliArgs::augment_args(Command::new(COMMAND_NAME)).display_order(0))
+}
+
+pub fn main(submatches: &ArgMatches) -> Result<()> {
+    let args = CliArgs::from_arg_matches(submatches)?;
+
+    create_parent_dir(&args.dst)?;
+
+    match get_endianness(&args.src)?.as_str() {
+        #[cfg(any(
+            feature = "be_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        BE::NAME => complement::<BE>(args),
+        #[cfg(any(
+            feature = "le_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        LE::NAME => complement::<LE>(args),
+        e => panic!("Unknown endianness: {}", e),
+    }
+}
+
+pub fn complement<E: Endianness + Send + Sync + 'static>(args: CliArgs) -> Result<()>
+where
+    for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+{
+    let thread_pool = crate::cli::get_thread_pool(args.num_threads.num_threads);
+    let output = GraphOutput::new(&args.dst);
+
+    let seq_graph = crate::graphs::bvgraph::sequential::BvGraphSeq::with_basename(&args.src)
+        .endianness::<E>()
+        .load()?;
+    let num_nodes = seq_graph.num_nodes();
+    let size = (num_nodes as u128) * (num_nodes as u128);
+    if size > args.max_size as u128 {
+        ensure!(
+            args.force,
+            "The complement of a graph with {} nodes might have up to {} arcs, more than the limit of {} (use --force to compute it anyway)",
+            num_nodes,
+            size,
+            args.max_size
+        );
+        log::warn!(
+            "The complement of a graph with {} nodes might have up to {} arcs",
+            num_nodes,
+            size
+        );
+    }
+
+    let complement = ComplementGraph(seq_graph);
+    let target_endianness = args.ca.endianness.clone();
+    let dir = Builder::new().prefix("transform_complement_").tempdir()?;
+    BvComp::parallel_endianness(
+        &args.dst,
+        &complement,
+        num_nodes,
+        args.ca.into(),
+        &thread_pool,
+        dir,
+        &target_endianness.unwrap_or_else(|| E::NAME.into()),
+    )?;
+    output.finish()?;
+
+    Ok(())
+}
@@ -0,0 +1,120 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::cli::*;
+use crate::prelude::*;
+use anyhow::Result;
+use clap::{ArgMatches, Args, Command, FromArgMatches};
+use dsi_bitstream::prelude::*;
+use std::path::PathBuf;
+use tempfile::Builder;
+
+pub const COMMAND_NAME: &str = "contract";
+
+#[derive(Args, Debug)]
+#[command(about = "Contracts a BvGraph by a mapping from nodes to supernodes: the contracted graph has an arc between two supernodes if some node of the first has an arc towards some node of the second. Duplicate arcs are removed, and loops are removed unless --keep-loops is specified.", long_about = None)]
+pub struct CliArgs {
+    /// The basename of the graph.
+    pub src: PathBuf,
+    /// The basename of the contracted graph.
+    pub dst: PathBuf,
+
+    #[arg(long)]
+    /// The mapping from nodes to supernodes; the number of supernodes is the
+    /// maximum value plus one.
+    pub mapping: PathBuf,
+
+    #[arg(long, value_enum, default_value_t = IntVectorFormat::Ascii)]
+    /// The format of the mapping.
+    pub mapping_format: IntVectorFormat,
+
+    #[arg(long)]
+    /// Keep the loops of the contracted graph.
+    pub keep_loops: bool,
+
+    #[arg(long)]
+    /// Also store the number of nodes mapped to each supernode in the given
+    /// file, in the format of the mapping.
+    pub sizes: Option<PathBuf>,
+
+    #[clap(flatten)]
+    pub num_threads: NumThreadsArg,
+
+    #[clap(flatten)]
+    pub batch_size: BatchSizeArg,
+
+    #[clap(flatten)]
+    pub ca: CompressArgs,
+}
+
+pub fn cli(command: Command) -> Command {
+    command.subcommand(CliArgs::augment_args(Command::new(COMMAND_NAME)).display_order(0))
+}
+
+pub fn main(submatches: &ArgMatches) -> Result<()> {
+    let args = CliArgs::from_arg_matches(submatches)?;
+
+    create_parent_dir(&args.dst)?;
+
+    match get_endianness(&args.src)?.as_str() {
+        #[cfg(any(
+            feature = "be_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        BE::NAME => contract::<BE>(args),
+        #[cfg(any(
+            feature = "le_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        LE::NAME => contract::<LE>(args),
+        e => panic!("Unknown endianness: {}", e),
+    }
+}
+
+pub fn contract<E: Endianness + 'static>(args: CliArgs) -> Result<()>
+where
+    for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+{
+    let thread_pool = crate::cli::get_thread_pool(args.num_threads.num_threads);
+    let output = GraphOutput::new(&args.dst);
+
+    let seq_graph = crate::graphs::bvgraph::sequential::BvGraphSeq::with_basename(&args.src)
+        .endianness::<E>()
+        .load()?;
+    let mapping = args.mapping_format.load(&args.mapping)?;
+
+    let contracted = crate::transform::contract(
+        &seq_graph,
+        &mapping,
+        args.keep_loops,
+        args.batch_size.batch_size,
+    )?;
+    log::info!(
+        "Contracting {} nodes into {} supernodes",
+        seq_graph.num_nodes(),
+        contracted.num_nodes()
+    );
+
+    let target_endianness = args.ca.endianness.clone();
+    let dir = Builder::new().prefix("transform_contract_").tempdir()?;
+    BvComp::parallel_endianness(
+        &args.dst,
+        &contracted,
+        contracted.num_nodes(),
+        args.ca.into(),
+        &thread_pool,
+        dir,
+        &target_endianness.unwrap_or_else(|| E::NAME.into()),
+    )?;
+    output.finish()?;
+
+    if let Some(path) = &args.sizes {
+        args.mapping_format
+            .store(path, &supernode_sizes(&mapping))?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,107 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::cli::*;
+use crate::prelude::*;
+use anyhow::{ensure, Result};
+use clap::{ArgMatches, Args, Command, FromArgMatches};
+use dsi_bitstream::prelude::*;
+use std::path::PathBuf;
+use tempfile::Builder;
+
+pub const COMMAND_NAME: &str = "line-graph";
+
+#[derive(Args, Debug)]
+#[command(about = "Computes the line graph of a BvGraph, whose nodes are the arcs of the graph, numbered by their rank in the global order of arcs, and whose arcs connect each arc u → v to all arcs v → w. The number of arcs of the line graph is computed in advance, as it might be much larger than that of the graph.", long_about = None)]
+pub struct CliArgs {
+    /// The basename of the graph.
+    pub src: PathBuf,
+    /// The basename of the line graph.
+    pub dst: PathBuf,
+
+    #[arg(long, default_value_t = DEFAULT_LINE_GRAPH_MAX_ARCS)]
+    /// The maximum number of arcs of the line graph.
+    pub max_arcs: u64,
+
+    #[arg(long)]
+    /// Compute the line graph even if its number of arcs exceeds the maximum.
+    pub force: bool,
+
+    #[clap(flatten)]
+    pub num_threads: NumThreadsArg,
+
+    #[clap(flatten)]
+    pub ca: CompressArgs,
+}
+
+pub fn cli(command: Command) -> Command {
+    command.subcommand(CliArgs::augment_args(Command::new(COMMAND_NAME)).display_order(0))
+}
+
+pub fn main(submatches: &ArgMatches) -> Result<()> {
+    let args = CliArgs::from_arg_matches(submatches)?;
+
+    create_parent_dir(&args.dst)?;
+
+    match get_endianness(&args.src)?.as_str() {
+        #[cfg(any(
+            feature = "be_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        BE::NAME => line_graph::<BE>(args),
+        #[cfg(any(
+            feature = "le_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        LE::NAME => line_graph::<LE>(args),
+        e => panic!("Unknown endianness: {}", e),
+    }
+}
+
+pub fn line_graph<E: Endianness + Send + Sync + 'static>(args: CliArgs) -> Result<()>
+where
+    for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+{
+    let thread_pool = crate::cli::get_thread_pool(args.num_threads.num_threads);
+    let output = GraphOutput::new(&args.dst);
+
+    let seq_graph = crate::graphs::bvgraph::sequential::BvGraphSeq::with_basename(&args.src)
+        .endianness::<E>()
+        .load()?;
+    log::info!("Computing the size of the line graph...");
+    let line_graph = LineGraph::new(seq_graph);
+    let num_nodes = line_graph.num_nodes();
+    let num_arcs = line_graph.num_arcs();
+    log::info!(
+        "The line graph has {} nodes and {} arcs",
+        num_nodes,
+        num_arcs
+    );
+    if num_arcs > args.max_arcs {
+        ensure!(
+            args.force,
+            "The line graph has {} arcs, more than the limit of {} (use --force to compute it anyway)",
+            num_arcs,
+            args.max_arcs
+        );
+        log::warn!("The line graph has {} arcs", num_arcs);
+    }
+
+    let target_endianness = args.ca.endianness.clone();
+    let dir = Builder::new().prefix("transform_line_graph_").tempdir()?;
+    BvComp::parallel_endianness(
+        &args.dst,
+        &line_graph,
+        num_nodes,
+        args.ca.into(),
+        &thread_pool,
+        dir,
+        &target_endianness.unwrap_or_else(|| E::NAME.into()),
+    )?;
+    output.finish()?;
+
+    Ok(())
+}
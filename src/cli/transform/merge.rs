@@ -0,0 +1,105 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::cli::*;
+use crate::graphs::merge_graphs::MergeGraphs;
+use crate::prelude::*;
+use anyhow::{ensure, Context, Result};
+use clap::{ArgMatches, Args, Command, FromArgMatches};
+use dsi_bitstream::prelude::*;
+use std::path::PathBuf;
+use tempfile::Builder;
+
+pub const COMMAND_NAME: &str = "merge";
+
+#[derive(Args, Debug)]
+#[command(about = "Compresses the union of several BvGraphs, merging their successor lists in a single pass. Graphs with fewer nodes are considered to have no successors for the missing nodes.", long_about = None)]
+pub struct CliArgs {
+    /// The basename of the merged graph.
+    pub dst: PathBuf,
+    /// The basenames of the graphs to merge.
+    #[arg(required = true, num_args = 1..)]
+    pub srcs: Vec<PathBuf>,
+
+    #[clap(flatten)]
+    pub num_threads: NumThreadsArg,
+
+    #[clap(flatten)]
+    pub ca: CompressArgs,
+}
+
+pub fn cli(command: Command) -> Command {
+    command.subcommand(CliArgs::augment_args(Command::new(COMMAND_NAME)).display_order(0))
+}
+
+pub fn main(submatches: &ArgMatches) -> Result<()> {
+    let args = CliArgs::from_arg_matches(submatches)?;
+
+    create_parent_dir(&args.dst)?;
+
+    match get_endianness(&args.srcs[0])?.as_str() {
+        #[cfg(any(
+            feature = "be_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        BE::NAME => merge::<BE>(args),
+        #[cfg(any(
+            feature = "le_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        LE::NAME => merge::<LE>(args),
+        e => panic!("Unknown endianness: {}", e),
+    }
+}
+
+pub fn merge<E: Endianness + Send + Sync + 'static>(args: CliArgs) -> Result<()>
+where
+    for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+{
+    let thread_pool = crate::cli::get_thread_pool(args.num_threads.num_threads);
+    let output = GraphOutput::new(&args.dst);
+
+    let mut graphs = Vec::with_capacity(args.srcs.len());
+    for src in &args.srcs {
+        let endianness = get_endianness(src)?;
+        ensure!(
+            endianness == E::NAME,
+            "The graph {} has endianness {}, but {} has endianness {}",
+            src.display(),
+            endianness,
+            args.srcs[0].display(),
+            E::NAME
+        );
+        graphs.push(
+            BvGraphSeq::with_basename(src)
+                .endianness::<E>()
+                .load()
+                .with_context(|| format!("Could not load graph at {}", src.display()))?,
+        );
+    }
+
+    let merged = MergeGraphs::new(graphs);
+    log::info!(
+        "Merging {} graphs with {} nodes",
+        args.srcs.len(),
+        merged.num_nodes()
+    );
+
+    let target_endianness = args.ca.endianness.clone();
+    let dir = Builder::new().prefix("transform_merge_").tempdir()?;
+    BvComp::parallel_endianness(
+        &args.dst,
+        &merged,
+        merged.num_nodes(),
+        args.ca.into(),
+        &thread_pool,
+        dir,
+        &target_endianness.unwrap_or_else(|| E::NAME.into()),
+    )?;
+    output.finish()?;
+
+    Ok(())
+}
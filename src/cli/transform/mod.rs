@@ -7,7 +7,12 @@
 use anyhow::Result;
 use clap::{ArgMatches, Command};
 
+pub mod complement;
+pub mod contract;
+pub mod line_graph;
+pub mod merge;
 pub mod simplify;
+pub mod top_k_arcs;
 pub mod transpose;
 
 pub const COMMAND_NAME: &str = "transform";
@@ -18,14 +23,24 @@ pub fn cli(command: Command) -> Command {
         .subcommand_required(true)
         .arg_required_else_help(true)
         .allow_external_subcommands(true);
+    let sub_command = complement::cli(sub_command);
+    let sub_command = contract::cli(sub_command);
+    let sub_command = line_graph::cli(sub_command);
+    let sub_command = merge::cli(sub_command);
     let sub_command = simplify::cli(sub_command);
+    let sub_command = top_k_arcs::cli(sub_command);
     let sub_command = transpose::cli(sub_command);
     command.subcommand(sub_command.display_order(0))
 }
 
 pub fn main(submatches: &ArgMatches) -> Result<()> {
     match submatches.subcommand() {
+        Some((complement::COMMAND_NAME, sub_m)) => complement::main(sub_m),
+        Some((contract::COMMAND_NAME, sub_m)) => contract::main(sub_m),
+        Some((line_graph::COMMAND_NAME, sub_m)) => line_graph::main(sub_m),
+        Some((merge::COMMAND_NAME, sub_m)) => merge::main(sub_m),
         Some((simplify::COMMAND_NAME, sub_m)) => simplify::main(sub_m),
+        Some((top_k_arcs::COMMAND_NAME, sub_m)) => top_k_arcs::main(sub_m),
         Some((transpose::COMMAND_NAME, sub_m)) => transpose::main(sub_m),
         Some((command_name, _)) => {
             eprintln!("Unknown command: {:?}", command_name);
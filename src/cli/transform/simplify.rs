@@ -42,6 +42,9 @@ pub struct CliArgs {
     #[arg(long)]
     /// The path to an optional permutation in binary big-endian format to apply to the graph.
     pub permutation: Option<PathBuf>,
+
+    #[clap(flatten)]
+    pub perm_mode: PermModeArg,
 }
 
 pub fn cli(command: Command) -> Command {
@@ -52,6 +55,7 @@ pub fn main(submatches: &ArgMatches) -> Result<()> {
     let args = CliArgs::from_arg_matches(submatches)?;
 
     create_parent_dir(&args.dst)?;
+    let dst = args.dst.clone();
 
     match get_endianness(&args.src)?.as_str() {
         #[cfg(any(
@@ -65,7 +69,11 @@ pub fn main(submatches: &ArgMatches) -> Result<()> {
         ))]
         LE::NAME => simplify::<LE>(args),
         e => panic!("Unknown endianness: {}", e),
-    }
+    }?;
+
+    set_loopless(&dst, true)?;
+    set_symmetric(&dst, true)?;
+    Ok(())
 }
 
 fn no_ef_warn(basepath: impl AsRef<std::path::Path>) {
@@ -78,6 +86,7 @@ where
 {
     // TODO!: speed it up by using random access graph if possible
     let thread_pool = crate::cli::get_thread_pool(args.num_threads.num_threads);
+    let output = GraphOutput::new(&args.dst);
 
     let target_endianness = args.ca.endianness.clone().unwrap_or_else(|| E::NAME.into());
 
@@ -88,11 +97,12 @@ where
         // without doing any sorting
         (None, Some(t_path)) => {
             log::info!("Transposed graph provided, using it to simplify the graph");
+            check_transposed(&args.src, &t_path)?;
 
-            let has_ef_graph =
-                std::fs::metadata(args.src.with_extension(".ef")).is_ok_and(|x| x.is_file());
-            let has_ef_t_graph =
-                std::fs::metadata(t_path.with_extension(".ef")).is_ok_and(|x| x.is_file());
+            let has_ef_graph = std::fs::metadata(Basename::new(&args.src).with_ext(EF_EXTENSION))
+                .is_ok_and(|x| x.is_file());
+            let has_ef_t_graph = std::fs::metadata(Basename::new(&t_path).with_ext(EF_EXTENSION))
+                .is_ok_and(|x| x.is_file());
 
             match (has_ef_graph, has_ef_t_graph) {
                 (true, true) => {
@@ -124,7 +134,7 @@ where
                         &target_endianness,
                     )?;
 
-                    return Ok(());
+                    return output.finish();
                 }
                 (true, false) => {
                     no_ef_warn(&args.src);
@@ -174,9 +184,15 @@ where
             log::info!("Permutation provided, applying it to the graph");
 
             let perm = JavaPermutation::mmap(perm_path, MmapFlags::RANDOM_ACCESS)?;
+            // check the length of the permutation before starting
+            let (num_nodes, _, _) =
+                parse_properties::<E>(Basename::new(&args.src).with_ext(PROPERTIES_EXTENSION))?;
+            let perm = fit_perm(perm, num_nodes, args.perm_mode.into())?;
 
             // if the .ef file exists, we can use the simplify split
-            if std::fs::metadata(args.src.with_extension(".ef")).is_ok_and(|x| x.is_file()) {
+            if std::fs::metadata(Basename::new(&args.src).with_ext(EF_EXTENSION))
+                .is_ok_and(|x| x.is_file())
+            {
                 log::info!(".ef file found, using simplify split");
                 let graph =
                     crate::graphs::bvgraph::random_access::BvGraph::with_basename(&args.src)
@@ -204,7 +220,7 @@ where
                     &target_endianness,
                 )?;
 
-                return Ok(());
+                return output.finish();
             }
 
             no_ef_warn(&args.src);
@@ -220,8 +236,7 @@ where
             };
 
             // simplify the graph
-            let sorted =
-                crate::transform::simplify(&perm_graph, args.batch_size.batch_size).unwrap();
+            let sorted = crate::transform::simplify(&perm_graph, args.batch_size.batch_size)?;
 
             BvComp::parallel_endianness(
                 &args.dst,
@@ -239,7 +254,9 @@ where
                 "No permutation or transposed graph provided, computing the transpose on the fly"
             );
             // if the .ef file exists, we can use the simplify split
-            if std::fs::metadata(args.src.with_extension(".ef")).is_ok_and(|x| x.is_file()) {
+            if std::fs::metadata(Basename::new(&args.src).with_ext(EF_EXTENSION))
+                .is_ok_and(|x| x.is_file())
+            {
                 log::info!(".ef file found, using simplify split");
 
                 let graph =
@@ -263,7 +280,7 @@ where
                     &target_endianness,
                 )?;
 
-                return Ok(());
+                return output.finish();
             }
 
             no_ef_warn(&args.src);
@@ -275,8 +292,7 @@ where
 
             let num_nodes = seq_graph.num_nodes();
             // transpose the graph
-            let sorted =
-                crate::transform::simplify_sorted(seq_graph, args.batch_size.batch_size).unwrap();
+            let sorted = crate::transform::simplify_sorted(seq_graph, args.batch_size.batch_size)?;
 
             BvComp::parallel_endianness(
                 &args.dst,
@@ -290,5 +306,6 @@ where
         }
     }
 
+    output.finish()?;
     Ok(())
 }
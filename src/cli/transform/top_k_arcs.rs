@@ -0,0 +1,111 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::cli::to::bvgraph::{compress_graph, write_labels};
+use crate::cli::*;
+use crate::labels::bitstream::MmapBitStreamLabeling;
+use crate::prelude::*;
+use anyhow::{ensure, Result};
+use clap::{ArgMatches, Args, Command, FromArgMatches};
+use dsi_bitstream::prelude::*;
+use std::path::PathBuf;
+
+pub const COMMAND_NAME: &str = "top-k-arcs";
+
+#[derive(Args, Debug)]
+#[command(about = "Keeps, for each node of a graph with γ-coded arc labels (e.g., the multiplicities written by from arcs --multigraph), only the k arcs with the largest labels, writing the truncated graph and its labels.", long_about = None)]
+pub struct CliArgs {
+    /// The basename of the graph.
+    pub src: PathBuf,
+    /// The basename of the truncated graph.
+    pub dst: PathBuf,
+
+    #[arg(short)]
+    /// The maximum number of arcs to keep for each node; ties between labels
+    /// are broken by increasing target.
+    pub k: usize,
+
+    #[arg(short, long)]
+    /// The basename of the labels (files .labels and .labelef, the latter
+    /// built by build label-ef); if missing, the basename of the graph.
+    pub labels: Option<PathBuf>,
+
+    #[clap(flatten)]
+    pub num_threads: NumThreadsArg,
+
+    #[clap(flatten)]
+    pub batch_size: BatchSizeArg,
+
+    #[clap(flatten)]
+    pub ca: CompressArgs,
+}
+
+pub fn cli(command: Command) -> Command {
+    command.subcommand(CliArgs::augment_args(Command::new(COMMAND_NAME)).display_order(0))
+}
+
+pub fn main(submatches: &ArgMatches) -> Result<()> {
+    let args = CliArgs::from_arg_matches(submatches)?;
+
+    create_parent_dir(&args.dst)?;
+
+    match get_endianness(&args.src)?.as_str() {
+        #[cfg(any(
+            feature = "be_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        BE::NAME => top_k_arcs::<BE>(args),
+        #[cfg(any(
+            feature = "le_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        LE::NAME => top_k_arcs::<LE>(args),
+        e => panic!("Unknown endianness: {}", e),
+    }
+}
+
+pub fn top_k_arcs<E: Endianness + 'static>(args: CliArgs) -> Result<()>
+where
+    for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+{
+    let thread_pool = crate::cli::get_thread_pool(args.num_threads.num_threads);
+    let output = GraphOutput::new(&args.dst);
+
+    let seq_graph = BvGraphSeq::with_basename(&args.src)
+        .endianness::<E>()
+        .load()?;
+    let labels_basename = args.labels.as_ref().unwrap_or(&args.src);
+    let labels = MmapBitStreamLabeling::<BE, _>::mmap(labels_basename, GammaLabel)?;
+    ensure!(
+        labels.num_nodes() == seq_graph.num_nodes(),
+        "The graph has {} nodes, but the labels are for {} nodes",
+        seq_graph.num_nodes(),
+        labels.num_nodes()
+    );
+    let graph = Zip(seq_graph, labels);
+
+    let truncated = crate::transform::truncate_top_k_by_label(
+        &graph,
+        args.k,
+        args.batch_size.batch_size,
+        GammaLabel,
+        GammaLabel,
+    )?;
+
+    let target_endianness = args.ca.endianness.clone();
+    compress_graph(
+        &Left(truncated.clone()),
+        &args.dst,
+        args.ca.into(),
+        &thread_pool,
+        &target_endianness.unwrap_or_else(|| E::NAME.into()),
+    )?;
+    write_labels(&args.dst, &truncated)?;
+    output.finish()?;
+
+    log::info!("Run \"webgraph build label-ef\" to build the Elias-Fano offsets of the new labels");
+    Ok(())
+}
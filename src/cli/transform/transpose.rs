@@ -31,6 +31,11 @@ pub struct CliArgs {
 
     #[clap(flatten)]
     pub ca: CompressArgs,
+
+    #[arg(long)]
+    /// The basename to record in the properties of the transposed graph as its
+    /// counterpart (defaults to the source basename).
+    pub mark_transpose_of: Option<PathBuf>,
 }
 
 pub fn cli(command: Command) -> Command {
@@ -62,6 +67,7 @@ where
     for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
 {
     let thread_pool = crate::cli::get_thread_pool(args.num_threads.num_threads);
+    let output = GraphOutput::new(&args.dst);
 
     // TODO!: speed it up by using random access graph if possible
     let seq_graph = crate::graphs::bvgraph::sequential::BvGraphSeq::with_basename(&args.src)
@@ -69,7 +75,7 @@ where
         .load()?;
 
     // transpose the graph
-    let sorted = crate::transform::transpose(&seq_graph, args.batch_size.batch_size).unwrap();
+    let sorted = crate::transform::transpose(&seq_graph, args.batch_size.batch_size)?;
 
     let target_endianness = args.ca.endianness.clone();
     let dir = Builder::new().prefix("transform_transpose_").tempdir()?;
@@ -83,5 +89,12 @@ where
         &target_endianness.unwrap_or_else(|| E::NAME.into()),
     )?;
 
+    set_direction(
+        &args.dst,
+        ArcDirection::Backward,
+        args.mark_transpose_of.as_ref().unwrap_or(&args.src),
+    )?;
+    output.finish()?;
+
     Ok(())
 }
@@ -39,6 +39,7 @@ pub struct CompFlagsFuzz {
     pub min_interval_length: u8,
     pub compression_window: u8,
     pub max_ref_count: u8,
+    pub reference_selection: ReferenceSelection,
 }
 
 impl From<CompFlagsFuzz> for CompFlags {
@@ -52,6 +53,7 @@ impl From<CompFlagsFuzz> for CompFlags {
             min_interval_length: value.min_interval_length as usize,
             compression_window: value.compression_window as usize,
             max_ref_count: value.max_ref_count as usize,
+            reference_selection: value.reference_selection,
         }
     }
 }
@@ -83,7 +85,8 @@ pub fn harness(data: FuzzCase) {
             comp_flags.max_ref_count,
             comp_flags.min_interval_length,
             0,
-        );
+        )
+        .with_reference_selection(comp_flags.reference_selection);
         bvcomp.extend(graph.iter()).unwrap();
         bvcomp.flush().unwrap();
     }
@@ -98,7 +101,8 @@ pub fn harness(data: FuzzCase) {
             comp_flags.max_ref_count,
             comp_flags.min_interval_length,
             0,
-        );
+        )
+        .with_reference_selection(comp_flags.reference_selection);
         bvcomp.extend(graph.iter()).unwrap();
         bvcomp.flush().unwrap();
     }
@@ -194,7 +198,16 @@ pub fn harness(data: FuzzCase) {
     }
     // build elias-fano
     let ef = efb.build();
-    let ef: EF = unsafe { ef.map_high_bits(SelectAdaptConst::<_, _, 12, 4>::new) };
+    let ef: EF = unsafe {
+        ef.map_high_bits(
+            SelectAdaptConst::<
+                _,
+                _,
+                { SelectParams::DEFAULT_LOG2_ONES_PER_INVENTORY },
+                { SelectParams::DEFAULT_LOG2_U64_PER_SUBINVENTORY },
+            >::new,
+        )
+    };
 
     // verify that elias-fano has the right values
     assert_eq!(ef.len(), offsets.len());
@@ -0,0 +1,265 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+//! A wrapper checking the number of arcs returned by full iterations.
+
+use crate::prelude::*;
+use anyhow::{ensure, Result};
+use lender::*;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// A wrapper that counts the arcs returned by the iterators of a graph, so
+/// that their number can be checked against an expected value (usually, the
+/// number of arcs stored in the `.properties` file).
+///
+/// Every lender returned by [`iter`](SequentialLabeling::iter) or
+/// [`split_iter`](SplitLabeling::split_iter) counts the successors it
+/// returns, and adds its count to a shared total when it is dropped. After a
+/// full iteration (or after a full parallel iteration on all splits), calling
+/// [`check`](ArcCountCheck::check) returns an error if the total differs from
+/// the expected number of arcs. The overhead is one increment per arc.
+///
+/// The check is meaningful only if all successors of all nodes are
+/// enumerated exactly once between two calls to
+/// [`check`](ArcCountCheck::check).
+///
+/// A wrapped [`BvGraphSeq`] can be obtained by calling
+/// [`verify_arc_count`](LoadConfig::verify_arc_count) on a load
+/// configuration.
+#[derive(Debug)]
+pub struct ArcCountCheck<G> {
+    graph: G,
+    expected: u64,
+    counted: AtomicU64,
+}
+
+impl<G> ArcCountCheck<G> {
+    /// Wraps a graph that is expected to have `expected` arcs.
+    pub fn new(graph: G, expected: u64) -> Self {
+        Self {
+            graph,
+            expected,
+            counted: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns the expected number of arcs.
+    pub fn expected(&self) -> u64 {
+        self.expected
+    }
+
+    /// Checks that the number of arcs returned since the last check is the
+    /// expected one, and resets the count.
+    ///
+    /// All lenders must have been dropped before calling this method.
+    pub fn check(&self) -> Result<()> {
+        let counted = self.counted.swap(0, Ordering::Relaxed);
+        ensure!(
+            counted == self.expected,
+            "The number of decoded arcs ({}) differs from the number of arcs in the properties ({}): the graph might be corrupted",
+            counted,
+            self.expected
+        );
+        Ok(())
+    }
+
+    /// Returns the wrapped graph.
+    pub fn into_inner(self) -> G {
+        self.graph
+    }
+}
+
+impl<G: SequentialGraph> SequentialLabeling for ArcCountCheck<G> {
+    type Label = usize;
+    type Lender<'b>
+        = Iter<'b, G::Lender<'b>>
+    where
+        Self: 'b;
+
+    #[inline(always)]
+    fn num_nodes(&self) -> usize {
+        self.graph.num_nodes()
+    }
+
+    #[inline(always)]
+    fn successors_sorted(&self) -> bool {
+        self.graph.successors_sorted()
+    }
+
+    #[inline(always)]
+    fn num_arcs_hint(&self) -> Option<u64> {
+        self.graph.num_arcs_hint()
+    }
+
+    #[inline(always)]
+    fn iter_from(&self, from: usize) -> Self::Lender<'_> {
+        Iter {
+            iter: self.graph.iter_from(from),
+            count: 0,
+            counted: &self.counted,
+        }
+    }
+}
+
+impl<G: SequentialGraph + SplitLabeling> SplitLabeling for ArcCountCheck<G> {
+    type SplitLender<'a>
+        = Iter<'a, G::SplitLender<'a>>
+    where
+        Self: 'a;
+    type IntoIterator<'a>
+        = SplitIter<'a, <G::IntoIterator<'a> as IntoIterator>::IntoIter>
+    where
+        Self: 'a;
+
+    fn split_iter(&self, how_many: usize) -> Self::IntoIterator<'_> {
+        SplitIter {
+            iter: self.graph.split_iter(how_many).into_iter(),
+            counted: &self.counted,
+        }
+    }
+}
+
+impl<G: SequentialGraph> SequentialGraph for ArcCountCheck<G> {}
+
+impl<'b, G: SequentialGraph> IntoLender for &'b ArcCountCheck<G> {
+    type Lender = <ArcCountCheck<G> as SequentialLabeling>::Lender<'b>;
+
+    #[inline(always)]
+    fn into_lender(self) -> Self::Lender {
+        self.iter()
+    }
+}
+
+/// An iterator over the splits of an [`ArcCountCheck`].
+#[derive(Debug)]
+pub struct SplitIter<'a, I> {
+    iter: I,
+    counted: &'a AtomicU64,
+}
+
+impl<'a, I: Iterator> Iterator for SplitIter<'a, I> {
+    type Item = Iter<'a, I::Item>;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|iter| Iter {
+            iter,
+            count: 0,
+            counted: self.counted,
+        })
+    }
+}
+
+/// A lender counting the successors it returns.
+///
+/// The count is added to the total of the [`ArcCountCheck`] that
+/// created the lender when the lender is dropped.
+#[derive(Debug)]
+pub struct Iter<'a, I> {
+    iter: I,
+    count: u64,
+    counted: &'a AtomicU64,
+}
+
+impl<I> Drop for Iter<'_, I> {
+    fn drop(&mut self) {
+        self.counted.fetch_add(self.count, Ordering::Relaxed);
+    }
+}
+
+impl<'succ, I: Lender + for<'next> NodeLabelsLender<'next, Label = usize>> NodeLabelsLender<'succ>
+    for Iter<'_, I>
+{
+    type Label = usize;
+    type IntoIterator = Succ<'succ, LenderIntoIter<'succ, I>>;
+}
+
+impl<'succ, I: Lender + for<'next> NodeLabelsLender<'next, Label = usize>> Lending<'succ>
+    for Iter<'_, I>
+{
+    type Lend = (usize, <Self as NodeLabelsLender<'succ>>::IntoIterator);
+}
+
+unsafe impl<I: SortedLender + Lender + for<'next> NodeLabelsLender<'next, Label = usize>>
+    SortedLender for Iter<'_, I>
+{
+}
+
+impl<L: Lender + for<'next> NodeLabelsLender<'next, Label = usize>> Lender for Iter<'_, L> {
+    #[inline(always)]
+    fn next(&mut self) -> Option<Lend<'_, Self>> {
+        let count = &mut self.count;
+        self.iter.next().map(|x| {
+            let (node, succ) = x.into_pair();
+            (
+                node,
+                Succ {
+                    iter: succ.into_iter(),
+                    count,
+                },
+            )
+        })
+    }
+}
+
+/// An iterator over successors that increments a count for each successor.
+#[derive(Debug)]
+pub struct Succ<'a, I: Iterator<Item = usize>> {
+    iter: I,
+    count: &'a mut u64,
+}
+
+impl<I: Iterator<Item = usize>> Iterator for Succ<'_, I> {
+    type Item = usize;
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        let dst = self.iter.next()?;
+        *self.count += 1;
+        Some(dst)
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+unsafe impl<I: Iterator<Item = usize> + SortedIterator> SortedIterator for Succ<'_, I> {}
+
+impl<I: ExactSizeIterator<Item = usize>> ExactSizeIterator for Succ<'_, I> {
+    #[inline(always)]
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_arc_count_check() -> anyhow::Result<()> {
+    use crate::{graphs::vec_graph::VecGraph, prelude::proj::Left};
+    let g = Left(VecGraph::from_arc_list([(0, 1), (1, 2), (2, 0), (2, 1)]));
+
+    let checked = ArcCountCheck::new(&g, 4);
+    for_!( (_, succ) in checked.iter() {
+        for _ in succ {}
+    });
+    checked.check()?;
+
+    // Splits report their counts separately
+    for lender in checked.split_iter(3) {
+        for_!( (_, succ) in lender {
+            for _ in succ {}
+        });
+    }
+    checked.check()?;
+
+    let wrong = ArcCountCheck::new(&g, 5);
+    for_!( (_, succ) in wrong.iter() {
+        for _ in succ {}
+    });
+    assert!(wrong.check().is_err());
+    Ok(())
+}
@@ -0,0 +1,98 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+//! Counting arcs between ranges of nodes.
+
+use crate::traits::RandomAccessGraph;
+use rayon::prelude::*;
+use rayon::ThreadPool;
+use std::ops::Range;
+
+/// Returns the number of arcs whose source is in `src_range` and whose
+/// target is in `dst_range`.
+///
+/// The successor lists of the nodes in `src_range` are scanned in parallel
+/// using `thread_pool`, so the cost is linear in the number of arcs leaving
+/// `src_range`, independently of `dst_range`.
+///
+/// Computing this function for all pairs of blocks of a partition of the
+/// nodes into intervals yields a coarse adjacency matrix, which can be used to
+/// assess quantitatively the locality of an ordering (e.g., the block structure
+/// induced by [LLP](crate::algo::llp)).
+///
+/// # Panics
+///
+/// Panics if `src_range` contains nodes of index larger than or equal to the
+/// number of nodes of the graph.
+pub fn count_arcs_between(
+    graph: &(impl RandomAccessGraph + Sync),
+    src_range: Range<usize>,
+    dst_range: Range<usize>,
+    thread_pool: &ThreadPool,
+) -> u64 {
+    assert!(
+        src_range.end <= graph.num_nodes() || src_range.is_empty(),
+        "The source range {:?} exceeds the number of nodes ({})",
+        src_range,
+        graph.num_nodes()
+    );
+    if dst_range.is_empty() {
+        return 0;
+    }
+    thread_pool.install(|| {
+        src_range
+            .into_par_iter()
+            .map(|node| {
+                graph
+                    .successors(node)
+                    .into_iter()
+                    .filter(|succ| dst_range.contains(succ))
+                    .count() as u64
+            })
+            .sum()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphs::random::ErdosRenyi;
+    use crate::graphs::vec_graph::VecGraph;
+    use crate::prelude::*;
+
+    #[test]
+    fn test_count_arcs_between() {
+        let graph = Left(VecGraph::from_lender(ErdosRenyi::new(100, 0.1, 0).iter()));
+        let thread_pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(4)
+            .build()
+            .unwrap();
+        let ranges = [0..0, 0..10, 5..50, 30..100, 0..100, 99..100];
+        for src_range in ranges.clone() {
+            for dst_range in ranges.clone() {
+                let mut expected = 0;
+                for src in src_range.clone() {
+                    for dst in graph.successors(src) {
+                        if dst_range.contains(&dst) {
+                            expected += 1;
+                        }
+                    }
+                }
+                assert_eq!(
+                    count_arcs_between(&graph, src_range.clone(), dst_range.clone(), &thread_pool),
+                    expected,
+                    "{:?} -> {:?}",
+                    src_range,
+                    dst_range
+                );
+            }
+        }
+        assert_eq!(
+            count_arcs_between(&graph, 0..100, 0..100, &thread_pool),
+            graph.num_arcs()
+        );
+    }
+}
@@ -5,7 +5,7 @@
  */
 
 use crate::traits::*;
-use core::mem::MaybeUninit;
+use crate::utils::sort_pairs::DuplicateArcs;
 use lender::*;
 
 /// An adapter exhibiting a list of labeled
@@ -13,20 +13,65 @@ use lender::*;
 ///
 /// If for every source the arcs are sorted by destination, the
 /// successors of the graph will be sorted.
+///
+/// Duplicate arcs are returned by default; this behavior can be changed
+/// using [`ArcListGraph::duplicates`]. Note that only consecutive duplicates
+/// are removed, so all duplicates are removed only if arcs are sorted by
+/// destination.
+///
+/// Labels need not be [`Copy`]: the recommended way to materialize the
+/// labeled adjacency lists of an arc-list graph is
+/// [`collect_labeled`](ArcListGraph::collect_labeled).
 #[derive(Clone)]
 pub struct ArcListGraph<I: Clone> {
     num_nodes: usize,
     into_iter: I,
+    duplicates: DuplicateArcs,
 }
 
-impl<L: Clone + Copy + 'static, I: IntoIterator<Item = (usize, usize, L)> + Clone> ArcListGraph<I> {
+impl<L: Clone + 'static, I: IntoIterator<Item = (usize, usize, L)> + Clone> ArcListGraph<I> {
     /// Creates a new arc list graph from the given [`IntoIterator`].
     #[inline(always)]
     pub fn new_labeled(num_nodes: usize, iter: I) -> Self {
         Self {
             num_nodes,
             into_iter: iter,
+            duplicates: DuplicateArcs::Keep,
+        }
+    }
+
+    /// Returns the labeled adjacency lists of the graph, that is, for each
+    /// node, the node and the list of its successors with their labels.
+    ///
+    /// ```
+    /// # use webgraph::graphs::arc_list_graph::ArcListGraph;
+    /// let arcs = [(0, 1, Box::new(1_u64)), (0, 2, Box::new(2)), (2, 0, Box::new(3))];
+    /// let graph = ArcListGraph::new_labeled(3, arcs);
+    /// assert_eq!(
+    ///     graph.collect_labeled(),
+    ///     vec![
+    ///         (0, vec![(1, Box::new(1)), (2, Box::new(2))]),
+    ///         (1, vec![]),
+    ///         (2, vec![(0, Box::new(3))]),
+    ///     ]
+    /// );
+    /// ```
+    pub fn collect_labeled(&self) -> Vec<(usize, Vec<(usize, L)>)> {
+        let mut result = Vec::with_capacity(self.num_nodes);
+        let mut iter = self.iter();
+        while let Some((node, succ)) = iter.next() {
+            result.push((node, succ.collect()));
         }
+        result
+    }
+}
+
+impl<I: Clone> ArcListGraph<I> {
+    /// Sets the policy for duplicate arcs; if duplicate arcs are removed, the
+    /// label of the first one is returned.
+    pub fn duplicates(mut self, duplicates: DuplicateArcs) -> Self {
+        self.duplicates = duplicates;
+        self
     }
 }
 
@@ -42,6 +87,7 @@ impl<I: Iterator<Item = (usize, usize)> + Clone>
         Self {
             num_nodes,
             into_iter: iter.into_iter().map(|(src, dst)| (src, dst, ())),
+            duplicates: DuplicateArcs::Keep,
         }
     }
 }
@@ -70,8 +116,10 @@ where
 pub struct Iter<L, I: IntoIterator<Item = (usize, usize, L)>> {
     num_nodes: usize,
     curr_node: usize,
-    next_pair: (usize, usize, L),
+    /// The next triple, or `None` if the arcs are exhausted.
+    next_pair: Option<(usize, usize, L)>,
     iter: I::IntoIter,
+    remove_duplicates: bool,
 }
 
 unsafe impl<L: Clone + 'static, I: IntoIterator<Item = (usize, usize, L)> + Clone> SortedLender
@@ -84,16 +132,22 @@ impl<L: Clone + 'static, I: IntoIterator<Item = (usize, usize, L)>> Iter<L, I> {
         Iter {
             num_nodes,
             curr_node: 0_usize.wrapping_sub(1), // No node seen yet
-            next_pair: iter.next().unwrap_or((usize::MAX, usize::MAX, unsafe {
-                #[allow(clippy::uninit_assumed_init)]
-                // SAFETY: L is Copy
-                MaybeUninit::uninit().assume_init()
-            })),
+            next_pair: iter.next(),
             iter,
+            remove_duplicates: false,
         }
     }
 }
 
+impl<L, I: IntoIterator<Item = (usize, usize, L)>> Iter<L, I> {
+    /// Returns the source of the next triple, or `usize::MAX` if the arcs
+    /// are exhausted.
+    #[inline(always)]
+    fn next_src(&self) -> usize {
+        self.next_pair.as_ref().map_or(usize::MAX, |pair| pair.0)
+    }
+}
+
 impl<'succ, L: Clone + 'static, I: IntoIterator<Item = (usize, usize, L)> + Clone>
     NodeLabelsLender<'succ> for Iter<L, I>
 {
@@ -109,18 +163,17 @@ impl<'succ, L: Clone + 'static, I: IntoIterator<Item = (usize, usize, L)> + Clon
 
 impl<L: Clone + 'static, I: IntoIterator<Item = (usize, usize, L)> + Clone> Lender for Iter<L, I> {
     fn next(&mut self) -> Option<Lend<'_, Self>> {
-        self.curr_node = self.curr_node.wrapping_add(1);
-        if self.curr_node == self.num_nodes {
+        // curr_node is usize::MAX before the first call, and stays equal to
+        // num_nodes once the lender is exhausted
+        if self.curr_node.wrapping_add(1) >= self.num_nodes {
+            self.curr_node = self.num_nodes;
             return None;
         }
+        self.curr_node = self.curr_node.wrapping_add(1);
 
         // This happens if the user doesn't use the successors iter
-        while self.next_pair.0 < self.curr_node {
-            self.next_pair = self.iter.next().unwrap_or((usize::MAX, usize::MAX, unsafe {
-                #[allow(clippy::uninit_assumed_init)]
-                // SAFETY: L is Copy
-                MaybeUninit::uninit().assume_init()
-            }));
+        while self.next_src() < self.curr_node {
+            self.next_pair = self.iter.next();
         }
 
         Some((self.curr_node, Succ { node_iter: self }))
@@ -131,7 +184,8 @@ impl<L: Clone + 'static, I: IntoIterator<Item = (usize, usize, L)> + Clone> Exac
     for Iter<L, I>
 {
     fn len(&self) -> usize {
-        self.num_nodes - self.curr_node.wrapping_add(1)
+        self.num_nodes
+            .saturating_sub(self.curr_node.wrapping_add(1))
     }
 }
 
@@ -173,6 +227,7 @@ impl<L: Clone + 'static, I: IntoIterator<Item = (usize, usize, L)> + Clone> Sequ
     #[inline(always)]
     fn iter_from(&self, from: usize) -> Self::Lender<'_> {
         let mut iter = Iter::new(self.num_nodes, self.into_iter.clone().into_iter());
+        iter.remove_duplicates = self.duplicates == DuplicateArcs::Remove;
         for _ in 0..from {
             iter.next();
         }
@@ -181,6 +236,11 @@ impl<L: Clone + 'static, I: IntoIterator<Item = (usize, usize, L)> + Clone> Sequ
     }
 }
 
+impl<L: Clone + 'static, I: IntoIterator<Item = (usize, usize, L)> + Clone>
+    LabeledSequentialGraph<L> for ArcListGraph<I>
+{
+}
+
 /// Iter until we found a triple with src different than curr_node
 pub struct Succ<'succ, L, I: IntoIterator<Item = (usize, usize, L)>> {
     node_iter: &'succ mut Iter<L, I>,
@@ -196,26 +256,278 @@ impl<L, I: IntoIterator<Item = (usize, usize, L)>> Iterator for Succ<'_, L, I> {
     fn next(&mut self) -> Option<Self::Item> {
         // If the source of the next pair is not the current node,
         // we return None.
-        if self.node_iter.next_pair.0 != self.node_iter.curr_node {
+        if self.node_iter.next_src() != self.node_iter.curr_node {
             None
         } else {
             // get the next triple
-            let pair = self
-                .node_iter
-                .iter
-                .next()
-                .unwrap_or((usize::MAX, usize::MAX, unsafe {
-                    #[allow(clippy::uninit_assumed_init)]
-                    MaybeUninit::uninit().assume_init()
-                }));
+            let mut pair = self.node_iter.iter.next();
+            // skip duplicates of the triple we are going to return
+            if self.node_iter.remove_duplicates {
+                let (src, dst, _) = self.node_iter.next_pair.as_ref().unwrap();
+                while matches!(&pair, Some((s, d, _)) if s == src && d == dst) {
+                    pair = self.node_iter.iter.next();
+                }
+            }
             // store the triple and return the previous successor
             // storing the label since it should be one step behind the successor
-            let (_src, dst, label) = core::mem::replace(&mut self.node_iter.next_pair, pair);
+            let (_src, dst, label) =
+                core::mem::replace(&mut self.node_iter.next_pair, pair).unwrap();
             Some((dst, label))
         }
     }
 }
 
+/// Owned arcs sorted by source, backing the arc list graphs built by
+/// [`ArcListGraph::from_sorted_vec`] and
+/// [`ArcListGraph::from_sorted_labeled_vec`].
+///
+/// Since the arcs are in memory and sorted by source, the lender of the graph
+/// can start from any node by binary search, and the graph can be split at
+/// node boundaries without scanning the arcs.
+#[derive(Debug, Clone)]
+pub struct SortedArcs<L>(Vec<(usize, usize, L)>);
+
+impl<L> SortedArcs<L> {
+    /// Returns the first index of an arc whose source is at least `node`.
+    #[inline(always)]
+    fn first_arc(&self, node: usize) -> usize {
+        self.0.partition_point(|(src, _, _)| *src < node)
+    }
+}
+
+impl ArcListGraph<SortedArcs<()>> {
+    /// Creates a new arc list graph owning the given arcs, which must be
+    /// sorted by source.
+    ///
+    /// As in the case of [`ArcListGraph::new`], the resulting graph will be
+    /// labeled by the unit type `()`. Differently from the iterator-based
+    /// graphs, however, [`iter_from`](SequentialLabeling::iter_from) does not
+    /// scan the arcs of the previous nodes, and
+    /// [`split_iter`](SplitLabeling::split_iter) cuts the vector at node
+    /// boundaries, so the graph can be compressed in parallel directly from
+    /// memory.
+    ///
+    /// # Panics
+    ///
+    /// If the arcs are not sorted by source, or if a source is not smaller
+    /// than `num_nodes`.
+    ///
+    /// ```
+    /// # use webgraph::prelude::*;
+    /// # use webgraph::graphs::arc_list_graph::ArcListGraph;
+    /// # use lender::*;
+    /// let graph = ArcListGraph::from_sorted_vec(3, vec![(0, 1), (0, 2), (2, 0)]);
+    /// let mut parts = graph.split_iter(2).into_iter();
+    /// let _first = parts.next().unwrap();
+    /// // The second part starts directly from the arcs of node 2
+    /// let mut second = parts.next().unwrap();
+    /// let (node, succ) = second.next().unwrap();
+    /// assert_eq!((node, succ.collect::<Vec<_>>()), (2, vec![(0, ())]));
+    /// ```
+    pub fn from_sorted_vec(num_nodes: usize, arcs: Vec<(usize, usize)>) -> Self {
+        Self::from_sorted_labeled_vec(
+            num_nodes,
+            arcs.into_iter().map(|(src, dst)| (src, dst, ())).collect(),
+        )
+    }
+}
+
+impl<L: Clone + 'static> ArcListGraph<SortedArcs<L>> {
+    /// Creates a new labeled arc list graph owning the given triples, which
+    /// must be sorted by source.
+    ///
+    /// See [`ArcListGraph::from_sorted_vec`].
+    ///
+    /// # Panics
+    ///
+    /// If the triples are not sorted by source, or if a source is not smaller
+    /// than `num_nodes`.
+    pub fn from_sorted_labeled_vec(num_nodes: usize, arcs: Vec<(usize, usize, L)>) -> Self {
+        assert!(
+            arcs.windows(2).all(|w| w[0].0 <= w[1].0),
+            "The arcs are not sorted by source"
+        );
+        if let Some((src, _, _)) = arcs.last() {
+            assert!(
+                *src < num_nodes,
+                "Source {} is not smaller than the number of nodes {}",
+                src,
+                num_nodes
+            );
+        }
+        Self {
+            num_nodes,
+            into_iter: SortedArcs(arcs),
+            duplicates: DuplicateArcs::Keep,
+        }
+    }
+
+    /// Returns the labeled adjacency lists of the graph, as
+    /// [`collect_labeled`](ArcListGraph::collect_labeled) does for
+    /// iterator-based graphs.
+    pub fn collect_labeled(&self) -> Vec<(usize, Vec<(usize, L)>)> {
+        let mut result = Vec::with_capacity(self.num_nodes);
+        let mut iter = self.iter();
+        while let Some((node, succ)) = iter.next() {
+            result.push((node, succ.collect()));
+        }
+        result
+    }
+
+    /// Returns the arcs of the graph.
+    pub fn into_inner(self) -> Vec<(usize, usize, L)> {
+        self.into_iter.0
+    }
+}
+
+impl<L: Clone + 'static> SequentialLabeling for ArcListGraph<SortedArcs<L>> {
+    type Label = (usize, L);
+    type Lender<'node>
+        = SortedIter<'node, L>
+    where
+        Self: 'node;
+
+    #[inline(always)]
+    fn num_nodes(&self) -> usize {
+        self.num_nodes
+    }
+
+    #[inline(always)]
+    fn num_arcs_hint(&self) -> Option<u64> {
+        match self.duplicates {
+            DuplicateArcs::Keep => Some(self.into_iter.0.len() as u64),
+            _ => None,
+        }
+    }
+
+    #[inline(always)]
+    fn iter_from(&self, from: usize) -> Self::Lender<'_> {
+        let from = from.min(self.num_nodes);
+        SortedIter {
+            arcs: &self.into_iter.0[self.into_iter.first_arc(from)..],
+            next_node: from,
+            num_nodes: self.num_nodes,
+            remove_duplicates: self.duplicates == DuplicateArcs::Remove,
+        }
+    }
+}
+
+impl<L: Clone + Send + Sync + 'static> SplitLabeling for ArcListGraph<SortedArcs<L>> {
+    type SplitLender<'a>
+        = lender::Take<SortedIter<'a, L>>
+    where
+        Self: 'a;
+    type IntoIterator<'a>
+        = Vec<Self::SplitLender<'a>>
+    where
+        Self: 'a;
+
+    fn split_iter(&self, how_many: usize) -> Self::IntoIterator<'_> {
+        let nodes_per_iter = self.num_nodes.div_ceil(how_many);
+        // iter_from finds the first arc of each part by binary search
+        (0..how_many)
+            .map(|i| self.iter_from(i * nodes_per_iter).take(nodes_per_iter))
+            .collect()
+    }
+}
+
+impl<'lend, L: Clone + 'static> Lending<'lend> for &ArcListGraph<SortedArcs<L>> {
+    type Lend = (usize, SortedSucc<'lend, L>);
+}
+
+impl<'a, L: Clone + 'static> IntoLender for &'a ArcListGraph<SortedArcs<L>> {
+    type Lender = SortedIter<'a, L>;
+
+    fn into_lender(self) -> Self::Lender {
+        self.iter()
+    }
+}
+
+/// The lender of an arc list graph built from a sorted vector.
+#[derive(Debug, Clone)]
+pub struct SortedIter<'a, L> {
+    /// The arcs whose source is at least `next_node`.
+    arcs: &'a [(usize, usize, L)],
+    next_node: usize,
+    num_nodes: usize,
+    remove_duplicates: bool,
+}
+
+unsafe impl<L: Clone + 'static> SortedLender for SortedIter<'_, L> {}
+
+impl<'succ, L: Clone + 'static> NodeLabelsLender<'succ> for SortedIter<'_, L> {
+    type Label = (usize, L);
+    type IntoIterator = SortedSucc<'succ, L>;
+}
+
+impl<'succ, L: Clone + 'static> Lending<'succ> for SortedIter<'_, L> {
+    type Lend = (usize, <Self as NodeLabelsLender<'succ>>::IntoIterator);
+}
+
+impl<L: Clone + 'static> Lender for SortedIter<'_, L> {
+    fn next(&mut self) -> Option<Lend<'_, Self>> {
+        if self.next_node >= self.num_nodes {
+            return None;
+        }
+        let node = self.next_node;
+        self.next_node += 1;
+        // All remaining arcs have source at least node
+        let (succ, rest) = self
+            .arcs
+            .split_at(self.arcs.partition_point(|(src, _, _)| *src == node));
+        self.arcs = rest;
+        Some((
+            node,
+            SortedSucc {
+                arcs: succ.iter(),
+                remove_duplicates: self.remove_duplicates,
+            },
+        ))
+    }
+}
+
+impl<L: Clone + 'static> ExactSizeLender for SortedIter<'_, L> {
+    fn len(&self) -> usize {
+        self.num_nodes - self.next_node
+    }
+}
+
+/// The successors of a node of an arc list graph built from a sorted vector.
+#[derive(Debug, Clone)]
+pub struct SortedSucc<'a, L> {
+    arcs: core::slice::Iter<'a, (usize, usize, L)>,
+    remove_duplicates: bool,
+}
+
+impl<L: Clone> Iterator for SortedSucc<'_, L> {
+    type Item = (usize, L);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (_, dst, label) = self.arcs.next()?;
+        // skip consecutive duplicates, returning the first label
+        if self.remove_duplicates {
+            while self
+                .arcs
+                .as_slice()
+                .first()
+                .is_some_and(|(_, d, _)| d == dst)
+            {
+                self.arcs.next();
+            }
+        }
+        Some((*dst, label.clone()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.arcs.len();
+        if self.remove_duplicates {
+            (len.min(1), Some(len))
+        } else {
+            (len, Some(len))
+        }
+    }
+}
+
 #[cfg(test)]
 #[cfg_attr(test, test)]
 fn test() -> anyhow::Result<()> {
@@ -235,3 +547,120 @@ fn test() -> anyhow::Result<()> {
     assert_eq!(g, g2);
     Ok(())
 }
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_collect_labeled_drop() {
+    // Non-Copy labels must be dropped exactly once, also when successors
+    // are skipped or the iteration is not completed
+    let arcs = (0..10)
+        .flat_map(|src| (0..src).map(move |dst| (src, dst, format!("{src}->{dst}"))))
+        .collect::<Vec<_>>();
+    let graph = ArcListGraph::new_labeled(12, arcs.clone());
+    let collected = graph.collect_labeled();
+    assert_eq!(collected.len(), 12);
+    for (node, succ) in &collected {
+        let expected = arcs
+            .iter()
+            .filter(|(src, _, _)| src == node)
+            .map(|(_, dst, label)| (*dst, label.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(succ, &expected);
+    }
+
+    let mut iter = graph.iter();
+    let (_, mut succ) = iter.next().unwrap();
+    assert!(succ.next().is_none());
+    let _ = iter.nth(3);
+    let (node, mut succ) = iter.next().unwrap();
+    assert_eq!(node, 5);
+    assert_eq!(succ.next(), Some((0, "5->0".to_string())));
+    drop(iter);
+
+    let graph = ArcListGraph::new_labeled(3, [(0, 1, Box::new(0)), (0, 1, Box::new(1))])
+        .duplicates(DuplicateArcs::Remove);
+    assert_eq!(
+        graph.collect_labeled(),
+        vec![(0, vec![(1, Box::new(0))]), (1, vec![]), (2, vec![])]
+    );
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_tiny() {
+    let graph = ArcListGraph::new_labeled(0, Vec::<(usize, usize, ())>::new());
+    let mut iter = graph.iter();
+    assert_eq!(iter.len(), 0);
+    assert!(iter.next().is_none());
+    // The lender must stay exhausted
+    assert!(iter.next().is_none());
+    assert_eq!(iter.len(), 0);
+
+    let graph = ArcListGraph::new_labeled(1, vec![(0, 0, ())]);
+    let mut iter = graph.iter();
+    assert_eq!(iter.len(), 1);
+    let (node, succ) = iter.next().unwrap();
+    assert_eq!(node, 0);
+    assert_eq!(succ.collect::<Vec<_>>(), vec![(0, ())]);
+    assert_eq!(iter.len(), 0);
+    assert!(iter.next().is_none());
+    assert!(iter.next().is_none());
+    assert_eq!(iter.len(), 0);
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_from_sorted_vec() {
+    let arcs = vec![
+        (0, 1, 'a'),
+        (0, 1, 'b'),
+        (0, 4, 'c'),
+        (2, 0, 'd'),
+        (4, 2, 'e'),
+        (4, 3, 'f'),
+        (4, 3, 'g'),
+    ];
+    let iter_graph = ArcListGraph::new_labeled(6, arcs.clone());
+    let vec_graph = ArcListGraph::from_sorted_labeled_vec(6, arcs.clone());
+    assert_eq!(vec_graph.num_arcs_hint(), Some(7));
+    let expected = iter_graph.collect_labeled();
+    assert_eq!(vec_graph.collect_labeled(), expected);
+
+    for from in 0..=6 {
+        let mut iter = vec_graph.iter_from(from);
+        assert_eq!(iter.len(), 6 - from);
+        let mut result = vec![];
+        while let Some((node, succ)) = iter.next() {
+            result.push((node, succ.collect::<Vec<_>>()));
+        }
+        assert_eq!(result, &expected[from..]);
+    }
+
+    // The parts must cover the graph in order, also with more parts than nodes
+    for how_many in 1..10 {
+        let mut result = vec![];
+        for mut part in vec_graph.split_iter(how_many) {
+            while let Some((node, succ)) = part.next() {
+                result.push((node, succ.collect::<Vec<_>>()));
+            }
+        }
+        assert_eq!(result, expected);
+    }
+
+    let iter_graph = iter_graph.duplicates(DuplicateArcs::Remove);
+    let vec_graph = vec_graph.duplicates(DuplicateArcs::Remove);
+    assert_eq!(vec_graph.num_arcs_hint(), None);
+    assert_eq!(vec_graph.collect_labeled(), iter_graph.collect_labeled());
+    assert_eq!(vec_graph.into_inner(), arcs);
+
+    let empty = ArcListGraph::from_sorted_vec(0, vec![]);
+    assert!(empty.iter().next().is_none());
+    assert_eq!(empty.split_iter(3).len(), 3);
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+#[should_panic(expected = "not sorted")]
+fn test_from_unsorted_vec() {
+    ArcListGraph::from_sorted_vec(3, vec![(1, 0), (0, 1)]);
+}
@@ -0,0 +1,164 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+//! An asynchronous facade for random-access graphs.
+//!
+//! Accessing a memory-mapped graph, such as a
+//! [`BvGraph`](crate::graphs::bvgraph::BvGraph), might cause page faults,
+//! which would block the executor if successors were decoded directly from
+//! an asynchronous task. An [`AsyncGraph`] moves decoding to blocking
+//! threads (using [`tokio::task::spawn_blocking`] or a dedicated
+//! [Rayon](rayon) thread pool) and makes the result available to
+//! asynchronous code.
+//!
+//! Only an [`Arc`] of the graph and the decoded successor lists cross thread
+//! boundaries: decoders are created, used, and dropped on the blocking
+//! thread, so they are never sent across threads. Concurrent lookups of the
+//! same node are coalesced, so the successors of a node are decoded once
+//! while a lookup is in flight.
+//!
+//! The module is available only with the `async` feature.
+
+use crate::traits::RandomAccessGraph;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::panic::AssertUnwindSafe;
+use std::sync::{Arc, Mutex};
+use tokio::sync::oneshot;
+
+/// The lookups waiting for the successors of a node.
+type Waiters = Vec<oneshot::Sender<Arc<[usize]>>>;
+
+/// An asynchronous facade for a [`RandomAccessGraph`].
+///
+/// Cloning an `AsyncGraph` is cheap, and clones share the graph, the thread
+/// pool and the coalescing of lookups.
+///
+/// Unless a thread pool is set with [`with_thread_pool`](AsyncGraph::with_thread_pool),
+/// lookups use [`tokio::task::spawn_blocking`], so they must be performed
+/// within a Tokio runtime.
+///
+/// # Examples
+///
+/// ```ignore
+/// let graph = Arc::new(BvGraph::with_basename("graph").load()?);
+/// let async_graph = AsyncGraph::new(graph);
+/// let successors = async_graph.successors(0).await;
+/// ```
+pub struct AsyncGraph<G> {
+    graph: Arc<G>,
+    thread_pool: Option<Arc<rayon::ThreadPool>>,
+    pending: Arc<Mutex<HashMap<usize, Waiters>>>,
+}
+
+impl<G> Clone for AsyncGraph<G> {
+    fn clone(&self) -> Self {
+        Self {
+            graph: self.graph.clone(),
+            thread_pool: self.thread_pool.clone(),
+            pending: self.pending.clone(),
+        }
+    }
+}
+
+impl<G: RandomAccessGraph + Send + Sync + 'static> AsyncGraph<G> {
+    /// Creates a new facade for the given graph.
+    pub fn new(graph: Arc<G>) -> Self {
+        Self {
+            graph,
+            thread_pool: None,
+            pending: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Decodes successors using the given thread pool instead of
+    /// [`tokio::task::spawn_blocking`].
+    ///
+    /// A dedicated pool bounds the number of threads blocked on page faults,
+    /// and makes the facade usable with any asynchronous runtime.
+    pub fn with_thread_pool(mut self, thread_pool: Arc<rayon::ThreadPool>) -> Self {
+        self.thread_pool = Some(thread_pool);
+        self
+    }
+
+    /// Returns the underlying graph.
+    pub fn graph(&self) -> &Arc<G> {
+        &self.graph
+    }
+
+    /// Returns the number of nodes of the graph.
+    pub fn num_nodes(&self) -> usize {
+        self.graph.num_nodes()
+    }
+
+    /// Returns the successors of a node, in the same order of
+    /// [`RandomAccessGraph::successors`].
+    ///
+    /// # Panics
+    ///
+    /// This method panics if decoding the successors panics (e.g., because
+    /// `node` is out of bounds).
+    pub async fn successors(&self, node: usize) -> Vec<usize> {
+        self.lookup(node).await.to_vec()
+    }
+
+    /// Returns whether there is an arc from `src` to `dst`.
+    ///
+    /// The successors of `src` are obtained as in
+    /// [`successors`](AsyncGraph::successors), so concurrent lookups
+    /// are coalesced with those of the other method.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if decoding the successors panics (e.g., because
+    /// `src` is out of bounds).
+    pub async fn contains_arc(&self, src: usize, dst: usize) -> bool {
+        self.lookup(src).await.contains(&dst)
+    }
+
+    /// Returns the successors of a node, decoding them on a blocking thread
+    /// unless a lookup of the same node is already in flight.
+    async fn lookup(&self, node: usize) -> Arc<[usize]> {
+        let (sender, receiver) = oneshot::channel();
+        let first = match self.pending.lock().unwrap().entry(node) {
+            Entry::Occupied(mut entry) => {
+                entry.get_mut().push(sender);
+                false
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(vec![sender]);
+                true
+            }
+        };
+
+        if first {
+            let graph = self.graph.clone();
+            let pending = self.pending.clone();
+            // The task answers all waiters, so it does not depend on the
+            // lookup that started it, which might be cancelled
+            let task = move || {
+                let successors = std::panic::catch_unwind(AssertUnwindSafe(|| {
+                    graph.successors(node).into_iter().collect::<Arc<[usize]>>()
+                }));
+                let waiters = pending.lock().unwrap().remove(&node).unwrap_or_default();
+                // On a panic, dropping the senders makes the waiters panic
+                if let Ok(successors) = successors {
+                    for waiter in waiters {
+                        let _ = waiter.send(successors.clone());
+                    }
+                }
+            };
+            match &self.thread_pool {
+                Some(thread_pool) => thread_pool.spawn(task),
+                None => drop(tokio::task::spawn_blocking(task)),
+            }
+        }
+
+        receiver
+            .await
+            .unwrap_or_else(|_| panic!("Could not decode the successors of node {}", node))
+    }
+}
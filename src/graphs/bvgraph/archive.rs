@@ -0,0 +1,381 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+//! Single-file archives containing a [`BvGraph`] and its accessory files.
+//!
+//! An archive is a container storing a sequence of named sections, each
+//! section being the content of one of the files of a graph (the name of the
+//! section is the extension of the file). The layout is as follows, with all
+//! integers stored in little-endian order:
+//!
+//! - the eight-byte magic number [`ARCHIVE_MAGIC`];
+//! - the number _n_ of sections, as a `u64`;
+//! - _n_ entries of the section table, each made of the name of the section
+//!   (zero-padded to [`SECTION_NAME_LEN`] bytes), the offset in bytes of the
+//!   section from the start of the archive, as a `u64`, and its length in
+//!   bytes, as a `u64`;
+//! - the content of the sections.
+//!
+//! Every section starts at a multiple of [`ARCHIVE_ALIGNMENT`], and it is
+//! followed by zeroes up to the next multiple. In this way each section can be
+//! memory-mapped separately (the alignment is a multiple of the allocation
+//! granularity of all supported platforms), and the graph section is padded
+//! enough to be read one word at a time.
+//!
+//! Archives are written by [`write_archive`] and loaded by
+//! [`BvGraph::with_archive`].
+
+use super::load::parse_properties_from;
+use super::select_params::select_dispatch;
+use super::*;
+use crate::prelude::*;
+use anyhow::{ensure, Context, Result};
+use dsi_bitstream::prelude::*;
+use epserde::prelude::*;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+/// The extension of archive files.
+pub const ARCHIVE_EXTENSION: &str = "bvar";
+
+/// The magic number at the start of an archive.
+pub const ARCHIVE_MAGIC: [u8; 8] = *b"BVARCH01";
+
+/// The alignment in bytes of the sections of an archive.
+pub const ARCHIVE_ALIGNMENT: u64 = 1 << 16;
+
+/// The length in bytes of the name field of a section-table entry.
+pub const SECTION_NAME_LEN: usize = 16;
+
+/// The sections stored by [`write_archive`], if the corresponding files exist.
+pub const ARCHIVE_SECTIONS: [&str; 4] = [
+    PROPERTIES_EXTENSION,
+    GRAPH_EXTENSION,
+    OFFSETS_EXTENSION,
+    EF_EXTENSION,
+];
+
+/// An entry of the section table of an archive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section {
+    /// The name of the section (the extension of the original file).
+    pub name: String,
+    /// The offset in bytes of the section from the start of the archive.
+    pub offset: u64,
+    /// The length in bytes of the section.
+    pub len: u64,
+}
+
+/// An archive opened for reading.
+///
+/// Opening an archive reads and validates its section table; the content of
+/// the sections can then be read with [`Archive::reader`] or memory-mapped
+/// with [`Archive::mmap`].
+#[derive(Debug, Clone)]
+pub struct Archive {
+    path: PathBuf,
+    sections: Vec<Section>,
+}
+
+impl Archive {
+    /// Opens an archive and reads its section table.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let mut file = BufReader::new(
+            File::open(path).with_context(|| format!("Cannot open archive {}", path.display()))?,
+        );
+        let file_len = path
+            .metadata()
+            .with_context(|| format!("Cannot stat {}", path.display()))?
+            .len();
+
+        let mut magic = [0; 8];
+        file.read_exact(&mut magic)
+            .with_context(|| format!("Cannot read magic number of {}", path.display()))?;
+        ensure!(
+            magic == ARCHIVE_MAGIC,
+            "{} is not a graph archive (wrong magic number)",
+            path.display()
+        );
+
+        let mut word = [0; 8];
+        file.read_exact(&mut word)?;
+        let num_sections = u64::from_le_bytes(word);
+        ensure!(
+            num_sections <= (file_len - 16) / (SECTION_NAME_LEN as u64 + 16),
+            "The section table of {} is truncated",
+            path.display()
+        );
+
+        let mut sections = Vec::with_capacity(num_sections as usize);
+        for _ in 0..num_sections {
+            let mut name = [0; SECTION_NAME_LEN];
+            file.read_exact(&mut name)?;
+            let name_len = name.iter().position(|&b| b == 0).unwrap_or(name.len());
+            let name = std::str::from_utf8(&name[..name_len])
+                .with_context(|| format!("Invalid section name in {}", path.display()))?
+                .to_owned();
+            file.read_exact(&mut word)?;
+            let offset = u64::from_le_bytes(word);
+            file.read_exact(&mut word)?;
+            let len = u64::from_le_bytes(word);
+            ensure!(
+                offset % ARCHIVE_ALIGNMENT == 0,
+                "Section {} of {} is not aligned",
+                name,
+                path.display()
+            );
+            ensure!(
+                offset.checked_add(len).is_some_and(|end| end <= file_len),
+                "Section {} of {} is truncated",
+                name,
+                path.display()
+            );
+            sections.push(Section { name, offset, len });
+        }
+
+        Ok(Self {
+            path: path.to_owned(),
+            sections,
+        })
+    }
+
+    /// Returns the path of the archive.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the section table.
+    pub fn sections(&self) -> &[Section] {
+        &self.sections
+    }
+
+    /// Returns the section with the given name, if present.
+    pub fn section(&self, name: &str) -> Option<&Section> {
+        self.sections.iter().find(|s| s.name == name)
+    }
+
+    fn get(&self, name: &str) -> Result<&Section> {
+        self.section(name).with_context(|| {
+            format!(
+                "Archive {} does not contain a {} section",
+                self.path.display(),
+                name
+            )
+        })
+    }
+
+    /// Returns a reader on the content of a section.
+    pub fn reader(&self, name: &str) -> Result<impl Read> {
+        let section = self.get(name)?;
+        let mut file = File::open(&self.path)
+            .with_context(|| format!("Cannot open archive {}", self.path.display()))?;
+        file.seek(SeekFrom::Start(section.offset))?;
+        Ok(BufReader::new(file).take(section.len))
+    }
+
+    /// Memory-maps a section.
+    ///
+    /// The length of the mapping is the length of the section rounded up to a
+    /// multiple of the size of `W`; the additional bytes are zeroes.
+    pub fn mmap<W>(&self, name: &str, flags: MemoryFlags) -> Result<MmapHelper<W>> {
+        let section = self.get(name)?;
+        MmapHelper::mmap_range(
+            &self.path,
+            section.offset,
+            section.len.try_into()?,
+            flags.into(),
+        )
+        .with_context(|| {
+            format!(
+                "Cannot map section {} of archive {}",
+                name,
+                self.path.display()
+            )
+        })
+    }
+}
+
+/// Writes the files of a graph in an archive.
+///
+/// The sections are the files with the extensions in [`ARCHIVE_SECTIONS`]
+/// that exist; the `.properties` and `.graph` files are mandatory. Note that
+/// the `.ef` file is necessary to load the archive with
+/// [`BvGraph::with_archive`].
+pub fn write_archive(basename: impl AsRef<Path>, dst: impl AsRef<Path>) -> Result<()> {
+    let basename = Basename::new(basename);
+    let dst = dst.as_ref();
+
+    let mut files = vec![];
+    for extension in ARCHIVE_SECTIONS {
+        let path = basename.with_ext(extension);
+        if path.exists() {
+            let len = path
+                .metadata()
+                .with_context(|| format!("Cannot stat {}", path.display()))?
+                .len();
+            files.push((extension, path, len));
+        } else {
+            ensure!(
+                extension != PROPERTIES_EXTENSION && extension != GRAPH_EXTENSION,
+                "Missing file {}",
+                path.display()
+            );
+        }
+    }
+
+    let align = |x: u64| x.div_ceil(ARCHIVE_ALIGNMENT) * ARCHIVE_ALIGNMENT;
+    let mut offset = align(16 + files.len() as u64 * (SECTION_NAME_LEN as u64 + 16));
+
+    let mut writer = BufWriter::new(
+        File::create(dst).with_context(|| format!("Cannot create archive {}", dst.display()))?,
+    );
+    writer.write_all(&ARCHIVE_MAGIC)?;
+    writer.write_all(&(files.len() as u64).to_le_bytes())?;
+    for (extension, _, len) in &files {
+        let mut name = [0; SECTION_NAME_LEN];
+        name[..extension.len()].copy_from_slice(extension.as_bytes());
+        writer.write_all(&name)?;
+        writer.write_all(&offset.to_le_bytes())?;
+        writer.write_all(&len.to_le_bytes())?;
+        offset = align(offset + len);
+    }
+
+    let mut written = 16 + files.len() as u64 * (SECTION_NAME_LEN as u64 + 16);
+    for (_, path, len) in &files {
+        let padding = align(written) - written;
+        std::io::copy(&mut std::io::repeat(0).take(padding), &mut writer)?;
+        let copied = std::io::copy(
+            &mut File::open(path).with_context(|| format!("Cannot open {}", path.display()))?,
+            &mut writer,
+        )
+        .with_context(|| format!("Cannot copy {} to {}", path.display(), dst.display()))?;
+        ensure!(
+            copied == *len,
+            "{} changed while being archived",
+            path.display()
+        );
+        written += padding + len;
+    }
+    let padding = align(written) - written;
+    std::io::copy(&mut std::io::repeat(0).take(padding), &mut writer)?;
+    writer
+        .flush()
+        .with_context(|| format!("Cannot write archive {}", dst.display()))?;
+
+    Ok(())
+}
+
+/// A load configuration for a [`BvGraph`] stored in an [archive](self).
+///
+/// It is returned by [`BvGraph::with_archive`]. The graph section is
+/// memory-mapped, whereas the Elias–Fano representation of the offsets is
+/// fully deserialized in memory.
+#[derive(Debug, Clone)]
+pub struct ArchiveLoadConfig<E: Endianness> {
+    path: PathBuf,
+    flags: MemoryFlags,
+    _marker: std::marker::PhantomData<E>,
+}
+
+impl BvGraph<()> {
+    /// Returns a load configuration for a graph stored in an [archive](self)
+    /// written by [`write_archive`].
+    pub fn with_archive(path: impl AsRef<Path>) -> ArchiveLoadConfig<BE> {
+        ArchiveLoadConfig {
+            path: path.as_ref().to_owned(),
+            flags: MemoryFlags::empty(),
+            _marker: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<E: Endianness> ArchiveLoadConfig<E> {
+    /// Set the endianness of the graph.
+    pub fn endianness<E2: Endianness>(self) -> ArchiveLoadConfig<E2> {
+        ArchiveLoadConfig {
+            path: self.path,
+            flags: self.flags,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    /// Set flags for memory-mapping the graph section.
+    pub fn flags(self, flags: MemoryFlags) -> Self {
+        ArchiveLoadConfig { flags, ..self }
+    }
+
+    /// Load a random-access graph with dynamic dispatch.
+    #[allow(clippy::type_complexity)]
+    pub fn load(self) -> Result<BvGraph<DynCodesDecoderFactory<E, MmapHelper<u32>, DispatchEF>>>
+    where
+        for<'a> <MmapHelper<u32> as BitReaderFactory<E>>::BitReader<'a>: CodeRead<E> + BitSeek,
+    {
+        let archive = Archive::open(&self.path)?;
+        let name = format!("{}:{}", self.path.display(), PROPERTIES_EXTENSION);
+        let mut properties = vec![];
+        archive
+            .reader(PROPERTIES_EXTENSION)?
+            .read_to_end(&mut properties)
+            .with_context(|| format!("Cannot read {}", name))?;
+        let (num_nodes, num_arcs, comp_flags) =
+            parse_properties_from::<E>(properties.as_slice(), &name)?;
+        let params = SelectParams::from_properties_map(
+            &java_properties::read(properties.as_slice())?,
+            EF_SELECT_PROPERTY,
+        )
+        .with_context(|| format!("In {}", name))?;
+        let factory = archive.mmap::<u32>(GRAPH_EXTENSION, self.flags)?;
+        let mut reader = archive.reader(EF_EXTENSION)?;
+        let offsets = select_dispatch!(params, T => T::deserialize_full(&mut reader)
+            .with_context(|| {
+                format!(
+                    "Cannot load Elias-Fano pointer list from archive {}",
+                    self.path.display()
+                )
+            })?
+            .into());
+
+        Ok(BvGraph::new(
+            DynCodesDecoderFactory::new(factory, offsets.into(), comp_flags)?,
+            num_nodes,
+            num_arcs,
+            comp_flags.compression_window,
+            comp_flags.min_interval_length,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_archive_layout() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let basename = Basename::new(dir.path().join("g"));
+        std::fs::write(basename.with_ext(PROPERTIES_EXTENSION), b"nodes=0\n")?;
+        std::fs::write(basename.with_ext(GRAPH_EXTENSION), b"\x01\x02\x03")?;
+        let dst = basename.with_ext(ARCHIVE_EXTENSION);
+        write_archive(&basename, &dst)?;
+
+        let archive = Archive::open(&dst)?;
+        assert_eq!(archive.sections().len(), 2);
+        assert!(archive.section(EF_EXTENSION).is_none());
+        let graph = archive.section(GRAPH_EXTENSION).unwrap();
+        assert_eq!(graph.offset % ARCHIVE_ALIGNMENT, 0);
+        assert_eq!(graph.len, 3);
+        let mut content = vec![];
+        archive
+            .reader(PROPERTIES_EXTENSION)?
+            .read_to_end(&mut content)?;
+        assert_eq!(content, b"nodes=0\n");
+        let mmap = archive.mmap::<u32>(GRAPH_EXTENSION, MemoryFlags::empty())?;
+        assert_eq!(mmap.as_ref(), &[u32::from_ne_bytes([1, 2, 3, 0])]);
+        Ok(())
+    }
+}
@@ -182,6 +182,13 @@ impl<
     fn read_residual(&mut self) -> u64 {
         select_code_read!(self, RESIDUALS, K)
     }
+    #[inline(always)]
+    fn read_residuals_into(&mut self, buf: &mut [u64]) -> usize {
+        for value in buf.iter_mut() {
+            *value = select_code_read!(self, RESIDUALS, K);
+        }
+        buf.len()
+    }
 }
 
 pub struct ConstCodesDecoderFactory<
@@ -318,6 +325,11 @@ where
             _marker: PhantomData,
         })
     }
+
+    #[inline(always)]
+    fn offset(&self, node: usize) -> u64 {
+        self.offsets.get(node) as u64
+    }
 }
 
 impl<
@@ -75,3 +75,105 @@ impl<CR: Decode> Decode for DebugDecoder<CR> {
         residual
     }
 }
+
+/// The components of the record of a node, in the order in which they
+/// are read by a [`Decode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Component {
+    Outdegree,
+    ReferenceOffset,
+    BlockCount,
+    Block,
+    IntervalCount,
+    IntervalStart,
+    IntervalLen,
+    FirstResidual,
+    Residual,
+}
+
+impl core::fmt::Display for Component {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            Component::Outdegree => "outdegree",
+            Component::ReferenceOffset => "reference_offset",
+            Component::BlockCount => "block_count",
+            Component::Block => "block",
+            Component::IntervalCount => "interval_count",
+            Component::IntervalStart => "interval_start",
+            Component::IntervalLen => "interval_len",
+            Component::FirstResidual => "first_residual",
+            Component::Residual => "residual",
+        })
+    }
+}
+
+/// A debug wrapper on a code read that records the components it reads
+/// and their values.
+///
+/// Contrarily to [`DebugDecoder`], which prints the components to stderr,
+/// this decoder makes it possible to compare the decoding of the same
+/// node in different graphs (see [`diff_node_trace`](crate::graphs::bvgraph::diff_node_trace)).
+#[derive(Debug, Clone)]
+pub struct TraceDecoder<CR: Decode> {
+    pub cr: CR,
+    pub trace: Vec<(Component, u64)>,
+}
+
+impl<CR: Decode> TraceDecoder<CR> {
+    pub fn new(cr: CR) -> Self {
+        Self { cr, trace: vec![] }
+    }
+
+    #[inline(always)]
+    fn record(&mut self, component: Component, value: u64) -> u64 {
+        self.trace.push((component, value));
+        value
+    }
+}
+
+impl<CR: Decode> Decode for TraceDecoder<CR> {
+    fn read_outdegree(&mut self) -> u64 {
+        let value = self.cr.read_outdegree();
+        self.record(Component::Outdegree, value)
+    }
+
+    fn read_reference_offset(&mut self) -> u64 {
+        let value = self.cr.read_reference_offset();
+        self.record(Component::ReferenceOffset, value)
+    }
+
+    fn read_block_count(&mut self) -> u64 {
+        let value = self.cr.read_block_count();
+        self.record(Component::BlockCount, value)
+    }
+
+    fn read_block(&mut self) -> u64 {
+        let value = self.cr.read_block();
+        self.record(Component::Block, value)
+    }
+
+    fn read_interval_count(&mut self) -> u64 {
+        let value = self.cr.read_interval_count();
+        self.record(Component::IntervalCount, value)
+    }
+
+    fn read_interval_start(&mut self) -> u64 {
+        let value = self.cr.read_interval_start();
+        self.record(Component::IntervalStart, value)
+    }
+
+    fn read_interval_len(&mut self) -> u64 {
+        let value = self.cr.read_interval_len();
+        self.record(Component::IntervalLen, value)
+    }
+
+    fn read_first_residual(&mut self) -> u64 {
+        let value = self.cr.read_first_residual();
+        self.record(Component::FirstResidual, value)
+    }
+
+    fn read_residual(&mut self) -> u64 {
+        let value = self.cr.read_residual();
+        self.record(Component::Residual, value)
+    }
+}
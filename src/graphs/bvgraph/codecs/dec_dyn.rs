@@ -25,6 +25,7 @@ pub struct DynCodesDecoder<E: Endianness, CR: CodeRead<E>> {
     pub(crate) read_interval_len: fn(&mut CR) -> u64,
     pub(crate) read_first_residual: fn(&mut CR) -> u64,
     pub(crate) read_residual: fn(&mut CR) -> u64,
+    pub(crate) read_residuals: fn(&mut CR, &mut [u64]),
     pub(crate) _marker: core::marker::PhantomData<E>,
 }
 
@@ -42,6 +43,7 @@ impl<E: Endianness, CR: CodeRead<E> + Clone> Clone for DynCodesDecoder<E, CR> {
             read_interval_len: self.read_interval_len,
             read_first_residual: self.read_first_residual,
             read_residual: self.read_residual,
+            read_residuals: self.read_residuals,
             _marker: PhantomData,
         }
     }
@@ -59,6 +61,27 @@ impl<E: Endianness, CR: CodeRead<E>> DynCodesDecoder<E, CR> {
     const READ_ZETA7: fn(&mut CR) -> u64 = |cr| cr.read_zeta(7).unwrap();
     const READ_ZETA1: fn(&mut CR) -> u64 = Self::READ_GAMMA;
 
+    // Batched versions used for residuals
+    const READ_UNARY_BATCH: fn(&mut CR, &mut [u64]) =
+        |cr, buf| buf.iter_mut().for_each(|x| *x = cr.read_unary().unwrap());
+    const READ_GAMMA_BATCH: fn(&mut CR, &mut [u64]) =
+        |cr, buf| buf.iter_mut().for_each(|x| *x = cr.read_gamma().unwrap());
+    const READ_DELTA_BATCH: fn(&mut CR, &mut [u64]) =
+        |cr, buf| buf.iter_mut().for_each(|x| *x = cr.read_delta().unwrap());
+    const READ_ZETA2_BATCH: fn(&mut CR, &mut [u64]) =
+        |cr, buf| buf.iter_mut().for_each(|x| *x = cr.read_zeta(2).unwrap());
+    const READ_ZETA3_BATCH: fn(&mut CR, &mut [u64]) =
+        |cr, buf| buf.iter_mut().for_each(|x| *x = cr.read_zeta3().unwrap());
+    const READ_ZETA4_BATCH: fn(&mut CR, &mut [u64]) =
+        |cr, buf| buf.iter_mut().for_each(|x| *x = cr.read_zeta(4).unwrap());
+    const READ_ZETA5_BATCH: fn(&mut CR, &mut [u64]) =
+        |cr, buf| buf.iter_mut().for_each(|x| *x = cr.read_zeta(5).unwrap());
+    const READ_ZETA6_BATCH: fn(&mut CR, &mut [u64]) =
+        |cr, buf| buf.iter_mut().for_each(|x| *x = cr.read_zeta(6).unwrap());
+    const READ_ZETA7_BATCH: fn(&mut CR, &mut [u64]) =
+        |cr, buf| buf.iter_mut().for_each(|x| *x = cr.read_zeta(7).unwrap());
+    const READ_ZETA1_BATCH: fn(&mut CR, &mut [u64]) = Self::READ_GAMMA_BATCH;
+
     pub fn new(code_reader: CR, cf: &CompFlags) -> anyhow::Result<Self> {
         macro_rules! select_code {
             ($code:expr) => {
@@ -81,6 +104,27 @@ impl<E: Endianness, CR: CodeRead<E>> DynCodesDecoder<E, CR> {
             };
         }
 
+        macro_rules! select_batch_code {
+            ($code:expr) => {
+                match $code {
+                    Code::Unary => Self::READ_UNARY_BATCH,
+                    Code::Gamma => Self::READ_GAMMA_BATCH,
+                    Code::Delta => Self::READ_DELTA_BATCH,
+                    Code::Zeta { k: 1 } => Self::READ_ZETA1_BATCH,
+                    Code::Zeta { k: 2 } => Self::READ_ZETA2_BATCH,
+                    Code::Zeta { k: 3 } => Self::READ_ZETA3_BATCH,
+                    Code::Zeta { k: 4 } => Self::READ_ZETA4_BATCH,
+                    Code::Zeta { k: 5 } => Self::READ_ZETA5_BATCH,
+                    Code::Zeta { k: 6 } => Self::READ_ZETA6_BATCH,
+                    Code::Zeta { k: 7 } => Self::READ_ZETA7_BATCH,
+                    code => bail!(
+                        "Only unary, ɣ, δ, and ζ₁-ζ₇ codes are allowed, {:?} is not supported",
+                        code
+                    ),
+                }
+            };
+        }
+
         Ok(Self {
             code_reader,
             read_outdegree: select_code!(&cf.outdegrees),
@@ -92,6 +136,7 @@ impl<E: Endianness, CR: CodeRead<E>> DynCodesDecoder<E, CR> {
             read_interval_len: select_code!(&cf.intervals),
             read_first_residual: select_code!(&cf.residuals),
             read_residual: select_code!(&cf.residuals),
+            read_residuals: select_batch_code!(&cf.residuals),
             _marker: core::marker::PhantomData,
         })
     }
@@ -150,6 +195,11 @@ impl<E: Endianness, CR: CodeRead<E>> Decode for DynCodesDecoder<E, CR> {
     fn read_residual(&mut self) -> u64 {
         (self.read_residual)(&mut self.code_reader)
     }
+    #[inline(always)]
+    fn read_residuals_into(&mut self, buf: &mut [u64]) -> usize {
+        (self.read_residuals)(&mut self.code_reader, buf);
+        buf.len()
+    }
 }
 
 pub struct DynCodesDecoderFactory<
@@ -173,6 +223,7 @@ pub struct DynCodesDecoderFactory<
     read_interval_len: for<'a> fn(&mut <F as BitReaderFactory<E>>::BitReader<'a>) -> u64,
     read_first_residual: for<'a> fn(&mut <F as BitReaderFactory<E>>::BitReader<'a>) -> u64,
     read_residual: for<'a> fn(&mut <F as BitReaderFactory<E>>::BitReader<'a>) -> u64,
+    read_residuals: for<'a> fn(&mut <F as BitReaderFactory<E>>::BitReader<'a>, &mut [u64]),
     /// Tell the compiler that's Ok that we don't store `E` but we need it
     /// for typing.
     _marker: core::marker::PhantomData<E>,
@@ -210,6 +261,7 @@ where
             read_interval_len: self.read_interval_len,
             read_first_residual: self.read_first_residual,
             read_residual: self.read_residual,
+            read_residuals: self.read_residuals,
             _marker: PhantomData,
         }
     }
@@ -244,6 +296,28 @@ where
     const READ_ZETA1: for<'a> fn(&mut <F as BitReaderFactory<E>>::BitReader<'a>) -> u64 =
         Self::READ_GAMMA;
 
+    // Batched versions used for residuals
+    const READ_UNARY_BATCH: for<'a> fn(&mut <F as BitReaderFactory<E>>::BitReader<'a>, &mut [u64]) =
+        |cr, buf| buf.iter_mut().for_each(|x| *x = cr.read_unary().unwrap());
+    const READ_GAMMA_BATCH: for<'a> fn(&mut <F as BitReaderFactory<E>>::BitReader<'a>, &mut [u64]) =
+        |cr, buf| buf.iter_mut().for_each(|x| *x = cr.read_gamma().unwrap());
+    const READ_DELTA_BATCH: for<'a> fn(&mut <F as BitReaderFactory<E>>::BitReader<'a>, &mut [u64]) =
+        |cr, buf| buf.iter_mut().for_each(|x| *x = cr.read_delta().unwrap());
+    const READ_ZETA2_BATCH: for<'a> fn(&mut <F as BitReaderFactory<E>>::BitReader<'a>, &mut [u64]) =
+        |cr, buf| buf.iter_mut().for_each(|x| *x = cr.read_zeta(2).unwrap());
+    const READ_ZETA3_BATCH: for<'a> fn(&mut <F as BitReaderFactory<E>>::BitReader<'a>, &mut [u64]) =
+        |cr, buf| buf.iter_mut().for_each(|x| *x = cr.read_zeta3().unwrap());
+    const READ_ZETA4_BATCH: for<'a> fn(&mut <F as BitReaderFactory<E>>::BitReader<'a>, &mut [u64]) =
+        |cr, buf| buf.iter_mut().for_each(|x| *x = cr.read_zeta(4).unwrap());
+    const READ_ZETA5_BATCH: for<'a> fn(&mut <F as BitReaderFactory<E>>::BitReader<'a>, &mut [u64]) =
+        |cr, buf| buf.iter_mut().for_each(|x| *x = cr.read_zeta(5).unwrap());
+    const READ_ZETA6_BATCH: for<'a> fn(&mut <F as BitReaderFactory<E>>::BitReader<'a>, &mut [u64]) =
+        |cr, buf| buf.iter_mut().for_each(|x| *x = cr.read_zeta(6).unwrap());
+    const READ_ZETA7_BATCH: for<'a> fn(&mut <F as BitReaderFactory<E>>::BitReader<'a>, &mut [u64]) =
+        |cr, buf| buf.iter_mut().for_each(|x| *x = cr.read_zeta(7).unwrap());
+    const READ_ZETA1_BATCH: for<'a> fn(&mut <F as BitReaderFactory<E>>::BitReader<'a>, &mut [u64]) =
+        Self::READ_GAMMA_BATCH;
+
     #[inline(always)]
     /// Return a clone of the compression flags.
     pub fn get_compression_flags(&self) -> CompFlags {
@@ -273,6 +347,27 @@ where
             };
         }
 
+        macro_rules! select_batch_code {
+            ($code:expr) => {
+                match $code {
+                    Code::Unary => Self::READ_UNARY_BATCH,
+                    Code::Gamma => Self::READ_GAMMA_BATCH,
+                    Code::Delta => Self::READ_DELTA_BATCH,
+                    Code::Zeta { k: 1 } => Self::READ_ZETA1_BATCH,
+                    Code::Zeta { k: 2 } => Self::READ_ZETA2_BATCH,
+                    Code::Zeta { k: 3 } => Self::READ_ZETA3_BATCH,
+                    Code::Zeta { k: 4 } => Self::READ_ZETA4_BATCH,
+                    Code::Zeta { k: 5 } => Self::READ_ZETA5_BATCH,
+                    Code::Zeta { k: 6 } => Self::READ_ZETA6_BATCH,
+                    Code::Zeta { k: 7 } => Self::READ_ZETA7_BATCH,
+                    code => bail!(
+                        "Only unary, ɣ, δ, and ζ₁-ζ₇ codes are allowed, {:?} is not supported",
+                        code
+                    ),
+                }
+            };
+        }
+
         Ok(Self {
             factory,
             offsets,
@@ -285,6 +380,7 @@ where
             read_interval_len: select_code!(cf.intervals),
             read_first_residual: select_code!(cf.residuals),
             read_residual: select_code!(cf.residuals),
+            read_residuals: select_batch_code!(cf.residuals),
             compression_flags: cf,
             _marker: core::marker::PhantomData,
         })
@@ -316,9 +412,15 @@ where
             read_interval_len: self.read_interval_len,
             read_first_residual: self.read_first_residual,
             read_residual: self.read_residual,
+            read_residuals: self.read_residuals,
             _marker: PhantomData,
         })
     }
+
+    #[inline(always)]
+    fn offset(&self, node: usize) -> u64 {
+        self.offsets.get(node) as u64
+    }
 }
 
 impl<E: Endianness, F: BitReaderFactory<E>> SequentialDecoderFactory
@@ -343,6 +445,7 @@ where
             read_interval_len: self.read_interval_len,
             read_first_residual: self.read_first_residual,
             read_residual: self.read_residual,
+            read_residuals: self.read_residuals,
             _marker: PhantomData,
         })
     }
@@ -89,6 +89,16 @@ impl<E: Endianness, CW: CodeWrite<E>> DynCodesEncoder<E, CW> {
             _marker: core::marker::PhantomData,
         }
     }
+
+    /// Returns a mutable reference to the underlying code writer.
+    pub(crate) fn code_writer_mut(&mut self) -> &mut CW {
+        &mut self.code_writer
+    }
+
+    /// Returns the underlying code writer.
+    pub fn into_inner(self) -> CW {
+        self.code_writer
+    }
 }
 
 impl<E: Endianness, CW: CodeWrite<E> + BitSeek + Clone> BitSeek for DynCodesEncoder<E, CW> {
@@ -290,3 +300,16 @@ impl Encode for DynCodesEstimator {
         Ok(0)
     }
 }
+
+/// An estimator is its own estimator, so it can be used to estimate the
+/// size of a compressed graph without writing anything.
+impl EncodeAndEstimate for DynCodesEstimator {
+    type Estimator<'a>
+        = &'a mut DynCodesEstimator
+    where
+        Self: 'a;
+
+    fn estimator(&mut self) -> Self::Estimator<'_> {
+        self
+    }
+}
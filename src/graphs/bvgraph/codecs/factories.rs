@@ -16,6 +16,8 @@ bit readers accessing a graph data using different techniques.
 - [`MemoryFactory`] creates bit readers from a slice of memory,
   either [allocated](MemoryFactory::new_mem) or [mapped](MemoryFactory::new_mmap).
 - [`MmapHelper`] can be used to create a bit reader from a memory-mapped file.
+- [`PrefetchFactory`] creates bit readers from a memory-mapped file that
+  prefetch the part of the file following the current position.
 
 Any factory can be plugged either into a
 [`SequentialDecoderFactory`](super::SequentialDecoderFactory)
@@ -28,7 +30,7 @@ use bitflags::bitflags;
 use common_traits::UnsignedInt;
 use dsi_bitstream::{
     impls::{BufBitReader, MemWordReader, WordAdapter},
-    traits::Endianness,
+    traits::{Endianness, WordRead},
 };
 use std::{
     fs::File,
@@ -170,7 +172,9 @@ impl<E: Endianness> MemoryFactory<E, Box<[u32]>> {
             .len() as usize;
         let mut file = std::fs::File::open(path)
             .with_context(|| format!("Could not open {}", path.display()))?;
-        let capacity = file_len.align_to(16);
+        // The bitstream of an empty graph is empty, but allocations and mmaps
+        // must be nonempty
+        let capacity = file_len.align_to(16).max(16);
 
         // SAFETY: the entire vector will be filled with data read from the file,
         // or with zeroes if the file is shorter than the vector.
@@ -204,7 +208,9 @@ impl<E: Endianness> MemoryFactory<E, MmapHelper<u32>> {
             .len() as usize;
         let mut file = std::fs::File::open(path)
             .with_context(|| format!("Could not open {}", path.display()))?;
-        let capacity = file_len.align_to(16);
+        // The bitstream of an empty graph is empty, but allocations and mmaps
+        // must be nonempty
+        let capacity = file_len.align_to(16).max(16);
 
         let mut mmap = mmap_rs::MmapOptions::new(capacity)?
             .with_flags(flags.into())
@@ -279,3 +285,148 @@ impl<E: Endianness> BitReaderFactory<E> for MmapHelper<u32> {
         BufBitReader::<E, _>::new(MemWordReader::new(self.as_ref()))
     }
 }
+
+/// A factory of bit readers on a memory-mapped file that prefetch the part of
+/// the file following the current position.
+///
+/// The file is divided into chunks of fixed size. When a reader starts
+/// reading a chunk, it asks the kernel (using `madvise(MADV_WILLNEED)`) to
+/// start reading the following chunk in the background, so that page faults
+/// and decoding are overlapped, similarly to double buffering. This is useful
+/// for full sequential scans of graphs on storage with high latency, such as
+/// spinning disks or network filesystems; on fast local storage the readahead
+/// performed by the kernel is usually sufficient.
+///
+/// Prefetching is just advice: on non-Unix platforms, or if the kernel
+/// ignores the advice, the readers behave exactly like those of
+/// [`MmapHelper`].
+#[derive(Debug)]
+pub struct PrefetchFactory<E: Endianness> {
+    mmap: MmapHelper<u32>,
+    chunk_words: usize,
+    page_size: usize,
+    _marker: core::marker::PhantomData<E>,
+}
+
+impl<E: Endianness> PrefetchFactory<E> {
+    /// Maps a file, prefetching chunks of `chunk_bytes` bytes.
+    ///
+    /// The chunk size is rounded up to a multiple of the page size.
+    pub fn new(
+        path: impl AsRef<Path>,
+        flags: MemoryFlags,
+        chunk_bytes: usize,
+    ) -> anyhow::Result<Self> {
+        ensure!(chunk_bytes != 0, "The prefetch chunk size must be positive");
+        let page_size = page_size();
+        let chunk_bytes = chunk_bytes.div_ceil(page_size) * page_size;
+        Ok(Self {
+            mmap: MmapHelper::mmap(path, flags.into())?,
+            chunk_words: chunk_bytes / core::mem::size_of::<u32>(),
+            page_size,
+            _marker: core::marker::PhantomData,
+        })
+    }
+
+    /// Returns the size in bytes of the prefetched chunks.
+    pub fn chunk_bytes(&self) -> usize {
+        self.chunk_words * core::mem::size_of::<u32>()
+    }
+}
+
+impl<E: Endianness> BitReaderFactory<E> for PrefetchFactory<E> {
+    type BitReader<'a>
+        = BufBitReader<E, PrefetchWordReader<'a>>
+    where
+        Self: 'a;
+
+    fn new_reader(&self) -> Self::BitReader<'_> {
+        BufBitReader::<E, _>::new(PrefetchWordReader::new(
+            self.mmap.as_ref(),
+            self.chunk_words,
+            self.page_size,
+        ))
+    }
+}
+
+/// Returns the size of a memory page.
+fn page_size() -> usize {
+    #[cfg(unix)]
+    {
+        // SAFETY: sysconf has no preconditions
+        let size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+        if size > 0 {
+            return size as usize;
+        }
+    }
+    4096
+}
+
+/// A word reader on a slice of memory-mapped words that prefetches the chunk
+/// following the one being read.
+///
+/// It is returned (wrapped in a [`BufBitReader`]) by [`PrefetchFactory`].
+#[derive(Debug, Clone)]
+pub struct PrefetchWordReader<'a> {
+    reader: MemWordReader<u32, &'a [u32]>,
+    data: &'a [u32],
+    chunk_words: usize,
+    page_size: usize,
+    word_pos: usize,
+    /// The position of the first word of the next chunk.
+    next_chunk: usize,
+}
+
+impl<'a> PrefetchWordReader<'a> {
+    fn new(data: &'a [u32], chunk_words: usize, page_size: usize) -> Self {
+        let reader = Self {
+            reader: MemWordReader::new(data),
+            data,
+            chunk_words,
+            page_size,
+            word_pos: 0,
+            next_chunk: chunk_words,
+        };
+        // The first chunk will be needed immediately, and the second one
+        // will be needed next
+        reader.will_need(0, 2 * chunk_words);
+        reader
+    }
+
+    /// Advises the kernel that the words in the given range will be needed.
+    fn will_need(&self, start: usize, len: usize) {
+        let end = (start + len).min(self.data.len());
+        if start >= end {
+            return;
+        }
+        #[cfg(unix)]
+        {
+            let addr = self.data[start..].as_ptr() as usize;
+            let aligned = addr & !(self.page_size - 1);
+            let len = addr - aligned + (end - start) * core::mem::size_of::<u32>();
+            // SAFETY: the advised range is contained in the pages of the
+            // mapping, and MADV_WILLNEED does not change its contents; errors
+            // are ignored as this is just advice.
+            unsafe {
+                libc::madvise(aligned as *mut libc::c_void, len, libc::MADV_WILLNEED);
+            }
+        }
+    }
+}
+
+impl WordRead for PrefetchWordReader<'_> {
+    type Error = <MemWordReader<u32, &'static [u32]> as WordRead>::Error;
+    type Word = u32;
+
+    #[inline(always)]
+    fn read_word(&mut self) -> Result<u32, Self::Error> {
+        if self.word_pos == self.next_chunk {
+            // We are entering a new chunk, which has already been
+            // prefetched: prefetch the following one
+            self.will_need(self.next_chunk + self.chunk_words, self.chunk_words);
+            self.next_chunk += self.chunk_words;
+        }
+        self.word_pos += 1;
+        self.reader.read_word()
+    }
+}
@@ -57,6 +57,19 @@ pub trait Decode {
     fn read_interval_len(&mut self) -> u64;
     fn read_first_residual(&mut self) -> u64;
     fn read_residual(&mut self) -> u64;
+    /// Fills `buf` with residuals, returning the number of residuals read,
+    /// which is always `buf.len()`.
+    ///
+    /// The default implementation calls [`read_residual`](Decode::read_residual)
+    /// repeatedly, but decoders using dynamic dispatch should override it so
+    /// to pay for a single indirect call per batch.
+    #[inline(always)]
+    fn read_residuals_into(&mut self, buf: &mut [u64]) -> usize {
+        for value in buf.iter_mut() {
+            *value = self.read_residual();
+        }
+        buf.len()
+    }
 }
 
 use impl_tools::autoimpl;
@@ -101,6 +114,13 @@ pub trait RandomAccessDecoderFactory {
 
     /// Creates a new reader starting at the given node.
     fn new_decoder(&self, node: usize) -> anyhow::Result<Self::Decoder<'_>>;
+
+    /// Returns the bit offset of the record of the given node in the graph
+    /// bitstream.
+    ///
+    /// `node` can be equal to the number of nodes, in which case the offset
+    /// is the length of the bitstream.
+    fn offset(&self, node: usize) -> u64;
 }
 
 /// A trait providing decoders on the whole graph.
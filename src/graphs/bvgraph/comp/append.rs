@@ -0,0 +1,401 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::prelude::*;
+use anyhow::{bail, ensure, Context, Result};
+use dsi_bitstream::prelude::*;
+use lender::prelude::*;
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom};
+use std::path::Path;
+use sux::prelude::*;
+
+type AppendWriter<E> = BufBitWriter<E, WordAdapter<usize, BufWriter<File>>>;
+
+/// Opens a bitstream for writing at the given bit position.
+///
+/// The writer starts at the byte containing `bit_pos`, and the bits of the
+/// byte preceding `bit_pos`, if any, are written again, so the returned
+/// writer continues the bitstream exactly at `bit_pos`. The file is not
+/// truncated, so if nothing is written the bitstream is left unchanged.
+fn open_append<E: Endianness>(path: &Path, bit_pos: u64) -> Result<AppendWriter<E>>
+where
+    AppendWriter<E>: BitWrite<E>,
+    <AppendWriter<E> as BitWrite<E>>::Error: std::error::Error + Send + Sync + 'static,
+{
+    let mut file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(path)
+        .with_context(|| format!("Could not open {} for appending", path.display()))?;
+    let byte_pos = bit_pos / 8;
+    let residual = (bit_pos % 8) as usize;
+    let mut byte = [0_u8];
+    if residual != 0 {
+        file.seek(SeekFrom::Start(byte_pos))?;
+        file.read_exact(&mut byte)
+            .with_context(|| format!("{} is shorter than {} bits", path.display(), bit_pos))?;
+    }
+    file.seek(SeekFrom::Start(byte_pos))?;
+
+    let mut writer = <BufBitWriter<E, _>>::new(<WordAdapter<usize, _>>::new(BufWriter::new(file)));
+    if residual != 0 {
+        // Big-endian bitstreams fill bytes from the most significant bit,
+        // little-endian ones from the least significant bit
+        let bits = if E::NAME == BE::NAME {
+            byte[0] as u64 >> (8 - residual)
+        } else {
+            byte[0] as u64 & ((1 << residual) - 1)
+        };
+        writer.write_bits(bits, residual)?;
+    }
+    Ok(writer)
+}
+
+/// Returns the number of recursive references needed to decode a node,
+/// memoizing the results.
+fn ref_count<F: RandomAccessDecoderFactory>(
+    graph: &BvGraph<F>,
+    node: usize,
+    cache: &mut HashMap<usize, usize>,
+) -> usize {
+    let mut chain = vec![];
+    let mut x = node;
+    let mut count = loop {
+        if let Some(&count) = cache.get(&x) {
+            break count;
+        }
+        let offset = graph.reference_offset(x);
+        if offset == 0 {
+            cache.insert(x, 0);
+            break 0;
+        }
+        chain.push(x);
+        x -= offset;
+    };
+    while let Some(x) = chain.pop() {
+        count += 1;
+        cache.insert(x, count);
+    }
+    count
+}
+
+/// A compressor appending nodes to an existing [`BvGraph`] without rewriting
+/// the successor lists of its nodes.
+///
+/// [`open`](BvCompAppend::open) positions the encoder at the end of the
+/// `.graph` bitstream of the graph; successor lists of new nodes are then
+/// appended using [`push`](BvCompAppend::push),
+/// [`extend`](BvCompAppend::extend) or
+/// [`extend_arcs`](BvCompAppend::extend_arcs), and compressed using as
+/// references also the successor lists of the last `compression_window`
+/// existing nodes. Finally, [`close`](BvCompAppend::close) extends the `.ef`
+/// file (and the `.offsets` file, if present) and updates the `.properties`
+/// file.
+///
+/// Given the same compression parameters, the resulting bitstream is
+/// identical to the one produced by [`BvComp::single_thread`] on the
+/// concatenation of the successor lists. Since successor lists of existing
+/// nodes cannot change, the new nodes can be the targets only of arcs
+/// starting from new nodes.
+///
+/// The `.ef` file must be available. Until [`close`](BvCompAppend::close)
+/// completes successfully, the graph is still readable as the original graph,
+/// as the new bits follow the end of its bitstream. A `.dcf` file, if present,
+/// is not updated, and must be rebuilt.
+pub struct BvCompAppend<E: Endianness>
+where
+    AppendWriter<E>: CodeWrite<E>,
+{
+    basename: Basename,
+    bvcomp: BvComp<DynCodesEncoder<E, AppendWriter<E>>>,
+    comp_flags: CompFlags,
+    /// The number of nodes of the original graph.
+    num_old_nodes: usize,
+    /// The number of arcs of the original graph.
+    num_old_arcs: u64,
+    /// The end of the bitstream of each new node.
+    offsets: Vec<u64>,
+    /// The bit position of the end of the bitstream.
+    bit_pos: u64,
+    /// The largest target of the new arcs, if any.
+    max_target: Option<usize>,
+}
+
+impl<E: Endianness> BvCompAppend<E>
+where
+    AppendWriter<E>: CodeWrite<E>,
+    <AppendWriter<E> as BitWrite<E>>::Error: std::error::Error + Send + Sync + 'static,
+    for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+{
+    /// Opens the graph with the given basename for appending.
+    ///
+    /// The compression parameters and codes are read from the `.properties`
+    /// file.
+    pub fn open(basename: impl AsRef<Path>) -> Result<Self> {
+        let basename = Basename::new(basename);
+        let properties_path = basename.with_ext(PROPERTIES_EXTENSION);
+        let (num_old_nodes, num_old_arcs, comp_flags) = parse_properties::<E>(&properties_path)?;
+
+        let ef_path = basename.with_ext(EF_EXTENSION);
+        ensure!(
+            ef_path.exists(),
+            "Missing Elias-Fano offsets {} (you can build them with `webgraph build ef`)",
+            ef_path.display()
+        );
+        let params = SelectParams::load(&basename, EF_SELECT_PROPERTY)?;
+        let bit_pos = {
+            let ef = Mmap::load_offsets(&ef_path, params, MemoryFlags::empty())?;
+            ensure!(
+                ef.len() == num_old_nodes + 1,
+                "{} contains {} offsets, but the graph has {} nodes",
+                ef_path.display(),
+                ef.len(),
+                num_old_nodes
+            );
+            ef.get(num_old_nodes) as u64
+        };
+
+        // Recover the state of the compressor after the last existing node
+        let first_node = num_old_nodes - comp_flags.compression_window.min(num_old_nodes);
+        let mut history = vec![];
+        {
+            let graph = BvGraph::with_basename(&basename).endianness::<E>().load()?;
+            let mut cache = HashMap::new();
+            for node in first_node..num_old_nodes {
+                history.push((
+                    graph.successors(node).collect::<Vec<_>>(),
+                    ref_count(&graph, node, &mut cache),
+                ));
+            }
+        }
+
+        let graph_path = basename.with_ext(GRAPH_EXTENSION);
+        let writer = open_append::<E>(&graph_path, bit_pos)?;
+        let mut bvcomp = BvComp::new(
+            DynCodesEncoder::new(writer, &comp_flags),
+            comp_flags.compression_window,
+            comp_flags.max_ref_count,
+            comp_flags.min_interval_length,
+            first_node,
+        )
+        .with_reference_selection(comp_flags.reference_selection);
+        for (successors, ref_count) in history {
+            bvcomp.push_history(successors, ref_count);
+        }
+
+        Ok(Self {
+            basename,
+            bvcomp,
+            comp_flags,
+            num_old_nodes,
+            num_old_arcs,
+            offsets: vec![],
+            bit_pos,
+            max_target: None,
+        })
+    }
+
+    /// Returns the current number of nodes, that is, the number of nodes of
+    /// the original graph plus the number of appended nodes.
+    pub fn num_nodes(&self) -> usize {
+        self.num_old_nodes + self.offsets.len()
+    }
+
+    /// Appends a new node with the given successors, which must be strictly
+    /// increasing, and returns the number of bits written.
+    pub fn push<I: IntoIterator<Item = usize>>(&mut self, succ_iter: I) -> Result<u64> {
+        let node = self.num_nodes();
+        let successors = succ_iter.into_iter().collect::<Vec<_>>();
+        if let Some(w) = successors.windows(2).find(|w| w[0] >= w[1]) {
+            bail!(
+                "The successors of node {} are not strictly increasing ({} is followed by {})",
+                node,
+                w[0],
+                w[1]
+            );
+        }
+        if let Some(&last) = successors.last() {
+            self.max_target = self.max_target.max(Some(last));
+        }
+        let written_bits = self
+            .bvcomp
+            .push(successors)
+            .with_context(|| format!("Could not compress node {}", node))?;
+        self.bit_pos += written_bits;
+        self.offsets.push(self.bit_pos);
+        Ok(written_bits)
+    }
+
+    /// Appends the successor lists returned by a lender, which must return
+    /// consecutive nodes starting from [`num_nodes`](BvCompAppend::num_nodes),
+    /// and returns the number of bits written.
+    pub fn extend<L>(&mut self, iter_nodes: L) -> Result<u64>
+    where
+        L: IntoLender,
+        L::Lender: for<'next> NodeLabelsLender<'next, Label = usize>,
+    {
+        let mut count = 0;
+        for_! ( (node, succ) in iter_nodes {
+            ensure!(
+                node == self.num_nodes(),
+                "Expected node {}, found node {}",
+                self.num_nodes(),
+                node
+            );
+            count += self.push(succ)?;
+        });
+        Ok(count)
+    }
+
+    /// Appends new nodes up to `end_node` (excluded), given their arcs sorted
+    /// by source and target, and returns the number of bits written.
+    ///
+    /// Nodes without arcs are appended with an empty successor list. Arcs
+    /// whose source is a node of the original graph are rejected, as the
+    /// successor lists of such nodes cannot be modified.
+    pub fn extend_arcs(
+        &mut self,
+        arcs: impl IntoIterator<Item = (usize, usize)>,
+        end_node: usize,
+    ) -> Result<u64> {
+        let mut count = 0;
+        let mut successors = vec![];
+        for (src, dst) in arcs {
+            ensure!(
+                src >= self.num_old_nodes,
+                "Cannot add arc ({}, {}): node {} belongs to the original graph, whose first {} nodes cannot be modified",
+                src,
+                dst,
+                src,
+                self.num_old_nodes
+            );
+            ensure!(
+                src >= self.num_nodes(),
+                "Cannot add arc ({}, {}): node {} has already been appended (arcs must be sorted by source)",
+                src,
+                dst,
+                src
+            );
+            ensure!(
+                src < end_node,
+                "Cannot add arc ({}, {}): the source is not smaller than the end node {}",
+                src,
+                dst,
+                end_node
+            );
+            // The pending successors are those of the next node to append
+            while self.num_nodes() < src {
+                count += self.push(successors.drain(..))?;
+            }
+            successors.push(dst);
+        }
+        while self.num_nodes() < end_node {
+            count += self.push(successors.drain(..))?;
+        }
+        Ok(count)
+    }
+
+    /// Completes the append, extending the `.ef` file (and the `.offsets`
+    /// file, if present) and updating the `.properties` file.
+    ///
+    /// Fails if some appended arc has a target that is not a node of the
+    /// resulting graph; in this case, the graph is left unchanged.
+    pub fn close(self) -> Result<()> {
+        let num_nodes = self.num_nodes();
+        if let Some(max_target) = self.max_target {
+            ensure!(
+                max_target < num_nodes,
+                "The appended arcs have target {}, but the graph has {} nodes",
+                max_target,
+                num_nodes
+            );
+        }
+        let num_arcs = self.num_old_arcs + self.bvcomp.arcs;
+        let padding = self.bvcomp.flush().context("Could not flush bvcomp")? as u64;
+        let basename = self.basename;
+
+        let graph_path = basename.with_ext(GRAPH_EXTENSION);
+        let file_len = 8 * graph_path
+            .metadata()
+            .with_context(|| format!("Could not stat {}", graph_path.display()))?
+            .len();
+
+        // Stream the old offsets and the new ones into a new Elias-Fano
+        // representation, computing on the way the length of the old
+        // .offsets file
+        let ef_path = basename.with_ext(EF_EXTENSION);
+        let params = SelectParams::load(&basename, EF_SELECT_PROPERTY)?;
+        let mut efb = EliasFanoBuilder::new(num_nodes + 1, file_len as usize);
+        let mut offsets_len = 0;
+        let mut prev = 0;
+        {
+            let old = Mmap::load_offsets(&ef_path, params, MemoryFlags::empty())?;
+            for offset in old.into_iter() {
+                offsets_len += len_gamma((offset - prev) as u64) as u64;
+                prev = offset;
+                efb.push(offset);
+            }
+        }
+        for &offset in &self.offsets {
+            efb.push(offset as usize);
+        }
+
+        let offsets_path = basename.with_ext(OFFSETS_EXTENSION);
+        if offsets_path.exists() {
+            // Offsets are always big endian
+            let mut writer = open_append::<BE>(&offsets_path, offsets_len)?;
+            let mut prev = prev as u64;
+            for &offset in &self.offsets {
+                writer
+                    .write_gamma(offset - prev)
+                    .context("Could not write delta")?;
+                prev = offset;
+            }
+            writer.flush().context("Could not flush offsets")?;
+        }
+
+        let mut ef_file = BufWriter::new(
+            File::create(&ef_path)
+                .with_context(|| format!("Could not create {}", ef_path.display()))?,
+        );
+        params
+            .serialize_ef(efb.build(), &mut ef_file)
+            .with_context(|| format!("Could not serialize Elias-Fano to {}", ef_path.display()))?;
+        drop(ef_file);
+
+        // Update the properties depending on the graph, keeping the others
+        let properties_path = basename.with_ext(PROPERTIES_EXTENSION);
+        let f = File::open(&properties_path)
+            .with_context(|| format!("Cannot open property file {}", properties_path.display()))?;
+        let mut map = java_properties::read(BufReader::new(f)).with_context(|| {
+            format!(
+                "cannot parse {} as a java properties file",
+                properties_path.display()
+            )
+        })?;
+        let properties = self
+            .comp_flags
+            .to_properties::<E>(num_nodes, num_arcs, self.bit_pos + padding)
+            .context("Could not serialize properties")?;
+        map.extend(java_properties::read(properties.as_bytes())?);
+        let f = File::create(&properties_path).with_context(|| {
+            format!("Cannot create property file {}", properties_path.display())
+        })?;
+        java_properties::write(BufWriter::new(f), &map)
+            .with_context(|| format!("Cannot write property file {}", properties_path.display()))?;
+
+        let dcf_path = basename.with_ext(DEG_CUMUL_EXTENSION);
+        if dcf_path.exists() {
+            log::warn!(
+                "{} has not been updated and must be rebuilt",
+                dcf_path.display()
+            );
+        }
+        Ok(())
+    }
+}
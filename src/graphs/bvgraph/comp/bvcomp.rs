@@ -33,6 +33,8 @@ pub struct BvComp<E> {
     max_ref_count: usize,
     /// The minimum length of sequences that will be compressed as a (start, len)
     min_interval_length: usize,
+    /// The strategy used to choose references
+    reference_selection: ReferenceSelection,
     /// The current node we are compressing
     curr_node: usize,
     /// The first node we are compressing, this is needed because during
@@ -40,6 +42,8 @@ pub struct BvComp<E> {
     start_node: usize,
     /// The number of arcs compressed so far
     pub arcs: u64,
+    /// The number of bits written so far for each component
+    pub stats: CompStats,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -83,22 +87,29 @@ impl Compressor {
     /// buffers which are initialized by calling `compress` so this has to be
     /// called only after `compress`.
     ///
-    /// This returns the number of bits written.
+    /// This returns the number of bits written, and adds the bits written for
+    /// each component to `stats`.
     fn write<E: Encode>(
         &self,
         writer: &mut E,
         curr_node: usize,
         reference_offset: Option<usize>,
         min_interval_length: usize,
+        stats: &mut CompStats,
     ) -> Result<u64, E::Error> {
         let mut written_bits: u64 = 0;
         written_bits += writer.start_node(curr_node)? as u64;
         // write the outdegree
+        let mut mark = written_bits;
         written_bits += writer.write_outdegree(self.outdegree as u64)? as u64;
+        stats.outdegree_bits += written_bits - mark;
+        mark = written_bits;
         // write the references
         if self.outdegree != 0 {
             if let Some(reference_offset) = reference_offset {
                 written_bits += writer.write_reference_offset(reference_offset as u64)? as u64;
+                stats.reference_bits += written_bits - mark;
+                mark = written_bits;
                 if reference_offset != 0 {
                     written_bits += writer.write_block_count(self.blocks.len() as _)? as u64;
                     if !self.blocks.is_empty() {
@@ -109,6 +120,8 @@ impl Compressor {
                 }
             }
         }
+        stats.block_bits += written_bits - mark;
+        mark = written_bits;
         // write the intervals
         if !self.extra_nodes.is_empty() && min_interval_length != Self::NO_INTERVALS {
             written_bits += writer.write_interval_count(self.left_interval.len() as _)? as u64;
@@ -133,6 +146,8 @@ impl Compressor {
                 }
             }
         }
+        stats.interval_bits += written_bits - mark;
+        mark = written_bits;
         // write the residuals
         if !self.residuals.is_empty() {
             written_bits += writer
@@ -145,6 +160,7 @@ impl Compressor {
                     as u64;
             }
         }
+        stats.residual_bits += written_bits - mark;
 
         written_bits += writer.end_node(curr_node)? as u64;
         Ok(written_bits)
@@ -322,15 +338,24 @@ impl<E: EncodeAndEstimate> BvComp<E> {
             min_interval_length,
             compression_window,
             max_ref_count,
+            reference_selection: ReferenceSelection::default(),
             start_node,
             curr_node: start_node,
             compressors: (0..compression_window + 1)
                 .map(|_| Compressor::new())
                 .collect(),
             arcs: 0,
+            stats: CompStats::default(),
         }
     }
 
+    /// Sets the [strategy](ReferenceSelection) used to choose references
+    /// (by default, [`ReferenceSelection::BestOfWindow`]).
+    pub fn with_reference_selection(mut self, reference_selection: ReferenceSelection) -> Self {
+        self.reference_selection = reference_selection;
+        self
+    }
+
     /// Push a new node to the compressor.
     /// The iterator must yield the successors of the node and the nodes HAVE
     /// TO BE CONTIGUOUS (i.e. if a node has no neighbours you have to pass an
@@ -346,19 +371,32 @@ impl<E: EncodeAndEstimate> BvComp<E> {
         }
         // get the ref
         let curr_list = &self.backrefs[self.curr_node];
+        // unsorted lists would be silently corrupted by gap coding
+        anyhow::ensure!(
+            curr_list.windows(2).all(|w| w[0] < w[1]),
+            "The successors of node {} are not strictly increasing (see SequentialLabeling::successors_sorted and SortedSuccessorsGraph)",
+            self.curr_node
+        );
         self.arcs += curr_list.len() as u64;
+        self.stats.num_nodes += 1;
+        self.stats.num_arcs += curr_list.len() as u64;
         // first try to compress the current node without references
         let compressor = &mut self.compressors[0];
         // Compute how we would compress this
         compressor.compress(curr_list, None, self.min_interval_length)?;
         // avoid the mock writing
-        if self.compression_window == 0 {
+        if self.compression_window == 0 || self.reference_selection == ReferenceSelection::None {
             let written_bits = compressor.write(
                 &mut self.encoder,
                 self.curr_node,
-                None,
+                // with a nonzero window, the decoder expects a reference
+                (self.compression_window != 0).then_some(0),
                 self.min_interval_length,
+                &mut self.stats,
             )?;
+            if self.compression_window != 0 {
+                self.ref_counts[self.curr_node] = 0;
+            }
             // update the current node
             self.curr_node += 1;
             return Ok(written_bits);
@@ -373,6 +411,7 @@ impl<E: EncodeAndEstimate> BvComp<E> {
                 self.curr_node,
                 Some(0),
                 self.min_interval_length,
+                &mut CompStats::default(),
             )?
         };
 
@@ -407,6 +446,7 @@ impl<E: EncodeAndEstimate> BvComp<E> {
                     self.curr_node,
                     Some(delta),
                     self.min_interval_length,
+                    &mut CompStats::default(),
                 )?
             };
             // keep track of the best, it's strictly less so we keep the
@@ -415,6 +455,8 @@ impl<E: EncodeAndEstimate> BvComp<E> {
                 min_bits = bits;
                 ref_delta = delta;
                 ref_count = count + 1;
+            } else if self.reference_selection == ReferenceSelection::Greedy {
+                break;
             }
         }
         // write the best result reusing the precomputed compression
@@ -424,6 +466,7 @@ impl<E: EncodeAndEstimate> BvComp<E> {
             self.curr_node,
             Some(ref_delta),
             self.min_interval_length,
+            &mut self.stats,
         )?;
         self.ref_counts[self.curr_node] = ref_count;
         // consistency check
@@ -433,6 +476,31 @@ impl<E: EncodeAndEstimate> BvComp<E> {
         Ok(written_bits)
     }
 
+    /// Records the successors of the current node, which has already been
+    /// compressed with the given reference count, without writing anything.
+    ///
+    /// This method makes it possible to resume compression after the end of
+    /// an existing bitstream: the compressor must be created with a start
+    /// node preceding the first node to compress by at most the compression
+    /// window, and the successor lists of the nodes in between must be
+    /// provided by this method, so that they can be used as references.
+    pub(crate) fn push_history(&mut self, successors: Vec<usize>, ref_count: usize) {
+        self.backrefs.replace(self.curr_node, successors);
+        self.ref_counts[self.curr_node] = ref_count;
+        self.curr_node += 1;
+    }
+
+    /// Returns the number of recursive references needed to decode one of
+    /// the last `compression_window` nodes pushed.
+    pub(crate) fn ref_count(&self, node: usize) -> usize {
+        self.ref_counts[node]
+    }
+
+    /// Returns a mutable reference to the underlying encoder.
+    pub(crate) fn encoder_mut(&mut self) -> &mut E {
+        &mut self.encoder
+    }
+
     /// Given an iterator over the nodes successors iterators, push them all.
     /// The iterator must yield the successors of the node and the nodes HAVE
     /// TO BE CONTIGUOUS (i.e. if a node has no neighbours you have to pass an
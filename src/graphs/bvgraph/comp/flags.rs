@@ -5,7 +5,7 @@
  * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
  */
 
-use anyhow::{bail, ensure, Result};
+use anyhow::{bail, ensure, Context, Result};
 use dsi_bitstream::traits::{BigEndian, Endianness, LittleEndian};
 use std::collections::HashMap;
 
@@ -18,6 +18,55 @@ pub enum Code {
     Zeta { k: usize },
 }
 
+/// The strategy used by [`BvComp`](super::BvComp) to choose the reference of
+/// each successor list among the previous nodes in the compression window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub enum ReferenceSelection {
+    /// Candidates are examined from the nearest one, and the search stops at
+    /// the first candidate whose estimated cost is not smaller than that of
+    /// the best candidate so far. This is faster, but it might miss better
+    /// references farther away.
+    Greedy,
+    /// The exact cost of encoding the list using each candidate in the
+    /// window is estimated, and the cheapest one is chosen (the nearest one
+    /// in case of ties).
+    #[default]
+    BestOfWindow,
+    /// References are not used; if the compression window is not zero, a
+    /// zero reference offset is written for each node, so that the result
+    /// can be decoded with the same window.
+    None,
+}
+
+impl ReferenceSelection {
+    /// Returns the name of the strategy, as stored in the `.properties` file.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReferenceSelection::Greedy => "greedy",
+            ReferenceSelection::BestOfWindow => "best-of-window",
+            ReferenceSelection::None => "none",
+        }
+    }
+
+    /// Parses the name of a strategy, as returned by
+    /// [`as_str`](ReferenceSelection::as_str).
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "greedy" => Some(ReferenceSelection::Greedy),
+            "best-of-window" => Some(ReferenceSelection::BestOfWindow),
+            "none" => Some(ReferenceSelection::None),
+            _ => None,
+        }
+    }
+}
+
+impl core::fmt::Display for ReferenceSelection {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 #[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
 /// The compression flags for reading or compressing a graph.
@@ -45,6 +94,9 @@ pub struct CompFlags {
     /// The maximum recursion depth during decoding, this modulates the tradeoff
     /// between compression ratio and decoding speed
     pub max_ref_count: usize,
+    /// The strategy used to choose references; it does not affect decoding,
+    /// and it is stored in the `.properties` file just for provenance
+    pub reference_selection: ReferenceSelection,
 }
 
 impl core::default::Default for CompFlags {
@@ -58,6 +110,7 @@ impl core::default::Default for CompFlags {
             min_interval_length: 4,
             compression_window: 7,
             max_ref_count: 3,
+            reference_selection: ReferenceSelection::default(),
         }
     }
 }
@@ -108,6 +161,10 @@ impl CompFlags {
         s.push_str(&format!("minintervallength={}\n", self.min_interval_length));
         s.push_str(&format!("maxrefcount={}\n", self.max_ref_count));
         s.push_str(&format!("windowsize={}\n", self.compression_window));
+        s.push_str(&format!(
+            "referenceselection={}\n",
+            self.reference_selection
+        ));
         s.push_str(&format!(
             "bitsperlink={}\n",
             bitstream_len as f64 / num_arcs as f64
@@ -258,6 +315,13 @@ impl CompFlags {
         if let Some(min_interval_length) = map.get("minintervallength") {
             cf.min_interval_length = min_interval_length.parse()?;
         }
+        if let Some(max_ref_count) = map.get("maxrefcount") {
+            cf.max_ref_count = max_ref_count.parse()?;
+        }
+        if let Some(reference_selection) = map.get("referenceselection") {
+            cf.reference_selection = ReferenceSelection::from_name(reference_selection)
+                .with_context(|| format!("Unknown reference selection {}", reference_selection))?;
+        }
         Ok(cf)
     }
 }
@@ -6,6 +6,7 @@
  */
 
 use crate::prelude::*;
+use crate::utils::interrupt::{check_interrupted, is_interrupted};
 use anyhow::{ensure, Context, Result};
 use dsi_bitstream::prelude::*;
 use dsi_progress_logger::prelude::*;
@@ -15,6 +16,7 @@ use rayon::ThreadPool;
 use std::fs::File;
 use std::io::{BufReader, BufWriter};
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
 /// A queue that pulls jobs with ids in a contiguous initial segment of the
 /// natural numbers from an iterator out of order and implement an iterator in
@@ -63,6 +65,14 @@ where
                 }
                 self.jobs[id] = Some(item);
             } else {
+                // All jobs have arrived: ids that never did belong to empty
+                // lenders, and are skipped
+                while self.next_id < self.jobs.len() {
+                    self.next_id += 1;
+                    if let Some(item) = self.jobs[self.next_id - 1].take() {
+                        return Some(item);
+                    }
+                }
                 return None;
             }
         }
@@ -79,7 +89,9 @@ struct Job {
     written_bits: u64,
     chunk_offsets_path: PathBuf,
     offsets_written_bits: u64,
+    node_bits: Vec<u64>,
     num_arcs: u64,
+    stats: CompStats,
 }
 
 impl JobId for Job {
@@ -104,8 +116,8 @@ impl BvComp<()> {
         L::Lender: for<'next> NodeLabelsLender<'next, Label = usize>,
         BufBitWriter<E, WordAdapter<usize, BufWriter<File>>>: CodeWrite<E>,
     {
-        let basename = basename.as_ref();
-        let graph_path = basename.with_extension(GRAPH_EXTENSION);
+        let basename = Basename::new(basename);
+        let graph_path = basename.with_ext(GRAPH_EXTENSION);
 
         // Compress the graph
         let bit_write = <BufBitWriter<E, _>>::new(<WordAdapter<usize, _>>::new(BufWriter::new(
@@ -125,7 +137,8 @@ impl BvComp<()> {
             compression_flags.max_ref_count,
             compression_flags.min_interval_length,
             0,
-        );
+        )
+        .with_reference_selection(compression_flags.reference_selection);
 
         let mut pl = ProgressLogger::default();
         pl.display_memory(true)
@@ -136,7 +149,7 @@ impl BvComp<()> {
 
         let mut real_num_nodes = 0;
         if build_offsets {
-            let offsets_path = basename.with_extension(OFFSETS_EXTENSION);
+            let offsets_path = basename.with_ext(OFFSETS_EXTENSION);
             let file = std::fs::File::create(&offsets_path)
                 .with_context(|| format!("Could not create {}", offsets_path.display()))?;
             // create a bit writer on the file
@@ -148,6 +161,7 @@ impl BvComp<()> {
                 .write_gamma(0)
                 .context("Could not write initial delta")?;
             for_! ( (_node_id, successors) in iter {
+                check_interrupted()?;
                 let delta = bvcomp.push(successors).context("Could not push successors")?;
                 bitstream_len += delta;
                 writer.write_gamma(delta).context("Could not write delta")?;
@@ -156,6 +170,7 @@ impl BvComp<()> {
             });
         } else {
             for_! ( (_node_id, successors) in iter {
+                check_interrupted()?;
                 bitstream_len += bvcomp.push(successors).context("Could not push successors")?;
                 pl.update();
                 real_num_nodes += 1;
@@ -179,7 +194,7 @@ impl BvComp<()> {
         let properties = compression_flags
             .to_properties::<BE>(real_num_nodes, num_arcs, bitstream_len)
             .context("Could not serialize properties")?;
-        let properties_path = basename.with_extension(PROPERTIES_EXTENSION);
+        let properties_path = basename.with_ext(PROPERTIES_EXTENSION);
         std::fs::write(&properties_path, properties)
             .with_context(|| format!("Could not write {}", properties_path.display()))?;
 
@@ -276,15 +291,110 @@ impl BvComp<()> {
         threads: &ThreadPool,
         tmp_dir: impl AsRef<Path>,
     ) -> Result<u64>
+    where
+        BufBitWriter<E, WordAdapter<usize, BufWriter<std::fs::File>>>: CodeWrite<E>,
+        BufBitReader<E, WordAdapter<u32, BufReader<std::fs::File>>>: BitRead<E>,
+    {
+        Self::parallel_iter_with_stats::<E, L>(
+            basename,
+            iter,
+            num_nodes,
+            compression_flags,
+            threads,
+            tmp_dir,
+        )
+        .map(|stats| stats.total_bits())
+    }
+
+    /// Compresses multiple [`NodeLabelsLender`] in parallel as
+    /// [`parallel_iter`](Self::parallel_iter), but returns the [compression
+    /// statistics](CompStats) of the whole graph.
+    pub fn parallel_iter_with_stats<
+        E: Endianness,
+        L: Lender + for<'next> NodeLabelsLender<'next, Label = usize> + Send,
+    >(
+        basename: impl AsRef<Path> + Send + Sync,
+        iter: impl Iterator<Item = L>,
+        num_nodes: usize,
+        compression_flags: CompFlags,
+        threads: &ThreadPool,
+        tmp_dir: impl AsRef<Path>,
+    ) -> Result<CompStats>
+    where
+        BufBitWriter<E, WordAdapter<usize, BufWriter<std::fs::File>>>: CodeWrite<E>,
+        BufBitReader<E, WordAdapter<u32, BufReader<std::fs::File>>>: BitRead<E>,
+    {
+        Self::parallel_iter_impl::<E, L>(
+            basename,
+            iter,
+            num_nodes,
+            compression_flags,
+            threads,
+            tmp_dir,
+            false,
+        )
+        .map(|(stats, _)| stats)
+    }
+
+    /// Compresses multiple [`NodeLabelsLender`] in parallel as
+    /// [`parallel_iter_with_stats`](Self::parallel_iter_with_stats), but
+    /// returns also the length in bits of the successor list of each node,
+    /// that is, the gaps between consecutive offsets written in the
+    /// `.offsets` file.
+    ///
+    /// The lengths are collected by the compression threads, using one word
+    /// of memory per node.
+    pub fn parallel_iter_with_node_bits<
+        E: Endianness,
+        L: Lender + for<'next> NodeLabelsLender<'next, Label = usize> + Send,
+    >(
+        basename: impl AsRef<Path> + Send + Sync,
+        iter: impl Iterator<Item = L>,
+        num_nodes: usize,
+        compression_flags: CompFlags,
+        threads: &ThreadPool,
+        tmp_dir: impl AsRef<Path>,
+    ) -> Result<(CompStats, Vec<u64>)>
+    where
+        BufBitWriter<E, WordAdapter<usize, BufWriter<std::fs::File>>>: CodeWrite<E>,
+        BufBitReader<E, WordAdapter<u32, BufReader<std::fs::File>>>: BitRead<E>,
+    {
+        Self::parallel_iter_impl::<E, L>(
+            basename,
+            iter,
+            num_nodes,
+            compression_flags,
+            threads,
+            tmp_dir,
+            true,
+        )
+    }
+
+    /// Compresses multiple [`NodeLabelsLender`] in parallel, returning the
+    /// compression statistics and, if `record_node_bits` is true, the length
+    /// in bits of the successor list of each node (otherwise, an empty
+    /// vector).
+    fn parallel_iter_impl<
+        E: Endianness,
+        L: Lender + for<'next> NodeLabelsLender<'next, Label = usize> + Send,
+    >(
+        basename: impl AsRef<Path> + Send + Sync,
+        iter: impl Iterator<Item = L>,
+        num_nodes: usize,
+        compression_flags: CompFlags,
+        threads: &ThreadPool,
+        tmp_dir: impl AsRef<Path>,
+        record_node_bits: bool,
+    ) -> Result<(CompStats, Vec<u64>)>
     where
         BufBitWriter<E, WordAdapter<usize, BufWriter<std::fs::File>>>: CodeWrite<E>,
         BufBitReader<E, WordAdapter<u32, BufReader<std::fs::File>>>: BitRead<E>,
     {
         let tmp_dir = tmp_dir.as_ref();
-        let basename = basename.as_ref();
+        let basename = Basename::new(basename);
 
-        let graph_path = basename.with_extension(GRAPH_EXTENSION);
-        let offsets_path = basename.with_extension(OFFSETS_EXTENSION);
+        let graph_path = basename.with_ext(GRAPH_EXTENSION);
+        let offsets_path = basename.with_ext(OFFSETS_EXTENSION);
 
         let (tx, rx) = std::sync::mpsc::channel();
 
@@ -306,6 +416,7 @@ impl BvComp<()> {
                     let mut offsets_writer;
                     let mut written_bits;
                     let mut offsets_written_bits;
+                    let mut node_bits = Vec::new();
 
                     match thread_lender.next() {
                         None => return,
@@ -327,21 +438,33 @@ impl BvComp<()> {
                                 cp_flags.max_ref_count,
                                 cp_flags.min_interval_length,
                                 node_id,
-                            );
+                            )
+                            .with_reference_selection(cp_flags.reference_selection);
                             written_bits = bvcomp.push(successors).unwrap();
                             offsets_written_bits = offsets_writer.write_gamma(written_bits).unwrap() as u64;
+                            if record_node_bits {
+                                node_bits.push(written_bits);
+                            }
                         }
                     };
 
                     let mut last_node = first_node;
                     let iter_nodes = thread_lender.inspect(|(x, _)| last_node = *x);
                     for_! ( (_, succ) in iter_nodes {
-                        let node_bits = bvcomp.push(succ.into_iter()).unwrap();
-                        written_bits += node_bits;
-                        offsets_written_bits += offsets_writer.write_gamma(node_bits).unwrap() as u64;
+                        // The error is returned by the merging thread
+                        if is_interrupted() {
+                            break;
+                        }
+                        let bits = bvcomp.push(succ.into_iter()).unwrap();
+                        written_bits += bits;
+                        offsets_written_bits += offsets_writer.write_gamma(bits).unwrap() as u64;
+                        if record_node_bits {
+                            node_bits.push(bits);
+                        }
                     });
 
                     let num_arcs = bvcomp.arcs;
+                    let stats = bvcomp.stats;
                     bvcomp.flush().unwrap();
                     offsets_writer.flush().unwrap();
 
@@ -359,7 +482,9 @@ impl BvComp<()> {
                         written_bits,
                         chunk_offsets_path,
                         offsets_written_bits,
+                        node_bits,
                         num_arcs,
+                        stats,
                     })
                     .unwrap()
                 });
@@ -381,6 +506,8 @@ impl BvComp<()> {
             let mut total_written_bits: u64 = 0;
             let mut total_offsets_written_bits: u64 = 0;
             let mut total_arcs: u64 = 0;
+            let mut total_stats = CompStats::default();
+            let mut total_node_bits = Vec::with_capacity(if record_node_bits { num_nodes } else { 0 });
 
             let mut next_node = 0;
             // glue together the bitstreams as they finish, this allows us to do
@@ -393,9 +520,12 @@ impl BvComp<()> {
                 written_bits,
                 chunk_offsets_path,
                 offsets_written_bits,
+                node_bits,
                 num_arcs,
+                stats,
             } in TaskQueue::new(rx.iter())
             {
+                check_interrupted()?;
                 ensure!(
                     first_node == next_node,
                     "Non-adjacent lenders: lender {} has first node {} instead of {}",
@@ -406,6 +536,8 @@ impl BvComp<()> {
 
                 next_node = last_node + 1;
                 total_arcs += num_arcs;
+                total_stats += stats;
+                total_node_bits.extend(node_bits);
                 log::info!(
                     "Copying {} [{}..{}) bits from {} to {}",
                     written_bits,
@@ -457,6 +589,7 @@ impl BvComp<()> {
                     })?;
             }
 
+            check_interrupted()?;
             log::info!("Flushing the merged bitstreams");
             graph_writer.flush()?;
             offsets_writer.flush()?;
@@ -465,7 +598,7 @@ impl BvComp<()> {
             let properties = compression_flags
                 .to_properties::<BE>(num_nodes, total_arcs, total_written_bits)
                 .context("Could not serialize properties")?;
-            let properties_path = basename.with_extension(PROPERTIES_EXTENSION);
+            let properties_path = basename.with_ext(PROPERTIES_EXTENSION);
             std::fs::write(&properties_path, properties).with_context(|| {
                 format!(
                     "Could not write properties to {}",
@@ -489,7 +622,165 @@ impl BvComp<()> {
             std::fs::remove_dir_all(tmp_dir).with_context(|| {
                 format!("Could not clean temporary directory {}", tmp_dir.display())
             })?;
-            Ok(total_written_bits)
+            debug_assert_eq!(total_stats.total_bits(), total_written_bits);
+            Ok((total_stats, total_node_bits))
         })
     }
 }
+
+/// The number of nodes compressed by a thread of [`compress`] between two
+/// updates of the progress logger.
+const PL_BATCH_SIZE: usize = 1 << 12;
+
+/// Compresses in parallel any splittable sequential graph, returning the
+/// length in bits of the graph bitstream.
+///
+/// This is the library entry point for the code path used by the `to bvgraph`
+/// command: `graph` can be a [`BvGraph`], a [`BvGraphSeq`], an in-memory graph
+/// such as a [`VecGraph`], or the result of a transformation. The graph is
+/// split in as many parts as the threads of `thread_pool`, the parts are
+/// compressed in parallel in a temporary directory, and then they are
+/// concatenated into the `.graph`, `.offsets`, and `.properties` files with the
+/// given basename.
+///
+/// The progress logger is used to log the compression as a single activity
+/// on the nodes of the graph; each thread updates it every few thousand
+/// nodes.
+pub fn compress<E: Endianness, G: SequentialGraph + SplitLabeling>(
+    graph: &G,
+    basename: impl AsRef<Path> + Send + Sync,
+    compression_flags: CompFlags,
+    thread_pool: &ThreadPool,
+    pl: &mut (impl ProgressLog + Send),
+) -> Result<u64>
+where
+    BufBitWriter<E, WordAdapter<usize, BufWriter<File>>>: CodeWrite<E>,
+    BufBitReader<E, WordAdapter<u32, BufReader<File>>>: BitRead<E>,
+{
+    compress_with_stats::<E, G>(graph, basename, compression_flags, thread_pool, pl)
+        .map(|stats| stats.total_bits())
+}
+
+/// Compresses in parallel any splittable sequential graph as [`compress`],
+/// but returns the [compression statistics](CompStats) of the graph, which
+/// can be stored in a `.stats` file using [`CompStats::store`].
+pub fn compress_with_stats<E: Endianness, G: SequentialGraph + SplitLabeling>(
+    graph: &G,
+    basename: impl AsRef<Path> + Send + Sync,
+    compression_flags: CompFlags,
+    thread_pool: &ThreadPool,
+    pl: &mut (impl ProgressLog + Send),
+) -> Result<CompStats>
+where
+    BufBitWriter<E, WordAdapter<usize, BufWriter<File>>>: CodeWrite<E>,
+    BufBitReader<E, WordAdapter<u32, BufReader<File>>>: BitRead<E>,
+{
+    compress_impl::<E, G>(graph, basename, compression_flags, thread_pool, pl, false)
+        .map(|(stats, _)| stats)
+}
+
+/// Compresses in parallel any splittable sequential graph as
+/// [`compress_with_stats`], but returns also the length in bits of the
+/// successor list of each node, that is, the gaps between consecutive offsets
+/// written in the `.offsets` file.
+///
+/// The lengths can be used to build the Elias–Fano representation of the
+/// offsets without reading the `.offsets` file again; they are collected by
+/// the compression threads, using one word of memory per node.
+pub fn compress_with_node_bits<E: Endianness, G: SequentialGraph + SplitLabeling>(
+    graph: &G,
+    basename: impl AsRef<Path> + Send + Sync,
+    compression_flags: CompFlags,
+    thread_pool: &ThreadPool,
+    pl: &mut (impl ProgressLog + Send),
+) -> Result<(CompStats, Vec<u64>)>
+where
+    BufBitWriter<E, WordAdapter<usize, BufWriter<File>>>: CodeWrite<E>,
+    BufBitReader<E, WordAdapter<u32, BufReader<File>>>: BitRead<E>,
+{
+    compress_impl::<E, G>(graph, basename, compression_flags, thread_pool, pl, true)
+}
+
+fn compress_impl<E: Endianness, G: SequentialGraph + SplitLabeling>(
+    graph: &G,
+    basename: impl AsRef<Path> + Send + Sync,
+    compression_flags: CompFlags,
+    thread_pool: &ThreadPool,
+    pl: &mut (impl ProgressLog + Send),
+    record_node_bits: bool,
+) -> Result<(CompStats, Vec<u64>)>
+where
+    BufBitWriter<E, WordAdapter<usize, BufWriter<File>>>: CodeWrite<E>,
+    BufBitReader<E, WordAdapter<u32, BufReader<File>>>: BitRead<E>,
+{
+    let tmp_dir = tempfile::Builder::new()
+        .prefix("compress_")
+        .tempdir()
+        .context("Could not create temporary directory")?;
+    let num_nodes = graph.num_nodes();
+
+    pl.item_name("node").expected_updates(Some(num_nodes));
+    pl.start(format!(
+        "Compressing the graph with {} threads...",
+        thread_pool.current_num_threads()
+    ));
+    // The logger, and the number of nodes reported to it
+    let locked_pl = Mutex::new((&mut *pl, 0));
+    let locked_pl_ref = &locked_pl;
+    let result = BvComp::parallel_iter_impl::<E, _>(
+        basename,
+        graph
+            .split_iter(thread_pool.current_num_threads())
+            .into_iter()
+            .map(|lender| {
+                let mut count = 0;
+                lender.inspect(move |_| {
+                    count += 1;
+                    if count == PL_BATCH_SIZE {
+                        let (pl, reported) = &mut *locked_pl_ref.lock().unwrap();
+                        pl.update_with_count(count);
+                        *reported += count;
+                        count = 0;
+                    }
+                })
+            }),
+        num_nodes,
+        compression_flags,
+        thread_pool,
+        tmp_dir.path(),
+        record_node_bits,
+    )?;
+    // Report the nodes of the last, partial batches
+    let (pl, reported) = locked_pl.into_inner().unwrap();
+    pl.update_with_count(num_nodes - reported);
+    pl.done();
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Item(usize);
+
+    impl JobId for Item {
+        fn id(&self) -> usize {
+            self.0
+        }
+    }
+
+    #[test]
+    fn test_task_queue() {
+        let ids = |jobs: &[usize]| {
+            TaskQueue::new(jobs.iter().copied().map(Item))
+                .map(|item| item.0)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(ids(&[3, 0, 2, 1]), vec![0, 1, 2, 3]);
+        // Jobs of empty lenders are never sent
+        assert_eq!(ids(&[3, 0, 2]), vec![0, 2, 3]);
+        assert_eq!(ids(&[2]), vec![2]);
+        assert!(ids(&[]).is_empty());
+    }
+}
@@ -9,6 +9,16 @@ mod bvcomp;
 pub use bvcomp::*;
 
 mod impls;
+pub use impls::{compress, compress_with_node_bits, compress_with_stats};
 
 mod flags;
 pub use flags::*;
+
+mod append;
+pub use append::*;
+
+mod reuse;
+pub use reuse::*;
+
+mod stats;
+pub use stats::*;
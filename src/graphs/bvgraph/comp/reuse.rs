@@ -0,0 +1,227 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::prelude::*;
+use anyhow::{ensure, Context, Result};
+use dsi_bitstream::prelude::*;
+use dsi_progress_logger::prelude::*;
+use lender::prelude::*;
+use mmap_rs::MmapFlags;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+use sux::prelude::*;
+
+/// Statistics about a [recompression](BvComp::reuse_old) reusing the
+/// bitstream of an old version of a graph.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReuseStats {
+    /// The number of nodes of the new graph.
+    pub num_nodes: usize,
+    /// The number of arcs of the new graph.
+    pub num_arcs: u64,
+    /// The number of nodes whose bits have been copied from the old bitstream.
+    pub copied_nodes: usize,
+    /// The length in bits of the new bitstream.
+    pub bits: u64,
+    /// The number of bits copied from the old bitstream.
+    pub copied_bits: u64,
+}
+
+impl BvComp<()> {
+    /// Compresses a new version of a graph reusing the bitstream of an old
+    /// version, and returns statistics about the reuse.
+    ///
+    /// `changed` must have a bit set for each node of the new graph whose
+    /// successor list is different from that of the same node in the old
+    /// graph; nodes beyond the length of `changed` or the number of nodes of
+    /// the old graph are considered changed. Before copying the bits of a
+    /// node, its successors are checked against those in the old graph, and
+    /// an error is returned if a changed node is not marked.
+    ///
+    /// The encoding of a node depends only on its successor list and on the
+    /// successor lists and reference counts of the previous
+    /// `compression_window` nodes. Thus, the bits of an unchanged node can be
+    /// copied verbatim from the old bitstream if no node in its window is
+    /// changed or has been re-encoded with a different reference count; all
+    /// other nodes are re-encoded. The compression parameters and codes are
+    /// those of the old graph, and the `.ef` file of the old graph must be
+    /// available.
+    ///
+    /// The `.graph`, `.offsets` and `.properties` files of the new graph are
+    /// written. The result is always a correct compression of the new graph;
+    /// if the old graph has been compressed by
+    /// [`single_thread`](BvComp::single_thread), the result is identical to
+    /// the one [`single_thread`](BvComp::single_thread) would produce on the
+    /// new graph.
+    pub fn reuse_old<E, L, B>(
+        old_basename: impl AsRef<Path>,
+        basename: impl AsRef<Path>,
+        iter: L,
+        changed: &BitVec<B>,
+    ) -> Result<ReuseStats>
+    where
+        E: Endianness,
+        L: IntoLender,
+        L::Lender: for<'next> NodeLabelsLender<'next, Label = usize>,
+        B: AsRef<[usize]>,
+        BufBitWriter<E, WordAdapter<usize, BufWriter<File>>>: CodeWrite<E>,
+        for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+    {
+        let old_basename = Basename::new(old_basename);
+        let basename = Basename::new(basename);
+        let (num_old_nodes, _, comp_flags) =
+            parse_properties::<E>(old_basename.with_ext(PROPERTIES_EXTENSION))?;
+
+        let ef_path = old_basename.with_ext(EF_EXTENSION);
+        ensure!(
+            ef_path.exists(),
+            "Missing Elias-Fano offsets {} (you can build them with `webgraph build ef`)",
+            ef_path.display()
+        );
+        let params = SelectParams::load(&old_basename, EF_SELECT_PROPERTY)?;
+        let old_offsets = Mmap::load_offsets(&ef_path, params, MemoryFlags::empty())?;
+        let old_graph = BvGraph::with_basename(&old_basename)
+            .endianness::<E>()
+            .load()?;
+        let old_graph_path = old_basename.with_ext(GRAPH_EXTENSION);
+        let old_bitstream = MmapHelper::<u32>::mmap(&old_graph_path, MmapFlags::SEQUENTIAL)
+            .with_context(|| format!("Could not mmap {}", old_graph_path.display()))?;
+        let mut old_reader = <BufBitReader<E, _>>::new(MemWordReader::new(old_bitstream.as_ref()));
+
+        let graph_path = basename.with_ext(GRAPH_EXTENSION);
+        let bit_write = <BufBitWriter<E, _>>::new(<WordAdapter<usize, _>>::new(BufWriter::new(
+            File::create(&graph_path)
+                .with_context(|| format!("Could not create {}", graph_path.display()))?,
+        )));
+        let mut bvcomp = BvComp::new(
+            DynCodesEncoder::new(bit_write, &comp_flags),
+            comp_flags.compression_window,
+            comp_flags.max_ref_count,
+            comp_flags.min_interval_length,
+            0,
+        )
+        .with_reference_selection(comp_flags.reference_selection);
+
+        // Offsets are always big endian
+        let offsets_path = basename.with_ext(OFFSETS_EXTENSION);
+        let mut offsets =
+            <BufBitWriter<BE, _>>::new(<WordAdapter<usize, _>>::new(BufWriter::with_capacity(
+                1 << 20,
+                File::create(&offsets_path)
+                    .with_context(|| format!("Could not create {}", offsets_path.display()))?,
+            )));
+        offsets
+            .write_gamma(0)
+            .context("Could not write initial delta")?;
+
+        let window = comp_flags.compression_window;
+        // The reference counts in the old graph of the last nodes
+        let mut old_ref_counts = vec![0; window + 1];
+        // The last node that is changed, or that has been re-encoded with a
+        // reference count different from the old one
+        let mut last_dirty: Option<usize> = None;
+        let mut copied_arcs = 0;
+        let mut stats = ReuseStats::default();
+
+        let mut pl = ProgressLogger::default();
+        pl.display_memory(true)
+            .item_name("node")
+            .expected_updates(Some(num_old_nodes));
+        pl.start("Compressing successors reusing the old bitstream...");
+
+        for_! ( (node, successors) in iter {
+            ensure!(
+                node == stats.num_nodes,
+                "Expected node {}, found node {}",
+                stats.num_nodes,
+                node
+            );
+            let unchanged = node < num_old_nodes && node < changed.len() && !changed.get(node);
+            let old_ref_count = if node < num_old_nodes {
+                let offset = old_graph.reference_offset(node);
+                let count = if offset == 0 {
+                    0
+                } else {
+                    old_ref_counts[(node - offset) % (window + 1)] + 1
+                };
+                old_ref_counts[node % (window + 1)] = count;
+                count
+            } else {
+                0
+            };
+
+            let clean_window = !matches!(last_dirty, Some(dirty) if node - dirty <= window);
+            let bits = if unchanged && clean_window {
+                let successors = successors.into_iter().collect::<Vec<_>>();
+                ensure!(
+                    successors.iter().copied().eq(old_graph.successors(node)),
+                    "Node {} is not marked as changed, but its successors are different",
+                    node
+                );
+                copied_arcs += successors.len() as u64;
+                bvcomp.push_history(successors, old_ref_count);
+
+                let start = old_offsets.get(node) as u64;
+                let bits = old_offsets.get(node + 1) as u64 - start;
+                old_reader
+                    .set_bit_pos(start)
+                    .context("Could not seek the old bitstream")?;
+                let writer = bvcomp.encoder_mut().code_writer_mut();
+                let mut to_copy = bits;
+                while to_copy != 0 {
+                    let len = to_copy.min(32) as usize;
+                    let value = old_reader
+                        .read_bits(len)
+                        .context("Could not read the old bitstream")?;
+                    writer
+                        .write_bits(value, len)
+                        .context("Could not write bits")?;
+                    to_copy -= len as u64;
+                }
+                stats.copied_nodes += 1;
+                stats.copied_bits += bits;
+                bits
+            } else {
+                let bits = bvcomp
+                    .push(successors)
+                    .with_context(|| format!("Could not compress node {}", node))?;
+                if !unchanged || bvcomp.ref_count(node) != old_ref_count {
+                    last_dirty = Some(node);
+                }
+                bits
+            };
+
+            stats.bits += bits;
+            stats.num_nodes += 1;
+            offsets.write_gamma(bits).context("Could not write delta")?;
+            pl.light_update();
+        });
+        pl.done();
+
+        stats.num_arcs = bvcomp.arcs + copied_arcs;
+        stats.bits += bvcomp.flush().context("Could not flush bvcomp")? as u64;
+        offsets.flush().context("Could not flush offsets")?;
+
+        log::info!(
+            "Copied {} nodes out of {} and {} bits out of {} ({:.2}%)",
+            stats.copied_nodes,
+            stats.num_nodes,
+            stats.copied_bits,
+            stats.bits,
+            100.0 * stats.copied_bits as f64 / stats.bits.max(1) as f64
+        );
+
+        let properties = comp_flags
+            .to_properties::<E>(stats.num_nodes, stats.num_arcs, stats.bits)
+            .context("Could not serialize properties")?;
+        let properties_path = basename.with_ext(PROPERTIES_EXTENSION);
+        std::fs::write(&properties_path, properties)
+            .with_context(|| format!("Could not write {}", properties_path.display()))?;
+
+        Ok(stats)
+    }
+}
@@ -0,0 +1,140 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::prelude::*;
+use anyhow::{Context, Result};
+use std::ops::AddAssign;
+use std::path::Path;
+
+/// The extension of the file containing the [compression
+/// statistics](CompStats) of a graph.
+pub const STATS_EXTENSION: &str = "stats";
+
+/// The version of the JSON schema of [`CompStats::to_json`].
+pub const STATS_VERSION: u64 = 1;
+
+/// Statistics about the bits written by a [`BvComp`] for each component of
+/// the successor lists.
+///
+/// Statistics are accumulated during compression (estimates used to choose
+/// the reference do not count), and statistics of parallel compression
+/// threads can be summed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct CompStats {
+    /// The number of compressed nodes.
+    pub num_nodes: u64,
+    /// The number of compressed arcs.
+    pub num_arcs: u64,
+    /// The bits used by outdegrees.
+    pub outdegree_bits: u64,
+    /// The bits used by reference offsets.
+    pub reference_bits: u64,
+    /// The bits used by block counts and blocks.
+    pub block_bits: u64,
+    /// The bits used by interval counts, starts and lengths.
+    pub interval_bits: u64,
+    /// The bits used by residuals.
+    pub residual_bits: u64,
+}
+
+impl AddAssign for CompStats {
+    fn add_assign(&mut self, rhs: Self) {
+        self.num_nodes += rhs.num_nodes;
+        self.num_arcs += rhs.num_arcs;
+        self.outdegree_bits += rhs.outdegree_bits;
+        self.reference_bits += rhs.reference_bits;
+        self.block_bits += rhs.block_bits;
+        self.interval_bits += rhs.interval_bits;
+        self.residual_bits += rhs.residual_bits;
+    }
+}
+
+/// Formats a ratio as a JSON value, using `null` if the denominator is zero.
+fn json_ratio(num: u64, den: u64) -> String {
+    if den == 0 {
+        "null".to_owned()
+    } else {
+        format!("{}", num as f64 / den as f64)
+    }
+}
+
+impl CompStats {
+    /// Returns the total number of bits written, that is, the length of the
+    /// graph bitstream.
+    pub fn total_bits(&self) -> u64 {
+        self.outdegree_bits
+            + self.reference_bits
+            + self.block_bits
+            + self.interval_bits
+            + self.residual_bits
+    }
+
+    /// Returns the statistics in JSON format.
+    ///
+    /// The schema is stable; fields will only be added, and incompatible
+    /// changes will increase the value of `version` ([`STATS_VERSION`]):
+    ///
+    /// ```json
+    /// {
+    ///   "version": 1,
+    ///   "nodes": <integer>,
+    ///   "arcs": <integer>,
+    ///   "total_bits": <integer>,
+    ///   "bits_per_node": <number or null>,
+    ///   "bits_per_arc": <number or null>,
+    ///   "components": {
+    ///     "outdegrees": <integer>,
+    ///     "references": <integer>,
+    ///     "blocks": <integer>,
+    ///     "intervals": <integer>,
+    ///     "residuals": <integer>
+    ///   }
+    /// }
+    /// ```
+    ///
+    /// Component values are in bits, and they sum to `total_bits`; ratios are
+    /// `null` if there are no nodes or arcs.
+    pub fn to_json(&self) -> String {
+        let total_bits = self.total_bits();
+        format!(
+            r#"{{
+  "version": {},
+  "nodes": {},
+  "arcs": {},
+  "total_bits": {},
+  "bits_per_node": {},
+  "bits_per_arc": {},
+  "components": {{
+    "outdegrees": {},
+    "references": {},
+    "blocks": {},
+    "intervals": {},
+    "residuals": {}
+  }}
+}}
+"#,
+            STATS_VERSION,
+            self.num_nodes,
+            self.num_arcs,
+            total_bits,
+            json_ratio(total_bits, self.num_nodes),
+            json_ratio(total_bits, self.num_arcs),
+            self.outdegree_bits,
+            self.reference_bits,
+            self.block_bits,
+            self.interval_bits,
+            self.residual_bits,
+        )
+    }
+
+    /// Stores the statistics in [JSON format](CompStats::to_json) in the
+    /// file with the given basename and extension [`STATS_EXTENSION`].
+    pub fn store(&self, basename: impl AsRef<Path>) -> Result<()> {
+        let path = Basename::new(basename).with_ext(STATS_EXTENSION);
+        std::fs::write(&path, self.to_json())
+            .with_context(|| format!("Could not write {}", path.display()))
+    }
+}
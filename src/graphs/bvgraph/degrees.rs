@@ -0,0 +1,113 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::prelude::*;
+use anyhow::{Context, Result};
+use epserde::prelude::*;
+use rayon::prelude::*;
+use rayon::ThreadPool;
+use std::path::Path;
+use sux::prelude::*;
+
+/// The extension of the file containing the outdegrees of a graph, stored as
+/// a [`BitFieldVec`] of minimal bit width serialized with ε-serde.
+pub const DEGREES_EXTENSION: &str = "degrees";
+
+/// Returns the outdegrees of a graph in a [`BitFieldVec`] of minimal bit
+/// width.
+///
+/// Nodes are split in chunks of `node_granularity` nodes (rounded up to a
+/// multiple of [`usize::BITS`]), which are scanned in parallel by
+/// [`OffsetDegIter`]s using the given thread pool. Since chunks start at a
+/// multiple of [`usize::BITS`] nodes, the values of distinct chunks lie in
+/// disjoint ranges of words of the backing vector, which are filled
+/// independently.
+///
+/// If `max_degree` is `None`, the maximum outdegree is computed by a
+/// preliminary parallel pass. Otherwise, it is used to determine the bit
+/// width, and this function panics if some outdegree is larger.
+pub fn outdegrees_bitfield<F>(
+    graph: &BvGraph<F>,
+    max_degree: Option<usize>,
+    node_granularity: usize,
+    thread_pool: &ThreadPool,
+) -> BitFieldVec
+where
+    F: RandomAccessDecoderFactory + Sync,
+{
+    let num_nodes = graph.num_nodes();
+    let node_granularity = node_granularity
+        .clamp(1, num_nodes.max(1))
+        .next_multiple_of(usize::BITS as usize);
+    let num_chunks = num_nodes.div_ceil(node_granularity);
+    let chunk_range = |chunk: usize| {
+        let start = chunk * node_granularity;
+        start..(start + node_granularity).min(num_nodes)
+    };
+
+    let max_degree = max_degree.unwrap_or_else(|| {
+        thread_pool.install(|| {
+            (0..num_chunks)
+                .into_par_iter()
+                .map(|chunk| {
+                    let range = chunk_range(chunk);
+                    let mut iter = graph.offset_deg_iter_from(range.start);
+                    range
+                        .map(|_| iter.next_degree().unwrap())
+                        .max()
+                        .unwrap_or(0)
+                })
+                .max()
+                .unwrap_or(0)
+        })
+    });
+
+    let bit_width = (usize::BITS - max_degree.leading_zeros()) as usize;
+    let degrees = BitFieldVec::new(bit_width, num_nodes);
+    if bit_width == 0 {
+        return degrees;
+    }
+
+    // A chunk of node_granularity values occupies exactly this many words
+    let words_per_chunk = node_granularity / usize::BITS as usize * bit_width;
+    let (mut words, bit_width, len) = degrees.into_raw_parts();
+    thread_pool.install(|| {
+        words
+            .par_chunks_mut(words_per_chunk)
+            .enumerate()
+            // Trailing padding words, if any, belong to no chunk
+            .filter(|(chunk, _)| *chunk < num_chunks)
+            .for_each(|(chunk, words)| {
+                let range = chunk_range(chunk);
+                let mut iter = graph.offset_deg_iter_from(range.start);
+                // SAFETY: the chunk contains the words of the values of the
+                // nodes in range
+                let mut values =
+                    unsafe { BitFieldVec::from_raw_parts(words, bit_width, range.len()) };
+                for i in 0..range.len() {
+                    values.set(i, iter.next_degree().unwrap());
+                }
+            });
+    });
+    // SAFETY: the parts come from a BitFieldVec
+    unsafe { BitFieldVec::from_raw_parts(words, bit_width, len) }
+}
+
+/// Stores the outdegrees of the graph with given basename.
+pub fn store_degrees(basename: impl AsRef<Path>, degrees: &BitFieldVec) -> Result<()> {
+    let path = Basename::new(basename).with_ext(DEGREES_EXTENSION);
+    degrees
+        .store(&path)
+        .with_context(|| format!("Could not store outdegrees to {}", path.display()))?;
+    Ok(())
+}
+
+/// Loads the outdegrees of the graph with given basename.
+pub fn load_degrees(basename: impl AsRef<Path>) -> Result<BitFieldVec> {
+    let path = Basename::new(basename).with_ext(DEGREES_EXTENSION);
+    <BitFieldVec>::load_full(&path)
+        .with_context(|| format!("Could not load outdegrees from {}", path.display()))
+}
@@ -0,0 +1,605 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+//! An experimental format extension coding frequent successor lists with a
+//! dictionary.
+//!
+//! Some graphs contain many nodes with identical successor lists (e.g., the
+//! navigation boilerplate of web pages). [`build_dictionary`] finds, with a
+//! sequential pass hashing the successor lists, the duplicate lists whose
+//! coding by dictionary saves the largest number of arcs, and
+//! [`compress_with_dictionary`] stores them once in a `.dict` file, together
+//! with the set of the nodes whose successor list is one of the entries,
+//! represented by an Elias–Fano [`DCF`], and the corresponding entry indices,
+//! in a [`BitFieldVec`] using just the bits needed to represent the largest
+//! index. Thus, besides the code of a zero outdegree in the graph bitstream,
+//! a coded node costs about two bits plus the logarithm of the average gap
+//! between coded nodes for the node set, and the logarithm of the number of
+//! entries for its index; all other nodes are compressed as usual.
+//!
+//! A [`DictGraph`] combines a [`BvGraph`](super::BvGraph) (or a
+//! [`BvGraphSeq`](super::BvGraphSeq)) with a (usually memory-mapped)
+//! [`Dictionary`], returning the original successor lists.
+//!
+//! **This format is not compatible with the Java implementation**, and it
+//! might change in future versions: the graph bitstream alone describes a
+//! different graph, so the `.properties` file of a graph compressed with a
+//! dictionary has a different graph class, and contains the key
+//! `experimental=dictionary`. The module is available only with the `dict`
+//! feature.
+
+use super::load::update_properties;
+use crate::prelude::*;
+use anyhow::{Context, Result};
+use core::ops::Deref;
+use dsi_bitstream::prelude::*;
+use dsi_progress_logger::prelude::*;
+use epserde::prelude::*;
+use lender::*;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fs::File;
+use std::hash::{Hash, Hasher};
+use std::io::BufWriter;
+use std::path::Path;
+use sux::bits::BitFieldVec;
+use sux::dict::EliasFanoBuilder;
+use sux::prelude::{SelectAdaptConst, SelectZeroAdaptConst};
+use sux::traits::{BitFieldSlice, BitFieldSliceMut, IndexedDict, IndexedSeq};
+
+/// The extension of the file containing the dictionary of a graph.
+pub const DICT_EXTENSION: &str = "dict";
+
+/// The graph class written in the `.properties` file of graphs compressed
+/// with a dictionary.
+pub const DICT_GRAPH_CLASS: &str = "it.unimi.dsi.webgraph.rust.experimental.DictBVGraph";
+
+/// A dictionary of successor lists, and the nodes coded using it.
+///
+/// The successors of entry `i` are `successors[offsets[i]..offsets[i + 1]]`;
+/// `nodes` is the set of nodes whose successor list is an entry, and
+/// `node_entries` contains the corresponding entry indices, in the same
+/// order.
+#[derive(Epserde, Debug, Clone)]
+pub struct Dictionary<D = Vec<usize>, N = DCF, B = BitFieldVec> {
+    offsets: D,
+    successors: D,
+    nodes: N,
+    node_entries: B,
+}
+
+impl<D, N, B> Dictionary<D, N, B>
+where
+    D: AsRef<[usize]>,
+    N: IndexedSeq<Input = usize, Output = usize> + IndexedDict<Input = usize, Output = usize>,
+    B: BitFieldSlice<usize>,
+{
+    /// Returns the number of entries.
+    pub fn num_entries(&self) -> usize {
+        self.offsets.as_ref().len() - 1
+    }
+
+    /// Returns the successors of an entry.
+    pub fn entry(&self, entry: usize) -> &[usize] {
+        let offsets = self.offsets.as_ref();
+        &self.successors.as_ref()[offsets[entry]..offsets[entry + 1]]
+    }
+
+    /// Returns the number of nodes coded by the dictionary.
+    pub fn num_coded_nodes(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns an iterator over the nodes coded by the dictionary, in
+    /// increasing order.
+    pub fn nodes(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.nodes.len()).map(|i| self.nodes.get(i))
+    }
+
+    /// Returns the entry of a node, or `None` if the node is not coded by
+    /// the dictionary.
+    #[inline(always)]
+    pub fn entry_of(&self, node: usize) -> Option<usize> {
+        self.nodes.index_of(node).map(|i| self.node_entries.get(i))
+    }
+
+    /// Returns the number of arcs of the nodes coded by the dictionary.
+    pub fn num_coded_arcs(&self) -> u64 {
+        (0..self.node_entries.len())
+            .map(|i| self.entry(self.node_entries.get(i)).len() as u64)
+            .sum()
+    }
+}
+
+fn hash_list(list: &[usize]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    list.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Builds a dictionary containing at most `max_entries` duplicate successor
+/// lists of at least `min_outdegree` (and at least one) successors.
+///
+/// A first sequential pass counts successor lists by their hash, using
+/// memory proportional to the number of distinct lists, and entries are
+/// chosen among the lists appearing at least twice by the number of arcs
+/// they save, that is, their outdegree times their number of occurrences
+/// minus one. A second pass collects the entries and the coded nodes,
+/// checking that lists are actually equal to the entry with the same hash.
+pub fn build_dictionary(
+    graph: &impl SequentialGraph,
+    max_entries: usize,
+    min_outdegree: usize,
+    pl: &mut impl ProgressLog,
+) -> Dictionary {
+    let min_outdegree = min_outdegree.max(1);
+    let mut buf = Vec::new();
+
+    // The number of occurrences and the outdegree of each hash
+    let mut counts = HashMap::<u64, (usize, usize)>::new();
+    pl.item_name("node")
+        .expected_updates(Some(graph.num_nodes()));
+    pl.start("Hashing successor lists...");
+    for_!((_node, succ) in graph.iter() {
+        buf.clear();
+        buf.extend(succ);
+        if buf.len() >= min_outdegree {
+            counts.entry(hash_list(&buf)).or_insert((0, buf.len())).0 += 1;
+        }
+        pl.light_update();
+    });
+    pl.done();
+
+    let mut candidates = counts
+        .into_iter()
+        .filter(|&(_, (count, _))| count > 1)
+        .map(|(hash, (count, outdegree))| ((count - 1) * outdegree, hash))
+        .collect::<Vec<_>>();
+    candidates.sort_unstable_by(|a, b| b.cmp(a));
+    candidates.truncate(max_entries);
+    let mut entries = candidates
+        .into_iter()
+        .map(|(_, hash)| (hash, None))
+        .collect::<HashMap<u64, Option<usize>>>();
+
+    let mut offsets = vec![0];
+    let mut successors = Vec::new();
+    let mut nodes = Vec::new();
+    let mut node_entries = Vec::new();
+    pl.expected_updates(Some(graph.num_nodes()));
+    pl.start("Collecting dictionary entries...");
+    for_!((node, succ) in graph.iter() {
+        buf.clear();
+        buf.extend(succ);
+        if buf.len() >= min_outdegree {
+            if let Some(slot) = entries.get_mut(&hash_list(&buf)) {
+                let entry = *slot.get_or_insert_with(|| {
+                    successors.extend_from_slice(&buf);
+                    offsets.push(successors.len());
+                    offsets.len() - 2
+                });
+                // Lists with the same hash might be different
+                if successors[offsets[entry]..offsets[entry + 1]] == buf[..] {
+                    nodes.push(node);
+                    node_entries.push(entry);
+                }
+            }
+        }
+        pl.light_update();
+    });
+    pl.done();
+
+    let mut efb = EliasFanoBuilder::new(nodes.len(), graph.num_nodes());
+    for &node in &nodes {
+        efb.push(node);
+    }
+    // SAFETY: the selection structures are built on the high bits
+    let nodes = unsafe {
+        efb.build().map_high_bits(|bits| {
+            SelectZeroAdaptConst::<_, _, 12, 4>::new(SelectAdaptConst::<_, _, 12, 4>::new(bits))
+        })
+    };
+    let bit_width = (usize::BITS - (offsets.len() - 1).leading_zeros()) as usize;
+    let mut entries_vec = BitFieldVec::new(bit_width, node_entries.len());
+    for (i, &entry) in node_entries.iter().enumerate() {
+        entries_vec.set(i, entry);
+    }
+
+    let dict = Dictionary {
+        offsets,
+        successors,
+        nodes,
+        node_entries: entries_vec,
+    };
+    log::info!(
+        "{} dictionary entries code {} nodes and {} arcs",
+        dict.num_entries(),
+        dict.num_coded_nodes(),
+        dict.num_coded_arcs()
+    );
+    dict
+}
+
+/// Statistics about a graph compressed with a dictionary, returned by
+/// [`compress_with_dictionary`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DictStats {
+    /// The number of nodes of the graph.
+    pub num_nodes: usize,
+    /// The number of nodes coded by the dictionary.
+    pub coded_nodes: usize,
+    /// The number of entries of the dictionary.
+    pub num_entries: usize,
+    /// The length in bits of the graph bitstream.
+    pub graph_bits: u64,
+    /// The size in bits of the `.dict` file.
+    pub dict_bits: u64,
+    /// The length in bits of the graph bitstream compressed without a
+    /// dictionary.
+    pub plain_bits: u64,
+}
+
+impl DictStats {
+    /// Returns the fraction of nodes coded by the dictionary.
+    pub fn coded_fraction(&self) -> f64 {
+        self.coded_nodes as f64 / self.num_nodes.max(1) as f64
+    }
+
+    /// Returns the number of bits saved with respect to compression without
+    /// a dictionary, including the size of the dictionary (a negative
+    /// value means that the dictionary does not pay off).
+    pub fn saved_bits(&self) -> i64 {
+        self.plain_bits as i64 - (self.graph_bits + self.dict_bits) as i64
+    }
+}
+
+/// Compresses a graph with the given dictionary, writing the `.graph`,
+/// `.offsets`, `.properties` and `.dict` files with the given basename.
+///
+/// Besides the compression, this function estimates the length of the
+/// bitstream without dictionary with an additional sequential pass, so that
+/// the returned [statistics](DictStats) report the size saved.
+pub fn compress_with_dictionary<E: Endianness>(
+    basename: impl AsRef<Path>,
+    graph: &impl SequentialGraph,
+    dict: &Dictionary,
+    compression_flags: CompFlags,
+) -> Result<DictStats>
+where
+    BufBitWriter<E, WordAdapter<usize, BufWriter<File>>>: CodeWrite<E>,
+{
+    let basename = Basename::new(basename);
+    let num_nodes = graph.num_nodes();
+    let graph_bits = BvComp::single_thread::<E, _>(
+        basename.as_path(),
+        Masked {
+            iter: graph.iter(),
+            dict,
+        },
+        compression_flags,
+        true,
+        Some(num_nodes),
+    )?;
+
+    let dict_path = basename.with_ext(DICT_EXTENSION);
+    dict.store(&dict_path)
+        .with_context(|| format!("Could not store {}", dict_path.display()))?;
+
+    // Mark the graph as not compatible with the Java implementation
+    update_properties(basename.as_path(), |map| {
+        map.insert("graphclass".to_owned(), DICT_GRAPH_CLASS.to_owned());
+        map.insert("experimental".to_owned(), "dictionary".to_owned());
+        map.insert(
+            "dictionaryentries".to_owned(),
+            dict.num_entries().to_string(),
+        );
+        map.insert(
+            "dictionarynodes".to_owned(),
+            dict.num_coded_nodes().to_string(),
+        );
+    })?;
+
+    let mut bvcomp = BvComp::new(
+        DynCodesEstimator::new(&compression_flags),
+        compression_flags.compression_window,
+        compression_flags.max_ref_count,
+        compression_flags.min_interval_length,
+        0,
+    )
+    .with_reference_selection(compression_flags.reference_selection);
+    let plain_bits = bvcomp.extend(graph.iter())?;
+
+    let stats = DictStats {
+        num_nodes,
+        coded_nodes: dict.num_coded_nodes(),
+        num_entries: dict.num_entries(),
+        graph_bits,
+        dict_bits: std::fs::metadata(&dict_path)?.len() * 8,
+        plain_bits,
+    };
+    log::info!(
+        "{:.2}% of the nodes are coded by the dictionary; {} bits saved ({} bits without dictionary)",
+        100.0 * stats.coded_fraction(),
+        stats.saved_bits(),
+        stats.plain_bits
+    );
+    Ok(stats)
+}
+
+/// A lender returning no successors for the nodes coded by a dictionary.
+struct Masked<'a, L> {
+    iter: L,
+    dict: &'a Dictionary,
+}
+
+impl<'succ, L> NodeLabelsLender<'succ> for Masked<'_, L>
+where
+    L: Lender + for<'next> NodeLabelsLender<'next, Label = usize>,
+{
+    type Label = usize;
+    type IntoIterator = core::iter::Take<LenderIntoIter<'succ, L>>;
+}
+
+impl<'succ, L> Lending<'succ> for Masked<'_, L>
+where
+    L: Lender + for<'next> NodeLabelsLender<'next, Label = usize>,
+{
+    type Lend = (usize, <Self as NodeLabelsLender<'succ>>::IntoIterator);
+}
+
+impl<L> Lender for Masked<'_, L>
+where
+    L: Lender + for<'next> NodeLabelsLender<'next, Label = usize>,
+{
+    #[inline(always)]
+    fn next(&mut self) -> Option<Lend<'_, Self>> {
+        let (node, succ) = self.iter.next()?.into_pair();
+        let coded = self.dict.entry_of(node).is_some();
+        Some((
+            node,
+            succ.into_iter().take(if coded { 0 } else { usize::MAX }),
+        ))
+    }
+}
+
+/// The successors of a node of a [`DictGraph`].
+#[derive(Debug, Clone)]
+pub enum Succ<'a, I> {
+    /// The successors are decoded from the graph.
+    Graph(I),
+    /// The successors are an entry of the dictionary.
+    Entry(core::iter::Copied<core::slice::Iter<'a, usize>>),
+}
+
+impl<I: Iterator<Item = usize>> Iterator for Succ<'_, I> {
+    type Item = usize;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<usize> {
+        match self {
+            Succ::Graph(iter) => iter.next(),
+            Succ::Entry(iter) => iter.next(),
+        }
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            Succ::Graph(iter) => iter.size_hint(),
+            Succ::Entry(iter) => iter.size_hint(),
+        }
+    }
+}
+
+/// A graph compressed with a [`Dictionary`].
+///
+/// The graph is usually a [`BvGraph`](super::BvGraph) or a
+/// [`BvGraphSeq`](super::BvGraphSeq) compressed by
+/// [`compress_with_dictionary`], and the dictionary is usually
+/// memory-mapped from the `.dict` file with [`load_dictionary`]; any type
+/// dereferencing to a [`Dictionary`] can be used, however.
+pub struct DictGraph<G, T> {
+    graph: G,
+    dict: T,
+    coded_arcs: u64,
+}
+
+impl<G, T, D, N, B> DictGraph<G, T>
+where
+    T: Deref<Target = Dictionary<D, N, B>>,
+    D: AsRef<[usize]>,
+    N: IndexedSeq<Input = usize, Output = usize> + IndexedDict<Input = usize, Output = usize>,
+    B: BitFieldSlice<usize>,
+{
+    /// Combines a graph with its dictionary.
+    pub fn new(graph: G, dict: T) -> Self {
+        let coded_arcs = dict.num_coded_arcs();
+        Self {
+            graph,
+            dict,
+            coded_arcs,
+        }
+    }
+
+    /// Returns the dictionary.
+    pub fn dictionary(&self) -> &Dictionary<D, N, B> {
+        &self.dict
+    }
+}
+
+/// Memory-maps the dictionary of the graph with the given basename.
+pub fn load_dictionary(
+    basename: impl AsRef<Path>,
+) -> Result<MemCase<DeserType<'static, Dictionary>>> {
+    let path = Basename::new(basename).with_ext(DICT_EXTENSION);
+    <Dictionary>::mmap(&path, Flags::empty())
+        .with_context(|| format!("Could not map {}", path.display()))
+}
+
+impl<G, T, D, N, B> SequentialLabeling for DictGraph<G, T>
+where
+    G: SequentialGraph,
+    T: Deref<Target = Dictionary<D, N, B>>,
+    D: AsRef<[usize]> + 'static,
+    N: IndexedSeq<Input = usize, Output = usize>
+        + IndexedDict<Input = usize, Output = usize>
+        + 'static,
+    B: BitFieldSlice<usize> + 'static,
+{
+    type Label = usize;
+    type Lender<'b>
+        = Iter<'b, G::Lender<'b>, D, N, B>
+    where
+        Self: 'b;
+
+    #[inline(always)]
+    fn num_nodes(&self) -> usize {
+        self.graph.num_nodes()
+    }
+
+    #[inline(always)]
+    fn successors_sorted(&self) -> bool {
+        self.graph.successors_sorted()
+    }
+
+    #[inline(always)]
+    fn num_arcs_hint(&self) -> Option<u64> {
+        self.graph
+            .num_arcs_hint()
+            .map(|num_arcs| num_arcs + self.coded_arcs)
+    }
+
+    fn iter_from(&self, from: usize) -> Self::Lender<'_> {
+        Iter {
+            iter: self.graph.iter_from(from),
+            dict: &self.dict,
+        }
+    }
+}
+
+impl<G, T, D, N, B> SequentialGraph for DictGraph<G, T>
+where
+    G: SequentialGraph,
+    T: Deref<Target = Dictionary<D, N, B>>,
+    D: AsRef<[usize]> + 'static,
+    N: IndexedSeq<Input = usize, Output = usize>
+        + IndexedDict<Input = usize, Output = usize>
+        + 'static,
+    B: BitFieldSlice<usize> + 'static,
+{
+}
+
+impl<G, T, D, N, B> RandomAccessLabeling for DictGraph<G, T>
+where
+    G: RandomAccessGraph,
+    T: Deref<Target = Dictionary<D, N, B>>,
+    D: AsRef<[usize]> + 'static,
+    N: IndexedSeq<Input = usize, Output = usize>
+        + IndexedDict<Input = usize, Output = usize>
+        + 'static,
+    B: BitFieldSlice<usize> + 'static,
+{
+    type Labels<'b>
+        = Succ<'b, <G::Labels<'b> as IntoIterator>::IntoIter>
+    where
+        Self: 'b;
+
+    #[inline(always)]
+    fn num_arcs(&self) -> u64 {
+        self.graph.num_arcs() + self.coded_arcs
+    }
+
+    #[inline(always)]
+    fn labels(&self, node_id: usize) -> Self::Labels<'_> {
+        match self.dict.entry_of(node_id) {
+            Some(entry) => Succ::Entry(self.dict.entry(entry).iter().copied()),
+            None => Succ::Graph(self.graph.successors(node_id).into_iter()),
+        }
+    }
+
+    #[inline(always)]
+    fn outdegree(&self, node_id: usize) -> usize {
+        match self.dict.entry_of(node_id) {
+            Some(entry) => self.dict.entry(entry).len(),
+            None => self.graph.outdegree(node_id),
+        }
+    }
+}
+
+impl<G, T, D, N, B> RandomAccessGraph for DictGraph<G, T>
+where
+    G: RandomAccessGraph,
+    T: Deref<Target = Dictionary<D, N, B>>,
+    D: AsRef<[usize]> + 'static,
+    N: IndexedSeq<Input = usize, Output = usize>
+        + IndexedDict<Input = usize, Output = usize>
+        + 'static,
+    B: BitFieldSlice<usize> + 'static,
+{
+}
+
+impl<'a, G, T, D, N, B> IntoLender for &'a DictGraph<G, T>
+where
+    G: SequentialGraph,
+    T: Deref<Target = Dictionary<D, N, B>>,
+    D: AsRef<[usize]> + 'static,
+    N: IndexedSeq<Input = usize, Output = usize>
+        + IndexedDict<Input = usize, Output = usize>
+        + 'static,
+    B: BitFieldSlice<usize> + 'static,
+{
+    type Lender = <DictGraph<G, T> as SequentialLabeling>::Lender<'a>;
+
+    #[inline(always)]
+    fn into_lender(self) -> Self::Lender {
+        self.iter()
+    }
+}
+
+/// A lender over the nodes of a [`DictGraph`].
+pub struct Iter<'a, L, D, N, B> {
+    iter: L,
+    dict: &'a Dictionary<D, N, B>,
+}
+
+impl<'a, 'succ, L, D, N, B> NodeLabelsLender<'succ> for Iter<'a, L, D, N, B>
+where
+    L: Lender + for<'next> NodeLabelsLender<'next, Label = usize>,
+    D: AsRef<[usize]>,
+    N: IndexedSeq<Input = usize, Output = usize> + IndexedDict<Input = usize, Output = usize>,
+    B: BitFieldSlice<usize>,
+{
+    type Label = usize;
+    type IntoIterator = Succ<'a, LenderIntoIter<'succ, L>>;
+}
+
+impl<'a, 'succ, L, D, N, B> Lending<'succ> for Iter<'a, L, D, N, B>
+where
+    L: Lender + for<'next> NodeLabelsLender<'next, Label = usize>,
+    D: AsRef<[usize]>,
+    N: IndexedSeq<Input = usize, Output = usize> + IndexedDict<Input = usize, Output = usize>,
+    B: BitFieldSlice<usize>,
+{
+    type Lend = (usize, <Self as NodeLabelsLender<'succ>>::IntoIterator);
+}
+
+impl<L, D, N, B> Lender for Iter<'_, L, D, N, B>
+where
+    L: Lender + for<'next> NodeLabelsLender<'next, Label = usize>,
+    D: AsRef<[usize]>,
+    N: IndexedSeq<Input = usize, Output = usize> + IndexedDict<Input = usize, Output = usize>,
+    B: BitFieldSlice<usize>,
+{
+    #[inline(always)]
+    fn next(&mut self) -> Option<Lend<'_, Self>> {
+        let (node, succ) = self.iter.next()?.into_pair();
+        let dict = self.dict;
+        match dict.entry_of(node) {
+            Some(entry) => Some((node, Succ::Entry(dict.entry(entry).iter().copied()))),
+            None => Some((node, Succ::Graph(succ.into_iter()))),
+        }
+    }
+}
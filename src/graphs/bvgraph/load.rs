@@ -5,6 +5,7 @@
  * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
  */
 
+use super::select_params::select_dispatch;
 use super::*;
 use crate::prelude::*;
 use anyhow::{Context, Result};
@@ -12,6 +13,7 @@ use dsi_bitstream::prelude::*;
 use epserde::prelude::*;
 use sealed::sealed;
 use std::{
+    collections::HashMap,
     io::BufReader,
     path::{Path, PathBuf},
 };
@@ -82,15 +84,23 @@ impl Dispatch for Dynamic {}
 pub trait LoadMode: 'static {
     type Factory<E: Endianness>: BitReaderFactory<E>;
 
+    /// Creates a factory for the given graph file.
+    ///
+    /// `prefetch_bytes` is the size of the chunks prefetched by the readers,
+    /// and it is ignored by all modes but [`Prefetch`].
     fn new_factory<E: Endianness, P: AsRef<Path>>(
         graph: P,
         flags: codecs::MemoryFlags,
+        prefetch_bytes: usize,
     ) -> Result<Self::Factory<E>>;
 
     type Offsets: IndexedSeq<Input = usize, Output = usize>;
 
+    /// Loads the offsets, dispatching on the [parameters](SelectParams) of
+    /// their selection structure.
     fn load_offsets<P: AsRef<Path>>(
         offsets: P,
+        params: SelectParams,
         flags: MemoryFlags,
     ) -> Result<MemCase<Self::Offsets>>;
 }
@@ -104,23 +114,26 @@ pub struct File {}
 #[sealed]
 impl LoadMode for File {
     type Factory<E: Endianness> = FileFactory<E>;
-    type Offsets = EF;
+    type Offsets = DispatchEF;
 
     fn new_factory<E: Endianness, P: AsRef<Path>>(
         graph: P,
         _flags: MemoryFlags,
+        _prefetch_bytes: usize,
     ) -> Result<Self::Factory<E>> {
         FileFactory::<E>::new(graph)
     }
 
     fn load_offsets<P: AsRef<Path>>(
         offsets: P,
+        params: SelectParams,
         _flags: MemoryFlags,
     ) -> Result<MemCase<Self::Offsets>> {
         let path = offsets.as_ref();
-        Ok(EF::load_full(path)
+        Ok(select_dispatch!(params, T => T::load_full(path)
             .with_context(|| format!("Cannot load Elias-Fano pointer list {}", path.display()))?
             .into())
+        .into())
     }
 }
 
@@ -132,22 +145,55 @@ pub struct Mmap {}
 #[sealed]
 impl LoadMode for Mmap {
     type Factory<E: Endianness> = MmapHelper<u32>;
-    type Offsets = DeserType<'static, EF>;
+    type Offsets = DispatchEFDeser;
 
     fn new_factory<E: Endianness, P: AsRef<Path>>(
         graph: P,
         flags: MemoryFlags,
+        _prefetch_bytes: usize,
     ) -> Result<Self::Factory<E>> {
         MmapHelper::mmap(graph, flags.into())
     }
 
     fn load_offsets<P: AsRef<Path>>(
         offsets: P,
+        params: SelectParams,
         flags: MemoryFlags,
     ) -> Result<MemCase<Self::Offsets>> {
         let path = offsets.as_ref();
-        EF::mmap(path, flags.into())
-            .with_context(|| format!("Cannot map Elias-Fano pointer list {}", path.display()))
+        Ok(select_dispatch!(params, T => T::mmap(path, flags.into())
+            .with_context(|| format!("Cannot map Elias-Fano pointer list {}", path.display()))?)
+        .into())
+    }
+}
+
+/// The graph is memory mapped and read sequentially prefetching the chunk
+/// following the one being decoded; offsets are memory mapped.
+///
+/// This mode is meant for full sequential scans on high-latency storage, and
+/// it is set by [`LoadConfig::prefetch`], which specifies the chunk size. See
+/// [`PrefetchFactory`] for the details.
+#[derive(Debug, Clone)]
+pub struct Prefetch {}
+#[sealed]
+impl LoadMode for Prefetch {
+    type Factory<E: Endianness> = PrefetchFactory<E>;
+    type Offsets = DispatchEFDeser;
+
+    fn new_factory<E: Endianness, P: AsRef<Path>>(
+        graph: P,
+        flags: MemoryFlags,
+        prefetch_bytes: usize,
+    ) -> Result<Self::Factory<E>> {
+        PrefetchFactory::new(graph, flags, prefetch_bytes)
+    }
+
+    fn load_offsets<P: AsRef<Path>>(
+        offsets: P,
+        params: SelectParams,
+        flags: MemoryFlags,
+    ) -> Result<MemCase<Self::Offsets>> {
+        Mmap::load_offsets(offsets, params, flags)
     }
 }
 
@@ -157,22 +203,25 @@ pub struct LoadMem {}
 #[sealed]
 impl LoadMode for LoadMem {
     type Factory<E: Endianness> = MemoryFactory<E, Box<[u32]>>;
-    type Offsets = DeserType<'static, EF>;
+    type Offsets = DispatchEFDeser;
 
     fn new_factory<E: Endianness, P: AsRef<Path>>(
         graph: P,
         _flags: MemoryFlags,
+        _prefetch_bytes: usize,
     ) -> Result<Self::Factory<E>> {
         MemoryFactory::<E, _>::new_mem(graph)
     }
 
     fn load_offsets<P: AsRef<Path>>(
         offsets: P,
+        params: SelectParams,
         _flags: MemoryFlags,
     ) -> Result<MemCase<Self::Offsets>> {
         let path = offsets.as_ref();
-        EF::load_mem(path)
-            .with_context(|| format!("Cannot load Elias-Fano pointer list {}", path.display()))
+        Ok(select_dispatch!(params, T => T::load_mem(path)
+            .with_context(|| format!("Cannot load Elias-Fano pointer list {}", path.display()))?)
+        .into())
     }
 }
 
@@ -184,22 +233,27 @@ pub struct LoadMmap {}
 #[sealed]
 impl LoadMode for LoadMmap {
     type Factory<E: Endianness> = MemoryFactory<E, MmapHelper<u32>>;
-    type Offsets = DeserType<'static, EF>;
+    type Offsets = DispatchEFDeser;
 
     fn new_factory<E: Endianness, P: AsRef<Path>>(
         graph: P,
         flags: MemoryFlags,
+        _prefetch_bytes: usize,
     ) -> Result<Self::Factory<E>> {
         MemoryFactory::<E, _>::new_mmap(graph, flags)
     }
 
     fn load_offsets<P: AsRef<Path>>(
         offsets: P,
+        params: SelectParams,
         flags: MemoryFlags,
     ) -> Result<MemCase<Self::Offsets>> {
         let path = offsets.as_ref();
-        EF::load_mmap(path, flags.into())
-            .with_context(|| format!("Cannot load Elias-Fano pointer list {}", path.display()))
+        Ok(
+            select_dispatch!(params, T => T::load_mmap(path, flags.into())
+            .with_context(|| format!("Cannot load Elias-Fano pointer list {}", path.display()))?)
+            .into(),
+        )
     }
 }
 
@@ -210,9 +264,11 @@ impl LoadMode for LoadMmap {
 /// can then be customized using the methods of this struct.
 #[derive(Debug, Clone)]
 pub struct LoadConfig<E: Endianness, A: Access, D: Dispatch, GLM: LoadMode, OLM: LoadMode> {
-    pub(crate) basename: PathBuf,
+    pub(crate) basename: Basename,
     pub(crate) graph_load_flags: MemoryFlags,
     pub(crate) offsets_load_flags: MemoryFlags,
+    pub(crate) prefetch_bytes: usize,
+    pub(crate) validate_offsets: bool,
     pub(crate) _marker: std::marker::PhantomData<(E, A, D, GLM, OLM)>,
 }
 
@@ -225,6 +281,8 @@ impl<E: Endianness, A: Access, D: Dispatch, GLM: LoadMode, OLM: LoadMode>
             basename: self.basename,
             graph_load_flags: self.graph_load_flags,
             offsets_load_flags: self.offsets_load_flags,
+            prefetch_bytes: self.prefetch_bytes,
+            validate_offsets: self.validate_offsets,
             _marker: std::marker::PhantomData,
         }
     }
@@ -239,6 +297,8 @@ impl<E: Endianness, A: Access, D: Dispatch, GLM: LoadMode, OLM: LoadMode>
             basename: self.basename,
             graph_load_flags: self.graph_load_flags,
             offsets_load_flags: self.offsets_load_flags,
+            prefetch_bytes: self.prefetch_bytes,
+            validate_offsets: self.validate_offsets,
             _marker: std::marker::PhantomData,
         }
     }
@@ -253,6 +313,8 @@ impl<E: Endianness, A: Access, D: Dispatch, GLM: LoadMode, OLM: LoadMode>
             basename: self.basename,
             graph_load_flags: self.graph_load_flags,
             offsets_load_flags: self.offsets_load_flags,
+            prefetch_bytes: self.prefetch_bytes,
+            validate_offsets: self.validate_offsets,
             _marker: std::marker::PhantomData,
         }
     }
@@ -265,6 +327,8 @@ impl<E: Endianness, A: Access, D: Dispatch> LoadConfig<E, A, D, Mmap, Mmap> {
             basename: self.basename,
             graph_load_flags: flags,
             offsets_load_flags: flags,
+            prefetch_bytes: self.prefetch_bytes,
+            validate_offsets: self.validate_offsets,
             _marker: std::marker::PhantomData,
         }
     }
@@ -277,6 +341,8 @@ impl<E: Endianness, A: Access, D: Dispatch> LoadConfig<E, A, D, LoadMmap, LoadMm
             basename: self.basename,
             graph_load_flags: flags,
             offsets_load_flags: flags,
+            prefetch_bytes: self.prefetch_bytes,
+            validate_offsets: self.validate_offsets,
             _marker: std::marker::PhantomData,
         }
     }
@@ -291,6 +357,8 @@ impl<E: Endianness, A: Access, D: Dispatch, GLM: LoadMode, OLM: LoadMode>
             basename: self.basename,
             graph_load_flags: self.graph_load_flags,
             offsets_load_flags: self.offsets_load_flags,
+            prefetch_bytes: self.prefetch_bytes,
+            validate_offsets: self.validate_offsets,
             _marker: std::marker::PhantomData,
         }
     }
@@ -303,6 +371,8 @@ impl<E: Endianness, A: Access, D: Dispatch, OLM: LoadMode> LoadConfig<E, A, D, M
             basename: self.basename,
             graph_load_flags: flags,
             offsets_load_flags: self.offsets_load_flags,
+            prefetch_bytes: self.prefetch_bytes,
+            validate_offsets: self.validate_offsets,
             _marker: std::marker::PhantomData,
         }
     }
@@ -315,6 +385,27 @@ impl<E: Endianness, A: Access, D: Dispatch, OLM: LoadMode> LoadConfig<E, A, D, L
             basename: self.basename,
             graph_load_flags: flags,
             offsets_load_flags: self.offsets_load_flags,
+            prefetch_bytes: self.prefetch_bytes,
+            validate_offsets: self.validate_offsets,
+            _marker: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<E: Endianness, D: Dispatch, GLM: LoadMode, OLM: LoadMode>
+    LoadConfig<E, Sequential, D, GLM, OLM>
+{
+    /// Memory-maps the graph and reads it [prefetching](Prefetch) chunks of
+    /// the given size in bytes ahead of the decoder.
+    ///
+    /// The memory-mapping flags of the graph are preserved.
+    pub fn prefetch(self, bytes: usize) -> LoadConfig<E, Sequential, D, Prefetch, OLM> {
+        LoadConfig {
+            basename: self.basename,
+            graph_load_flags: self.graph_load_flags,
+            offsets_load_flags: self.offsets_load_flags,
+            prefetch_bytes: bytes,
+            validate_offsets: self.validate_offsets,
             _marker: std::marker::PhantomData,
         }
     }
@@ -327,11 +418,29 @@ impl<E: Endianness, D: Dispatch, GLM: LoadMode, OLM: LoadMode> LoadConfig<E, Ran
             basename: self.basename,
             graph_load_flags: self.graph_load_flags,
             offsets_load_flags: self.offsets_load_flags,
+            prefetch_bytes: self.prefetch_bytes,
+            validate_offsets: self.validate_offsets,
             _marker: std::marker::PhantomData,
         }
     }
 }
 
+impl<E: Endianness, D: Dispatch, GLM: LoadMode, OLM: LoadMode> LoadConfig<E, Random, D, GLM, OLM> {
+    /// Enables or disables the validation of the offsets.
+    ///
+    /// By default, after loading the offsets their length, their first and
+    /// last value, and the monotonicity of a sample of
+    /// [`LOAD_VALIDATION_SAMPLES`] values are checked using [`validate_ef`],
+    /// so that `.ef` files that do not belong to the graph, or graph files
+    /// that have been truncated, are detected at load time.
+    pub fn validate_offsets(self, validate: bool) -> Self {
+        LoadConfig {
+            validate_offsets: validate,
+            ..self
+        }
+    }
+}
+
 impl<E: Endianness, D: Dispatch, GLM: LoadMode> LoadConfig<E, Random, D, GLM, Mmap> {
     /// Set flags for memory-mapping the offsets.
     pub fn offsets_flags(self, flags: MemoryFlags) -> LoadConfig<E, Random, D, GLM, Mmap> {
@@ -339,6 +448,8 @@ impl<E: Endianness, D: Dispatch, GLM: LoadMode> LoadConfig<E, Random, D, GLM, Mm
             basename: self.basename,
             graph_load_flags: self.graph_load_flags,
             offsets_load_flags: flags,
+            prefetch_bytes: self.prefetch_bytes,
+            validate_offsets: self.validate_offsets,
             _marker: std::marker::PhantomData,
         }
     }
@@ -351,6 +462,8 @@ impl<E: Endianness, D: Dispatch, GLM: LoadMode> LoadConfig<E, Random, D, GLM, Lo
             basename: self.basename,
             graph_load_flags: self.graph_load_flags,
             offsets_load_flags: flags,
+            prefetch_bytes: self.prefetch_bytes,
+            validate_offsets: self.validate_offsets,
             _marker: std::marker::PhantomData,
         }
     }
@@ -360,18 +473,28 @@ impl<E: Endianness, GLM: LoadMode, OLM: LoadMode> LoadConfig<E, Random, Dynamic,
     /// Load a random-access graph with dynamic dispatch.
     #[allow(clippy::type_complexity)]
     pub fn load(
-        mut self,
+        self,
     ) -> anyhow::Result<BvGraph<DynCodesDecoderFactory<E, GLM::Factory<E>, OLM::Offsets>>>
     where
         for<'a> <<GLM as LoadMode>::Factory<E> as BitReaderFactory<E>>::BitReader<'a>:
             CodeRead<E> + BitSeek,
     {
-        self.basename.set_extension(PROPERTIES_EXTENSION);
-        let (num_nodes, num_arcs, comp_flags) = parse_properties::<E>(&self.basename)?;
-        self.basename.set_extension(GRAPH_EXTENSION);
-        let factory = GLM::new_factory(&self.basename, self.graph_load_flags)?;
-        self.basename.set_extension(EF_EXTENSION);
-        let offsets = OLM::load_offsets(&self.basename, self.offsets_load_flags)?;
+        let (num_nodes, num_arcs, comp_flags) =
+            parse_properties::<E>(self.basename.with_ext(PROPERTIES_EXTENSION))?;
+        let factory = GLM::new_factory(
+            self.basename.with_ext(GRAPH_EXTENSION),
+            self.graph_load_flags,
+            self.prefetch_bytes,
+        )?;
+        let params = SelectParams::load(&self.basename, EF_SELECT_PROPERTY)?;
+        let offsets = OLM::load_offsets(
+            self.basename.with_ext(EF_EXTENSION),
+            params,
+            self.offsets_load_flags,
+        )?;
+        if self.validate_offsets {
+            check_offsets(&self.basename, &*offsets, num_nodes)?;
+        }
 
         Ok(BvGraph::new(
             DynCodesDecoderFactory::new(factory, offsets, comp_flags)?,
@@ -387,17 +510,20 @@ impl<E: Endianness, GLM: LoadMode, OLM: LoadMode> LoadConfig<E, Sequential, Dyna
     /// Load a sequential graph with dynamic dispatch.
     #[allow(clippy::type_complexity)]
     pub fn load(
-        mut self,
+        self,
     ) -> anyhow::Result<
         BvGraphSeq<DynCodesDecoderFactory<E, GLM::Factory<E>, EmptyDict<usize, usize>>>,
     >
     where
         for<'a> <<GLM as LoadMode>::Factory<E> as BitReaderFactory<E>>::BitReader<'a>: CodeRead<E>,
     {
-        self.basename.set_extension(PROPERTIES_EXTENSION);
-        let (num_nodes, num_arcs, comp_flags) = parse_properties::<E>(&self.basename)?;
-        self.basename.set_extension(GRAPH_EXTENSION);
-        let factory = GLM::new_factory(&self.basename, self.graph_load_flags)?;
+        let (num_nodes, num_arcs, comp_flags) =
+            parse_properties::<E>(self.basename.with_ext(PROPERTIES_EXTENSION))?;
+        let factory = GLM::new_factory(
+            self.basename.with_ext(GRAPH_EXTENSION),
+            self.graph_load_flags,
+            self.prefetch_bytes,
+        )?;
 
         Ok(BvGraphSeq::new(
             DynCodesDecoderFactory::new(factory, MemCase::from(EmptyDict::default()), comp_flags)?,
@@ -407,6 +533,44 @@ impl<E: Endianness, GLM: LoadMode, OLM: LoadMode> LoadConfig<E, Sequential, Dyna
             comp_flags.min_interval_length,
         ))
     }
+
+    /// Enables checking the number of arcs decoded by full iterations against
+    /// the number of arcs in the `.properties` file.
+    ///
+    /// The graph loaded by the returned configuration is wrapped in an
+    /// [`ArcCountCheck`]; call [`ArcCountCheck::check`] after iterating on
+    /// the graph to detect mismatches.
+    pub fn verify_arc_count(self) -> VerifyArcCount<Self> {
+        VerifyArcCount(self)
+    }
+}
+
+/// A load configuration whose graph will be wrapped in an [`ArcCountCheck`].
+///
+/// It is returned by [`LoadConfig::verify_arc_count`].
+#[derive(Debug, Clone)]
+pub struct VerifyArcCount<C>(C);
+
+impl<E: Endianness, GLM: LoadMode, OLM: LoadMode>
+    VerifyArcCount<LoadConfig<E, Sequential, Dynamic, GLM, OLM>>
+{
+    /// Load a sequential graph with dynamic dispatch, checking its number of
+    /// arcs.
+    #[allow(clippy::type_complexity)]
+    pub fn load(
+        self,
+    ) -> anyhow::Result<
+        ArcCountCheck<
+            BvGraphSeq<DynCodesDecoderFactory<E, GLM::Factory<E>, EmptyDict<usize, usize>>>,
+        >,
+    >
+    where
+        for<'a> <<GLM as LoadMode>::Factory<E> as BitReaderFactory<E>>::BitReader<'a>: CodeRead<E>,
+    {
+        let (_, num_arcs, _) =
+            parse_properties::<E>(self.0.basename.with_ext(PROPERTIES_EXTENSION))?;
+        Ok(ArcCountCheck::new(self.0.load()?, num_arcs))
+    }
 }
 
 impl<
@@ -425,7 +589,7 @@ impl<
     /// Load a random-access graph with static dispatch.
     #[allow(clippy::type_complexity)]
     pub fn load(
-        mut self,
+        self,
     ) -> anyhow::Result<
         BvGraph<
             ConstCodesDecoderFactory<
@@ -445,12 +609,22 @@ impl<
         for<'a> <<GLM as LoadMode>::Factory<E> as BitReaderFactory<E>>::BitReader<'a>:
             CodeRead<E> + BitSeek,
     {
-        self.basename.set_extension(PROPERTIES_EXTENSION);
-        let (num_nodes, num_arcs, comp_flags) = parse_properties::<E>(&self.basename)?;
-        self.basename.set_extension(GRAPH_EXTENSION);
-        let factory = GLM::new_factory(&self.basename, self.graph_load_flags)?;
-        self.basename.set_extension(EF_EXTENSION);
-        let offsets = OLM::load_offsets(&self.basename, self.offsets_load_flags)?;
+        let (num_nodes, num_arcs, comp_flags) =
+            parse_properties::<E>(self.basename.with_ext(PROPERTIES_EXTENSION))?;
+        let factory = GLM::new_factory(
+            self.basename.with_ext(GRAPH_EXTENSION),
+            self.graph_load_flags,
+            self.prefetch_bytes,
+        )?;
+        let params = SelectParams::load(&self.basename, EF_SELECT_PROPERTY)?;
+        let offsets = OLM::load_offsets(
+            self.basename.with_ext(EF_EXTENSION),
+            params,
+            self.offsets_load_flags,
+        )?;
+        if self.validate_offsets {
+            check_offsets(&self.basename, &*offsets, num_nodes)?;
+        }
 
         Ok(BvGraph::new(
             ConstCodesDecoderFactory::new(factory, offsets, comp_flags)?,
@@ -484,7 +658,7 @@ impl<
     /// Load a sequential graph with static dispatch.
     #[allow(clippy::type_complexity)]
     pub fn load(
-        mut self,
+        self,
     ) -> anyhow::Result<
         BvGraphSeq<
             ConstCodesDecoderFactory<
@@ -503,10 +677,13 @@ impl<
     where
         for<'a> <<GLM as LoadMode>::Factory<E> as BitReaderFactory<E>>::BitReader<'a>: CodeRead<E>,
     {
-        self.basename.set_extension(PROPERTIES_EXTENSION);
-        let (num_nodes, num_arcs, comp_flags) = parse_properties::<E>(&self.basename)?;
-        self.basename.set_extension(GRAPH_EXTENSION);
-        let factory = GLM::new_factory(&self.basename, self.graph_load_flags)?;
+        let (num_nodes, num_arcs, comp_flags) =
+            parse_properties::<E>(self.basename.with_ext(PROPERTIES_EXTENSION))?;
+        let factory = GLM::new_factory(
+            self.basename.with_ext(GRAPH_EXTENSION),
+            self.graph_load_flags,
+            self.prefetch_bytes,
+        )?;
 
         Ok(BvGraphSeq::new(
             ConstCodesDecoderFactory::new(
@@ -522,9 +699,29 @@ impl<
     }
 }
 
+/// Validates a sample of the offsets of the graph with given basename
+/// against the length of its graph file.
+fn check_offsets(
+    basename: &Basename,
+    offsets: &impl IndexedSeq<Input = usize, Output = usize>,
+    num_nodes: usize,
+) -> Result<()> {
+    let graph_path = basename.with_ext(GRAPH_EXTENSION);
+    let graph_bits = 8 * std::fs::metadata(&graph_path)
+        .with_context(|| format!("Could not read metadata of {}", graph_path.display()))?
+        .len();
+    validate_ef(offsets, num_nodes, graph_bits, LOAD_VALIDATION_SAMPLES).with_context(|| {
+        format!(
+            "The offsets in {} are inconsistent with {} (see LoadConfig::validate_offsets)",
+            basename.with_ext(EF_EXTENSION).display(),
+            graph_path.display()
+        )
+    })
+}
+
 /// Read the .properties file and return the endianness
 pub fn get_endianness<P: AsRef<Path>>(basename: P) -> Result<String> {
-    let path = basename.as_ref().with_extension(PROPERTIES_EXTENSION);
+    let path = Basename::new(basename).with_ext(PROPERTIES_EXTENSION);
     let f = std::fs::File::open(&path)
         .with_context(|| format!("Cannot open property file {}", path.display()))?;
     let map = java_properties::read(BufReader::new(f))
@@ -544,7 +741,16 @@ pub fn parse_properties<E: Endianness>(path: impl AsRef<Path>) -> Result<(usize,
     let name = path.as_ref().display();
     let f = std::fs::File::open(&path)
         .with_context(|| format!("Cannot open property file {}", name))?;
-    let map = java_properties::read(BufReader::new(f))
+    parse_properties_from::<E>(BufReader::new(f), name)
+}
+
+/// Parses properties from a reader, using `name` in error messages, and
+/// returns the number of nodes, number of arcs and compression flags.
+pub(crate) fn parse_properties_from<E: Endianness>(
+    reader: impl std::io::Read,
+    name: impl std::fmt::Display,
+) -> Result<(usize, u64, CompFlags)> {
+    let map = java_properties::read(reader)
         .with_context(|| format!("cannot parse {} as a java properties file", name))?;
 
     let num_nodes = map
@@ -562,3 +768,186 @@ pub fn parse_properties<E: Endianness>(path: impl AsRef<Path>) -> Result<(usize,
         .with_context(|| format!("Cannot parse compression flags from {}", name))?;
     Ok((num_nodes, num_arcs, comp_flags))
 }
+
+/// The semantic direction of the arcs of a graph with respect to a
+/// counterpart graph, as recorded in the `direction` key of the
+/// `.properties` file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArcDirection {
+    /// Arcs point from source to target; the counterpart is the transpose.
+    Forward,
+    /// Arcs point from target to source, that is, the graph is the
+    /// transpose of its counterpart.
+    Backward,
+}
+
+impl ArcDirection {
+    /// The value stored in the `.properties` file.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ArcDirection::Forward => "forward",
+            ArcDirection::Backward => "backward",
+        }
+    }
+}
+
+impl core::str::FromStr for ArcDirection {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "forward" => Ok(ArcDirection::Forward),
+            "backward" => Ok(ArcDirection::Backward),
+            _ => anyhow::bail!("Unknown arc direction {}", s),
+        }
+    }
+}
+
+/// The key of the `.properties` file storing the [arc direction](ArcDirection).
+pub const DIRECTION_PROPERTY: &str = "direction";
+/// The key of the `.properties` file storing the basename of the counterpart
+/// graph (i.e., the graph this graph is the transpose of, or vice versa).
+pub const COUNTERPART_PROPERTY: &str = "counterpart";
+
+/// Read the .properties file and return the arc direction and the basename
+/// of the counterpart graph, if they were recorded.
+///
+/// Graphs that were not marked (e.g., graphs compressed by older versions)
+/// return `None`.
+pub fn get_direction(basename: impl AsRef<Path>) -> Result<Option<(ArcDirection, PathBuf)>> {
+    let path = Basename::new(basename).with_ext(PROPERTIES_EXTENSION);
+    let f = std::fs::File::open(&path)
+        .with_context(|| format!("Cannot open property file {}", path.display()))?;
+    let map = java_properties::read(BufReader::new(f))
+        .with_context(|| format!("cannot parse {} as a java properties file", path.display()))?;
+
+    match (map.get(DIRECTION_PROPERTY), map.get(COUNTERPART_PROPERTY)) {
+        (Some(direction), Some(counterpart)) => Ok(Some((
+            direction
+                .parse()
+                .with_context(|| format!("Cannot parse direction in {}", path.display()))?,
+            PathBuf::from(counterpart),
+        ))),
+        (None, None) => Ok(None),
+        _ => anyhow::bail!(
+            "Only one of '{}' and '{}' is present in {}",
+            DIRECTION_PROPERTY,
+            COUNTERPART_PROPERTY,
+            path.display()
+        ),
+    }
+}
+
+/// Records in the .properties file the arc direction of the graph and the
+/// basename of its counterpart.
+///
+/// The basename of the counterpart is [canonicalized](canonicalize_basename),
+/// so that it can be checked independently of the current directory.
+///
+/// All other keys of the .properties file are preserved.
+pub fn set_direction(
+    basename: impl AsRef<Path>,
+    direction: ArcDirection,
+    counterpart: impl AsRef<Path>,
+) -> Result<()> {
+    let counterpart = canonicalize_basename(counterpart);
+    update_properties(basename, |map| {
+        map.insert(DIRECTION_PROPERTY.to_owned(), direction.as_str().to_owned());
+        map.insert(
+            COUNTERPART_PROPERTY.to_owned(),
+            counterpart.display().to_string(),
+        );
+    })
+}
+
+/// Returns the given basename with its parent directory canonicalized, so
+/// that different paths to the same graph compare equal.
+///
+/// The last component is kept as it is, as a basename is not the path of an
+/// existing file. If the parent directory cannot be canonicalized (e.g.,
+/// because it does not exist), the basename is returned unchanged.
+pub fn canonicalize_basename(basename: impl AsRef<Path>) -> PathBuf {
+    let basename = basename.as_ref();
+    let parent = match basename.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    match (parent.canonicalize(), basename.file_name()) {
+        (Ok(parent), Some(file_name)) => parent.join(file_name),
+        _ => basename.to_owned(),
+    }
+}
+
+/// The key of the `.properties` file recording whether the graph is
+/// loopless.
+pub const LOOPLESS_PROPERTY: &str = "loopless";
+/// The key of the `.properties` file recording whether the graph is
+/// symmetric.
+pub const SYMMETRIC_PROPERTY: &str = "symmetric";
+
+/// Reads the .properties file and returns whether the graph is loopless, or
+/// `None` if this is not known.
+pub fn is_loopless(basename: impl AsRef<Path>) -> Result<Option<bool>> {
+    get_bool_property(basename, LOOPLESS_PROPERTY)
+}
+
+/// Reads the .properties file and returns whether the graph is symmetric, or
+/// `None` if this is not known.
+pub fn is_symmetric(basename: impl AsRef<Path>) -> Result<Option<bool>> {
+    get_bool_property(basename, SYMMETRIC_PROPERTY)
+}
+
+/// Records in the .properties file whether the graph is loopless.
+///
+/// All other keys of the .properties file are preserved.
+pub fn set_loopless(basename: impl AsRef<Path>, loopless: bool) -> Result<()> {
+    update_properties(basename, |map| {
+        map.insert(LOOPLESS_PROPERTY.to_owned(), loopless.to_string());
+    })
+}
+
+/// Records in the .properties file whether the graph is symmetric.
+///
+/// All other keys of the .properties file are preserved.
+pub fn set_symmetric(basename: impl AsRef<Path>, symmetric: bool) -> Result<()> {
+    update_properties(basename, |map| {
+        map.insert(SYMMETRIC_PROPERTY.to_owned(), symmetric.to_string());
+    })
+}
+
+/// Returns the value of a boolean key of the .properties file, or `None` if
+/// the key is missing.
+fn get_bool_property(basename: impl AsRef<Path>, key: &str) -> Result<Option<bool>> {
+    let path = Basename::new(basename).with_ext(PROPERTIES_EXTENSION);
+    let f = std::fs::File::open(&path)
+        .with_context(|| format!("Cannot open property file {}", path.display()))?;
+    let map = java_properties::read(BufReader::new(f))
+        .with_context(|| format!("cannot parse {} as a java properties file", path.display()))?;
+    map.get(key)
+        .map(|value| {
+            value
+                .parse::<bool>()
+                .with_context(|| format!("Cannot parse '{}' as bool in {}", key, path.display()))
+        })
+        .transpose()
+}
+
+/// Applies `update` to the keys of the .properties file and writes it back.
+pub(crate) fn update_properties(
+    basename: impl AsRef<Path>,
+    update: impl FnOnce(&mut HashMap<String, String>),
+) -> Result<()> {
+    let path = Basename::new(basename).with_ext(PROPERTIES_EXTENSION);
+    let f = std::fs::File::open(&path)
+        .with_context(|| format!("Cannot open property file {}", path.display()))?;
+    let mut map = java_properties::read(BufReader::new(f))
+        .with_context(|| format!("cannot parse {} as a java properties file", path.display()))?;
+
+    update(&mut map);
+
+    let f = std::fs::File::create(&path)
+        .with_context(|| format!("Cannot create property file {}", path.display()))?;
+    java_properties::write(std::io::BufWriter::new(f), &map)
+        .with_context(|| format!("Cannot write property file {}", path.display()))?;
+    Ok(())
+}
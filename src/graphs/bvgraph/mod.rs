@@ -19,7 +19,8 @@
 //! little-endian version, too.
 //!
 //! The main access point to the implementation is [`BvGraph::with_basename`],
-//! which provides a [`LoadConfig`] that can be further customized.
+//! which provides a [`LoadConfig`] that can be further customized. Graphs
+//! can also be stored in, and loaded from, a single-file [archive].
 
 use crate::traits::*;
 
@@ -29,10 +30,11 @@ pub const OFFSETS_EXTENSION: &str = "offsets";
 pub const EF_EXTENSION: &str = "ef";
 pub const LABELS_EXTENSION: &str = "labels";
 pub const LABELOFFSETS_EXTENSION: &str = "labeloffsets";
+pub const LABELEF_EXTENSION: &str = "labelef";
 pub const DEG_CUMUL_EXTENSION: &str = "dcf";
 
 mod offset_deg_iter;
-pub use offset_deg_iter::OffsetDegIter;
+pub use offset_deg_iter::{DegreesIter, OffsetDegIter};
 
 pub mod sequential;
 pub use sequential::BvGraphSeq;
@@ -51,25 +53,69 @@ pub use comp::*;
 
 mod load;
 pub use load::*;
+
+mod select_params;
+#[cfg(feature = "cli")]
+pub(crate) use select_params::match_select_params;
+pub use select_params::*;
+
+mod validate;
+pub use validate::*;
+
+mod trace;
+pub use trace::*;
+
+mod degrees;
+pub use degrees::*;
+
+#[cfg(feature = "dict")]
+pub mod dict;
+
+pub mod archive;
+pub use archive::{write_archive, Archive, ArchiveLoadConfig, ARCHIVE_EXTENSION};
 use sux::traits::{IndexedSeq, Types};
 
-/// The default version of EliasFano we use for the CLI.
-pub type EF = sux::dict::EliasFano<
-    sux::rank_sel::SelectAdaptConst<sux::bits::BitVec<Box<[usize]>>, Box<[usize]>, 12, 4>,
+/// An Elias–Fano representation of a monotone sequence (e.g., the offsets of
+/// a graph) with a selection structure on the ones of the high bits with
+/// 2<sup>`L`</sup> ones per inventory entry and 2<sup>`S`</sup> words of
+/// subinventory per inventory entry.
+///
+/// The supported values of `L` and `S` are described by [`SelectParams`].
+pub type EFSelect<const L: usize, const S: usize> = sux::dict::EliasFano<
+    sux::rank_sel::SelectAdaptConst<sux::bits::BitVec<Box<[usize]>>, Box<[usize]>, L, S>,
     sux::bits::BitFieldVec<usize, Box<[usize]>>,
 >;
 
-/// The default version of EliasFano we use for the cumulative function of degrees.
-pub type DCF = sux::dict::EliasFano<
+/// The default version of EliasFano we use for the CLI.
+///
+/// This is the only type used to load `.ef` files whose parameters are not
+/// recorded in the `.properties` file, so its parameters are by definition
+/// the [default ones](SelectParams::default).
+pub type EF = EFSelect<
+    { SelectParams::DEFAULT_LOG2_ONES_PER_INVENTORY },
+    { SelectParams::DEFAULT_LOG2_U64_PER_SUBINVENTORY },
+>;
+
+/// An Elias–Fano representation of a monotone sequence with selection
+/// structures on both the ones and the zeroes of the high bits, using the
+/// same parameters of [`EFSelect`].
+pub type DCFSelect<const L: usize, const S: usize> = sux::dict::EliasFano<
     sux::rank_sel::SelectZeroAdaptConst<
-        sux::rank_sel::SelectAdaptConst<sux::bits::BitVec<Box<[usize]>>, Box<[usize]>, 12, 4>,
+        sux::rank_sel::SelectAdaptConst<sux::bits::BitVec<Box<[usize]>>, Box<[usize]>, L, S>,
         Box<[usize]>,
-        12,
-        4,
+        L,
+        S,
     >,
     sux::bits::BitFieldVec<usize, Box<[usize]>>,
 >;
 
+/// The default version of EliasFano we use for the cumulative function of
+/// degrees, with the [default parameters](SelectParams::default).
+pub type DCF = DCFSelect<
+    { SelectParams::DEFAULT_LOG2_ONES_PER_INVENTORY },
+    { SelectParams::DEFAULT_LOG2_U64_PER_SUBINVENTORY },
+>;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct SliceSeq<O: PartialEq<usize> + PartialEq + Copy, A: AsRef<[O]>>(
     A,
@@ -198,3 +198,59 @@ impl<D: Decode> OffsetDegIter<D> {
         Ok(degree)
     }
 }
+
+/// Fast iterator over the degrees of a [`BvGraph`].
+///
+/// This iterator decodes just the codes needed to compute the outdegree of
+/// each node: successors are not materialized, references are not merged,
+/// and the values of intervals and residuals are read but discarded (their
+/// number is implied by the degree, as the bitstream does not record their
+/// length). Differently from [`OffsetDegIter`], it does not need to know
+/// the current position in the bitstream, so it works with any decoder.
+#[derive(Debug, Clone)]
+pub struct DegreesIter<D: Decode>(OffsetDegIter<D>);
+
+impl<D: Decode> DegreesIter<D> {
+    /// Creates a new iterator over the degrees of the graph.
+    pub fn new(
+        decoder: D,
+        number_of_nodes: usize,
+        compression_window: usize,
+        min_interval_length: usize,
+    ) -> Self {
+        Self(OffsetDegIter::new(
+            decoder,
+            number_of_nodes,
+            compression_window,
+            min_interval_length,
+        ))
+    }
+
+    /// Get the number of nodes in the graph
+    #[inline(always)]
+    pub fn num_nodes(&self) -> usize {
+        self.0.num_nodes()
+    }
+}
+
+impl<D: Decode> Iterator for DegreesIter<D> {
+    type Item = usize;
+    #[inline(always)]
+    fn next(&mut self) -> Option<usize> {
+        if self.0.node_id >= self.0.number_of_nodes {
+            return None;
+        }
+        Some(self.0.next_degree().unwrap())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len();
+        (len, Some(len))
+    }
+}
+
+impl<D: Decode> ExactSizeIterator for DegreesIter<D> {
+    fn len(&self) -> usize {
+        self.0.number_of_nodes - self.0.node_id
+    }
+}
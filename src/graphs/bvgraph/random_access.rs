@@ -11,7 +11,6 @@ use crate::prelude::*;
 use bitflags::Flags;
 use dsi_bitstream::traits::{Endianness, BE};
 use lender::IntoLender;
-use std::path::PathBuf;
 use sux::traits::IndexedSeq;
 
 use self::sequential::Iter;
@@ -31,9 +30,11 @@ impl BvGraph<()> {
         basename: impl AsRef<std::path::Path>,
     ) -> LoadConfig<BE, Random, Dynamic, Mmap, Mmap> {
         LoadConfig {
-            basename: PathBuf::from(basename.as_ref()),
+            basename: Basename::new(basename),
             graph_load_flags: Flags::empty(),
             offsets_load_flags: Flags::empty(),
+            prefetch_bytes: 0,
+            validate_offsets: true,
             _marker: std::marker::PhantomData,
         }
     }
@@ -145,6 +146,133 @@ where
     pub fn into_inner(self) -> F {
         self.factory
     }
+
+    /// Returns the reference offset of a node, that is, the difference
+    /// between the node and the node whose successor list was used as a
+    /// reference to compress its successor list, or zero if no reference was
+    /// used.
+    pub fn reference_offset(&self, node_id: usize) -> usize {
+        let mut codes_reader = self
+            .factory
+            .new_decoder(node_id)
+            .expect("Cannot create reader");
+        if codes_reader.read_outdegree() == 0 || self.compression_window == 0 {
+            0
+        } else {
+            codes_reader.read_reference_offset() as usize
+        }
+    }
+
+    /// Returns the bit offset in the graph bitstream at which the record of
+    /// a node starts.
+    ///
+    /// The offset is read from the offsets of the graph (usually, the
+    /// Elias–Fano representation in the `.ef` file), so it is available only
+    /// on random-access graphs. Passing the number of nodes returns the length
+    /// in bits of the bitstream (excluding padding).
+    ///
+    /// # Panics
+    ///
+    /// If `node` is larger than the number of nodes.
+    #[inline(always)]
+    pub fn node_offset(&self, node: usize) -> u64 {
+        assert!(
+            node <= self.number_of_nodes,
+            "Node {} does not exist (the graph has {} nodes)",
+            node,
+            self.number_of_nodes
+        );
+        self.factory.offset(node)
+    }
+
+    /// Returns the length in bits of the record of a node, computed as the
+    /// difference between the [offsets](BvGraph::node_offset) of the node
+    /// and of the next one.
+    ///
+    /// Note that the record of a node does not contain its successors when
+    /// they are copied from a reference, so this is not a measure of the
+    /// cost of its successor list alone.
+    ///
+    /// # Panics
+    ///
+    /// If `node` is not smaller than the number of nodes.
+    #[inline(always)]
+    pub fn node_bit_len(&self, node: usize) -> u64 {
+        assert!(
+            node < self.number_of_nodes,
+            "Node {} does not exist (the graph has {} nodes)",
+            node,
+            self.number_of_nodes
+        );
+        self.factory.offset(node + 1) - self.factory.offset(node)
+    }
+
+    /// Returns the components of the record of a node, and their values, in
+    /// the order in which they are read.
+    ///
+    /// The record is decoded with a [`TraceDecoder`]; the outdegree of the
+    /// reference, if any, is read too, as it is necessary to know how many
+    /// residuals must be read. Contrarily to
+    /// [`successors`](RandomAccessGraph::successors), this method does not
+    /// panic on inconsistent records, so it can be used to debug corrupted
+    /// graphs.
+    pub fn node_trace(&self, node_id: usize) -> Vec<(Component, u64)> {
+        let mut reader = TraceDecoder::new(
+            self.factory
+                .new_decoder(node_id)
+                .expect("Cannot create reader"),
+        );
+        let degree = reader.read_outdegree() as usize;
+        if degree == 0 {
+            return reader.trace;
+        }
+        let mut nodes_left_to_decode = degree;
+        let ref_delta = if self.compression_window != 0 {
+            reader.read_reference_offset() as usize
+        } else {
+            0
+        };
+        if ref_delta != 0 && ref_delta <= node_id {
+            // the number of copied successors, computed as in MaskedIterator
+            let number_of_blocks = reader.read_block_count() as usize;
+            let mut copied = 0;
+            let mut cumsum_blocks = 0;
+            for i in 0..number_of_blocks {
+                let block = reader.read_block() as usize + (i != 0) as usize;
+                if i % 2 == 0 {
+                    copied += block;
+                }
+                cumsum_blocks += block;
+            }
+            if number_of_blocks.is_multiple_of(2) {
+                copied += self
+                    .outdegree(node_id - ref_delta)
+                    .saturating_sub(cumsum_blocks);
+            }
+            nodes_left_to_decode = nodes_left_to_decode.saturating_sub(copied);
+        }
+
+        if nodes_left_to_decode != 0 && self.min_interval_length != 0 {
+            let number_of_intervals = reader.read_interval_count() as usize;
+            for _ in 0..number_of_intervals {
+                if nodes_left_to_decode == 0 {
+                    // corrupted record
+                    break;
+                }
+                reader.read_interval_start();
+                let len = reader.read_interval_len() as usize + self.min_interval_length;
+                nodes_left_to_decode = nodes_left_to_decode.saturating_sub(len);
+            }
+        }
+
+        if nodes_left_to_decode != 0 {
+            reader.read_first_residual();
+            for _ in 1..nodes_left_to_decode {
+                reader.read_residual();
+            }
+        }
+        reader.trace
+    }
 }
 
 impl<F> SequentialLabeling for BvGraph<F>
@@ -163,6 +291,11 @@ where
         self.number_of_nodes
     }
 
+    #[inline(always)]
+    fn successors_sorted(&self) -> bool {
+        true
+    }
+
     #[inline(always)]
     fn num_arcs_hint(&self) -> Option<u64> {
         Some(self.number_of_arcs)
@@ -360,7 +493,29 @@ where
         )
     }
 }
-impl<F> RandomAccessGraph for BvGraph<F> where F: RandomAccessDecoderFactory {}
+impl<F> RandomAccessGraph for BvGraph<F>
+where
+    F: RandomAccessDecoderFactory,
+{
+    /// Returns the successor of a node of given rank.
+    ///
+    /// This implementation skips intervals in constant time.
+    #[inline(always)]
+    fn succ_select(&self, node_id: usize, k: usize) -> Option<usize> {
+        self.successors(node_id).select(k)
+    }
+
+    /// Returns the number of successors of a node that are smaller than
+    /// `dst_node_id`.
+    ///
+    /// This implementation counts the successors in each interval in
+    /// constant time, and stops decoding residuals and copied successors as
+    /// soon as they are greater than or equal to `dst_node_id`.
+    #[inline(always)]
+    fn succ_rank(&self, node_id: usize, dst_node_id: usize) -> usize {
+        self.successors(node_id).rank(dst_node_id)
+    }
+}
 
 /// The iterator returned from [`BvGraph`] that returns the successors of a
 /// node in sorted order.
@@ -413,6 +568,101 @@ impl<D: Decode> Succ<D> {
     }
 }
 
+impl<D: Decode> Succ<D> {
+    /// Returns the number of values returned by the iterator that are smaller
+    /// than `upper`, consuming the iterator.
+    ///
+    /// Since values are returned in increasing order, the three sources of
+    /// values (copied nodes, intervals and residuals) are examined
+    /// independently: intervals are counted arithmetically, whereas copied
+    /// nodes and residuals are decoded only until they reach `upper`.
+    pub fn rank(mut self, upper: usize) -> usize {
+        if self.size == 0 {
+            return 0;
+        }
+        let mut count = 0;
+
+        // copied nodes
+        let mut copied = self.next_copied_node;
+        while copied < upper {
+            count += 1;
+            copied = self
+                .copied_nodes_iter
+                .as_mut()
+                .and_then(|iter| iter.next())
+                .unwrap_or(usize::MAX);
+        }
+
+        // intervals, excluding the final fake one
+        if !self.intervals.is_empty() {
+            count += (self.next_interval_node < upper) as usize;
+            for &(start, len) in &self.intervals[self.intervals_idx..self.intervals.len() - 1] {
+                if start >= upper {
+                    break;
+                }
+                count += len.min(upper - start);
+            }
+        }
+
+        // residuals
+        let mut residual = self.next_residual_node;
+        while residual < upper {
+            count += 1;
+            if self.residuals_to_go == 0 {
+                break;
+            }
+            self.residuals_to_go -= 1;
+            residual += 1 + self.reader.read_residual() as usize;
+        }
+
+        count
+    }
+
+    /// Returns the `k`-th value (starting from zero) returned by the
+    /// iterator, or `None` if the iterator returns at most `k` values,
+    /// consuming the iterator.
+    ///
+    /// Runs of consecutive values coming from an interval are skipped in
+    /// constant time.
+    pub fn select(mut self, mut k: usize) -> Option<usize> {
+        if k >= self.size {
+            return None;
+        }
+        loop {
+            let other = self.next_residual_node.min(self.next_copied_node);
+            if self.next_interval_node < other {
+                // The run of interval values starting at the next one: if the
+                // current interval has more values, they start right after
+                let head = self.next_interval_node;
+                let (start, len) = self.intervals[self.intervals_idx];
+                let rest = if start == head + 1 { len } else { 0 };
+                let avail = (rest + 1).min(other - head);
+                if k < avail {
+                    return Some(head + k);
+                }
+                // skip avail - 1 values arithmetically, and then the last one
+                // using the iterator, so to set up the next interval value
+                let skip = avail - 1;
+                if skip != 0 {
+                    let (start, len) = &mut self.intervals[self.intervals_idx];
+                    *start += skip;
+                    *len -= skip;
+                    self.next_interval_node += skip;
+                    self.intervals_idx += (*len == 0) as usize;
+                    self.size -= skip;
+                }
+                self.next();
+                k -= avail;
+            } else if k == 0 {
+                return self.next();
+            } else {
+                self.next();
+                k -= 1;
+            }
+        }
+    }
+}
+
 impl<D: Decode> Iterator for Succ<D> {
     type Item = usize;
 
@@ -465,6 +715,11 @@ impl<D: Decode> Iterator for Succ<D> {
 
         Some(min)
     }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.size, Some(self.size))
+    }
 }
 
 impl<'a, F: RandomAccessDecoderFactory> IntoLender for &'a BvGraph<F> {
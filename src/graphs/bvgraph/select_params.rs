@@ -0,0 +1,457 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use super::*;
+use crate::utils::Basename;
+use anyhow::{bail, Context, Result};
+use epserde::prelude::*;
+use std::collections::HashMap;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
+use sux::dict::EliasFano;
+use sux::prelude::{SelectAdaptConst, SelectZeroAdaptConst};
+use sux::traits::{IndexedSeq, Types};
+
+/// The key of the `.properties` file storing the [parameters](SelectParams)
+/// of the selection structure of the `.ef` file.
+pub const EF_SELECT_PROPERTY: &str = "efselect";
+/// The key of the `.properties` file storing the [parameters](SelectParams)
+/// of the selection structures of the `.dcf` file.
+pub const DCF_SELECT_PROPERTY: &str = "dcfselect";
+
+/// Evaluates an expression with the constants `$l` and `$s` set to the
+/// values of the given [`SelectParams`].
+///
+/// The expression is thus monomorphized once for each supported
+/// parameterization, and the variant to use is selected at runtime.
+macro_rules! match_select_params {
+    ($params:expr, $l:ident, $s:ident => $e:expr) => {
+        match $params {
+            $crate::graphs::bvgraph::SelectParams::L12S4 => {
+                const $l: usize = 12;
+                const $s: usize = 4;
+                $e
+            }
+            $crate::graphs::bvgraph::SelectParams::L10S2 => {
+                const $l: usize = 10;
+                const $s: usize = 2;
+                $e
+            }
+            $crate::graphs::bvgraph::SelectParams::L8S1 => {
+                const $l: usize = 8;
+                const $s: usize = 1;
+                $e
+            }
+        }
+    };
+}
+#[cfg(feature = "cli")]
+pub(crate) use match_select_params;
+
+/// Evaluates an expression returning a [`MemCase`] with the type `$t` set to
+/// the [`EFSelect`] type corresponding to the given [`SelectParams`], and
+/// wraps the result in the corresponding variant of a [`SelectDispatch`].
+macro_rules! select_dispatch {
+    ($params:expr, $t:ident => $e:expr) => {
+        match $params {
+            $crate::graphs::bvgraph::SelectParams::L12S4 => {
+                type $t = $crate::graphs::bvgraph::EFSelect<12, 4>;
+                $crate::graphs::bvgraph::SelectDispatch::L12S4($e)
+            }
+            $crate::graphs::bvgraph::SelectParams::L10S2 => {
+                type $t = $crate::graphs::bvgraph::EFSelect<10, 2>;
+                $crate::graphs::bvgraph::SelectDispatch::L10S2($e)
+            }
+            $crate::graphs::bvgraph::SelectParams::L8S1 => {
+                type $t = $crate::graphs::bvgraph::EFSelect<8, 1>;
+                $crate::graphs::bvgraph::SelectDispatch::L8S1($e)
+            }
+        }
+    };
+}
+pub(crate) use select_dispatch;
+
+/// The supported parameters of the selection structures of [`EFSelect`] and
+/// [`DCFSelect`].
+///
+/// Each variant `LxSy` uses 2<sup>_x_</sup> ones per inventory entry and
+/// 2<sup>_y_</sup> words of subinventory per inventory entry. Every
+/// inventory entry is stored together with its subinventory, so the number
+/// of cache lines (or pages) touched by a selection depends on the size of
+/// such a block.
+///
+/// - [`L12S4`](SelectParams::L12S4) (the default) uses about 0.27 bits per
+///   element, but blocks span 17 words, so the inventory entry and the
+///   subinventory entry used by a selection are usually in different cache
+///   lines. It is the recommended setting for indices resident in RAM.
+/// - [`L10S2`](SelectParams::L10S2) uses about the same space with blocks
+///   of five words, so that in most cases a selection touches a single cache
+///   line (or page) of the index before accessing the high bits. It is the
+///   recommended setting for indices accessed on cold caches or resident on
+///   disk (i.e., memory-mapped and not in the page cache).
+/// - [`L8S1`](SelectParams::L8S1) uses about 0.75 bits per element with
+///   blocks of three words, further improving locality at the cost of
+///   more space.
+///
+/// The parameters used to build an index are recorded in the `.properties`
+/// file (see [`EF_SELECT_PROPERTY`] and [`DCF_SELECT_PROPERTY`]), and
+/// loaders dispatch at runtime among the corresponding monomorphized types;
+/// indices without such a record use the default parameters. Use the `bench
+/// offsets` command of the CLI to measure the speed of each variant on a
+/// specific graph.
+///
+/// The textual representation of the parameters is `L,S` (e.g., `12,4`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum SelectParams {
+    #[default]
+    L12S4,
+    L10S2,
+    L8S1,
+}
+
+impl SelectParams {
+    /// The base-2 logarithm of the number of ones per inventory entry of the
+    /// default parameters, which are those of [`EF`](super::EF) and
+    /// [`DCF`](super::DCF).
+    pub const DEFAULT_LOG2_ONES_PER_INVENTORY: usize = 12;
+
+    /// The base-2 logarithm of the number of words of subinventory per
+    /// inventory entry of the default parameters, which are those of
+    /// [`EF`](super::EF) and [`DCF`](super::DCF).
+    pub const DEFAULT_LOG2_U64_PER_SUBINVENTORY: usize = 4;
+
+    /// All supported parameters.
+    pub const ALL: [SelectParams; 3] =
+        [SelectParams::L12S4, SelectParams::L10S2, SelectParams::L8S1];
+
+    /// Returns the base-2 logarithm of the number of ones per inventory entry.
+    pub fn log2_ones_per_inventory(self) -> usize {
+        match self {
+            SelectParams::L12S4 => 12,
+            SelectParams::L10S2 => 10,
+            SelectParams::L8S1 => 8,
+        }
+    }
+
+    /// Returns the base-2 logarithm of the number of words of subinventory
+    /// per inventory entry.
+    pub fn log2_u64_per_subinventory(self) -> usize {
+        match self {
+            SelectParams::L12S4 => 4,
+            SelectParams::L10S2 => 2,
+            SelectParams::L8S1 => 1,
+        }
+    }
+
+    /// Returns the parameters stored under the given key in a map of
+    /// properties, or the default parameters if the key is missing.
+    pub fn from_properties_map(map: &HashMap<String, String>, key: &str) -> Result<Self> {
+        match map.get(key) {
+            Some(value) => value
+                .parse()
+                .with_context(|| format!("Cannot parse property '{}'", key)),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Returns the parameters stored under the given key in the
+    /// `.properties` file of a graph, or the default parameters if the key
+    /// or the file are missing.
+    pub fn load(basename: impl AsRef<Path>, key: &str) -> Result<Self> {
+        let path = Basename::new(basename).with_ext(PROPERTIES_EXTENSION);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let f = std::fs::File::open(&path)
+            .with_context(|| format!("Cannot open property file {}", path.display()))?;
+        let map = java_properties::read(BufReader::new(f)).with_context(|| {
+            format!("cannot parse {} as a java properties file", path.display())
+        })?;
+        Self::from_properties_map(&map, key).with_context(|| format!("In {}", path.display()))
+    }
+
+    /// Records these parameters under the given key in the `.properties` file
+    /// of a graph.
+    ///
+    /// All other keys of the `.properties` file are preserved. Since missing
+    /// parameters are interpreted as default parameters, nothing is written
+    /// for the default parameters if the key or the file are missing;
+    /// non-default parameters require an existing file.
+    pub fn store(self, basename: impl AsRef<Path>, key: &str) -> Result<()> {
+        let path = Basename::new(basename).with_ext(PROPERTIES_EXTENSION);
+        if !path.exists() {
+            if self == Self::default() {
+                return Ok(());
+            }
+            bail!(
+                "Cannot record select parameters {} as {} does not exist",
+                self,
+                path.display()
+            );
+        }
+        let f = std::fs::File::open(&path)
+            .with_context(|| format!("Cannot open property file {}", path.display()))?;
+        let mut map = java_properties::read(BufReader::new(f)).with_context(|| {
+            format!("cannot parse {} as a java properties file", path.display())
+        })?;
+        if self == Self::default() && !map.contains_key(key) {
+            return Ok(());
+        }
+        map.insert(key.to_owned(), self.to_string());
+        let f = std::fs::File::create(&path)
+            .with_context(|| format!("Cannot create property file {}", path.display()))?;
+        java_properties::write(BufWriter::new(f), &map)
+            .with_context(|| format!("Cannot write property file {}", path.display()))?;
+        Ok(())
+    }
+
+    /// Builds a selection structure with these parameters on the high bits of
+    /// an Elias–Fano representation, and serializes the resulting
+    /// [`EFSelect`].
+    pub fn serialize_ef(self, ef: EliasFano, writer: &mut impl Write) -> Result<usize> {
+        Ok(match_select_params!(self, L, S => {
+            let ef: EFSelect<L, S> =
+                unsafe { ef.map_high_bits(SelectAdaptConst::<_, _, L, S>::new) };
+            ef.serialize(writer)?
+        }))
+    }
+
+    /// Builds selection structures with these parameters on the ones and on
+    /// the zeroes of the high bits of an Elias–Fano representation, and
+    /// serializes the resulting [`DCFSelect`].
+    pub fn serialize_dcf(self, ef: EliasFano, writer: &mut impl Write) -> Result<usize> {
+        Ok(match_select_params!(self, L, S => {
+            let ef: DCFSelect<L, S> = unsafe {
+                ef.map_high_bits(|bits| {
+                    SelectZeroAdaptConst::<_, _, L, S>::new(SelectAdaptConst::<_, _, L, S>::new(
+                        bits,
+                    ))
+                })
+            };
+            ef.serialize(writer)?
+        }))
+    }
+}
+
+impl core::fmt::Display for SelectParams {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{},{}",
+            self.log2_ones_per_inventory(),
+            self.log2_u64_per_subinventory()
+        )
+    }
+}
+
+impl core::str::FromStr for SelectParams {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        Self::ALL
+            .into_iter()
+            .find(|params| params.to_string() == s)
+            .with_context(|| {
+                format!(
+                    "Unsupported select parameters {} (supported: {})",
+                    s,
+                    Self::ALL.map(|params| params.to_string()).join("; ")
+                )
+            })
+    }
+}
+
+/// An indexed sequence whose type is chosen at runtime among one type for
+/// each supported [`SelectParams`].
+///
+/// This is the type of the offsets of graphs loaded by [`LoadConfig`]: the
+/// type of the selection structure of the `.ef` file is chosen using the
+/// parameters recorded in the `.properties` file. Each access dispatches on
+/// the variant, which is a perfectly predictable branch.
+pub enum SelectDispatch<A, B, C> {
+    L12S4(MemCase<A>),
+    L10S2(MemCase<B>),
+    L8S1(MemCase<C>),
+}
+
+/// The fully deserialized offsets loaded by the [`File`] load mode.
+pub type DispatchEF = SelectDispatch<EFSelect<12, 4>, EFSelect<10, 2>, EFSelect<8, 1>>;
+
+/// The ε-copy deserialized offsets loaded by the [`Mmap`], [`LoadMem`] and
+/// [`LoadMmap`] load modes.
+pub type DispatchEFDeser = SelectDispatch<
+    DeserType<'static, EFSelect<12, 4>>,
+    DeserType<'static, EFSelect<10, 2>>,
+    DeserType<'static, EFSelect<8, 1>>,
+>;
+
+impl<A, B, C> SelectDispatch<A, B, C> {
+    /// Returns the parameters of the selection structure.
+    pub fn params(&self) -> SelectParams {
+        match self {
+            SelectDispatch::L12S4(_) => SelectParams::L12S4,
+            SelectDispatch::L10S2(_) => SelectParams::L10S2,
+            SelectDispatch::L8S1(_) => SelectParams::L8S1,
+        }
+    }
+}
+
+impl<A, B, C> core::fmt::Debug for SelectDispatch<A, B, C> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("SelectDispatch")
+            .field(&self.params())
+            .finish()
+    }
+}
+
+impl<A, B, C> Types for SelectDispatch<A, B, C> {
+    type Input = usize;
+    type Output = usize;
+}
+
+impl<A, B, C> IndexedSeq for SelectDispatch<A, B, C>
+where
+    A: IndexedSeq<Input = usize, Output = usize> + Send + Sync,
+    B: IndexedSeq<Input = usize, Output = usize> + Send + Sync,
+    C: IndexedSeq<Input = usize, Output = usize> + Send + Sync,
+{
+    #[inline(always)]
+    unsafe fn get_unchecked(&self, index: usize) -> usize {
+        match self {
+            SelectDispatch::L12S4(seq) => seq.get_unchecked(index),
+            SelectDispatch::L10S2(seq) => seq.get_unchecked(index),
+            SelectDispatch::L8S1(seq) => seq.get_unchecked(index),
+        }
+    }
+
+    #[inline(always)]
+    fn len(&self) -> usize {
+        match self {
+            SelectDispatch::L12S4(seq) => seq.len(),
+            SelectDispatch::L10S2(seq) => seq.len(),
+            SelectDispatch::L8S1(seq) => seq.len(),
+        }
+    }
+}
+
+/// An iterator on the values of a [`SelectDispatch`].
+pub struct SelectDispatchIter<'a, A, B, C> {
+    seq: &'a SelectDispatch<A, B, C>,
+    next: usize,
+}
+
+impl<A, B, C> Iterator for SelectDispatchIter<'_, A, B, C>
+where
+    SelectDispatch<A, B, C>: IndexedSeq<Input = usize, Output = usize>,
+{
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.next == self.seq.len() {
+            return None;
+        }
+        let value = unsafe { self.seq.get_unchecked(self.next) };
+        self.next += 1;
+        Some(value)
+    }
+}
+
+impl<'a, A, B, C> IntoIterator for &'a SelectDispatch<A, B, C>
+where
+    SelectDispatch<A, B, C>: IndexedSeq<Input = usize, Output = usize>,
+{
+    type Item = usize;
+    type IntoIter = SelectDispatchIter<'a, A, B, C>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        SelectDispatchIter { seq: self, next: 0 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sux::dict::EliasFanoBuilder;
+
+    fn build(values: &[usize]) -> EliasFano {
+        let mut efb = EliasFanoBuilder::new(values.len(), *values.last().unwrap());
+        for &value in values {
+            efb.push(value);
+        }
+        efb.build()
+    }
+
+    #[test]
+    fn test_parse() -> Result<()> {
+        for params in SelectParams::ALL {
+            assert_eq!(params.to_string().parse::<SelectParams>()?, params);
+        }
+        assert_eq!(" 10,2".parse::<SelectParams>()?, SelectParams::L10S2);
+        assert!("12,3".parse::<SelectParams>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_default() {
+        // EF and DCF must be the types of the default parameters, as indices
+        // without a record of their parameters are loaded as such
+        let params = SelectParams::default();
+        assert_eq!(
+            params.log2_ones_per_inventory(),
+            SelectParams::DEFAULT_LOG2_ONES_PER_INVENTORY
+        );
+        assert_eq!(
+            params.log2_u64_per_subinventory(),
+            SelectParams::DEFAULT_LOG2_U64_PER_SUBINVENTORY
+        );
+    }
+
+    #[test]
+    fn test_round_trip() -> Result<()> {
+        let values = (0..10_000).map(|x| x * 7 + x % 7).collect::<Vec<_>>();
+        let dir = tempfile::tempdir()?;
+        let basename = dir.path().join("test");
+        let path = basename.with_extension(EF_EXTENSION);
+        std::fs::write(basename.with_extension(PROPERTIES_EXTENSION), "nodes=0\n")?;
+
+        for params in SelectParams::ALL {
+            let mut writer = BufWriter::new(std::fs::File::create(&path)?);
+            params.serialize_ef(build(&values), &mut writer)?;
+            writer.flush()?;
+            drop(writer);
+            params.store(&basename, EF_SELECT_PROPERTY)?;
+            assert_eq!(SelectParams::load(&basename, EF_SELECT_PROPERTY)?, params);
+
+            let ef = Mmap::load_offsets(&path, params, MemoryFlags::empty())?;
+            assert_eq!(ef.params(), params);
+            assert_eq!(ef.len(), values.len());
+            assert_eq!(ef.into_iter().collect::<Vec<_>>(), values);
+
+            let ef = File::load_offsets(&path, params, MemoryFlags::empty())?;
+            assert_eq!(ef.params(), params);
+            for (i, &value) in values.iter().enumerate() {
+                assert_eq!(ef.get(i), value);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_dcf_round_trip() -> Result<()> {
+        let values = (0..1000).map(|x| x * x).collect::<Vec<_>>();
+        for params in SelectParams::ALL {
+            let mut bytes = vec![];
+            params.serialize_dcf(build(&values), &mut bytes)?;
+            let ok = match_select_params!(params, L, S => {
+                let dcf = DCFSelect::<L, S>::deserialize_full(&mut bytes.as_slice())?;
+                (0..values.len()).all(|i| dcf.get(i) == values[i])
+            });
+            assert!(ok);
+        }
+        Ok(())
+    }
+}
@@ -5,7 +5,7 @@
  * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
  */
 
-use std::path::PathBuf;
+use crate::utils::Basename;
 
 use super::*;
 use crate::utils::nat2int;
@@ -16,6 +16,15 @@ use dsi_bitstream::traits::BitSeek;
 use dsi_bitstream::traits::BE;
 use lender::*;
 
+/// The minimum number of residuals of a node (excluding the first one) for
+/// which the [sequential iterator](Iter) decodes residuals in batches using
+/// [`Decode::read_residuals_into`].
+pub const RESIDUALS_BATCH_THRESHOLD: usize = 16;
+
+/// The maximum number of residuals decoded by a single call to
+/// [`Decode::read_residuals_into`] in the [sequential iterator](Iter).
+pub const RESIDUALS_BATCH_SIZE: usize = 64;
+
 /// A sequential BvGraph that can be read from a `codes_reader_builder`.
 /// The builder is needed because we should be able to create multiple iterators
 /// and this allows us to have a single place where to store the mmapped file.
@@ -33,9 +42,11 @@ impl BvGraphSeq<()> {
         basename: impl AsRef<std::path::Path>,
     ) -> LoadConfig<BE, Sequential, Dynamic, Mmap, Mmap> {
         LoadConfig {
-            basename: PathBuf::from(basename.as_ref()),
+            basename: Basename::new(basename),
             graph_load_flags: Flags::empty(),
             offsets_load_flags: Flags::empty(),
+            prefetch_bytes: 0,
+            validate_offsets: true,
             _marker: std::marker::PhantomData,
         }
     }
@@ -72,6 +83,11 @@ impl<F: SequentialDecoderFactory> SequentialLabeling for BvGraphSeq<F> {
         self.number_of_nodes
     }
 
+    #[inline(always)]
+    fn successors_sorted(&self) -> bool {
+        true
+    }
+
     #[inline(always)]
     fn num_arcs_hint(&self) -> Option<u64> {
         self.number_of_arcs
@@ -160,6 +176,21 @@ where
             self.min_interval_length,
         )
     }
+
+    #[inline(always)]
+    /// Creates an iterator over the outdegrees of the nodes.
+    ///
+    /// This is much faster than a full scan of the graph, as successors are
+    /// not materialized, and it does not require the offsets or a seekable
+    /// decoder.
+    pub fn degrees_iter(&self) -> DegreesIter<F::Decoder<'_>> {
+        DegreesIter::new(
+            self.factory.new_decoder().unwrap(),
+            self.number_of_nodes,
+            self.compression_window,
+            self.min_interval_length,
+        )
+    }
 }
 
 /// A fast sequential iterator over the nodes of the graph and their successors.
@@ -296,9 +327,24 @@ impl<D: Decode> Iter<D> {
             let mut extra = (node_id as i64 + node_id_offset) as usize;
             results.push(extra);
             // decode the successive extra nodes
-            for _ in 1..nodes_left_to_decode {
-                extra += 1 + self.decoder.read_residual() as usize;
-                results.push(extra);
+            let mut residuals_to_go = nodes_left_to_decode - 1;
+            if residuals_to_go >= RESIDUALS_BATCH_THRESHOLD {
+                // amortize the cost of dynamic dispatch over many residuals
+                let mut buf = [0; RESIDUALS_BATCH_SIZE];
+                while residuals_to_go != 0 {
+                    let len = residuals_to_go.min(RESIDUALS_BATCH_SIZE);
+                    let read = self.decoder.read_residuals_into(&mut buf[..len]);
+                    for &residual in &buf[..read] {
+                        extra += 1 + residual as usize;
+                        results.push(extra);
+                    }
+                    residuals_to_go -= read;
+                }
+            } else {
+                for _ in 0..residuals_to_go {
+                    extra += 1 + self.decoder.read_residual() as usize;
+                    results.push(extra);
+                }
             }
         }
 
@@ -0,0 +1,127 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+//! Tools to localize mismatches between two compressed versions of the same
+//! graph (e.g., when debugging encoders or decoders).
+
+use crate::prelude::*;
+use core::fmt::{self, Display, Formatter};
+use dsi_progress_logger::prelude::*;
+use lender::*;
+
+/// Returns the first node whose successors differ in two graphs, or `None`
+/// if the graphs have the same successor lists.
+///
+/// If one graph has fewer nodes than the other, and their common nodes have
+/// the same successors, the number of nodes of the smaller graph is
+/// returned.
+pub fn first_differing_node(
+    a: &impl SequentialGraph,
+    b: &impl SequentialGraph,
+    pl: &mut impl ProgressLog,
+) -> Option<usize> {
+    pl.item_name("node")
+        .expected_updates(Some(a.num_nodes().min(b.num_nodes())));
+    pl.start("Comparing successor lists...");
+    let mut a_iter = a.iter();
+    let mut b_iter = b.iter();
+    let mut node = 0;
+    let result = loop {
+        match (a_iter.next(), b_iter.next()) {
+            (None, None) => break None,
+            (Some(a_lend), Some(b_lend)) => {
+                let (_, a_succ) = a_lend.into_pair();
+                let (_, b_succ) = b_lend.into_pair();
+                if !a_succ.into_iter().eq(b_succ) {
+                    break Some(node);
+                }
+            }
+            _ => break Some(node),
+        }
+        node += 1;
+        pl.light_update();
+    };
+    pl.done();
+    result
+}
+
+/// The comparison of the [traces](BvGraph::node_trace) of the same node in
+/// two graphs, returned by [`diff_node_trace`].
+///
+/// The [`Display`] implementation prints the two traces side by side,
+/// marking with `*` the differing components.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceDiff {
+    /// The node.
+    pub node: usize,
+    /// The trace of the node in the first graph.
+    pub a: Vec<(Component, u64)>,
+    /// The trace of the node in the second graph.
+    pub b: Vec<(Component, u64)>,
+}
+
+impl TraceDiff {
+    /// Returns the index in the traces of the first differing component, or
+    /// `None` if the traces are equal.
+    pub fn first_difference(&self) -> Option<usize> {
+        (0..self.a.len().max(self.b.len())).find(|&i| self.a.get(i) != self.b.get(i))
+    }
+
+    /// Returns the first differing component, as found in the first trace
+    /// (or in the second one, if the first one is shorter), or `None` if the
+    /// traces are equal.
+    pub fn first_differing_component(&self) -> Option<Component> {
+        self.first_difference()
+            .map(|i| self.a.get(i).or(self.b.get(i)).unwrap().0)
+    }
+}
+
+impl Display for TraceDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let entry = |trace: &[(Component, u64)], i: usize| {
+            trace
+                .get(i)
+                .map_or_else(String::new, |(c, v)| format!("{}: {}", c, v))
+        };
+        writeln!(f, "Trace of node {}", self.node)?;
+        writeln!(f, "  {:<32} second graph", "first graph")?;
+        for i in 0..self.a.len().max(self.b.len()) {
+            let mark = if self.a.get(i) != self.b.get(i) {
+                '*'
+            } else {
+                ' '
+            };
+            writeln!(
+                f,
+                "{} {:<32} {}",
+                mark,
+                entry(&self.a, i),
+                entry(&self.b, i)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Decodes the record of a node in two graphs with a [`TraceDecoder`] and
+/// compares the resulting traces.
+///
+/// This function is useful to find which component of the record of a node
+/// was encoded or decoded incorrectly, once the node has been located, for
+/// example, by [`first_differing_node`]. Note that the traces of two
+/// graphs compressed with different parameters might differ even if the
+/// successors are the same.
+pub fn diff_node_trace<A: RandomAccessDecoderFactory, B: RandomAccessDecoderFactory>(
+    a: &BvGraph<A>,
+    b: &BvGraph<B>,
+    node: usize,
+) -> TraceDiff {
+    TraceDiff {
+        node,
+        a: a.node_trace(node),
+        b: b.node_trace(node),
+    }
+}
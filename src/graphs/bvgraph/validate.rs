@@ -0,0 +1,188 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+//! Validation of deserialized `.ef` and `.dcf` files.
+//!
+//! ε-serde checks the type and the endianness of serialized structures, but
+//! a file that has been truncated, or that has been overwritten by a file for
+//! a different graph, might still deserialize to a structure returning
+//! garbage. The functions in this module check the structural invariants of
+//! the offsets and of the degree cumulative function of a graph, reporting
+//! the first violated invariant and the first offending index.
+
+use anyhow::{ensure, Result};
+use sux::traits::IndexedSeq;
+
+/// The number of values checked by [`validate_ef`] when loading a graph
+/// with [`LoadConfig`](super::LoadConfig).
+pub const LOAD_VALIDATION_SAMPLES: usize = 1024;
+
+/// The maximum number of padding bits at the end of a graph file.
+///
+/// Compressors pad the bitstream to a 64-bit word, and the `pad` command
+/// can align files to 128 bits.
+pub const MAX_GRAPH_PADDING_BITS: u64 = 128;
+
+/// Checks the structural invariants of the offsets of a graph.
+///
+/// The offsets must contain `num_nodes` + 1 values, start from zero, be
+/// nondecreasing, and their last value must be at most `graph_bits`, the
+/// length in bits of the graph file, and larger than `graph_bits` minus
+/// [`MAX_GRAPH_PADDING_BITS`].
+///
+/// Monotonicity is checked on at most `samples` values, evenly spaced and
+/// always including the first and the last one; pass `usize::MAX` to check
+/// all values. When sampling, the reported index is the first sampled index
+/// at which monotonicity fails.
+pub fn validate_ef(
+    ef: &impl IndexedSeq<Input = usize, Output = usize>,
+    num_nodes: usize,
+    graph_bits: u64,
+    samples: usize,
+) -> Result<()> {
+    validate_cumulative(ef, "offsets", num_nodes, samples)?;
+    let last = ef.get(num_nodes) as u64;
+    ensure!(
+        last <= graph_bits && graph_bits - last < MAX_GRAPH_PADDING_BITS,
+        "Invalid offsets: last-value invariant violated at index {}: the last offset is {}, but the graph is {} bits long",
+        num_nodes,
+        last,
+        graph_bits
+    );
+    Ok(())
+}
+
+/// Checks the structural invariants of the degree cumulative function of a
+/// graph.
+///
+/// The function must contain `num_nodes` + 1 values, start from zero, be
+/// nondecreasing, and its last value must be `num_arcs`.
+///
+/// Monotonicity is checked as in [`validate_ef`].
+pub fn validate_dcf(
+    dcf: &impl IndexedSeq<Input = usize, Output = usize>,
+    num_nodes: usize,
+    num_arcs: u64,
+    samples: usize,
+) -> Result<()> {
+    validate_cumulative(dcf, "degree cumulative function", num_nodes, samples)?;
+    let last = dcf.get(num_nodes) as u64;
+    ensure!(
+        last == num_arcs,
+        "Invalid degree cumulative function: last-value invariant violated at index {}: the last value is {}, but the graph has {} arcs",
+        num_nodes,
+        last,
+        num_arcs
+    );
+    Ok(())
+}
+
+/// Checks the length, the first value and (on a sample) the monotonicity
+/// of a sequence of `num_nodes` + 1 cumulative values.
+fn validate_cumulative(
+    seq: &impl IndexedSeq<Input = usize, Output = usize>,
+    what: &str,
+    num_nodes: usize,
+    samples: usize,
+) -> Result<()> {
+    let len = seq.len();
+    ensure!(
+        len == num_nodes + 1,
+        "Invalid {}: length invariant violated at index {}: there are {} values, but the graph has {} nodes",
+        what,
+        len.min(num_nodes + 1),
+        len,
+        num_nodes
+    );
+    let first = seq.get(0);
+    ensure!(
+        first == 0,
+        "Invalid {}: first-value invariant violated at index 0: the first value is {} instead of 0",
+        what,
+        first
+    );
+
+    let last_index = len - 1;
+    let step = Ord::max(1, last_index / Ord::max(1, samples.saturating_sub(1)));
+    let (mut prev_index, mut prev) = (0, first);
+    for index in (step..last_index).step_by(step).chain([last_index]) {
+        let value = seq.get(index);
+        ensure!(
+            value >= prev,
+            "Invalid {}: monotonicity invariant violated at index {}: the value is {}, but the value at index {} is {}",
+            what,
+            index,
+            value,
+            prev_index,
+            prev
+        );
+        (prev_index, prev) = (index, value);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphs::bvgraph::SliceSeq;
+
+    fn check(values: &[usize], samples: usize) -> Result<()> {
+        validate_dcf(
+            &SliceSeq::new(values),
+            values.len() - 1,
+            *values.last().unwrap() as u64,
+            samples,
+        )
+    }
+
+    #[test]
+    fn test_validate() -> Result<()> {
+        let values = (0..1000).map(|x| x * 2).collect::<Vec<_>>();
+        check(&values, usize::MAX)?;
+        check(&values, 10)?;
+        validate_ef(&SliceSeq::new(&values[..]), 999, 1998, usize::MAX)?;
+        validate_ef(&SliceSeq::new(&values[..]), 999, 2000, usize::MAX)?;
+
+        let err = validate_ef(&SliceSeq::new(&values[..]), 1000, 1998, usize::MAX).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("length invariant violated at index 1000"));
+        let err = validate_ef(&SliceSeq::new(&values[..]), 999, 1000, usize::MAX).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("last-value invariant violated at index 999"));
+        let err = validate_ef(&SliceSeq::new(&values[..]), 999, 4000, usize::MAX).unwrap_err();
+        assert!(err.to_string().contains("last-value invariant"));
+        let err = validate_dcf(&SliceSeq::new(&values[..]), 999, 1000, usize::MAX).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("last-value invariant violated at index 999"));
+
+        let mut corrupted = values.clone();
+        corrupted[0] = 1;
+        let err = check(&corrupted, usize::MAX).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("first-value invariant violated at index 0"));
+
+        let mut corrupted = values.clone();
+        corrupted[500] = 0;
+        let err = check(&corrupted, usize::MAX).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("monotonicity invariant violated at index 500"));
+        // Sampling every 111 values misses the corruption, but not one
+        // spanning several samples
+        check(&corrupted, 10)?;
+        corrupted[..500].fill(5000);
+        corrupted[0] = 0;
+        let err = check(&corrupted, 10).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("monotonicity invariant violated at index 555"));
+        Ok(())
+    }
+}
@@ -0,0 +1,260 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+//! A wrapper exhibiting the concatenation of any number of graphs.
+
+use crate::prelude::*;
+use lender::*;
+
+/// A wrapper exhibiting the concatenation of any number of graphs, that is,
+/// their disjoint union.
+///
+/// Unlike [`UnionGraph`] and [`MergeGraphs`], which merge graphs on the same
+/// set of nodes, this wrapper puts the nodes of the graphs one after the
+/// other: the nodes of the *i*-th graph are shifted by the sum of the number
+/// of nodes of the previous graphs (see [`offsets`](ConcatGraphs::offsets)),
+/// and so are their successors. It is useful, for example, to assemble a
+/// graph from independently numbered shards.
+///
+/// The concatenation is lazy: successors are shifted on the fly while
+/// iterating on the graphs in sequence.
+#[derive(Debug, Clone)]
+pub struct ConcatGraphs<G> {
+    graphs: Vec<G>,
+    /// The first node of each graph, followed by the number of nodes.
+    offsets: Vec<usize>,
+}
+
+impl<G: SequentialGraph> ConcatGraphs<G> {
+    /// Creates the concatenation of the given graphs.
+    pub fn new(graphs: Vec<G>) -> Self {
+        let mut offsets = Vec::with_capacity(graphs.len() + 1);
+        offsets.push(0);
+        for graph in &graphs {
+            offsets.push(offsets.last().unwrap() + graph.num_nodes());
+        }
+        Self { graphs, offsets }
+    }
+
+    /// Returns the first node of each graph in the concatenation, followed by
+    /// the number of nodes of the concatenation.
+    pub fn offsets(&self) -> &[usize] {
+        &self.offsets
+    }
+
+    /// Returns the concatenated graphs.
+    pub fn into_inner(self) -> Vec<G> {
+        self.graphs
+    }
+}
+
+/// Returns the [concatenation](ConcatGraphs) of the given graphs.
+pub fn concat_graphs<G: SequentialGraph>(graphs: impl IntoIterator<Item = G>) -> ConcatGraphs<G> {
+    ConcatGraphs::new(graphs.into_iter().collect())
+}
+
+impl<G: SequentialGraph> SequentialLabeling for ConcatGraphs<G> {
+    type Label = usize;
+    type Lender<'b>
+        = Iter<G::Lender<'b>>
+    where
+        Self: 'b;
+
+    #[inline(always)]
+    fn num_nodes(&self) -> usize {
+        *self.offsets.last().unwrap()
+    }
+
+    #[inline(always)]
+    fn successors_sorted(&self) -> bool {
+        self.graphs.iter().all(G::successors_sorted)
+    }
+
+    #[inline(always)]
+    fn num_arcs_hint(&self) -> Option<u64> {
+        self.graphs.iter().map(G::num_arcs_hint).sum()
+    }
+
+    fn iter_from(&self, from: usize) -> Self::Lender<'_> {
+        let from = from.min(self.num_nodes());
+        // The graph containing from, skipping graphs without nodes
+        let first = self.offsets.partition_point(|&offset| offset <= from) - 1;
+        let mut lenders = vec![];
+        for (i, graph) in self.graphs.iter().enumerate().skip(first) {
+            let start = from.saturating_sub(self.offsets[i]);
+            lenders.push((
+                graph.iter_from(start),
+                self.offsets[i],
+                graph.num_nodes() - start,
+            ));
+        }
+        let mut lenders = lenders.into_iter();
+        Iter {
+            curr: lenders.next(),
+            lenders,
+            len: self.num_nodes() - from,
+        }
+    }
+}
+
+impl<G: SequentialGraph> SplitLabeling for ConcatGraphs<G>
+where
+    for<'a> G::Lender<'a>: Clone + Send + Sync,
+{
+    type SplitLender<'a>
+        = split::seq::Lender<'a, ConcatGraphs<G>>
+    where
+        Self: 'a;
+    type IntoIterator<'a>
+        = split::seq::IntoIterator<'a, ConcatGraphs<G>>
+    where
+        Self: 'a;
+
+    fn split_iter(&self, how_many: usize) -> Self::IntoIterator<'_> {
+        split::seq::Iter::new(self.iter(), self.num_nodes(), how_many)
+    }
+}
+
+impl<G: SequentialGraph> SequentialGraph for ConcatGraphs<G> {}
+
+impl<'c, G: SequentialGraph> IntoLender for &'c ConcatGraphs<G> {
+    type Lender = <ConcatGraphs<G> as SequentialLabeling>::Lender<'c>;
+
+    #[inline(always)]
+    fn into_lender(self) -> Self::Lender {
+        self.iter()
+    }
+}
+
+/// A lender returning the shifted successor lists of a [`ConcatGraphs`].
+#[derive(Debug, Clone)]
+pub struct Iter<L> {
+    /// The lender of the current graph, with its offset and the number of
+    /// nodes it has still to return.
+    curr: Option<(L, usize, usize)>,
+    /// The lenders of the following graphs, in the same format.
+    lenders: std::vec::IntoIter<(L, usize, usize)>,
+    len: usize,
+}
+
+impl<'succ, L: Lender + for<'next> NodeLabelsLender<'next, Label = usize>> NodeLabelsLender<'succ>
+    for Iter<L>
+{
+    type Label = usize;
+    type IntoIterator = Succ<LenderIntoIter<'succ, L>>;
+}
+
+impl<'succ, L: Lender + for<'next> NodeLabelsLender<'next, Label = usize>> Lending<'succ>
+    for Iter<L>
+{
+    type Lend = (usize, <Self as NodeLabelsLender<'succ>>::IntoIterator);
+}
+
+unsafe impl<L: SortedLender + Lender + for<'next> NodeLabelsLender<'next, Label = usize>>
+    SortedLender for Iter<L>
+{
+}
+
+impl<L: Lender + for<'next> NodeLabelsLender<'next, Label = usize>> Lender for Iter<L> {
+    fn next(&mut self) -> Option<Lend<'_, Self>> {
+        // Move to the next graph with nodes left
+        while self.curr.as_ref()?.2 == 0 {
+            self.curr = self.lenders.next();
+        }
+        let (lender, offset, remaining) = self.curr.as_mut().unwrap();
+        *remaining -= 1;
+        self.len -= 1;
+        let offset = *offset;
+        let (node, succ) = lender.next()?.into_pair();
+        Some((
+            node + offset,
+            Succ {
+                iter: succ.into_iter(),
+                offset,
+            },
+        ))
+    }
+}
+
+impl<L: Lender + for<'next> NodeLabelsLender<'next, Label = usize>> ExactSizeLender for Iter<L> {
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+/// An iterator shifting successors by a given offset.
+#[derive(Debug, Clone)]
+pub struct Succ<I: Iterator<Item = usize>> {
+    iter: I,
+    offset: usize,
+}
+
+impl<I: Iterator<Item = usize>> Iterator for Succ<I> {
+    type Item = usize;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|succ| succ + self.offset)
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I: ExactSizeIterator<Item = usize>> ExactSizeIterator for Succ<I> {
+    #[inline(always)]
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+unsafe impl<I: Iterator<Item = usize> + SortedIterator> SortedIterator for Succ<I> {}
+
+#[cfg(test)]
+#[test]
+fn test_concat_graphs() -> anyhow::Result<()> {
+    use crate::graphs::vec_graph::VecGraph;
+    use crate::prelude::proj::Left;
+    let graphs = [
+        Left(VecGraph::from_arc_list([(0, 1), (1, 0), (1, 2)])),
+        Left(VecGraph::empty(0)),
+        Left(VecGraph::from_arc_list([(0, 0), (1, 0)])),
+        Left(VecGraph::empty(2)),
+    ];
+    let concat = concat_graphs(graphs.iter());
+    assert_eq!(concat.num_nodes(), 7);
+    assert_eq!(concat.offsets(), &[0, 3, 3, 5, 7]);
+    assert_eq!(concat.num_arcs_hint(), Some(5));
+
+    let expected = [
+        (0, vec![1]),
+        (1, vec![0, 2]),
+        (2, vec![]),
+        (3, vec![3]),
+        (4, vec![3]),
+        (5, vec![]),
+        (6, vec![]),
+    ];
+    for from in 0..=7 {
+        let mut iter = concat.iter_from(from);
+        assert_eq!(iter.len(), 7 - from);
+        let mut result = vec![];
+        while let Some((node, succ)) = iter.next() {
+            result.push((node, succ.collect::<Vec<_>>()));
+        }
+        assert_eq!(result, &expected[from..]);
+        assert_eq!(iter.len(), 0);
+        assert!(iter.next().is_none());
+    }
+
+    let empty = ConcatGraphs::<Left<VecGraph>>::new(vec![]);
+    assert_eq!(empty.num_nodes(), 0);
+    assert_eq!(empty.num_arcs_hint(), Some(0));
+    assert!(empty.iter().next().is_none());
+    Ok(())
+}
@@ -0,0 +1,141 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::prelude::*;
+use lender::*;
+
+/// An immutable in-memory graph in compressed sparse row format.
+///
+/// The successors of node `x` are stored in `successors[offsets[x]..offsets[x
+/// + 1]]`. The structure uses `(n + 1 + m) * size_of::<usize>()` bytes, where
+/// _n_ is the number of nodes and _m_ is the number of arcs (see
+/// [`CsrGraph::estimated_size`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsrGraph {
+    offsets: Vec<usize>,
+    successors: Vec<usize>,
+}
+
+impl core::default::Default for CsrGraph {
+    fn default() -> Self {
+        Self {
+            offsets: vec![0],
+            successors: vec![],
+        }
+    }
+}
+
+impl CsrGraph {
+    /// Creates a new graph from a vector of offsets and a vector of
+    /// successors.
+    ///
+    /// # Panics
+    ///
+    /// If `offsets` is empty, not monotone, or its last element is not the
+    /// length of `successors`.
+    pub fn from_parts(offsets: Vec<usize>, successors: Vec<usize>) -> Self {
+        assert!(!offsets.is_empty(), "The offsets cannot be empty");
+        assert!(
+            offsets.windows(2).all(|w| w[0] <= w[1]),
+            "The offsets must be monotone"
+        );
+        assert_eq!(
+            *offsets.last().unwrap(),
+            successors.len(),
+            "The last offset must be the number of successors"
+        );
+        Self {
+            offsets,
+            successors,
+        }
+    }
+
+    /// Returns the vector of offsets and the vector of successors.
+    pub fn into_parts(self) -> (Vec<usize>, Vec<usize>) {
+        (self.offsets, self.successors)
+    }
+
+    /// Returns the number of bytes used by a graph with the given number of
+    /// nodes and arcs.
+    pub fn estimated_size(num_nodes: usize, num_arcs: u64) -> usize {
+        (num_nodes + 1 + num_arcs as usize) * core::mem::size_of::<usize>()
+    }
+}
+
+impl<'a> IntoLender for &'a CsrGraph {
+    type Lender = <CsrGraph as SequentialLabeling>::Lender<'a>;
+
+    #[inline(always)]
+    fn into_lender(self) -> Self::Lender {
+        self.iter()
+    }
+}
+
+impl SequentialLabeling for CsrGraph {
+    type Label = usize;
+    type Lender<'a>
+        = IteratorImpl<'a, Self>
+    where
+        Self: 'a;
+
+    #[inline(always)]
+    fn num_nodes(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    #[inline(always)]
+    fn num_arcs_hint(&self) -> Option<u64> {
+        Some(self.num_arcs())
+    }
+
+    #[inline(always)]
+    fn iter_from(&self, from: usize) -> Self::Lender<'_> {
+        IteratorImpl {
+            labeling: self,
+            nodes: (from..self.num_nodes()),
+        }
+    }
+}
+
+impl SequentialGraph for CsrGraph {}
+
+impl RandomAccessLabeling for CsrGraph {
+    type Labels<'succ> = core::iter::Copied<core::slice::Iter<'succ, usize>>;
+
+    #[inline(always)]
+    fn num_arcs(&self) -> u64 {
+        self.successors.len() as u64
+    }
+
+    #[inline(always)]
+    fn outdegree(&self, node: usize) -> usize {
+        self.offsets[node + 1] - self.offsets[node]
+    }
+
+    #[inline(always)]
+    fn labels(&self, node: usize) -> <Self as RandomAccessLabeling>::Labels<'_> {
+        self.successors[self.offsets[node]..self.offsets[node + 1]]
+            .iter()
+            .copied()
+    }
+}
+
+impl RandomAccessGraph for CsrGraph {}
+
+impl SplitLabeling for CsrGraph {
+    type SplitLender<'a>
+        = split::ra::Lender<'a, CsrGraph>
+    where
+        Self: 'a;
+    type IntoIterator<'a>
+        = split::ra::IntoIterator<'a, CsrGraph>
+    where
+        Self: 'a;
+
+    fn split_iter(&self, how_many: usize) -> Self::IntoIterator<'_> {
+        split::ra::Iter::new(self, how_many)
+    }
+}
@@ -0,0 +1,252 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+//! A wrapper recording the outdegrees returned by iterations.
+
+use crate::prelude::*;
+use lender::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// A wrapper that records the number of successors returned for each node by
+/// the iterators of a graph, so that the outdegrees are available after a
+/// single pass (e.g., a [compression](crate::graphs::bvgraph::compress)).
+///
+/// Every lender returned by [`iter`](SequentialLabeling::iter) or
+/// [`split_iter`](SplitLabeling::split_iter) counts the successors it
+/// returns for the current node, and stores the count when it moves to the
+/// next node or when it is dropped. The overhead is one increment per arc
+/// and one word of memory per node.
+///
+/// The recorded outdegrees are meaningful only if all successors of all nodes
+/// have been enumerated, and all lenders have been dropped.
+#[derive(Debug)]
+pub struct DegreeRecorder<G> {
+    graph: G,
+    degrees: Box<[AtomicUsize]>,
+}
+
+impl<G: SequentialGraph> DegreeRecorder<G> {
+    /// Wraps a graph, recording its outdegrees.
+    pub fn new(graph: G) -> Self {
+        let degrees = (0..graph.num_nodes())
+            .map(|_| AtomicUsize::new(0))
+            .collect();
+        Self { graph, degrees }
+    }
+
+    /// Returns the recorded outdegrees.
+    pub fn into_degrees(self) -> Vec<usize> {
+        self.degrees
+            .into_vec()
+            .into_iter()
+            .map(AtomicUsize::into_inner)
+            .collect()
+    }
+
+    /// Returns the wrapped graph.
+    pub fn into_inner(self) -> G {
+        self.graph
+    }
+}
+
+impl<G: SequentialGraph> SequentialLabeling for DegreeRecorder<G> {
+    type Label = usize;
+    type Lender<'b>
+        = Iter<'b, G::Lender<'b>>
+    where
+        Self: 'b;
+
+    #[inline(always)]
+    fn num_nodes(&self) -> usize {
+        self.graph.num_nodes()
+    }
+
+    #[inline(always)]
+    fn successors_sorted(&self) -> bool {
+        self.graph.successors_sorted()
+    }
+
+    #[inline(always)]
+    fn num_arcs_hint(&self) -> Option<u64> {
+        self.graph.num_arcs_hint()
+    }
+
+    #[inline(always)]
+    fn iter_from(&self, from: usize) -> Self::Lender<'_> {
+        Iter::new(self.graph.iter_from(from), &self.degrees)
+    }
+}
+
+impl<G: SequentialGraph + SplitLabeling> SplitLabeling for DegreeRecorder<G> {
+    type SplitLender<'a>
+        = Iter<'a, G::SplitLender<'a>>
+    where
+        Self: 'a;
+    type IntoIterator<'a>
+        = SplitIter<'a, <G::IntoIterator<'a> as IntoIterator>::IntoIter>
+    where
+        Self: 'a;
+
+    fn split_iter(&self, how_many: usize) -> Self::IntoIterator<'_> {
+        SplitIter {
+            iter: self.graph.split_iter(how_many).into_iter(),
+            degrees: &self.degrees,
+        }
+    }
+}
+
+impl<G: SequentialGraph> SequentialGraph for DegreeRecorder<G> {}
+
+impl<'b, G: SequentialGraph> IntoLender for &'b DegreeRecorder<G> {
+    type Lender = <DegreeRecorder<G> as SequentialLabeling>::Lender<'b>;
+
+    #[inline(always)]
+    fn into_lender(self) -> Self::Lender {
+        self.iter()
+    }
+}
+
+/// An iterator over the splits of a [`DegreeRecorder`].
+#[derive(Debug)]
+pub struct SplitIter<'a, I> {
+    iter: I,
+    degrees: &'a [AtomicUsize],
+}
+
+impl<'a, I: Iterator> Iterator for SplitIter<'a, I> {
+    type Item = Iter<'a, I::Item>;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|iter| Iter::new(iter, self.degrees))
+    }
+}
+
+/// A lender counting the successors it returns for each node.
+///
+/// The count of a node is stored in the [`DegreeRecorder`] that created the
+/// lender when the lender moves to the next node or is dropped.
+#[derive(Debug)]
+pub struct Iter<'a, I> {
+    iter: I,
+    /// The node returned last, if any.
+    node: Option<usize>,
+    count: usize,
+    degrees: &'a [AtomicUsize],
+}
+
+impl<'a, I> Iter<'a, I> {
+    fn new(iter: I, degrees: &'a [AtomicUsize]) -> Self {
+        Self {
+            iter,
+            node: None,
+            count: 0,
+            degrees,
+        }
+    }
+
+    /// Stores the count of the node returned last.
+    #[inline(always)]
+    fn record(&mut self) {
+        if let Some(node) = self.node.take() {
+            self.degrees[node].store(self.count, Ordering::Relaxed);
+        }
+        self.count = 0;
+    }
+}
+
+impl<I> Drop for Iter<'_, I> {
+    fn drop(&mut self) {
+        self.record();
+    }
+}
+
+impl<'succ, I: Lender + for<'next> NodeLabelsLender<'next, Label = usize>> NodeLabelsLender<'succ>
+    for Iter<'_, I>
+{
+    type Label = usize;
+    type IntoIterator = Succ<'succ, LenderIntoIter<'succ, I>>;
+}
+
+impl<'succ, I: Lender + for<'next> NodeLabelsLender<'next, Label = usize>> Lending<'succ>
+    for Iter<'_, I>
+{
+    type Lend = (usize, <Self as NodeLabelsLender<'succ>>::IntoIterator);
+}
+
+unsafe impl<I: SortedLender + Lender + for<'next> NodeLabelsLender<'next, Label = usize>>
+    SortedLender for Iter<'_, I>
+{
+}
+
+impl<L: Lender + for<'next> NodeLabelsLender<'next, Label = usize>> Lender for Iter<'_, L> {
+    #[inline(always)]
+    fn next(&mut self) -> Option<Lend<'_, Self>> {
+        self.record();
+        let (node, succ) = self.iter.next()?.into_pair();
+        self.node = Some(node);
+        Some((
+            node,
+            Succ {
+                iter: succ.into_iter(),
+                count: &mut self.count,
+            },
+        ))
+    }
+}
+
+/// An iterator over successors that increments a count for each successor.
+#[derive(Debug)]
+pub struct Succ<'a, I: Iterator<Item = usize>> {
+    iter: I,
+    count: &'a mut usize,
+}
+
+impl<I: Iterator<Item = usize>> Iterator for Succ<'_, I> {
+    type Item = usize;
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        let dst = self.iter.next()?;
+        *self.count += 1;
+        Some(dst)
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+unsafe impl<I: Iterator<Item = usize> + SortedIterator> SortedIterator for Succ<'_, I> {}
+
+impl<I: ExactSizeIterator<Item = usize>> ExactSizeIterator for Succ<'_, I> {
+    #[inline(always)]
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_degree_recorder() {
+    use crate::{graphs::vec_graph::VecGraph, prelude::proj::Left};
+    let g = Left(VecGraph::from_arc_list([
+        (0, 1),
+        (1, 2),
+        (2, 0),
+        (2, 1),
+        (4, 0),
+    ]));
+
+    // Splits record the degrees of their nodes
+    let recorder = DegreeRecorder::new(&g);
+    for lender in recorder.split_iter(3) {
+        for_!( (_, succ) in lender {
+            for _ in succ {}
+        });
+    }
+    assert_eq!(recorder.into_degrees(), vec![1, 1, 2, 0, 1]);
+}
@@ -0,0 +1,539 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+//! Graphs stored in a key-value store.
+//!
+//! A [`KvGraph`] provides sequential and random access to a graph whose
+//! successor lists are stored in a read-only key-value store with ordered
+//! keys, such as [RocksDB](https://rocksdb.org/) or
+//! [sled](https://sled.rs/), abstracted by the [`KvBackend`] trait. In this
+//! way, graphs kept in a store for mutability can be analyzed without
+//! exporting them to a [`BvGraph`](crate::graphs::bvgraph::BvGraph).
+//!
+//! The successor list of a node is stored under the [key](node_key) given by
+//! the big-endian representation of the node as a `u64`, so keys are sorted
+//! as the nodes, and the value is [encoded](encode_successors) using
+//! instantaneous codes. Nodes without a key have no successors. The number of
+//! nodes (and, optionally, the number of arcs) are stored as big-endian `u64`
+//! under the [`NUM_NODES_KEY`] (and [`NUM_ARCS_KEY`]) metadata keys, which
+//! are never eight bytes long, so they cannot be mistaken for node keys.
+//!
+//! [`KvBackend`] is implemented for an in-memory [`BTreeMap`]; implementations
+//! for actual stores are left to downstream crates. The module is available
+//! only with the `kv` feature.
+
+use crate::traits::*;
+use crate::utils::{int2nat, nat2int};
+use anyhow::{bail, ensure, Context, Result};
+use dsi_bitstream::prelude::*;
+use lender::*;
+use std::collections::BTreeMap;
+use std::ops::Bound;
+
+/// The metadata key under which the number of nodes is stored.
+pub const NUM_NODES_KEY: &[u8] = b"webgraph.num_nodes";
+/// The metadata key under which the number of arcs is stored, if available.
+pub const NUM_ARCS_KEY: &[u8] = b"webgraph.num_arcs";
+
+// Node keys are exactly eight bytes long
+const _: () = assert!(NUM_NODES_KEY.len() != 8 && NUM_ARCS_KEY.len() != 8);
+
+/// A read-only key-value store with ordered keys.
+pub trait KvBackend {
+    /// The iterator over the entries of the store.
+    type Entries<'a>: Iterator<Item = (&'a [u8], &'a [u8])>
+    where
+        Self: 'a;
+
+    /// Returns the value associated with a key, if any.
+    fn get(&self, key: &[u8]) -> Option<&[u8]>;
+
+    /// Returns an iterator over the entries of the store with key greater
+    /// than or equal to `key` in lexicographical order of the keys.
+    ///
+    /// Stores should implement this method with a seek, so that
+    /// [`KvGraph::new`] and [`iter_from`](SequentialLabeling::iter_from) do not
+    /// need to scan the store.
+    fn entries_from(&self, key: &[u8]) -> Self::Entries<'_>;
+
+    /// Returns an iterator over the entries of the store in lexicographical
+    /// order of the keys.
+    fn entries(&self) -> Self::Entries<'_> {
+        self.entries_from(&[])
+    }
+}
+
+/// Converts an entry of a [`BTreeMap`] into a pair of slices.
+fn as_slices<'a>((key, value): (&'a Vec<u8>, &'a Vec<u8>)) -> (&'a [u8], &'a [u8]) {
+    (key.as_slice(), value.as_slice())
+}
+
+impl KvBackend for BTreeMap<Vec<u8>, Vec<u8>> {
+    type Entries<'a> = core::iter::Map<
+        std::collections::btree_map::Range<'a, Vec<u8>, Vec<u8>>,
+        fn((&'a Vec<u8>, &'a Vec<u8>)) -> (&'a [u8], &'a [u8]),
+    >;
+
+    fn get(&self, key: &[u8]) -> Option<&[u8]> {
+        BTreeMap::get(self, key).map(|value| value.as_slice())
+    }
+
+    fn entries_from(&self, key: &[u8]) -> Self::Entries<'_> {
+        self.range::<[u8], _>((Bound::Included(key), Bound::Unbounded))
+            .map(as_slices)
+    }
+}
+
+/// Returns the key of a node.
+pub fn node_key(node: usize) -> [u8; 8] {
+    (node as u64).to_be_bytes()
+}
+
+/// Returns the node of a key, or `None` if the key is not a node key.
+fn key_node(key: &[u8]) -> Option<usize> {
+    Some(u64::from_be_bytes(key.try_into().ok()?) as usize)
+}
+
+/// Encodes the successors of a node, which must be strictly increasing.
+///
+/// The value is a big-endian bitstream containing the outdegree in γ code,
+/// followed by the difference between the first successor and the node
+/// (mapped to a natural number) and by the gaps between consecutive
+/// successors minus one, all in ζ₃ code, as in the residuals of a
+/// [`BvGraph`](crate::graphs::bvgraph::BvGraph).
+///
+/// Fails if the successors are not strictly increasing.
+pub fn encode_successors(node: usize, successors: &[usize]) -> Result<Vec<u8>> {
+    let mut words: Vec<u64> = vec![];
+    let mut bits = 0;
+    {
+        let mut writer = <BufBitWriter<BE, _>>::new(MemWordWriterVec::new(&mut words));
+        // Writing to memory cannot fail
+        bits += writer.write_gamma(successors.len() as u64).unwrap();
+        let mut prev = None;
+        for &succ in successors {
+            bits += writer
+                .write_zeta3(match prev {
+                    None => int2nat(succ as i64 - node as i64),
+                    Some(prev) => {
+                        ensure!(
+                            succ > prev,
+                            "The successors of node {} are not strictly increasing: {} follows {}",
+                            node,
+                            succ,
+                            prev
+                        );
+                        (succ - prev - 1) as u64
+                    }
+                })
+                .unwrap();
+            prev = Some(succ);
+        }
+        writer.flush().unwrap();
+    }
+    // In memory, the words written by a big-endian writer contain the
+    // bitstream in byte order
+    Ok(words
+        .iter()
+        .flat_map(|word| word.to_ne_bytes())
+        .take(bits.div_ceil(8))
+        .collect())
+}
+
+/// Converts a value into zero-padded words for a big-endian reader.
+fn to_words(value: &[u8]) -> Vec<u32> {
+    value
+        .chunks(4)
+        .map(|chunk| {
+            let mut bytes = [0; 4];
+            bytes[..chunk.len()].copy_from_slice(chunk);
+            u32::from_ne_bytes(bytes)
+        })
+        .collect()
+}
+
+/// Decodes the successors of a node encoded by [`encode_successors`].
+///
+/// Fails if the value is truncated or does not encode a valid successor
+/// list.
+pub fn decode_successors(node: usize, value: &[u8]) -> Result<Vec<usize>> {
+    let words = to_words(value);
+    let mut reader = <BufBitReader<BE, _>>::new(MemWordReader::new_strict(words.as_slice()));
+    let outdegree = reader
+        .read_gamma()
+        .context("Could not read the outdegree")? as usize;
+    // Each successor uses at least one bit
+    ensure!(
+        outdegree <= value.len() * 8,
+        "The outdegree {} is too large for a value of {} bytes",
+        outdegree,
+        value.len()
+    );
+    let mut successors: Vec<usize> = Vec::with_capacity(outdegree);
+    for i in 0..outdegree {
+        let value = reader
+            .read_zeta3()
+            .with_context(|| format!("Could not read successor {}", i))?;
+        let succ = if i == 0 {
+            usize::try_from(node as i64 + nat2int(value)).ok()
+        } else {
+            successors[i - 1]
+                .checked_add(value as usize)
+                .and_then(|succ| succ.checked_add(1))
+        };
+        successors.push(succ.with_context(|| format!("Successor {} is out of range", i))?);
+    }
+    Ok(successors)
+}
+
+/// Returns the outdegree of a node given its encoded successors.
+fn decode_outdegree(value: &[u8]) -> Result<usize> {
+    // A γ code of a 64-bit value is at most 127 bits long
+    let words = to_words(&value[..value.len().min(16)]);
+    let mut reader = <BufBitReader<BE, _>>::new(MemWordReader::new_strict(words.as_slice()));
+    Ok(reader
+        .read_gamma()
+        .context("Could not read the outdegree")? as usize)
+}
+
+/// Stores a graph, calling `put` on each key-value pair to store.
+///
+/// Only nodes with at least one successor are stored, together with the
+/// [number of nodes](NUM_NODES_KEY) and the [number of arcs](NUM_ARCS_KEY).
+/// Successor lists are sorted, and duplicate successors are stored once.
+pub fn store_graph(graph: &impl SequentialGraph, mut put: impl FnMut(&[u8], &[u8])) {
+    let mut num_arcs = 0_u64;
+    let mut successors = vec![];
+    for_!((node, succ) in graph.iter() {
+        successors.clear();
+        successors.extend(succ);
+        if !graph.successors_sorted() {
+            successors.sort_unstable();
+        }
+        successors.dedup();
+        if !successors.is_empty() {
+            num_arcs += successors.len() as u64;
+            // Sorted and deduplicated successors are strictly increasing
+            put(
+                &node_key(node),
+                &encode_successors(node, &successors).unwrap(),
+            );
+        }
+    });
+    put(NUM_NODES_KEY, &(graph.num_nodes() as u64).to_be_bytes());
+    put(NUM_ARCS_KEY, &num_arcs.to_be_bytes());
+}
+
+/// A graph stored in a [`KvBackend`].
+///
+/// Random access uses one lookup per node, whereas sequential access
+/// scans the entries of the store in order.
+#[derive(Debug, Clone)]
+pub struct KvGraph<B: KvBackend> {
+    backend: B,
+    num_nodes: usize,
+    num_arcs: u64,
+}
+
+/// Reads a big-endian `u64` stored under a metadata key.
+fn read_metadata(backend: &impl KvBackend, key: &[u8]) -> Result<Option<u64>> {
+    backend
+        .get(key)
+        .map(|value| {
+            Ok(u64::from_be_bytes(value.try_into().with_context(|| {
+                format!(
+                    "The value of key {} is not a u64",
+                    String::from_utf8_lossy(key)
+                )
+            })?))
+        })
+        .transpose()
+}
+
+impl<B: KvBackend> KvGraph<B> {
+    /// Creates a new graph over a backend.
+    ///
+    /// The number of nodes is read from the [`NUM_NODES_KEY`] key. If the
+    /// [`NUM_ARCS_KEY`] key is missing, the number of arcs is computed by
+    /// a scan of the store.
+    ///
+    /// Fails if the store contains a node key not smaller than the number of
+    /// nodes, which is checked with a [seek](KvBackend::entries_from).
+    pub fn new(backend: B) -> Result<Self> {
+        let num_nodes = read_metadata(&backend, NUM_NODES_KEY)?
+            .context("Missing number of nodes in the key-value store")?
+            as usize;
+        let num_arcs = match read_metadata(&backend, NUM_ARCS_KEY)? {
+            Some(num_arcs) => num_arcs,
+            None => backend
+                .entries()
+                .filter_map(|(key, value)| Some((key_node(key)?, value)))
+                .map(|(node, value)| {
+                    decode_outdegree(value)
+                        .map(|outdegree| outdegree as u64)
+                        .with_context(|| format!("Could not decode the value of node {}", node))
+                })
+                .sum::<Result<u64>>()?,
+        };
+        // Past the key of num_nodes there are only metadata keys and nodes
+        // out of range
+        if let Some(node) = backend
+            .entries_from(&node_key(num_nodes))
+            .find_map(|(key, _)| key_node(key))
+        {
+            bail!(
+                "The store contains node {}, but the graph has {} nodes",
+                node,
+                num_nodes
+            );
+        }
+        Ok(Self {
+            backend,
+            num_nodes,
+            num_arcs,
+        })
+    }
+
+    /// Returns the backend.
+    pub fn into_inner(self) -> B {
+        self.backend
+    }
+}
+
+impl<B: KvBackend> SequentialLabeling for KvGraph<B> {
+    type Label = usize;
+    type Lender<'a>
+        = Iter<'a, B>
+    where
+        Self: 'a;
+
+    #[inline(always)]
+    fn num_nodes(&self) -> usize {
+        self.num_nodes
+    }
+
+    #[inline(always)]
+    fn successors_sorted(&self) -> bool {
+        true
+    }
+
+    #[inline(always)]
+    fn num_arcs_hint(&self) -> Option<u64> {
+        Some(self.num_arcs)
+    }
+
+    fn iter_from(&self, from: usize) -> Self::Lender<'_> {
+        Iter {
+            entries: self.backend.entries_from(&node_key(from)).peekable(),
+            next_node: from,
+            num_nodes: self.num_nodes,
+        }
+    }
+}
+
+impl<B: KvBackend> SequentialGraph for KvGraph<B> {}
+
+impl<B: KvBackend> RandomAccessLabeling for KvGraph<B> {
+    type Labels<'succ>
+        = std::vec::IntoIter<usize>
+    where
+        Self: 'succ;
+
+    #[inline(always)]
+    fn num_arcs(&self) -> u64 {
+        self.num_arcs
+    }
+
+    /// # Panics
+    ///
+    /// If the value associated with the node cannot be decoded.
+    fn outdegree(&self, node: usize) -> usize {
+        self.backend.get(&node_key(node)).map_or(0, |value| {
+            decode_outdegree(value).unwrap_or_else(|err| corrupted(node, err))
+        })
+    }
+
+    /// # Panics
+    ///
+    /// If the value associated with the node cannot be decoded.
+    fn labels(&self, node: usize) -> <Self as RandomAccessLabeling>::Labels<'_> {
+        self.backend
+            .get(&node_key(node))
+            .map_or_else(Vec::new, |value| {
+                decode_successors(node, value).unwrap_or_else(|err| corrupted(node, err))
+            })
+            .into_iter()
+    }
+}
+
+impl<B: KvBackend> RandomAccessGraph for KvGraph<B> {}
+
+/// Panics reporting that the value of a node cannot be decoded.
+///
+/// The methods of the labeling traits cannot return errors, so a corrupted
+/// store is detected only when a node is accessed.
+#[cold]
+fn corrupted(node: usize, err: anyhow::Error) -> ! {
+    panic!("Could not decode the value of node {}: {:#}", node, err)
+}
+
+impl<'a, B: KvBackend> IntoLender for &'a KvGraph<B> {
+    type Lender = <KvGraph<B> as SequentialLabeling>::Lender<'a>;
+
+    #[inline(always)]
+    fn into_lender(self) -> Self::Lender {
+        self.iter()
+    }
+}
+
+/// A sequential iterator over a [`KvGraph`] scanning the entries of the
+/// store.
+pub struct Iter<'a, B: KvBackend + 'a> {
+    entries: core::iter::Peekable<B::Entries<'a>>,
+    next_node: usize,
+    num_nodes: usize,
+}
+
+unsafe impl<B: KvBackend> SortedLender for Iter<'_, B> {}
+
+impl<'succ, B: KvBackend> NodeLabelsLender<'succ> for Iter<'_, B> {
+    type Label = usize;
+    type IntoIterator = std::vec::IntoIter<usize>;
+}
+
+impl<'succ, B: KvBackend> Lending<'succ> for Iter<'_, B> {
+    type Lend = (usize, std::vec::IntoIter<usize>);
+}
+
+impl<B: KvBackend> Lender for Iter<'_, B> {
+    fn next(&mut self) -> Option<Lend<'_, Self>> {
+        if self.next_node == self.num_nodes {
+            return None;
+        }
+        let node = self.next_node;
+        self.next_node += 1;
+        // Skip metadata keys
+        while self
+            .entries
+            .next_if(|(key, _)| key_node(key).is_none())
+            .is_some()
+        {}
+        let successors = match self.entries.next_if(|(key, _)| key_node(key) == Some(node)) {
+            Some((_, value)) => {
+                decode_successors(node, value).unwrap_or_else(|err| corrupted(node, err))
+            }
+            None => vec![],
+        };
+        Some((node, successors.into_iter()))
+    }
+}
+
+impl<B: KvBackend> ExactSizeLender for Iter<'_, B> {
+    fn len(&self) -> usize {
+        self.num_nodes - self.next_node
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algo::{bridges, BfsOrder};
+    use crate::graphs::arc_list_graph::ArcListGraph;
+    use crate::graphs::random::ErdosRenyi;
+    use crate::graphs::vec_graph::VecGraph;
+    use crate::labels::Left;
+
+    #[test]
+    fn test_codec() {
+        for (node, successors) in [
+            (0, vec![]),
+            (5, vec![0, 1, 2, 5, 100]),
+            (100, vec![3]),
+            (0, vec![1 << 40]),
+        ] {
+            let value = encode_successors(node, &successors).unwrap();
+            assert_eq!(decode_successors(node, &value).unwrap(), successors);
+            assert_eq!(decode_outdegree(&value).unwrap(), successors.len());
+        }
+
+        // Truncated or corrupted values are rejected
+        let value = encode_successors(5, &[0, 1, 2, 5, 100]).unwrap();
+        assert!(decode_successors(5, &value[..1]).is_err());
+        assert!(decode_successors(5, &[0; 4]).is_err());
+        assert!(decode_outdegree(&[]).is_err());
+        // The first successor would be negative
+        assert!(decode_successors(0, &encode_successors(3, &[0]).unwrap()).is_err());
+
+        // Unsorted or duplicate successors are rejected
+        assert!(encode_successors(0, &[2, 1]).is_err());
+        assert!(encode_successors(0, &[1, 1]).is_err());
+    }
+
+    #[test]
+    fn test_store_unsorted() -> Result<()> {
+        let graph = Left(ArcListGraph::new(
+            4,
+            [(0, 3), (0, 1), (0, 3), (2, 0), (2, 0)],
+        ));
+        let mut map = BTreeMap::new();
+        store_graph(&graph, |key, value| {
+            map.insert(key.to_vec(), value.to_vec());
+        });
+        let kv_graph = KvGraph::new(map.clone())?;
+        assert_eq!(kv_graph.num_arcs(), 3);
+        itertools::assert_equal(kv_graph.successors(0), [1, 3]);
+        itertools::assert_equal(kv_graph.successors(2), [0]);
+
+        // Nodes out of range are detected
+        map.insert(node_key(4).to_vec(), encode_successors(4, &[0])?);
+        assert!(KvGraph::new(map).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_kv_graph() -> Result<()> {
+        // A symmetric random graph, with some isolated nodes at the end
+        let random = Left(VecGraph::from_lender(ErdosRenyi::new(100, 0.02, 0).iter()));
+        let mut graph = VecGraph::empty(110);
+        for x in 0..100 {
+            for y in random.successors(x) {
+                graph.add_arc(x, y);
+                graph.add_arc(y, x);
+            }
+        }
+        let graph = Left(graph);
+
+        let mut map = BTreeMap::new();
+        store_graph(&graph, |key, value| {
+            map.insert(key.to_vec(), value.to_vec());
+        });
+        let kv_graph = KvGraph::new(map.clone())?;
+        assert_eq!(kv_graph.num_nodes(), graph.num_nodes());
+        assert_eq!(kv_graph.num_arcs(), graph.num_arcs());
+        let mut iter = kv_graph.iter();
+        while let Some((x, succ)) = iter.next() {
+            itertools::assert_equal(succ, graph.successors(x));
+        }
+        for x in 0..graph.num_nodes() {
+            assert_eq!(kv_graph.outdegree(x), graph.outdegree(x));
+            itertools::assert_equal(kv_graph.successors(x), graph.successors(x));
+        }
+        let mut iter = kv_graph.iter_from(50);
+        while let Some((x, succ)) = iter.next() {
+            itertools::assert_equal(succ, graph.successors(x));
+        }
+
+        assert_eq!(bridges(&kv_graph), bridges(&graph));
+        assert!(BfsOrder::new(&kv_graph).eq(BfsOrder::new(&graph)));
+
+        // Without the number of arcs, it is computed by a scan
+        map.remove(NUM_ARCS_KEY);
+        assert_eq!(KvGraph::new(map.clone())?.num_arcs(), graph.num_arcs());
+        map.remove(NUM_NODES_KEY);
+        assert!(KvGraph::new(map).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,227 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::prelude::*;
+use anyhow::{ensure, Context, Result};
+use epserde::prelude::*;
+use lender::*;
+use std::path::Path;
+use sux::prelude::*;
+
+/// The extension of the file containing the mask of present nodes of a
+/// graph, stored as a [`BitVec`] serialized with ε-serde.
+///
+/// The more natural extension `nodes` is already used for the textual
+/// labels of the nodes written by `webgraph from`.
+pub const NODE_MASK_EXTENSION: &str = "nodemask";
+
+/// Stores the mask of present nodes of the graph with given basename.
+pub fn store_node_mask<B: AsRef<[usize]>>(
+    basename: impl AsRef<Path>,
+    mask: &BitVec<B>,
+) -> Result<()>
+where
+    BitVec<B>: Serialize,
+{
+    let path = Basename::new(basename).with_ext(NODE_MASK_EXTENSION);
+    mask.store(&path)
+        .with_context(|| format!("Could not store node mask to {}", path.display()))?;
+    Ok(())
+}
+
+/// Loads the mask of present nodes of the graph with given basename.
+pub fn load_node_mask(basename: impl AsRef<Path>) -> Result<BitVec> {
+    let path = Basename::new(basename).with_ext(NODE_MASK_EXTENSION);
+    <BitVec>::load_full(&path)
+        .with_context(|| format!("Could not load node mask from {}", path.display()))
+}
+
+/// A wrapper recording which nodes of a graph are present.
+///
+/// Graphs derived by filtering usually keep the original node ids, so many
+/// ids have no arcs and are semantically absent. This wrapper couples a graph
+/// with a mask of present nodes (see [`NODE_MASK_EXTENSION`]), and provides
+/// methods to test for presence and to iterate on present nodes only.
+///
+/// The wrapper is a graph with the same nodes and arcs of the underlying graph,
+/// so it can be used wherever the latter can.
+#[derive(Debug, Clone)]
+pub struct MaskedNodeGraph<G, B = Vec<usize>> {
+    graph: G,
+    mask: BitVec<B>,
+    num_nodes_present: usize,
+}
+
+impl<G: SequentialGraph, B: AsRef<[usize]>> MaskedNodeGraph<G, B> {
+    /// Creates a new masked graph.
+    ///
+    /// The mask must have a bit for each node of the graph, and absent nodes
+    /// must have no arcs.
+    pub fn new(graph: G, mask: BitVec<B>) -> Result<Self> {
+        ensure!(
+            mask.len() == graph.num_nodes(),
+            "The mask has length {}, but the graph has {} nodes",
+            mask.len(),
+            graph.num_nodes()
+        );
+        let num_nodes_present = (0..mask.len()).filter(|&node| mask.get(node)).count();
+        Ok(Self {
+            graph,
+            mask,
+            num_nodes_present,
+        })
+    }
+
+    /// Returns whether a node is present.
+    #[inline(always)]
+    pub fn is_present(&self, node: usize) -> bool {
+        self.mask.get(node)
+    }
+
+    /// Returns the number of present nodes.
+    #[inline(always)]
+    pub fn num_nodes_present(&self) -> usize {
+        self.num_nodes_present
+    }
+
+    /// Returns the mask of present nodes.
+    pub fn mask(&self) -> &BitVec<B> {
+        &self.mask
+    }
+
+    /// Returns an iterator on the present nodes in increasing order.
+    pub fn present_nodes(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.mask.len()).filter(|&node| self.mask.get(node))
+    }
+
+    /// Returns the underlying graph and mask.
+    pub fn into_inner(self) -> (G, BitVec<B>) {
+        (self.graph, self.mask)
+    }
+}
+
+impl<G: RandomAccessGraph, B: AsRef<[usize]>> MaskedNodeGraph<G, B> {
+    /// Returns an iterator on the present nodes in increasing order and their
+    /// successors.
+    ///
+    /// Absent nodes are skipped without accessing the graph.
+    pub fn iter_present(
+        &self,
+    ) -> impl Iterator<Item = (usize, <G as RandomAccessLabeling>::Labels<'_>)> + '_ {
+        self.present_nodes()
+            .map(|node| (node, self.graph.successors(node)))
+    }
+}
+
+impl<G: SequentialGraph, B> SequentialLabeling for MaskedNodeGraph<G, B> {
+    type Label = usize;
+    type Lender<'b>
+        = G::Lender<'b>
+    where
+        Self: 'b;
+
+    #[inline(always)]
+    fn num_nodes(&self) -> usize {
+        self.graph.num_nodes()
+    }
+
+    #[inline(always)]
+    fn successors_sorted(&self) -> bool {
+        self.graph.successors_sorted()
+    }
+
+    #[inline(always)]
+    fn num_arcs_hint(&self) -> Option<u64> {
+        self.graph.num_arcs_hint()
+    }
+
+    #[inline(always)]
+    fn iter_from(&self, from: usize) -> Self::Lender<'_> {
+        self.graph.iter_from(from)
+    }
+}
+
+impl<G: SequentialGraph, B> SequentialGraph for MaskedNodeGraph<G, B> {}
+
+impl<G: RandomAccessGraph, B> RandomAccessLabeling for MaskedNodeGraph<G, B> {
+    type Labels<'succ>
+        = <G as RandomAccessLabeling>::Labels<'succ>
+    where
+        Self: 'succ;
+
+    #[inline(always)]
+    fn num_arcs(&self) -> u64 {
+        self.graph.num_arcs()
+    }
+
+    #[inline(always)]
+    fn outdegree(&self, node: usize) -> usize {
+        self.graph.outdegree(node)
+    }
+
+    #[inline(always)]
+    fn outdegree_hint(&self, node: usize) -> usize {
+        self.graph.outdegree_hint(node)
+    }
+
+    #[inline(always)]
+    fn labels(&self, node: usize) -> <Self as RandomAccessLabeling>::Labels<'_> {
+        self.graph.successors(node)
+    }
+}
+
+impl<G: RandomAccessGraph, B> RandomAccessGraph for MaskedNodeGraph<G, B> {}
+
+impl<'b, G: SequentialGraph, B> IntoLender for &'b MaskedNodeGraph<G, B> {
+    type Lender = <MaskedNodeGraph<G, B> as SequentialLabeling>::Lender<'b>;
+
+    #[inline(always)]
+    fn into_lender(self) -> Self::Lender {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphs::vec_graph::VecGraph;
+
+    #[test]
+    fn test_masked_node_graph() -> Result<()> {
+        let graph = Left(VecGraph::from_arc_list([(0, 3), (3, 5), (5, 0), (5, 3)]));
+        let mut mask = BitVec::new(graph.num_nodes());
+        for node in [0, 3, 5] {
+            mask.set(node, true);
+        }
+
+        let tmp_dir = tempfile::tempdir()?;
+        let basename = tmp_dir.path().join("graph");
+        store_node_mask(&basename, &mask)?;
+        let loaded = load_node_mask(&basename)?;
+        assert_eq!(loaded.len(), mask.len());
+        for node in 0..mask.len() {
+            assert_eq!(loaded.get(node), mask.get(node));
+        }
+
+        let masked = MaskedNodeGraph::new(&graph, loaded)?;
+        assert_eq!(masked.num_nodes(), 6);
+        assert_eq!(masked.num_nodes_present(), 3);
+        assert!(masked.is_present(3));
+        assert!(!masked.is_present(4));
+        assert_eq!(masked.present_nodes().collect::<Vec<_>>(), vec![0, 3, 5]);
+        assert_eq!(
+            masked
+                .iter_present()
+                .map(|(node, succ)| (node, succ.into_iter().collect::<Vec<_>>()))
+                .collect::<Vec<_>>(),
+            vec![(0, vec![3]), (3, vec![5]), (5, vec![0, 3])]
+        );
+        crate::assert_graph_eq!(masked, graph);
+
+        assert!(MaskedNodeGraph::new(&graph, BitVec::new(5)).is_err());
+        Ok(())
+    }
+}
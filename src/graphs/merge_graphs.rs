@@ -0,0 +1,251 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+//! A wrapper exhibiting the union of any number of graphs.
+
+use crate::prelude::*;
+use crate::traits::labels::SortedIter;
+use core::cmp::Reverse;
+use lender::*;
+use std::collections::BinaryHeap;
+
+/// A wrapper exhibiting the union of any number of graphs.
+///
+/// While [`UnionGraph`] merges two graphs, merging *k* graphs with it
+/// requires nesting *k* − 1 wrappers. This wrapper performs instead a *k*-way
+/// merge of the successor lists of each node in a single pass, using a heap
+/// over the *k* successor lists, and returns the sorted, deduplicated union
+/// of the successor lists. The successor lists of each node and their merge
+/// are stored in buffers owned by the lender, which are reused, so no
+/// allocation happens after the largest node has been merged, and memory
+/// usage is *O*(*k*) plus twice the largest sum of the lengths of the
+/// successor lists of a node.
+///
+/// The graphs may have a different number of nodes: the number of nodes of
+/// the union is the maximum number of nodes, and nodes missing from a graph
+/// have no successors in that graph. All graphs must return nodes and
+/// successors in increasing order.
+#[derive(Debug, Clone)]
+pub struct MergeGraphs<G> {
+    graphs: Vec<G>,
+    num_nodes: usize,
+}
+
+impl<G: SequentialGraph> MergeGraphs<G> {
+    /// Creates the union of the given graphs.
+    pub fn new(graphs: Vec<G>) -> Self {
+        let num_nodes = graphs.iter().map(G::num_nodes).max().unwrap_or(0);
+        Self { graphs, num_nodes }
+    }
+
+    /// Returns the merged graphs.
+    pub fn into_inner(self) -> Vec<G> {
+        self.graphs
+    }
+}
+
+impl<G: SequentialGraph> SequentialLabeling for MergeGraphs<G>
+where
+    for<'a> G::Lender<'a>: SortedLender,
+    for<'a, 'b> LenderIntoIter<'b, G::Lender<'a>>: SortedIterator,
+{
+    type Label = usize;
+    type Lender<'b>
+        = Iter<G::Lender<'b>>
+    where
+        Self: 'b;
+
+    #[inline(always)]
+    fn num_nodes(&self) -> usize {
+        self.num_nodes
+    }
+
+    #[inline(always)]
+    fn successors_sorted(&self) -> bool {
+        true
+    }
+
+    #[inline(always)]
+    fn num_arcs_hint(&self) -> Option<u64> {
+        None
+    }
+
+    fn iter_from(&self, from: usize) -> Self::Lender<'_> {
+        Iter {
+            lenders: self
+                .graphs
+                .iter()
+                .map(|graph| {
+                    let num_nodes = graph.num_nodes();
+                    (graph.iter_from(from.min(num_nodes)), num_nodes)
+                })
+                .collect(),
+            next_node: from.min(self.num_nodes),
+            num_nodes: self.num_nodes,
+            heap: BinaryHeap::new(),
+            lists: Vec::new(),
+            succ: Vec::new(),
+        }
+    }
+}
+
+impl<G: SequentialGraph> SplitLabeling for MergeGraphs<G>
+where
+    for<'a> G::Lender<'a>: SortedLender + Clone + Send + Sync,
+    for<'a, 'b> LenderIntoIter<'b, G::Lender<'a>>: SortedIterator,
+{
+    type SplitLender<'a>
+        = split::seq::Lender<'a, MergeGraphs<G>>
+    where
+        Self: 'a;
+    type IntoIterator<'a>
+        = split::seq::IntoIterator<'a, MergeGraphs<G>>
+    where
+        Self: 'a;
+
+    fn split_iter(&self, how_many: usize) -> Self::IntoIterator<'_> {
+        split::seq::Iter::new(self.iter(), self.num_nodes(), how_many)
+    }
+}
+
+impl<G: SequentialGraph> SequentialGraph for MergeGraphs<G>
+where
+    for<'a> G::Lender<'a>: SortedLender,
+    for<'a, 'b> LenderIntoIter<'b, G::Lender<'a>>: SortedIterator,
+{
+}
+
+impl<'c, G: SequentialGraph> IntoLender for &'c MergeGraphs<G>
+where
+    for<'a> G::Lender<'a>: SortedLender,
+    for<'a, 'b> LenderIntoIter<'b, G::Lender<'a>>: SortedIterator,
+{
+    type Lender = <MergeGraphs<G> as SequentialLabeling>::Lender<'c>;
+
+    #[inline(always)]
+    fn into_lender(self) -> Self::Lender {
+        self.iter()
+    }
+}
+
+/// A lender returning the merged successor lists of a [`MergeGraphs`].
+#[derive(Debug, Clone)]
+pub struct Iter<L> {
+    /// The lenders of the graphs, with their number of nodes.
+    lenders: Vec<(L, usize)>,
+    next_node: usize,
+    num_nodes: usize,
+    /// Triples (successor, position in `lists`, end of its list in `lists`)
+    /// still to be merged.
+    heap: BinaryHeap<Reverse<(usize, usize, usize)>>,
+    /// The concatenation of the successor lists of the current node.
+    lists: Vec<usize>,
+    /// The merged successor list of the current node.
+    succ: Vec<usize>,
+}
+
+impl<'succ, L: Lender + for<'next> NodeLabelsLender<'next, Label = usize>> NodeLabelsLender<'succ>
+    for Iter<L>
+{
+    type Label = usize;
+    type IntoIterator = SortedIter<core::iter::Copied<core::slice::Iter<'succ, usize>>>;
+}
+
+impl<'succ, L: Lender + for<'next> NodeLabelsLender<'next, Label = usize>> Lending<'succ>
+    for Iter<L>
+{
+    type Lend = (usize, <Self as NodeLabelsLender<'succ>>::IntoIterator);
+}
+
+unsafe impl<L: SortedLender + Lender + for<'next> NodeLabelsLender<'next, Label = usize>>
+    SortedLender for Iter<L>
+{
+}
+
+impl<L: Lender + for<'next> NodeLabelsLender<'next, Label = usize>> Lender for Iter<L> {
+    fn next(&mut self) -> Option<Lend<'_, Self>> {
+        if self.next_node >= self.num_nodes {
+            return None;
+        }
+        let node = self.next_node;
+        self.next_node += 1;
+
+        self.heap.clear();
+        self.lists.clear();
+        for (lender, num_nodes) in &mut self.lenders {
+            // graphs with fewer nodes have no successors for this node
+            if node < *num_nodes {
+                if let Some(lend) = lender.next() {
+                    let (_, succ) = lend.into_pair();
+                    let start = self.lists.len();
+                    self.lists.extend(succ);
+                    if start < self.lists.len() {
+                        self.heap
+                            .push(Reverse((self.lists[start], start, self.lists.len())));
+                    }
+                }
+            }
+        }
+
+        self.succ.clear();
+        while let Some(Reverse((succ, pos, end))) = self.heap.pop() {
+            if self.succ.last() != Some(&succ) {
+                self.succ.push(succ);
+            }
+            if pos + 1 < end {
+                self.heap.push(Reverse((self.lists[pos + 1], pos + 1, end)));
+            }
+        }
+
+        // SAFETY: the buffer contains the merge of sorted lists
+        Some((node, unsafe { SortedIter::new(self.succ.iter().copied()) }))
+    }
+}
+
+impl<L: Lender + for<'next> NodeLabelsLender<'next, Label = usize>> ExactSizeLender for Iter<L> {
+    fn len(&self) -> usize {
+        self.num_nodes - self.next_node
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_merge_graphs() -> anyhow::Result<()> {
+    use crate::graphs::vec_graph::VecGraph;
+    use crate::prelude::proj::Left;
+    let graphs = vec![
+        Left(VecGraph::from_arc_list([(0, 1), (0, 3), (2, 0), (2, 2)])),
+        Left(VecGraph::from_arc_list([(0, 1), (0, 2), (1, 2)])),
+        Left(VecGraph::from_arc_list([(2, 4), (4, 0), (4, 4)])),
+    ];
+    let merged = MergeGraphs::new(graphs);
+    assert_eq!(merged.num_nodes(), 5);
+
+    let mut iter = merged.iter();
+    assert_eq!(iter.len(), 5);
+    let mut result = vec![];
+    while let Some((node, succ)) = iter.next() {
+        result.push((node, succ.collect::<Vec<_>>()));
+    }
+    assert_eq!(
+        result,
+        vec![
+            (0, vec![1, 2, 3]),
+            (1, vec![2]),
+            (2, vec![0, 2, 4]),
+            (3, vec![]),
+            (4, vec![0, 4]),
+        ]
+    );
+
+    let mut iter = merged.iter_from(2);
+    assert_eq!(iter.next().unwrap().1.collect::<Vec<_>>(), vec![0, 2, 4]);
+
+    let empty = MergeGraphs::<Left<VecGraph>>::new(vec![]);
+    assert_eq!(empty.num_nodes(), 0);
+    assert!(empty.iter().next().is_none());
+    Ok(())
+}
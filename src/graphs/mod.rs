@@ -7,18 +7,40 @@
 
 //! Implementations of graphs.
 
+pub mod arc_count_check;
+pub mod arc_counts;
 pub mod arc_list_graph;
+#[cfg(feature = "async")]
+pub mod async_graph;
 pub mod bvgraph;
+pub mod concat_graphs;
+pub mod csr_graph;
+pub mod degree_recorder;
+#[cfg(feature = "kv")]
+pub mod kv_graph;
+pub mod masked_node_graph;
+pub mod merge_graphs;
 pub mod no_selfloops_graph;
 pub mod permuted_graph;
 pub mod random;
+pub mod sorted_successors_graph;
+pub mod symmetric_view;
 pub mod union_graph;
 pub mod vec_graph;
 
 pub mod prelude {
+    pub use super::arc_count_check::ArcCountCheck;
+    pub use super::arc_counts::count_arcs_between;
     pub use super::bvgraph::*;
+    pub use super::concat_graphs::{concat_graphs, ConcatGraphs};
+    pub use super::csr_graph::CsrGraph;
+    pub use super::degree_recorder::DegreeRecorder;
+    pub use super::masked_node_graph::*;
+    pub use super::merge_graphs::MergeGraphs;
     pub use super::no_selfloops_graph::NoSelfLoopsGraph;
     pub use super::permuted_graph::PermutedGraph;
+    pub use super::sorted_successors_graph::SortedSuccessorsGraph;
+    pub use super::symmetric_view::SymmetricView;
     pub use super::union_graph::UnionGraph;
     pub use super::vec_graph::VecGraph;
 }
@@ -25,6 +25,11 @@ impl<G: SequentialGraph> SequentialLabeling for NoSelfLoopsGraph<G> {
         self.0.num_nodes()
     }
 
+    #[inline(always)]
+    fn successors_sorted(&self) -> bool {
+        self.0.successors_sorted()
+    }
+
     #[inline(always)]
     fn num_arcs_hint(&self) -> Option<u64> {
         // it's just a hint, and we don't know how many self-loops there are
@@ -133,17 +138,16 @@ impl<I: Iterator<Item = usize>> Iterator for Succ<I> {
             }
         }
     }
-}
-
-unsafe impl<I: Iterator<Item = usize> + SortedIterator> SortedIterator for Succ<I> {}
 
-impl<I: ExactSizeIterator<Item = usize>> ExactSizeIterator for Succ<I> {
     #[inline(always)]
-    fn len(&self) -> usize {
-        self.iter.len()
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // we do not know how many loops are left
+        (0, self.iter.size_hint().1)
     }
 }
 
+unsafe impl<I: Iterator<Item = usize> + SortedIterator> SortedIterator for Succ<I> {}
+
 #[cfg(test)]
 #[test]
 fn test_no_selfloops_graph() -> anyhow::Result<()> {
@@ -5,9 +5,13 @@
  * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
  */
 
+use crate::graphs::csr_graph::CsrGraph;
 use crate::prelude::*;
+use anyhow::{ensure, Result};
+use dsi_progress_logger::prelude::*;
 use lender::*;
 use sux::traits::BitFieldSlice;
+use sysinfo::System;
 
 #[derive(Debug, Clone)]
 /// A wrapper applying a permutation to the iterators of an underlying graph.
@@ -77,6 +81,93 @@ impl<'a, 'b, G: SequentialGraph, P: BitFieldSlice<usize>> IntoLender
     }
 }
 
+impl<G: SequentialGraph, P: BitFieldSlice<usize>> PermutedGraph<'_, G, P> {
+    /// Returns an estimate in bytes of the memory used by
+    /// [`materialize`](PermutedGraph::materialize), or `None` if the number
+    /// of arcs of the underlying graph is unknown.
+    pub fn materialized_size(&self) -> Option<usize> {
+        self.num_arcs_hint()
+            .map(|num_arcs| CsrGraph::estimated_size(self.num_nodes(), num_arcs))
+    }
+
+    /// Materializes the permuted graph into an in-memory [`CsrGraph`] with
+    /// sorted successor lists.
+    ///
+    /// Compressing a permuted graph usually requires sorting its arcs
+    /// externally, as the iteration order follows the underlying graph. If
+    /// the permuted graph fits in memory, however, it is faster to scatter
+    /// the successors directly into a CSR representation. This method makes
+    /// two sequential passes on the underlying graph: the first one computes
+    /// the permuted outdegrees, and the second one writes the permuted
+    /// successors, which are then sorted.
+    ///
+    /// The permutation is [checked](check_perm) before starting, and an error
+    /// is returned if it is not a permutation of the nodes of the graph.
+    /// After the first pass the exact memory usage is known, and an error is
+    /// returned if it exceeds the memory currently available.
+    pub fn materialize(&self) -> Result<CsrGraph> {
+        let num_nodes = self.num_nodes();
+        check_perm(self.perm, num_nodes)?;
+
+        let mut pl = ProgressLogger::default();
+        pl.display_memory(true)
+            .item_name("node")
+            .expected_updates(Some(num_nodes));
+        pl.start("Computing permuted outdegrees...");
+        let mut offsets = vec![0_usize; num_nodes + 1];
+        for_!( (node, succ) in self.graph.iter() {
+            let succ = succ.into_iter();
+            offsets[self.perm.get(node) + 1] =
+                successors_len_hint(&succ).unwrap_or_else(|| succ.count());
+            pl.light_update();
+        });
+        pl.done();
+
+        for i in 0..num_nodes {
+            offsets[i + 1] += offsets[i];
+        }
+        let num_arcs = offsets[num_nodes];
+
+        let required = CsrGraph::estimated_size(num_nodes, num_arcs as u64);
+        let mut system = System::new();
+        system.refresh_memory();
+        ensure!(
+            required as u64 <= system.available_memory(),
+            "Materializing the permuted graph requires {} bytes, but only {} bytes are available",
+            required,
+            system.available_memory()
+        );
+
+        let mut successors = vec![0_usize; num_arcs];
+        pl.expected_updates(Some(num_nodes));
+        pl.start("Scattering permuted successors...");
+        for_!( (node, succ) in self.graph.iter() {
+            let node = self.perm.get(node);
+            let (start, end) = (offsets[node], offsets[node + 1]);
+            let mut pos = start;
+            for dst in succ {
+                ensure!(
+                    pos < end,
+                    "The outdegree of node {} changed between passes",
+                    node
+                );
+                successors[pos] = self.perm.get(dst);
+                pos += 1;
+            }
+            ensure!(
+                pos == end,
+                "The outdegree of node {} changed between passes",
+                node
+            );
+            successors[start..end].sort_unstable();
+            pl.light_update();
+        });
+        pl.done();
+
+        Ok(CsrGraph::from_parts(offsets, successors))
+    }
+}
+
 /// An iterator over the nodes of a graph that applies on the fly a permutation of the nodes.
 #[derive(Debug, Clone)]
 pub struct Iter<'node, I, P> {
@@ -143,6 +234,11 @@ impl<I: Iterator<Item = usize>, P: BitFieldSlice<usize>> Iterator for Succ<'_, I
     fn next(&mut self) -> Option<Self::Item> {
         self.iter.next().map(|succ| self.perm.get(succ))
     }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
 }
 
 impl<I: ExactSizeIterator<Item = usize>, P: BitFieldSlice<usize>> ExactSizeIterator
@@ -175,5 +271,24 @@ fn test_permuted_graph() -> anyhow::Result<()> {
     assert_eq!(v.successors(1).into_iter().collect::<Vec<_>>(), vec![0, 2]);
     assert_eq!(v.successors(2).into_iter().collect::<Vec<_>>(), vec![0]);
 
+    let m = p.materialize()?;
+    assert_eq!(m.num_nodes(), 3);
+    assert_eq!(m.num_arcs(), 4);
+    for node in 0..3 {
+        assert_eq!(
+            m.successors(node).collect::<Vec<_>>(),
+            v.successors(node).into_iter().collect::<Vec<_>>()
+        );
+    }
+
+    // Invalid permutations are rejected before materializing
+    for perm in [vec![2, 0], vec![2, 0, 3], vec![2, 0, 0]] {
+        let p = PermutedGraph {
+            graph: &Left(VecGraph::from_arc_list([(0, 1), (1, 2), (2, 0), (2, 1)])),
+            perm: &perm,
+        };
+        assert!(p.materialize().is_err());
+    }
+
     Ok(())
 }
@@ -49,6 +49,11 @@ impl SequentialLabeling for ErdosRenyi {
         self.n
     }
 
+    #[inline(always)]
+    fn successors_sorted(&self) -> bool {
+        true
+    }
+
     fn iter_from(&self, from: usize) -> Iter {
         let mut rng = SmallRng::seed_from_u64(self.seed);
         for _ in 0..from * (self.n - 1) {
@@ -0,0 +1,226 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+//! A wrapper sorting the successors of each node of a graph.
+
+use crate::prelude::*;
+use crate::traits::labels::SortedIter;
+use lender::*;
+
+/// A wrapper that sorts (and optionally deduplicates) the successor list of
+/// each node of a graph.
+///
+/// Compressors such as [`BvComp`] require successors in increasing order, but
+/// adapters (e.g., graphs built from unsorted arc lists, or wrappers
+/// permuting successors) might return them in arbitrary order. Wrapping such
+/// a graph guarantees that its successor lists can be compressed.
+///
+/// Each successor list is collected into a buffer owned by the lender, and
+/// then sorted; the cost per node is thus *O*(*d* log *d*), where *d* is the
+/// outdegree, plus a copy of the list. The buffer is reused, so after the
+/// largest successor list has been seen there are no further allocations.
+///
+/// If [deduplication](SortedSuccessorsGraph::new) is requested, repeated
+/// successors are returned once. Since the number of duplicates is not known
+/// in advance, the [number of arcs](SequentialLabeling::num_arcs_hint) of the
+/// underlying graph is returned as a hint.
+///
+/// Only graphs (i.e., successors without labels) can be wrapped: sorting
+/// labeled successors would require moving labels together with successors,
+/// and deduplication would require choosing which label to keep.
+#[derive(Debug, Clone)]
+pub struct SortedSuccessorsGraph<G> {
+    graph: G,
+    dedup: bool,
+}
+
+impl<G> SortedSuccessorsGraph<G> {
+    /// Wraps a graph, sorting its successor lists and, if `dedup` is true,
+    /// removing duplicate successors.
+    pub fn new(graph: G, dedup: bool) -> Self {
+        Self { graph, dedup }
+    }
+
+    /// Returns the wrapped graph.
+    pub fn into_inner(self) -> G {
+        self.graph
+    }
+}
+
+impl<G: SequentialGraph> SequentialLabeling for SortedSuccessorsGraph<G> {
+    type Label = usize;
+    type Lender<'b>
+        = Iter<G::Lender<'b>>
+    where
+        Self: 'b;
+
+    #[inline(always)]
+    fn num_nodes(&self) -> usize {
+        self.graph.num_nodes()
+    }
+
+    #[inline(always)]
+    fn successors_sorted(&self) -> bool {
+        self.dedup || self.graph.successors_sorted()
+    }
+
+    #[inline(always)]
+    fn num_arcs_hint(&self) -> Option<u64> {
+        // it's just a hint, and we don't know how many duplicates there are
+        self.graph.num_arcs_hint()
+    }
+
+    #[inline(always)]
+    fn iter_from(&self, from: usize) -> Self::Lender<'_> {
+        Iter::new(self.graph.iter_from(from), self.dedup)
+    }
+}
+
+impl<G: SequentialGraph + SplitLabeling> SplitLabeling for SortedSuccessorsGraph<G> {
+    type SplitLender<'a>
+        = Iter<G::SplitLender<'a>>
+    where
+        Self: 'a;
+    type IntoIterator<'a>
+        = SplitIter<<G::IntoIterator<'a> as IntoIterator>::IntoIter>
+    where
+        Self: 'a;
+
+    fn split_iter(&self, how_many: usize) -> Self::IntoIterator<'_> {
+        SplitIter {
+            iter: self.graph.split_iter(how_many).into_iter(),
+            dedup: self.dedup,
+        }
+    }
+}
+
+impl<G: SequentialGraph> SequentialGraph for SortedSuccessorsGraph<G> {}
+
+impl<'b, G: SequentialGraph> IntoLender for &'b SortedSuccessorsGraph<G> {
+    type Lender = <SortedSuccessorsGraph<G> as SequentialLabeling>::Lender<'b>;
+
+    #[inline(always)]
+    fn into_lender(self) -> Self::Lender {
+        self.iter()
+    }
+}
+
+/// An iterator over the splits of a [`SortedSuccessorsGraph`].
+#[derive(Debug, Clone)]
+pub struct SplitIter<I> {
+    iter: I,
+    dedup: bool,
+}
+
+impl<I: Iterator> Iterator for SplitIter<I> {
+    type Item = Iter<I::Item>;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|iter| Iter::new(iter, self.dedup))
+    }
+}
+
+/// A lender returning the successors of the underlying lender in sorted
+/// order.
+#[derive(Debug, Clone)]
+pub struct Iter<I> {
+    iter: I,
+    dedup: bool,
+    succ: Vec<usize>,
+}
+
+impl<I> Iter<I> {
+    fn new(iter: I, dedup: bool) -> Self {
+        Self {
+            iter,
+            dedup,
+            succ: Vec::new(),
+        }
+    }
+}
+
+impl<'succ, I: Lender + for<'next> NodeLabelsLender<'next, Label = usize>> NodeLabelsLender<'succ>
+    for Iter<I>
+{
+    type Label = usize;
+    type IntoIterator = SortedIter<core::iter::Copied<core::slice::Iter<'succ, usize>>>;
+}
+
+impl<'succ, I: Lender + for<'next> NodeLabelsLender<'next, Label = usize>> Lending<'succ>
+    for Iter<I>
+{
+    type Lend = (usize, <Self as NodeLabelsLender<'succ>>::IntoIterator);
+}
+
+unsafe impl<I: SortedLender + Lender + for<'next> NodeLabelsLender<'next, Label = usize>>
+    SortedLender for Iter<I>
+{
+}
+
+impl<L: Lender + for<'next> NodeLabelsLender<'next, Label = usize>> Lender for Iter<L> {
+    #[inline(always)]
+    fn next(&mut self) -> Option<Lend<'_, Self>> {
+        let (node, succ) = self.iter.next()?.into_pair();
+        self.succ.clear();
+        self.succ.extend(succ);
+        self.succ.sort_unstable();
+        if self.dedup {
+            self.succ.dedup();
+        }
+        // SAFETY: the buffer has just been sorted
+        Some((node, unsafe { SortedIter::new(self.succ.iter().copied()) }))
+    }
+}
+
+impl<L: ExactSizeLender + for<'next> NodeLabelsLender<'next, Label = usize>> ExactSizeLender
+    for Iter<L>
+{
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_sorted_successors_graph() -> anyhow::Result<()> {
+    use crate::graphs::{arc_list_graph::ArcListGraph, vec_graph::VecGraph};
+    use crate::prelude::proj::Left;
+    // An arc list graph returns successors in the order of the arcs
+    let arcs = [(0, 2), (0, 1), (0, 2), (1, 0), (2, 2), (2, 1), (2, 0)];
+    let unsorted = Left(ArcListGraph::new(3, arcs));
+    let mut iter = unsorted.iter();
+    assert_eq!(
+        iter.next().unwrap().1.into_iter().collect::<Vec<_>>(),
+        vec![2, 1, 2]
+    );
+
+    let sorted = SortedSuccessorsGraph::new(&unsorted, false);
+    let mut iter = sorted.iter();
+    assert_eq!(iter.next().unwrap().1.collect::<Vec<_>>(), vec![1, 2, 2]);
+    assert_eq!(iter.next().unwrap().1.collect::<Vec<_>>(), vec![0]);
+    assert_eq!(iter.next().unwrap().1.collect::<Vec<_>>(), vec![0, 1, 2]);
+    assert!(iter.next().is_none());
+
+    let dedup = SortedSuccessorsGraph::new(&unsorted, true);
+    let expected = Left(VecGraph::from_arc_list(arcs));
+    crate::assert_graph_eq!(dedup, expected);
+
+    // Splits are sorted, too
+    let g = SortedSuccessorsGraph::new(&expected, true);
+    let mut nodes = 0;
+    for lender in g.split_iter(2) {
+        for_!((node, succ) in lender {
+            assert_eq!(
+                succ.collect::<Vec<_>>(),
+                expected.successors(node).into_iter().collect::<Vec<_>>()
+            );
+            nodes += 1;
+        });
+    }
+    assert_eq!(nodes, 3);
+    Ok(())
+}
@@ -0,0 +1,270 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use super::union_graph::Succ;
+use crate::prelude::*;
+use lender::*;
+use std::sync::OnceLock;
+
+/// A wrapper exhibiting the symmetric (i.e., undirected) view of a graph,
+/// given the graph and its transpose.
+///
+/// The successors of a node are the sorted, deduplicated union of its
+/// successors in the graph and in the transpose, that is, of its out- and
+/// in-neighbors in the graph. Thus, algorithms needing an undirected graph,
+/// such as [layered label propagation](crate::algo::llp) or the computation
+/// of connected components, can be run without materializing a symmetrized
+/// graph with [`simplify`](crate::transform::simplify).
+///
+/// Both the graph and its transpose must be loaded with random access, and
+/// both must return sorted successors with no duplicates.
+///
+/// Note that since the view is computed on the fly,
+/// [`outdegree`](RandomAccessLabeling::outdegree) returns the undirected
+/// degree of a node, and it is computed by merging the two successor lists;
+/// [`outdegree_hint`](RandomAccessLabeling::outdegree_hint) returns instead
+/// an upper bound in constant time.
+/// Analogously, the first call to [`num_arcs`](RandomAccessLabeling::num_arcs)
+/// computes the number of arcs of the symmetric graph by scanning all nodes;
+/// the result is cached for subsequent calls.
+///
+/// Self-loops are reported once.
+#[derive(Debug, Clone)]
+pub struct SymmetricView<G, T> {
+    graph: G,
+    transpose: T,
+    num_arcs: OnceLock<u64>,
+}
+
+impl<G: RandomAccessGraph, T: RandomAccessGraph> SymmetricView<G, T> {
+    /// Creates a new symmetric view of `graph`, given its `transpose`.
+    ///
+    /// # Panics
+    ///
+    /// If the two graphs do not have the same number of nodes.
+    pub fn new(graph: G, transpose: T) -> Self {
+        assert_eq!(
+            graph.num_nodes(),
+            transpose.num_nodes(),
+            "The graph and its transpose have a different number of nodes"
+        );
+        Self {
+            graph,
+            transpose,
+            num_arcs: OnceLock::new(),
+        }
+    }
+
+    /// Returns the underlying graph and transpose.
+    pub fn into_inner(self) -> (G, T) {
+        (self.graph, self.transpose)
+    }
+}
+
+impl<G: RandomAccessGraph, T: RandomAccessGraph> SequentialLabeling for SymmetricView<G, T> {
+    type Label = usize;
+    type Lender<'b>
+        = Iter<'b, G, T>
+    where
+        Self: 'b;
+
+    #[inline(always)]
+    fn num_nodes(&self) -> usize {
+        self.graph.num_nodes()
+    }
+
+    #[inline(always)]
+    fn successors_sorted(&self) -> bool {
+        true
+    }
+
+    #[inline(always)]
+    fn num_arcs_hint(&self) -> Option<u64> {
+        self.num_arcs.get().copied()
+    }
+
+    #[inline(always)]
+    fn iter_from(&self, from: usize) -> Self::Lender<'_> {
+        Iter {
+            view: self,
+            nodes: (from..self.num_nodes()),
+        }
+    }
+}
+
+impl<G: RandomAccessGraph, T: RandomAccessGraph> RandomAccessLabeling for SymmetricView<G, T> {
+    type Labels<'succ>
+        = Succ<
+        <<G as RandomAccessLabeling>::Labels<'succ> as IntoIterator>::IntoIter,
+        <<T as RandomAccessLabeling>::Labels<'succ> as IntoIterator>::IntoIter,
+    >
+    where
+        Self: 'succ;
+
+    /// Returns the number of arcs of the symmetric graph.
+    ///
+    /// The first call scans all nodes.
+    fn num_arcs(&self) -> u64 {
+        *self.num_arcs.get_or_init(|| {
+            (0..self.num_nodes())
+                .map(|node| self.outdegree(node) as u64)
+                .sum()
+        })
+    }
+
+    /// Returns the undirected degree of a node, computed by merging its
+    /// successors in the graph and in the transpose.
+    #[inline(always)]
+    fn outdegree(&self, node: usize) -> usize {
+        RandomAccessLabeling::labels(self, node).count()
+    }
+
+    /// Returns the sum of the outdegrees of a node in the graph and in the
+    /// transpose, without merging the successor lists.
+    #[inline(always)]
+    fn outdegree_hint(&self, node: usize) -> usize {
+        self.graph.outdegree(node) + self.transpose.outdegree(node)
+    }
+
+    #[inline(always)]
+    fn labels(&self, node: usize) -> <Self as RandomAccessLabeling>::Labels<'_> {
+        Succ::new(
+            Some(self.graph.successors(node).into_iter()),
+            Some(self.transpose.successors(node).into_iter()),
+        )
+    }
+}
+
+impl<G: RandomAccessGraph, T: RandomAccessGraph> SequentialGraph for SymmetricView<G, T> {}
+
+impl<G: RandomAccessGraph, T: RandomAccessGraph> RandomAccessGraph for SymmetricView<G, T> {}
+
+impl<G: RandomAccessGraph + Send + Sync, T: RandomAccessGraph + Send + Sync> SplitLabeling
+    for SymmetricView<G, T>
+{
+    type SplitLender<'a>
+        = split::ra::Lender<'a, SymmetricView<G, T>>
+    where
+        Self: 'a;
+    type IntoIterator<'a>
+        = split::ra::IntoIterator<'a, SymmetricView<G, T>>
+    where
+        Self: 'a;
+
+    fn split_iter(&self, how_many: usize) -> Self::IntoIterator<'_> {
+        split::ra::Iter::new(self, how_many)
+    }
+}
+
+impl<'b, G: RandomAccessGraph, T: RandomAccessGraph> IntoLender for &'b SymmetricView<G, T> {
+    type Lender = <SymmetricView<G, T> as SequentialLabeling>::Lender<'b>;
+
+    #[inline(always)]
+    fn into_lender(self) -> Self::Lender {
+        self.iter()
+    }
+}
+
+/// A sequential iterator over a [`SymmetricView`].
+///
+/// Contrarily to [`IteratorImpl`], the successors returned by the lender
+/// borrow from the view, rather than from the lender, so no lifetime bound
+/// on the underlying graphs is needed.
+pub struct Iter<'a, G, T> {
+    view: &'a SymmetricView<G, T>,
+    nodes: core::ops::Range<usize>,
+}
+
+unsafe impl<'a, G: RandomAccessGraph, T: RandomAccessGraph> SortedLender for Iter<'a, G, T>
+where
+    <<G as RandomAccessLabeling>::Labels<'a> as IntoIterator>::IntoIter: SortedIterator,
+    <<T as RandomAccessLabeling>::Labels<'a> as IntoIterator>::IntoIter: SortedIterator,
+{
+}
+
+impl<'a, G: RandomAccessGraph, T: RandomAccessGraph> NodeLabelsLender<'_> for Iter<'a, G, T> {
+    type Label = usize;
+    type IntoIterator = <SymmetricView<G, T> as RandomAccessLabeling>::Labels<'a>;
+}
+
+impl<'a, G: RandomAccessGraph, T: RandomAccessGraph> Lending<'_> for Iter<'a, G, T> {
+    type Lend = (
+        usize,
+        <SymmetricView<G, T> as RandomAccessLabeling>::Labels<'a>,
+    );
+}
+
+impl<G: RandomAccessGraph, T: RandomAccessGraph> Lender for Iter<'_, G, T> {
+    #[inline(always)]
+    fn next(&mut self) -> Option<Lend<'_, Self>> {
+        let view = self.view;
+        self.nodes
+            .next()
+            .map(|node| (node, RandomAccessLabeling::labels(view, node)))
+    }
+}
+
+impl<G: RandomAccessGraph, T: RandomAccessGraph> ExactSizeLender for Iter<'_, G, T> {
+    #[inline(always)]
+    fn len(&self) -> usize {
+        self.nodes.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphs::vec_graph::VecGraph;
+
+    #[test]
+    fn test_symmetric_view() -> anyhow::Result<()> {
+        let arcs = [
+            (0, 1),
+            (0, 3),
+            (1, 2),
+            (2, 0),
+            (2, 2),
+            (2, 4),
+            (3, 4),
+            (3, 5),
+            (4, 1),
+            (5, 3),
+            (6, 6),
+        ];
+        let graph = Left(VecGraph::from_arc_list(arcs));
+        let transpose = Left(VecGraph::from_arc_list(arcs.map(|(x, y)| (y, x))));
+        let symmetric = Left(VecGraph::from_arc_list(
+            arcs.into_iter().chain(arcs.map(|(x, y)| (y, x))),
+        ));
+
+        let view = SymmetricView::new(&graph, &transpose);
+        assert_eq!(view.num_nodes(), 7);
+        assert_eq!(view.num_arcs_hint(), None);
+        assert_eq!(view.num_arcs(), symmetric.num_arcs());
+        assert_eq!(view.num_arcs_hint(), Some(symmetric.num_arcs()));
+
+        for node in 0..view.num_nodes() {
+            assert_eq!(
+                view.successors(node).collect::<Vec<_>>(),
+                symmetric.successors(node).into_iter().collect::<Vec<_>>()
+            );
+            assert_eq!(view.outdegree(node), symmetric.outdegree(node));
+        }
+
+        // Sequential and split iteration agree with random access
+        assert_eq!(Left(VecGraph::from_lender(&view)), symmetric);
+        let mut split = VecGraph::empty(view.num_nodes());
+        for lender in view.split_iter(3) {
+            for_!((node, succ) in lender {
+                for dst in succ {
+                    split.add_arc(node, dst);
+                }
+            });
+        }
+        assert_eq!(Left(split), symmetric);
+        Ok(())
+    }
+}
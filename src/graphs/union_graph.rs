@@ -29,6 +29,11 @@ where
         self.0.num_nodes().max(self.1.num_nodes())
     }
 
+    #[inline(always)]
+    fn successors_sorted(&self) -> bool {
+        self.0.successors_sorted() && self.1.successors_sorted()
+    }
+
     #[inline(always)]
     fn num_arcs_hint(&self) -> Option<u64> {
         None
@@ -179,6 +184,25 @@ impl<I: Iterator<Item = usize>, J: Iterator<Item = usize>> Iterator for Succ<I,
             }
         }
     }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // each call to next consumes at most one element from each iterator
+        let (lower0, upper0) = self
+            .iter0
+            .as_ref()
+            .map_or((0, Some(0)), Iterator::size_hint);
+        let (lower1, upper1) = self
+            .iter1
+            .as_ref()
+            .map_or((0, Some(0)), Iterator::size_hint);
+        (
+            lower0.max(lower1),
+            upper0
+                .zip(upper1)
+                .and_then(|(upper0, upper1)| upper0.checked_add(upper1)),
+        )
+    }
 }
 
 unsafe impl<I: Iterator<Item = usize> + SortedIterator, J: Iterator<Item = usize> + SortedIterator>
@@ -166,6 +166,23 @@ impl<L: Clone + 'static> VecGraph<L> {
         g.add_labeled_arcs(arcs);
         g
     }
+
+    /// Returns the transpose of this graph, built directly in memory.
+    ///
+    /// The transpose has the same number of nodes, successors are sorted, and
+    /// each arc keeps its label. For large graphs, use
+    /// [`transpose`](crate::transform::transpose), which sorts arcs in
+    /// external memory.
+    pub fn transpose(&self) -> Self {
+        let mut transpose = Self::empty(self.succ.len());
+        for (node, succ) in self.succ.iter().enumerate() {
+            for Successor(v, l) in succ {
+                transpose.succ[*v].insert(Successor(node, l.clone()));
+            }
+        }
+        transpose.number_of_arcs = self.number_of_arcs;
+        transpose
+    }
 }
 
 impl VecGraph<()> {
@@ -245,6 +262,11 @@ impl<L: Clone + 'static> SequentialLabeling for VecGraph<L> {
         self.succ.len()
     }
 
+    #[inline(always)]
+    fn successors_sorted(&self) -> bool {
+        true
+    }
+
     #[inline(always)]
     fn num_arcs_hint(&self) -> Option<u64> {
         Some(self.num_arcs())
@@ -284,6 +306,21 @@ impl<L: Clone + 'static> RandomAccessLabeling for VecGraph<L> {
 
 impl<L: Clone + 'static> LabeledRandomAccessGraph<L> for VecGraph<L> {}
 
+impl<L: Clone + Send + Sync + 'static> SplitLabeling for VecGraph<L> {
+    type SplitLender<'a>
+        = split::ra::Lender<'a, VecGraph<L>>
+    where
+        Self: 'a;
+    type IntoIterator<'a>
+        = split::ra::IntoIterator<'a, VecGraph<L>>
+    where
+        Self: 'a;
+
+    fn split_iter(&self, how_many: usize) -> Self::IntoIterator<'_> {
+        split::ra::Iter::new(self, how_many)
+    }
+}
+
 #[doc(hidden)]
 #[repr(transparent)]
 pub struct Successors<'a, L: Clone + 'static>(std::collections::btree_set::Iter<'a, Successor<L>>);
@@ -294,6 +331,11 @@ impl<L: Clone + 'static> Iterator for Successors<'_, L> {
     fn next(&mut self) -> Option<Self::Item> {
         self.0.next().cloned().map(|x| (x.0, x.1))
     }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
 }
 
 unsafe impl<L: Clone + 'static> SortedIterator for Successors<'_, L> {}
@@ -311,3 +353,27 @@ fn test_remove() {
     assert!(g.remove_arc(0, 2));
     assert!(!g.remove_arc(0, 2));
 }
+
+#[test]
+fn test_transpose() {
+    let mut g = VecGraph::<_>::from_labeled_arc_list([(0, 1, 1), (0, 2, 2), (2, 1, 3), (2, 0, 4)]);
+    // An isolated node at the end
+    g.add_node(3);
+    let t = g.transpose();
+    assert_eq!(t.num_nodes(), 4);
+    assert_eq!(t.num_arcs(), 4);
+    assert_eq!(
+        RandomAccessLabeling::labels(&t, 0).collect::<Vec<_>>(),
+        vec![(2, 4)]
+    );
+    assert_eq!(
+        RandomAccessLabeling::labels(&t, 1).collect::<Vec<_>>(),
+        vec![(0, 1), (2, 3)]
+    );
+    assert_eq!(
+        RandomAccessLabeling::labels(&t, 2).collect::<Vec<_>>(),
+        vec![(0, 2)]
+    );
+    assert_eq!(RandomAccessLabeling::labels(&t, 3).count(), 0);
+    assert_eq!(t.transpose(), g);
+}
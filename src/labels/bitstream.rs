@@ -11,13 +11,22 @@
 //! deserializer](BitDeserializer). An [`IndexedSeq`] provides pointers into the
 //! bitstream. Both sequential and random access are provided.
 //!
-//! See the examples for a complete implementation based on memory mapping.
+//! [`BitStreamLabeling::mmap`] provides a complete implementation based on
+//! memory mapping, in which offsets are stored in a `.labelef` file built by
+//! `webgraph build label-ef`.
 
+use crate::graphs::bvgraph::{EF, LABELEF_EXTENSION, LABELS_EXTENSION};
 use crate::prelude::BitDeserializer;
 use crate::prelude::{NodeLabelsLender, RandomAccessLabeling, SequentialLabeling};
+use crate::utils::{Basename, MmapHelper};
+use anyhow::{Context, Result};
+use dsi_bitstream::impls::{BufBitReader, MemWordReader};
 use dsi_bitstream::traits::{BitRead, BitSeek, Endianness};
+use epserde::deser::{DeserType, Deserialize, Flags, MemCase};
 use lender::*;
+use mmap_rs::MmapFlags;
 use std::ops::Deref;
+use std::path::Path;
 use sux::traits::{IndexedSeq, Types};
 
 /// A basic supplier trait.
@@ -68,6 +77,70 @@ where
     }
 }
 
+/// A [`Supply`] of bit readers on a memory-mapped bitstream.
+pub struct MmapReaderSupplier<E: Endianness> {
+    backend: MmapHelper<u32>,
+    _marker: std::marker::PhantomData<E>,
+}
+
+impl<E: Endianness> MmapReaderSupplier<E> {
+    /// Memory-maps the bitstream at `path` using the given flags.
+    pub fn mmap(path: impl AsRef<Path>, flags: MmapFlags) -> Result<Self> {
+        let path = path.as_ref();
+        Ok(Self {
+            backend: MmapHelper::<u32>::mmap(path, flags)
+                .with_context(|| format!("Could not mmap {}", path.display()))?,
+            _marker: std::marker::PhantomData,
+        })
+    }
+}
+
+impl<E: Endianness> Supply for MmapReaderSupplier<E> {
+    type Item<'a>
+        = BufBitReader<E, MemWordReader<u32, &'a [u32]>>
+    where
+        Self: 'a;
+
+    fn request(&self) -> Self::Item<'_> {
+        BufBitReader::<E, _>::new(MemWordReader::new(self.backend.as_ref()))
+    }
+}
+
+/// A [`BitStreamLabeling`] on a memory-mapped bitstream whose offsets are a
+/// memory-mapped Elias–Fano representation.
+pub type MmapBitStreamLabeling<E, D> =
+    BitStreamLabeling<E, MmapReaderSupplier<E>, D, MemCase<DeserType<'static, EF>>>;
+
+impl<E: Endianness, D> MmapBitStreamLabeling<E, D>
+where
+    for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: BitRead<E> + BitSeek,
+    for<'a> D: BitDeserializer<E, BufBitReader<E, MemWordReader<u32, &'a [u32]>>>,
+{
+    /// Memory-maps the labels with given basename.
+    ///
+    /// The bitstream of labels is read from the file with extension
+    /// [`.labels`](LABELS_EXTENSION), and the Elias–Fano representation of
+    /// the offsets, which provides random access to the labels of each node,
+    /// from the file with extension [`.labelef`](LABELEF_EXTENSION), which
+    /// can be built from the `.labeloffsets` file using `webgraph build
+    /// label-ef`.
+    pub fn mmap(basename: impl AsRef<Path>, bit_deser: D) -> Result<Self> {
+        let basename = Basename::new(basename);
+        let labels_path = basename.with_ext(LABELS_EXTENSION);
+        let ef_path = basename.with_ext(LABELEF_EXTENSION);
+        Ok(BitStreamLabeling::new(
+            MmapReaderSupplier::mmap(&labels_path, MmapFlags::empty())?,
+            bit_deser,
+            EF::mmap(&ef_path, Flags::empty()).with_context(|| {
+                format!(
+                    "Could not load {} (use \"webgraph build label-ef\" to build it)",
+                    ef_path.display()
+                )
+            })?,
+        ))
+    }
+}
+
 pub struct Iter<'a, 'b, E, BR, D, O> {
     reader: BR,
     bit_deser: &'a D,
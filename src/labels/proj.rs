@@ -66,6 +66,11 @@ where
     fn next(&mut self) -> Option<Self::Item> {
         self.0.next().map(|x| x.into_pair().0)
     }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
 }
 
 impl<I: IntoIterator> IntoIterator for LeftIntoIterator<I>
@@ -143,6 +148,11 @@ where
         self.0.num_nodes()
     }
 
+    #[inline(always)]
+    fn successors_sorted(&self) -> bool {
+        self.0.successors_sorted()
+    }
+
     fn iter_from(&self, from: usize) -> Self::Lender<'_> {
         LeftIterator(self.0.iter_from(from))
     }
@@ -172,6 +182,11 @@ where
     fn outdegree(&self, _node_id: usize) -> usize {
         self.0.outdegree(_node_id)
     }
+
+    #[inline(always)]
+    fn outdegree_hint(&self, node_id: usize) -> usize {
+        self.0.outdegree_hint(node_id)
+    }
 }
 
 impl<S: SequentialLabeling> SequentialGraph for Left<S> where S::Label: Pair<Left = usize> {}
@@ -232,6 +247,11 @@ where
     fn next(&mut self) -> Option<Self::Item> {
         self.0.next().map(|x| x.into_pair().1)
     }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
 }
 
 impl<I: IntoIterator> IntoIterator for RightIntoIterator<I>
@@ -338,6 +358,11 @@ where
     fn outdegree(&self, _node_id: usize) -> usize {
         self.0.outdegree(_node_id)
     }
+
+    #[inline(always)]
+    fn outdegree_hint(&self, node_id: usize) -> usize {
+        self.0.outdegree_hint(node_id)
+    }
 }
 
 impl<S: SequentialLabeling> SequentialGraph for Right<S> where S::Label: Pair<Right = usize> {}
@@ -128,6 +128,11 @@ impl<L: SequentialLabeling, R: SequentialLabeling> SequentialLabeling for Zip<L,
         self.0.num_nodes()
     }
 
+    #[inline(always)]
+    fn successors_sorted(&self) -> bool {
+        self.0.successors_sorted()
+    }
+
     fn iter_from(&self, from: usize) -> Self::Lender<'_> {
         Iter(self.0.iter_from(from), self.1.iter_from(from))
     }
@@ -159,6 +164,11 @@ impl<L: RandomAccessLabeling, R: RandomAccessLabeling> RandomAccessLabeling for
         debug_assert_eq!(self.0.outdegree(_node_id), self.1.outdegree(_node_id));
         self.0.outdegree(_node_id)
     }
+
+    #[inline(always)]
+    fn outdegree_hint(&self, node_id: usize) -> usize {
+        self.0.outdegree_hint(node_id)
+    }
 }
 
 impl<G: SequentialGraph, L: SequentialLabeling> LabeledSequentialGraph<L::Label> for Zip<G, L> {}
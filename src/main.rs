@@ -4,10 +4,38 @@
  * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
  */
 
-use anyhow::Result;
 use webgraph::cli::main as cli_main;
+use webgraph::cli::ExitCode;
+use webgraph::utils::interrupt;
 
-pub fn main() -> Result<()> {
+/// Requests an [interruption](interrupt) on `SIGINT`.
+///
+/// The running command stops at the next check of the interruption flag and
+/// exits with [`ExitCode::Interrupted`], after removing partial output files
+/// and temporary directories. Since some loops do not check the flag, a
+/// second `SIGINT` exits immediately, leaving files behind.
+#[cfg(unix)]
+extern "C" fn on_sigint(_signal: libc::c_int) {
+    if interrupt::is_interrupted() {
+        unsafe { libc::_exit(ExitCode::Interrupted as libc::c_int) }
+    }
+    interrupt::interrupt();
+}
+
+pub fn main() -> std::process::ExitCode {
+    #[cfg(unix)]
+    unsafe {
+        libc::signal(
+            libc::SIGINT,
+            on_sigint as extern "C" fn(libc::c_int) as libc::sighandler_t,
+        );
+    }
     // Call the main function of the CLI with cli args
-    cli_main(std::env::args_os())
+    match cli_main(std::env::args_os()) {
+        Ok(()) => ExitCode::Success.into(),
+        Err(err) => {
+            eprintln!("Error: {:?}", err);
+            ExitCode::of(&err).into()
+        }
+    }
 }
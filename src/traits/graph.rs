@@ -41,6 +41,8 @@ use std::rc::Rc;
 use crate::prelude::{Pair, RandomAccessLabeling, SequentialLabeling};
 use impl_tools::autoimpl;
 use lender::*;
+use rand::Rng;
+use sux::traits::Succ;
 
 use super::lenders::{LenderIntoIter, NodeLabelsLender};
 
@@ -55,7 +57,26 @@ struct this_method_cannot_be_called_use_successors_instead;
 /// [`SortedIterator`](super::labels::SortedIterator) can be used to force these
 /// properties.
 #[autoimpl(for<S: trait + ?Sized> &S, &mut S, Rc<S>)]
-pub trait SequentialGraph: SequentialLabeling<Label = usize> {}
+pub trait SequentialGraph: SequentialLabeling<Label = usize> {
+    /// Returns an iterator over the arcs of the graph as `(src, dst)` pairs.
+    ///
+    /// The iterator flattens the [lender](SequentialLabeling::iter) of the
+    /// graph, so arcs are returned in the same order as the lender and its
+    /// successor iterators return them: in particular, if the lender is a
+    /// [`SortedLender`](super::labels::SortedLender) and the successors are a
+    /// [`SortedIterator`](super::labels::SortedIterator), arcs are returned in
+    /// lexicographical order.
+    ///
+    /// Arcs are streamed lazily: only the successors of the current node are
+    /// buffered.
+    fn arcs(&self) -> Arcs<Self::Lender<'_>> {
+        Arcs {
+            lender: self.iter(),
+            src: 0,
+            succ: Vec::new(),
+        }
+    }
+}
 
 /// Convenience type alias for the iterator over the successors of a node
 /// returned by the [`iter_from`](SequentialLabeling::iter_from) method.
@@ -98,15 +119,107 @@ pub trait RandomAccessGraph: RandomAccessLabeling<Label = usize> + SequentialGra
 
     /// Returns whether there is an arc going from `src_node_id` to `dst_node_id`.
     ///
-    /// Note that the default implementation performs a linear scan.
+    /// Note that the default implementation performs a linear scan, which
+    /// stops at the first successor greater than `dst_node_id` if
+    /// [successors are sorted](SequentialLabeling::successors_sorted).
     fn has_arc(&self, src_node_id: usize, dst_node_id: usize) -> bool {
+        let sorted = self.successors_sorted();
         for neighbour_id in self.successors(src_node_id) {
             if neighbour_id == dst_node_id {
                 return true;
             }
+            if sorted && neighbour_id > dst_node_id {
+                return false;
+            }
         }
         false
     }
+
+    /// Returns the successor of a node of given rank, that is, the `k`-th
+    /// successor (starting from zero) in the order in which successors are
+    /// returned by [`successors`](RandomAccessGraph::successors), or `None`
+    /// if the node has at most `k` successors.
+    ///
+    /// Note that the default implementation enumerates the first `k + 1`
+    /// successors.
+    fn succ_select(&self, node_id: usize, k: usize) -> Option<usize> {
+        self.successors(node_id).into_iter().nth(k)
+    }
+
+    /// Returns the number of successors of a node that are smaller than
+    /// `dst_node_id`.
+    ///
+    /// Note that the default implementation enumerates all successors, as it
+    /// cannot assume that they are returned in increasing order.
+    fn succ_rank(&self, node_id: usize, dst_node_id: usize) -> usize {
+        self.successors(node_id)
+            .into_iter()
+            .filter(|&succ| succ < dst_node_id)
+            .count()
+    }
+
+    /// Decodes the successors of a node into a buffer, returning their number.
+    ///
+    /// The buffer is cleared before being filled, so its previous content is
+    /// overwritten. Reusing the same buffer across calls avoids allocating
+    /// memory for each node in loops that need the successors of many nodes
+    /// in a slice, as the buffer grows to the maximum outdegree seen so far.
+    fn decode_successors_into(&self, node_id: usize, buf: &mut Vec<usize>) -> usize {
+        buf.clear();
+        buf.extend(self.successors(node_id));
+        buf.len()
+    }
+
+    /// Returns the successors of a node in reverse order.
+    ///
+    /// If successors are returned by [`successors`](RandomAccessGraph::successors)
+    /// in increasing order (e.g., if they implement
+    /// [`SortedIterator`](super::labels::SortedIterator)), this method returns
+    /// them from the largest to the smallest.
+    ///
+    /// Note that the default implementation decodes the successors in the
+    /// usual order and buffers them in a vector, so it allocates memory
+    /// linear in the outdegree of the node at each call.
+    fn successors_rev(&self, node_id: usize) -> std::iter::Rev<std::vec::IntoIter<usize>> {
+        self.successors(node_id)
+            .into_iter()
+            .collect::<Vec<_>>()
+            .into_iter()
+            .rev()
+    }
+    /// Returns an arc chosen uniformly at random as a pair (source, target).
+    ///
+    /// The arc is chosen by drawing a random index in `0..num_arcs`: its
+    /// source is located using the [successor](Succ::succ) method of
+    /// `deg_cumul`, and its target is the successor of the source with
+    /// the appropriate [rank](RandomAccessGraph::succ_select).
+    ///
+    /// `deg_cumul` must be the degree cumulative function of the graph, that
+    /// is, the sequence of the number of arcs of the nodes smaller than each
+    /// node, from zero to the number of arcs of the graph included, such as
+    /// the `.dcf` file built by `webgraph build dcf`. Thanks to it, the source
+    /// is located in time logarithmic in the number of nodes (constant, in
+    /// practice, if the function is an Elias–Fano representation with
+    /// selection structures, as [`DCF`](crate::graphs::bvgraph::DCF)).
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the graph has no arcs.
+    fn random_arc(
+        &self,
+        deg_cumul: &impl Succ<Input = usize, Output = usize>,
+        rng: &mut impl Rng,
+    ) -> (usize, usize) {
+        let num_arcs = self.num_arcs() as usize;
+        assert!(num_arcs != 0, "The graph has no arcs");
+        let arc = rng.gen_range(0..num_arcs);
+        // The first node whose cumulative degree exceeds the index of the
+        // arc follows the source
+        let (next, _) = deg_cumul.succ(&(arc + 1)).unwrap();
+        let src = next - 1;
+        let rank = arc - deg_cumul.get(src);
+        (src, self.succ_select(src, rank).unwrap())
+    }
 }
 
 /// A labeled sequential graph.
@@ -115,7 +228,78 @@ pub trait RandomAccessGraph: RandomAccessLabeling<Label = usize> + SequentialGra
 /// `(usize, L)`. The first coordinate is the successor, the second is the
 /// label.
 #[autoimpl(for<S: trait + ?Sized> &S, &mut S, Rc<S>)]
-pub trait LabeledSequentialGraph<L>: SequentialLabeling<Label = (usize, L)> {}
+pub trait LabeledSequentialGraph<L>: SequentialLabeling<Label = (usize, L)> {
+    /// Returns an iterator over the arcs of the graph as `(src, dst, label)`
+    /// triples.
+    ///
+    /// This is the labeled analogue of [`SequentialGraph::arcs`].
+    fn labeled_arcs(&self) -> LabeledArcs<Self::Lender<'_>, L> {
+        LabeledArcs {
+            lender: self.iter(),
+            src: 0,
+            succ: Vec::new(),
+        }
+    }
+}
+
+/// An iterator over the arcs of a [`SequentialGraph`].
+///
+/// This struct is returned by [`SequentialGraph::arcs`].
+#[derive(Debug, Clone)]
+pub struct Arcs<L> {
+    lender: L,
+    src: usize,
+    /// The successors of `src` still to be returned, in reverse order.
+    succ: Vec<usize>,
+}
+
+impl<L: Lender + for<'next> NodeLabelsLender<'next, Label = usize>> Iterator for Arcs<L> {
+    type Item = (usize, usize);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(dst) = self.succ.pop() {
+                return Some((self.src, dst));
+            }
+            let (src, succ) = self.lender.next()?.into_pair();
+            self.src = src;
+            self.succ.extend(succ);
+            self.succ.reverse();
+        }
+    }
+}
+
+/// An iterator over the arcs of a [`LabeledSequentialGraph`].
+///
+/// This struct is returned by [`LabeledSequentialGraph::labeled_arcs`].
+#[derive(Debug, Clone)]
+pub struct LabeledArcs<L, T> {
+    lender: L,
+    src: usize,
+    /// The successors of `src` and their labels still to be returned, in
+    /// reverse order.
+    succ: Vec<(usize, T)>,
+}
+
+impl<T, L: Lender + for<'next> NodeLabelsLender<'next, Label = (usize, T)>> Iterator
+    for LabeledArcs<L, T>
+{
+    type Item = (usize, usize, T);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((dst, label)) = self.succ.pop() {
+                return Some((self.src, dst, label));
+            }
+            let (src, succ) = self.lender.next()?.into_pair();
+            self.src = src;
+            self.succ.extend(succ);
+            self.succ.reverse();
+        }
+    }
+}
 
 /// A wrapper associating to each successor the label `()`.
 ///
@@ -170,6 +354,18 @@ impl<I: Iterator<Item = usize>> Iterator for UnitSuccessors<I> {
     fn next(&mut self) -> Option<Self::Item> {
         Some((self.0.next()?, ()))
     }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<I: ExactSizeIterator<Item = usize>> ExactSizeIterator for UnitSuccessors<I> {
+    #[inline(always)]
+    fn len(&self) -> usize {
+        self.0.len()
+    }
 }
 
 impl<G: SequentialGraph> SequentialLabeling for UnitLabelGraph<G> {
@@ -231,12 +427,18 @@ pub trait LabeledRandomAccessGraph<L>: RandomAccessLabeling<Label = (usize, L)>
 
     /// Returns whether there is an arc going from `src_node_id` to `dst_node_id`.
     ///
-    /// Note that the default implementation performs a linear scan.
+    /// Note that the default implementation performs a linear scan, which
+    /// stops at the first successor greater than `dst_node_id` if
+    /// [successors are sorted](SequentialLabeling::successors_sorted).
     fn has_arc(&self, src_node_id: usize, dst_node_id: usize) -> bool {
+        let sorted = self.successors_sorted();
         for (neighbour_id, _) in self.successors(src_node_id) {
             if neighbour_id == dst_node_id {
                 return true;
             }
+            if sorted && neighbour_id > dst_node_id {
+                return false;
+            }
         }
         false
     }
@@ -259,6 +461,11 @@ impl<G: RandomAccessGraph> RandomAccessLabeling for UnitLabelGraph<G> {
     fn outdegree(&self, node_id: usize) -> usize {
         self.0.outdegree(node_id)
     }
+
+    #[inline(always)]
+    fn outdegree_hint(&self, node_id: usize) -> usize {
+        self.0.outdegree_hint(node_id)
+    }
 }
 
 impl<G: RandomAccessGraph> LabeledRandomAccessGraph<()> for UnitLabelGraph<G> {}
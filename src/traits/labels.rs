@@ -74,6 +74,22 @@ pub trait SequentialLabeling {
         None
     }
 
+    /// Returns whether the labels of each node are guaranteed to be returned
+    /// in strictly increasing order of successor (i.e., sorted and without
+    /// duplicates), both by the [lender](SequentialLabeling::iter) and, if
+    /// available, by random access.
+    ///
+    /// Consumers needing sorted successors, such as [`BvComp`](crate::graphs::bvgraph::BvComp),
+    /// can use this method to decide whether to sort successors first, and
+    /// searches in successor lists can stop early when it returns true.
+    ///
+    /// The default implementation conservatively returns false; wrappers
+    /// that do not change the order of the successors of the underlying
+    /// labeling should delegate to it.
+    fn successors_sorted(&self) -> bool {
+        false
+    }
+
     /// Returns an iterator over the labeling.
     ///
     /// Iterators over the labeling return pairs given by a node of the graph
@@ -343,6 +359,11 @@ impl<I: Iterator> Iterator for SortedIter<I> {
     fn next(&mut self) -> Option<Self::Item> {
         self.iter.next()
     }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
 }
 
 impl<I: ExactSizeIterator> ExactSizeIterator for SortedIter<I> {
@@ -369,6 +390,35 @@ pub trait RandomAccessLabeling: SequentialLabeling {
 
     /// Returns the number of labels associated with a node.
     fn outdegree(&self, node_id: usize) -> usize;
+
+    /// Returns an upper bound on the number of labels associated with a node
+    /// that is cheap to compute.
+    ///
+    /// This method is meant to pre-size buffers, or to skip nodes with no
+    /// labels (a hint of zero implies an outdegree of zero). The default
+    /// implementation returns the [outdegree](RandomAccessLabeling::outdegree),
+    /// and it should be overridden only by implementations for which the
+    /// outdegree is expensive to compute (e.g., it requires scanning the
+    /// labels).
+    #[inline(always)]
+    fn outdegree_hint(&self, node_id: usize) -> usize {
+        self.outdegree(node_id)
+    }
+}
+
+/// Returns the length of an iterator if its [size
+/// hint](Iterator::size_hint) is exact.
+///
+/// Adapters forward the size hint of the underlying successor iterators, so
+/// this function provides a fast path to compute the number of successors
+/// returned by a [lender](SequentialLabeling::Lender), falling back to
+/// counting (e.g., with [`Iterator::count`]) only when necessary.
+#[inline(always)]
+pub fn successors_len_hint(iter: &impl Iterator) -> Option<usize> {
+    match iter.size_hint() {
+        (lower, Some(upper)) if lower == upper => Some(lower),
+        _ => None,
+    }
 }
 
 /// A struct used to make it easy to implement sequential access
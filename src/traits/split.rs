@@ -171,11 +171,13 @@ pub mod ra {
                 return None;
             }
             self.i += 1;
-            Some(
-                self.labeling
-                    .iter_from((self.i - 1) * self.nodes_per_iter)
-                    .take(self.nodes_per_iter),
-            )
+            // With more parts than nodes the last parts are empty, and must
+            // not start past the end of the labeling
+            let from = Ord::min(
+                (self.i - 1) * self.nodes_per_iter,
+                self.labeling.num_nodes(),
+            );
+            Some(self.labeling.iter_from(from).take(self.nodes_per_iter))
         }
     }
 
@@ -0,0 +1,305 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::graphs::vec_graph::VecGraph;
+use crate::prelude::*;
+use anyhow::{ensure, Result};
+use lender::*;
+
+/// The default limit on the square of the number of nodes of a graph whose
+/// complement is computed by [`complement`] (about ten thousand nodes).
+pub const DEFAULT_COMPLEMENT_MAX_SIZE: u64 = 100_000_000;
+
+/// Returns the complement of a graph as a [`VecGraph`].
+///
+/// The complement has the same nodes of the graph, and an arc *x* → *y* if
+/// and only if *x* ≠ *y* and there is no arc *x* → *y* in the graph; that is,
+/// self-loops are never part of the complement.
+///
+/// Since the complement of a sparse graph is dense, an error is returned if
+/// the square of the number of nodes exceeds `max_size` (see
+/// [`DEFAULT_COMPLEMENT_MAX_SIZE`]). To stream the complement of a larger
+/// graph, use [`ComplementGraph`].
+pub fn complement(graph: &impl SequentialGraph, max_size: u64) -> Result<VecGraph> {
+    let num_nodes = graph.num_nodes();
+    let size = (num_nodes as u128) * (num_nodes as u128);
+    ensure!(
+        size <= max_size as u128,
+        "The complement of a graph with {} nodes might have up to {} arcs, more than the limit of {}",
+        num_nodes,
+        size,
+        max_size
+    );
+    let mut complement = VecGraph::empty(num_nodes);
+    for_!((node, succ) in Iter::new(graph.iter(), num_nodes) {
+        for dst in succ {
+            complement.add_arc(node, dst);
+        }
+    });
+    Ok(complement)
+}
+
+#[derive(Debug, Clone)]
+/// A wrapper exhibiting lazily the complement of a graph.
+///
+/// The successors of a node in the complement are the nodes that are not
+/// successors in the graph, except for the node itself (see [`complement`]).
+/// The wrapped graph can have unsorted and duplicate successors, as the
+/// successors of each node are sorted in a buffer; the successors of the
+/// complement are always sorted.
+///
+/// Note that iterating over the complement requires time quadratic in the
+/// number of nodes.
+pub struct ComplementGraph<G>(pub G);
+
+impl<G: SequentialGraph> SequentialLabeling for ComplementGraph<G> {
+    type Label = usize;
+    type Lender<'b>
+        = Iter<G::Lender<'b>>
+    where
+        Self: 'b;
+
+    #[inline(always)]
+    fn num_nodes(&self) -> usize {
+        self.0.num_nodes()
+    }
+
+    #[inline(always)]
+    fn successors_sorted(&self) -> bool {
+        true
+    }
+
+    #[inline(always)]
+    fn num_arcs_hint(&self) -> Option<u64> {
+        // we do not know how many self-loops and duplicate arcs there are
+        None
+    }
+
+    #[inline(always)]
+    fn iter_from(&self, from: usize) -> Self::Lender<'_> {
+        Iter::new(self.0.iter_from(from), self.num_nodes())
+    }
+}
+
+impl<G: SequentialGraph + SplitLabeling> SplitLabeling for ComplementGraph<G>
+where
+    for<'a> <G as SequentialLabeling>::Lender<'a>: Clone + Send + Sync,
+{
+    type SplitLender<'a>
+        = split::seq::Lender<'a, ComplementGraph<G>>
+    where
+        Self: 'a;
+    type IntoIterator<'a>
+        = split::seq::IntoIterator<'a, ComplementGraph<G>>
+    where
+        Self: 'a;
+
+    fn split_iter(&self, how_many: usize) -> Self::IntoIterator<'_> {
+        split::seq::Iter::new(self.iter(), self.num_nodes(), how_many)
+    }
+}
+
+impl<G: SequentialGraph> SequentialGraph for ComplementGraph<G> {}
+
+impl<'b, G: SequentialGraph> IntoLender for &'b ComplementGraph<G> {
+    type Lender = <ComplementGraph<G> as SequentialLabeling>::Lender<'b>;
+
+    #[inline(always)]
+    fn into_lender(self) -> Self::Lender {
+        self.iter()
+    }
+}
+
+/// An iterator over the nodes of the complement of a graph.
+#[derive(Debug, Clone)]
+pub struct Iter<I> {
+    iter: I,
+    num_nodes: usize,
+    /// The sorted, deduplicated successors of the current node in the graph.
+    succ: Vec<usize>,
+}
+
+impl<I> Iter<I> {
+    fn new(iter: I, num_nodes: usize) -> Self {
+        Self {
+            iter,
+            num_nodes,
+            succ: Vec::new(),
+        }
+    }
+}
+
+impl<'succ, I: Lender + for<'next> NodeLabelsLender<'next, Label = usize>> NodeLabelsLender<'succ>
+    for Iter<I>
+{
+    type Label = usize;
+    type IntoIterator = Succ<'succ>;
+}
+
+impl<'succ, I: Lender + for<'next> NodeLabelsLender<'next, Label = usize>> Lending<'succ>
+    for Iter<I>
+{
+    type Lend = (usize, <Self as NodeLabelsLender<'succ>>::IntoIterator);
+}
+
+unsafe impl<I: SortedLender + Lender + for<'next> NodeLabelsLender<'next, Label = usize>>
+    SortedLender for Iter<I>
+{
+}
+
+impl<L: Lender + for<'next> NodeLabelsLender<'next, Label = usize>> Lender for Iter<L> {
+    #[inline(always)]
+    fn next(&mut self) -> Option<Lend<'_, Self>> {
+        let node = {
+            let (node, succ) = self.iter.next()?.into_pair();
+            self.succ.clear();
+            self.succ.extend(succ);
+            node
+        };
+        self.succ.sort_unstable();
+        self.succ.dedup();
+        Some((
+            node,
+            Succ {
+                src: node,
+                next: 0,
+                num_nodes: self.num_nodes,
+                succ: &self.succ,
+            },
+        ))
+    }
+}
+
+impl<L: ExactSizeLender + for<'next> NodeLabelsLender<'next, Label = usize>> ExactSizeLender
+    for Iter<L>
+{
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+/// An iterator over the successors of a node in the complement of a graph.
+#[derive(Debug, Clone)]
+pub struct Succ<'a> {
+    src: usize,
+    /// The next candidate successor.
+    next: usize,
+    num_nodes: usize,
+    /// The successors in the graph not smaller than `next`.
+    succ: &'a [usize],
+}
+
+impl Iterator for Succ<'_> {
+    type Item = usize;
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.next < self.num_nodes {
+            let dst = self.next;
+            self.next += 1;
+            if self.succ.first() == Some(&dst) {
+                self.succ = &self.succ[1..];
+            } else if dst != self.src {
+                return Some(dst);
+            }
+        }
+        None
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // we do not know whether the source is among the remaining nodes
+        let remaining = self.num_nodes - self.next - self.succ.len();
+        (remaining.saturating_sub(1), Some(remaining))
+    }
+}
+
+unsafe impl SortedIterator for Succ<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphs::random::ErdosRenyi;
+
+    fn arcs(graph: &impl SequentialGraph) -> Vec<(usize, usize)> {
+        let mut arcs = vec![];
+        for_!((src, succ) in graph.iter() {
+            arcs.extend(succ.into_iter().map(|dst| (src, dst)));
+        });
+        arcs
+    }
+
+    #[test]
+    fn test_complement() -> Result<()> {
+        // An 8-node graph with a self-loop and an isolated node
+        let mut graph = VecGraph::from_arc_list([
+            (0, 1),
+            (0, 2),
+            (1, 2),
+            (2, 0),
+            (2, 3),
+            (3, 3),
+            (3, 4),
+            (4, 5),
+            (5, 6),
+            (6, 0),
+        ]);
+        graph.add_node(7);
+        let graph = Left(graph);
+        assert_eq!(graph.num_nodes(), 8);
+
+        let comp = Left(complement(&graph, DEFAULT_COMPLEMENT_MAX_SIZE)?);
+        assert_eq!(comp.num_nodes(), 8);
+        // 8 * 7 possible arcs, minus 9 arcs that are not self-loops
+        assert_eq!(comp.num_arcs(), 8 * 7 - 9);
+        assert_eq!(
+            comp.successors(0).into_iter().collect::<Vec<_>>(),
+            vec![3, 4, 5, 6, 7]
+        );
+        assert_eq!(
+            comp.successors(7).into_iter().collect::<Vec<_>>(),
+            vec![0, 1, 2, 3, 4, 5, 6]
+        );
+        assert_eq!(arcs(&ComplementGraph(graph.clone())), arcs(&comp));
+
+        // Complementing twice yields the original graph without self-loops
+        let twice = Left(complement(&comp, DEFAULT_COMPLEMENT_MAX_SIZE)?);
+        assert_eq!(
+            arcs(&twice),
+            arcs(&graph)
+                .into_iter()
+                .filter(|(src, dst)| src != dst)
+                .collect::<Vec<_>>()
+        );
+
+        assert!(complement(&graph, 63).is_err());
+        complement(&graph, 64)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_complement_random() -> Result<()> {
+        let empty = Left(VecGraph::<()>::empty(0));
+        assert_eq!(
+            complement(&empty, DEFAULT_COMPLEMENT_MAX_SIZE)?.num_nodes(),
+            0
+        );
+        for (n, p) in [(1, 0.0), (10, 0.5), (100, 0.1), (1000, 0.005)] {
+            let graph = Left(VecGraph::from_lender(ErdosRenyi::new(n, p, 0).iter()));
+            let comp = Left(complement(&graph, DEFAULT_COMPLEMENT_MAX_SIZE)?);
+            assert_eq!(comp.num_nodes(), graph.num_nodes());
+            let twice = Left(complement(&comp, DEFAULT_COMPLEMENT_MAX_SIZE)?);
+            assert_eq!(
+                arcs(&twice),
+                arcs(&graph)
+                    .into_iter()
+                    .filter(|(src, dst)| src != dst)
+                    .collect::<Vec<_>>()
+            );
+            assert_eq!(arcs(&ComplementGraph(comp.clone())), arcs(&twice));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,123 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::graphs::arc_list_graph;
+use crate::labels::Left;
+use crate::traits::SequentialGraph;
+use crate::utils::sort_pairs::{BatchIterator, DuplicateArcs, KMergeIters, SortPairs};
+use anyhow::{ensure, Result};
+use dsi_progress_logger::prelude::*;
+use lender::*;
+use tempfile::Builder;
+
+/// Returns the number of supernodes of a mapping from nodes to supernodes,
+/// that is, its maximum value plus one (or zero, if the mapping is empty).
+pub fn num_supernodes(mapping: &[usize]) -> usize {
+    mapping.iter().max().map_or(0, |&max| max + 1)
+}
+
+/// Returns the number of nodes mapped to each supernode.
+pub fn supernode_sizes(mapping: &[usize]) -> Vec<usize> {
+    let mut sizes = vec![0; num_supernodes(mapping)];
+    for &supernode in mapping {
+        sizes[supernode] += 1;
+    }
+    sizes
+}
+
+/// Returns the contraction of a graph by a mapping from nodes to supernodes
+/// as a [sequential graph](crate::traits::SequentialGraph).
+///
+/// The contracted graph has [`num_supernodes(mapping)`](num_supernodes)
+/// nodes, and an arc *x* → *y* if and only if there is an arc *u* → *v* in
+/// the graph such that `mapping[u]` = *x* and `mapping[v]` = *y*. Duplicate
+/// arcs are removed, and loops (including the loops of the graph) are kept
+/// only if `keep_loops` is true. Supernodes with no nodes mapped to them are
+/// isolated.
+///
+/// An error is returned if the length of the mapping is not the number of
+/// nodes of the graph.
+///
+/// For the meaning of the additional parameter, see
+/// [`SortPairs`](crate::prelude::sort_pairs::SortPairs).
+#[allow(clippy::type_complexity)]
+pub fn contract(
+    graph: &impl SequentialGraph,
+    mapping: &[usize],
+    keep_loops: bool,
+    batch_size: usize,
+) -> Result<Left<arc_list_graph::ArcListGraph<KMergeIters<BatchIterator<()>, ()>>>> {
+    ensure!(
+        mapping.len() == graph.num_nodes(),
+        "The mapping has length {}, but the graph has {} nodes",
+        mapping.len(),
+        graph.num_nodes()
+    );
+    let num_supernodes = num_supernodes(mapping);
+    let dir = Builder::new().prefix("contract_").tempdir()?;
+    let mut sorted = SortPairs::new(batch_size, dir.path())?.duplicates(DuplicateArcs::Remove);
+
+    let mut pl = ProgressLogger::default();
+    pl.item_name("node")
+        .expected_updates(Some(graph.num_nodes()));
+    pl.start("Creating batches...");
+    for_!((src, succ) in graph.iter() {
+        let src = mapping[src];
+        for dst in succ {
+            let dst = mapping[dst];
+            if keep_loops || src != dst {
+                sorted.push(src, dst)?;
+            }
+        }
+        pl.light_update();
+    });
+    // merge the batches
+    let sorted = arc_list_graph::ArcListGraph::new_labeled(num_supernodes, sorted.iter()?);
+    pl.done();
+
+    Ok(Left(sorted))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphs::vec_graph::VecGraph;
+    use crate::traits::SequentialLabeling;
+
+    fn arcs(graph: &impl SequentialGraph) -> Vec<(usize, usize)> {
+        let mut arcs = vec![];
+        for_!((src, succ) in graph.iter() {
+            arcs.extend(succ.into_iter().map(|dst| (src, dst)));
+        });
+        arcs
+    }
+
+    #[test]
+    fn test_contract() -> Result<()> {
+        let graph = Left(VecGraph::from_arc_list([
+            (0, 1),
+            (0, 2),
+            (1, 0),
+            (1, 3),
+            (2, 3),
+            (3, 3),
+            (3, 4),
+        ]));
+        // {0, 1} → 0, {2, 3} → 1, 4 → 3, and supernode 2 is empty
+        let mapping = [0, 0, 1, 1, 3];
+        assert_eq!(supernode_sizes(&mapping), vec![2, 2, 0, 1]);
+
+        let contracted = contract(&graph, &mapping, false, 10)?;
+        assert_eq!(contracted.num_nodes(), 4);
+        assert_eq!(arcs(&contracted), vec![(0, 1), (1, 3)]);
+
+        let contracted = contract(&graph, &mapping, true, 10)?;
+        assert_eq!(arcs(&contracted), vec![(0, 0), (0, 1), (1, 1), (1, 3)]);
+
+        assert!(contract(&graph, &mapping[..4], false, 10).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,301 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::prelude::*;
+use lender::*;
+
+/// The default limit on the number of arcs of a line graph computed by the
+/// CLI.
+pub const DEFAULT_LINE_GRAPH_MAX_ARCS: u64 = 1_000_000_000;
+
+#[derive(Debug, Clone)]
+/// A wrapper exhibiting lazily the line graph of a graph.
+///
+/// The nodes of the line graph are the arcs of the graph: the identifier of
+/// an arc *u* → *v* is its rank in the global order of arcs, that is, the
+/// number of arcs of the nodes smaller than *u* plus the position of *v* in
+/// the successors of *u*. There is an arc from *u* → *v* to *v* → *w* for
+/// every such pair of consecutive arcs.
+///
+/// Since the successors of *u* → *v* are the arcs of *v*, which have
+/// consecutive identifiers, the line graph is generated directly in order,
+/// with sorted successors, and no sorting is needed to compress it.
+///
+/// The construction scans the graph once to compute the cumulative
+/// outdegrees and the exact [number of arcs](LineGraph::num_arcs) of the
+/// line graph, which is Σ<sub>*v*</sub> indeg(*v*) · outdeg(*v*) and can
+/// be much larger than the number of arcs of the graph. Memory usage is
+/// sixteen bytes per node.
+pub struct LineGraph<G> {
+    graph: G,
+    /// The number of arcs of the nodes smaller than each node.
+    cumul: Box<[usize]>,
+    num_arcs: u64,
+}
+
+impl<G: SequentialGraph> LineGraph<G> {
+    /// Creates a new line graph.
+    pub fn new(graph: G) -> Self {
+        let num_nodes = graph.num_nodes();
+        let mut outdegrees = vec![0; num_nodes];
+        let mut indegrees = vec![0_usize; num_nodes];
+        for_!((node, succ) in graph.iter() {
+            for dst in succ {
+                outdegrees[node] += 1;
+                indegrees[dst] += 1;
+            }
+        });
+        let num_arcs = indegrees
+            .iter()
+            .zip(&outdegrees)
+            .map(|(&indegree, &outdegree)| indegree as u64 * outdegree as u64)
+            .sum();
+        drop(indegrees);
+
+        let mut cumul = Vec::with_capacity(num_nodes + 1);
+        cumul.push(0);
+        let mut sum = 0;
+        for outdegree in outdegrees {
+            sum += outdegree;
+            cumul.push(sum);
+        }
+        Self {
+            graph,
+            cumul: cumul.into_boxed_slice(),
+            num_arcs,
+        }
+    }
+
+    /// Returns the exact number of arcs of the line graph.
+    pub fn num_arcs(&self) -> u64 {
+        self.num_arcs
+    }
+
+    /// Returns the identifier of the arc of given rank among the successors
+    /// of `src`.
+    pub fn arc_id(&self, src: usize, rank: usize) -> usize {
+        debug_assert!(rank < self.cumul[src + 1] - self.cumul[src]);
+        self.cumul[src] + rank
+    }
+}
+
+impl<G: SequentialGraph> SequentialLabeling for LineGraph<G> {
+    type Label = usize;
+    type Lender<'b>
+        = Iter<'b, G::Lender<'b>>
+    where
+        Self: 'b;
+
+    #[inline(always)]
+    fn num_nodes(&self) -> usize {
+        self.cumul[self.cumul.len() - 1]
+    }
+
+    #[inline(always)]
+    fn successors_sorted(&self) -> bool {
+        true
+    }
+
+    #[inline(always)]
+    fn num_arcs_hint(&self) -> Option<u64> {
+        Some(self.num_arcs)
+    }
+
+    fn iter_from(&self, from: usize) -> Self::Lender<'_> {
+        // the source of the arc with identifier from
+        let src = self.cumul.partition_point(|&c| c <= from).saturating_sub(1);
+        let mut iter = Iter {
+            iter: self.graph.iter_from(src),
+            cumul: &self.cumul,
+            succ: Vec::new(),
+            pos: 0,
+            next_id: from,
+        };
+        if from < self.num_nodes() {
+            iter.fill();
+            iter.pos = from - self.cumul[src];
+        }
+        iter
+    }
+}
+
+impl<G: SequentialGraph + SplitLabeling> SplitLabeling for LineGraph<G>
+where
+    for<'a> <G as SequentialLabeling>::Lender<'a>: Clone + Send + Sync,
+{
+    type SplitLender<'a>
+        = split::seq::Lender<'a, LineGraph<G>>
+    where
+        Self: 'a;
+    type IntoIterator<'a>
+        = split::seq::IntoIterator<'a, LineGraph<G>>
+    where
+        Self: 'a;
+
+    fn split_iter(&self, how_many: usize) -> Self::IntoIterator<'_> {
+        split::seq::Iter::new(self.iter(), self.num_nodes(), how_many)
+    }
+}
+
+impl<G: SequentialGraph> SequentialGraph for LineGraph<G> {}
+
+impl<'b, G: SequentialGraph> IntoLender for &'b LineGraph<G> {
+    type Lender = <LineGraph<G> as SequentialLabeling>::Lender<'b>;
+
+    #[inline(always)]
+    fn into_lender(self) -> Self::Lender {
+        self.iter()
+    }
+}
+
+/// An iterator over the nodes of a line graph.
+#[derive(Debug, Clone)]
+pub struct Iter<'a, I> {
+    iter: I,
+    cumul: &'a [usize],
+    /// The successors of the current node of the graph.
+    succ: Vec<usize>,
+    /// The position in `succ` of the next arc.
+    pos: usize,
+    next_id: usize,
+}
+
+impl<I: Lender + for<'next> NodeLabelsLender<'next, Label = usize>> Iter<'_, I> {
+    /// Loads the successors of the next node of the graph, returning false
+    /// if there are no more nodes.
+    fn fill(&mut self) -> bool {
+        let Some((_, succ)) = self.iter.next().map(|x| x.into_pair()) else {
+            return false;
+        };
+        self.succ.clear();
+        self.succ.extend(succ);
+        self.pos = 0;
+        true
+    }
+}
+
+impl<'succ, I: Lender + for<'next> NodeLabelsLender<'next, Label = usize>> NodeLabelsLender<'succ>
+    for Iter<'_, I>
+{
+    type Label = usize;
+    type IntoIterator = Succ;
+}
+
+impl<'succ, I: Lender + for<'next> NodeLabelsLender<'next, Label = usize>> Lending<'succ>
+    for Iter<'_, I>
+{
+    type Lend = (usize, <Self as NodeLabelsLender<'succ>>::IntoIterator);
+}
+
+unsafe impl<I: Lender + for<'next> NodeLabelsLender<'next, Label = usize>> SortedLender
+    for Iter<'_, I>
+{
+}
+
+impl<L: Lender + for<'next> NodeLabelsLender<'next, Label = usize>> Lender for Iter<'_, L> {
+    #[inline(always)]
+    fn next(&mut self) -> Option<Lend<'_, Self>> {
+        while self.pos == self.succ.len() {
+            if !self.fill() {
+                return None;
+            }
+        }
+        let dst = self.succ[self.pos];
+        self.pos += 1;
+        let node = self.next_id;
+        self.next_id += 1;
+        Some((node, Succ(self.cumul[dst]..self.cumul[dst + 1])))
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.cumul[self.cumul.len() - 1] - self.next_id;
+        (len, Some(len))
+    }
+}
+
+impl<L: Lender + for<'next> NodeLabelsLender<'next, Label = usize>> ExactSizeLender
+    for Iter<'_, L>
+{
+    fn len(&self) -> usize {
+        self.cumul[self.cumul.len() - 1] - self.next_id
+    }
+}
+
+/// An iterator over the successors of a node of a line graph.
+#[derive(Debug, Clone)]
+pub struct Succ(core::ops::Range<usize>);
+
+impl Iterator for Succ {
+    type Item = usize;
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl ExactSizeIterator for Succ {}
+
+unsafe impl SortedIterator for Succ {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphs::vec_graph::VecGraph;
+
+    #[test]
+    fn test_line_graph() {
+        // Arcs 0: 0 → 1, 1: 0 → 2, 2: 1 → 2, 3: 2 → 0, 4: 2 → 2
+        let graph = Left(VecGraph::from_arc_list([
+            (0, 1),
+            (0, 2),
+            (1, 2),
+            (2, 0),
+            (2, 2),
+        ]));
+        let line_graph = LineGraph::new(&graph);
+        assert_eq!(line_graph.num_nodes(), 5);
+        // indeg · outdeg: 1 · 2 + 1 · 1 + 3 · 2
+        assert_eq!(line_graph.num_arcs(), 9);
+        assert_eq!(line_graph.arc_id(2, 1), 4);
+
+        let line_graph = Left(VecGraph::from_lender(line_graph.iter()));
+        assert_eq!(line_graph.num_arcs(), 9);
+        assert_eq!(
+            line_graph.successors(0).into_iter().collect::<Vec<_>>(),
+            vec![2]
+        );
+        assert_eq!(
+            line_graph.successors(1).into_iter().collect::<Vec<_>>(),
+            vec![3, 4]
+        );
+        assert_eq!(
+            line_graph.successors(2).into_iter().collect::<Vec<_>>(),
+            vec![3, 4]
+        );
+        assert_eq!(
+            line_graph.successors(3).into_iter().collect::<Vec<_>>(),
+            vec![0, 1]
+        );
+        assert_eq!(
+            line_graph.successors(4).into_iter().collect::<Vec<_>>(),
+            vec![3, 4]
+        );
+
+        // Starting from an arc in the middle of a successor list
+        let line_graph = LineGraph::new(&graph);
+        let mut iter = line_graph.iter_from(4);
+        let (node, succ) = iter.next().unwrap();
+        assert_eq!(node, 4);
+        assert_eq!(succ.collect::<Vec<_>>(), vec![3, 4]);
+        assert!(iter.next().is_none());
+    }
+}
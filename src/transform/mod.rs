@@ -15,3 +15,15 @@ pub use transpose::*;
 
 mod perm;
 pub use perm::*;
+
+mod complement;
+pub use complement::{complement, ComplementGraph, DEFAULT_COMPLEMENT_MAX_SIZE};
+
+mod line_graph;
+pub use line_graph::{LineGraph, DEFAULT_LINE_GRAPH_MAX_ARCS};
+
+mod contract;
+pub use contract::*;
+
+mod top_k;
+pub use top_k::*;
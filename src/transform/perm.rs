@@ -5,9 +5,11 @@
  */
 
 use crate::graphs::arc_list_graph;
-use crate::prelude::sort_pairs::{BatchIterator, KMergeIters};
+use crate::prelude::sort_pairs::{BatchIterator, BitReader, BitWriter, KMergeIters};
 use crate::prelude::*;
+use crate::traits::graph::UnitLabelGraph;
 use anyhow::{ensure, Context, Result};
+use dsi_bitstream::traits::NE;
 use dsi_progress_logger::prelude::*;
 use lender::*;
 use rayon::ThreadPool;
@@ -28,26 +30,58 @@ pub fn permute(
     perm: &impl BitFieldSlice<usize>,
     batch_size: usize,
 ) -> Result<Left<arc_list_graph::ArcListGraph<KMergeIters<BatchIterator<()>, ()>>>> {
+    Ok(Left(permute_labeled(
+        &UnitLabelGraph(graph),
+        perm,
+        batch_size,
+        (),
+        (),
+    )?))
+}
+
+/// Returns a permuted labeled graph as a [labeled sequential
+/// graph](crate::traits::LabeledSequentialGraph).
+///
+/// Each arc `(src, dst)` with label `l` becomes the arc `(perm[src],
+/// perm[dst])` with the same label `l`, so labels are preserved by the
+/// permutation. Duplicate arcs are preserved.
+///
+/// This assumes that the permutation is bijective. For the meaning of the
+/// additional parameters, see
+/// [`SortPairs`](crate::prelude::sort_pairs::SortPairs).
+#[allow(clippy::type_complexity)]
+pub fn permute_labeled<
+    S: BitSerializer<NE, BitWriter> + Clone,
+    D: BitDeserializer<NE, BitReader> + Clone + 'static,
+>(
+    graph: &impl LabeledSequentialGraph<S::SerType>,
+    perm: &impl BitFieldSlice<usize>,
+    batch_size: usize,
+    serializer: S,
+    deserializer: D,
+) -> Result<arc_list_graph::ArcListGraph<KMergeIters<BatchIterator<D>, D::DeserType>>>
+where
+    S::SerType: Send + Sync + Copy,
+    D::DeserType: Clone + Copy,
+{
     ensure!(perm.len() == graph.num_nodes(),
         "The given permutation has {} values and thus it's incompatible with a graph with {} nodes.", 
         perm.len(), graph.num_nodes(),
     );
     let dir = Builder::new().prefix("permute_").tempdir()?;
 
-    // create a stream where to dump the sorted pairs
-    let mut sorted = SortPairs::new(batch_size, dir)?;
-
-    // get a premuted view
-    let pgraph = PermutedGraph { graph, perm };
+    // create a stream where to dump the sorted triples
+    let mut sorted = SortPairs::new_labeled(batch_size, dir.path(), serializer, deserializer)?;
 
     let mut pl = ProgressLogger::default();
     pl.item_name("node")
         .expected_updates(Some(graph.num_nodes()));
     pl.start("Creating batches...");
-    // create batches of sorted edges
-    for_!( (src, succ) in pgraph.iter() {
-        for dst in succ {
-            sorted.push(dst, src)?;
+    // create batches of sorted labeled arcs
+    for_!( (src, succ) in graph.iter() {
+        let src = perm.get(src);
+        for (dst, l) in succ {
+            sorted.push_labeled(src, perm.get(dst), l)?;
         }
         pl.light_update();
     });
@@ -57,7 +91,7 @@ pub fn permute(
     let sorted = arc_list_graph::ArcListGraph::new_labeled(graph.num_nodes(), edges);
     pl.done();
 
-    Ok(Left(sorted))
+    Ok(sorted)
 }
 
 /// Returns a [sequential](crate::traits::SequentialGraph) permuted graph
@@ -129,3 +163,73 @@ where
         edges,
     )))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphs::vec_graph::VecGraph;
+    use dsi_bitstream::codes::{GammaRead, GammaWrite};
+    use dsi_bitstream::traits::{BitRead, BitWrite};
+
+    #[derive(Clone, Copy)]
+    struct GammaSerializer;
+
+    impl BitSerializer<NE, BitWriter> for GammaSerializer {
+        type SerType = u64;
+
+        fn serialize(
+            &self,
+            value: &Self::SerType,
+            bitstream: &mut BitWriter,
+        ) -> Result<usize, <BitWriter as BitWrite<NE>>::Error> {
+            bitstream.write_gamma(*value)
+        }
+    }
+
+    #[derive(Clone, Copy)]
+    struct GammaDeserializer;
+
+    impl BitDeserializer<NE, BitReader> for GammaDeserializer {
+        type DeserType = u64;
+
+        fn deserialize(
+            &self,
+            bitstream: &mut BitReader,
+        ) -> Result<Self::DeserType, <BitReader as BitRead<NE>>::Error> {
+            bitstream.read_gamma()
+        }
+    }
+
+    #[test]
+    fn test_permute_labeled() -> Result<()> {
+        let g = VecGraph::from_labeled_arc_list([
+            (0, 1, 10),
+            (0, 2, 20),
+            (1, 2, 30),
+            (2, 0, 40),
+            (2, 3, 50),
+            (3, 3, 60),
+        ]);
+        let perm = vec![2, 0, 3, 1];
+        let p = permute_labeled(&g, &perm, 2, GammaSerializer, GammaDeserializer)?;
+        assert_eq!(
+            VecGraph::from_labeled_lender(p.iter()),
+            VecGraph::from_labeled_arc_list([
+                (2, 0, 10),
+                (2, 3, 20),
+                (0, 3, 30),
+                (3, 2, 40),
+                (3, 1, 50),
+                (1, 1, 60),
+            ])
+        );
+
+        // The unlabeled permutation agrees with the labeled one
+        let p = permute(&Left(g), &perm, 2)?;
+        assert_eq!(
+            VecGraph::from_lender(&p),
+            VecGraph::from_arc_list([(2, 0), (2, 3), (0, 3), (3, 2), (3, 1), (1, 1)])
+        );
+        Ok(())
+    }
+}
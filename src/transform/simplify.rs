@@ -8,9 +8,13 @@ use crate::graphs::{
     arc_list_graph, no_selfloops_graph::NoSelfLoopsGraph, union_graph::UnionGraph,
 };
 use crate::labels::Left;
-use crate::traits::{LenderIntoIter, SequentialGraph, SortedIterator, SortedLender, SplitLabeling};
-use crate::utils::sort_pairs::{BatchIterator, KMergeIters, SortPairs};
-use anyhow::{Context, Result};
+use crate::traits::{
+    BitDeserializer, BitSerializer, LabeledSequentialGraph, LenderIntoIter, SequentialGraph,
+    SortedIterator, SortedLender, SplitLabeling,
+};
+use crate::utils::sort_pairs::{BatchIterator, BitReader, BitWriter, KMergeIters, SortPairs};
+use anyhow::{bail, Context, Result};
+use dsi_bitstream::traits::{BitRead, BitWrite, NE};
 use dsi_progress_logger::prelude::*;
 use itertools::{Dedup, Itertools};
 use lender::*;
@@ -23,6 +27,9 @@ use super::transpose;
 /// sorted (both on nodes and successors) graph as a [sequential
 /// graph](crate::traits::SequentialGraph).
 ///
+/// Pairs of opposite arcs are merged, but duplicate arcs of the graph are not
+/// removed.
+///
 /// This method exploits the fact that the input graph is already sorted,
 /// sorting half the number of arcs of
 /// [`simplify`](crate::transform::simplify::simplify).
@@ -46,6 +53,8 @@ where
 /// Returns a simplified (i.e., undirected and loopless) version of the provided
 /// graph as a [sequential graph](crate::traits::SequentialGraph).
 ///
+/// Duplicate arcs are removed.
+///
 /// Note that if the graph is sorted (both on nodes and successors), it is
 /// recommended to use [`simplify_sorted`](crate::transform::simplify::simplify_sorted).
 ///
@@ -105,6 +114,7 @@ pub fn simplify(
 /// graph as a [sequential graph](crate::traits::SequentialGraph).
 ///
 /// This method uses splitting to sort in parallel different parts of the graph.
+/// Duplicate arcs are removed.
 ///
 /// For the meaning of the additional parameter, see
 /// [`SortPairs`](crate::prelude::sort_pairs::SortPairs).
@@ -163,3 +173,243 @@ where
     drop(dirs);
     Ok(Left(sorted))
 }
+
+/// How [`simplify_labeled`] merges the labels of two opposite arcs.
+///
+/// The _forward_ label of a pair of opposite arcs is the label of the arc going
+/// from the smaller node to the larger node, and the _backward_ label is the
+/// label of the other arc.
+#[derive(Default)]
+pub enum LabelMergePolicy<L> {
+    /// Returns an error if both arcs exist (the default).
+    #[default]
+    Error,
+    /// Combines the forward and backward labels (in this order) using the given function.
+    CombineWith(Box<dyn Fn(L, L) -> L>),
+    /// Keeps the forward label.
+    KeepForward,
+    /// Keeps the backward label.
+    KeepBackward,
+    /// Keeps the larger label.
+    KeepMax,
+    /// Keeps the smaller label.
+    KeepMin,
+}
+
+/// A serializer prefixing the serialization of a label with a bit
+/// representing its direction.
+#[derive(Clone)]
+struct DirectedSerializer<S>(S);
+
+impl<S: BitSerializer<NE, BitWriter>> BitSerializer<NE, BitWriter> for DirectedSerializer<S> {
+    type SerType = (bool, S::SerType);
+
+    fn serialize(
+        &self,
+        value: &Self::SerType,
+        bitstream: &mut BitWriter,
+    ) -> Result<usize, <BitWriter as BitWrite<NE>>::Error> {
+        Ok(bitstream.write_bits(value.0 as u64, 1)? + self.0.serialize(&value.1, bitstream)?)
+    }
+}
+
+/// The deserializer matching [`DirectedSerializer`].
+#[derive(Clone)]
+struct DirectedDeserializer<D>(D);
+
+impl<D: BitDeserializer<NE, BitReader>> BitDeserializer<NE, BitReader> for DirectedDeserializer<D> {
+    type DeserType = (bool, D::DeserType);
+
+    fn deserialize(
+        &self,
+        bitstream: &mut BitReader,
+    ) -> Result<Self::DeserType, <BitReader as BitRead<NE>>::Error> {
+        let forward = bitstream.read_bits(1)? != 0;
+        Ok((forward, self.0.deserialize(bitstream)?))
+    }
+}
+
+/// Returns a simplified (i.e., undirected and loopless) version of the provided
+/// labeled graph as a [sequential graph](crate::traits::SequentialGraph).
+///
+/// Each arc of the result carries the label of the corresponding arc of the
+/// graph; the two opposite arcs of the result carry the same label. If the graph
+/// contains both an arc and its opposite, their labels are merged as specified
+/// by `policy`; the default policy, [`LabelMergePolicy::Error`], makes this
+/// function return an error rather than silently dropping a label. An error is
+/// returned also if the graph contains duplicate arcs.
+///
+/// Arcs are sorted twice, as labels must be merged before the result is
+/// built. For the meaning of the additional parameters, see
+/// [`SortPairs`](crate::prelude::sort_pairs::SortPairs).
+#[allow(clippy::type_complexity)]
+pub fn simplify_labeled<S, D>(
+    graph: &impl LabeledSequentialGraph<S::SerType>,
+    batch_size: usize,
+    serializer: S,
+    deserializer: D,
+    policy: LabelMergePolicy<S::SerType>,
+) -> Result<arc_list_graph::ArcListGraph<KMergeIters<BatchIterator<D>, D::DeserType>>>
+where
+    S: BitSerializer<NE, BitWriter> + Clone,
+    D: BitDeserializer<NE, BitReader, DeserType = S::SerType> + Clone + 'static,
+    S::SerType: Send + Sync + Copy + PartialOrd + 'static,
+{
+    let dir = Builder::new().prefix("simplify_labeled_").tempdir()?;
+    let mut directed = SortPairs::new_labeled(
+        batch_size,
+        dir.path(),
+        DirectedSerializer(serializer.clone()),
+        DirectedDeserializer(deserializer.clone()),
+    )?;
+
+    let mut pl = ProgressLogger::default();
+    pl.item_name("node")
+        .expected_updates(Some(graph.num_nodes()))
+        .display_memory(true);
+    pl.start("Creating batches...");
+    for_!( (src, succ) in graph.iter() {
+        for (dst, l) in succ {
+            if src != dst {
+                let forward = src < dst;
+                directed.push_labeled(src, dst, (forward, l))?;
+                directed.push_labeled(dst, src, (forward, l))?;
+            }
+        }
+        pl.light_update();
+    });
+    pl.done();
+
+    let merged_dir = Builder::new()
+        .prefix("simplify_labeled_merged_")
+        .tempdir()?;
+    let mut merged =
+        SortPairs::new_labeled(batch_size, merged_dir.path(), serializer, deserializer)?;
+
+    pl.item_name("arc").expected_updates(None);
+    pl.start("Merging labels...");
+    let mut iter = directed.iter()?.peekable();
+    while let Some((src, dst, (forward, label))) = iter.next() {
+        let mut labels = [None, None];
+        labels[forward as usize] = Some(label);
+        while let Some((_, _, (forward, label))) = iter.next_if(|&(s, d, _)| s == src && d == dst) {
+            if labels[forward as usize].replace(label).is_some() {
+                bail!(
+                    "The graph contains duplicate arcs between {} and {}",
+                    src.min(dst),
+                    src.max(dst)
+                );
+            }
+        }
+        let label = match labels {
+            [Some(backward), Some(forward)] => match &policy {
+                LabelMergePolicy::Error => bail!(
+                    "The graph contains both an arc from {} to {} and its opposite; please choose a label merge policy",
+                    src,
+                    dst
+                ),
+                LabelMergePolicy::CombineWith(combine) => combine(forward, backward),
+                LabelMergePolicy::KeepForward => forward,
+                LabelMergePolicy::KeepBackward => backward,
+                LabelMergePolicy::KeepMax => {
+                    if backward > forward {
+                        backward
+                    } else {
+                        forward
+                    }
+                }
+                LabelMergePolicy::KeepMin => {
+                    if backward < forward {
+                        backward
+                    } else {
+                        forward
+                    }
+                }
+            },
+            [Some(label), None] | [None, Some(label)] => label,
+            [None, None] => unreachable!(),
+        };
+        merged.push_labeled(src, dst, label)?;
+        pl.light_update();
+    }
+    pl.done();
+
+    Ok(arc_list_graph::ArcListGraph::new_labeled(
+        graph.num_nodes(),
+        merged.iter()?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphs::vec_graph::VecGraph;
+    use crate::traits::SequentialLabeling;
+    use dsi_bitstream::codes::{GammaRead, GammaWrite};
+
+    #[derive(Clone, Copy)]
+    struct GammaSerializer;
+
+    impl BitSerializer<NE, BitWriter> for GammaSerializer {
+        type SerType = u64;
+
+        fn serialize(
+            &self,
+            value: &Self::SerType,
+            bitstream: &mut BitWriter,
+        ) -> Result<usize, <BitWriter as BitWrite<NE>>::Error> {
+            bitstream.write_gamma(*value)
+        }
+    }
+
+    #[derive(Clone, Copy)]
+    struct GammaDeserializer;
+
+    impl BitDeserializer<NE, BitReader> for GammaDeserializer {
+        type DeserType = u64;
+
+        fn deserialize(
+            &self,
+            bitstream: &mut BitReader,
+        ) -> Result<Self::DeserType, <BitReader as BitRead<NE>>::Error> {
+            bitstream.read_gamma()
+        }
+    }
+
+    #[test]
+    fn test_simplify_labeled() -> Result<()> {
+        let g = VecGraph::from_labeled_arc_list([(0, 1, 5), (1, 0, 7), (1, 2, 3), (2, 2, 1)]);
+        let simplify = |policy| -> Result<VecGraph<u64>> {
+            let s = simplify_labeled(&g, 2, GammaSerializer, GammaDeserializer, policy)?;
+            Ok(VecGraph::from_labeled_lender(s.iter()))
+        };
+        let expected = |label| {
+            VecGraph::from_labeled_arc_list([(0, 1, label), (1, 0, label), (1, 2, 3), (2, 1, 3)])
+        };
+
+        assert!(simplify(LabelMergePolicy::default()).is_err());
+        assert_eq!(simplify(LabelMergePolicy::KeepForward)?, expected(5));
+        assert_eq!(simplify(LabelMergePolicy::KeepBackward)?, expected(7));
+        assert_eq!(simplify(LabelMergePolicy::KeepMax)?, expected(7));
+        assert_eq!(simplify(LabelMergePolicy::KeepMin)?, expected(5));
+        assert_eq!(
+            simplify(LabelMergePolicy::CombineWith(Box::new(|f, b| 10 * f + b)))?,
+            expected(57)
+        );
+
+        // Without opposite arcs the default policy succeeds
+        let g = VecGraph::from_labeled_arc_list([(0, 1, 5), (1, 2, 3)]);
+        let s = simplify_labeled(
+            &g,
+            2,
+            GammaSerializer,
+            GammaDeserializer,
+            LabelMergePolicy::default(),
+        )?;
+        assert_eq!(
+            VecGraph::from_labeled_lender(s.iter()),
+            VecGraph::from_labeled_arc_list([(0, 1, 5), (1, 0, 5), (1, 2, 3), (2, 1, 3)])
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,178 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::graphs::arc_list_graph;
+use crate::prelude::sort_pairs::{BatchIterator, BitReader, BitWriter, ByLabelDesc, KMergeIters};
+use crate::prelude::*;
+use anyhow::{Context, Result};
+use dsi_bitstream::traits::NE;
+use dsi_progress_logger::prelude::*;
+use lender::*;
+use std::iter::Peekable;
+use tempfile::Builder;
+
+/// An iterator adapter that keeps the first `k` arcs of each source of an
+/// iterator on labeled arcs sorted by source, returning them sorted by
+/// target.
+///
+/// This structure is returned by [`truncate_top_k_by_label`].
+#[derive(Clone, Debug)]
+pub struct TopKArcs<L, I: Iterator<Item = (usize, usize, L)>> {
+    iter: Peekable<I>,
+    k: usize,
+    /// The kept arcs of the current source, by decreasing target.
+    kept: Vec<(usize, usize, L)>,
+}
+
+impl<L, I: Iterator<Item = (usize, usize, L)>> TopKArcs<L, I> {
+    fn new(iter: I, k: usize) -> Self {
+        TopKArcs {
+            iter: iter.peekable(),
+            k,
+            kept: Vec::with_capacity(k),
+        }
+    }
+}
+
+impl<L, I: Iterator<Item = (usize, usize, L)>> Iterator for TopKArcs<L, I> {
+    type Item = (usize, usize, L);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.kept.is_empty() {
+            let src = self.iter.peek()?.0;
+            while let Some(arc) = self.iter.next_if(|&(s, _, _)| s == src) {
+                if self.kept.len() < self.k {
+                    self.kept.push(arc);
+                }
+            }
+            self.kept.sort_unstable_by(|(_, a, _), (_, b, _)| b.cmp(a));
+        }
+        self.kept.pop()
+    }
+}
+
+/// Returns a labeled graph containing, for each node, only the `k` arcs with
+/// the largest labels, as a [labeled sequential
+/// graph](crate::traits::LabeledSequentialGraph).
+///
+/// Ties between labels are broken by increasing target. Arcs are sorted by
+/// source and decreasing label using [`SortPairs`] with the [`ByLabelDesc`]
+/// order, and truncated while merging the batches; the resulting arcs of each
+/// node are then sorted by target.
+///
+/// For the meaning of the additional parameters, see
+/// [`SortPairs`](crate::prelude::sort_pairs::SortPairs).
+#[allow(clippy::type_complexity)]
+pub fn truncate_top_k_by_label<
+    S: BitSerializer<NE, BitWriter> + Clone,
+    D: BitDeserializer<NE, BitReader> + Clone + 'static,
+>(
+    graph: &impl LabeledSequentialGraph<S::SerType>,
+    k: usize,
+    batch_size: usize,
+    serializer: S,
+    deserializer: D,
+) -> Result<
+    arc_list_graph::ArcListGraph<
+        TopKArcs<
+            D::DeserType,
+            KMergeIters<BatchIterator<D, ByLabelDesc>, D::DeserType, ByLabelDesc>,
+        >,
+    >,
+>
+where
+    S::SerType: Ord + Send + Sync + Copy,
+    D::DeserType: Ord + Clone + Copy,
+{
+    let dir = Builder::new().prefix("top_k_").tempdir()?;
+
+    let mut sorted = SortPairs::new_labeled(batch_size, dir.path(), serializer, deserializer)?
+        .order(ByLabelDesc);
+
+    let mut pl = ProgressLogger::default();
+    pl.item_name("node")
+        .expected_updates(Some(graph.num_nodes()));
+    pl.start("Creating batches...");
+    for_!( (src, succ) in graph.iter() {
+        for (dst, l) in succ {
+            sorted.push_labeled(src, dst, l)?;
+        }
+        pl.light_update();
+    });
+
+    let arcs = sorted.iter().context("Could not read arcs")?;
+    let truncated =
+        arc_list_graph::ArcListGraph::new_labeled(graph.num_nodes(), TopKArcs::new(arcs, k));
+    pl.done();
+
+    Ok(truncated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphs::vec_graph::VecGraph;
+    use crate::prelude::sort_pairs::{BitReader, BitWriter};
+    use crate::utils::sort_pairs::ArcOrder;
+    use dsi_bitstream::prelude::*;
+
+    #[derive(Clone, Copy, Debug)]
+    struct Delta;
+
+    impl BitSerializer<NE, BitWriter> for Delta {
+        type SerType = usize;
+        fn serialize(
+            &self,
+            value: &usize,
+            bitstream: &mut BitWriter,
+        ) -> Result<usize, <BitWriter as BitWrite<NE>>::Error> {
+            bitstream.write_delta(*value as u64)
+        }
+    }
+
+    impl BitDeserializer<NE, BitReader> for Delta {
+        type DeserType = usize;
+        fn deserialize(
+            &self,
+            bitstream: &mut BitReader,
+        ) -> Result<usize, <BitReader as BitRead<NE>>::Error> {
+            bitstream.read_delta().map(|x| x as usize)
+        }
+    }
+
+    #[test]
+    fn test_truncate_top_k_by_label() -> Result<()> {
+        let mut arcs = vec![];
+        for src in 0..20 {
+            for i in 0..src {
+                arcs.push((src, (src * 7 + i * 3) % 20, (src * i * 31) % 11));
+            }
+        }
+        let mut graph = VecGraph::empty(20);
+        for &(src, dst, label) in &arcs {
+            graph.add_labeled_arc(src, dst, label);
+        }
+
+        for k in [0, 1, 3, 100] {
+            // Small batches, so that the arcs of a node span several of them
+            let truncated = truncate_top_k_by_label(&graph, k, 7, Delta, Delta)?;
+            let mut expected = vec![];
+            for src in 0..20 {
+                let mut succ = arcs
+                    .iter()
+                    .filter(|&&(s, _, _)| s == src)
+                    .copied()
+                    .collect::<Vec<_>>();
+                succ.sort_by(|a, b| ByLabelDesc.cmp((a.0, a.1, &a.2), (b.0, b.1, &b.2)));
+                succ.truncate(k);
+                succ.sort_by_key(|&(_, dst, _)| dst);
+                expected.push((src, succ.into_iter().map(|(_, d, l)| (d, l)).collect()));
+            }
+            assert_eq!(truncated.collect_labeled(), expected);
+        }
+        Ok(())
+    }
+}
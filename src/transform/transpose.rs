@@ -18,6 +18,8 @@ use tempfile::Builder;
 /// Returns the transpose of the provided labeled graph as a [sequential
 /// graph](crate::traits::SequentialGraph).
 ///
+/// Duplicate arcs are preserved.
+///
 /// For the meaning of the additional parameters, see
 /// [`SortPairs`](crate::prelude::sort_pairs::SortPairs).
 #[allow(clippy::type_complexity)]
@@ -37,11 +39,10 @@ where
     let dir = Builder::new().prefix("transpose_").tempdir()?;
     let mut sorted = SortPairs::new_labeled(batch_size, dir.path(), serializer, deserializer)?;
 
-    let mut pl = progress_logger!(
-        item_name = "node",
-        expected_updates = Some(graph.num_nodes()),
-        display_memory = true
-    );
+    let mut pl = ProgressLogger::default();
+    pl.item_name("node")
+        .expected_updates(Some(graph.num_nodes()))
+        .display_memory(true);
     pl.start("Creating batches...");
     // create batches of sorted edges
     for_!( (src, succ) in graph.iter() {
@@ -60,6 +61,8 @@ where
 /// Returns the transpose of the provided graph as a [sequential
 /// graph](crate::traits::SequentialGraph).
 ///
+/// Duplicate arcs are preserved.
+///
 /// For the meaning of the additional parameter, see
 /// [`SortPairs`](crate::prelude::sort_pairs::SortPairs).
 #[allow(clippy::type_complexity)]
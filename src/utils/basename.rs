@@ -0,0 +1,120 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use std::ffi::OsStr;
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+
+/// The basename of a graph, that is, the common prefix of the paths of the
+/// files representing the graph.
+///
+/// The path of each file is obtained by appending a dot and an extension to
+/// the basename using [`with_ext`](Basename::with_ext). The basename is used
+/// verbatim as a prefix: differently from [`Path::with_extension`], dots in
+/// the basename (as in `graph-2024.03.01`) are never interpreted as the
+/// start of an extension, and nothing is ever stripped. The only
+/// normalization is the removal of trailing (and repeated) separators, so
+/// that `dir/graph/` refers to the same files as `dir/graph`.
+///
+/// ```
+/// # use webgraph::utils::Basename;
+/// # use std::path::PathBuf;
+/// let basename = Basename::new("data/graph-2024.03.01/");
+/// assert_eq!(
+///     basename.with_ext("graph"),
+///     PathBuf::from("data/graph-2024.03.01.graph")
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Basename(PathBuf);
+
+impl Basename {
+    /// Creates a new basename from a path.
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self(path.as_ref().components().collect())
+    }
+
+    /// Returns the path of the file with the given extension, that is, the
+    /// basename followed by a dot and by `ext`.
+    pub fn with_ext(&self, ext: impl AsRef<OsStr>) -> PathBuf {
+        let mut path = self.0.as_os_str().to_owned();
+        path.push(".");
+        path.push(ext);
+        path.into()
+    }
+
+    /// Returns the basename as a path.
+    pub fn as_path(&self) -> &Path {
+        &self.0
+    }
+
+    /// Returns an object implementing [`Display`] for the basename.
+    pub fn display(&self) -> std::path::Display<'_> {
+        self.0.display()
+    }
+
+    /// Returns the basename as a path buffer.
+    pub fn into_path_buf(self) -> PathBuf {
+        self.0
+    }
+}
+
+impl AsRef<Path> for Basename {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl From<PathBuf> for Basename {
+    fn from(path: PathBuf) -> Self {
+        Self::new(path)
+    }
+}
+
+impl From<&Path> for Basename {
+    fn from(path: &Path) -> Self {
+        Self::new(path)
+    }
+}
+
+impl From<&str> for Basename {
+    fn from(path: &str) -> Self {
+        Self::new(path)
+    }
+}
+
+impl Display for Basename {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0.display())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_with_ext() {
+        for (path, expected) in [
+            ("graph", "graph.graph"),
+            ("graph-2024.03.01", "graph-2024.03.01.graph"),
+            ("graph.graph", "graph.graph.graph"),
+            ("dir.d/graph", "dir.d/graph.graph"),
+            ("dir.d/graph/", "dir.d/graph.graph"),
+            ("dir.d//graph", "dir.d/graph.graph"),
+            ("./v1.2/graph", "./v1.2/graph.graph"),
+            ("../graph.v2", "../graph.v2.graph"),
+            ("/tmp/a.b/c.d", "/tmp/a.b/c.d.graph"),
+        ] {
+            assert_eq!(
+                Basename::new(path).with_ext("graph"),
+                PathBuf::from(expected),
+                "{}",
+                path
+            );
+        }
+    }
+}
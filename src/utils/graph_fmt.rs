@@ -0,0 +1,309 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+//! Pretty-printing and comparison of small graphs.
+
+use crate::traits::SequentialGraph;
+use core::fmt::{self, Display, Formatter, Write};
+use lender::*;
+
+/// Writes at most `max` successors separated by spaces, followed by an
+/// ellipsis if there are more, and returns the number of successors.
+///
+/// If `lead` is true, a space is written also before the first item.
+fn write_successors(
+    f: &mut impl Write,
+    successors: impl IntoIterator<Item = usize>,
+    max: usize,
+    lead: bool,
+) -> Result<usize, fmt::Error> {
+    let mut count = 0;
+    for succ in successors {
+        if count <= max && (lead || count != 0) {
+            f.write_char(' ')?;
+        }
+        if count < max {
+            write!(f, "{}", succ)?;
+        } else if count == max {
+            f.write_str("...")?;
+        }
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// A [`Display`] adapter for a list of successors.
+///
+/// At most [`max_successors`](SuccFmt::max_successors) successors (by
+/// default, 10) are displayed, followed by an ellipsis if there are more.
+/// The list is cloned at each formatting.
+///
+/// ```
+/// # use webgraph::utils::SuccFmt;
+/// assert_eq!(SuccFmt::new(0..5).to_string(), "0 1 2 3 4");
+/// assert_eq!(SuccFmt::new(0..5).max_successors(3).to_string(), "0 1 2 ...");
+/// ```
+#[derive(Debug, Clone)]
+pub struct SuccFmt<I> {
+    successors: I,
+    max_successors: usize,
+}
+
+impl<I: IntoIterator<Item = usize> + Clone> SuccFmt<I> {
+    pub fn new(successors: I) -> Self {
+        Self {
+            successors,
+            max_successors: 10,
+        }
+    }
+
+    /// Sets the maximum number of successors to display.
+    pub fn max_successors(mut self, max_successors: usize) -> Self {
+        self.max_successors = max_successors;
+        self
+    }
+}
+
+impl<I: IntoIterator<Item = usize> + Clone> Display for SuccFmt<I> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write_successors(f, self.successors.clone(), self.max_successors, false).map(|_| ())
+    }
+}
+
+/// A [`Display`] adapter for a [sequential graph](SequentialGraph).
+///
+/// Each line contains a node, a colon and its successors. At most
+/// [`max_nodes`](GraphFmt::max_nodes) nodes (by default, 10) and
+/// [`max_successors`](GraphFmt::max_successors) successors per node (by
+/// default, 10) are displayed, followed by an ellipsis if there are more.
+/// A final line reports the number of nodes and arcs; the number of arcs
+/// is unknown if the graph provides no [hint](crate::traits::SequentialLabeling::num_arcs_hint)
+/// and not all nodes have been displayed.
+///
+/// ```
+/// # use webgraph::prelude::*;
+/// # use webgraph::graphs::vec_graph::VecGraph;
+/// let graph = Left(VecGraph::from_arc_list([(0, 1), (0, 2), (0, 3), (1, 0), (3, 3)]));
+/// assert_eq!(
+///     GraphFmt::new(&graph).max_nodes(2).max_successors(2).to_string(),
+///     "0: 1 2 ...\n1: 0\n...\n4 nodes, 5 arcs"
+/// );
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct GraphFmt<'a, G> {
+    graph: &'a G,
+    max_nodes: usize,
+    max_successors: usize,
+}
+
+impl<'a, G: SequentialGraph> GraphFmt<'a, G> {
+    pub fn new(graph: &'a G) -> Self {
+        Self {
+            graph,
+            max_nodes: 10,
+            max_successors: 10,
+        }
+    }
+
+    /// Sets the maximum number of nodes to display.
+    pub fn max_nodes(mut self, max_nodes: usize) -> Self {
+        self.max_nodes = max_nodes;
+        self
+    }
+
+    /// Sets the maximum number of successors to display for each node.
+    pub fn max_successors(mut self, max_successors: usize) -> Self {
+        self.max_successors = max_successors;
+        self
+    }
+}
+
+impl<G: SequentialGraph> Display for GraphFmt<'_, G> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let num_nodes = self.graph.num_nodes();
+        let mut num_arcs = 0;
+        let mut iter = self.graph.iter().take(self.max_nodes);
+        while let Some((node, successors)) = iter.next() {
+            write!(f, "{}:", node)?;
+            num_arcs += write_successors(f, successors, self.max_successors, true)? as u64;
+            f.write_char('\n')?;
+        }
+        if num_nodes > self.max_nodes {
+            f.write_str("...\n")?;
+        }
+        match self.graph.num_arcs_hint() {
+            Some(num_arcs) => write!(f, "{} nodes, {} arcs", num_nodes, num_arcs),
+            None if num_nodes <= self.max_nodes => {
+                write!(f, "{} nodes, {} arcs", num_nodes, num_arcs)
+            }
+            None => write!(f, "{} nodes, unknown number of arcs", num_nodes),
+        }
+    }
+}
+
+/// Compares two [sequential graphs](SequentialGraph) and returns a report of
+/// their differences, or `None` if they have the same nodes and successor
+/// lists.
+///
+/// Successor lists are compared in enumeration order. At most `max_diffs`
+/// differing nodes are described in detail; the remaining ones are just
+/// counted. This is the function used by [`assert_graph_eq!`](crate::assert_graph_eq).
+pub fn graph_diff<G0: SequentialGraph, G1: SequentialGraph>(
+    g0: &G0,
+    g1: &G1,
+    max_diffs: usize,
+) -> Option<String> {
+    let mut report = String::new();
+    let mut diffs = 0;
+    if g0.num_nodes() != g1.num_nodes() {
+        diffs += 1;
+        _ = writeln!(
+            report,
+            "number of nodes: left {}, right {}",
+            g0.num_nodes(),
+            g1.num_nodes()
+        );
+    }
+
+    let mut iter0 = g0.iter();
+    let mut iter1 = g1.iter();
+    loop {
+        let diff = match (iter0.next(), iter1.next()) {
+            (None, None) => break,
+            (Some((node, _)), None) => format!("node {}: missing in right graph", node),
+            (None, Some((node, _))) => format!("node {}: missing in left graph", node),
+            (Some((node0, succ0)), Some((node1, succ1))) => {
+                let succ0 = succ0.into_iter().collect::<Vec<_>>();
+                let succ1 = succ1.into_iter().collect::<Vec<_>>();
+                if node0 != node1 {
+                    format!("node ids: left {}, right {}", node0, node1)
+                } else if succ0 != succ1 {
+                    let missing = succ0.iter().filter(|x| !succ1.contains(x));
+                    let extra = succ1.iter().filter(|x| !succ0.contains(x));
+                    format!(
+                        "node {}:\n   left: {}\n  right: {}\n  only left: {}\n  only right: {}",
+                        node0,
+                        SuccFmt::new(succ0.iter().copied()),
+                        SuccFmt::new(succ1.iter().copied()),
+                        SuccFmt::new(missing.copied()),
+                        SuccFmt::new(extra.copied()),
+                    )
+                } else {
+                    continue;
+                }
+            }
+        };
+        if diffs < max_diffs {
+            report.push_str(&diff);
+            report.push('\n');
+        }
+        diffs += 1;
+    }
+
+    if diffs == 0 {
+        return None;
+    }
+    if diffs > max_diffs {
+        _ = writeln!(report, "... and {} more differences", diffs - max_diffs);
+    }
+    Some(report)
+}
+
+/// Asserts that two [sequential graphs](crate::traits::SequentialGraph) have
+/// the same nodes and successor lists.
+///
+/// On failure, the panic message describes the first differing nodes using
+/// [`graph_diff`](crate::utils::graph_diff).
+///
+/// ```
+/// # use webgraph::prelude::*;
+/// # use webgraph::graphs::vec_graph::VecGraph;
+/// let g = Left(VecGraph::from_arc_list([(0, 1), (1, 0)]));
+/// let h = Left(VecGraph::from_arc_list([(1, 0), (0, 1)]));
+/// webgraph::assert_graph_eq!(g, h);
+/// ```
+#[macro_export]
+macro_rules! assert_graph_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        if let Some(diff) = $crate::utils::graph_diff(&$left, &$right, 5) {
+            panic!("assertion `left == right` failed: graphs differ\n{}", diff);
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphs::arc_list_graph::ArcListGraph;
+    use crate::graphs::vec_graph::VecGraph;
+    use crate::prelude::*;
+
+    #[test]
+    fn test_graph_fmt() {
+        let graph = Left(VecGraph::from_arc_list([
+            (0, 1),
+            (0, 2),
+            (0, 3),
+            (0, 4),
+            (2, 0),
+            (4, 4),
+        ]));
+        assert_eq!(
+            GraphFmt::new(&graph).to_string(),
+            "0: 1 2 3 4\n1:\n2: 0\n3:\n4: 4\n5 nodes, 6 arcs"
+        );
+        assert_eq!(
+            GraphFmt::new(&graph)
+                .max_nodes(3)
+                .max_successors(2)
+                .to_string(),
+            "0: 1 2 ...\n1:\n2: 0\n...\n5 nodes, 6 arcs"
+        );
+        assert_eq!(
+            GraphFmt::new(&graph).max_successors(0).to_string(),
+            "0: ...\n1:\n2: ...\n3:\n4: ...\n5 nodes, 6 arcs"
+        );
+
+        // Without a hint, arcs are counted only if all nodes are displayed
+        let graph = Left(ArcListGraph::new(
+            5,
+            [(0, 1), (0, 2), (0, 3), (0, 4), (2, 0), (4, 4)],
+        ));
+        assert_eq!(
+            GraphFmt::new(&graph).max_successors(1).to_string(),
+            "0: 1 ...\n1:\n2: 0\n3:\n4: 4\n5 nodes, 6 arcs"
+        );
+        assert_eq!(
+            GraphFmt::new(&graph).max_nodes(1).to_string(),
+            "0: 1 2 3 4\n...\n5 nodes, unknown number of arcs"
+        );
+    }
+
+    #[test]
+    fn test_assert_graph_eq() {
+        let g = Left(VecGraph::from_arc_list([(0, 1), (1, 2), (2, 0), (2, 1)]));
+        let h = Left(VecGraph::from_arc_list([(0, 1), (1, 2), (2, 0), (2, 2)]));
+        assert_graph_eq!(g, g);
+        assert_eq!(graph_diff(&g, &g, 5), None);
+
+        let err = std::panic::catch_unwind(|| assert_graph_eq!(g, h)).unwrap_err();
+        let msg = err.downcast_ref::<String>().unwrap();
+        assert_eq!(
+            msg,
+            "assertion `left == right` failed: graphs differ\n\
+             node 2:\n   left: 0 1\n  right: 0 2\n  only left: 1\n  only right: 2\n"
+        );
+
+        let k = Left(VecGraph::from_arc_list([(0, 2), (1, 0), (2, 0), (3, 3)]));
+        let msg = graph_diff(&g, &k, 2).unwrap();
+        assert_eq!(
+            msg,
+            "number of nodes: left 3, right 4\n\
+             node 0:\n   left: 1\n  right: 2\n  only left: 1\n  only right: 2\n\
+             ... and 3 more differences\n"
+        );
+    }
+}
@@ -0,0 +1,63 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+//! Cooperative cancellation of long-running operations.
+//!
+//! [`interrupt`] sets a global flag, and long-running loops (e.g., those of
+//! [`SortPairs`](super::SortPairs) and of the compressors) call
+//! [`check_interrupted`], which returns an [`Interrupted`] error once the
+//! flag is set. In this way the error propagates as usual, and destructors
+//! run: temporary directories are deleted and partial output files are
+//! removed.
+//!
+//! The CLI calls [`interrupt`] when it receives `SIGINT`.
+
+use std::fmt::{self, Display, Formatter};
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// Requests the interruption of long-running operations.
+///
+/// This function is async-signal-safe, so it can be called by a signal
+/// handler.
+pub fn interrupt() {
+    INTERRUPTED.store(true, Ordering::Relaxed);
+}
+
+/// Returns whether an interruption has been requested.
+#[inline(always)]
+pub fn is_interrupted() -> bool {
+    INTERRUPTED.load(Ordering::Relaxed)
+}
+
+/// The error signaling that an operation has been interrupted.
+///
+/// Note that it is not an error of kind
+/// [`Interrupted`](io::ErrorKind::Interrupted), as such errors are retried by
+/// methods such as [`write_all`](io::Write::write_all).
+#[derive(Debug, Clone, Copy)]
+pub struct Interrupted;
+
+impl Display for Interrupted {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("Interrupted")
+    }
+}
+
+impl std::error::Error for Interrupted {}
+
+/// Returns an [`Interrupted`] error, wrapped in an [`io::Error`], if an
+/// interruption has been requested.
+#[inline(always)]
+pub fn check_interrupted() -> io::Result<()> {
+    if is_interrupted() {
+        Err(io::Error::other(Interrupted))
+    } else {
+        Ok(())
+    }
+}
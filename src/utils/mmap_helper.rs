@@ -133,6 +133,74 @@ impl<W> MmapHelper<W> {
             _marker: core::marker::PhantomData,
         })
     }
+
+    /// Maps a section of a file into memory (read-only).
+    ///
+    /// The offset must be a multiple of the [allocation
+    /// granularity](MmapOptions::allocation_granularity) of the platform, and
+    /// the file must contain enough padding after the section so that the
+    /// length of the section can be rounded up to a multiple of the size of
+    /// `W`.
+    ///
+    /// # Arguments
+    /// - `path`: The path to the file containing the section.
+    /// - `offset`: The offset in bytes of the section.
+    /// - `len`: The length in bytes of the section.
+    /// - `flags`: The flags to be used for the mmap.
+    pub fn mmap_range(
+        path: impl AsRef<Path>,
+        offset: u64,
+        len: usize,
+        flags: MmapFlags,
+    ) -> Result<Self> {
+        let granularity = MmapOptions::allocation_granularity() as u64;
+        ensure!(
+            offset.is_multiple_of(granularity),
+            "Offset {} is not a multiple of the allocation granularity {}",
+            offset,
+            granularity
+        );
+        let file_len = path
+            .as_ref()
+            .metadata()
+            .with_context(|| format!("Cannot stat {}", path.as_ref().display()))?
+            .len();
+        // Align to multiple of size_of::<W>
+        let mmap_len = len.align_to(size_of::<W>());
+        ensure!(
+            offset + mmap_len as u64 <= file_len,
+            "Section [{}..{}) exceeds the length {} of {}",
+            offset,
+            offset + mmap_len as u64,
+            file_len,
+            path.as_ref().display()
+        );
+        let file = std::fs::File::open(path.as_ref())
+            .with_context(|| "Cannot open file for MmapHelper")?;
+
+        let mmap = unsafe {
+            // Length must be > 0, or we get a panic.
+            mmap_rs::MmapOptions::new(mmap_len.max(size_of::<W>()))
+                .with_context(|| format!("Cannot initialize mmap of size {}", mmap_len))?
+                .with_flags(flags)
+                .with_file(&file, offset)
+                .map()
+                .with_context(|| {
+                    format!(
+                        "Cannot mmap {} (offset {}, size {})",
+                        path.as_ref().display(),
+                        offset,
+                        mmap_len
+                    )
+                })?
+        };
+
+        Ok(Self {
+            len: mmap_len / core::mem::size_of::<W>(),
+            mmap,
+            _marker: core::marker::PhantomData,
+        })
+    }
 }
 
 impl<W> MmapHelper<W, MmapMut> {
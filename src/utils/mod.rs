@@ -52,14 +52,32 @@ pub fn temp_dir<P: AsRef<std::path::Path>>(base: P) -> anyhow::Result<PathBuf> {
     }
 }
 
+mod basename;
+pub use basename::Basename;
+
 mod circular_buffer;
 pub(crate) use circular_buffer::*;
 
 mod mmap_helper;
 pub use mmap_helper::*;
 
+pub mod interrupt;
+
 mod java_perm;
 pub use java_perm::*;
 
+mod perm_mode;
+pub use perm_mode::*;
+
+mod node_compaction;
+pub use node_compaction::*;
+
 pub mod sort_pairs;
 pub use sort_pairs::SortPairs;
+
+pub mod random_key;
+
+pub mod typed_ids;
+
+mod graph_fmt;
+pub use graph_fmt::*;
@@ -0,0 +1,102 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+/// An order-preserving map from a subset of the nodes of a graph to a
+/// compact range of identifiers.
+///
+/// The kept nodes are mapped to `0..num_kept()`, in increasing order, and the
+/// remaining nodes are removed. This is the renumbering of the nodes of an
+/// induced subgraph, and it should be used by all code restricting a graph to
+/// a subset of its nodes, so that the resulting identifiers are consistent.
+///
+/// Memory usage is eight bytes per node of the original graph.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeCompaction {
+    map: Box<[usize]>,
+    kept: Box<[usize]>,
+}
+
+impl NodeCompaction {
+    /// Creates a compaction keeping the given nodes of a graph with
+    /// `num_nodes` nodes.
+    ///
+    /// Nodes can be given in any order, and repeated nodes are kept once.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if a node is not smaller than `num_nodes`.
+    pub fn new(num_nodes: usize, nodes: impl IntoIterator<Item = usize>) -> Self {
+        let mut map = vec![usize::MAX; num_nodes].into_boxed_slice();
+        for node in nodes {
+            assert!(
+                node < num_nodes,
+                "Node {} is out of bounds (the graph has {} nodes)",
+                node,
+                num_nodes
+            );
+            map[node] = 0;
+        }
+        let mut kept = vec![];
+        for (node, new) in map.iter_mut().enumerate() {
+            if *new == 0 {
+                *new = kept.len();
+                kept.push(node);
+            }
+        }
+        Self {
+            map,
+            kept: kept.into_boxed_slice(),
+        }
+    }
+
+    /// Returns the number of nodes of the original graph.
+    pub fn num_nodes(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns the number of kept nodes.
+    pub fn num_kept(&self) -> usize {
+        self.kept.len()
+    }
+
+    /// Returns the new identifier of a node, or `None` if the node has been
+    /// removed or is out of bounds.
+    pub fn get(&self, node: usize) -> Option<usize> {
+        self.map.get(node).copied().filter(|&new| new != usize::MAX)
+    }
+
+    /// Returns the new identifiers of the endpoints of an arc, or `None` if
+    /// one of them has been removed or is out of bounds.
+    pub fn arc(&self, src: usize, dst: usize) -> Option<(usize, usize)> {
+        Some((self.get(src)?, self.get(dst)?))
+    }
+
+    /// Returns the kept nodes in increasing order, that is, the original
+    /// identifier of each new identifier.
+    pub fn kept(&self) -> &[usize] {
+        &self.kept
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_node_compaction() {
+        let compaction = NodeCompaction::new(6, [4, 1, 4, 3]);
+        assert_eq!(compaction.num_nodes(), 6);
+        assert_eq!(compaction.num_kept(), 3);
+        assert_eq!(compaction.kept(), &[1, 3, 4]);
+        assert_eq!(
+            (0..7).map(|node| compaction.get(node)).collect::<Vec<_>>(),
+            vec![None, Some(0), None, Some(1), Some(2), None, None]
+        );
+        assert_eq!(compaction.arc(4, 1), Some((2, 0)));
+        assert_eq!(compaction.arc(4, 2), None);
+        assert_eq!(compaction.arc(6, 1), None);
+    }
+}
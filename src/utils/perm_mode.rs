@@ -0,0 +1,207 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use anyhow::{bail, ensure, Result};
+use sux::bits::BitVec;
+use sux::traits::*;
+
+/// How to handle a permutation whose length is different from the number of
+/// nodes of the graph it is applied to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PermMode {
+    /// Fail with an error.
+    #[default]
+    Strict,
+    /// Pad missing entries with the identity and ignore extra entries.
+    Lenient,
+}
+
+/// Checks the length of a permutation against the number of nodes of a
+/// graph.
+///
+/// In [strict](PermMode::Strict) mode, an error is returned if the lengths
+/// differ. In [lenient](PermMode::Lenient) mode, the number of entries that
+/// will be padded or ignored is logged.
+///
+/// All entry points applying a permutation to a graph should call this
+/// function (or [`fit_perm`]) before starting the computation, so that they
+/// fail fast rather than panicking mid-run.
+pub fn validate_perm(perm_len: usize, num_nodes: usize, mode: PermMode) -> Result<()> {
+    if perm_len == num_nodes {
+        return Ok(());
+    }
+    match mode {
+        PermMode::Strict => bail!(
+            "The permutation has length {}, but the graph has {} nodes (use lenient mode to pad or truncate it)",
+            perm_len,
+            num_nodes
+        ),
+        PermMode::Lenient if perm_len < num_nodes => log::warn!(
+            "The permutation has length {}, but the graph has {} nodes: padding {} entries with the identity",
+            perm_len,
+            num_nodes,
+            num_nodes - perm_len
+        ),
+        PermMode::Lenient => log::warn!(
+            "The permutation has length {}, but the graph has {} nodes: ignoring {} extra entries",
+            perm_len,
+            num_nodes,
+            perm_len - num_nodes
+        ),
+    }
+    Ok(())
+}
+
+/// A permutation whose length has been [checked](validate_perm) against the
+/// number of nodes of a graph, returned by [`fit_perm`].
+#[derive(Debug, Clone)]
+pub enum FittedPerm<P> {
+    /// The permutation had the right length, and it is used as is.
+    Exact(P),
+    /// The permutation had the wrong length, and it has been padded with the
+    /// identity or truncated.
+    Fitted(Box<[usize]>),
+}
+
+/// Checks the length of a permutation against the number of nodes of a
+/// graph using [`validate_perm`], and returns a permutation with the right
+/// length.
+///
+/// If the lengths are equal, the permutation is returned as is. Otherwise,
+/// in [lenient](PermMode::Lenient) mode, a copy of the permutation padded
+/// with the identity or truncated is returned; an error is returned if the
+/// copy is not a permutation, that is, if some of its values are not smaller
+/// than the number of nodes, or if two nodes are mapped to the same value.
+/// Injectivity is checked using a bit vector with one bit per node.
+pub fn fit_perm<P: BitFieldSlice<usize>>(
+    perm: P,
+    num_nodes: usize,
+    mode: PermMode,
+) -> Result<FittedPerm<P>> {
+    let perm_len = perm.len();
+    validate_perm(perm_len, num_nodes, mode)?;
+    if perm_len == num_nodes {
+        return Ok(FittedPerm::Exact(perm));
+    }
+    let fitted = (0..num_nodes)
+        .map(|node| {
+            if node < perm_len {
+                perm.get(node)
+            } else {
+                node
+            }
+        })
+        .collect::<Box<[usize]>>();
+    check_perm(&fitted, num_nodes)?;
+    Ok(FittedPerm::Fitted(fitted))
+}
+
+/// Checks that a permutation is a permutation of the nodes of a graph with
+/// `num_nodes` nodes.
+///
+/// An error is returned if the permutation has not length `num_nodes`, if
+/// some of its values are not smaller than `num_nodes`, or if two nodes are
+/// mapped to the same value. Injectivity is checked using a bit vector with
+/// one bit per node.
+pub fn check_perm(perm: &impl BitFieldSlice<usize>, num_nodes: usize) -> Result<()> {
+    ensure!(
+        perm.len() == num_nodes,
+        "The permutation has length {}, but the graph has {} nodes",
+        perm.len(),
+        num_nodes
+    );
+    let mut seen = BitVec::new(num_nodes);
+    for node in 0..num_nodes {
+        let value = perm.get(node);
+        ensure!(
+            value < num_nodes,
+            "The permutation maps node {} to {}, but the graph has {} nodes",
+            node,
+            value,
+            num_nodes
+        );
+        ensure!(
+            !seen.get(value),
+            "The permutation maps node {} to {}, which is the image of another node",
+            node,
+            value
+        );
+        seen.set(value, true);
+    }
+    Ok(())
+}
+
+impl<P: BitFieldSliceCore<usize>> BitFieldSliceCore<usize> for FittedPerm<P> {
+    fn bit_width(&self) -> usize {
+        match self {
+            FittedPerm::Exact(perm) => perm.bit_width(),
+            FittedPerm::Fitted(_) => usize::BITS as usize,
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            FittedPerm::Exact(perm) => perm.len(),
+            FittedPerm::Fitted(perm) => perm.len(),
+        }
+    }
+}
+
+impl<P: BitFieldSlice<usize>> BitFieldSlice<usize> for FittedPerm<P> {
+    #[inline(always)]
+    unsafe fn get_unchecked(&self, index: usize) -> usize {
+        match self {
+            FittedPerm::Exact(perm) => perm.get_unchecked(index),
+            FittedPerm::Fitted(perm) => *<[usize]>::get_unchecked(perm, index),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fit_perm() -> Result<()> {
+        let perm = vec![2, 0, 1];
+        for mode in [PermMode::Strict, PermMode::Lenient] {
+            let fitted = fit_perm(perm.as_slice(), 3, mode)?;
+            assert!(matches!(fitted, FittedPerm::Exact(_)));
+            assert_eq!((0..3).map(|i| fitted.get(i)).collect::<Vec<_>>(), perm);
+        }
+
+        // Short
+        assert!(fit_perm(perm.as_slice(), 5, PermMode::Strict).is_err());
+        let fitted = fit_perm(perm.as_slice(), 5, PermMode::Lenient)?;
+        assert_eq!(fitted.len(), 5);
+        assert_eq!(
+            (0..5).map(|i| fitted.get(i)).collect::<Vec<_>>(),
+            vec![2, 0, 1, 3, 4]
+        );
+
+        // Long
+        let perm = vec![1, 0, 2, 3];
+        assert!(fit_perm(perm.as_slice(), 2, PermMode::Strict).is_err());
+        let fitted = fit_perm(perm.as_slice(), 2, PermMode::Lenient)?;
+        assert_eq!(
+            (0..2).map(|i| fitted.get(i)).collect::<Vec<_>>(),
+            vec![1, 0]
+        );
+        // Truncation must not leave out-of-range values
+        let perm = vec![3, 0, 2, 1];
+        assert!(fit_perm(perm.as_slice(), 2, PermMode::Lenient).is_err());
+        // Padding and truncation must not map two nodes to the same value
+        let perm = vec![4, 0, 1];
+        assert!(fit_perm(perm.as_slice(), 5, PermMode::Lenient).is_err());
+        let perm = vec![1, 1, 0];
+        assert!(fit_perm(perm.as_slice(), 2, PermMode::Lenient).is_err());
+
+        validate_perm(3, 3, PermMode::Strict)?;
+        assert!(validate_perm(3, 4, PermMode::Strict).is_err());
+        validate_perm(3, 4, PermMode::Lenient)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,218 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+//! Parallel, reproducible sorting of indices by random keys.
+//!
+//! Sorting indices by independent random keys yields a uniformly random
+//! permutation, and unlike Fisher–Yates shuffling the process can be
+//! distributed among threads and performed in external memory. Keys are
+//! generated by a counter-based generator, that is, the key of an index
+//! depends only on the seed and on the index, so the result does not depend
+//! on the number of threads or on the batch size.
+
+use super::sort_pairs::{BatchIterator, BitReader, BitWriter, KMergeIters};
+use super::SortPairs;
+use crate::traits::{BitDeserializer, BitSerializer};
+use anyhow::{Context, Result};
+use dsi_bitstream::prelude::*;
+use rayon::prelude::*;
+use rayon::ThreadPool;
+use std::path::Path;
+
+/// The increment of the SplitMix64 generator.
+pub(crate) const GOLDEN_GAMMA: u64 = 0x9e3779b97f4a7c15;
+
+/// The output function of the SplitMix64 generator.
+#[inline(always)]
+pub(crate) fn mix64(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+/// Returns the random key of an index, as a pair of 63-bit values to be
+/// compared lexicographically.
+///
+/// The key is given by the outputs of index `2 * index` and `2 * index + 1`
+/// of a SplitMix64 generator seeded with `seed`, with their lowest bit
+/// discarded, so that they can be stored by [`SortPairs`]. With 126 random
+/// bits, the probability of a collision is negligible even for hundreds of
+/// billions of indices; in any case, a collision just makes the relative order
+/// of the colliding indices arbitrary.
+#[inline(always)]
+pub fn random_key(seed: u64, index: usize) -> (usize, usize) {
+    let base = seed.wrapping_add((index as u64).wrapping_mul(2).wrapping_mul(GOLDEN_GAMMA));
+    (
+        (mix64(base.wrapping_add(GOLDEN_GAMMA)) >> 1) as usize,
+        (mix64(base.wrapping_add(GOLDEN_GAMMA.wrapping_mul(2))) >> 1) as usize,
+    )
+}
+
+/// A serializer and deserializer for the indices sorted by
+/// [`sort_by_random_key`].
+#[derive(Clone, Debug)]
+pub struct IndexSerDes;
+
+impl BitSerializer<NE, BitWriter> for IndexSerDes {
+    type SerType = usize;
+
+    fn serialize(
+        &self,
+        value: &Self::SerType,
+        bitstream: &mut BitWriter,
+    ) -> Result<usize, <BitWriter as BitWrite<NE>>::Error> {
+        bitstream.write_delta(*value as u64)
+    }
+}
+
+impl BitDeserializer<NE, BitReader> for IndexSerDes {
+    type DeserType = usize;
+
+    fn deserialize(
+        &self,
+        bitstream: &mut BitReader,
+    ) -> Result<Self::DeserType, <BitReader as BitRead<NE>>::Error> {
+        bitstream.read_delta().map(|x| x as usize)
+    }
+}
+
+/// Returns the indices in `0..n` sorted by their [random key](random_key).
+///
+/// The indices are split evenly among the threads of `thread_pool`; each
+/// thread generates the keys of its indices and sorts them in batches of
+/// `batch_size / num_threads` elements using a [`SortPairs`] in a
+/// subdirectory of `dir`, which must be empty. The returned iterator merges
+/// the batches, so memory usage is bounded by the batch size also when
+/// iterating. The output is a uniformly random permutation that depends
+/// only on `n` and `seed`.
+pub fn sort_by_random_key(
+    n: usize,
+    seed: u64,
+    batch_size: usize,
+    dir: impl AsRef<Path>,
+    thread_pool: &ThreadPool,
+) -> Result<impl Iterator<Item = usize>> {
+    let dir = dir.as_ref();
+    let num_threads = thread_pool.current_num_threads().max(1);
+    let batch_size = (batch_size / num_threads).max(1);
+    let chunk_size = n.div_ceil(num_threads).max(1);
+
+    let iters = thread_pool.install(|| {
+        (0..n.div_ceil(chunk_size))
+            .into_par_iter()
+            .map(|chunk| {
+                let chunk_dir = dir.join(format!("{:04x}", chunk));
+                std::fs::create_dir(&chunk_dir)
+                    .with_context(|| format!("Could not create {}", chunk_dir.display()))?;
+                let mut sort_pairs =
+                    SortPairs::new_labeled(batch_size, &chunk_dir, IndexSerDes, IndexSerDes)?;
+                for index in chunk * chunk_size..((chunk + 1) * chunk_size).min(n) {
+                    let (high, low) = random_key(seed, index);
+                    sort_pairs.push_labeled(high, low, index)?;
+                }
+                sort_pairs.iter()
+            })
+            .collect::<Result<Vec<_>>>()
+    })?;
+
+    Ok(iters
+        .into_iter()
+        .collect::<KMergeIters<BatchIterator<IndexSerDes>, usize>>()
+        .map(|(_, _, index)| index))
+}
+
+/// Returns a fingerprint of a sequence of indices that depends only on
+/// their multiset.
+///
+/// The fingerprint of a permutation of `0..n` is equal to
+/// [`perm_fingerprint_range`] of `n`, and the probability that the
+/// fingerprints of different multisets coincide is about 2<sup>-64</sup>, so
+/// this function provides a streaming check that a sequence is a
+/// permutation.
+pub fn perm_fingerprint(indices: impl IntoIterator<Item = usize>) -> u64 {
+    indices.into_iter().fold(0, |acc: u64, x| {
+        acc.wrapping_add(mix64(x as u64 ^ GOLDEN_GAMMA))
+    })
+}
+
+/// Returns the [fingerprint](perm_fingerprint) of `0..n`, computing it in
+/// parallel.
+pub fn perm_fingerprint_range(n: usize, thread_pool: &ThreadPool) -> u64 {
+    thread_pool.install(|| {
+        (0..n)
+            .into_par_iter()
+            .map(|x| mix64(x as u64 ^ GOLDEN_GAMMA))
+            .reduce(|| 0, |a, b| a.wrapping_add(b))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted(n: usize, seed: u64, batch_size: usize, threads: usize) -> Result<Vec<usize>> {
+        let dir = tempfile::tempdir()?;
+        let thread_pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()?;
+        let perm = sort_by_random_key(n, seed, batch_size, dir.path(), &thread_pool)?.collect();
+        Ok(perm)
+    }
+
+    #[test]
+    fn test_reproducible() -> Result<()> {
+        let perm = sorted(1000, 42, 100, 1)?;
+        let mut check = perm.clone();
+        check.sort();
+        assert_eq!(check, (0..1000).collect::<Vec<_>>());
+        assert_ne!(perm, (0..1000).collect::<Vec<_>>());
+        // The result depends only on the seed
+        assert_eq!(sorted(1000, 42, 7, 4)?, perm);
+        assert_eq!(sorted(1000, 42, 10000, 3)?, perm);
+        assert_ne!(sorted(1000, 43, 100, 1)?, perm);
+        assert!(sorted(0, 0, 10, 2)?.is_empty());
+
+        let thread_pool = rayon::ThreadPoolBuilder::new().num_threads(2).build()?;
+        assert_eq!(
+            perm_fingerprint(perm),
+            perm_fingerprint_range(1000, &thread_pool)
+        );
+        assert_ne!(
+            perm_fingerprint((0..999).chain([0])),
+            perm_fingerprint_range(1000, &thread_pool)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_uniform() -> Result<()> {
+        // Chi-square test on the 6 permutations of 3 elements
+        let samples = 1200;
+        let mut counts = [0_usize; 6];
+        for seed in 0..samples {
+            let perm = sorted(3, seed, 2, 2)?;
+            let rank = match perm.as_slice() {
+                [0, 1, 2] => 0,
+                [0, 2, 1] => 1,
+                [1, 0, 2] => 2,
+                [1, 2, 0] => 3,
+                [2, 0, 1] => 4,
+                [2, 1, 0] => 5,
+                _ => panic!("Not a permutation: {:?}", perm),
+            };
+            counts[rank] += 1;
+        }
+        let expected = samples as f64 / 6.0;
+        let chi_square = counts
+            .iter()
+            .map(|&c| (c as f64 - expected).powi(2) / expected)
+            .sum::<f64>();
+        // 99.9% quantile of the chi-square distribution with 5 degrees of
+        // freedom
+        assert!(chi_square < 20.52, "{:?} {}", counts, chi_square);
+        Ok(())
+    }
+}
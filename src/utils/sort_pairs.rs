@@ -7,6 +7,7 @@
 
 //! Facilities to sort externally pairs of nodes with an associated label.
 
+use super::interrupt::check_interrupted;
 use super::{ArcMmapHelper, MmapHelper};
 use crate::traits::{BitDeserializer, BitSerializer, SortedIterator};
 use anyhow::{anyhow, Context};
@@ -16,14 +17,165 @@ use log::debug;
 use mmap_rs::MmapFlags;
 use rdst::*;
 use std::{
+    cmp::Ordering,
     fs::File,
-    io::BufWriter,
+    io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write},
+    marker::PhantomData,
     path::{Path, PathBuf},
     sync::Arc,
 };
 
-pub type BitWriter = BufBitWriter<NE, WordAdapter<usize, BufWriter<File>>>;
-pub type BitReader = BufBitReader<NE, MemWordReader<u32, ArcMmapHelper<u32>>>;
+pub type BitWriter = BufBitWriter<NE, WordAdapter<usize, BatchWriter>>;
+pub type BitReader = BufBitReader<NE, BatchReader>;
+
+/// The magic bytes at the start of a batch file.
+const BATCH_MAGIC: [u8; 4] = *b"WGSP";
+/// The version of the batch file format.
+const BATCH_VERSION: u8 = 1;
+/// The length in bytes of the header of a batch file.
+const BATCH_HEADER_LEN: u64 = 8;
+/// The header byte for uncompressed bitstreams.
+const CODEC_NONE: u8 = 0;
+/// The header byte for Zstandard-compressed bitstreams.
+const CODEC_ZSTD: u8 = 1;
+/// The header byte for batches whose targets are gap-coded, that is, sorted
+/// by [`Lex`].
+const TARGETS_GAPS: u8 = 0;
+/// The header byte for batches whose targets are coded verbatim, that is,
+/// sorted by a custom [`ArcOrder`].
+const TARGETS_PLAIN: u8 = 1;
+
+/// Returns the header of a batch file.
+///
+/// The header is made of the [magic bytes](BATCH_MAGIC), the [format
+/// version](BATCH_VERSION), the codec of the bitstream, the coding of
+/// targets, and a byte of padding, so that the bitstream of uncompressed
+/// batches is aligned.
+fn batch_header(codec: u8, targets: u8) -> [u8; BATCH_HEADER_LEN as usize] {
+    let mut header = [0; BATCH_HEADER_LEN as usize];
+    header[..4].copy_from_slice(&BATCH_MAGIC);
+    header[4] = BATCH_VERSION;
+    header[5] = codec;
+    header[6] = targets;
+    header
+}
+
+/// The backend of the [`BitWriter`] used to write batch files, possibly
+/// compressing the bitstream with Zstandard.
+pub enum BatchWriter {
+    Plain(BufWriter<File>),
+    Zstd(zstd::stream::write::Encoder<'static, BufWriter<File>>),
+}
+
+impl BatchWriter {
+    /// Completes the batch file, flushing the buffers and, if necessary,
+    /// terminating the Zstandard frame.
+    fn finish(self) -> std::io::Result<()> {
+        match self {
+            BatchWriter::Plain(mut writer) => writer.flush(),
+            BatchWriter::Zstd(encoder) => encoder.finish()?.flush(),
+        }
+    }
+}
+
+impl Write for BatchWriter {
+    #[inline(always)]
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            BatchWriter::Plain(writer) => writer.write(buf),
+            BatchWriter::Zstd(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            BatchWriter::Plain(writer) => writer.flush(),
+            BatchWriter::Zstd(encoder) => encoder.flush(),
+        }
+    }
+}
+
+/// A word reader over a Zstandard-compressed batch file.
+///
+/// Words after the end of the bitstream are zero, as in the case of a
+/// [`MemWordReader`]. Cloning reopens the file and skips the words already
+/// read.
+pub struct ZstdWordReader {
+    path: Arc<PathBuf>,
+    decoder: zstd::stream::read::Decoder<'static, BufReader<File>>,
+    /// The number of words read so far.
+    word_pos: u64,
+}
+
+impl ZstdWordReader {
+    fn open(path: Arc<PathBuf>, word_pos: u64) -> std::io::Result<Self> {
+        let mut file = File::open(path.as_path())?;
+        file.seek(SeekFrom::Start(BATCH_HEADER_LEN))?;
+        let mut decoder = zstd::stream::read::Decoder::new(file)?;
+        std::io::copy(
+            &mut (&mut decoder).take(word_pos * core::mem::size_of::<u32>() as u64),
+            &mut std::io::sink(),
+        )?;
+        Ok(ZstdWordReader {
+            path,
+            decoder,
+            word_pos,
+        })
+    }
+}
+
+impl Clone for ZstdWordReader {
+    fn clone(&self) -> Self {
+        Self::open(self.path.clone(), self.word_pos)
+            .unwrap_or_else(|e| panic!("Could not reopen {}: {}", self.path.display(), e))
+    }
+}
+
+impl WordRead for ZstdWordReader {
+    type Error = std::io::Error;
+    type Word = u32;
+
+    fn read_word(&mut self) -> Result<u32, std::io::Error> {
+        let mut bytes = [0; core::mem::size_of::<u32>()];
+        let mut len = 0;
+        while len < bytes.len() {
+            match self.decoder.read(&mut bytes[len..]) {
+                Ok(0) if len == 0 => return Ok(0),
+                Ok(0) => return Err(std::io::ErrorKind::UnexpectedEof.into()),
+                Ok(n) => len += n,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        self.word_pos += 1;
+        Ok(u32::from_ne_bytes(bytes))
+    }
+}
+
+/// The backend of the [`BitReader`] used to read batch files: a memory
+/// mapping for uncompressed batches, or a streaming decoder for compressed
+/// ones.
+#[derive(Clone)]
+pub enum BatchReader {
+    Mmap(MemWordReader<u32, ArcMmapHelper<u32>>),
+    Zstd(ZstdWordReader),
+}
+
+impl WordRead for BatchReader {
+    type Error = std::io::Error;
+    type Word = u32;
+
+    #[inline(always)]
+    fn read_word(&mut self) -> Result<u32, std::io::Error> {
+        match self {
+            BatchReader::Mmap(reader) => match reader.read_word() {
+                Ok(word) => Ok(word),
+                Err(e) => match e {},
+            },
+            BatchReader::Zstd(reader) => reader.read_word(),
+        }
+    }
+}
 
 /// An arc expressed as a pair of nodes and the associated label.
 ///
@@ -63,6 +215,157 @@ impl<T: Copy> Ord for Triple<T> {
     }
 }
 
+/// An order on labeled arcs, used by [`SortPairs`] to sort batches and by
+/// [`KMergeIters`] to merge them.
+///
+/// The order must sort arcs by source first, but arcs with the same source
+/// can be sorted arbitrarily, for example by label. The default order,
+/// [`Lex`], is the lexicographical order on pairs of nodes, which is the
+/// order needed to build a graph.
+///
+/// Note that when using a custom order duplicate arcs are not necessarily
+/// consecutive, and thus [`DuplicateArcs::Remove`] removes only duplicates
+/// that are adjacent in the order.
+pub trait ArcOrder<T>: Clone {
+    /// Compares two arcs, given as source, target, and label.
+    fn cmp(&self, a: (usize, usize, &T), b: (usize, usize, &T)) -> Ordering;
+
+    /// Returns whether the targets of arcs with the same source are
+    /// nondecreasing, in which case batches store their gaps.
+    fn sorted_targets(&self) -> bool {
+        false
+    }
+
+    /// Sorts a batch of triples.
+    fn sort(&self, batch: &mut [Triple<T>])
+    where
+        T: Copy + Send + Sync,
+    {
+        batch.sort_unstable_by(|a, b| {
+            self.cmp(
+                (a.pair[0], a.pair[1], &a.label),
+                (b.pair[0], b.pair[1], &b.label),
+            )
+        });
+    }
+}
+
+/// The lexicographical order on pairs of nodes, ignoring labels.
+///
+/// This is the default [`ArcOrder`]; batches are sorted using a radix sort.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Lex;
+
+impl<T> ArcOrder<T> for Lex {
+    #[inline(always)]
+    fn cmp(&self, a: (usize, usize, &T), b: (usize, usize, &T)) -> Ordering {
+        (a.0, a.1).cmp(&(b.0, b.1))
+    }
+
+    fn sorted_targets(&self) -> bool {
+        true
+    }
+
+    fn sort(&self, batch: &mut [Triple<T>])
+    where
+        T: Copy + Send + Sync,
+    {
+        batch.radix_sort_unstable();
+    }
+}
+
+/// The order by source, decreasing label, and target.
+///
+/// Merging batches sorted by this order returns, for each source, the arcs
+/// with the largest labels first; ties are broken by increasing target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ByLabelDesc;
+
+impl<T: Ord> ArcOrder<T> for ByLabelDesc {
+    #[inline(always)]
+    fn cmp(&self, a: (usize, usize, &T), b: (usize, usize, &T)) -> Ordering {
+        a.0.cmp(&b.0)
+            .then_with(|| b.2.cmp(a.2))
+            .then_with(|| a.1.cmp(&b.1))
+    }
+}
+
+/// A policy for handling duplicate arcs, that is, arcs with the same source and
+/// target.
+///
+/// The policy is applied by [`SortPairs`], [`KMergeIters`], and
+/// [`ArcListGraph`](crate::graphs::arc_list_graph::ArcListGraph). To preserve
+/// the multiplicity of arcs as a label, use [`CountDuplicates`] instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DuplicateArcs {
+    /// Duplicate arcs are returned (the default).
+    #[default]
+    Keep,
+    /// Only one arc is returned for each pair of nodes; if arcs are labeled,
+    /// the label is that of one of the duplicates.
+    Remove,
+}
+
+/// An iterator adapter merging duplicate arcs of a sorted iterator on labeled
+/// arcs into a single arc labeled by its multiplicity.
+///
+/// The labels of the underlying iterator are discarded.
+pub struct CountDuplicates<I: Iterator> {
+    iter: core::iter::Peekable<I>,
+}
+
+impl<I: Iterator + Clone> Clone for CountDuplicates<I>
+where
+    I::Item: Clone,
+{
+    fn clone(&self) -> Self {
+        CountDuplicates {
+            iter: self.iter.clone(),
+        }
+    }
+}
+
+impl<I: Iterator + core::fmt::Debug> core::fmt::Debug for CountDuplicates<I>
+where
+    I::Item: core::fmt::Debug,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("CountDuplicates")
+            .field("iter", &self.iter)
+            .finish()
+    }
+}
+
+impl<T, I: Iterator<Item = (usize, usize, T)>> CountDuplicates<I> {
+    pub fn new(iter: impl IntoIterator<IntoIter = I>) -> Self {
+        CountDuplicates {
+            iter: iter.into_iter().peekable(),
+        }
+    }
+}
+
+impl<T, I: Iterator<Item = (usize, usize, T)>> Iterator for CountDuplicates<I> {
+    type Item = (usize, usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (src, dst, _) = self.iter.next()?;
+        let mut count = 1;
+        while self
+            .iter
+            .next_if(|&(s, d, _)| s == src && d == dst)
+            .is_some()
+        {
+            count += 1;
+        }
+        Some((src, dst, count))
+    }
+}
+
+unsafe impl<T, I: Iterator<Item = (usize, usize, T)> + SortedIterator> SortedIterator
+    for CountDuplicates<I>
+{
+}
+
 /// A struct that provides external sorting for pairs of nodes with an
 /// associated label.
 ///
@@ -92,6 +395,13 @@ impl<T: Copy> Ord for Triple<T> {
 /// methods without labels. Note however that the [resulting
 /// iterator](SortPairs::iter) is labeled, and returns pairs labeled with `()`.
 ///
+/// Duplicate pairs are returned by default; this behavior can be changed
+/// using [`SortPairs::duplicates`].
+///
+/// The triples are sorted by lexicographical order of the pairs by default,
+/// but a different [`ArcOrder`] can be set using [`SortPairs::order`], for
+/// example to obtain the arcs of each source by decreasing label.
+///
 /// Note that batches must be deleted manually using
 /// [`SortPairs::delete_batches`] after usage, unless you stored them in a
 /// self-deleting temporary directory, such as those created by the
@@ -99,6 +409,7 @@ impl<T: Copy> Ord for Triple<T> {
 pub struct SortPairs<
     S: BitSerializer<NE, BitWriter> = (),
     D: BitDeserializer<NE, BitReader> + Clone = (),
+    O: ArcOrder<S::SerType> = Lex,
 > where
     S::SerType: Send + Sync + Copy,
 {
@@ -118,6 +429,13 @@ pub struct SortPairs<
     last_batch_len: usize,
     /// The batch of triples we are currently building.
     batch: Vec<Triple<S::SerType>>,
+    /// The policy for duplicate pairs.
+    duplicates: DuplicateArcs,
+    /// The Zstandard compression level of the batches, if they are
+    /// compressed.
+    compression: Option<i32>,
+    /// The order of the triples.
+    order: O,
 }
 
 impl SortPairs<(), ()> {
@@ -166,10 +484,66 @@ where
                 num_batches: 0,
                 last_batch_len: 0,
                 batch: Vec::with_capacity(batch_size),
+                duplicates: DuplicateArcs::Keep,
+                compression: None,
+                order: Lex,
             })
         }
     }
 
+    /// Sets the order of the triples returned by the
+    /// [iterator](SortPairs::iter), which is lexicographical on pairs by
+    /// default.
+    ///
+    /// # Panics
+    ///
+    /// If some pairs have already been added.
+    pub fn order<O: ArcOrder<S::SerType>>(self, order: O) -> SortPairs<S, D, O> {
+        assert!(
+            self.batch.is_empty() && self.num_batches == 0,
+            "The order must be set before adding pairs"
+        );
+        SortPairs {
+            batch_size: self.batch_size,
+            dir: self.dir,
+            serializer: self.serializer,
+            deserializer: self.deserializer,
+            num_batches: 0,
+            last_batch_len: 0,
+            batch: self.batch,
+            duplicates: self.duplicates,
+            compression: self.compression,
+            order,
+        }
+    }
+}
+
+impl<
+        S: BitSerializer<NE, BitWriter>,
+        D: BitDeserializer<NE, BitReader> + Clone,
+        O: ArcOrder<S::SerType>,
+    > SortPairs<S, D, O>
+where
+    S::SerType: Send + Sync + Copy,
+{
+    /// Sets the policy for duplicate pairs of the [iterator](SortPairs::iter).
+    pub fn duplicates(mut self, duplicates: DuplicateArcs) -> Self {
+        self.duplicates = duplicates;
+        self
+    }
+
+    /// Sets the Zstandard compression level of the batches written to disk,
+    /// or disables compression if `level` is `None` (the default).
+    ///
+    /// Compression reduces the space used by the batches and the amount of
+    /// I/O at the cost of additional CPU time, so it is useful when disks are
+    /// slow with respect to the CPU. The codec is recorded in the header of
+    /// each batch, so reading requires no configuration.
+    pub fn compression(mut self, level: Option<i32>) -> Self {
+        self.compression = level;
+        self
+    }
+
     /// Adds a labeled pair to the graph.
     pub fn push_labeled(&mut self, x: usize, y: usize, t: S::SerType) -> anyhow::Result<()> {
         self.batch.push(Triple {
@@ -177,6 +551,7 @@ where
             label: t,
         });
         if self.batch.len() >= self.batch_size {
+            check_interrupted()?;
             self.dump()?;
         }
         Ok(())
@@ -191,11 +566,15 @@ where
 
         // Creates a batch file where to dump
         let batch_name = self.dir.join(format!("{:06x}", self.num_batches));
-        BatchIterator::new_from_vec_labeled(
+        let start = std::time::Instant::now();
+        self.order.sort(&mut self.batch);
+        debug!("Sorted {} arcs in {:?}", self.batch.len(), start.elapsed());
+        write_batch(
             batch_name,
-            &mut self.batch,
+            &self.batch,
             &self.serializer,
-            self.deserializer.clone(),
+            self.compression,
+            self.order.sorted_targets(),
         )?;
         self.last_batch_len = self.batch.len();
         self.batch.clear();
@@ -217,32 +596,130 @@ where
         Ok(())
     }
 
-    /// Returns an iterator over the labeled pairs, lexicographically sorted.
-    pub fn iter(&mut self) -> anyhow::Result<KMergeIters<BatchIterator<D>, D::DeserType>> {
+    /// Returns an iterator over the labeled pairs, sorted by the
+    /// [order](SortPairs::order).
+    ///
+    /// Duplicate pairs are handled as specified by [`SortPairs::duplicates`].
+    #[allow(clippy::type_complexity)]
+    pub fn iter(&mut self) -> anyhow::Result<KMergeIters<BatchIterator<D, O>, D::DeserType, O>>
+    where
+        O: ArcOrder<D::DeserType>,
+    {
         self.dump()?;
-        Ok(KMergeIters::new((0..self.num_batches).map(|batch_idx| {
-            BatchIterator::new_labeled(
-                self.dir.join(format!("{:06x}", batch_idx)),
-                if batch_idx == self.num_batches - 1 {
-                    self.last_batch_len
-                } else {
-                    self.batch_size
-                },
-                self.deserializer.clone(),
+        let duplicates = self.duplicates;
+        Ok(KMergeIters::with_order(
+            (0..self.num_batches).map(|batch_idx| {
+                BatchIterator::new_labeled_with_order(
+                    self.dir.join(format!("{:06x}", batch_idx)),
+                    if batch_idx == self.num_batches - 1 {
+                        self.last_batch_len
+                    } else {
+                        self.batch_size
+                    },
+                    self.deserializer.clone(),
+                )
+                .unwrap()
+            }),
+            self.order.clone(),
+        )
+        .duplicates(duplicates))
+    }
+}
+
+/// Writes the given sorted labeled pairs to a batch file in `file_path`,
+/// compressing the bitstream with Zstandard if `compression` is not `None`.
+///
+/// Sources are always gap-coded; targets are gap-coded only if
+/// `sorted_targets` is true.
+fn write_batch<S: BitSerializer<NE, BitWriter>>(
+    file_path: impl AsRef<Path>,
+    batch: &[Triple<S::SerType>],
+    serializer: &S,
+    compression: Option<i32>,
+    sorted_targets: bool,
+) -> anyhow::Result<()>
+where
+    S::SerType: Copy,
+{
+    let file_path = file_path.as_ref();
+    let mut file = BufWriter::with_capacity(
+        1 << 16,
+        File::create(file_path).with_context(|| {
+            format!(
+                "Could not create BatchIterator temporary file {}",
+                file_path.display()
             )
-            .unwrap()
-        })))
+        })?,
+    );
+    file.write_all(&batch_header(
+        match compression {
+            None => CODEC_NONE,
+            Some(_) => CODEC_ZSTD,
+        },
+        if sorted_targets {
+            TARGETS_GAPS
+        } else {
+            TARGETS_PLAIN
+        },
+    ))
+    .with_context(|| format!("Could not write header to {}", file_path.display()))?;
+    let writer = match compression {
+        None => BatchWriter::Plain(file),
+        Some(level) => BatchWriter::Zstd(
+            zstd::stream::write::Encoder::new(file, level)
+                .context("Could not create Zstandard encoder")?,
+        ),
+    };
+    // create a bitstream to write to the file
+    let mut stream = <BufBitWriter<NE, _>>::new(<WordAdapter<usize, _>>::new(writer));
+    // dump the triples to the bitstream
+    let (mut prev_src, mut prev_dst) = (0, 0);
+    for Triple {
+        pair: [src, dst],
+        label,
+    } in batch.iter()
+    {
+        // write the source gap as gamma
+        stream
+            .write_gamma((src - prev_src) as _)
+            .with_context(|| format!("Could not write {} after {}", src, prev_src))?;
+        if *src != prev_src || !sorted_targets {
+            // Reset prev_y
+            prev_dst = 0;
+        }
+        // write the destination gap as gamma
+        stream
+            .write_gamma((dst - prev_dst) as _)
+            .with_context(|| format!("Could not write {} after {}", dst, prev_dst))?;
+        // write the label
+        serializer
+            .serialize(label, &mut stream)
+            .context("Could not serialize label")?;
+        (prev_src, prev_dst) = (*src, *dst);
     }
+    // flush the stream and complete the file
+    stream
+        .into_inner()
+        .context("Could not flush stream")?
+        .into_inner()
+        .finish()
+        .with_context(|| format!("Could not complete {}", file_path.display()))?;
+    Ok(())
 }
 
 /// An iterator that can read the batch files generated by [`SortPairs`].
-pub struct BatchIterator<D: BitDeserializer<NE, BitReader> = ()> {
+///
+/// The type parameter `O` is the [order](ArcOrder) of the batch.
+pub struct BatchIterator<D: BitDeserializer<NE, BitReader> = (), O = Lex> {
     stream: BitReader,
     len: usize,
     current: usize,
     prev_src: usize,
     prev_dst: usize,
+    /// Whether targets are gap-coded.
+    sorted_targets: bool,
     deserializer: D,
+    _marker: PhantomData<O>,
 }
 
 impl BatchIterator<()> {
@@ -305,75 +782,101 @@ impl<D: BitDeserializer<NE, BitReader>> BatchIterator<D> {
     where
         S::SerType: Send + Sync + Copy,
     {
-        // create a batch file where to dump
         let file_path = file_path.as_ref();
-        let file = std::io::BufWriter::with_capacity(
-            1 << 16,
-            std::fs::File::create(file_path).with_context(|| {
-                format!(
-                    "Could not create BatchIterator temporary file {}",
-                    file_path.display()
-                )
-            })?,
-        );
-        // create a bitstream to write to the file
-        let mut stream = <BufBitWriter<NE, _>>::new(<WordAdapter<usize, _>>::new(file));
-        // dump the triples to the bitstream
-        let (mut prev_src, mut prev_dst) = (0, 0);
-        for Triple {
-            pair: [src, dst],
-            label,
-        } in batch.iter()
-        {
-            // write the source gap as gamma
-            stream
-                .write_gamma((src - prev_src) as _)
-                .with_context(|| format!("Could not write {} after {}", src, prev_src))?;
-            if *src != prev_src {
-                // Reset prev_y
-                prev_dst = 0;
-            }
-            // write the destination gap as gamma
-            stream
-                .write_gamma((dst - prev_dst) as _)
-                .with_context(|| format!("Could not write {} after {}", dst, prev_dst))?;
-            // write the label
-            serializer
-                .serialize(label, &mut stream)
-                .context("Could not serialize label")?;
-            (prev_src, prev_dst) = (*src, *dst);
-        }
-        // flush the stream and reset the buffer
-        stream.flush().context("Could not flush stream")?;
-
+        write_batch(file_path, batch, serializer, None, true)?;
         Self::new_labeled(file_path, batch.len(), deserializer)
     }
 
-    /// Creates a new iterator over the triples previously serialized in `file_path`.
+    /// Creates a new iterator over the triples previously serialized in
+    /// `file_path`, which must be sorted lexicographically.
     pub fn new_labeled<P: AsRef<std::path::Path>>(
         file_path: P,
         len: usize,
         deserializer: D,
     ) -> anyhow::Result<Self> {
-        let stream = <BufBitReader<NE, _>>::new(MemWordReader::new(ArcMmapHelper(Arc::new(
-            MmapHelper::mmap(
-                file_path.as_ref(),
-                MmapFlags::TRANSPARENT_HUGE_PAGES | MmapFlags::SEQUENTIAL,
-            )
-            .with_context(|| format!("Could not mmap {}", file_path.as_ref().display()))?,
-        ))));
+        let file_path = file_path.as_ref();
+        let iter = Self::new_labeled_with_order(file_path, len, deserializer)?;
+        if !iter.sorted_targets {
+            return Err(anyhow!(
+                "{} is not sorted lexicographically",
+                file_path.display()
+            ));
+        }
+        Ok(iter)
+    }
+}
+
+impl<D: BitDeserializer<NE, BitReader>, O> BatchIterator<D, O> {
+    /// Creates a new iterator over the triples previously serialized in
+    /// `file_path`, which must be sorted by the [order](ArcOrder) `O`.
+    pub fn new_labeled_with_order<P: AsRef<std::path::Path>>(
+        file_path: P,
+        len: usize,
+        deserializer: D,
+    ) -> anyhow::Result<Self> {
+        let file_path = file_path.as_ref();
+        let mut header = [0; BATCH_HEADER_LEN as usize];
+        File::open(file_path)
+            .and_then(|mut file| file.read_exact(&mut header))
+            .with_context(|| format!("Could not read header of {}", file_path.display()))?;
+        if header[..4] != BATCH_MAGIC || header[4] != BATCH_VERSION {
+            return Err(anyhow!(
+                "{} is not a batch file of version {}",
+                file_path.display(),
+                BATCH_VERSION
+            ));
+        }
+        let reader = match header[5] {
+            CODEC_NONE => {
+                let mut reader = MemWordReader::new(ArcMmapHelper(Arc::new(
+                    MmapHelper::mmap(
+                        file_path,
+                        MmapFlags::TRANSPARENT_HUGE_PAGES | MmapFlags::SEQUENTIAL,
+                    )
+                    .with_context(|| format!("Could not mmap {}", file_path.display()))?,
+                )));
+                // Skip the header
+                reader.set_word_pos(BATCH_HEADER_LEN / core::mem::size_of::<u32>() as u64)?;
+                BatchReader::Mmap(reader)
+            }
+            CODEC_ZSTD => BatchReader::Zstd(
+                ZstdWordReader::open(Arc::new(file_path.to_owned()), 0)
+                    .with_context(|| format!("Could not open {}", file_path.display()))?,
+            ),
+            codec => {
+                return Err(anyhow!(
+                    "Unknown codec {} in batch file {}",
+                    codec,
+                    file_path.display()
+                ))
+            }
+        };
+        let sorted_targets = match header[6] {
+            TARGETS_GAPS => true,
+            TARGETS_PLAIN => false,
+            targets => {
+                return Err(anyhow!(
+                    "Unknown coding of targets {} in batch file {}",
+                    targets,
+                    file_path.display()
+                ))
+            }
+        };
+        let stream = <BufBitReader<NE, _>>::new(reader);
         Ok(BatchIterator {
             stream,
             len,
             current: 0,
             prev_src: 0,
             prev_dst: 0,
+            sorted_targets,
             deserializer,
+            _marker: PhantomData,
         })
     }
 }
 
-impl<D: BitDeserializer<NE, BitReader> + Clone> Clone for BatchIterator<D> {
+impl<D: BitDeserializer<NE, BitReader> + Clone, O> Clone for BatchIterator<D, O> {
     fn clone(&self) -> Self {
         BatchIterator {
             stream: self.stream.clone(),
@@ -381,21 +884,23 @@ impl<D: BitDeserializer<NE, BitReader> + Clone> Clone for BatchIterator<D> {
             current: self.current,
             prev_src: self.prev_src,
             prev_dst: self.prev_dst,
+            sorted_targets: self.sorted_targets,
             deserializer: self.deserializer.clone(),
+            _marker: PhantomData,
         }
     }
 }
 
 unsafe impl<D: BitDeserializer<NE, BitReader>> SortedIterator for BatchIterator<D> {}
 
-impl<D: BitDeserializer<NE, BitReader>> Iterator for BatchIterator<D> {
+impl<D: BitDeserializer<NE, BitReader>, O> Iterator for BatchIterator<D, O> {
     type Item = (usize, usize, D::DeserType);
     fn next(&mut self) -> Option<Self::Item> {
         if self.current == self.len {
             return None;
         }
         let src = self.prev_src + self.stream.read_gamma().unwrap() as usize;
-        if src != self.prev_src {
+        if src != self.prev_src || !self.sorted_targets {
             // Reset prev_y
             self.prev_dst = 0;
         }
@@ -409,42 +914,50 @@ impl<D: BitDeserializer<NE, BitReader>> Iterator for BatchIterator<D> {
 }
 
 #[derive(Clone, Debug)]
-/// Private struct that can be used to sort triples based only on the pair of
-/// nodes and ignoring the label.
-struct HeadTail<T, I: Iterator<Item = (usize, usize, T)>> {
+/// Private struct that can be used to sort triples based on an
+/// [`ArcOrder`] (by default, only on the pair of nodes, ignoring the label).
+struct HeadTail<T, I: Iterator<Item = (usize, usize, T)>, O = Lex> {
     head: (usize, usize, T),
     tail: I,
+    order: O,
 }
 
-impl<T, I: Iterator<Item = (usize, usize, T)>> PartialEq for HeadTail<T, I> {
+impl<T, I: Iterator<Item = (usize, usize, T)>, O: ArcOrder<T>> PartialEq for HeadTail<T, I, O> {
     #[inline(always)]
     fn eq(&self, other: &Self) -> bool {
-        (self.head.0, self.head.1) == (other.head.0, other.head.1)
+        self.cmp(other) == Ordering::Equal
     }
 }
 
-impl<T, I: Iterator<Item = (usize, usize, T)>> Eq for HeadTail<T, I> {}
+impl<T, I: Iterator<Item = (usize, usize, T)>, O: ArcOrder<T>> Eq for HeadTail<T, I, O> {}
 
-impl<T, I: Iterator<Item = (usize, usize, T)>> PartialOrd for HeadTail<T, I> {
+impl<T, I: Iterator<Item = (usize, usize, T)>, O: ArcOrder<T>> PartialOrd for HeadTail<T, I, O> {
     #[inline(always)]
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some((other.head.0, other.head.1).cmp(&(self.head.0, self.head.1)))
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
     }
 }
 
-impl<T, I: Iterator<Item = (usize, usize, T)>> Ord for HeadTail<T, I> {
+impl<T, I: Iterator<Item = (usize, usize, T)>, O: ArcOrder<T>> Ord for HeadTail<T, I, O> {
     #[inline(always)]
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        (other.head.0, other.head.1).cmp(&(self.head.0, self.head.1))
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed, as the heap is a max-heap
+        self.order.cmp(
+            (other.head.0, other.head.1, &other.head.2),
+            (self.head.0, self.head.1, &self.head.2),
+        )
     }
 }
 
 /// A structure using a [quaternary heap](dary_heap::QuaternaryHeap) to merge sorted iterators.
 ///
 /// The iterators must be sorted by the pair of nodes, and the structure will return the triples
-/// sorted by lexicographical order of the pairs of nodes.
+/// sorted by lexicographical order of the pairs of nodes. Iterators sorted by a
+/// different [`ArcOrder`] can be merged using [`KMergeIters::with_order`].
 ///
 /// The structure implements [`Iterator`] and returns triples of the form `(src, dst, label)`.
+/// Duplicate pairs are returned by default; this behavior can be changed using
+/// [`KMergeIters::duplicates`].
 ///
 /// The structure implements [`Default`], [`core::iter::Sum`],
 /// [`core::ops::AddAssign`], [`Extend`], and [`core::iter::FromIterator`]
@@ -476,12 +989,24 @@ impl<T, I: Iterator<Item = (usize, usize, T)>> Ord for HeadTail<T, I> {
 /// let merged = iter.into_iter().collect::<KMergeIters<_, usize>>();
 /// ```
 #[derive(Clone, Debug)]
-pub struct KMergeIters<I: Iterator<Item = (usize, usize, T)>, T = ()> {
-    heap: dary_heap::QuaternaryHeap<HeadTail<T, I>>,
+pub struct KMergeIters<I: Iterator<Item = (usize, usize, T)>, T = (), O = Lex> {
+    heap: dary_heap::QuaternaryHeap<HeadTail<T, I, O>>,
+    /// Whether to skip triples with the same pair of the last returned one.
+    remove_duplicates: bool,
+    /// The last pair returned, if duplicates must be removed.
+    last_pair: Option<(usize, usize)>,
 }
 
 impl<T, I: Iterator<Item = (usize, usize, T)>> KMergeIters<I, T> {
     pub fn new(iters: impl IntoIterator<Item = I>) -> Self {
+        Self::with_order(iters, Lex)
+    }
+}
+
+impl<T, I: Iterator<Item = (usize, usize, T)>, O: ArcOrder<T>> KMergeIters<I, T, O> {
+    /// Creates a structure merging iterators sorted by the given
+    /// [order](ArcOrder).
+    pub fn with_order(iters: impl IntoIterator<Item = I>, order: O) -> Self {
         let iters = iters.into_iter();
         let mut heap = dary_heap::QuaternaryHeap::with_capacity(iters.size_hint().1.unwrap_or(10));
         for mut iter in iters {
@@ -489,10 +1014,28 @@ impl<T, I: Iterator<Item = (usize, usize, T)>> KMergeIters<I, T> {
                 heap.push(HeadTail {
                     head: (src, dst, label),
                     tail: iter,
+                    order: order.clone(),
                 });
             }
         }
-        KMergeIters { heap }
+        KMergeIters::from_heap(heap)
+    }
+
+    fn from_heap(heap: dary_heap::QuaternaryHeap<HeadTail<T, I, O>>) -> Self {
+        KMergeIters {
+            heap,
+            remove_duplicates: false,
+            last_pair: None,
+        }
+    }
+
+    /// Sets the policy for duplicate pairs.
+    ///
+    /// Note that merging structures using `+=`, `sum()` or `collect()` does
+    /// not propagate the policy: it must be set on the result.
+    pub fn duplicates(mut self, duplicates: DuplicateArcs) -> Self {
+        self.remove_duplicates = duplicates == DuplicateArcs::Remove;
+        self
     }
 }
 
@@ -502,26 +1045,36 @@ unsafe impl<T, I: Iterator<Item = (usize, usize, T)> + SortedIterator> SortedIte
 }
 
 #[allow(clippy::uninit_assumed_init)]
-impl<T, I: Iterator<Item = (usize, usize, T)>> Iterator for KMergeIters<I, T> {
+impl<T, I: Iterator<Item = (usize, usize, T)>, O: ArcOrder<T>> Iterator for KMergeIters<I, T, O> {
     type Item = (usize, usize, T);
 
     fn next(&mut self) -> Option<Self::Item> {
-        let mut head_tail = self.heap.peek_mut()?;
+        loop {
+            let next = {
+                let mut head_tail = self.heap.peek_mut()?;
+                match head_tail.tail.next() {
+                    None => PeekMut::pop(head_tail).head,
+                    Some((src, dst, label)) => {
+                        std::mem::replace(&mut head_tail.head, (src, dst, label))
+                    }
+                }
+            };
 
-        match head_tail.tail.next() {
-            None => Some(PeekMut::pop(head_tail).head),
-            Some((src, dst, label)) => {
-                Some(std::mem::replace(&mut head_tail.head, (src, dst, label)))
+            if self.remove_duplicates {
+                let pair = Some((next.0, next.1));
+                if self.last_pair == pair {
+                    continue;
+                }
+                self.last_pair = pair;
             }
+            return Some(next);
         }
     }
 }
 
 impl<T, I: Iterator<Item = (usize, usize, T)>> core::default::Default for KMergeIters<I, T> {
     fn default() -> Self {
-        KMergeIters {
-            heap: dary_heap::QuaternaryHeap::default(),
-        }
+        KMergeIters::from_heap(dary_heap::QuaternaryHeap::default())
     }
 }
 
@@ -531,7 +1084,7 @@ impl<T, I: Iterator<Item = (usize, usize, T)>> core::iter::Sum for KMergeIters<I
         for mut kmerge in iter {
             heap.extend(kmerge.heap.drain());
         }
-        KMergeIters { heap }
+        KMergeIters::from_heap(heap)
     }
 }
 
@@ -568,6 +1121,7 @@ impl<T, I: IntoIterator<Item = (usize, usize, T)>> core::ops::AddAssign<I>
             self.heap.push(HeadTail {
                 head: (src, dst, label),
                 tail: rhs,
+                order: Lex,
             });
         }
     }
@@ -587,6 +1141,7 @@ impl<T, I: IntoIterator<Item = (usize, usize, T)>> Extend<I> for KMergeIters<I::
             Some(HeadTail {
                 head: (src, dst, label),
                 tail: iter,
+                order: Lex,
             })
         }));
     }
@@ -630,28 +1185,144 @@ mod tests {
                 bitstream.write_delta(*value as u64)
             }
         }
-        let dir = Builder::new().prefix("test_sort_pairs_").tempdir()?;
-        let mut sp = SortPairs::new_labeled(10, dir.path(), MyDessert, MyDessert)?;
-        let n = 25;
-        for i in 0..n {
-            sp.push_labeled(i, i + 1, i + 2)?;
+        for compression in [None, Some(3)] {
+            let dir = Builder::new().prefix("test_sort_pairs_").tempdir()?;
+            let mut sp = SortPairs::new_labeled(10, dir.path(), MyDessert, MyDessert)?
+                .compression(compression);
+            let n = 25;
+            for i in 0..n {
+                sp.push_labeled(i, i + 1, i + 2)?;
+            }
+            let mut iter = sp.iter()?;
+            let mut cloned = iter.clone();
+
+            for _ in 0..n {
+                let (x, y, p) = iter.next().unwrap();
+                println!("{} {} {}", x, y, p);
+                assert_eq!(x + 1, y);
+                assert_eq!(x + 2, p);
+            }
+
+            for _ in 0..n {
+                let (x, y, p) = cloned.next().unwrap();
+                println!("{} {} {}", x, y, p);
+                assert_eq!(x + 1, y);
+                assert_eq!(x + 2, p);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_compression() -> anyhow::Result<()> {
+        let pairs = (0..100_000_usize)
+            .map(|i| ((i * 7919) % 1000, (i * 104729) % 100_000))
+            .collect::<Vec<_>>();
+        let mut expected = pairs.clone();
+        expected.sort();
+
+        let mut sizes = vec![];
+        for compression in [None, Some(1), Some(19)] {
+            let dir = tempfile::tempdir()?;
+            let mut sp = SortPairs::new(30_000, dir.path())?.compression(compression);
+            for &(x, y) in &pairs {
+                sp.push(x, y)?;
+            }
+            let mut iter = sp.iter()?;
+            // Clone in the middle of the merge
+            assert!(iter.nth(49_999).is_some());
+            let cloned = iter.clone();
+            assert_eq!(iter.next().map(|(x, y, _)| (x, y)), Some(expected[50_000]));
+            assert!(cloned
+                .map(|(x, y, _)| (x, y))
+                .eq(expected[50_000..].iter().copied()));
+
+            let mut size = 0;
+            for entry in std::fs::read_dir(dir.path())? {
+                size += entry?.metadata()?.len();
+            }
+            sizes.push(size);
+        }
+        assert!(sizes[1] < sizes[0], "{:?}", sizes);
+        assert!(sizes[2] <= sizes[1], "{:?}", sizes);
+
+        // Files without a valid header are rejected
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("batch");
+        std::fs::write(&path, [0_u8; 16])?;
+        assert!(BatchIterator::new_labeled(&path, 1, ()).is_err());
+        Ok(())
+    }
+
+    #[derive(Clone, Debug)]
+    struct Gamma;
+
+    impl BitSerializer<NE, BitWriter> for Gamma {
+        type SerType = usize;
+        fn serialize(
+            &self,
+            value: &Self::SerType,
+            bitstream: &mut BitWriter,
+        ) -> Result<usize, <BitWriter as BitWrite<NE>>::Error> {
+            bitstream.write_gamma(*value as u64)
+        }
+    }
+
+    impl BitDeserializer<NE, BitReader> for Gamma {
+        type DeserType = usize;
+        fn deserialize(
+            &self,
+            bitstream: &mut BitReader,
+        ) -> Result<Self::DeserType, <BitReader as BitRead<NE>>::Error> {
+            bitstream.read_gamma().map(|x| x as usize)
         }
-        let mut iter = sp.iter()?;
-        let mut cloned = iter.clone();
+    }
+
+    #[test]
+    fn test_order() -> anyhow::Result<()> {
+        let triples = (0..1000_usize)
+            .map(|i| ((i * 7919) % 50, (i * 104729) % 1000, (i * 31) % 13))
+            .collect::<Vec<_>>();
 
-        for _ in 0..n {
-            let (x, y, p) = iter.next().unwrap();
-            println!("{} {} {}", x, y, p);
-            assert_eq!(x + 1, y);
-            assert_eq!(x + 2, p);
+        // The default order writes the same batches as an explicit Lex order
+        let dir = tempfile::tempdir()?;
+        let mut sp = SortPairs::new_labeled(100, dir.path(), Gamma, Gamma)?;
+        let lex_dir = tempfile::tempdir()?;
+        let mut lex_sp = SortPairs::new_labeled(100, lex_dir.path(), Gamma, Gamma)?.order(Lex);
+        for &(x, y, l) in &triples {
+            sp.push_labeled(x, y, l)?;
+            lex_sp.push_labeled(x, y, l)?;
+        }
+        let mut expected = triples.clone();
+        expected.sort_by_key(|&(x, y, _)| (x, y));
+        assert!(sp
+            .iter()?
+            .map(|(x, y, _)| (x, y))
+            .eq(expected.iter().map(|&(x, y, _)| (x, y))));
+        lex_sp.iter()?;
+        for i in 0..10 {
+            let name = format!("{:06x}", i);
+            let batch = std::fs::read(dir.path().join(&name))?;
+            assert_eq!(&batch[..8], b"WGSP\x01\x00\x00\x00");
+            assert_eq!(batch, std::fs::read(lex_dir.path().join(&name))?);
         }
 
-        for _ in 0..n {
-            let (x, y, p) = cloned.next().unwrap();
-            println!("{} {} {}", x, y, p);
-            assert_eq!(x + 1, y);
-            assert_eq!(x + 2, p);
+        // By decreasing label
+        let dir = tempfile::tempdir()?;
+        let mut sp = SortPairs::new_labeled(100, dir.path(), Gamma, Gamma)?.order(ByLabelDesc);
+        for &(x, y, l) in &triples {
+            sp.push_labeled(x, y, l)?;
         }
+        let mut expected = triples.clone();
+        expected.sort_by(|&(x0, y0, l0), &(x1, y1, l1)| {
+            x0.cmp(&x1).then(l1.cmp(&l0)).then(y0.cmp(&y1))
+        });
+        let iter = sp.iter()?;
+        assert!(iter.clone().eq(expected.iter().copied()));
+        assert!(iter.eq(expected.iter().copied()));
+
+        // Batches with a custom order cannot be read as lexicographically sorted
+        assert!(BatchIterator::new_labeled(dir.path().join("000000"), 100, Gamma).is_err());
         Ok(())
     }
 }
@@ -0,0 +1,458 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+//! Opt-in node identifiers typed by the space they belong to.
+//!
+//! Analyses often handle the same nodes under different numberings: for
+//! example, the numbering of the original graph and the one induced by the
+//! [LLP](crate::algo::llp) permutation. All algorithms of this crate use plain
+//! `usize` identifiers, so nothing prevents applying a permutation to one
+//! array but not to another, silently producing garbage.
+//!
+//! This module provides a zero-cost layer that can be used at the boundaries
+//! of a pipeline to let the compiler catch such mistakes:
+//!
+//! - a [`NodeId<S>`](NodeId) is a node identifier in the space `S`, which is
+//!   just a marker type (usually an empty `enum`);
+//! - a [`Permutation<From, To>`](Permutation) maps identifiers of space `From`
+//!   to identifiers of space `To`;
+//! - a [`NodeVec<S, V>`](NodeVec) is a vector of values indexed by
+//!   identifiers of space `S`;
+//! - a [`TypedGraph<G, S>`](TypedGraph) lifts a [`RandomAccessGraph`] to
+//!   identifiers of space `S`.
+//!
+//! Conversions from and to `usize` are explicit, so that the untyped
+//! algorithms can still be used on the wrapped data.
+//!
+//! # Examples
+//!
+//! ```
+//! # use webgraph::prelude::*;
+//! # use webgraph::graphs::vec_graph::VecGraph;
+//! use webgraph::utils::typed_ids::*;
+//!
+//! // The spaces
+//! enum Original {}
+//! enum Llp {}
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let graph = TypedGraph::<_, Original>::new(Left(VecGraph::from_arc_list([
+//!     (0, 1),
+//!     (1, 2),
+//!     (2, 0),
+//!     (2, 3),
+//! ])));
+//! // A permutation computed, e.g., by LLP
+//! let llp = Permutation::<Original, Llp, _>::new(vec![2, 0, 3, 1]);
+//! let llp_graph = llp.permute_graph(&graph)?;
+//!
+//! // Some values computed on the original graph
+//! let scores = NodeVec::<Original, _>::new(vec![0.1, 0.2, 0.3, 0.4]);
+//! // must be permuted before being used with the permuted graph
+//! let llp_scores = llp.permute(&scores);
+//!
+//! let node = NodeId::<Original>::new(2);
+//! let llp_node = llp.apply(node);
+//! assert_eq!(llp_node.get(), 3);
+//! assert_eq!(llp_scores[llp_node], scores[node]);
+//! // The successors of the materialized permuted graph are sorted
+//! let mut succ = graph.successors(node).map(|succ| llp.apply(succ)).collect::<Vec<_>>();
+//! succ.sort();
+//! assert_eq!(llp_graph.successors(llp_node).collect::<Vec<_>>(), succ);
+//!
+//! // Going back to the original space
+//! let inv = llp.inverse();
+//! assert_eq!(inv.apply(llp_node), node);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Mixing up spaces does not compile: a permutation cannot be applied to an
+//! identifier of the wrong space,
+//!
+//! ```compile_fail
+//! use webgraph::utils::typed_ids::*;
+//! enum Original {}
+//! enum Llp {}
+//!
+//! let llp = Permutation::<Original, Llp, _>::new(vec![1, 0]);
+//! let llp_node = llp.apply(NodeId::new(0));
+//! llp.apply(llp_node); // llp_node is already in the Llp space
+//! ```
+//!
+//! values cannot be indexed by identifiers of another space,
+//!
+//! ```compile_fail
+//! use webgraph::utils::typed_ids::*;
+//! enum Original {}
+//! enum Llp {}
+//!
+//! let scores = NodeVec::<Original, _>::new(vec![0.5, 0.5]);
+//! let node = NodeId::<Llp>::new(0);
+//! let _ = scores[node];
+//! ```
+//!
+//! and the successors of a node must be asked to the graph of its space.
+//!
+//! ```compile_fail
+//! # use webgraph::prelude::*;
+//! # use webgraph::graphs::vec_graph::VecGraph;
+//! use webgraph::utils::typed_ids::*;
+//! enum Original {}
+//! enum Llp {}
+//!
+//! let graph = TypedGraph::<_, Original>::new(Left(VecGraph::from_arc_list([(0, 1)])));
+//! let node = NodeId::<Llp>::new(0);
+//! let _ = graph.successors(node);
+//! ```
+
+use crate::graphs::csr_graph::CsrGraph;
+use crate::prelude::*;
+use anyhow::Result;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+use core::ops::{Index, IndexMut};
+use sux::traits::*;
+
+/// A node identifier in the space `S`.
+///
+/// The space is a marker type, and it is never instantiated: an empty `enum`
+/// is the natural choice. The identifier has the same representation as a
+/// `usize`.
+#[repr(transparent)]
+pub struct NodeId<S> {
+    id: usize,
+    space: PhantomData<fn() -> S>,
+}
+
+impl<S> NodeId<S> {
+    /// Creates an identifier of the space `S` from a plain node identifier.
+    #[inline(always)]
+    pub const fn new(id: usize) -> Self {
+        Self {
+            id,
+            space: PhantomData,
+        }
+    }
+
+    /// Returns the plain node identifier.
+    #[inline(always)]
+    pub const fn get(self) -> usize {
+        self.id
+    }
+}
+
+// Manual implementations, as derive would require bounds on S
+
+impl<S> Clone for NodeId<S> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<S> Copy for NodeId<S> {}
+
+impl<S> PartialEq for NodeId<S> {
+    #[inline(always)]
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<S> Eq for NodeId<S> {}
+
+impl<S> PartialOrd for NodeId<S> {
+    #[inline(always)]
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S> Ord for NodeId<S> {
+    #[inline(always)]
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.id.cmp(&other.id)
+    }
+}
+
+impl<S> Hash for NodeId<S> {
+    #[inline(always)]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state)
+    }
+}
+
+impl<S> fmt::Debug for NodeId<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "NodeId<{}>({})", core::any::type_name::<S>(), self.id)
+    }
+}
+
+impl<S> fmt::Display for NodeId<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.id.fmt(f)
+    }
+}
+
+impl<S> From<NodeId<S>> for usize {
+    #[inline(always)]
+    fn from(node: NodeId<S>) -> usize {
+        node.id
+    }
+}
+
+/// A permutation mapping the identifiers of the space `From` to the
+/// identifiers of the space `To`.
+///
+/// The underlying permutation can be any [`BitFieldSlice`], such as a
+/// `Vec<usize>` or a [`JavaPermutation`]; it is not checked to be a
+/// permutation.
+pub struct Permutation<From, To, P = Vec<usize>> {
+    perm: P,
+    spaces: PhantomData<fn(From) -> To>,
+}
+
+impl<From, To, P: BitFieldSlice<usize>> Permutation<From, To, P> {
+    /// Wraps a permutation mapping the identifiers of the space `From` to the
+    /// identifiers of the space `To`.
+    pub fn new(perm: P) -> Self {
+        Self {
+            perm,
+            spaces: PhantomData,
+        }
+    }
+
+    /// Returns the number of elements of the permutation.
+    pub fn len(&self) -> usize {
+        self.perm.len()
+    }
+
+    /// Returns true if the permutation is empty.
+    pub fn is_empty(&self) -> bool {
+        self.perm.len() == 0
+    }
+
+    /// Maps an identifier of the space `From` to the space `To`.
+    #[inline(always)]
+    pub fn apply(&self, node: NodeId<From>) -> NodeId<To> {
+        NodeId::new(self.perm.get(node.get()))
+    }
+
+    /// Returns the inverse permutation.
+    pub fn inverse(&self) -> Permutation<To, From> {
+        let mut inv = vec![0; self.len()];
+        for node in 0..self.len() {
+            inv[self.perm.get(node)] = node;
+        }
+        Permutation::new(inv)
+    }
+
+    /// Returns the composition of this permutation with another one, that
+    /// is, the permutation mapping a node `x` to `other.apply(self.apply(x))`.
+    pub fn then<Next, Q: BitFieldSlice<usize>>(
+        &self,
+        other: &Permutation<To, Next, Q>,
+    ) -> Permutation<From, Next> {
+        Permutation::new(
+            (0..self.len())
+                .map(|node| other.perm.get(self.perm.get(node)))
+                .collect(),
+        )
+    }
+
+    /// Returns the values of the space `To` corresponding to the given values
+    /// of the space `From`.
+    pub fn permute<V: Clone>(&self, values: &NodeVec<From, V>) -> NodeVec<To, V> {
+        let mut permuted = values.values.clone();
+        for (node, value) in values.values.iter().enumerate() {
+            permuted[self.perm.get(node)] = value.clone();
+        }
+        NodeVec::new(permuted)
+    }
+
+    /// Returns the graph of the space `To` obtained by permuting a graph of
+    /// the space `From`, materialized as a [`CsrGraph`].
+    ///
+    /// See [`PermutedGraph::materialize`].
+    pub fn permute_graph<G: RandomAccessGraph>(
+        &self,
+        graph: &TypedGraph<G, From>,
+    ) -> Result<TypedGraph<CsrGraph, To>> {
+        let permuted = PermutedGraph {
+            graph: &graph.graph,
+            perm: &self.perm,
+        };
+        Ok(TypedGraph::new(permuted.materialize()?))
+    }
+
+    /// Returns a reference to the underlying permutation.
+    pub fn as_inner(&self) -> &P {
+        &self.perm
+    }
+
+    /// Returns the underlying permutation.
+    pub fn into_inner(self) -> P {
+        self.perm
+    }
+}
+
+/// A vector of values indexed by the identifiers of the space `S`.
+pub struct NodeVec<S, V> {
+    values: Vec<V>,
+    space: PhantomData<fn() -> S>,
+}
+
+impl<S, V> NodeVec<S, V> {
+    /// Wraps a vector of values indexed by the identifiers of the space `S`.
+    pub fn new(values: Vec<V>) -> Self {
+        Self {
+            values,
+            space: PhantomData,
+        }
+    }
+
+    /// Returns the number of values.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns true if there are no values.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns an iterator on the identifiers and the values.
+    pub fn iter(&self) -> impl Iterator<Item = (NodeId<S>, &V)> {
+        self.values
+            .iter()
+            .enumerate()
+            .map(|(node, value)| (NodeId::new(node), value))
+    }
+
+    /// Returns the underlying values.
+    pub fn into_inner(self) -> Vec<V> {
+        self.values
+    }
+}
+
+impl<S, V: Clone> Clone for NodeVec<S, V> {
+    fn clone(&self) -> Self {
+        Self::new(self.values.clone())
+    }
+}
+
+impl<S, V: fmt::Debug> fmt::Debug for NodeVec<S, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NodeVec")
+            .field("space", &core::any::type_name::<S>())
+            .field("values", &self.values)
+            .finish()
+    }
+}
+
+impl<S, V: PartialEq> PartialEq for NodeVec<S, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.values == other.values
+    }
+}
+
+impl<S, V> Index<NodeId<S>> for NodeVec<S, V> {
+    type Output = V;
+
+    #[inline(always)]
+    fn index(&self, node: NodeId<S>) -> &V {
+        &self.values[node.get()]
+    }
+}
+
+impl<S, V> IndexMut<NodeId<S>> for NodeVec<S, V> {
+    #[inline(always)]
+    fn index_mut(&mut self, node: NodeId<S>) -> &mut V {
+        &mut self.values[node.get()]
+    }
+}
+
+/// A [`RandomAccessGraph`] whose nodes are identifiers of the space `S`.
+pub struct TypedGraph<G, S> {
+    graph: G,
+    space: PhantomData<fn() -> S>,
+}
+
+impl<G: RandomAccessGraph, S> TypedGraph<G, S> {
+    /// Wraps a graph whose nodes are identifiers of the space `S`.
+    pub fn new(graph: G) -> Self {
+        Self {
+            graph,
+            space: PhantomData,
+        }
+    }
+
+    /// Returns the number of nodes of the graph.
+    pub fn num_nodes(&self) -> usize {
+        self.graph.num_nodes()
+    }
+
+    /// Returns the number of arcs of the graph.
+    pub fn num_arcs(&self) -> u64 {
+        self.graph.num_arcs()
+    }
+
+    /// Returns an iterator on the nodes of the graph.
+    pub fn nodes(&self) -> impl Iterator<Item = NodeId<S>> {
+        (0..self.num_nodes()).map(NodeId::new)
+    }
+
+    /// Returns the successors of a node.
+    pub fn successors(&self, node: NodeId<S>) -> impl Iterator<Item = NodeId<S>> + '_ {
+        self.graph
+            .successors(node.get())
+            .into_iter()
+            .map(NodeId::new)
+    }
+
+    /// Returns the outdegree of a node.
+    pub fn outdegree(&self, node: NodeId<S>) -> usize {
+        self.graph.outdegree(node.get())
+    }
+
+    /// Returns a reference to the underlying graph.
+    pub fn as_inner(&self) -> &G {
+        &self.graph
+    }
+
+    /// Returns the underlying graph.
+    pub fn into_inner(self) -> G {
+        self.graph
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_typed_ids() {
+    enum A {}
+    enum B {}
+    enum C {}
+
+    let a_to_b = Permutation::<A, B, _>::new(vec![3, 0, 1, 2]);
+    let b_to_c = Permutation::<B, C, _>::new(vec![1, 2, 3, 0]);
+    let a_to_c = a_to_b.then(&b_to_c);
+    let b_to_a = a_to_b.inverse();
+    for node in 0..4 {
+        let node = NodeId::<A>::new(node);
+        assert_eq!(b_to_a.apply(a_to_b.apply(node)), node);
+        assert_eq!(a_to_c.apply(node), b_to_c.apply(a_to_b.apply(node)));
+    }
+    assert_eq!(a_to_c.into_inner(), vec![0, 1, 2, 3]);
+
+    let values = NodeVec::<A, _>::new(vec!["a", "b", "c", "d"]);
+    let permuted = a_to_b.permute(&values);
+    for (node, value) in values.iter() {
+        assert_eq!(&permuted[a_to_b.apply(node)], value);
+    }
+    assert_eq!(b_to_a.permute(&permuted), values);
+}
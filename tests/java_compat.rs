@@ -0,0 +1,190 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+//! Compatibility tests against graphs compressed by the Java implementation.
+//!
+//! The fixtures are the graphs in `tests/data` whose `.properties` file
+//! carries the timestamp written by Java's `BVGraph.store`:
+//!
+//! - `cnr-2000`, compressed with the default parameters (window size 7,
+//!   maximum reference count 3, minimum interval length 4);
+//! - `cnr-2000-hc`, the same graph compressed for high compression (window
+//!   size 16, unbounded reference count).
+//!
+//! To regenerate them (or add new ones) use the Java implementation of
+//! WebGraph, e.g.,
+//!
+//! ```text
+//! java it.unimi.dsi.webgraph.BVGraph -o -O -L ORIGINAL cnr-2000
+//! java it.unimi.dsi.webgraph.BVGraph -o -O -L -w 16 -m 2147483647 ORIGINAL cnr-2000-hc
+//! ```
+//!
+//! where `-o` writes the `.offsets` file and `-O -L` the remaining accessory
+//! files; the `.ef` files are then built with `webgraph build ef`. Fixtures
+//! must be committed with their `.properties`, `.graph` and `.offsets` files,
+//! and listed in [`FIXTURES`].
+
+use anyhow::Result;
+use dsi_bitstream::prelude::*;
+use lender::*;
+use std::collections::HashMap;
+use std::io::BufReader;
+use std::path::Path;
+use webgraph::prelude::*;
+
+/// The basenames of the graphs compressed by the Java implementation.
+const FIXTURES: [&str; 2] = ["tests/data/cnr-2000", "tests/data/cnr-2000-hc"];
+
+/// The keys that Java's `BVGraph` needs to load a graph.
+const JAVA_KEYS: [&str; 9] = [
+    "graphclass",
+    "version",
+    "nodes",
+    "arcs",
+    "windowsize",
+    "maxrefcount",
+    "minintervallength",
+    "zetak",
+    "compressionflags",
+];
+
+fn properties(basename: impl AsRef<Path>) -> Result<HashMap<String, String>> {
+    let path = basename.as_ref().with_extension(PROPERTIES_EXTENSION);
+    Ok(java_properties::read(BufReader::new(std::fs::File::open(
+        path,
+    )?))?)
+}
+
+/// Reads a Java `.offsets` file (γ-coded differences between the bit offsets
+/// of consecutive nodes, starting from zero).
+fn read_offsets(basename: impl AsRef<Path>, num_nodes: usize) -> Result<Vec<u64>> {
+    let data = std::fs::read(basename.as_ref().with_extension(OFFSETS_EXTENSION))?;
+    let mut reader = BufBitReader::<BE, _>::new(MemWordReader::new(&data));
+    let mut offset = 0;
+    let mut offsets = Vec::with_capacity(num_nodes + 1);
+    for _ in 0..num_nodes + 1 {
+        offset += reader.read_gamma().unwrap();
+        offsets.push(offset);
+    }
+    Ok(offsets)
+}
+
+/// Checks the invariants relating a graph to its `.properties` and
+/// `.offsets` files: the number of nodes and arcs, and the bit offset and
+/// outdegree of each node.
+fn check_invariants(basename: impl AsRef<Path>) -> Result<()> {
+    let basename = basename.as_ref();
+    let map = properties(basename)?;
+    let graph = BvGraphSeq::with_basename(basename)
+        .endianness::<BE>()
+        .load()?;
+    let num_nodes = map["nodes"].parse::<usize>()?;
+    assert_eq!(graph.num_nodes(), num_nodes);
+
+    let offsets = read_offsets(basename, num_nodes)?;
+    let mut num_arcs = 0;
+    let mut iter = graph.offset_deg_iter();
+    let mut degrees = vec![];
+    for &offset in &offsets[..num_nodes] {
+        let (pos, degree) = iter.next().unwrap();
+        assert_eq!(pos, offset);
+        degrees.push(degree);
+        num_arcs += degree as u64;
+    }
+    assert_eq!(num_arcs, map["arcs"].parse::<u64>()?);
+    // The last offset is the length of the bitstream
+    let len = std::fs::metadata(basename.with_extension(GRAPH_EXTENSION))?.len();
+    assert!(offsets[num_nodes] <= len * 8);
+
+    let mut node = 0;
+    for_!((_, succ) in graph.iter() {
+        let succ = succ.into_iter().collect::<Vec<_>>();
+        assert_eq!(succ.len(), degrees[node]);
+        assert!(succ.windows(2).all(|w| w[0] < w[1]));
+        node += 1;
+    });
+    assert_eq!(node, num_nodes);
+    Ok(())
+}
+
+#[test]
+fn test_decode_java_fixtures() -> Result<()> {
+    for basename in FIXTURES {
+        check_invariants(basename)?;
+        let map = properties(basename)?;
+        for key in JAVA_KEYS {
+            assert!(map.contains_key(key), "{}: missing key {}", basename, key);
+        }
+    }
+    // All fixtures describe the same graph
+    let graph = BvGraphSeq::with_basename(FIXTURES[0]).load()?;
+    for basename in &FIXTURES[1..] {
+        let other = BvGraphSeq::with_basename(basename).load()?;
+        webgraph::assert_graph_eq!(graph, other);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_recompress_java_fixtures() -> Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    for basename in FIXTURES {
+        let map = properties(basename)?;
+        let comp_flags = CompFlags::from_properties::<BE>(&map)?;
+        let graph = BvGraphSeq::with_basename(basename).load()?;
+        let new_basename = tmp_dir
+            .path()
+            .join(Path::new(basename).file_name().unwrap());
+        BvComp::single_thread::<BE, _>(&new_basename, &graph, comp_flags, true, None)?;
+
+        // Decoding with our big-endian reader and checking against our
+        // .offsets file approximates decoding with Java
+        check_invariants(&new_basename)?;
+        let new_graph = BvGraphSeq::with_basename(&new_basename)
+            .endianness::<BE>()
+            .load()?;
+        webgraph::assert_graph_eq!(graph, new_graph);
+
+        // Same parameters, same keys Java needs, and same values for them
+        let new_map = properties(&new_basename)?;
+        for key in JAVA_KEYS {
+            assert!(
+                new_map.contains_key(key),
+                "{}: missing key {}",
+                new_basename.display(),
+                key
+            );
+        }
+        for key in [
+            "graphclass",
+            "version",
+            "nodes",
+            "arcs",
+            "windowsize",
+            "maxrefcount",
+            "minintervallength",
+            "zetak",
+        ] {
+            assert_eq!(new_map[key], map[key], "{}: key {}", basename, key);
+        }
+        assert_eq!(
+            CompFlags::from_properties::<BE>(&new_map)?.compression_window,
+            comp_flags.compression_window
+        );
+
+        // The bitstream is the one written by Java, except for the padding
+        // to a whole number of words
+        let new_data = std::fs::read(new_basename.with_extension(GRAPH_EXTENSION))?;
+        let java_data = std::fs::read(format!("{}.{}", basename, GRAPH_EXTENSION))?;
+        assert!(
+            new_data.starts_with(&java_data),
+            "{}: the bitstream differs from Java's",
+            basename
+        );
+        assert!(new_data[java_data.len()..].iter().all(|&byte| byte == 0));
+    }
+    Ok(())
+}
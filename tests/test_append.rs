@@ -0,0 +1,138 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+#![cfg(feature = "cli")]
+
+use anyhow::Result;
+use dsi_bitstream::prelude::*;
+use lender::*;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use webgraph::assert_graph_eq;
+use webgraph::cli::build::ef_from_offsets::ef_from_offsets;
+use webgraph::graphs::random::ErdosRenyi;
+use webgraph::graphs::vec_graph::VecGraph;
+use webgraph::prelude::*;
+
+const NUM_OLD_NODES: usize = 100;
+const MID_NODES: usize = 150;
+const NUM_NODES: usize = 200;
+
+/// Reads the first `n` gamma-coded values of a `.offsets` file.
+fn read_offsets(basename: &Path, n: usize) -> Result<Vec<u64>> {
+    let file = File::open(basename.with_extension(OFFSETS_EXTENSION))?;
+    let mut reader = BufBitReader::<BE, _>::new(<WordAdapter<u32, _>>::new(BufReader::new(file)));
+    (0..n).map(|_| Ok(reader.read_gamma()?)).collect()
+}
+
+#[test]
+fn test_append() -> Result<()> {
+    let compression_flags = CompFlags {
+        compression_window: 7,
+        max_ref_count: 3,
+        ..CompFlags::default()
+    };
+
+    // Nodes can point only to nodes of the graph they are appended to, as
+    // each append must leave a valid graph
+    let mut graph = VecGraph::empty(NUM_NODES);
+    let mut old_graph = VecGraph::empty(NUM_OLD_NODES);
+    let random = ErdosRenyi::new(NUM_NODES, 0.05, 0);
+    let mut iter = random.iter();
+    while let Some((src, succ)) = iter.next() {
+        for dst in succ {
+            if src < NUM_OLD_NODES {
+                if dst < NUM_OLD_NODES {
+                    graph.add_arc(src, dst);
+                    old_graph.add_arc(src, dst);
+                }
+            } else if src >= MID_NODES || dst < MID_NODES {
+                graph.add_arc(src, dst);
+            }
+        }
+    }
+
+    let graph = Left(graph);
+    let old_graph = Left(old_graph);
+
+    let tmp_dir = tempfile::tempdir()?;
+    let expected = tmp_dir.path().join("expected");
+    BvComp::single_thread::<BE, _>(&expected, &graph, compression_flags, true, None)?;
+    ef_from_offsets(&expected)?;
+
+    let basename = tmp_dir.path().join("appended");
+    BvComp::single_thread::<BE, _>(&basename, &old_graph, compression_flags, true, None)?;
+    ef_from_offsets(&basename)?;
+
+    // Append the new nodes in two batches
+    let mut append = BvCompAppend::<BE>::open(&basename)?;
+    assert_eq!(append.num_nodes(), NUM_OLD_NODES);
+    append.extend_arcs(
+        (NUM_OLD_NODES..MID_NODES)
+            .flat_map(|x| graph.successors(x).into_iter().map(move |y| (x, y))),
+        MID_NODES,
+    )?;
+    append.close()?;
+
+    let mut append = BvCompAppend::<BE>::open(&basename)?;
+    assert_eq!(append.num_nodes(), MID_NODES);
+    for x in MID_NODES..NUM_NODES {
+        append.push(graph.successors(x))?;
+    }
+    append.close()?;
+
+    let expected_graph = BvGraph::with_basename(&expected)
+        .endianness::<BE>()
+        .load()?;
+    let appended_graph = BvGraph::with_basename(&basename)
+        .endianness::<BE>()
+        .load()?;
+    assert_eq!(appended_graph.num_nodes(), NUM_NODES);
+    assert_eq!(appended_graph.num_arcs(), expected_graph.num_arcs());
+    assert_graph_eq!(appended_graph, graph);
+
+    // The bitstreams must be identical
+    let expected_bytes = std::fs::read(expected.with_extension(GRAPH_EXTENSION))?;
+    let appended_bytes = std::fs::read(basename.with_extension(GRAPH_EXTENSION))?;
+    let offsets = read_offsets(&expected, NUM_NODES + 1)?;
+    assert_eq!(read_offsets(&basename, NUM_NODES + 1)?, offsets);
+    let bit_len = offsets.iter().sum::<u64>() as usize;
+    assert_eq!(appended_bytes[..bit_len / 8], expected_bytes[..bit_len / 8]);
+
+    Ok(())
+}
+
+#[test]
+fn test_append_errors() -> Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    let basename = tmp_dir.path().join("graph");
+    let graph = Left(VecGraph::from_arc_list([(0, 1), (1, 2), (2, 0)]));
+    BvComp::single_thread::<BE, _>(&basename, &graph, CompFlags::default(), true, None)?;
+    ef_from_offsets(&basename)?;
+
+    // Arcs from nodes of the original graph are rejected
+    let mut append = BvCompAppend::<BE>::open(&basename)?;
+    assert!(append.extend_arcs([(1, 3)], 4).is_err());
+    drop(append);
+
+    // Targets must be nodes of the resulting graph
+    let mut append = BvCompAppend::<BE>::open(&basename)?;
+    append.push([0, 5])?;
+    assert!(append.close().is_err());
+
+    // Appending nothing leaves the graph unchanged
+    BvCompAppend::<BE>::open(&basename)?.close()?;
+
+    // The original graph is unchanged
+    let loaded = BvGraph::with_basename(&basename)
+        .endianness::<BE>()
+        .load()?;
+    assert_eq!(loaded.num_nodes(), 3);
+    assert_eq!(loaded.successors(2).collect::<Vec<_>>(), vec![0]);
+
+    Ok(())
+}
@@ -0,0 +1,69 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+#![cfg(feature = "cli")]
+
+use anyhow::Result;
+use std::path::PathBuf;
+use webgraph::cli::main as cli_main;
+use webgraph::graphs::bvgraph::{GRAPH_EXTENSION, OFFSETS_EXTENSION, PROPERTIES_EXTENSION};
+
+const TEST_GRAPH: &str = "tests/data/cnr-2000";
+
+#[test]
+fn test_arc_count_mismatch() -> Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    let basename = tmp_dir.path().join("cnr-2000");
+    std::fs::copy(
+        PathBuf::from(TEST_GRAPH).with_extension(GRAPH_EXTENSION),
+        basename.with_extension(GRAPH_EXTENSION),
+    )?;
+    // Tamper with the number of arcs
+    let properties =
+        std::fs::read_to_string(PathBuf::from(TEST_GRAPH).with_extension(PROPERTIES_EXTENSION))?;
+    assert!(properties.contains("\narcs=3216152\n"));
+    std::fs::write(
+        basename.with_extension(PROPERTIES_EXTENSION),
+        properties.replace("\narcs=3216152\n", "\narcs=3216153\n"),
+    )?;
+
+    let src = basename.display().to_string();
+    let dst_basename = tmp_dir.path().join("copy");
+    let dst = dst_basename.display().to_string();
+    let dst_exists = |ext| dst_basename.with_extension(ext).exists();
+    let err = cli_main(vec!["webgraph", "to", "bvgraph", &src, &dst]).unwrap_err();
+    assert!(format!("{:#}", err).contains("3216152"));
+    // The check fails after compression, and the partial graph is removed
+    for ext in [GRAPH_EXTENSION, OFFSETS_EXTENSION, PROPERTIES_EXTENSION] {
+        assert!(!dst_exists(ext), "{}", ext);
+    }
+
+    cli_main(vec![
+        "webgraph",
+        "--keep-partial",
+        "to",
+        "bvgraph",
+        &src,
+        &dst,
+    ])
+    .unwrap_err();
+    for ext in [GRAPH_EXTENSION, OFFSETS_EXTENSION, PROPERTIES_EXTENSION] {
+        assert!(dst_exists(ext), "{}", ext);
+    }
+
+    cli_main(vec![
+        "webgraph",
+        "to",
+        "bvgraph",
+        "--no-arc-count-check",
+        &src,
+        &dst,
+    ])?;
+
+    // The untampered graph passes the check
+    cli_main(vec!["webgraph", "to", "bvgraph", TEST_GRAPH, &dst])?;
+    Ok(())
+}
@@ -0,0 +1,51 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use anyhow::Result;
+use dsi_bitstream::prelude::*;
+use lender::*;
+use webgraph::graphs::bvgraph::{write_archive, Archive, ARCHIVE_EXTENSION};
+use webgraph::prelude::*;
+
+#[test]
+fn test_archive() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let dst = dir
+        .path()
+        .join("cnr-2000")
+        .with_extension(ARCHIVE_EXTENSION);
+    write_archive("tests/data/cnr-2000", &dst)?;
+
+    let archive = Archive::open(&dst)?;
+    assert!(archive.section(GRAPH_EXTENSION).is_some());
+    assert!(archive.section(EF_EXTENSION).is_some());
+
+    let graph = BvGraph::with_basename("tests/data/cnr-2000")
+        .endianness::<BE>()
+        .load()?;
+    let archived = BvGraph::with_archive(&dst)
+        .endianness::<BE>()
+        .flags(MemoryFlags::RANDOM_ACCESS)
+        .load()?;
+    assert_eq!(archived.num_nodes(), graph.num_nodes());
+    assert_eq!(archived.num_arcs(), graph.num_arcs());
+
+    let mut iter = graph.iter();
+    while let Some((node, succ)) = iter.next() {
+        let succ = succ.collect::<Vec<_>>();
+        assert_eq!(archived.successors(node).collect::<Vec<_>>(), succ);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_archive_bad_magic() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let dst = dir.path().join("bad").with_extension(ARCHIVE_EXTENSION);
+    std::fs::write(&dst, b"NOTANARCHIVE0000")?;
+    assert!(Archive::open(&dst).is_err());
+    Ok(())
+}
@@ -0,0 +1,71 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+#![cfg(feature = "async")]
+
+use anyhow::Result;
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+use std::sync::Arc;
+use webgraph::graphs::async_graph::AsyncGraph;
+use webgraph::prelude::*;
+
+const NUM_TASKS: usize = 16;
+const NUM_LOOKUPS: usize = 1000;
+
+/// Performs random lookups from many tasks, some of them on the same nodes,
+/// and checks them against the synchronous API.
+async fn hammer<G: RandomAccessGraph + Send + Sync + 'static>(
+    async_graph: AsyncGraph<G>,
+) -> Result<()> {
+    let num_nodes = async_graph.num_nodes();
+    let mut handles = vec![];
+    for task in 0..NUM_TASKS {
+        let async_graph = async_graph.clone();
+        handles.push(tokio::spawn(async move {
+            let graph = async_graph.graph().clone();
+            let mut rng = SmallRng::seed_from_u64(task as u64);
+            for _ in 0..NUM_LOOKUPS {
+                // a small set of hot nodes makes coalescing likely
+                let node = if rng.gen_bool(0.5) {
+                    rng.gen_range(0..16)
+                } else {
+                    rng.gen_range(0..num_nodes)
+                };
+                let successors = async_graph.successors(node).await;
+                assert_eq!(
+                    successors,
+                    graph.successors(node).into_iter().collect::<Vec<_>>()
+                );
+                let dst = rng.gen_range(0..num_nodes);
+                assert_eq!(
+                    async_graph.contains_arc(node, dst).await,
+                    successors.contains(&dst)
+                );
+                if let Some(&dst) = successors.first() {
+                    assert!(async_graph.contains_arc(node, dst).await);
+                }
+            }
+        }));
+    }
+    for handle in handles {
+        handle.await?;
+    }
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_async_graph_spawn_blocking() -> Result<()> {
+    let graph = Arc::new(BvGraph::with_basename("tests/data/cnr-2000").load()?);
+    hammer(AsyncGraph::new(graph)).await
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_async_graph_thread_pool() -> Result<()> {
+    let graph = Arc::new(BvGraph::with_basename("tests/data/cnr-2000").load()?);
+    let thread_pool = Arc::new(rayon::ThreadPoolBuilder::new().num_threads(2).build()?);
+    hammer(AsyncGraph::new(graph).with_thread_pool(thread_pool)).await
+}
@@ -0,0 +1,87 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+#![cfg(feature = "cli")]
+
+use anyhow::Result;
+use dsi_bitstream::prelude::*;
+use std::path::{Path, PathBuf};
+use webgraph::cli::append;
+use webgraph::cli::build::ef_from_offsets::ef_from_offsets;
+use webgraph::graphs::vec_graph::VecGraph;
+use webgraph::prelude::*;
+
+/// Compresses a small graph, builds its `.ef` file and loads it back using
+/// the given basename.
+fn round_trip(basename: &Path) -> Result<()> {
+    let graph = Left(VecGraph::from_arc_list([(0, 1), (1, 2), (2, 0), (2, 1)]));
+    BvComp::single_thread::<BE, _>(basename, &graph, CompFlags::default(), true, None)?;
+    ef_from_offsets(basename)?;
+
+    let basename = Basename::new(basename);
+    for ext in [
+        PROPERTIES_EXTENSION,
+        GRAPH_EXTENSION,
+        OFFSETS_EXTENSION,
+        EF_EXTENSION,
+    ] {
+        assert!(
+            basename.with_ext(ext).exists(),
+            "{} does not exist",
+            basename.with_ext(ext).display()
+        );
+    }
+
+    let loaded = BvGraph::with_basename(&basename)
+        .endianness::<BE>()
+        .load()?;
+    assert_eq!(loaded.num_nodes(), 3);
+    assert_eq!(loaded.successors(2).collect::<Vec<_>>(), vec![0, 1]);
+    let loaded = BvGraphSeq::with_basename(&basename)
+        .endianness::<BE>()
+        .load()?;
+    assert_eq!(loaded.num_nodes(), 3);
+    Ok(())
+}
+
+#[test]
+fn test_dotted_basenames() -> Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    // Dots in the filename are not extensions
+    round_trip(&tmp_dir.path().join("graph-2024.03.01"))?;
+    assert!(tmp_dir.path().join("graph-2024.03.01.graph").exists());
+    // Directories with extensions
+    let dir = tmp_dir.path().join("graphs.d");
+    std::fs::create_dir(&dir)?;
+    round_trip(&dir.join("web.v2"))?;
+    assert!(dir.join("web.v2.ef").exists());
+    // A basename looking like a file of a graph is not stripped
+    round_trip(&dir.join("graph.graph"))?;
+    assert!(dir.join("graph.graph.graph").exists());
+    Ok(())
+}
+
+#[test]
+fn test_relative_basenames() -> Result<()> {
+    let tmp_dir = tempfile::tempdir_in(".")?;
+    let relative = Path::new(".").join(tmp_dir.path().file_name().unwrap());
+    round_trip(&relative.join("a.b"))?;
+    // A trailing separator does not change the files
+    let mut trailing = relative.join("c.d").into_os_string();
+    trailing.push("/");
+    round_trip(Path::new(&trailing))?;
+    assert!(relative.join("c.d.properties").exists());
+    Ok(())
+}
+
+#[test]
+fn test_append() {
+    assert_eq!(
+        append("graph-2024.03.01", "-t"),
+        PathBuf::from("graph-2024.03.01-t")
+    );
+    assert_eq!(append("dir.d/graph/", ".t"), PathBuf::from("dir.d/graph.t"));
+}
@@ -0,0 +1,103 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+#![cfg(feature = "cli")]
+
+use anyhow::Result;
+use dsi_bitstream::prelude::*;
+use std::path::Path;
+use std::process::Command;
+use webgraph::graphs::random::ErdosRenyi;
+use webgraph::graphs::vec_graph::VecGraph;
+use webgraph::prelude::*;
+
+/// Returns the standard output of `bench sweep`.
+fn sweep(args: &[&str]) -> Result<String> {
+    let output = Command::new(env!("CARGO_BIN_EXE_webgraph"))
+        .args(["bench", "sweep"])
+        .args(args)
+        .output()?;
+    assert!(output.status.success(), "{:?}", output);
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+/// Returns the length in bits of the bitstream of a compressed graph.
+fn bitstream_len(basename: impl AsRef<Path>) -> Result<u64> {
+    let graph = BvGraphSeq::with_basename(basename)
+        .endianness::<BE>()
+        .load()?;
+    let mut iter = graph.offset_deg_iter();
+    iter.by_ref().for_each(drop);
+    Ok(iter.get_pos())
+}
+
+#[test]
+fn test_bench_sweep() -> Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    let basename = tmp_dir.path().join("graph");
+    let graph = Left(VecGraph::from_lender(ErdosRenyi::new(1000, 0.01, 0).iter()));
+    BvComp::single_thread::<BE, _>(&basename, &graph, CompFlags::default(), true, None)?;
+    let src = basename.to_str().unwrap();
+    let grid = ["-w", "0,7", "-i", "0,4", "--residuals", "gamma,zeta3"];
+
+    let csv = sweep(&[&[src][..], &grid, &["-f", "csv"]].concat())?;
+    let mut lines = csv.lines();
+    assert_eq!(
+        lines.next().unwrap(),
+        "compression_window,min_interval_length,max_ref_count,outdegrees,references,blocks,residuals,bits,bits_per_arc,seconds,pareto"
+    );
+    let rows = lines
+        .map(|line| line.split(',').collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    // One row for each combination of the grid
+    assert_eq!(rows.len(), 8);
+    let bits_per_arc = rows
+        .iter()
+        .map(|row| row[8].parse::<f64>().unwrap())
+        .collect::<Vec<_>>();
+    assert!(bits_per_arc.windows(2).all(|w| w[0] <= w[1]));
+    // The best configuration is never dominated
+    assert_eq!(rows[0][10], "true");
+
+    // The estimates are the sizes of the compressed graphs
+    let thread_pool = rayon::ThreadPoolBuilder::new().num_threads(1).build()?;
+    for row in &rows {
+        let comp_flags = CompFlags {
+            compression_window: row[0].parse()?,
+            min_interval_length: row[1].parse()?,
+            max_ref_count: row[2].parse()?,
+            residuals: match row[6] {
+                "gamma" => Code::Gamma,
+                "zeta3" => Code::Zeta { k: 3 },
+                code => panic!("Unexpected code {}", code),
+            },
+            ..Default::default()
+        };
+        let comp_basename = tmp_dir.path().join("comp");
+        BvComp::parallel_graph::<BE>(
+            &comp_basename,
+            &graph,
+            comp_flags,
+            &thread_pool,
+            tempfile::tempdir()?,
+        )?;
+        assert_eq!(
+            row[7].parse::<u64>()?,
+            bitstream_len(&comp_basename)?,
+            "{:?}",
+            row
+        );
+    }
+
+    let json = sweep(&[&[src][..], &grid, &["-f", "json"]].concat())?;
+    assert_eq!(
+        json.lines()
+            .filter(|line| line.starts_with("  {\"compression_window\": "))
+            .count(),
+        8
+    );
+    Ok(())
+}
@@ -3,6 +3,10 @@ use std::collections::HashSet;
 use anyhow::Result;
 
 use dsi_bitstream::prelude::BE;
+use dsi_progress_logger::prelude::*;
+use webgraph::algo::{par_bfs_layers, par_bfs_perm, IntraLayerOrder, ParBfs, SeqBfs};
+use webgraph::graphs::vec_graph::VecGraph;
+use webgraph::traits::{RandomAccessGraph, SequentialLabeling};
 use webgraph::{algo::BfsOrder, graphs::bvgraph::BvGraph, labels::proj::Left};
 
 #[test]
@@ -49,6 +53,23 @@ fn test_start_orphan() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_tiny() -> Result<()> {
+    let empty = Left(VecGraph::<()>::empty(0));
+    assert_eq!(BfsOrder::new(&empty).count(), 0);
+    assert!(par_bfs_perm(
+        &empty,
+        IntraLayerOrder::Original,
+        &rayon::ThreadPoolBuilder::new().build()?,
+        &mut ProgressLogger::default()
+    )
+    .is_empty());
+
+    let single = Left(VecGraph::from_arc_list([(0, 0)]));
+    assert_eq!(BfsOrder::new(&single).collect::<Vec<_>>(), vec![0]);
+    Ok(())
+}
+
 #[test]
 fn test_cnr2000() -> Result<()> {
     let graph = BvGraph::with_basename("tests/data/cnr-2000")
@@ -60,3 +81,247 @@ fn test_cnr2000() -> Result<()> {
     }
     Ok(())
 }
+
+#[test]
+fn test_par_bfs_layers() -> Result<()> {
+    // 0 -> 3 -> 1
+    //  |    `-> 4
+    //  `-> 2 -> 1
+    //       |-> 5 -> 4
+    //       `-> 6
+    // 8 -> 7 -> 6
+    let graph = Left(VecGraph::from_arc_list([
+        (0, 3),
+        (0, 2),
+        (3, 1),
+        (3, 4),
+        (2, 1),
+        (2, 5),
+        (2, 6),
+        (5, 4),
+        (7, 6),
+        (8, 7),
+    ]));
+    let thread_pool = rayon::ThreadPoolBuilder::new().num_threads(2).build()?;
+    let mut layers = vec![];
+    par_bfs_layers(
+        &graph,
+        IntraLayerOrder::DegreeDesc,
+        &thread_pool,
+        &mut ProgressLogger::default(),
+        |distance, layer| layers.push((distance, layer.to_vec())),
+    );
+    assert_eq!(
+        layers,
+        vec![
+            (0, vec![0]),
+            (1, vec![2, 3]),
+            (2, vec![5, 1, 4, 6]),
+            (0, vec![7]),
+            (0, vec![8]),
+        ]
+    );
+
+    let visit_order = |order| {
+        let perm = par_bfs_perm(&graph, order, &thread_pool, &mut ProgressLogger::default());
+        let mut visit_order = vec![0; perm.len()];
+        for (node, &pos) in perm.iter().enumerate() {
+            visit_order[pos] = node;
+        }
+        visit_order
+    };
+    assert_eq!(
+        visit_order(IntraLayerOrder::Original),
+        [0, 2, 3, 1, 4, 5, 6, 7, 8]
+    );
+    assert_eq!(
+        visit_order(IntraLayerOrder::DegreeDesc),
+        [0, 2, 3, 5, 1, 4, 6, 7, 8]
+    );
+    assert_eq!(
+        visit_order(IntraLayerOrder::Parent),
+        [0, 2, 3, 1, 5, 6, 4, 7, 8]
+    );
+    // With sorted successors, the parent order is the sequential order
+    assert_eq!(
+        visit_order(IntraLayerOrder::Parent),
+        BfsOrder::new(&graph).collect::<Vec<_>>()
+    );
+    Ok(())
+}
+
+#[test]
+fn test_par_bfs_perm_deterministic() -> Result<()> {
+    let graph = BvGraph::with_basename("tests/data/cnr-2000")
+        .endianness::<BE>()
+        .load()?;
+    for order in [
+        IntraLayerOrder::Original,
+        IntraLayerOrder::DegreeDesc,
+        IntraLayerOrder::Parent,
+    ] {
+        let perms = [1, 2, 4, 7]
+            .into_iter()
+            .map(|num_threads| {
+                let thread_pool = rayon::ThreadPoolBuilder::new()
+                    .num_threads(num_threads)
+                    .build()
+                    .unwrap();
+                par_bfs_perm(&graph, order, &thread_pool, &mut ProgressLogger::default())
+            })
+            .collect::<Vec<_>>();
+        for perm in &perms[1..] {
+            assert_eq!(perm, &perms[0], "{:?}", order);
+        }
+        // It is a permutation
+        let mut seen = vec![false; graph.num_nodes()];
+        for &pos in perms[0].iter() {
+            assert!(!seen[pos]);
+            seen[pos] = true;
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_par_bfs_resume() -> Result<()> {
+    let graph = BvGraph::with_basename("tests/data/cnr-2000")
+        .endianness::<BE>()
+        .load()?;
+    let thread_pool = rayon::ThreadPoolBuilder::new().num_threads(2).build()?;
+    let mut layers = vec![];
+    par_bfs_layers(
+        &graph,
+        IntraLayerOrder::Parent,
+        &thread_pool,
+        &mut ProgressLogger::default(),
+        |distance, layer| layers.push((distance, layer.to_vec())),
+    );
+
+    let tmp_dir = tempfile::tempdir()?;
+    let state_path = tmp_dir.path().join("visit.state");
+    for stop in [0, 1, 10, layers.len() / 2, layers.len()] {
+        let mut resumed_layers = vec![];
+        let mut visit = ParBfs::new(&graph, IntraLayerOrder::Parent);
+        for _ in 0..stop {
+            assert!(visit.next_layer(&thread_pool, |distance, layer| {
+                resumed_layers.push((distance, layer.to_vec()))
+            }));
+        }
+        visit.save_state(&state_path)?;
+        let num_visited = visit.num_visited();
+        drop(visit);
+
+        // Resume with a different number of threads
+        let other_thread_pool = rayon::ThreadPoolBuilder::new().num_threads(3).build()?;
+        let mut visit = ParBfs::new(&graph, IntraLayerOrder::Parent);
+        visit.load_state(&state_path)?;
+        assert_eq!(visit.num_visited(), num_visited);
+        while visit.next_layer(&other_thread_pool, |distance, layer| {
+            resumed_layers.push((distance, layer.to_vec()))
+        }) {}
+        assert_eq!(resumed_layers, layers, "stop = {}", stop);
+        assert_eq!(visit.num_visited(), graph.num_nodes());
+
+        visit.reset();
+        assert_eq!(visit.num_visited(), 0);
+    }
+
+    // The state of a different graph
+    let small_graph = Left(VecGraph::from_arc_list([(0, 1), (1, 2)]));
+    let mut visit = ParBfs::new(&small_graph, IntraLayerOrder::Original);
+    assert!(visit.load_state(&state_path).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_seq_bfs_reuse() -> Result<()> {
+    let graph = BvGraph::with_basename("tests/data/cnr-2000")
+        .endianness::<BE>()
+        .load()?;
+    let num_nodes = graph.num_nodes();
+
+    // Reference balls computed with a fresh visit each time
+    let ball = |root: usize, radius: usize| {
+        let mut dist = vec![usize::MAX; num_nodes];
+        let mut queue = std::collections::VecDeque::from([root]);
+        dist[root] = 0;
+        let mut result = vec![(root, 0)];
+        while let Some(node) = queue.pop_front() {
+            if dist[node] == radius {
+                continue;
+            }
+            for succ in graph.successors(node) {
+                if dist[succ] == usize::MAX {
+                    dist[succ] = dist[node] + 1;
+                    result.push((succ, dist[succ]));
+                    queue.push_back(succ);
+                }
+            }
+        }
+        result.sort_unstable();
+        result
+    };
+
+    let mut bfs = SeqBfs::new(&graph);
+    for i in 0..500 {
+        // Interleave roots far apart and radii
+        let root = (i * 7919) % num_nodes;
+        let radius = i % 4;
+        bfs.reset();
+        let mut visited = vec![];
+        bfs.visit(root, radius, |node, distance| {
+            visited.push((node, distance))
+        });
+        visited.sort_unstable();
+        assert_eq!(
+            visited,
+            ball(root, radius),
+            "root {} radius {}",
+            root,
+            radius
+        );
+        assert!(visited.iter().all(|&(node, _)| bfs.is_visited(node)));
+    }
+
+    // Without a reset, a visit from a visited root does nothing
+    bfs.reset();
+    let mut count = 0;
+    bfs.visit(0, 2, |_, _| count += 1);
+    assert!(count > 0);
+    bfs.visit(0, 2, |_, _| count = 0);
+    assert!(count > 0);
+    Ok(())
+}
+
+#[test]
+fn test_seq_bfs_components() -> Result<()> {
+    // Three components of a symmetric graph: {0, 2, 4}, {1, 5} and {3}
+    let mut graph = VecGraph::from_arc_list([(0, 2), (2, 0), (2, 4), (4, 2), (1, 5), (5, 1)]);
+    graph.add_node(3);
+    let graph = Left(graph);
+
+    let mut bfs = SeqBfs::new(&graph);
+    let mut nodes = vec![];
+    let mut components = vec![];
+    bfs.visit_components(
+        0..graph.num_nodes(),
+        |node, _| nodes.push(node),
+        |root, size| components.push((root, size)),
+    );
+    assert_eq!(components, vec![(0, 3), (1, 2), (3, 1)]);
+    assert_eq!(nodes, vec![0, 2, 4, 1, 5, 3]);
+
+    // Roots already visited are skipped, and on a directed graph regions
+    // do not include nodes discovered by previous visits
+    let graph = Left(VecGraph::from_arc_list([(0, 1), (2, 0), (2, 3)]));
+    let mut bfs = SeqBfs::new(&graph);
+    let mut components = vec![];
+    bfs.visit_components(
+        [0, 1, 2, 3],
+        |_, _| {},
+        |root, size| components.push((root, size)),
+    );
+    assert_eq!(components, vec![(0, 2), (2, 2)]);
+    Ok(())
+}
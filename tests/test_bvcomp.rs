@@ -58,6 +58,7 @@ where
                                         min_interval_length,
                                         compression_window,
                                         max_ref_count,
+                                        ..Default::default()
                                     };
 
                                     _test_body::<E, _>(tmp_path, &seq_graph, compression_flags)?;
@@ -0,0 +1,40 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+#![cfg(feature = "cli")]
+
+use anyhow::Result;
+use dsi_bitstream::prelude::*;
+use webgraph::cli::build::ef_from_offsets::ef_from_offsets;
+use webgraph::cli::check::targets::{check_targets, CliArgs};
+use webgraph::cli::NumThreadsArg;
+use webgraph::graphs::vec_graph::VecGraph;
+use webgraph::prelude::*;
+
+#[test]
+fn test_check_targets() -> Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    let basename = tmp_dir.path().join("graph");
+    let graph = Left(VecGraph::from_arc_list([(0, 1), (1, 2), (2, 0), (2, 3)]));
+    BvComp::single_thread::<BE, _>(&basename, &graph, CompFlags::default(), true, None)?;
+    ef_from_offsets(&basename)?;
+
+    let args = || CliArgs {
+        src: basename.clone(),
+        num_threads: NumThreadsArg { num_threads: 2 },
+    };
+    check_targets::<BE>(args())?;
+
+    // Drop the last node, so that the arc (2, 3) is out of range
+    let properties_path = basename.with_extension(PROPERTIES_EXTENSION);
+    let properties = std::fs::read_to_string(&properties_path)?;
+    std::fs::write(&properties_path, properties.replace("nodes=4", "nodes=3"))?;
+    ef_from_offsets(&basename)?;
+
+    let err = check_targets::<BE>(args()).unwrap_err();
+    assert!(err.to_string().contains("(2, 3)"), "{}", err);
+    Ok(())
+}
@@ -0,0 +1,65 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+#![cfg(feature = "cli")]
+
+use anyhow::Result;
+use dsi_bitstream::prelude::*;
+use lender::*;
+use webgraph::cli::main as cli_main;
+use webgraph::graphs::random::ErdosRenyi;
+use webgraph::prelude::*;
+
+#[test]
+fn test_complement() -> Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    let graph = ErdosRenyi::new(50, 0.1, 0);
+    let basename = tmp_dir.path().join("graph");
+    BvComp::single_thread::<BE, _>(&basename, graph.iter(), CompFlags::default(), true, None)?;
+    let src = basename.to_str().unwrap();
+    let complement_basename = tmp_dir.path().join("complement");
+    let dst = complement_basename.to_str().unwrap();
+
+    // 50 * 50 exceeds the limit
+    assert!(cli_main([
+        "webgraph",
+        "transform",
+        "complement",
+        src,
+        dst,
+        "--max-size",
+        "2000"
+    ])
+    .is_err());
+    cli_main([
+        "webgraph",
+        "transform",
+        "complement",
+        src,
+        dst,
+        "--max-size",
+        "2000",
+        "--force",
+    ])?;
+
+    let expected = complement(&graph, DEFAULT_COMPLEMENT_MAX_SIZE)?;
+    let complement = BvGraphSeq::with_basename(&complement_basename)
+        .endianness::<BE>()
+        .load()?;
+    assert_eq!(complement.num_nodes(), 50);
+    assert_eq!(complement.num_arcs_hint(), Some(expected.num_arcs()));
+    let expected = Left(expected);
+    let mut iter = expected.iter();
+    for_!((node, succ) in complement.iter() {
+        let (expected_node, expected_succ) = iter.next().unwrap().into_pair();
+        assert_eq!(node, expected_node);
+        assert_eq!(
+            succ.into_iter().collect::<Vec<_>>(),
+            expected_succ.into_iter().collect::<Vec<_>>()
+        );
+    });
+    Ok(())
+}
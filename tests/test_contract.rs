@@ -0,0 +1,86 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+#![cfg(feature = "cli")]
+
+use anyhow::Result;
+use dsi_bitstream::prelude::*;
+use lender::*;
+use std::collections::BTreeSet;
+use webgraph::cli::main as cli_main;
+use webgraph::prelude::*;
+
+fn arcs(graph: &impl SequentialGraph) -> Vec<(usize, usize)> {
+    let mut arcs = vec![];
+    for_!((src, succ) in graph.iter() {
+        arcs.extend(succ.into_iter().map(|dst| (src, dst)));
+    });
+    arcs
+}
+
+#[test]
+fn test_contract_cnr_2000() -> Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    let graph = BvGraphSeq::with_basename("tests/data/cnr-2000").load()?;
+    // Blocks of 1000 consecutive nodes, in reverse order
+    let num_blocks = graph.num_nodes().div_ceil(1000);
+    let mapping = (0..graph.num_nodes())
+        .map(|node| num_blocks - 1 - node / 1000)
+        .collect::<Vec<_>>();
+    let mapping_path = tmp_dir.path().join("mapping.txt");
+    std::fs::write(
+        &mapping_path,
+        mapping
+            .iter()
+            .map(|supernode| format!("{}\n", supernode))
+            .collect::<String>(),
+    )?;
+
+    for keep_loops in [false, true] {
+        let expected = arcs(&graph)
+            .into_iter()
+            .map(|(src, dst)| (mapping[src], mapping[dst]))
+            .filter(|(src, dst)| keep_loops || src != dst)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            arcs(&contract(&graph, &mapping, keep_loops, 100_000)?),
+            expected
+        );
+
+        let basename = tmp_dir.path().join(format!("contracted-{}", keep_loops));
+        let sizes_path = tmp_dir.path().join("sizes.txt");
+        let mut args = vec![
+            "webgraph",
+            "transform",
+            "contract",
+            "tests/data/cnr-2000",
+            basename.to_str().unwrap(),
+            "--mapping",
+            mapping_path.to_str().unwrap(),
+            "--sizes",
+            sizes_path.to_str().unwrap(),
+        ];
+        if keep_loops {
+            args.push("--keep-loops");
+        }
+        cli_main(args)?;
+
+        let contracted = BvGraphSeq::with_basename(&basename)
+            .endianness::<BE>()
+            .load()?;
+        assert_eq!(contracted.num_nodes(), num_blocks);
+        assert_eq!(arcs(&contracted), expected);
+        let sizes = std::fs::read_to_string(&sizes_path)?
+            .lines()
+            .map(|line| line.parse::<usize>())
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(sizes.iter().sum::<usize>(), graph.num_nodes());
+        assert_eq!(sizes[num_blocks - 1], 1000);
+    }
+    Ok(())
+}
@@ -0,0 +1,42 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use anyhow::Result;
+use sux::prelude::*;
+use webgraph::prelude::*;
+
+#[test]
+fn test_outdegrees_bitfield() -> Result<()> {
+    let graph = BvGraph::with_basename("tests/data/cnr-2000").load()?;
+    let thread_pool = rayon::ThreadPoolBuilder::new().num_threads(4).build()?;
+    let max_degree = (0..graph.num_nodes())
+        .map(|node| graph.outdegree(node))
+        .max()
+        .unwrap();
+    let bit_width = (usize::BITS - max_degree.leading_zeros()) as usize;
+
+    for granularity in [1, 64, 100, 1000, 100_000, usize::MAX] {
+        for hint in [None, Some(max_degree)] {
+            let degrees = outdegrees_bitfield(&graph, hint, granularity, &thread_pool);
+            assert_eq!(degrees.len(), graph.num_nodes());
+            assert_eq!(degrees.bit_width(), bit_width);
+            for node in 0..graph.num_nodes() {
+                assert_eq!(degrees.get(node), graph.outdegree(node));
+            }
+        }
+    }
+
+    let tmp_dir = tempfile::tempdir()?;
+    let basename = tmp_dir.path().join("cnr-2000");
+    let degrees = outdegrees_bitfield(&graph, None, 1000, &thread_pool);
+    store_degrees(&basename, &degrees)?;
+    let loaded = load_degrees(&basename)?;
+    assert_eq!(loaded.len(), degrees.len());
+    for node in 0..degrees.len() {
+        assert_eq!(loaded.get(node), degrees.get(node));
+    }
+    Ok(())
+}
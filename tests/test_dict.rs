@@ -0,0 +1,86 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+#![cfg(all(feature = "dict", feature = "cli"))]
+
+use anyhow::Result;
+use dsi_bitstream::prelude::*;
+use dsi_progress_logger::prelude::*;
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+use webgraph::cli::build::ef_from_offsets::ef_from_offsets;
+use webgraph::graphs::bvgraph::dict::*;
+use webgraph::graphs::vec_graph::VecGraph;
+use webgraph::prelude::*;
+
+/// A graph in which most nodes have one of a few boilerplate successor
+/// lists, and the remaining ones have random successors.
+fn duplicated_graph(num_nodes: usize) -> Left<VecGraph> {
+    let mut rng = SmallRng::seed_from_u64(0);
+    let patterns = (0..4)
+        .map(|_| {
+            (0..20)
+                .map(|_| rng.gen_range(0..num_nodes))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let mut graph = VecGraph::new();
+    graph.add_node(num_nodes - 1);
+    for node in 0..num_nodes {
+        if rng.gen_bool(0.7) {
+            for &succ in &patterns[rng.gen_range(0..patterns.len())] {
+                graph.add_arc(node, succ);
+            }
+        } else {
+            for _ in 0..rng.gen_range(0..10) {
+                graph.add_arc(node, rng.gen_range(0..num_nodes));
+            }
+        }
+    }
+    Left(graph)
+}
+
+#[test]
+fn test_dict_roundtrip() -> Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    let basename = tmp_dir.path().join("dict");
+    let graph = duplicated_graph(1000);
+
+    let dict = build_dictionary(&graph, 16, 2, &mut ProgressLogger::default());
+    assert!(dict.num_entries() >= 4);
+    for node in dict.nodes() {
+        assert_eq!(
+            dict.entry(dict.entry_of(node).unwrap()),
+            graph.successors(node).into_iter().collect::<Vec<_>>()
+        );
+    }
+
+    let stats = compress_with_dictionary::<BE>(&basename, &graph, &dict, CompFlags::default())?;
+    assert_eq!(stats.num_nodes, 1000);
+    assert!(stats.coded_fraction() > 0.5);
+    assert!(stats.saved_bits() > 0);
+    ef_from_offsets(&basename)?;
+
+    let properties = std::fs::read_to_string(basename.with_extension(PROPERTIES_EXTENSION))?;
+    assert!(properties.contains(&format!("graphclass={}", DICT_GRAPH_CLASS)));
+    assert!(properties.contains("experimental=dictionary"));
+    assert!(properties.contains(&format!("dictionarynodes={}", stats.coded_nodes)));
+
+    let dict = load_dictionary(&basename)?;
+    let seq_graph = DictGraph::new(BvGraphSeq::with_basename(&basename).load()?, &*dict);
+    webgraph::assert_graph_eq!(graph, seq_graph);
+
+    let random_graph = DictGraph::new(BvGraph::with_basename(&basename).load()?, &*dict);
+    assert_eq!(random_graph.num_arcs(), graph.num_arcs());
+    for node in 0..graph.num_nodes() {
+        assert_eq!(random_graph.outdegree(node), graph.outdegree(node));
+        assert_eq!(
+            random_graph.successors(node).collect::<Vec<_>>(),
+            graph.successors(node).into_iter().collect::<Vec<_>>()
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,88 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+#![cfg(feature = "cli")]
+
+use anyhow::Result;
+use dsi_bitstream::prelude::*;
+use tempfile::Builder;
+use webgraph::cli::{check_symmetric, check_transposed};
+use webgraph::prelude::*;
+
+#[test]
+fn test_mark_and_check_direction() -> Result<()> {
+    let tmp_dir = Builder::new().prefix("test_direction").tempdir()?;
+    let basename = tmp_dir.path().join("graph");
+    let transposed = tmp_dir.path().join("graph-t");
+
+    let graph = Left(VecGraph::from_arc_list([(0, 1), (1, 2), (2, 0), (2, 1)]));
+    let graph_t = Left(VecGraph::from_arc_list([(1, 0), (2, 1), (0, 2), (1, 2)]));
+    BvComp::single_thread::<BE, _>(&basename, &graph, CompFlags::default(), false, None)?;
+    BvComp::single_thread::<BE, _>(&transposed, &graph_t, CompFlags::default(), false, None)?;
+
+    // Legacy, unmarked graph: only a warning
+    assert_eq!(get_direction(&transposed)?, None);
+    check_transposed(&basename, &transposed)?;
+
+    // Mark, then verify
+    set_direction(&transposed, ArcDirection::Backward, &basename)?;
+    assert_eq!(
+        get_direction(&transposed)?,
+        Some((ArcDirection::Backward, canonicalize_basename(&basename)))
+    );
+    check_transposed(&basename, &transposed)?;
+    // Other keys must be preserved
+    let (num_nodes, num_arcs, _) =
+        parse_properties::<BE>(transposed.with_extension(PROPERTIES_EXTENSION))?;
+    assert_eq!(num_nodes, 3);
+    assert_eq!(num_arcs, 4);
+
+    // Same graph through a different path
+    check_transposed(tmp_dir.path().join(".").join("graph"), &transposed)?;
+
+    // Wrong counterpart
+    assert!(check_transposed(tmp_dir.path().join("other"), &transposed).is_err());
+    // Same name in a different directory
+    let other_dir = tmp_dir.path().join("other_dir");
+    std::fs::create_dir(&other_dir)?;
+    assert!(check_transposed(other_dir.join("graph"), &transposed).is_err());
+
+    // Wrong direction
+    set_direction(&transposed, ArcDirection::Forward, &basename)?;
+    assert!(check_transposed(&basename, &transposed).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_mark_loopless_symmetric() -> Result<()> {
+    let tmp_dir = Builder::new().prefix("test_structure").tempdir()?;
+    let basename = tmp_dir.path().join("graph");
+    let graph = Left(VecGraph::from_arc_list([(0, 1), (1, 0), (1, 2), (2, 1)]));
+    BvComp::single_thread::<BE, _>(&basename, &graph, CompFlags::default(), false, None)?;
+
+    // Legacy, unmarked graph: unknown, and only a warning
+    assert_eq!(is_loopless(&basename)?, None);
+    assert_eq!(is_symmetric(&basename)?, None);
+    check_symmetric(&basename, "test")?;
+
+    set_loopless(&basename, true)?;
+    set_symmetric(&basename, true)?;
+    assert_eq!(is_loopless(&basename)?, Some(true));
+    assert_eq!(is_symmetric(&basename)?, Some(true));
+    check_symmetric(&basename, "test")?;
+    // Other keys must be preserved
+    let (num_nodes, num_arcs, _) =
+        parse_properties::<BE>(basename.with_extension(PROPERTIES_EXTENSION))?;
+    assert_eq!(num_nodes, 3);
+    assert_eq!(num_arcs, 4);
+
+    set_symmetric(&basename, false)?;
+    assert_eq!(is_symmetric(&basename)?, Some(false));
+    assert_eq!(is_loopless(&basename)?, Some(true));
+    assert!(check_symmetric(&basename, "test").is_err());
+    Ok(())
+}
@@ -0,0 +1,55 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+#![cfg(feature = "cli")]
+
+use anyhow::Result;
+use dsi_bitstream::prelude::*;
+use std::process::Command;
+use webgraph::cli::build::ef_from_offsets::ef_from_offsets;
+use webgraph::graphs::vec_graph::VecGraph;
+use webgraph::prelude::*;
+
+fn dist_path(basename: &str, src: usize, dst: usize) -> Result<String> {
+    let output = Command::new(env!("CARGO_BIN_EXE_webgraph"))
+        .args(["dist", "path", basename, &src.to_string(), &dst.to_string()])
+        .output()?;
+    assert!(output.status.success());
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+#[test]
+fn test_dist_path() -> Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    let basename = tmp_dir.path().join("graph");
+    // 0 -> 1 -> 2 -> 3 with a shortcut 0 -> 2; node 4 is unreachable
+    let graph = Left(VecGraph::from_arc_list([
+        (0, 1),
+        (0, 2),
+        (1, 2),
+        (2, 3),
+        (4, 0),
+    ]));
+    BvComp::single_thread::<BE, _>(&basename, &graph, CompFlags::default(), true, None)?;
+    ef_from_offsets(&basename)?;
+    let basename = basename.to_str().unwrap();
+
+    assert_eq!(
+        dist_path(basename, 0, 3)?,
+        "Distance: 2\nPath: 0 -> 2 -> 3\n"
+    );
+    assert_eq!(dist_path(basename, 1, 1)?, "Distance: 0\nPath: 1\n");
+    assert_eq!(
+        dist_path(basename, 0, 4)?,
+        "Node 4 is not reachable from node 0\n"
+    );
+
+    let output = Command::new(env!("CARGO_BIN_EXE_webgraph"))
+        .args(["dist", "path", basename, "0", "5"])
+        .output()?;
+    assert!(!output.status.success());
+    Ok(())
+}
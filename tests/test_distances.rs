@@ -0,0 +1,60 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use anyhow::Result;
+use dsi_bitstream::prelude::*;
+use dsi_progress_logger::prelude::*;
+use lender::*;
+use webgraph::algo::distances::{sample_pair_distances, PairDistances};
+use webgraph::graphs::vec_graph::VecGraph;
+use webgraph::prelude::*;
+
+#[test]
+fn test_bidirectional_bfs_cnr_2000() -> Result<()> {
+    let graph = BvGraph::with_basename("tests/data/cnr-2000")
+        .endianness::<BE>()
+        .load()?;
+    let num_nodes = graph.num_nodes();
+
+    let mut transpose = VecGraph::empty(num_nodes);
+    let mut iter = graph.iter();
+    while let Some((node, succ)) = iter.next() {
+        for s in succ {
+            transpose.add_arc(s, node);
+        }
+    }
+    let transpose = Left(transpose);
+
+    let mut state = PairDistances::new(num_nodes);
+    for i in 0..200 {
+        let src = (i * 7919) % num_nodes;
+        let dst = (i * 104729 + 17) % num_nodes;
+        assert_eq!(
+            state.bidirectional(&graph, &transpose, src, dst),
+            state.bfs(&graph, src, dst),
+            "Distance mismatch between {} and {}",
+            src,
+            dst
+        );
+    }
+
+    let thread_pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(4)
+        .build()
+        .unwrap();
+    let sample = sample_pair_distances(
+        &graph,
+        None::<&Left<VecGraph>>,
+        500,
+        0,
+        &thread_pool,
+        &mut ProgressLogger::default(),
+    );
+    assert_eq!(sample.samples(), 500);
+    assert_eq!(sample.distribution.first().copied().unwrap_or(0), 0);
+
+    Ok(())
+}
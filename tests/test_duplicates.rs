@@ -0,0 +1,187 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use anyhow::Result;
+use lender::*;
+use webgraph::graphs::arc_list_graph::ArcListGraph;
+use webgraph::prelude::*;
+use webgraph::utils::sort_pairs::{CountDuplicates, DuplicateArcs};
+
+type Arcs = Vec<(usize, usize, usize)>;
+
+/// Arcs with known duplicates: (0, 1) appears three times and (1, 2) twice.
+const ARCS: [(usize, usize); 6] = [(0, 1), (1, 2), (0, 1), (2, 0), (1, 2), (0, 1)];
+
+/// The arcs of a graph as triples whose third element is the label.
+fn labeled_arcs<L: Copy>(
+    graph: &impl LabeledSequentialGraph<L>,
+    to_usize: impl Fn(L) -> usize,
+) -> Arcs {
+    let mut result = vec![];
+    for_![(src, succ) in graph.iter() {
+        for (dst, label) in succ {
+            result.push((src, dst, to_usize(label)));
+        }
+    }];
+    result
+}
+
+/// Sorts the pairs with [`SortPairs`], returning triples with a zero label.
+fn sort_pairs(duplicates: DuplicateArcs) -> Result<Arcs> {
+    let dir = tempfile::tempdir()?;
+    let mut sort_pairs = SortPairs::new(2, dir.path())?.duplicates(duplicates);
+    for (src, dst) in ARCS {
+        sort_pairs.push(src, dst)?;
+    }
+    Ok(sort_pairs
+        .iter()?
+        .map(|(src, dst, _)| (src, dst, 0))
+        .collect())
+}
+
+/// Builds an [`ArcListGraph`] on the arcs stably sorted by pair and labeled by
+/// their input position.
+fn arc_list_graph(duplicates: DuplicateArcs) -> Result<Arcs> {
+    let mut arcs = ARCS
+        .iter()
+        .enumerate()
+        .map(|(i, &(src, dst))| (src, dst, i))
+        .collect::<Vec<_>>();
+    arcs.sort_by_key(|&(src, dst, _)| (src, dst));
+    let graph = ArcListGraph::new_labeled(3, arcs).duplicates(duplicates);
+    Ok(labeled_arcs(&graph, |l| l))
+}
+
+/// Counts duplicates of the output of [`SortPairs`].
+fn count_duplicates() -> Result<Arcs> {
+    let dir = tempfile::tempdir()?;
+    let mut sort_pairs = SortPairs::new(2, dir.path())?;
+    for (src, dst) in ARCS {
+        sort_pairs.push(src, dst)?;
+    }
+    let graph = ArcListGraph::new_labeled(3, CountDuplicates::new(sort_pairs.iter()?));
+    Ok(labeled_arcs(&graph, |l| l))
+}
+
+#[test]
+fn test_duplicate_policies() -> Result<()> {
+    #[allow(clippy::type_complexity)]
+    let table: Vec<(&str, Box<dyn Fn() -> Result<Arcs>>, Arcs)> = vec![
+        (
+            "SortPairs, keep",
+            Box::new(|| sort_pairs(DuplicateArcs::Keep)),
+            vec![
+                (0, 1, 0),
+                (0, 1, 0),
+                (0, 1, 0),
+                (1, 2, 0),
+                (1, 2, 0),
+                (2, 0, 0),
+            ],
+        ),
+        (
+            "SortPairs, remove",
+            Box::new(|| sort_pairs(DuplicateArcs::Remove)),
+            vec![(0, 1, 0), (1, 2, 0), (2, 0, 0)],
+        ),
+        (
+            "ArcListGraph, keep",
+            Box::new(|| arc_list_graph(DuplicateArcs::Keep)),
+            vec![
+                (0, 1, 0),
+                (0, 1, 2),
+                (0, 1, 5),
+                (1, 2, 1),
+                (1, 2, 4),
+                (2, 0, 3),
+            ],
+        ),
+        (
+            "ArcListGraph, remove (first label)",
+            Box::new(|| arc_list_graph(DuplicateArcs::Remove)),
+            vec![(0, 1, 0), (1, 2, 1), (2, 0, 3)],
+        ),
+        (
+            "CountDuplicates (multigraph)",
+            Box::new(count_duplicates),
+            vec![(0, 1, 3), (1, 2, 2), (2, 0, 1)],
+        ),
+    ];
+
+    for (mode, arcs, expected) in table {
+        assert_eq!(arcs()?, expected, "{}", mode);
+    }
+    Ok(())
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn test_from_arcs_multigraph() -> Result<()> {
+    use clap::{Args, Command, FromArgMatches};
+    use dsi_bitstream::prelude::*;
+    use webgraph::cli::build::label_ef::build_label_ef;
+    use webgraph::cli::from::arcs::{from_reader, CliArgs};
+    use webgraph::labels::bitstream::MmapBitStreamLabeling;
+
+    let tmp_dir = tempfile::tempdir()?;
+    let basename = tmp_dir.path().join("multi");
+    let matches = CliArgs::augment_args(Command::new("arcs")).try_get_matches_from([
+        "arcs",
+        "--num-nodes",
+        "3",
+        "--exact",
+        "--multigraph",
+        basename.to_str().unwrap(),
+    ])?;
+    let input = ARCS
+        .iter()
+        .map(|(src, dst)| format!("{},{}\n", src, dst))
+        .collect::<String>();
+    from_reader(CliArgs::from_arg_matches(&matches)?, input.as_bytes())?;
+
+    let graph = BvGraphSeq::with_basename(&basename)
+        .endianness::<BE>()
+        .load()?;
+    assert_eq!(
+        labeled_arcs(&UnitLabelGraph(graph), |_| 0),
+        vec![(0, 1, 0), (1, 2, 0), (2, 0, 0)]
+    );
+
+    let reader = |extension| -> Result<_> {
+        Ok(<BufBitReader<BE, _>>::new(<WordAdapter<u32, _>>::new(
+            std::io::BufReader::new(std::fs::File::open(basename.with_extension(extension))?),
+        )))
+    };
+    let mut labels = reader("labels")?;
+    let counts = (0..3)
+        .map(|_| labels.read_gamma().map(|x| x + 1))
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(counts, vec![3, 2, 1]);
+    let mut offsets = reader("labeloffsets")?;
+    let deltas = (0..4)
+        .map(|_| offsets.read_gamma())
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(deltas, vec![0, 3, 3, 1]);
+
+    // Random access to the multiplicities through the .labelef file
+    struct GammaDeserializer;
+    impl<BR: BitRead<BE> + GammaRead<BE>> BitDeserializer<BE, BR> for GammaDeserializer {
+        type DeserType = u64;
+        fn deserialize(&self, bitstream: &mut BR) -> Result<u64, BR::Error> {
+            bitstream.read_gamma().map(|x| x + 1)
+        }
+    }
+    build_label_ef(webgraph::cli::build::label_ef::CliArgs {
+        src: basename.clone(),
+        num_nodes: None,
+    })?;
+    let labels = MmapBitStreamLabeling::<BE, _>::mmap(&basename, GammaDeserializer)?;
+    assert_eq!(labels.num_nodes(), 3);
+    for (node, count) in [(2, 1), (0, 3), (1, 2)] {
+        assert_eq!(labels.labels(node).collect::<Vec<_>>(), vec![count]);
+    }
+    Ok(())
+}
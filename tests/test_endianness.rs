@@ -0,0 +1,48 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+#![cfg(feature = "cli")]
+
+use anyhow::Result;
+use webgraph::cli::main as cli_main;
+use webgraph::graphs::bvgraph::{GRAPH_EXTENSION, PROPERTIES_EXTENSION};
+
+const TEST_GRAPH: &str = "tests/data/cnr-2000";
+
+#[test]
+fn test_to_endianness() -> Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    let src = tmp_dir.path().join("src");
+    let le = tmp_dir.path().join("le");
+    let be = tmp_dir.path().join("be");
+    let [src_str, le_str, be_str] = [&src, &le, &be].map(|p| p.display().to_string());
+
+    // The properties of the test graph do not contain the bitstream length
+    cli_main(vec!["webgraph", "to", "bvgraph", TEST_GRAPH, &src_str])?;
+    let src_properties = std::fs::read_to_string(src.with_extension(PROPERTIES_EXTENSION))?;
+
+    cli_main(vec!["webgraph", "to", "endianness", &src_str, &le_str])?;
+    cli_main(vec!["webgraph", "to", "endianness", &le_str, &be_str])?;
+
+    // The source is left untouched
+    assert_eq!(
+        src_properties,
+        std::fs::read_to_string(src.with_extension(PROPERTIES_EXTENSION))?
+    );
+    assert!(
+        std::fs::read_to_string(le.with_extension(PROPERTIES_EXTENSION))?
+            .contains("endianness=little")
+    );
+    assert_eq!(
+        std::fs::read(src.with_extension(GRAPH_EXTENSION))?,
+        std::fs::read(be.with_extension(GRAPH_EXTENSION))?
+    );
+    assert_eq!(
+        src_properties,
+        std::fs::read_to_string(be.with_extension(PROPERTIES_EXTENSION))?
+    );
+    Ok(())
+}
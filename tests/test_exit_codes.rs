@@ -0,0 +1,137 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+#![cfg(feature = "cli")]
+
+use anyhow::Result;
+use dsi_bitstream::prelude::*;
+use std::path::Path;
+use std::process::{Command, Output};
+use webgraph::cli::build::ef_from_offsets::ef_from_offsets;
+use webgraph::cli::ExitCode;
+use webgraph::graphs::vec_graph::VecGraph;
+use webgraph::prelude::*;
+
+fn webgraph(args: &[&str]) -> Result<Output> {
+    Ok(Command::new(env!("CARGO_BIN_EXE_webgraph"))
+        .args(args)
+        .output()?)
+}
+
+fn exit_code(output: &Output) -> i32 {
+    output.status.code().expect("terminated by a signal")
+}
+
+fn compress(basename: &Path, arcs: &[(usize, usize)]) -> Result<()> {
+    let graph = Left(VecGraph::from_arc_list(arcs.iter().copied()));
+    BvComp::single_thread::<BE, _>(basename, &graph, CompFlags::default(), true, None)?;
+    ef_from_offsets(basename)?;
+    Ok(())
+}
+
+#[test]
+fn test_exit_codes() -> Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    let basename = tmp_dir.path().join("graph");
+    compress(&basename, &[(0, 1), (0, 2), (1, 2)])?;
+    let basename = basename.to_str().unwrap();
+
+    let output = webgraph(&["analyze", "degree-dist", basename])?;
+    assert_eq!(exit_code(&output), ExitCode::Success as i32);
+
+    let missing = tmp_dir.path().join("missing");
+    let output = webgraph(&["analyze", "degree-dist", missing.to_str().unwrap()])?;
+    assert_eq!(exit_code(&output), ExitCode::Input as i32);
+
+    let output = webgraph(&["analyze", "degree-dist", "--no-such-flag", basename])?;
+    assert_eq!(exit_code(&output), ExitCode::Usage as i32);
+    let output = webgraph(&["analyze", "degree-dist"])?;
+    assert_eq!(exit_code(&output), ExitCode::Usage as i32);
+    Ok(())
+}
+
+#[test]
+fn test_check_exit_codes() -> Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    let dag = tmp_dir.path().join("dag");
+    compress(&dag, &[(0, 1), (0, 2), (1, 2)])?;
+    let cyclic = tmp_dir.path().join("cyclic");
+    compress(&cyclic, &[(0, 1), (1, 2), (2, 0), (2, 1)])?;
+    let dag = dag.to_str().unwrap();
+    let cyclic = cyclic.to_str().unwrap();
+
+    // 0 = consistent, 1 = inconsistency found, 2 = I/O or usage error
+    let output = webgraph(&["check", "acyclic", dag])?;
+    assert_eq!(exit_code(&output), 0);
+    let output = webgraph(&["check", "acyclic", cyclic])?;
+    assert_eq!(exit_code(&output), 1);
+    let missing = tmp_dir.path().join("missing");
+    let output = webgraph(&["check", "acyclic", missing.to_str().unwrap()])?;
+    assert_eq!(exit_code(&output), 2);
+    let output = webgraph(&["check", "acyclic", "--no-such-flag", dag])?;
+    assert_eq!(exit_code(&output), 2);
+
+    let output = webgraph(&["check", "ef", dag])?;
+    assert_eq!(exit_code(&output), 0);
+    let output = webgraph(&["check", "targets", dag])?;
+    assert_eq!(exit_code(&output), 0);
+
+    // --quiet suppresses all output but errors
+    let output = webgraph(&["--quiet", "check", "targets", dag])?;
+    assert_eq!(exit_code(&output), 0);
+    assert!(output.stderr.is_empty());
+
+    // A stale '.ef' file from another graph
+    std::fs::copy(
+        format!("{}.{}", cyclic, EF_EXTENSION),
+        format!("{}.{}", dag, EF_EXTENSION),
+    )?;
+    let output = webgraph(&["check", "ef", dag])?;
+    assert_eq!(exit_code(&output), 1);
+    let output = webgraph(&["check", "ef", "--quiet", dag])?;
+    assert_eq!(exit_code(&output), 1);
+    assert!(String::from_utf8(output.stderr)?.contains("Error"));
+    Ok(())
+}
+
+#[test]
+fn test_quiet() -> Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    let basename = tmp_dir.path().join("graph");
+    compress(&basename, &[(0, 1), (1, 2)])?;
+    let basename = basename.to_str().unwrap();
+
+    let output = webgraph(&["check", "acyclic", basename])?;
+    assert!(String::from_utf8(output.stderr)?.contains("INFO"));
+
+    let output = webgraph(&["--quiet", "check", "acyclic", basename])?;
+    assert_eq!(exit_code(&output), ExitCode::Success as i32);
+    assert!(!String::from_utf8(output.stderr)?.contains("INFO"));
+
+    // Errors are still reported
+    let output = webgraph(&[
+        "analyze",
+        "degree-dist",
+        "--quiet",
+        &format!("{}-missing", basename),
+    ])?;
+    assert_eq!(exit_code(&output), ExitCode::Input as i32);
+    assert!(String::from_utf8(output.stderr)?.contains("Error"));
+
+    let output = webgraph(&["--no-progress", "check", "acyclic", basename])?;
+    assert_eq!(exit_code(&output), ExitCode::Success as i32);
+    // Messages other than progress are still logged
+    assert!(String::from_utf8(output.stderr)?.contains("Acyclicity"));
+
+    let output = webgraph(&["build", "ef", basename])?;
+    assert!(String::from_utf8(output.stderr)?.contains("Completed."));
+    let output = webgraph(&["--no-progress", "build", "ef", basename])?;
+    assert_eq!(exit_code(&output), ExitCode::Success as i32);
+    let stderr = String::from_utf8(output.stderr)?;
+    assert!(!stderr.contains("Completed."));
+    assert!(stderr.contains("Wrote"));
+    Ok(())
+}
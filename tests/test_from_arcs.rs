@@ -0,0 +1,209 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+#![cfg(feature = "cli")]
+
+use anyhow::Result;
+use clap::{Args, Command, FromArgMatches};
+use dsi_bitstream::prelude::*;
+use webgraph::cli::build::ef_from_offsets::ef_from_offsets;
+use webgraph::cli::from::arcs::{from_reader, CliArgs};
+use webgraph::prelude::*;
+
+fn from_arcs(basename: &str, one_based: bool, input: &str) -> Result<()> {
+    let mut args = vec!["arcs", "--num-nodes", "3", "--exact"];
+    if one_based {
+        args.push("--one-based");
+    }
+    args.push(basename);
+    let matches = CliArgs::augment_args(Command::new("arcs")).try_get_matches_from(args)?;
+    from_reader(CliArgs::from_arg_matches(&matches)?, input.as_bytes())?;
+    ef_from_offsets(basename)
+}
+
+#[test]
+fn test_one_based() -> Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    let basename = tmp_dir.path().join("one-based");
+    let basename = basename.to_str().unwrap();
+    from_arcs(basename, true, "1,2\n2,3\n3,1\n")?;
+
+    let graph = BvGraph::with_basename(basename).endianness::<BE>().load()?;
+    assert_eq!(graph.num_nodes(), 3);
+    assert_eq!(graph.successors(0).collect::<Vec<_>>(), vec![1]);
+    assert_eq!(graph.successors(1).collect::<Vec<_>>(), vec![2]);
+    assert_eq!(graph.successors(2).collect::<Vec<_>>(), vec![0]);
+
+    // Zero is not a valid one-based identifier
+    assert!(from_arcs(basename, true, "1,2\n0,1\n").is_err());
+
+    // --one-based requires --exact
+    assert!(CliArgs::augment_args(Command::new("arcs"))
+        .try_get_matches_from(["arcs", "--num-nodes", "3", "--one-based", basename])
+        .is_err());
+    Ok(())
+}
+
+#[test]
+fn test_also_transpose() -> Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    let basename = tmp_dir.path().join("graph");
+    let transpose_basename = tmp_dir.path().join("graph-t");
+    let matches = CliArgs::augment_args(Command::new("arcs")).try_get_matches_from([
+        "arcs",
+        "--num-nodes",
+        "4",
+        "--exact",
+        "--also-transpose",
+        transpose_basename.to_str().unwrap(),
+        basename.to_str().unwrap(),
+    ])?;
+    from_reader(
+        CliArgs::from_arg_matches(&matches)?,
+        "0,1\n0,2\n1,2\n2,0\n0,1\n".as_bytes(),
+    )?;
+    ef_from_offsets(&basename)?;
+    ef_from_offsets(&transpose_basename)?;
+
+    let graph = BvGraph::with_basename(&basename)
+        .endianness::<BE>()
+        .load()?;
+    let transpose = BvGraph::with_basename(&transpose_basename)
+        .endianness::<BE>()
+        .load()?;
+    assert_eq!(graph.num_nodes(), 4);
+    assert_eq!(transpose.num_nodes(), 4);
+    assert_eq!(graph.num_arcs(), 4);
+    assert_eq!(transpose.num_arcs(), 4);
+    assert_eq!(transpose.successors(0).collect::<Vec<_>>(), vec![2]);
+    assert_eq!(transpose.successors(1).collect::<Vec<_>>(), vec![0]);
+    assert_eq!(transpose.successors(2).collect::<Vec<_>>(), vec![0, 1]);
+    assert_eq!(transpose.outdegree(3), 0);
+
+    assert_eq!(
+        get_direction(&basename)?,
+        Some((ArcDirection::Forward, transpose_basename.clone()))
+    );
+    assert_eq!(
+        get_direction(&transpose_basename)?,
+        Some((ArcDirection::Backward, basename.clone()))
+    );
+    Ok(())
+}
+
+#[test]
+fn test_node_filter() -> Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    let filter = tmp_dir.path().join("filter.txt");
+    let input = "0,1\n1,3\n3,0\n1,2\n2,4\n4,1\n";
+
+    // Exact identifiers are compacted preserving their order
+    std::fs::write(&filter, "4\n1\n\n3\n")?;
+    let basename = tmp_dir.path().join("exact");
+    let matches = CliArgs::augment_args(Command::new("arcs")).try_get_matches_from([
+        "arcs",
+        "--num-nodes",
+        "5",
+        "--exact",
+        "--node-filter",
+        filter.to_str().unwrap(),
+        basename.to_str().unwrap(),
+    ])?;
+    from_reader(CliArgs::from_arg_matches(&matches)?, input.as_bytes())?;
+    ef_from_offsets(&basename)?;
+    let graph = BvGraph::with_basename(&basename)
+        .endianness::<BE>()
+        .load()?;
+    assert_eq!(graph.num_nodes(), 3);
+    assert_eq!(graph.num_arcs(), 2);
+    assert_eq!(graph.successors(0).collect::<Vec<_>>(), vec![1]);
+    assert_eq!(graph.outdegree(1), 0);
+    assert_eq!(graph.successors(2).collect::<Vec<_>>(), vec![0]);
+    assert_eq!(
+        std::fs::read_to_string(basename.with_extension("nodes"))?,
+        "1\n3\n4\n"
+    );
+
+    // Labels are numbered in the order of the filter
+    let basename = tmp_dir.path().join("labels");
+    let matches = CliArgs::augment_args(Command::new("arcs")).try_get_matches_from([
+        "arcs",
+        "--num-nodes",
+        "3",
+        "--node-filter",
+        filter.to_str().unwrap(),
+        basename.to_str().unwrap(),
+    ])?;
+    from_reader(CliArgs::from_arg_matches(&matches)?, input.as_bytes())?;
+    ef_from_offsets(&basename)?;
+    let graph = BvGraph::with_basename(&basename)
+        .endianness::<BE>()
+        .load()?;
+    assert_eq!(graph.num_nodes(), 3);
+    // 4 → 1 and 1 → 3 become 0 → 1 and 1 → 2
+    assert_eq!(graph.successors(0).collect::<Vec<_>>(), vec![1]);
+    assert_eq!(graph.successors(1).collect::<Vec<_>>(), vec![2]);
+    assert_eq!(graph.outdegree(2), 0);
+    assert_eq!(
+        std::fs::read_to_string(basename.with_extension("nodes"))?,
+        "4\n1\n3\n"
+    );
+
+    // Out-of-bounds identifiers are rejected
+    std::fs::write(&filter, "1\n7\n")?;
+    let matches = CliArgs::augment_args(Command::new("arcs")).try_get_matches_from([
+        "arcs",
+        "--num-nodes",
+        "5",
+        "--exact",
+        "--node-filter",
+        filter.to_str().unwrap(),
+        basename.to_str().unwrap(),
+    ])?;
+    assert!(from_reader(CliArgs::from_arg_matches(&matches)?, input.as_bytes()).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_build_bvgraph() -> Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    let arcs_path = tmp_dir.path().join("arcs.csv");
+    std::fs::write(&arcs_path, "# a comment\n0,1\n0,2\n1,2\n2,0\n0,1\n")?;
+    let basename = tmp_dir.path().join("graph");
+    webgraph::cli::main(vec![
+        "webgraph",
+        "build",
+        "bvgraph",
+        arcs_path.to_str().unwrap(),
+        basename.to_str().unwrap(),
+        "--num-nodes",
+        "3",
+        "--exact",
+    ])?;
+    ef_from_offsets(&basename)?;
+
+    let graph = BvGraph::with_basename(&basename)
+        .endianness::<BE>()
+        .load()?;
+    assert_eq!(graph.num_nodes(), 3);
+    assert_eq!(graph.num_arcs(), 4);
+    assert_eq!(graph.successors(0).collect::<Vec<_>>(), vec![1, 2]);
+    assert_eq!(graph.successors(1).collect::<Vec<_>>(), vec![2]);
+    assert_eq!(graph.successors(2).collect::<Vec<_>>(), vec![0]);
+
+    // A missing file is an error
+    assert!(webgraph::cli::main(vec![
+        "webgraph",
+        "build",
+        "bvgraph",
+        tmp_dir.path().join("missing.csv").to_str().unwrap(),
+        basename.to_str().unwrap(),
+        "--num-nodes",
+        "3",
+    ])
+    .is_err());
+    Ok(())
+}
@@ -0,0 +1,76 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+#![cfg(feature = "cli")]
+
+use anyhow::Result;
+use dsi_bitstream::prelude::BE;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use lender::*;
+use std::io::Write;
+use webgraph::cli::main as cli_main;
+use webgraph::prelude::*;
+
+const EDGE_LIST: &str = "% Twitter follower dump
+# source target
+123456789012345678901234567890 12
+12 9
+9\t12
+
+12 123456789012345678901234567890
+9 12
+";
+
+fn check(basename: &std::path::Path) -> Result<()> {
+    let nodes = std::fs::read_to_string(basename.with_extension("nodes"))?;
+    assert_eq!(
+        nodes.lines().collect::<Vec<_>>(),
+        vec!["9", "12", "123456789012345678901234567890"]
+    );
+    let graph = BvGraphSeq::with_basename(basename)
+        .endianness::<BE>()
+        .load()?;
+    let mut successors = vec![];
+    for_![(_, succ) in graph.iter() {
+        successors.push(succ.into_iter().collect::<Vec<_>>());
+    }];
+    assert_eq!(successors, vec![vec![1], vec![0, 2], vec![1]]);
+    Ok(())
+}
+
+#[test]
+fn test_from_twitter_edge_list() -> Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+
+    let plain = tmp_dir.path().join("edges.txt");
+    std::fs::write(&plain, EDGE_LIST)?;
+    let basename = tmp_dir.path().join("plain");
+    cli_main(vec![
+        "webgraph",
+        "from",
+        "twitter-edge-list",
+        plain.to_str().unwrap(),
+        basename.to_str().unwrap(),
+    ])?;
+    check(&basename)?;
+
+    let gzipped = tmp_dir.path().join("edges.txt.gz");
+    let mut encoder = GzEncoder::new(std::fs::File::create(&gzipped)?, Compression::default());
+    encoder.write_all(EDGE_LIST.as_bytes())?;
+    encoder.finish()?;
+    let basename = tmp_dir.path().join("gzipped");
+    cli_main(vec![
+        "webgraph",
+        "from",
+        "twitter-edge-list",
+        gzipped.to_str().unwrap(),
+        basename.to_str().unwrap(),
+    ])?;
+    check(&basename)?;
+
+    Ok(())
+}
@@ -0,0 +1,67 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+#![cfg(feature = "cli")]
+
+use anyhow::Result;
+use dsi_bitstream::prelude::*;
+use std::process::Command;
+use webgraph::cli::main as cli_main;
+use webgraph::graphs::vec_graph::VecGraph;
+use webgraph::prelude::*;
+
+/// Returns the log-gap cost printed by `analyze gap-cost`.
+fn gap_cost(args: &[&str]) -> Result<f64> {
+    let output = Command::new(env!("CARGO_BIN_EXE_webgraph"))
+        .args(["analyze", "gap-cost"])
+        .args(args)
+        .output()?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout)?;
+    let cost = stdout
+        .lines()
+        .find_map(|line| line.strip_prefix("Log-gap cost: "))
+        .unwrap();
+    Ok(cost.parse()?)
+}
+
+#[test]
+fn test_gap_cost() -> Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    let basename = tmp_dir.path().join("graph");
+    let graph = Left(VecGraph::from_arc_list([
+        (0, 1),
+        (0, 3),
+        (1, 0),
+        (2, 0),
+        (2, 1),
+        (2, 3),
+        (3, 2),
+    ]));
+    BvComp::single_thread::<BE, _>(&basename, &graph, CompFlags::default(), true, None)?;
+    let basename = basename.to_str().unwrap();
+    cli_main(vec!["webgraph", "build", "ef", basename])?;
+    cli_main(vec!["webgraph", "build", "dcf", basename])?;
+
+    // Each node costs ⌊log(|x − s₀| + 1)⌋ plus ⌊log(sᵢ₊₁ − sᵢ)⌋ for each pair
+    // of consecutive successors:
+    // 0 → [1, 3]: 1 + 1; 1 → [0]: 1; 2 → [0, 1, 3]: 1 + 0 + 1; 3 → [2]: 1
+    assert_eq!(gap_cost(&[basename])?, 6.0);
+
+    // Reversing the nodes (x ↦ 3 − x):
+    // 3 → [0, 2]: 2 + 1; 2 → [3]: 1; 1 → [0, 2, 3]: 1 + 1 + 0; 0 → [1]: 1
+    let perm_path = tmp_dir.path().join("reverse.perm");
+    let perm = (0..4_u64)
+        .rev()
+        .flat_map(u64::to_be_bytes)
+        .collect::<Vec<_>>();
+    std::fs::write(&perm_path, perm)?;
+    assert_eq!(
+        gap_cost(&[basename, "--perm", perm_path.to_str().unwrap()])?,
+        7.0
+    );
+    Ok(())
+}
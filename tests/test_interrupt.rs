@@ -0,0 +1,80 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+#![cfg(feature = "cli")]
+
+use anyhow::Result;
+use std::io::Write;
+use webgraph::cli::{ExitCode, OutputFile};
+use webgraph::utils::interrupt;
+use webgraph::utils::SortPairs;
+
+#[test]
+fn test_interrupt() -> Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    let path = tmp_dir.path().join("out");
+    let mut out = OutputFile::create(&path)?;
+    out.write_all(b"hello")?;
+
+    interrupt::interrupt();
+
+    let err = anyhow::Error::from(out.write_all(b"hello").unwrap_err());
+    assert_eq!(ExitCode::of(&err), ExitCode::Interrupted);
+    drop(out);
+    assert!(!path.exists());
+
+    let batch_dir = tmp_dir.path().join("batches");
+    std::fs::create_dir(&batch_dir)?;
+    let mut sort_pairs = SortPairs::new(1, &batch_dir)?;
+    let err = sort_pairs.push(0, 1).unwrap_err();
+    assert_eq!(ExitCode::of(&err), ExitCode::Interrupted);
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_sigint() -> Result<()> {
+    use std::io::{BufRead, BufReader};
+    use std::process::{Command, Stdio};
+
+    let tmp_dir = tempfile::tempdir()?;
+    let dst = tmp_dir.path().join("transposed");
+    let tmp = tmp_dir.path().join("tmp");
+    std::fs::create_dir(&tmp)?;
+    let mut child = Command::new(env!("CARGO_BIN_EXE_webgraph"))
+        .args([
+            "transform",
+            "transpose",
+            "tests/data/cnr-2000",
+            "-b",
+            "10000",
+        ])
+        .arg(&dst)
+        .env("TMPDIR", &tmp)
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    // Interrupt while batches are being created
+    let mut stderr = BufReader::new(child.stderr.take().unwrap()).lines();
+    for line in stderr.by_ref() {
+        if line?.contains("Creating batches") {
+            break;
+        }
+    }
+    unsafe { libc::kill(child.id() as libc::pid_t, libc::SIGINT) };
+    let stderr = stderr.collect::<Result<Vec<_>, _>>()?;
+    let status = child.wait()?;
+
+    assert_eq!(
+        status.code(),
+        Some(ExitCode::Interrupted as i32),
+        "{:?}",
+        stderr
+    );
+    // The batches have been deleted
+    assert_eq!(std::fs::read_dir(&tmp)?.count(), 0);
+    Ok(())
+}
@@ -59,6 +59,21 @@ fn test_iter_nodes_from() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_successors_rev() -> Result<()> {
+    let bvgraph = BvGraph::with_basename("tests/data/cnr-2000")
+        .endianness::<BE>()
+        .load()?;
+
+    for node_id in 0..1000 {
+        let mut succ = bvgraph.successors(node_id).collect::<Vec<_>>();
+        succ.reverse();
+        assert_eq!(bvgraph.successors_rev(node_id).collect::<Vec<_>>(), succ);
+    }
+
+    Ok(())
+}
+
 #[test]
 fn test_split_iter_bv() -> Result<()> {
     let bvgraph_seq = BvGraphSeq::with_basename("tests/data/cnr-2000")
@@ -113,6 +128,28 @@ fn test_split_iter_arc_list() -> Result<()> {
     test_split_iter(&Left(arc_list_graph))
 }
 
+#[test]
+fn test_split_iter_tiny() -> Result<()> {
+    // More parts than nodes: the last parts must be empty
+    for arcs in [vec![], vec![(0, 0)], vec![(0, 1), (2, 4), (4, 0)]] {
+        let num_nodes = arcs.iter().map(|&(s, d)| s.max(d) + 1).max().unwrap_or(0);
+        test_split_iter(&Left(ArcListGraph::new(num_nodes, arcs.iter().copied())))?;
+
+        let mut graph = VecGraph::<()>::empty(num_nodes);
+        for &(src, dst) in &arcs {
+            graph.add_arc(src, dst);
+        }
+        let mut nodes = vec![];
+        for lender in graph.split_iter(10) {
+            for_![(node, _succ) in lender {
+                nodes.push(node);
+            }];
+        }
+        assert_eq!(nodes, (0..num_nodes).collect::<Vec<_>>());
+    }
+    Ok(())
+}
+
 fn test_split_iter<'a, S: SequentialGraph + SplitLabeling>(g: &'a S) -> anyhow::Result<()>
 where
     <S as SplitLabeling>::SplitLender<'a>: Clone,
@@ -130,3 +167,55 @@ where
     assert!(iter.next().is_none(), "Too few nodes in split_iter");
     Ok(())
 }
+
+#[test]
+fn test_arcs() -> Result<()> {
+    let arcs = vec![(0, 1), (0, 2), (1, 2), (2, 0), (2, 1), (4, 0)];
+    let mut g = VecGraph::from_arc_list(arcs.iter().copied());
+    g.add_node(5);
+    let g = Left(g);
+    assert_eq!(g.arcs().collect::<Vec<_>>(), arcs);
+
+    let labeled = VecGraph::from_labeled_arc_list(
+        arcs.iter()
+            .enumerate()
+            .map(|(i, &(src, dst))| (src, dst, i)),
+    );
+    assert_eq!(
+        labeled.labeled_arcs().collect::<Vec<_>>(),
+        arcs.iter()
+            .enumerate()
+            .map(|(i, &(src, dst))| (src, dst, i))
+            .collect::<Vec<_>>()
+    );
+
+    let bvgraph = BvGraph::with_basename("tests/data/cnr-2000")
+        .endianness::<BE>()
+        .load()?;
+    let mut num_arcs = 0;
+    let mut last = None;
+    for arc in bvgraph.arcs() {
+        assert!(last < Some(arc));
+        last = Some(arc);
+        num_arcs += 1;
+    }
+    assert_eq!(num_arcs, bvgraph.num_arcs());
+    Ok(())
+}
+
+#[test]
+fn test_decode_successors_into() -> Result<()> {
+    let bvgraph = BvGraph::with_basename("tests/data/cnr-2000")
+        .endianness::<BE>()
+        .load()?;
+
+    // The buffer is reused, and stale content must be overwritten
+    let mut buf = vec![usize::MAX; 1000];
+    for node_id in 0..bvgraph.num_nodes() {
+        let count = bvgraph.decode_successors_into(node_id, &mut buf);
+        assert_eq!(count, bvgraph.outdegree(node_id));
+        assert_eq!(buf, bvgraph.successors(node_id).collect::<Vec<_>>());
+    }
+
+    Ok(())
+}
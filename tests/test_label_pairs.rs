@@ -0,0 +1,82 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+#![cfg(feature = "cli")]
+
+use anyhow::Result;
+use std::collections::HashMap;
+use webgraph::cli::main as cli_main;
+
+#[test]
+fn test_label_pairs() -> Result<()> {
+    // The labels are the multiplicities minus one
+    let arcs = [(0, 1), (0, 1), (1, 2), (1, 2), (1, 2), (2, 0), (0, 2)];
+    let tmp_dir = tempfile::tempdir()?;
+    let basename = tmp_dir.path().join("multi");
+    let basename = basename.to_str().unwrap();
+    let arcs_path = tmp_dir.path().join("arcs.csv");
+    std::fs::write(
+        &arcs_path,
+        arcs.iter()
+            .map(|(src, dst)| format!("{},{}\n", src, dst))
+            .collect::<String>(),
+    )?;
+    cli_main(vec![
+        "webgraph",
+        "build",
+        "bvgraph",
+        arcs_path.to_str().unwrap(),
+        basename,
+        "--num-nodes",
+        "3",
+        "--exact",
+        "--multigraph",
+    ])?;
+    cli_main(vec!["webgraph", "build", "ef", basename])?;
+    cli_main(vec!["webgraph", "build", "label-ef", basename])?;
+
+    let output = tmp_dir.path().join("pairs.tsv");
+    cli_main(vec![
+        "webgraph",
+        "analyze",
+        "label-pairs",
+        basename,
+        "--max-pairs",
+        "100",
+        "-o",
+        output.to_str().unwrap(),
+    ])?;
+
+    // Brute force over all pairs of distinct arcs
+    let mut labels = HashMap::new();
+    for arc in arcs {
+        *labels.entry(arc).or_insert(0_u64) += 1;
+    }
+    labels.values_mut().for_each(|count| *count -= 1);
+    let mut expected = HashMap::new();
+    for (&(_, v), &first) in &labels {
+        for (&(x, _), &second) in &labels {
+            if x == v {
+                *expected.entry((first, second)).or_insert(0_u64) += 1;
+            }
+        }
+    }
+
+    let mut pairs = HashMap::new();
+    let content = std::fs::read_to_string(&output)?;
+    let lines = content.lines().collect::<Vec<_>>();
+    for line in &lines {
+        let fields = line
+            .split('\t')
+            .map(|field| field.parse::<u64>())
+            .collect::<Result<Vec<_>, _>>()?;
+        pairs.insert((fields[0], fields[1]), fields[2]);
+    }
+    assert_eq!(pairs, expected);
+    // The most frequent pair comes first
+    assert_eq!(lines[0], "0\t0\t2");
+    Ok(())
+}
@@ -0,0 +1,176 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use anyhow::Result;
+use dsi_bitstream::prelude::*;
+use dsi_progress_logger::prelude::*;
+use epserde::prelude::*;
+use lender::*;
+use std::collections::VecDeque;
+use webgraph::algo::distances::landmarks::{self, LandmarkSelection, Oracle};
+use webgraph::algo::distances::PairDistances;
+use webgraph::graphs::vec_graph::VecGraph;
+use webgraph::prelude::*;
+
+/// Returns the distances from `src`, with `usize::MAX` for unreachable nodes.
+fn distances(graph: &impl RandomAccessGraph, src: usize) -> Vec<usize> {
+    let mut dist = vec![usize::MAX; graph.num_nodes()];
+    let mut queue = VecDeque::from([src]);
+    dist[src] = 0;
+    while let Some(node) = queue.pop_front() {
+        for succ in graph.successors(node) {
+            if dist[succ] == usize::MAX {
+                dist[succ] = dist[node] + 1;
+                queue.push_back(succ);
+            }
+        }
+    }
+    dist
+}
+
+/// Checks the bounds of `oracle` against exact distances on a sample of
+/// pairs.
+fn check_oracle(
+    graph: &impl RandomAccessGraph,
+    transpose: &impl RandomAccessGraph,
+    oracle: &Oracle,
+    num_pairs: usize,
+) {
+    let num_nodes = graph.num_nodes();
+    let from = oracle
+        .landmarks()
+        .iter()
+        .map(|&l| distances(graph, l))
+        .collect::<Vec<_>>();
+    let to = oracle
+        .landmarks()
+        .iter()
+        .map(|&l| distances(transpose, l))
+        .collect::<Vec<_>>();
+    let mut state = PairDistances::new(num_nodes);
+    for i in 0..num_pairs {
+        let src = (i * 7919) % num_nodes;
+        let dst = (i * 104729 + 17) % num_nodes;
+        let exact = state.bfs(graph, src, dst);
+        let bound = oracle.upper_bound(src, dst);
+        match exact {
+            None => assert_eq!(bound, None, "{} -> {}", src, dst),
+            Some(exact) => {
+                let bound = bound.unwrap_or(usize::MAX);
+                assert!(bound >= exact, "{} -> {}: {} < {}", src, dst, bound, exact);
+                // A landmark on a shortest path gives the exact distance
+                let on_path = (0..from.len()).any(|l| {
+                    to[l][src] != usize::MAX
+                        && from[l][dst] != usize::MAX
+                        && to[l][src] + from[l][dst] == exact
+                });
+                if on_path {
+                    assert_eq!(bound, exact, "{} -> {}", src, dst);
+                }
+            }
+        }
+    }
+    // Distances to and from landmarks are exact
+    for (l, &landmark) in oracle.landmarks().iter().enumerate() {
+        for node in (0..num_nodes).step_by(num_nodes.div_ceil(100)) {
+            let expected = (from[l][node] != usize::MAX).then_some(from[l][node]);
+            assert_eq!(oracle.upper_bound(landmark, node), expected);
+            assert_eq!(oracle.from_landmark(l, node), expected);
+        }
+    }
+}
+
+#[test]
+fn test_landmarks_small() -> Result<()> {
+    // A directed cycle 0 -> ... -> 9 with a chord 0 -> 5, and an
+    // isolated pair 10 -> 11
+    let mut arcs = (0..10).map(|i| (i, (i + 1) % 10)).collect::<Vec<_>>();
+    arcs.push((0, 5));
+    arcs.push((10, 11));
+    let graph = Left(VecGraph::from_arc_list(arcs.iter().copied()));
+    let transpose = Left(VecGraph::from_arc_list(arcs.iter().map(|&(x, y)| (y, x))));
+
+    for selection in [LandmarkSelection::Degree, LandmarkSelection::Coverage] {
+        let oracle = landmarks::build(
+            &graph,
+            Some(&transpose),
+            3,
+            selection,
+            &mut ProgressLogger::default(),
+        );
+        assert_eq!(oracle.landmarks().len(), 3);
+        assert!(!oracle.symmetric());
+        // Eccentricities are at most 9, so at most four bits are needed
+        assert!(oracle.bit_width() <= 4);
+        check_oracle(&graph, &transpose, &oracle, 144);
+    }
+
+    // Coverage must pick a landmark in the isolated pair
+    let oracle = landmarks::build(
+        &graph,
+        Some(&transpose),
+        2,
+        LandmarkSelection::Coverage,
+        &mut ProgressLogger::default(),
+    );
+    assert_eq!(oracle.landmarks()[0], 0);
+    assert!(oracle.landmarks()[1] >= 10);
+    assert_eq!(oracle.upper_bound(10, 11), Some(1));
+    assert_eq!(oracle.upper_bound(11, 10), None);
+
+    // Symmetric graph, with more landmarks than nodes
+    let symm = Left(VecGraph::from_arc_list(
+        arcs.iter().flat_map(|&(x, y)| [(x, y), (y, x)]),
+    ));
+    let oracle = landmarks::build(
+        &symm,
+        None,
+        100,
+        LandmarkSelection::Degree,
+        &mut ProgressLogger::default(),
+    );
+    assert!(oracle.symmetric());
+    assert_eq!(oracle.landmarks().len(), 12);
+    check_oracle(&symm, &symm, &oracle, 144);
+    Ok(())
+}
+
+#[test]
+fn test_landmarks_cnr_2000() -> Result<()> {
+    let graph = BvGraph::with_basename("tests/data/cnr-2000")
+        .endianness::<BE>()
+        .load()?;
+    let mut transpose = VecGraph::empty(graph.num_nodes());
+    for_!((node, succ) in graph.iter() {
+        for s in succ {
+            transpose.add_arc(s, node);
+        }
+    });
+    let transpose = Left(transpose);
+    let graph = Left(VecGraph::from_lender(graph.iter()));
+
+    let oracle = landmarks::build(
+        &graph,
+        Some(&transpose),
+        8,
+        LandmarkSelection::Coverage,
+        &mut ProgressLogger::default(),
+    );
+    check_oracle(&graph, &transpose, &oracle, 200);
+
+    // Round trip through ε-serde
+    let tmp_dir = tempfile::tempdir()?;
+    let path = tmp_dir.path().join("cnr-2000.lmk");
+    oracle.store(&path)?;
+    let loaded = <Oracle>::load_full(&path)?;
+    assert_eq!(loaded.landmarks(), oracle.landmarks());
+    for i in 0..1000 {
+        let src = (i * 7919) % graph.num_nodes();
+        let dst = (i * 104729 + 17) % graph.num_nodes();
+        assert_eq!(loaded.upper_bound(src, dst), oracle.upper_bound(src, dst));
+    }
+    Ok(())
+}
@@ -0,0 +1,90 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+#![cfg(feature = "cli")]
+
+use anyhow::Result;
+use dsi_bitstream::prelude::*;
+use lender::*;
+use webgraph::cli::main as cli_main;
+use webgraph::graphs::random::ErdosRenyi;
+use webgraph::graphs::vec_graph::VecGraph;
+use webgraph::prelude::*;
+
+#[test]
+fn test_line_graph_cnr_2000() -> Result<()> {
+    let graph = BvGraph::with_basename("tests/data/cnr-2000").load()?;
+    let line_graph = LineGraph::new(BvGraphSeq::with_basename("tests/data/cnr-2000").load()?);
+    assert_eq!(line_graph.num_nodes() as u64, graph.num_arcs());
+
+    let mut indegrees = vec![0_u64; graph.num_nodes()];
+    for_!((_, succ) in graph.iter() {
+        for dst in succ {
+            indegrees[dst] += 1;
+        }
+    });
+    let expected = (0..graph.num_nodes())
+        .map(|node| indegrees[node] * graph.outdegree(node) as u64)
+        .sum::<u64>();
+    assert_eq!(line_graph.num_arcs(), expected);
+
+    // The successors of u → v are the arcs of v
+    for src in [0, 1, 1000, 100_000, graph.num_nodes() - 1] {
+        for (rank, dst) in graph.successors(src).enumerate() {
+            let id = line_graph.arc_id(src, rank);
+            let mut iter = line_graph.iter_from(id);
+            let (node, succ) = iter.next().unwrap().into_pair();
+            assert_eq!(node, id);
+            let expected = (0..graph.outdegree(dst))
+                .map(|rank| line_graph.arc_id(dst, rank))
+                .collect::<Vec<_>>();
+            assert_eq!(succ.into_iter().collect::<Vec<_>>(), expected);
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_line_graph_cli() -> Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    let graph = ErdosRenyi::new(50, 0.1, 0);
+    let basename = tmp_dir.path().join("graph");
+    BvComp::single_thread::<BE, _>(&basename, graph.iter(), CompFlags::default(), true, None)?;
+    let src = basename.to_str().unwrap();
+    let line_graph_basename = tmp_dir.path().join("line-graph");
+    let dst = line_graph_basename.to_str().unwrap();
+
+    let expected = LineGraph::new(&graph);
+    assert!(cli_main([
+        "webgraph",
+        "transform",
+        "line-graph",
+        src,
+        dst,
+        "--max-arcs",
+        &(expected.num_arcs() - 1).to_string(),
+    ])
+    .is_err());
+    cli_main([
+        "webgraph",
+        "transform",
+        "line-graph",
+        src,
+        dst,
+        "--max-arcs",
+        &(expected.num_arcs() - 1).to_string(),
+        "--force",
+    ])?;
+
+    let line_graph = BvGraphSeq::with_basename(&line_graph_basename)
+        .endianness::<BE>()
+        .load()?;
+    assert_eq!(line_graph.num_nodes(), expected.num_nodes());
+    assert_eq!(line_graph.num_arcs_hint(), Some(expected.num_arcs()));
+    let expected = Left(VecGraph::from_lender(expected.iter()));
+    webgraph::assert_graph_eq!(expected, line_graph);
+    Ok(())
+}
@@ -6,7 +6,9 @@ use std::path::PathBuf;
 use sux::traits::bit_field_slice::BitFieldSlice;
 use tempfile::Builder;
 use webgraph::cli::main as cli_main;
-use webgraph::graphs::bvgraph::{GRAPH_EXTENSION, OFFSETS_EXTENSION, PROPERTIES_EXTENSION};
+use webgraph::graphs::bvgraph::{
+    is_loopless, is_symmetric, GRAPH_EXTENSION, OFFSETS_EXTENSION, PROPERTIES_EXTENSION,
+};
 use webgraph::prelude::JavaPermutation;
 use webgraph::traits::{RandomAccessGraph, RandomAccessLabeling, SequentialLabeling};
 
@@ -47,6 +49,8 @@ fn llp_pipeline() -> Result<()> {
         "--permutation",
         &format!("{}.bfs", basename),
     ])?;
+    assert_eq!(is_loopless(format!("{}-simple", basename))?, Some(true));
+    assert_eq!(is_symmetric(format!("{}-simple", basename))?, Some(true));
     log::info!("Step 4: Create the Elias Fano for the simplified graph");
     cli_main(vec![
         "webgraph",
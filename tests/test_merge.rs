@@ -0,0 +1,73 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+#![cfg(feature = "cli")]
+
+use anyhow::Result;
+use dsi_bitstream::prelude::*;
+use lender::*;
+use webgraph::cli::main as cli_main;
+use webgraph::graphs::random::ErdosRenyi;
+use webgraph::prelude::*;
+
+#[test]
+fn test_merge() -> Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    let mut basenames = vec![];
+    let mut arcs = vec![];
+    for (i, num_nodes) in [100, 80, 100, 30, 120].into_iter().enumerate() {
+        let graph = ErdosRenyi::new(num_nodes, 0.05, i as u64);
+        for_!((src, succ) in graph.iter() {
+            arcs.extend(succ.into_iter().map(|dst| (src, dst)));
+        });
+        let basename = tmp_dir.path().join(format!("graph-{}", i));
+        BvComp::single_thread::<BE, _>(&basename, graph.iter(), CompFlags::default(), true, None)?;
+        basenames.push(basename.to_str().unwrap().to_owned());
+    }
+
+    // the reference union, computed by sorting
+    arcs.sort_unstable();
+    arcs.dedup();
+
+    let merged_basename = tmp_dir.path().join("merged");
+    let mut args = vec![
+        "webgraph".to_owned(),
+        "transform".to_owned(),
+        "merge".to_owned(),
+        merged_basename.to_str().unwrap().to_owned(),
+    ];
+    args.extend(basenames.iter().cloned());
+    cli_main(args)?;
+
+    let merged = BvGraphSeq::with_basename(&merged_basename)
+        .endianness::<BE>()
+        .load()?;
+    assert_eq!(merged.num_nodes(), 120);
+    assert_eq!(merged.num_arcs_hint(), Some(arcs.len() as u64));
+    let mut merged_arcs = vec![];
+    for_!((src, succ) in merged.iter() {
+        merged_arcs.extend(succ.into_iter().map(|dst| (src, dst)));
+    });
+    assert_eq!(merged_arcs, arcs);
+
+    // the in-memory wrapper gives the same result
+    let graphs = basenames
+        .iter()
+        .map(|basename| {
+            BvGraphSeq::with_basename(basename)
+                .endianness::<BE>()
+                .load()
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let merged_graph = MergeGraphs::new(graphs);
+    assert_eq!(merged_graph.num_nodes(), 120);
+    let mut wrapper_arcs = vec![];
+    for_!((src, succ) in merged_graph.iter() {
+        wrapper_arcs.extend(succ.into_iter().map(|dst| (src, dst)));
+    });
+    assert_eq!(wrapper_arcs, arcs);
+    Ok(())
+}
@@ -0,0 +1,62 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+#![cfg(feature = "cli")]
+
+use anyhow::Result;
+use webgraph::cli::main as cli_main;
+use webgraph::cli::IntVectorFormat;
+use webgraph::prelude::*;
+
+#[test]
+fn test_node_bits_cnr_2000() -> Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    let basename = tmp_dir.path().join("cnr-2000");
+    for ext in ["graph", "properties", "offsets"] {
+        std::fs::copy(
+            format!("tests/data/cnr-2000.{}", ext),
+            basename.with_extension(ext),
+        )?;
+    }
+    let graph = BvGraphSeq::with_basename(&basename).load()?;
+    let graph_bits = 8 * std::fs::metadata(basename.with_extension("graph"))?.len();
+
+    let run = |output: &std::path::Path, fmt: &str| {
+        cli_main(vec![
+            "webgraph",
+            "analyze",
+            "node-bits",
+            basename.to_str().unwrap(),
+            "-o",
+            output.to_str().unwrap(),
+            "--fmt",
+            fmt,
+        ])
+    };
+
+    let from_offsets = tmp_dir.path().join("bits.txt");
+    run(&from_offsets, "ascii")?;
+    let bits = IntVectorFormat::Ascii.load(&from_offsets)?;
+    assert_eq!(bits.len(), graph.num_nodes());
+    // The graph file is padded to a whole number of words
+    let total = bits.iter().map(|&b| b as u64).sum::<u64>();
+    assert!(total <= graph_bits);
+    assert!(graph_bits - total < 64);
+
+    // Without the offsets file the graph is decoded
+    std::fs::remove_file(basename.with_extension("offsets"))?;
+    for (fmt, format) in [
+        ("ascii", IntVectorFormat::Ascii),
+        ("epserde", IntVectorFormat::Epserde),
+        ("bitfieldvec", IntVectorFormat::Bitfieldvec),
+    ] {
+        let decoded = tmp_dir.path().join(format!("bits.{}", fmt));
+        run(&decoded, fmt)?;
+        assert_eq!(format.load(&decoded)?, bits);
+    }
+
+    Ok(())
+}
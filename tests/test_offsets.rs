@@ -11,6 +11,7 @@ use epserde::prelude::*;
 use lender::*;
 use std::io::prelude::*;
 use sux::prelude::*;
+use webgraph::assert_graph_eq;
 use webgraph::prelude::*;
 
 #[test]
@@ -65,6 +66,36 @@ fn test_offsets() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_node_offset() -> Result<()> {
+    let graph = BvGraph::with_basename("tests/data/cnr-2000")
+        .endianness::<BE>()
+        .load()?;
+    let static_graph = BvGraph::with_basename("tests/data/cnr-2000")
+        .endianness::<BE>()
+        .dispatch::<Static>()
+        .load()?;
+    let ef_offsets =
+        <webgraph::graphs::bvgraph::EF>::mmap("tests/data/cnr-2000.ef", deser::Flags::empty())?;
+
+    let num_nodes = graph.num_nodes();
+    for node in 0..=num_nodes {
+        assert_eq!(graph.node_offset(node), ef_offsets.get(node) as u64);
+        assert_eq!(static_graph.node_offset(node), ef_offsets.get(node) as u64);
+    }
+    let mut total = 0;
+    for (node, (offset, _)) in graph.offset_deg_iter().enumerate() {
+        assert_eq!(graph.node_offset(node), offset);
+        total += graph.node_bit_len(node);
+    }
+    assert_eq!(total, graph.node_offset(num_nodes));
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        graph.node_bit_len(num_nodes)
+    }));
+    assert!(result.is_err());
+    Ok(())
+}
+
 #[test]
 fn test_offsets_as_slice() -> Result<()> {
     // load the graph
@@ -76,12 +107,88 @@ fn test_offsets_as_slice() -> Result<()> {
         .load()?
         .offsets_to_slice();
 
-    graph0
-        .iter()
-        .zip(graph1.iter())
-        .for_each(|((s, a), (t, b))| {
-            assert_eq!(s, t);
-            itertools::assert_equal(a, b);
-        });
+    assert_graph_eq!(graph0, graph1);
+    Ok(())
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn test_ef_from_offsets() -> Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    let basename = tmp_dir.path().join("cnr-2000");
+    for extension in [PROPERTIES_EXTENSION, GRAPH_EXTENSION, OFFSETS_EXTENSION] {
+        std::fs::copy(
+            std::path::Path::new("tests/data/cnr-2000").with_extension(extension),
+            basename.with_extension(extension),
+        )?;
+    }
+    webgraph::cli::build::ef_from_offsets::ef_from_offsets(&basename)?;
+
+    let expected =
+        <webgraph::graphs::bvgraph::EF>::mmap("tests/data/cnr-2000.ef", deser::Flags::empty())?;
+    let found = <webgraph::graphs::bvgraph::EF>::mmap(
+        basename.with_extension(EF_EXTENSION),
+        deser::Flags::empty(),
+    )?;
+    assert_eq!(expected.len(), found.len());
+    for i in 0..expected.len() {
+        assert_eq!(expected.get(i), found.get(i));
+    }
+
+    // Offsets beyond the end of a truncated graph are rejected, also by
+    // build ef, which shares the translation
+    let graph_path = basename.with_extension(GRAPH_EXTENSION);
+    let graph_len = std::fs::metadata(&graph_path)?.len();
+    std::fs::OpenOptions::new()
+        .write(true)
+        .open(&graph_path)?
+        .set_len(graph_len / 2)?;
+    assert!(webgraph::cli::build::ef_from_offsets::ef_from_offsets(&basename).is_err());
+    assert!(
+        webgraph::cli::main(vec!["webgraph", "build", "ef", basename.to_str().unwrap()]).is_err()
+    );
+
+    // Without the offsets file there is nothing to translate
+    std::fs::remove_file(basename.with_extension(OFFSETS_EXTENSION))?;
+    assert!(webgraph::cli::build::ef_from_offsets::ef_from_offsets(&basename).is_err());
+    Ok(())
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn test_select_params_dispatch() -> Result<()> {
+    let graph = BvGraph::with_basename("tests/data/cnr-2000")
+        .endianness::<BE>()
+        .load()?;
+    let tmp_dir = tempfile::tempdir()?;
+    let basename = tmp_dir.path().join("cnr-2000");
+    for extension in [PROPERTIES_EXTENSION, GRAPH_EXTENSION, OFFSETS_EXTENSION] {
+        std::fs::copy(
+            std::path::Path::new("tests/data/cnr-2000").with_extension(extension),
+            basename.with_extension(extension),
+        )?;
+    }
+
+    for params in SelectParams::ALL {
+        webgraph::cli::build::ef_from_offsets::ef_from_offsets_with_params(&basename, params)?;
+        assert_eq!(SelectParams::load(&basename, EF_SELECT_PROPERTY)?, params);
+        let found = BvGraph::with_basename(&basename)
+            .endianness::<BE>()
+            .load()?;
+        for node in (0..graph.num_nodes()).step_by(97) {
+            assert_eq!(
+                found.successors(node).collect::<Vec<_>>(),
+                graph.successors(node).collect::<Vec<_>>()
+            );
+        }
+        let found = BvGraph::with_basename(&basename)
+            .endianness::<BE>()
+            .mode::<File>()
+            .load()?;
+        assert_eq!(
+            found.successors(graph.num_nodes() - 1).collect::<Vec<_>>(),
+            graph.successors(graph.num_nodes() - 1).collect::<Vec<_>>()
+        );
+    }
     Ok(())
 }
@@ -0,0 +1,46 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+#![cfg(feature = "cli")]
+
+use anyhow::Result;
+use webgraph::cli::main as cli_main;
+use webgraph::graphs::bvgraph::{DEG_CUMUL_EXTENSION, EF_EXTENSION, PROPERTIES_EXTENSION};
+
+const TEST_GRAPH: &str = "tests/data/cnr-2000";
+
+#[test]
+fn test_build_ef_dcf_while_compressing() -> Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    for options in [vec![], vec!["--sort-successors", "--dedup"]] {
+        let basename = tmp_dir.path().join("cnr-2000");
+        let dst = basename.display().to_string();
+        let mut args = vec!["webgraph", "to", "bvgraph", "--build-ef", "--build-dcf"];
+        args.extend(options);
+        args.extend([TEST_GRAPH, &dst]);
+        cli_main(args)?;
+
+        let ef = std::fs::read(basename.with_extension(EF_EXTENSION))?;
+        let dcf = std::fs::read(basename.with_extension(DEG_CUMUL_EXTENSION))?;
+        let properties = std::fs::read_to_string(basename.with_extension(PROPERTIES_EXTENSION))?;
+
+        // The files must be identical to those built afterwards
+        std::fs::remove_file(basename.with_extension(EF_EXTENSION))?;
+        std::fs::remove_file(basename.with_extension(DEG_CUMUL_EXTENSION))?;
+        cli_main(vec!["webgraph", "build", "ef", &dst])?;
+        cli_main(vec!["webgraph", "build", "dcf", &dst])?;
+        assert_eq!(ef, std::fs::read(basename.with_extension(EF_EXTENSION))?);
+        assert_eq!(
+            dcf,
+            std::fs::read(basename.with_extension(DEG_CUMUL_EXTENSION))?
+        );
+        assert_eq!(
+            properties,
+            std::fs::read_to_string(basename.with_extension(PROPERTIES_EXTENSION))?
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,113 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use anyhow::Result;
+use dsi_bitstream::prelude::*;
+use lender::*;
+use webgraph::graphs::vec_graph::VecGraph;
+use webgraph::prelude::*;
+
+/// Checks that the size hint of an iterator is consistent with its length,
+/// and returns the length.
+fn check_size_hint(iter: impl Iterator) -> usize {
+    let (lower, upper) = iter.size_hint();
+    let exact = successors_len_hint(&iter);
+    let len = iter.count();
+    assert!(lower <= len, "{} > {}", lower, len);
+    if let Some(upper) = upper {
+        assert!(upper >= len, "{} < {}", upper, len);
+    }
+    if let Some(exact) = exact {
+        assert_eq!(exact, len);
+    }
+    len
+}
+
+/// Checks that the outdegree of every node is the length of its label
+/// iterator, and that the outdegree hint is an upper bound.
+fn check_outdegrees<G: RandomAccessLabeling>(graph: &G) {
+    for node in 0..graph.num_nodes() {
+        let len = check_size_hint(graph.labels(node).into_iter());
+        assert_eq!(graph.outdegree(node), len, "node {}", node);
+        assert!(graph.outdegree_hint(node) >= len, "node {}", node);
+    }
+}
+
+/// Checks the size hints of the successors returned by a sequential graph.
+fn check_lender<G: SequentialLabeling>(graph: &G) {
+    for_!((_node, succ) in graph.iter() {
+        check_size_hint(succ.into_iter());
+    });
+}
+
+#[test]
+fn test_outdegree_adapters() -> Result<()> {
+    let graph = BvGraph::with_basename("tests/data/cnr-2000")
+        .endianness::<BE>()
+        .load()?;
+    check_outdegrees(&graph);
+    // The hint is exact for graphs with constant-time outdegree
+    for node in 0..graph.num_nodes() {
+        assert_eq!(graph.outdegree_hint(node), graph.outdegree(node));
+    }
+
+    let zip = Zip(&graph, &graph);
+    check_outdegrees(&zip);
+    check_outdegrees(&Left(&zip));
+    check_outdegrees(&Right(&zip));
+    check_outdegrees(&UnitLabelGraph(&graph));
+
+    // Using the graph as its own transpose, the hint doubles the outdegree
+    let view = SymmetricView::new(&graph, &graph);
+    check_outdegrees(&view);
+    for node in 0..graph.num_nodes() {
+        assert_eq!(view.outdegree_hint(node), 2 * graph.outdegree(node));
+    }
+
+    check_lender(&graph);
+    check_lender(&NoSelfLoopsGraph(&graph));
+    // Union graphs require lenders that do not borrow from the graphs
+    let vec_graph = Left(VecGraph::from_lender(&graph));
+    check_lender(&UnionGraph(vec_graph.clone(), vec_graph));
+    let perm = (0..graph.num_nodes()).rev().collect::<Vec<_>>();
+    check_lender(&PermutedGraph {
+        graph: &graph,
+        perm: &perm,
+    });
+    Ok(())
+}
+
+#[test]
+fn test_outdegree_symmetric_view() {
+    let arcs = [(0, 1), (0, 2), (1, 0), (1, 1), (2, 3), (3, 3), (4, 0)];
+    let graph = Left(VecGraph::from_arc_list(arcs));
+    let transpose = Left(VecGraph::from_arc_list(arcs.map(|(x, y)| (y, x))));
+    let view = SymmetricView::new(&graph, &transpose);
+    check_outdegrees(&graph);
+    check_outdegrees(&view);
+    assert_eq!(view.outdegree(0), 3);
+    assert_eq!(view.outdegree_hint(0), 4);
+    assert_eq!(view.outdegree(1), 2);
+    assert_eq!(view.outdegree_hint(1), 4);
+}
+
+#[test]
+fn test_degrees_iter() -> Result<()> {
+    let graph = BvGraph::with_basename("tests/data/cnr-2000")
+        .endianness::<BE>()
+        .load()?;
+    let seq_graph = BvGraphSeq::with_basename("tests/data/cnr-2000")
+        .endianness::<BE>()
+        .load()?;
+    let mut iter = seq_graph.degrees_iter();
+    assert_eq!(iter.len(), graph.num_nodes());
+    for node in 0..graph.num_nodes() {
+        assert_eq!(iter.next(), Some(graph.outdegree(node)), "node {}", node);
+    }
+    assert_eq!(iter.len(), 0);
+    assert_eq!(iter.next(), None);
+    Ok(())
+}
@@ -11,7 +11,7 @@ use std::path::PathBuf;
 use anyhow::Result;
 use dsi_bitstream::prelude::*;
 use dsi_progress_logger::prelude::*;
-use lender::*;
+use webgraph::assert_graph_eq;
 use webgraph::prelude::*;
 
 #[test]
@@ -73,25 +73,7 @@ fn _test_par_bvcomp(basename: &str) -> Result<()> {
             webgraph::graphs::bvgraph::sequential::BvGraphSeq::with_basename(&tmp_basename)
                 .endianness::<BE>()
                 .load()?;
-        let mut iter = comp_graph.iter();
-
-        let mut pr = ProgressLogger::default();
-        pr.display_memory(true)
-            .item_name("node")
-            .expected_updates(Some(graph.num_nodes()));
-        pr.start("Checking that the newly compressed graph is equivalent to the original one...");
-
-        let mut iter_nodes = graph.iter();
-        while let Some((node, succ_iter)) = iter_nodes.next() {
-            let (new_node, new_succ_iter) = iter.next().unwrap();
-            assert_eq!(node, new_node);
-            let succ = succ_iter.collect::<Vec<_>>();
-            let new_succ = new_succ_iter.collect::<Vec<_>>();
-            assert_eq!(succ, new_succ, "Node {} differs", node);
-            pr.light_update();
-        }
-
-        pr.done();
+        assert_graph_eq!(graph, comp_graph);
 
         let offsets_path = tmp_basename.with_extension(OFFSETS_EXTENSION);
         let mut offsets_reader =
@@ -124,3 +106,89 @@ fn _test_par_bvcomp(basename: &str) -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_compress_vec_graph() -> Result<()> {
+    let graph = Left(VecGraph::from_arc_list([
+        (0, 1),
+        (0, 3),
+        (1, 2),
+        (2, 0),
+        (2, 4),
+        (3, 4),
+        (4, 1),
+        (5, 0),
+    ]));
+    let tmp_dir = tempfile::tempdir()?;
+    let basename = tmp_dir.path().join("vec");
+    for thread_num in 1..4 {
+        webgraph::graphs::bvgraph::compress::<BE, _>(
+            &graph,
+            &basename,
+            CompFlags::default(),
+            &rayon::ThreadPoolBuilder::new()
+                .num_threads(thread_num)
+                .build()
+                .expect("Failed to create thread pool"),
+            &mut ProgressLogger::default(),
+        )?;
+
+        let comp_graph = BvGraphSeq::with_basename(&basename)
+            .endianness::<BE>()
+            .load()?;
+        assert_eq!(comp_graph.num_nodes(), graph.num_nodes());
+        assert_eq!(comp_graph.num_arcs_hint(), Some(8));
+        assert_graph_eq!(graph, comp_graph);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_compress_sorted_vec() -> Result<()> {
+    use lender::*;
+    use webgraph::graphs::arc_list_graph::ArcListGraph;
+    let mut arcs = vec![];
+    for src in 0..100_usize {
+        for i in 0..src % 7 {
+            arcs.push((src, (src * 13 + i * 17) % 100, src + i));
+        }
+    }
+    arcs.sort();
+    let tmp_dir = tempfile::tempdir()?;
+    let basename = tmp_dir.path().join("sorted");
+    let expected = ArcListGraph::new(100, arcs.iter().map(|&(src, dst, _)| (src, dst)));
+    let graph =
+        ArcListGraph::from_sorted_vec(100, arcs.iter().map(|&(src, dst, _)| (src, dst)).collect());
+    for thread_num in 1..5 {
+        webgraph::graphs::bvgraph::compress::<BE, _>(
+            &Left(graph.clone()),
+            &basename,
+            CompFlags::default(),
+            &rayon::ThreadPoolBuilder::new()
+                .num_threads(thread_num)
+                .build()
+                .expect("Failed to create thread pool"),
+            &mut ProgressLogger::default(),
+        )?;
+
+        let comp_graph = BvGraphSeq::with_basename(&basename)
+            .endianness::<BE>()
+            .load()?;
+        assert_eq!(comp_graph.num_arcs_hint(), Some(arcs.len() as u64));
+        assert_graph_eq!(Left(expected.clone()), comp_graph);
+    }
+
+    // Labeled triples are split at the same node boundaries
+    let labeled = ArcListGraph::from_sorted_labeled_vec(100, arcs.clone());
+    let expected = ArcListGraph::new_labeled(100, arcs.iter().copied()).collect_labeled();
+    for how_many in [1, 3, 8, 200] {
+        let mut result = vec![];
+        for mut part in labeled.split_iter(how_many) {
+            while let Some((node, succ)) = part.next() {
+                result.push((node, succ.collect::<Vec<_>>()));
+            }
+        }
+        assert_eq!(result, expected);
+    }
+    Ok(())
+}
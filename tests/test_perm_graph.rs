@@ -48,3 +48,52 @@ fn test_permuted() {
         ]
     );
 }
+
+#[test]
+fn test_materialize_equals_sorted() -> anyhow::Result<()> {
+    use dsi_bitstream::prelude::BE;
+    use dsi_progress_logger::prelude::*;
+    use rand::{rngs::SmallRng, seq::SliceRandom, SeedableRng};
+
+    let graph = BvGraphSeq::with_basename("tests/data/cnr-2000")
+        .endianness::<BE>()
+        .load()?;
+    let mut perm = (0..graph.num_nodes()).collect::<Vec<_>>();
+    perm.shuffle(&mut SmallRng::seed_from_u64(0));
+
+    let thread_pool = rayon::ThreadPoolBuilder::new().num_threads(4).build()?;
+    let tmp_dir = tempfile::tempdir()?;
+
+    let sorted = webgraph::transform::permute(&graph, &perm, 1_000_000)?;
+    let sorted_basename = tmp_dir.path().join("sorted");
+    compress::<BE, _>(
+        &sorted,
+        &sorted_basename,
+        CompFlags::default(),
+        &thread_pool,
+        &mut ProgressLogger::default(),
+    )?;
+
+    let permuted = PermutedGraph {
+        graph: &graph,
+        perm: &perm,
+    };
+    let csr = permuted.materialize()?;
+    assert_eq!(Some(csr.num_arcs()), graph.num_arcs_hint());
+    let csr_basename = tmp_dir.path().join("csr");
+    compress::<BE, _>(
+        &csr,
+        &csr_basename,
+        CompFlags::default(),
+        &thread_pool,
+        &mut ProgressLogger::default(),
+    )?;
+
+    for extension in [GRAPH_EXTENSION, OFFSETS_EXTENSION] {
+        assert_eq!(
+            std::fs::read(sorted_basename.with_extension(extension))?,
+            std::fs::read(csr_basename.with_extension(extension))?
+        );
+    }
+    Ok(())
+}
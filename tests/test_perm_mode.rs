@@ -0,0 +1,173 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+#![cfg(feature = "cli")]
+
+use anyhow::Result;
+use dsi_bitstream::prelude::*;
+use epserde::ser::Serialize;
+use std::path::Path;
+use webgraph::cli::main as cli_main;
+use webgraph::graphs::vec_graph::VecGraph;
+use webgraph::prelude::*;
+
+const NUM_NODES: usize = 10;
+
+/// Writes a permutation in binary big-endian format.
+fn write_perm(path: &Path, perm: &[usize]) -> Result<()> {
+    let bytes = perm
+        .iter()
+        .flat_map(|&x| (x as u64).to_be_bytes())
+        .collect::<Vec<_>>();
+    std::fs::write(path, bytes)?;
+    Ok(())
+}
+
+fn run(args: &[&str]) -> Result<()> {
+    cli_main(["webgraph"].iter().chain(args))
+}
+
+#[test]
+fn test_perm_mode() -> Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    let basename = tmp_dir.path().join("graph");
+    let src = basename.to_str().unwrap();
+    let mut graph = VecGraph::empty(NUM_NODES);
+    for node in 0..NUM_NODES {
+        graph.add_arc(node, (node + 1) % NUM_NODES);
+        graph.add_arc(node, (node * 3) % NUM_NODES);
+    }
+    let graph = Left(graph);
+    BvComp::single_thread::<BE, _>(&basename, &graph, CompFlags::default(), true, None)?;
+    run(&["build", "ef", "--fsync", src])?;
+    run(&["build", "dcf", src])?;
+
+    let exact = (0..NUM_NODES).rev().collect::<Vec<_>>();
+    let short = vec![3, 2, 1, 0];
+    let long = exact.iter().copied().chain([10, 11]).collect::<Vec<_>>();
+
+    for (name, perm) in [("exact", &exact), ("short", &short), ("long", &long)] {
+        let perm_path = tmp_dir.path().join(name);
+        write_perm(&perm_path, perm)?;
+        let perm_path = perm_path.to_str().unwrap();
+        let dst_path = tmp_dir.path().join(format!("{}-dst", name));
+        let dst = dst_path.to_str().unwrap();
+
+        for lenient in [false, true] {
+            let mut commands = vec![
+                vec!["to", "bvgraph", src, dst, "--permutation", perm_path],
+                vec![
+                    "transform",
+                    "simplify",
+                    src,
+                    dst,
+                    "--permutation",
+                    perm_path,
+                ],
+                vec!["analyze", "gap-cost", src, "--perm", perm_path],
+                vec!["perm", "score", src, perm_path],
+            ];
+            if lenient {
+                commands.iter_mut().for_each(|args| args.push("--lenient"));
+            }
+            for args in commands {
+                let result = run(&args);
+                if name == "exact" || lenient {
+                    result?;
+                } else {
+                    assert!(result.is_err(), "{:?} should fail", args);
+                }
+            }
+        }
+
+        // Check the graph recompressed in lenient mode
+        run(&[
+            "to",
+            "bvgraph",
+            src,
+            dst,
+            "--permutation",
+            perm_path,
+            "--lenient",
+        ])?;
+        run(&["build", "ef", dst])?;
+        let permuted = BvGraph::with_basename(&dst_path)
+            .endianness::<BE>()
+            .load()?;
+        let fitted = (0..NUM_NODES)
+            .map(|node| perm.get(node).copied().unwrap_or(node))
+            .collect::<Vec<_>>();
+        let mut expected = VecGraph::empty(NUM_NODES);
+        for node in 0..NUM_NODES {
+            for succ in graph.successors(node) {
+                expected.add_arc(fitted[node], fitted[succ]);
+            }
+        }
+        let expected = Left(expected);
+        for node in 0..NUM_NODES {
+            assert_eq!(
+                permuted.successors(node).collect::<Vec<_>>(),
+                expected.successors(node).into_iter().collect::<Vec<_>>(),
+                "{} node {}",
+                name,
+                node
+            );
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_llp_labels_length() -> Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    let basename = tmp_dir.path().join("graph");
+    let src = basename.to_str().unwrap();
+    let mut graph = VecGraph::empty(NUM_NODES);
+    for node in 0..NUM_NODES {
+        graph.add_arc(node, (node + 1) % NUM_NODES);
+        graph.add_arc((node + 1) % NUM_NODES, node);
+    }
+    BvComp::single_thread::<BE, _>(&basename, &Left(graph), CompFlags::default(), true, None)?;
+    run(&["build", "ef", src])?;
+    run(&["build", "dcf", src])?;
+
+    let checkpoint_dir = tmp_dir.path().join("checkpoint");
+    let checkpoint = checkpoint_dir.to_str().unwrap();
+    let perm_path = tmp_dir.path().join("llp");
+    let perm = perm_path.to_str().unwrap();
+    let llp = |resume: bool| {
+        let mut args = vec![
+            "run",
+            "llp",
+            src,
+            perm,
+            "-g",
+            "-0,-1",
+            "--checkpoint-combine",
+            checkpoint,
+        ];
+        if resume {
+            args.push("--resume");
+        }
+        run(&args)
+    };
+    llp(false)?;
+    llp(true)?;
+
+    // Labels of the wrong length are rejected before being applied
+    let labels_path = checkpoint_dir.join("labels_0.bin");
+    let labels = std::fs::read(&labels_path)?;
+    vec![0_usize; NUM_NODES - 1].store(&labels_path)?;
+    let err = llp(true).unwrap_err();
+    assert!(
+        format!("{:#}", err).contains("has length 9, but the graph has 10 nodes"),
+        "{:#}",
+        err
+    );
+    std::fs::write(&labels_path, labels)?;
+    llp(true)?;
+    Ok(())
+}
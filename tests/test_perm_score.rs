@@ -0,0 +1,99 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+#![cfg(feature = "cli")]
+
+use anyhow::Result;
+use dsi_bitstream::prelude::*;
+use std::path::Path;
+use std::process::Command;
+use webgraph::cli::main as cli_main;
+use webgraph::graphs::vec_graph::VecGraph;
+use webgraph::prelude::*;
+
+/// Returns the metrics printed by `perm score` as (name, value) pairs.
+fn score(basename: &str, perm: &Path) -> Result<Vec<(String, String)>> {
+    let output = Command::new(env!("CARGO_BIN_EXE_webgraph"))
+        .args(["perm", "score", basename, perm.to_str().unwrap()])
+        .output()?;
+    assert!(output.status.success(), "{:?}", output);
+    Ok(String::from_utf8(output.stdout)?
+        .lines()
+        .filter_map(|line| line.split_once(": "))
+        .map(|(name, value)| (name.to_owned(), value.to_owned()))
+        .collect())
+}
+
+/// Returns the value of a metric as a number.
+fn metric(metrics: &[(String, String)], name: &str) -> f64 {
+    let value = &metrics.iter().find(|(n, _)| n == name).unwrap().1;
+    value.trim_end_matches(" bits").parse().unwrap()
+}
+
+/// Returns the length in bits of the bitstream of a compressed graph.
+fn bitstream_len(basename: impl AsRef<Path>) -> Result<u64> {
+    let graph = BvGraphSeq::with_basename(basename)
+        .endianness::<BE>()
+        .load()?;
+    let mut iter = graph.offset_deg_iter();
+    iter.by_ref().for_each(drop);
+    Ok(iter.get_pos())
+}
+
+#[test]
+fn test_perm_score() -> Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    let basename = tmp_dir.path().join("graph");
+    let arcs = [(0, 1), (0, 3), (1, 0), (2, 0), (2, 1), (2, 3), (3, 2)];
+    let graph = Left(VecGraph::from_arc_list(arcs));
+    BvComp::single_thread::<BE, _>(&basename, &graph, CompFlags::default(), true, None)?;
+    let bits = bitstream_len(&basename)?;
+    let basename = basename.to_str().unwrap();
+    cli_main(vec!["webgraph", "build", "ef", basename])?;
+    cli_main(vec!["webgraph", "build", "dcf", basename])?;
+
+    // The costs are computed by hand in test_gap_cost
+    let identity_path = tmp_dir.path().join("identity.perm");
+    let identity = (0..4_u64).flat_map(u64::to_be_bytes).collect::<Vec<_>>();
+    std::fs::write(&identity_path, identity)?;
+    let metrics = score(basename, &identity_path)?;
+    // Gaps: 3 − 1; 1 − 0, 3 − 1
+    assert_eq!(metric(&metrics, "Average gap"), 5.0 / 3.0);
+    assert_eq!(metric(&metrics, "Log-gap cost"), 6.0);
+    assert_eq!(metric(&metrics, "Log-gap cost per arc"), 6.0 / 7.0);
+    // The estimate uses the default codes, as the compressor
+    assert_eq!(metric(&metrics, "Estimated size"), bits as f64);
+    assert_eq!(
+        metric(&metrics, "Estimated bits per arc"),
+        bits as f64 / 7.0
+    );
+
+    // Reversing the nodes (x ↦ 3 − x)
+    let reverse_path = tmp_dir.path().join("reverse.perm");
+    let reverse = (0..4_u64)
+        .rev()
+        .flat_map(u64::to_be_bytes)
+        .collect::<Vec<_>>();
+    std::fs::write(&reverse_path, reverse)?;
+    let reversed = Left(VecGraph::from_arc_list(arcs.map(|(x, y)| (3 - x, 3 - y))));
+    let reversed_basename = tmp_dir.path().join("reversed");
+    BvComp::single_thread::<BE, _>(
+        &reversed_basename,
+        &reversed,
+        CompFlags::default(),
+        true,
+        None,
+    )?;
+    let metrics = score(basename, &reverse_path)?;
+    // Gaps: 2 − 0; 2 − 0, 3 − 2
+    assert_eq!(metric(&metrics, "Average gap"), 5.0 / 3.0);
+    assert_eq!(metric(&metrics, "Log-gap cost"), 7.0);
+    assert_eq!(
+        metric(&metrics, "Estimated size"),
+        bitstream_len(&reversed_basename)? as f64
+    );
+    Ok(())
+}
@@ -0,0 +1,77 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+#![cfg(feature = "cli")]
+
+use anyhow::Result;
+use dsi_bitstream::prelude::BE;
+use lender::*;
+use webgraph::cli::main as cli_main;
+use webgraph::graphs::bvgraph::GRAPH_EXTENSION;
+use webgraph::prelude::*;
+
+#[test]
+fn test_pipeline_import_transpose() -> Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    let arcs = tmp_dir.path().join("arcs.tsv");
+    std::fs::write(&arcs, "0\t1\n0\t2\n1\t2\n2\t0\n3\t1\n")?;
+    let basename = tmp_dir.path().join("graph");
+    let transposed = tmp_dir.path().join("graph-t");
+    let plan = tmp_dir.path().join("plan.toml");
+    std::fs::write(
+        &plan,
+        format!(
+            r#"threads = 2
+
+[[stage]]
+name = "import"
+command = "from arcs"
+input = {:?}
+args = ["--num-nodes", "4", "--exact", "--separator", "\t", {:?}]
+
+[[stage]]
+name = "transpose"
+command = "transform transpose"
+args = [{:?}, {:?}]
+"#,
+            arcs, basename, basename, transposed
+        ),
+    )?;
+    let plan = plan.display().to_string();
+
+    let check_transposed = || -> Result<()> {
+        let graph = BvGraphSeq::with_basename(&transposed)
+            .endianness::<BE>()
+            .load()?;
+        let mut successors = vec![];
+        for_![(_, succ) in graph.iter() {
+            successors.push(succ.into_iter().collect::<Vec<_>>());
+        }];
+        assert_eq!(successors, vec![vec![2], vec![0, 3], vec![0, 1], vec![]]);
+        Ok(())
+    };
+
+    cli_main(vec!["webgraph", "pipeline", &plan])?;
+    check_transposed()?;
+    let markers = tmp_dir.path().join("plan.stages");
+    assert!(markers.join("import.done").exists());
+    assert!(markers.join("transpose.done").exists());
+
+    // Resume after deleting the marker of the second stage: only the
+    // second stage must be run again
+    let imported = std::fs::metadata(basename.with_extension(GRAPH_EXTENSION))?.modified()?;
+    std::fs::remove_file(markers.join("transpose.done"))?;
+    std::fs::remove_file(transposed.with_extension(GRAPH_EXTENSION))?;
+    cli_main(vec!["webgraph", "pipeline", &plan])?;
+    check_transposed()?;
+    assert!(markers.join("transpose.done").exists());
+    assert_eq!(
+        std::fs::metadata(basename.with_extension(GRAPH_EXTENSION))?.modified()?,
+        imported
+    );
+
+    Ok(())
+}
@@ -0,0 +1,54 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use anyhow::Result;
+use lender::*;
+use webgraph::assert_graph_eq;
+use webgraph::prelude::*;
+
+#[test]
+fn test_prefetch() -> Result<()> {
+    let graph = BvGraphSeq::with_basename("tests/data/cnr-2000").load()?;
+    // A small chunk size to cross many chunk boundaries
+    for bytes in [1, 1 << 12, 1 << 16, 1 << 30] {
+        let prefetch = BvGraphSeq::with_basename("tests/data/cnr-2000")
+            .prefetch(bytes)
+            .load()?;
+        assert_graph_eq!(graph, prefetch);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_prefetch_static_split() -> Result<()> {
+    let graph = BvGraphSeq::with_basename("tests/data/cnr-2000")
+        .dispatch::<Static>()
+        .load()?;
+    let prefetch = BvGraphSeq::with_basename("tests/data/cnr-2000")
+        .dispatch::<Static>()
+        .flags(MemoryFlags::SEQUENTIAL)
+        .prefetch(1 << 12)
+        .load()?;
+    assert_graph_eq!(graph, prefetch);
+
+    // Splits start in the middle of the bitstream
+    let mut num_arcs = 0;
+    for lender in prefetch.split_iter(4) {
+        for_!( (_, succ) in lender {
+            num_arcs += succ.count() as u64;
+        });
+    }
+    assert_eq!(Some(num_arcs), graph.num_arcs_hint());
+    Ok(())
+}
+
+#[test]
+fn test_prefetch_zero() {
+    assert!(BvGraphSeq::with_basename("tests/data/cnr-2000")
+        .prefetch(0)
+        .load()
+        .is_err());
+}
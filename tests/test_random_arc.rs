@@ -0,0 +1,59 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use rand::rngs::SmallRng;
+use rand::SeedableRng;
+use std::collections::HashMap;
+use sux::dict::EliasFanoBuilder;
+use sux::rank_sel::{SelectAdaptConst, SelectZeroAdaptConst};
+use webgraph::graphs::vec_graph::VecGraph;
+use webgraph::prelude::*;
+
+fn deg_cumul(graph: &impl RandomAccessGraph) -> DCF {
+    let mut efb = EliasFanoBuilder::new(graph.num_nodes() + 1, graph.num_arcs() as usize);
+    let mut cumul = 0;
+    efb.push(0);
+    for node in 0..graph.num_nodes() {
+        cumul += graph.outdegree(node);
+        efb.push(cumul);
+    }
+    unsafe {
+        efb.build().map_high_bits(|bits| {
+            SelectZeroAdaptConst::<_, _, 12, 4>::new(SelectAdaptConst::<_, _, 12, 4>::new(bits))
+        })
+    }
+}
+
+#[test]
+fn test_random_arc() {
+    // Nodes 1, 3, and 5 have no successors
+    let arcs = [(0, 1), (0, 4), (2, 0), (2, 3), (2, 5), (4, 4), (6, 0)];
+    let graph = Left(VecGraph::from_arc_list(arcs));
+    let deg_cumul = deg_cumul(&graph);
+
+    let mut rng = SmallRng::seed_from_u64(0);
+    let samples = 70_000;
+    let mut counts = HashMap::new();
+    for _ in 0..samples {
+        *counts
+            .entry(graph.random_arc(&deg_cumul, &mut rng))
+            .or_insert(0) += 1;
+    }
+    assert_eq!(counts.len(), arcs.len());
+    for arc in arcs {
+        // The expected count is 10000, with a standard deviation below 100
+        let count = counts[&arc];
+        assert!((9_500..10_500).contains(&count), "{:?}: {}", arc, count);
+    }
+}
+
+#[test]
+#[should_panic(expected = "The graph has no arcs")]
+fn test_random_arc_no_arcs() {
+    let graph = Left(VecGraph::<()>::empty(3));
+    let deg_cumul = deg_cumul(&graph);
+    graph.random_arc(&deg_cumul, &mut SmallRng::seed_from_u64(0));
+}
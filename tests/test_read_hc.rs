@@ -6,7 +6,7 @@
  */
 
 use anyhow::Result;
-use lender::*;
+use webgraph::assert_graph_eq;
 use webgraph::graphs::bvgraph;
 
 #[test]
@@ -14,16 +14,7 @@ fn test_hc() -> Result<()> {
     let norm = bvgraph::BvGraphSeq::with_basename("tests/data/cnr-2000").load()?;
     let hc = bvgraph::BvGraphSeq::with_basename("tests/data/cnr-2000-hc").load()?;
 
-    let mut norm_iter = norm.into_lender();
-    let mut hc_iter = hc.into_lender();
-
-    while let Some((norm_node, norm_succ)) = norm_iter.next() {
-        let succ = norm_succ.collect::<Vec<_>>();
-        let (hc_node, hc_succ) = hc_iter.next().unwrap();
-        let h_succ = hc_succ.collect::<Vec<_>>();
-        assert_eq!(norm_node, hc_node,);
-        assert_eq!(succ, h_succ,);
-    }
+    assert_graph_eq!(norm, hc);
 
     Ok(())
 }
@@ -0,0 +1,64 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use anyhow::Result;
+use dsi_bitstream::prelude::*;
+use dsi_progress_logger::prelude::*;
+use std::time::Instant;
+use webgraph::prelude::*;
+
+#[test]
+fn test_ref_selection() -> Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    let graph = BvGraphSeq::with_basename("tests/data/cnr-2000")
+        .endianness::<BE>()
+        .load()?;
+
+    let mut sizes = vec![];
+    for reference_selection in [
+        ReferenceSelection::Greedy,
+        ReferenceSelection::BestOfWindow,
+        ReferenceSelection::None,
+    ] {
+        let basename = tmp_dir.path().join(reference_selection.as_str());
+        let start = Instant::now();
+        BvComp::single_thread::<BE, _>(
+            &basename,
+            &graph,
+            CompFlags {
+                reference_selection,
+                ..Default::default()
+            },
+            true,
+            None,
+        )?;
+        let elapsed = start.elapsed();
+        let size = std::fs::metadata(basename.with_extension(GRAPH_EXTENSION))?.len();
+        log::info!(
+            "{}: {} bytes, {:.3} seconds",
+            reference_selection,
+            size,
+            elapsed.as_secs_f64()
+        );
+        sizes.push(size);
+
+        let properties = std::fs::read_to_string(basename.with_extension(PROPERTIES_EXTENSION))?;
+        assert!(properties.contains(&format!("referenceselection={}", reference_selection)));
+
+        let compressed = BvGraphSeq::with_basename(&basename)
+            .endianness::<BE>()
+            .load()?;
+        assert_eq!(
+            first_differing_node(&graph, &compressed, &mut ProgressLogger::default()),
+            None
+        );
+    }
+
+    let (greedy, best_of_window, none) = (sizes[0], sizes[1], sizes[2]);
+    assert!(best_of_window <= greedy);
+    assert!(best_of_window < none);
+    Ok(())
+}
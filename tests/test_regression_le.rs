@@ -9,6 +9,8 @@
 use anyhow::Result;
 #[cfg(feature = "fuzz")]
 use webgraph::fuzz::bvcomp_and_read::*;
+#[cfg(feature = "fuzz")]
+use webgraph::prelude::ReferenceSelection;
 
 #[test]
 #[cfg(feature = "fuzz")]
@@ -23,6 +25,7 @@ fn test_regression_le() -> Result<()> {
             min_interval_length: 248,
             compression_window: 255,
             max_ref_count: 255,
+            reference_selection: ReferenceSelection::default(),
         },
         edges: vec![(2, 187)],
     };
@@ -0,0 +1,136 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use anyhow::Result;
+use dsi_bitstream::prelude::*;
+use webgraph::graphs::bvgraph::sequential::{RESIDUALS_BATCH_SIZE, RESIDUALS_BATCH_THRESHOLD};
+use webgraph::graphs::vec_graph::VecGraph;
+use webgraph::prelude::*;
+use Code::{Delta, Gamma, Unary, Zeta};
+
+/// Encodes the given residuals using the residual code of `cf`.
+fn encode(values: &[u64], cf: &CompFlags) -> Vec<u64> {
+    let mut words: Vec<u64> = vec![];
+    {
+        let mut encoder = <DynCodesEncoder<BE, _>>::new(
+            <BufBitWriter<BE, _>>::new(MemWordWriterVec::new(&mut words)),
+            cf,
+        );
+        for &value in values {
+            encoder.write_residual(value).unwrap();
+        }
+        encoder.flush().unwrap();
+    }
+    // Padding, so that the reader can safely read ahead
+    words.extend([0; 4]);
+    words
+}
+
+/// Decodes the residuals in batches of the given sizes, interleaving single
+/// reads, and checks them against `values`.
+fn check(decoder: &mut impl Decode, values: &[u64], batches: &[usize]) {
+    let mut pos = 0;
+    for &batch in batches {
+        let mut buf = vec![u64::MAX; batch + 1];
+        assert_eq!(decoder.read_residuals_into(&mut buf[..batch]), batch);
+        assert_eq!(&buf[..batch], &values[pos..pos + batch]);
+        // Nothing is written past the end of the slice
+        assert_eq!(buf[batch], u64::MAX);
+        pos += batch;
+        if pos < values.len() {
+            assert_eq!(decoder.read_residual(), values[pos]);
+            pos += 1;
+        }
+    }
+    assert_eq!(pos, values.len());
+}
+
+#[test]
+fn test_read_residuals_into() -> Result<()> {
+    let values = (0..200_u64).map(|x| (x * x) % 97).collect::<Vec<_>>();
+    // Empty batches, batches around the batch size of the sequential
+    // iterator, and a final batch exhausting the values
+    let batches = [0, 1, 0, 63, 64, 65, 1];
+    for residuals in [
+        Unary,
+        Gamma,
+        Delta,
+        Zeta { k: 1 },
+        Zeta { k: 2 },
+        Zeta { k: 3 },
+        Zeta { k: 5 },
+    ] {
+        let cf = CompFlags {
+            residuals,
+            ..Default::default()
+        };
+        let words = encode(&values, &cf);
+        let words_32: &[u32] = unsafe { words.align_to().1 };
+        let mut decoder = DynCodesDecoder::new(
+            <BufBitReader<BE, _>>::new(MemWordReader::new(words_32)),
+            &cf,
+        )?;
+        check(&mut decoder, &values, &batches);
+    }
+
+    // Default codes for the constant decoder
+    let cf = CompFlags::default();
+    let words = encode(&values, &cf);
+    let words_32: &[u32] = unsafe { words.align_to().1 };
+    let mut decoder = <ConstCodesDecoder<BE, _>>::new(
+        <BufBitReader<BE, _>>::new(MemWordReader::new(words_32)),
+        &cf,
+    )?;
+    check(&mut decoder, &values, &batches);
+    Ok(())
+}
+
+#[test]
+fn test_batched_successors() -> Result<()> {
+    // Nodes whose number of residuals (excluding the first one) straddles
+    // the batch threshold and the batch size; successors are spaced so that
+    // there are no intervals
+    let counts = [
+        0,
+        1,
+        RESIDUALS_BATCH_THRESHOLD - 1,
+        RESIDUALS_BATCH_THRESHOLD,
+        RESIDUALS_BATCH_THRESHOLD + 1,
+        RESIDUALS_BATCH_SIZE - 1,
+        RESIDUALS_BATCH_SIZE,
+        RESIDUALS_BATCH_SIZE + 1,
+        2 * RESIDUALS_BATCH_SIZE + 3,
+    ];
+    let num_nodes = 3 * (2 * RESIDUALS_BATCH_SIZE + 4);
+    let mut graph = VecGraph::empty(num_nodes);
+    for (node, &count) in counts.iter().enumerate() {
+        for i in 0..count + 1 {
+            graph.add_arc(node, (node + 3 * i + 1) % num_nodes);
+        }
+    }
+    let graph = Left(graph);
+
+    let tmp_dir = tempfile::tempdir()?;
+    for compression_window in [0, 7] {
+        let basename = tmp_dir.path().join(format!("batches-{compression_window}"));
+        let cf = CompFlags {
+            compression_window,
+            ..Default::default()
+        };
+        BvComp::single_thread::<BE, _>(&basename, &graph, cf, true, None)?;
+
+        let seq_graph = BvGraphSeq::with_basename(&basename)
+            .endianness::<BE>()
+            .load()?;
+        webgraph::assert_graph_eq!(graph, seq_graph);
+        let seq_graph = BvGraphSeq::with_basename(&basename)
+            .endianness::<BE>()
+            .dispatch::<Static>()
+            .load()?;
+        webgraph::assert_graph_eq!(graph, seq_graph);
+    }
+    Ok(())
+}
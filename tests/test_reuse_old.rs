@@ -0,0 +1,150 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+#![cfg(feature = "cli")]
+
+use anyhow::Result;
+use dsi_bitstream::prelude::*;
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+use std::path::Path;
+use sux::bits::BitVec;
+use webgraph::cli::build::ef_from_offsets::ef_from_offsets;
+use webgraph::graphs::vec_graph::VecGraph;
+use webgraph::prelude::*;
+
+/// Returns a random graph with some locality, so that references and
+/// intervals are used.
+fn random_graph(num_nodes: usize, rng: &mut SmallRng) -> Vec<Vec<usize>> {
+    (0..num_nodes)
+        .map(|node| random_successors(node, num_nodes, rng))
+        .collect()
+}
+
+fn random_successors(node: usize, num_nodes: usize, rng: &mut SmallRng) -> Vec<usize> {
+    let mut succ = (0..rng.gen_range(0..20))
+        .map(|_| (node + rng.gen_range(0..50)) % num_nodes)
+        .collect::<Vec<_>>();
+    let start = rng.gen_range(0..num_nodes - 10);
+    succ.extend(start..start + rng.gen_range(0..10));
+    succ.sort();
+    succ.dedup();
+    succ
+}
+
+fn to_graph(lists: &[Vec<usize>]) -> Left<VecGraph> {
+    let mut graph = VecGraph::empty(lists.len());
+    for (node, succ) in lists.iter().enumerate() {
+        for &s in succ {
+            graph.add_arc(node, s);
+        }
+    }
+    Left(graph)
+}
+
+fn assert_same_files(a: &Path, b: &Path) -> Result<()> {
+    for ext in [GRAPH_EXTENSION, OFFSETS_EXTENSION] {
+        assert_eq!(
+            std::fs::read(Basename::new(a).with_ext(ext))?,
+            std::fs::read(Basename::new(b).with_ext(ext))?,
+            "The {} files differ",
+            ext
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_reuse_old() -> Result<()> {
+    let mut rng = SmallRng::seed_from_u64(0);
+    let tmp_dir = tempfile::tempdir()?;
+    let num_nodes = 5000;
+    let old_lists = random_graph(num_nodes, &mut rng);
+    let old_basename = tmp_dir.path().join("old");
+    BvComp::single_thread::<BE, _>(
+        &old_basename,
+        &to_graph(&old_lists),
+        CompFlags::default(),
+        true,
+        None,
+    )?;
+    ef_from_offsets(&old_basename)?;
+
+    // Change 2% of the nodes
+    let mut new_lists = old_lists.clone();
+    let mut changed = BitVec::new(num_nodes);
+    for _ in 0..num_nodes / 50 {
+        let node = rng.gen_range(0..num_nodes);
+        new_lists[node] = random_successors(node, num_nodes, &mut rng);
+        changed.set(node, true);
+    }
+    let new_graph = to_graph(&new_lists);
+
+    let new_basename = tmp_dir.path().join("new");
+    let stats = BvComp::reuse_old::<BE, _, _>(&old_basename, &new_basename, &new_graph, &changed)?;
+    let scratch_basename = tmp_dir.path().join("scratch");
+    let bits = BvComp::single_thread::<BE, _>(
+        &scratch_basename,
+        &new_graph,
+        CompFlags::default(),
+        true,
+        None,
+    )?;
+    assert_same_files(&new_basename, &scratch_basename)?;
+
+    assert_eq!(stats.num_nodes, num_nodes);
+    assert_eq!(stats.num_arcs, new_graph.num_arcs_hint().unwrap());
+    assert_eq!(stats.bits, bits);
+    assert!(stats.copied_nodes < num_nodes);
+    assert!(
+        stats.copied_bits > stats.bits * 3 / 4,
+        "Only {} bits out of {} were copied",
+        stats.copied_bits,
+        stats.bits
+    );
+
+    // Without changes, everything is copied
+    let stats = BvComp::reuse_old::<BE, _, _>(
+        &old_basename,
+        tmp_dir.path().join("same"),
+        &to_graph(&old_lists),
+        &BitVec::new(num_nodes),
+    )?;
+    assert_eq!(stats.copied_nodes, num_nodes);
+    assert_same_files(&old_basename, &tmp_dir.path().join("same"))?;
+
+    // New nodes are compressed from scratch
+    let mut more_lists = new_lists.clone();
+    more_lists.extend(random_graph(100, &mut rng));
+    let more_graph = to_graph(&more_lists);
+    BvComp::reuse_old::<BE, _, _>(
+        &old_basename,
+        tmp_dir.path().join("more"),
+        &more_graph,
+        &changed,
+    )?;
+    BvComp::single_thread::<BE, _>(
+        tmp_dir.path().join("more-scratch"),
+        &more_graph,
+        CompFlags::default(),
+        true,
+        None,
+    )?;
+    assert_same_files(
+        &tmp_dir.path().join("more"),
+        &tmp_dir.path().join("more-scratch"),
+    )?;
+
+    // Unmarked changes are detected
+    assert!(BvComp::reuse_old::<BE, _, _>(
+        &old_basename,
+        tmp_dir.path().join("unmarked"),
+        &new_graph,
+        &BitVec::new(num_nodes),
+    )
+    .is_err());
+    Ok(())
+}
@@ -0,0 +1,189 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+#![cfg(feature = "cli")]
+
+use anyhow::Result;
+use dsi_bitstream::prelude::*;
+use dsi_progress_logger::prelude::*;
+use lender::*;
+use std::fs::File;
+use std::io::BufWriter;
+use webgraph::cli::build::ef::{build_eliasfano, CliArgs as BuildEfArgs};
+use webgraph::cli::build::ef_from_offsets::ef_from_offsets;
+use webgraph::cli::main as cli_main;
+use webgraph::graphs::vec_graph::VecGraph;
+use webgraph::prelude::*;
+
+/// An encoder adding one to the first residual of a given node.
+struct BuggyEncoder<E> {
+    encoder: E,
+    node: usize,
+    bug_node: usize,
+}
+
+impl<E: Encode> Encode for BuggyEncoder<E> {
+    type Error = E::Error;
+    fn start_node(&mut self, node: usize) -> Result<usize, Self::Error> {
+        self.node = node;
+        self.encoder.start_node(node)
+    }
+    fn write_outdegree(&mut self, value: u64) -> Result<usize, Self::Error> {
+        self.encoder.write_outdegree(value)
+    }
+    fn write_reference_offset(&mut self, value: u64) -> Result<usize, Self::Error> {
+        self.encoder.write_reference_offset(value)
+    }
+    fn write_block_count(&mut self, value: u64) -> Result<usize, Self::Error> {
+        self.encoder.write_block_count(value)
+    }
+    fn write_block(&mut self, value: u64) -> Result<usize, Self::Error> {
+        self.encoder.write_block(value)
+    }
+    fn write_interval_count(&mut self, value: u64) -> Result<usize, Self::Error> {
+        self.encoder.write_interval_count(value)
+    }
+    fn write_interval_start(&mut self, value: u64) -> Result<usize, Self::Error> {
+        self.encoder.write_interval_start(value)
+    }
+    fn write_interval_len(&mut self, value: u64) -> Result<usize, Self::Error> {
+        self.encoder.write_interval_len(value)
+    }
+    fn write_first_residual(&mut self, value: u64) -> Result<usize, Self::Error> {
+        let value = value + (self.node == self.bug_node) as u64;
+        self.encoder.write_first_residual(value)
+    }
+    fn write_residual(&mut self, value: u64) -> Result<usize, Self::Error> {
+        self.encoder.write_residual(value)
+    }
+    fn flush(&mut self) -> Result<usize, Self::Error> {
+        self.encoder.flush()
+    }
+    fn end_node(&mut self, node: usize) -> Result<usize, Self::Error> {
+        self.encoder.end_node(node)
+    }
+}
+
+impl<E: EncodeAndEstimate> EncodeAndEstimate for BuggyEncoder<E> {
+    type Estimator<'a>
+        = E::Estimator<'a>
+    where
+        Self: 'a;
+    fn estimator(&mut self) -> Self::Estimator<'_> {
+        self.encoder.estimator()
+    }
+}
+
+#[test]
+fn test_diff_node_trace() -> Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    let graph = Left(VecGraph::from_arc_list([
+        (0, 1),
+        (0, 5),
+        (0, 9),
+        (1, 2),
+        (1, 7),
+        (2, 0),
+        (2, 3),
+        (2, 8),
+        (3, 1),
+        (3, 6),
+        (4, 0),
+        (4, 2),
+        (4, 11),
+        (5, 3),
+        (5, 10),
+        (6, 1),
+        (6, 5),
+        (7, 0),
+        (8, 4),
+        (9, 2),
+        (10, 6),
+        (11, 7),
+    ]));
+    let bug_node = 4;
+
+    let correct = tmp_dir.path().join("correct");
+    BvComp::single_thread::<BE, _>(&correct, &graph, CompFlags::default(), true, None)?;
+    ef_from_offsets(&correct)?;
+
+    let buggy = tmp_dir.path().join("buggy");
+    let flags = CompFlags::default();
+    let writer = <BufBitWriter<BE, _>>::new(<WordAdapter<usize, _>>::new(BufWriter::new(
+        File::create(buggy.with_extension(GRAPH_EXTENSION))?,
+    )));
+    let mut bvcomp = BvComp::new(
+        BuggyEncoder {
+            encoder: DynCodesEncoder::new(writer, &flags),
+            node: 0,
+            bug_node,
+        },
+        flags.compression_window,
+        flags.max_ref_count,
+        flags.min_interval_length,
+        0,
+    );
+    for_!((_node, succ) in graph.iter() {
+        bvcomp.push(succ)?;
+    });
+    bvcomp.flush()?;
+    std::fs::copy(
+        correct.with_extension(PROPERTIES_EXTENSION),
+        buggy.with_extension(PROPERTIES_EXTENSION),
+    )?;
+    build_eliasfano::<BE>(BuildEfArgs {
+        src: buggy.clone(),
+        n: None,
+        select_params: SelectParams::default(),
+    })?;
+
+    let correct_seq = BvGraphSeq::with_basename(&correct)
+        .endianness::<BE>()
+        .load()?;
+    let buggy_seq = BvGraphSeq::with_basename(&buggy)
+        .endianness::<BE>()
+        .load()?;
+    let mut pl = ProgressLogger::default();
+    assert_eq!(
+        first_differing_node(&correct_seq, &correct_seq, &mut pl),
+        None
+    );
+    assert_eq!(
+        first_differing_node(&correct_seq, &buggy_seq, &mut pl),
+        Some(bug_node)
+    );
+
+    let correct = BvGraph::with_basename(&correct).endianness::<BE>().load()?;
+    let buggy = BvGraph::with_basename(&buggy).endianness::<BE>().load()?;
+    assert_eq!(
+        diff_node_trace(&correct, &buggy, 0).first_difference(),
+        None
+    );
+    let diff = diff_node_trace(&correct, &buggy, bug_node);
+    assert_eq!(diff.a[0], (Component::Outdegree, 3));
+    assert_eq!(
+        diff.first_differing_component(),
+        Some(Component::FirstResidual)
+    );
+    let i = diff.first_difference().unwrap();
+    assert_eq!(diff.a[i].1 + 1, diff.b[i].1);
+    assert!(diff.to_string().contains("* first_residual"));
+    Ok(())
+}
+
+#[test]
+fn test_check_roundtrip() -> Result<()> {
+    cli_main(vec![
+        "webgraph",
+        "check",
+        "roundtrip",
+        "tests/data/cnr-2000",
+        "--compression-window",
+        "3",
+        "--residuals",
+        "delta",
+    ])
+}
@@ -0,0 +1,72 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+#![cfg(feature = "cli")]
+
+use anyhow::Result;
+use webgraph::cli::main as cli_main;
+use webgraph::graphs::bvgraph::{PROPERTIES_EXTENSION, STATS_EXTENSION};
+
+const TEST_GRAPH: &str = "tests/data/cnr-2000";
+
+/// Returns the integer value of a field of the .stats file.
+fn field(json: &str, name: &str) -> u64 {
+    let key = format!("\"{}\": ", name);
+    let start = json.find(&key).unwrap() + key.len();
+    let end = start + json[start..].find([',', '\n']).unwrap();
+    json[start..end].parse().unwrap()
+}
+
+#[test]
+fn test_stats_file() -> Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    let basename = tmp_dir.path().join("cnr-2000");
+    let dst = basename.display().to_string();
+    cli_main(vec![
+        "webgraph", "to", "bvgraph", "--stats", TEST_GRAPH, &dst,
+    ])?;
+
+    let json = std::fs::read_to_string(basename.with_extension(STATS_EXTENSION))?;
+    let properties = std::fs::read_to_string(basename.with_extension(PROPERTIES_EXTENSION))?;
+    let property = |name: &str| -> u64 {
+        properties
+            .lines()
+            .find_map(|line| line.strip_prefix(&format!("{}=", name)))
+            .unwrap()
+            .parse()
+            .unwrap()
+    };
+
+    assert_eq!(field(&json, "version"), 1);
+    assert_eq!(field(&json, "nodes"), property("nodes"));
+    assert_eq!(field(&json, "arcs"), property("arcs"));
+    let total_bits = field(&json, "total_bits");
+    assert_eq!(total_bits, property("length"));
+    let components = [
+        "outdegrees",
+        "references",
+        "blocks",
+        "intervals",
+        "residuals",
+    ]
+    .iter()
+    .map(|name| field(&json, name))
+    .sum::<u64>();
+    assert_eq!(components, total_bits);
+    assert!(json.contains("\"bits_per_arc\": "));
+
+    // Without the option, no .stats file is written
+    let other = tmp_dir.path().join("other");
+    cli_main(vec![
+        "webgraph",
+        "to",
+        "bvgraph",
+        TEST_GRAPH,
+        &other.display().to_string(),
+    ])?;
+    assert!(!other.with_extension(STATS_EXTENSION).exists());
+    Ok(())
+}
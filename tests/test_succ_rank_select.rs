@@ -0,0 +1,93 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+#![cfg(feature = "cli")]
+
+use anyhow::Result;
+use dsi_bitstream::prelude::*;
+use webgraph::cli::build::ef_from_offsets::ef_from_offsets;
+use webgraph::graphs::vec_graph::VecGraph;
+use webgraph::prelude::*;
+
+/// Checks rank and select on a node against the materialized successor list.
+fn check_node(graph: &impl RandomAccessGraph, node: usize) {
+    let succ = graph.successors(node).into_iter().collect::<Vec<_>>();
+    for k in 0..succ.len() + 2 {
+        assert_eq!(
+            graph.succ_select(node, k),
+            succ.get(k).copied(),
+            "select({node}, {k})"
+        );
+    }
+    for v in 0..graph.num_nodes() + 2 {
+        assert_eq!(
+            graph.succ_rank(node, v),
+            succ.partition_point(|&x| x < v),
+            "rank({node}, {v})"
+        );
+    }
+    assert_eq!(graph.succ_rank(node, usize::MAX), succ.len());
+}
+
+#[test]
+fn test_rank_select_kinds() -> Result<()> {
+    // Interval-dominated: long runs with a few residuals
+    let intervals = (10..60).chain(100..130).chain([5, 70, 200]);
+    // Residual-dominated: scattered successors
+    let residuals = (0..40).map(|i| 3 + i * 5 + i % 3).collect::<Vec<_>>();
+    // Reference-dominated: most of the previous list, plus a few extras
+    let references = residuals
+        .iter()
+        .copied()
+        .filter(|&x| x % 7 != 0)
+        .chain([1, 2, 205])
+        .collect::<Vec<_>>();
+    // Intervals and residuals on top of a reference
+    let mixed = (10..60).chain(100..140).chain([7, 8, 150, 201]);
+
+    let mut arcs = vec![];
+    arcs.extend(intervals.map(|x| (0, x)));
+    arcs.extend(residuals.iter().map(|&x| (1, x)));
+    arcs.extend(references.iter().map(|&x| (2, x)));
+    arcs.extend(mixed.map(|x| (3, x)));
+    arcs.push((209, 0));
+    let graph = Left(VecGraph::from_arc_list(arcs));
+
+    let tmp_dir = tempfile::tempdir()?;
+    let basename = tmp_dir.path().join("graph");
+    BvComp::single_thread::<BE, _>(&basename, &graph, CompFlags::default(), true, None)?;
+    ef_from_offsets(&basename)?;
+    let bvgraph = BvGraph::with_basename(&basename)
+        .endianness::<BE>()
+        .load()?;
+
+    // Make sure that we are testing a copied list
+    assert_eq!(bvgraph.reference_offset(2), 1);
+
+    for node in 0..bvgraph.num_nodes() {
+        check_node(&bvgraph, node);
+        // The default implementations agree
+        check_node(&graph, node);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_rank_select_cnr_2000() -> Result<()> {
+    let graph = BvGraph::with_basename("tests/data/cnr-2000")
+        .endianness::<BE>()
+        .load()?;
+    for node in (0..graph.num_nodes()).step_by(97) {
+        let succ = graph.successors(node).collect::<Vec<_>>();
+        for (k, &s) in succ.iter().enumerate() {
+            assert_eq!(graph.succ_select(node, k), Some(s));
+            assert_eq!(graph.succ_rank(node, s), k);
+            assert_eq!(graph.succ_rank(node, s + 1), k + 1);
+        }
+        assert_eq!(graph.succ_select(node, succ.len()), None);
+    }
+    Ok(())
+}
@@ -0,0 +1,73 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use anyhow::Result;
+use dsi_bitstream::prelude::*;
+use webgraph::graphs::arc_list_graph::ArcListGraph;
+use webgraph::graphs::vec_graph::VecGraph;
+use webgraph::prelude::*;
+
+#[test]
+fn test_successors_sorted() -> Result<()> {
+    let graph = BvGraph::with_basename("tests/data/cnr-2000").load()?;
+    assert!(graph.successors_sorted());
+    assert!(ArcCountCheck::new(&graph, graph.num_arcs()).successors_sorted());
+    let seq_graph = BvGraphSeq::with_basename("tests/data/cnr-2000").load()?;
+    assert!(seq_graph.successors_sorted());
+
+    let vec_graph = Left(VecGraph::from_arc_list([(0, 2), (0, 1), (1, 0)]));
+    assert!(vec_graph.successors_sorted());
+    assert!(NoSelfLoopsGraph(&vec_graph).successors_sorted());
+
+    let perm = vec![2, 0, 1];
+    let permuted = PermutedGraph {
+        graph: &vec_graph,
+        perm: &perm,
+    };
+    assert!(!permuted.successors_sorted());
+    assert!(!SortedSuccessorsGraph::new(&permuted, false).successors_sorted());
+    assert!(SortedSuccessorsGraph::new(&permuted, true).successors_sorted());
+
+    let arc_list = Left(ArcListGraph::new(2, [(0, 1), (0, 1)]));
+    assert!(!arc_list.successors_sorted());
+    Ok(())
+}
+
+#[test]
+fn test_has_arc_sorted() {
+    let graph = Left(VecGraph::from_arc_list([(0, 1), (0, 3), (0, 5), (1, 0)]));
+    assert!(graph.has_arc(0, 3));
+    assert!(graph.has_arc(0, 5));
+    assert!(!graph.has_arc(0, 2));
+    assert!(!graph.has_arc(0, 6));
+    assert!(!graph.has_arc(1, 1));
+}
+
+#[test]
+fn test_bvcomp_rejects_unsorted() -> Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    let graph = Left(ArcListGraph::new(3, [(0, 2), (0, 1), (1, 0)]));
+    assert!(!graph.successors_sorted());
+    assert!(BvComp::single_thread::<BE, _>(
+        tmp_dir.path().join("unsorted"),
+        &graph,
+        CompFlags::default(),
+        true,
+        None,
+    )
+    .is_err());
+
+    let sorted = SortedSuccessorsGraph::new(&graph, true);
+    assert!(sorted.successors_sorted());
+    BvComp::single_thread::<BE, _>(
+        tmp_dir.path().join("sorted"),
+        &sorted,
+        CompFlags::default(),
+        true,
+        None,
+    )?;
+    Ok(())
+}
@@ -0,0 +1,115 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+#![cfg(feature = "cli")]
+
+//! End-to-end processing of the empty graph and of a single node with a loop.
+
+use anyhow::Result;
+use clap::{Args, Command, FromArgMatches};
+use dsi_bitstream::prelude::*;
+use lender::*;
+use webgraph::cli::from::arcs::{from_reader, CliArgs};
+use webgraph::cli::main as cli_main;
+use webgraph::prelude::*;
+
+fn arcs(graph: &impl SequentialGraph) -> Vec<(usize, usize)> {
+    let mut arcs = vec![];
+    for_!((src, succ) in graph.iter() {
+        arcs.extend(succ.into_iter().map(|dst| (src, dst)));
+    });
+    arcs
+}
+
+fn check(basename: &str, num_nodes: usize, expected: &[(usize, usize)]) -> Result<()> {
+    let seq_graph = BvGraphSeq::with_basename(basename)
+        .endianness::<BE>()
+        .load()?;
+    assert_eq!(seq_graph.num_nodes(), num_nodes);
+    assert_eq!(arcs(&seq_graph), expected);
+    cli_main(vec!["webgraph", "build", "offsets", basename])?;
+    cli_main(vec!["webgraph", "build", "ef", basename])?;
+    cli_main(vec!["webgraph", "build", "dcf", basename])?;
+    // Loading in memory must work also with an empty bitstream
+    let graph = BvGraph::with_basename(basename)
+        .endianness::<BE>()
+        .mode::<LoadMmap>()
+        .load()?;
+    assert_eq!(graph.num_nodes(), num_nodes);
+    let graph = BvGraph::with_basename(basename).endianness::<BE>().load()?;
+    assert_eq!(graph.num_arcs(), expected.len() as u64);
+    assert_eq!(arcs(&graph), expected);
+    Ok(())
+}
+
+#[test]
+fn test_tiny_graphs() -> Result<()> {
+    for (num_nodes, input, expected) in [(0, "", vec![]), (1, "0,0\n\n", vec![(0, 0)])] {
+        let tmp_dir = tempfile::tempdir()?;
+        let basename = tmp_dir.path().join("graph").display().to_string();
+        let num_nodes_arg = num_nodes.to_string();
+        let matches = CliArgs::augment_args(Command::new("arcs")).try_get_matches_from([
+            "arcs",
+            "--num-nodes",
+            &num_nodes_arg,
+            "--exact",
+            // The input is shorter than the lines to skip
+            "--lines-to-skip",
+            if num_nodes == 0 { "1" } else { "0" },
+            &basename,
+        ])?;
+        from_reader(CliArgs::from_arg_matches(&matches)?, input.as_bytes())?;
+        check(&basename, num_nodes, &expected)?;
+
+        let transposed = format!("{}-t", basename);
+        cli_main(vec![
+            "webgraph",
+            "transform",
+            "transpose",
+            &basename,
+            &transposed,
+        ])?;
+        check(&transposed, num_nodes, &expected)?;
+
+        let bfs = format!("{}.bfs", basename);
+        cli_main(vec!["webgraph", "perm", "bfs", &basename, &bfs])?;
+        assert_eq!(std::fs::read(&bfs)?.len(), 8 * num_nodes);
+
+        // Removes the loop
+        let simple = format!("{}-simple", basename);
+        cli_main(vec![
+            "webgraph",
+            "transform",
+            "simplify",
+            &basename,
+            &simple,
+            "--permutation",
+            &bfs,
+        ])?;
+        check(&simple, num_nodes, &[])?;
+
+        let llp = format!("{}.llp", basename);
+        cli_main(vec!["webgraph", "run", "llp", &simple, &llp])?;
+        let perm = std::fs::read(&llp)?
+            .chunks_exact(8)
+            .map(|chunk| u64::from_be_bytes(chunk.try_into().unwrap()) as usize)
+            .collect::<Vec<_>>();
+        assert_eq!(perm, (0..num_nodes).collect::<Vec<_>>());
+
+        let permuted = format!("{}-llp", basename);
+        cli_main(vec![
+            "webgraph",
+            "transform",
+            "simplify",
+            &simple,
+            &permuted,
+            "--permutation",
+            &llp,
+        ])?;
+        check(&permuted, num_nodes, &[])?;
+    }
+    Ok(())
+}
@@ -0,0 +1,50 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+#![cfg(feature = "cli")]
+
+use anyhow::Result;
+use dsi_bitstream::prelude::*;
+use std::process::Command;
+use webgraph::graphs::vec_graph::VecGraph;
+use webgraph::prelude::*;
+
+fn to_csv(args: &[&str]) -> Result<Vec<(usize, usize)>> {
+    let output = Command::new(env!("CARGO_BIN_EXE_webgraph"))
+        .args(["to", "csv"])
+        .args(args)
+        .output()?;
+    assert!(output.status.success());
+    Ok(String::from_utf8(output.stdout)?
+        .lines()
+        .map(|line| {
+            let (src, dst) = line.split_once(',').unwrap();
+            (src.parse().unwrap(), dst.parse().unwrap())
+        })
+        .collect())
+}
+
+#[test]
+fn test_to_csv_sort_by() -> Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    let basename = tmp_dir.path().join("graph");
+    let arcs = vec![(0, 2), (0, 3), (1, 0), (1, 3), (2, 1), (3, 0), (3, 2)];
+    let graph = Left(VecGraph::from_arc_list(arcs.iter().copied()));
+    BvComp::single_thread::<BE, _>(&basename, &graph, CompFlags::default(), true, None)?;
+    let basename = basename.to_str().unwrap();
+
+    assert_eq!(to_csv(&[basename])?, arcs);
+    assert_eq!(to_csv(&[basename, "--sort-by", "source"])?, arcs);
+
+    let mut by_target = arcs.clone();
+    by_target.sort_by_key(|&(src, dst)| (dst, src));
+    // a tiny batch size forces spilling to several batches
+    assert_eq!(
+        to_csv(&[basename, "--sort-by", "target", "--batch-size", "2"])?,
+        by_target
+    );
+    Ok(())
+}
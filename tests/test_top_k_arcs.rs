@@ -0,0 +1,90 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+#![cfg(feature = "cli")]
+
+use anyhow::Result;
+use dsi_bitstream::prelude::*;
+use lender::*;
+use webgraph::cli::{main as cli_main, GammaLabel};
+use webgraph::labels::bitstream::MmapBitStreamLabeling;
+use webgraph::prelude::*;
+
+#[test]
+fn test_top_k_arcs() -> Result<()> {
+    // The labels are the multiplicities minus one
+    let arcs = [
+        (0, 1),
+        (0, 1),
+        (0, 1),
+        (0, 2),
+        (0, 3),
+        (0, 3),
+        (1, 3),
+        (1, 0),
+        (1, 2),
+        (2, 3),
+        (2, 3),
+        (2, 3),
+        (2, 3),
+        (2, 3),
+    ];
+    let tmp_dir = tempfile::tempdir()?;
+    let basename = tmp_dir.path().join("multi");
+    let basename = basename.to_str().unwrap();
+    let arcs_path = tmp_dir.path().join("arcs.csv");
+    std::fs::write(
+        &arcs_path,
+        arcs.iter()
+            .map(|(src, dst)| format!("{},{}\n", src, dst))
+            .collect::<String>(),
+    )?;
+    cli_main(vec![
+        "webgraph",
+        "build",
+        "bvgraph",
+        arcs_path.to_str().unwrap(),
+        basename,
+        "--num-nodes",
+        "4",
+        "--exact",
+        "--multigraph",
+    ])?;
+    cli_main(vec!["webgraph", "build", "ef", basename])?;
+    cli_main(vec!["webgraph", "build", "label-ef", basename])?;
+
+    let truncated = tmp_dir.path().join("top");
+    let truncated = truncated.to_str().unwrap();
+    cli_main(vec![
+        "webgraph",
+        "transform",
+        "top-k-arcs",
+        basename,
+        truncated,
+        "-k",
+        "2",
+    ])?;
+    cli_main(vec!["webgraph", "build", "ef", truncated])?;
+    cli_main(vec!["webgraph", "build", "label-ef", truncated])?;
+
+    let graph = BvGraphSeq::with_basename(truncated)
+        .endianness::<BE>()
+        .load()?;
+    let labels = MmapBitStreamLabeling::<BE, _>::mmap(truncated, GammaLabel)?;
+    let mut kept = vec![];
+    let zip = Zip(graph, labels);
+    for_!((src, succ) in zip.iter() {
+        for (dst, label) in succ {
+            kept.push((src, dst, label));
+        }
+    });
+    // Ties between labels are broken by increasing target
+    assert_eq!(
+        kept,
+        vec![(0, 1, 2), (0, 3, 1), (1, 0, 0), (1, 2, 0), (2, 3, 4)]
+    );
+    Ok(())
+}
@@ -0,0 +1,148 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+#![cfg(feature = "cli")]
+
+use anyhow::Result;
+use dsi_bitstream::prelude::*;
+use std::path::Path;
+use webgraph::cli::build::dcf::{self, build_dcf};
+use webgraph::cli::build::ef_from_offsets::ef_from_offsets;
+use webgraph::cli::check;
+use webgraph::graphs::vec_graph::VecGraph;
+use webgraph::prelude::*;
+
+fn compress(basename: &Path, num_nodes: usize) -> Result<u64> {
+    let mut graph = VecGraph::empty(num_nodes);
+    for node in 0..num_nodes {
+        graph.add_arc(node, (node * 7 + 1) % num_nodes);
+        graph.add_arc(node, (node * 13 + 5) % num_nodes);
+    }
+    let graph = Left(graph);
+    BvComp::single_thread::<BE, _>(basename, &graph, CompFlags::default(), true, None)?;
+    ef_from_offsets(basename)?;
+    Ok(graph.num_arcs_hint().unwrap())
+}
+
+fn check_ef(basename: &Path) -> Result<()> {
+    check::ef::check_ef(check::ef::CliArgs {
+        src: basename.to_owned(),
+    })
+}
+
+fn check_dcf(basename: &Path) -> Result<()> {
+    check::dcf::check_dcf::<BE>(check::dcf::CliArgs {
+        src: basename.to_owned(),
+    })
+}
+
+#[test]
+fn test_validate_ef() -> Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    let basename = tmp_dir.path().join("graph");
+    let other = tmp_dir.path().join("other");
+    compress(&basename, 1000)?;
+    compress(&other, 500)?;
+
+    BvGraph::with_basename(&basename).load()?;
+    check_ef(&basename)?;
+
+    // The .ef file of a different graph
+    let ef_path = basename.with_extension(EF_EXTENSION);
+    let ef = std::fs::read(&ef_path)?;
+    std::fs::copy(other.with_extension(EF_EXTENSION), &ef_path)?;
+    let err = BvGraph::with_basename(&basename)
+        .load()
+        .map(drop)
+        .unwrap_err();
+    assert!(
+        format!("{:#}", err).contains("length invariant violated at index 501"),
+        "{:#}",
+        err
+    );
+    let err = check_ef(&basename).unwrap_err();
+    assert!(
+        format!("{:#}", err).contains("length invariant"),
+        "{:#}",
+        err
+    );
+    // Validation can be disabled
+    BvGraph::with_basename(&basename)
+        .validate_offsets(false)
+        .load()?;
+    std::fs::write(&ef_path, ef)?;
+
+    // A truncated graph file
+    let graph_path = basename.with_extension(GRAPH_EXTENSION);
+    let len = std::fs::metadata(&graph_path)?.len();
+    std::fs::File::options()
+        .write(true)
+        .open(&graph_path)?
+        .set_len(len / 2 / 16 * 16)?;
+    let err = BvGraph::with_basename(&basename)
+        .load()
+        .map(drop)
+        .unwrap_err();
+    assert!(
+        format!("{:#}", err).contains("last-value invariant violated at index 1000"),
+        "{:#}",
+        err
+    );
+    let err = check_ef(&basename).unwrap_err();
+    assert!(
+        format!("{:#}", err).contains("last-value invariant"),
+        "{:#}",
+        err
+    );
+    Ok(())
+}
+
+#[test]
+fn test_validate_dcf() -> Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    let basename = tmp_dir.path().join("graph");
+    let other = tmp_dir.path().join("other");
+    let num_arcs = compress(&basename, 1000)?;
+    compress(&other, 500)?;
+    for basename in [&basename, &other] {
+        build_dcf::<BE>(dcf::CliArgs {
+            src: basename.clone(),
+            select_params: SelectParams::default(),
+        })?;
+    }
+    check_dcf(&basename)?;
+
+    // A wrong number of arcs in the .properties file
+    let properties_path = basename.with_extension(PROPERTIES_EXTENSION);
+    let properties = std::fs::read_to_string(&properties_path)?;
+    std::fs::write(
+        &properties_path,
+        properties.replace(
+            &format!("\narcs={}\n", num_arcs),
+            &format!("\narcs={}\n", num_arcs + 1),
+        ),
+    )?;
+    let err = check_dcf(&basename).unwrap_err();
+    assert!(
+        format!("{:#}", err).contains("last-value invariant violated at index 1000"),
+        "{:#}",
+        err
+    );
+    std::fs::write(&properties_path, properties)?;
+
+    // The .dcf file of a different graph
+    std::fs::copy(
+        other.with_extension(DEG_CUMUL_EXTENSION),
+        basename.with_extension(DEG_CUMUL_EXTENSION),
+    )?;
+    let err = check_dcf(&basename).unwrap_err();
+    assert!(
+        format!("{:#}", err).contains("length invariant violated at index 501"),
+        "{:#}",
+        err
+    );
+    Ok(())
+}